@@ -0,0 +1,41 @@
+/*
+ * C bindings for git-ai attribution.
+ *
+ * Build: cargo rustc --release --lib --features git-ai-ffi --crate-type cdylib
+ *
+ * Every function except git_ai_version returns a NUL-terminated JSON string,
+ * either {"ok": <result>} or {"error": "<message>"}. Release it with
+ * git_ai_string_free.
+ */
+#ifndef GIT_AI_H
+#define GIT_AI_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Line number -> author map plus the prompts referenced by AI lines */
+char *git_ai_blame_file(const char *repo_path, const char *file_path);
+
+/* Aggregate attribution for the commits in start..end */
+char *git_ai_stats_for_range(const char *repo_path, const char *start, const char *end);
+
+/* Attribution for a single commit */
+char *git_ai_stats_for_commit(const char *repo_path, const char *commit);
+
+/*
+ * Checkpoint the working tree. agent_json is NULL for a human checkpoint, or
+ * {"tool": ..., "id": ..., "model": ..., "transcript"?: ..., "edited_filepaths"?: [...]}
+ */
+char *git_ai_checkpoint(const char *repo_path, const char *author, const char *agent_json);
+
+/* Static version string; do not free */
+const char *git_ai_version(void);
+
+void git_ai_string_free(char *ptr);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* GIT_AI_H */
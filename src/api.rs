@@ -0,0 +1,184 @@
+//! Embeddable entry point for tools that want AI attribution without shelling
+//! out to the `git-ai` binary.
+//!
+//! ```no_run
+//! use git_ai::GitAi;
+//!
+//! let git_ai = GitAi::open(".")?;
+//! let blame = git_ai.blame_file("src/main.rs")?;
+//! let stats = git_ai.stats_for_range("HEAD~10", "HEAD")?;
+//! println!("{} AI lines", stats.range_stats.ai_additions);
+//! # Ok::<(), git_ai::GitAiError>(())
+//! ```
+//!
+//! Every method returns a `Result` instead of printing or exiting, so callers
+//! decide how failures are surfaced.
+
+use crate::authorship::authorship_log::PromptRecord;
+use crate::authorship::range_authorship::{RangeAuthorshipStats, range_authorship};
+use crate::authorship::stats::{CommitStats, stats_for_commit_stats};
+use crate::authorship::working_log::CheckpointKind;
+use crate::commands::blame::GitAiBlameOptions;
+use crate::commands::checkpoint;
+use crate::commands::checkpoint_agent::agent_presets::AgentRunResult;
+use crate::error::GitAiError;
+use crate::git::repository::{CommitRange, Repository, find_repository_in_path};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// AI attribution for the committed lines of one file
+#[derive(Debug, Clone, Serialize)]
+pub struct FileBlame {
+    /// 1-based line number to author. AI lines carry the prompt id, which keys `prompts`.
+    pub lines: BTreeMap<u32, String>,
+    pub prompts: HashMap<String, PromptRecord>,
+}
+
+impl FileBlame {
+    pub fn ai_line_count(&self) -> usize {
+        self.lines
+            .values()
+            .filter(|author| self.prompts.contains_key(*author))
+            .count()
+    }
+}
+
+/// Outcome of [`GitAi::checkpoint`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CheckpointSummary {
+    pub entries: usize,
+    pub files: usize,
+    pub checkpoints: usize,
+}
+
+/// Handle to a repository with git-ai attribution
+pub struct GitAi {
+    repo: Repository,
+}
+
+impl GitAi {
+    /// Open the repository containing `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, GitAiError> {
+        let path = path.as_ref().to_string_lossy().to_string();
+        Ok(GitAi {
+            repo: find_repository_in_path(&path)?,
+        })
+    }
+
+    /// The underlying repository, for lower-level calls
+    pub fn repository(&self) -> &Repository {
+        &self.repo
+    }
+
+    /// Line-level attribution for `file_path` at HEAD
+    pub fn blame_file(&self, file_path: &str) -> Result<FileBlame, GitAiError> {
+        let options = GitAiBlameOptions {
+            use_prompt_hashes_as_names: true,
+            return_human_authors_as_human: true,
+            no_output: true,
+            ..Default::default()
+        };
+        let (lines, prompts) = self.repo.blame(file_path, &options)?;
+        Ok(FileBlame {
+            lines: lines.into_iter().collect(),
+            prompts,
+        })
+    }
+
+    /// Aggregate attribution for the commits in `start..end`
+    pub fn stats_for_range(
+        &self,
+        start: &str,
+        end: &str,
+    ) -> Result<RangeAuthorshipStats, GitAiError> {
+        let range =
+            CommitRange::new_infer_refname(&self.repo, start.to_string(), end.to_string(), None)?;
+        range_authorship(range, false, &[])
+    }
+
+    /// Attribution for a single commit
+    pub fn stats_for_commit(&self, commit: &str) -> Result<CommitStats, GitAiError> {
+        let sha = self.repo.revparse_single(commit)?.id();
+        stats_for_commit_stats(&self.repo, &sha, &[])
+    }
+
+    /// Record a checkpoint of the working tree. Pass `agent` for AI edits; `None`
+    /// records a human checkpoint attributed to `author`.
+    pub fn checkpoint(
+        &self,
+        author: &str,
+        agent: Option<AgentRunResult>,
+    ) -> Result<CheckpointSummary, GitAiError> {
+        let kind = agent
+            .as_ref()
+            .map(|result| result.checkpoint_kind)
+            .unwrap_or(CheckpointKind::Human);
+        let (entries, files, checkpoints) =
+            checkpoint::run(&self.repo, author, kind, false, false, true, agent, false)?;
+        Ok(CheckpointSummary {
+            entries,
+            files,
+            checkpoints,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::working_log::AgentId;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_checkpoint_blame_and_commit_stats_through_facade() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let mut file = tmp_repo.write_file("lib.rs", "fn a() {}\n", true).unwrap();
+        tmp_repo.trigger_checkpoint_with_author("dev").unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+
+        let git_ai = GitAi::open(tmp_repo.path()).unwrap();
+
+        file.append("fn b() {}\n").unwrap();
+        let summary = git_ai
+            .checkpoint(
+                "dev",
+                Some(AgentRunResult {
+                    agent_id: AgentId {
+                        tool: "cursor".to_string(),
+                        id: "session-1".to_string(),
+                        model: "claude-3-sonnet".to_string(),
+                    },
+                    agent_metadata: None,
+                    checkpoint_kind: CheckpointKind::AiAgent,
+                    transcript: None,
+                    repo_working_dir: None,
+                    edited_filepaths: None,
+                    will_edit_filepaths: None,
+                    dirty_files: None,
+                    commit_message: None,
+                    edited_line_ranges: None,
+                    append_transcript: false,
+                }),
+            )
+            .unwrap();
+        assert_eq!(summary.files, 1);
+        tmp_repo.commit_with_message("Add b").unwrap();
+
+        let blame = git_ai.blame_file("lib.rs").unwrap();
+        assert_eq!(blame.lines.len(), 2);
+        assert_eq!(
+            git_ai
+                .stats_for_commit("HEAD")
+                .unwrap()
+                .git_diff_added_lines,
+            1
+        );
+    }
+
+    #[test]
+    fn test_open_outside_repository_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(GitAi::open(dir.path()).is_err());
+    }
+}
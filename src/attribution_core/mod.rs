@@ -0,0 +1,19 @@
+//! Attribution data model and authorship-note parsing with no git, filesystem
+//! or subprocess access.
+//!
+//! This is the only module built without the default `cli` feature, so it
+//! compiles to `wasm32-unknown-unknown` for browser viewers that render AI
+//! overlays from exported notes:
+//!
+//! ```text
+//! cargo build --lib --no-default-features --target wasm32-unknown-unknown
+//! ```
+
+mod line_range;
+mod note;
+
+pub use line_range::LineRange;
+pub use note::{
+    AuthorshipNote, NoteAgent, NoteEntry, NoteFile, NoteParseError, NotePrompt, OverlaySpan,
+    parse_attestation_section, parse_line_ranges, split_note,
+};
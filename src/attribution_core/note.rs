@@ -0,0 +1,290 @@
+use super::line_range::LineRange;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Error from parsing the text authorship-note format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteParseError(pub String);
+
+impl fmt::Display for NoteParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for NoteParseError {}
+
+/// One `<hash> <ranges> [overrode:<hash>]` line of the attestation section
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NoteEntry {
+    pub hash: String,
+    pub line_ranges: Vec<LineRange>,
+    pub overrode: Option<String>,
+}
+
+/// A file path line followed by its indented entries
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NoteFile {
+    pub path: String,
+    pub entries: Vec<NoteEntry>,
+}
+
+/// Split a note into its attestation lines and the JSON metadata after `---`
+pub fn split_note(content: &str) -> Result<(Vec<&str>, String), NoteParseError> {
+    let lines: Vec<&str> = content.lines().collect();
+    let divider_pos = lines
+        .iter()
+        .position(|&line| line == "---")
+        .ok_or_else(|| NoteParseError("Missing divider '---' in authorship log".to_string()))?;
+    let json = lines[divider_pos + 1..].join("\n");
+    Ok((lines[..divider_pos].to_vec(), json))
+}
+
+/// Parse line ranges from a string like "1,2,19-222"
+/// No spaces are expected in the format
+pub fn parse_line_ranges(input: &str) -> Result<Vec<LineRange>, NoteParseError> {
+    let parse = |s: &str| {
+        s.parse::<u32>()
+            .map_err(|e| NoteParseError(format!("Invalid line number '{}': {}", s, e)))
+    };
+
+    let mut ranges = Vec::new();
+    for part in input.split(',') {
+        if part.is_empty() {
+            continue;
+        }
+
+        if let Some(dash_pos) = part.find('-') {
+            // Range format: "start-end"
+            let start = parse(&part[..dash_pos])?;
+            let end = parse(&part[dash_pos + 1..])?;
+            ranges.push(LineRange::Range(start, end));
+        } else {
+            // Single line format: "line"
+            ranges.push(LineRange::Single(parse(part)?));
+        }
+    }
+
+    Ok(ranges)
+}
+
+/// Parse the attestation section (before the divider). Files without entries are dropped.
+pub fn parse_attestation_section(lines: &[&str]) -> Result<Vec<NoteFile>, NoteParseError> {
+    let mut files = Vec::new();
+    let mut current_file: Option<NoteFile> = None;
+
+    for line in lines {
+        let line = line.trim_end(); // Remove trailing whitespace but preserve leading
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(entry_line) = line.strip_prefix("  ") {
+            // Attestation entry line (indented); split on first space to separate
+            // hash from line ranges
+            let Some(space_pos) = entry_line.find(' ') else {
+                return Err(NoteParseError(format!(
+                    "Invalid attestation entry format: {}",
+                    entry_line
+                )));
+            };
+            let hash = entry_line[..space_pos].to_string();
+            let rest = &entry_line[space_pos + 1..];
+
+            // Check if there's an "overrode:" suffix
+            let (ranges_str, overrode) = match rest.split_once(" overrode:") {
+                Some((ranges_str, overrode_hash)) => (ranges_str, Some(overrode_hash.to_string())),
+                None => (rest, None),
+            };
+
+            let entry = NoteEntry {
+                hash,
+                line_ranges: parse_line_ranges(ranges_str)?,
+                overrode,
+            };
+            match current_file.as_mut() {
+                Some(file) => file.entries.push(entry),
+                None => {
+                    return Err(NoteParseError(
+                        "Attestation entry found without a file path".to_string(),
+                    ));
+                }
+            }
+        } else {
+            // File path line (not indented)
+            if let Some(file) = current_file.take()
+                && !file.entries.is_empty()
+            {
+                files.push(file);
+            }
+
+            // Quoted paths only need the quotes removed; quotes aren't allowed in file names
+            let path = if line.len() >= 2 && line.starts_with('"') && line.ends_with('"') {
+                line[1..line.len() - 1].to_string()
+            } else {
+                line.to_string()
+            };
+
+            current_file = Some(NoteFile {
+                path,
+                entries: Vec::new(),
+            });
+        }
+    }
+
+    // Don't forget the last file
+    if let Some(file) = current_file
+        && !file.entries.is_empty()
+    {
+        files.push(file);
+    }
+
+    Ok(files)
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteAgent {
+    #[serde(default)]
+    pub tool: String,
+    #[serde(default)]
+    pub model: String,
+}
+
+/// The parts of a prompt record a viewer needs; transcripts are not read
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotePrompt {
+    #[serde(default)]
+    pub agent_id: NoteAgent,
+    #[serde(default)]
+    pub human_author: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct NoteMetadata {
+    #[serde(default)]
+    base_commit_sha: String,
+    #[serde(default)]
+    prompts: BTreeMap<String, NotePrompt>,
+}
+
+/// A parsed authorship note, independent of any repository
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuthorshipNote {
+    pub base_commit_sha: String,
+    pub files: Vec<NoteFile>,
+    pub prompts: BTreeMap<String, NotePrompt>,
+}
+
+/// Contiguous AI-attributed lines of one file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OverlaySpan {
+    pub start: u32,
+    pub end: u32,
+    pub prompt_id: String,
+    pub tool: Option<String>,
+    pub model: Option<String>,
+    pub human_author: Option<String>,
+}
+
+impl AuthorshipNote {
+    /// Parse the text note format stored under `refs/notes/ai`
+    pub fn parse(content: &str) -> Result<Self, NoteParseError> {
+        let (attestation_lines, json) = split_note(content)?;
+        let files = parse_attestation_section(&attestation_lines)?;
+        let metadata: NoteMetadata = serde_json::from_str(&json)
+            .map_err(|e| NoteParseError(format!("Invalid note metadata: {}", e)))?;
+        Ok(AuthorshipNote {
+            base_commit_sha: metadata.base_commit_sha,
+            files,
+            prompts: metadata.prompts,
+        })
+    }
+
+    /// AI spans for `path`, ordered by start line
+    pub fn overlay(&self, path: &str) -> Vec<OverlaySpan> {
+        let mut spans: Vec<OverlaySpan> = self
+            .files
+            .iter()
+            .filter(|file| file.path == path)
+            .flat_map(|file| &file.entries)
+            .flat_map(|entry| {
+                let prompt = self.prompts.get(&entry.hash);
+                entry.line_ranges.iter().map(move |range| {
+                    let (start, end) = match range {
+                        LineRange::Single(line) => (*line, *line),
+                        LineRange::Range(start, end) => (*start, *end),
+                    };
+                    OverlaySpan {
+                        start,
+                        end,
+                        prompt_id: entry.hash.clone(),
+                        tool: prompt.map(|p| p.agent_id.tool.clone()),
+                        model: prompt.map(|p| p.agent_id.model.clone()),
+                        human_author: prompt.and_then(|p| p.human_author.clone()),
+                    }
+                })
+            })
+            .collect();
+        spans.sort_by_key(|span| (span.start, span.end));
+        spans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTE: &str = r#"src/main.rs
+  abc1234 1-3,7
+"docs/read me.md"
+  def5678 2 overrode:abc1234
+---
+{
+  "schema_version": "authorship/3.0.0",
+  "base_commit_sha": "0123abcd",
+  "prompts": {
+    "abc1234": {
+      "agent_id": {"tool": "cursor", "id": "s1", "model": "claude-3-sonnet"},
+      "human_author": "Dev <dev@example.com>",
+      "messages": [],
+      "total_additions": 4
+    }
+  }
+}"#;
+
+    #[test]
+    fn test_parse_note_and_overlay() {
+        let note = AuthorshipNote::parse(NOTE).unwrap();
+        assert_eq!(note.base_commit_sha, "0123abcd");
+        assert_eq!(note.files.len(), 2);
+        assert_eq!(note.files[1].path, "docs/read me.md");
+        assert_eq!(
+            note.files[1].entries[0].overrode.as_deref(),
+            Some("abc1234")
+        );
+
+        let spans = note.overlay("src/main.rs");
+        assert_eq!(spans.len(), 2);
+        assert_eq!((spans[0].start, spans[0].end), (1, 3));
+        assert_eq!(spans[1].start, 7);
+        assert_eq!(spans[0].tool.as_deref(), Some("cursor"));
+        assert_eq!(
+            spans[0].human_author.as_deref(),
+            Some("Dev <dev@example.com>")
+        );
+
+        // Prompts missing from the metadata still produce spans, just unlabeled
+        let spans = note.overlay("docs/read me.md");
+        assert_eq!(spans[0].tool, None);
+        assert!(note.overlay("missing.rs").is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(AuthorshipNote::parse("src/main.rs\n  abc 1\n").is_err());
+        assert!(parse_attestation_section(&["  abc 1-2"]).is_err());
+        assert!(parse_line_ranges("1,x").is_err());
+    }
+}
@@ -0,0 +1,226 @@
+//! `--anonymize` for reports that leave the organisation: human identities become
+//! stable hashes and prompt text is dropped, while line counts, attestations and
+//! message structure stay intact so AI/human proportions are unchanged.
+//!
+//! Identities are hashed with HMAC-SHA256 under a secret salt, so a report can't
+//! be checked against guessed names. The salt is generated once per repository
+//! and kept in `.git/ai/anonymize_salt`; set `GIT_AI_ANONYMIZE_SALT` instead to
+//! share one between repositories and keep identities comparable across them.
+
+use crate::authorship::authorship_log::PromptRecord;
+use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::authorship::range_authorship::RangeAuthorshipStats;
+use crate::authorship::stats::CommitStats;
+use crate::authorship::transcript::Message;
+use crate::commands::blame::BlameHunk;
+use crate::git::repository::find_repository_in_path;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::OnceLock;
+
+const REDACTED: &str = "[redacted]";
+
+static SALT: OnceLock<Vec<u8>> = OnceLock::new();
+
+/// `anon-` followed by 12 hex digits of the HMAC of `identity` under the salt
+pub fn hash_identity(identity: &str) -> String {
+    let mac = hmac_sha256(salt(), identity.trim().to_lowercase().as_bytes());
+    format!("anon-{}", &hex(&mac)[..12])
+}
+
+fn salt() -> &'static [u8] {
+    SALT.get_or_init(|| {
+        if let Ok(salt) = std::env::var("GIT_AI_ANONYMIZE_SALT")
+            && !salt.is_empty()
+        {
+            return salt.into_bytes();
+        }
+        // Outside a repository the hashes only need to agree within this run
+        find_repository_in_path(".")
+            .ok()
+            .and_then(|repo| repo_salt(&repo.storage.anonymize_salt).ok())
+            .unwrap_or_else(|| rand::random::<[u8; 32]>().to_vec())
+    })
+}
+
+/// The salt stored at `path`, generated on first use. A new salt is written to
+/// a scratch file and hard-linked into place, so concurrent runs agree on one.
+fn repo_salt(path: &Path) -> io::Result<Vec<u8>> {
+    if let Ok(salt) = fs::read(path)
+        && !salt.is_empty()
+    {
+        return Ok(salt);
+    }
+    let scratch = path.with_extension(format!("new-{}", std::process::id()));
+    fs::write(&scratch, hex(&rand::random::<[u8; 32]>()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&scratch, fs::Permissions::from_mode(0o600))?;
+    }
+    let linked = fs::hard_link(&scratch, path);
+    let _ = fs::remove_file(&scratch);
+    match linked {
+        Ok(()) => fs::read(path),
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => fs::read(path),
+        Err(e) => Err(e),
+    }
+}
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK_SIZE: usize = 64;
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let inner = Sha256::new()
+        .chain_update(block.map(|byte| byte ^ 0x36))
+        .chain_update(message)
+        .finalize();
+    Sha256::new()
+        .chain_update(block.map(|byte| byte ^ 0x5c))
+        .chain_update(inner)
+        .finalize()
+        .into()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn hash_keys(map: BTreeMap<String, u32>) -> BTreeMap<String, u32> {
+    let mut hashed = BTreeMap::new();
+    for (identity, lines) in map {
+        *hashed.entry(hash_identity(&identity)).or_insert(0) += lines;
+    }
+    hashed
+}
+
+pub fn anonymize_prompt(prompt: &mut PromptRecord) {
+    prompt.human_author = prompt.human_author.as_deref().map(hash_identity);
+    prompt.agent_id.id = hash_identity(&prompt.agent_id.id);
+    prompt.agent_metadata.clear();
+    for message in &mut prompt.messages {
+        match message {
+            Message::User { text, .. } | Message::Assistant { text, .. } => {
+                *text = REDACTED.to_string();
+            }
+            Message::ToolUse { input, .. } => *input = serde_json::Value::Null,
+        }
+    }
+}
+
+pub fn anonymize_log(log: &mut AuthorshipLog) {
+    for prompt in log.metadata.prompts.values_mut() {
+        anonymize_prompt(prompt);
+    }
+    log.metadata.human_authors = hash_keys(std::mem::take(&mut log.metadata.human_authors));
+}
+
+pub fn anonymize_stats(stats: &mut CommitStats) {
+    stats.human_breakdown = hash_keys(std::mem::take(&mut stats.human_breakdown));
+}
+
+/// Hash the git author and committer blame prints for a hunk
+pub fn anonymize_blame_hunk(hunk: &mut BlameHunk) {
+    hunk.original_author = hash_identity(&hunk.original_author);
+    hunk.author_email = hash_identity(&hunk.author_email);
+    hunk.committer = hash_identity(&hunk.committer);
+    hunk.committer_email = hash_identity(&hunk.committer_email);
+}
+
+pub fn anonymize_range_stats(stats: &mut RangeAuthorshipStats) {
+    anonymize_stats(&mut stats.range_stats);
+    let data = &mut stats.authorship_stats;
+    for authors in [
+        &mut data.authors_commiting_authorship,
+        &mut data.authors_not_commiting_authorship,
+    ] {
+        *authors = authors.iter().map(|author| hash_identity(author)).collect();
+    }
+    for (_, author) in &mut data.commits_without_authorship_with_authors {
+        *author = hash_identity(author);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTE: &str = r#"src/main.rs
+  aaa1111 1-3
+---
+{
+  "schema_version": "authorship/3.0.0",
+  "base_commit_sha": "0123abcd",
+  "prompts": {
+    "aaa1111": {
+      "agent_id": {"tool": "cursor", "id": "session-42", "model": "claude-3-sonnet"},
+      "human_author": "Alice <alice@example.com>",
+      "messages": [{"type": "user", "text": "fix the login bug"}],
+      "total_additions": 3,
+      "total_deletions": 0,
+      "accepted_lines": 3,
+      "overriden_lines": 0
+    }
+  },
+  "human_authors": {"Bob <bob@example.com>": 5, "bob <BOB@example.com> ": 2}
+}"#;
+
+    #[test]
+    fn test_anonymize_log_keeps_structure() {
+        let mut log = AuthorshipLog::deserialize_from_string(NOTE).unwrap();
+        let original = log.clone();
+        anonymize_log(&mut log);
+
+        assert_eq!(log.attestations, original.attestations);
+        let prompt = &log.metadata.prompts["aaa1111"];
+        assert_eq!(
+            prompt.human_author.as_deref(),
+            Some(hash_identity("alice <alice@example.com>").as_str())
+        );
+        assert!(prompt.human_author.as_deref().unwrap().starts_with("anon-"));
+        assert_ne!(prompt.agent_id.id, "session-42");
+        assert_eq!(prompt.agent_id.tool, "cursor");
+        assert_eq!(prompt.messages.len(), 1);
+        assert!(matches!(&prompt.messages[0], Message::User { text, .. } if text == REDACTED));
+        assert_eq!(prompt.accepted_lines, 3);
+
+        // Case and whitespace variants of one identity merge
+        assert_eq!(log.metadata.human_authors.len(), 1);
+        assert_eq!(log.metadata.human_authors.values().sum::<u32>(), 7);
+    }
+
+    #[test]
+    fn test_hmac_sha256_matches_rfc_4231() {
+        let mac = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        assert_eq!(
+            hex(&mac),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // Keys longer than a block are hashed first
+        let mac = hmac_sha256(
+            &[0xaa; 131],
+            b"Test Using Larger Than Block-Size Key - Hash Key First",
+        );
+        assert_eq!(
+            hex(&mac),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn test_repo_salt_is_generated_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("anonymize_salt");
+        let salt = repo_salt(&path).unwrap();
+        assert_eq!(salt.len(), 64);
+        assert_eq!(repo_salt(&path).unwrap(), salt);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
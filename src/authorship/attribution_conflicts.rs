@@ -0,0 +1,278 @@
+//! Regions a rebase couldn't attribute with confidence: AI-written hunks the
+//! rebase rewrote heavily, usually while resolving conflicts. Rather than
+//! silently keeping whichever attribution the rewrite happened to land on,
+//! they're kept for `git-ai resolve-attribution`.
+
+use crate::attribution_core::LineRange;
+use crate::authorship::attribution_tracker::{LineAttribution, attribution_diff_algorithm};
+use crate::authorship::authorship_log_serialization::{AttestationEntry, AuthorshipLog};
+use crate::authorship::imara_diff_utils::{DiffOp, capture_diff_slices_with};
+use crate::authorship::working_log::CheckpointKind;
+use crate::error::GitAiError;
+use crate::git::repo_storage::RepoStorage;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AmbiguousRegion {
+    pub file: String,
+    /// 1-indexed, inclusive, in the rebased HEAD
+    pub start_line: u32,
+    pub end_line: u32,
+    /// Prompt hashes of the AI sessions behind the lines the rebase rewrote
+    pub candidates: Vec<String>,
+}
+
+/// Regions left by the last rebase, until they're resolved
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingResolution {
+    /// HEAD right after the rebase; the regions' line numbers refer to it
+    pub head: String,
+    /// The rebased commits, whose notes a resolution may change
+    pub commits: Vec<String>,
+    pub regions: Vec<AmbiguousRegion>,
+}
+
+fn ai_author(attributions: &[LineAttribution], line: u32) -> Option<&str> {
+    attributions
+        .iter()
+        .find(|attr| attr.start_line <= line && line <= attr.end_line)
+        .map(|attr| attr.author_id.as_str())
+        .filter(|author| *author != CheckpointKind::Human.to_str())
+}
+
+/// Dice coefficient of the two lines' character bigrams, from 0.0 (nothing
+/// in common) to 1.0
+fn similarity(a: &str, b: &str) -> f64 {
+    let bigrams = |line: &str| -> Vec<(char, char)> {
+        let chars: Vec<char> = line.split_whitespace().flat_map(str::chars).collect();
+        chars.windows(2).map(|pair| (pair[0], pair[1])).collect()
+    };
+    let (a, mut b) = (bigrams(a), bigrams(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let total = a.len() + b.len();
+    let mut shared = 0;
+    for bigram in &a {
+        if let Some(found) = b.iter().position(|other| other == bigram) {
+            b.swap_remove(found);
+            shared += 1;
+        }
+    }
+    2.0 * shared as f64 / total as f64
+}
+
+/// Lines at least this similar to a line they replaced count as an edit of
+/// it; anything less is a rewrite nobody can be credited with for certain
+const LIGHT_EDIT_SIMILARITY: f64 = 0.5;
+
+/// Runs of lines in `new_content` that replaced AI-written lines of
+/// `old_content` and can't be attributed with confidence, with the AI
+/// sessions they might be from. That is lines rewritten beyond recognition,
+/// whoever they ended up attributed to, and light edits of an AI line that
+/// lost its attribution on the way.
+pub fn ambiguous_regions(
+    old_content: &str,
+    old_attributions: &[LineAttribution],
+    new_content: &str,
+    new_attributions: &[LineAttribution],
+) -> Vec<(u32, u32, Vec<String>)> {
+    let old_lines: Vec<&str> = old_content.lines().collect();
+    let new_lines: Vec<&str> = new_content.lines().collect();
+    let mut regions = Vec::new();
+    for op in capture_diff_slices_with(attribution_diff_algorithm(), &old_lines, &new_lines) {
+        let DiffOp::Replace {
+            old_index,
+            old_len,
+            new_index,
+            new_len,
+        } = op
+        else {
+            continue;
+        };
+        let replaced: Vec<(&str, Option<&str>)> = (old_index..old_index + old_len)
+            .map(|i| (old_lines[i], ai_author(old_attributions, i as u32 + 1)))
+            .collect();
+        let replaced_ai: BTreeSet<&str> =
+            replaced.iter().filter_map(|(_, author)| *author).collect();
+        if replaced_ai.is_empty() {
+            continue;
+        }
+
+        let mut run: Option<(u32, u32, BTreeSet<&str>)> = None;
+        for (i, new_line) in new_lines.iter().enumerate().skip(new_index).take(new_len) {
+            let line = i as u32 + 1;
+            let (best, score) = replaced
+                .iter()
+                .map(|(old, author)| (*author, similarity(old, new_line)))
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap_or((None, 0.0));
+            let authors = if new_line.trim().is_empty() {
+                BTreeSet::new()
+            } else if score < LIGHT_EDIT_SIMILARITY {
+                replaced_ai.clone()
+            } else {
+                match best {
+                    Some(author) if ai_author(new_attributions, line).is_none() => {
+                        BTreeSet::from([author])
+                    }
+                    _ => BTreeSet::new(),
+                }
+            };
+            if authors.is_empty() {
+                if let Some(region) = run.take() {
+                    regions.push(region);
+                }
+            } else if let Some((_, end, candidates)) = run.as_mut() {
+                *end = line;
+                candidates.extend(authors);
+            } else {
+                run = Some((line, line, authors));
+            }
+        }
+        regions.extend(run);
+    }
+    regions
+        .into_iter()
+        .map(|(start, end, candidates)| {
+            (
+                start,
+                end,
+                candidates.into_iter().map(str::to_string).collect(),
+            )
+        })
+        .collect()
+}
+
+pub fn save_pending(storage: &RepoStorage, pending: &PendingResolution) -> Result<(), GitAiError> {
+    fs::write(
+        &storage.rebase_ambiguities,
+        serde_json::to_vec_pretty(pending)?,
+    )?;
+    Ok(())
+}
+
+pub fn load_pending(storage: &RepoStorage) -> Option<PendingResolution> {
+    let data = fs::read(&storage.rebase_ambiguities).ok()?;
+    serde_json::from_slice(&data).ok()
+}
+
+pub fn clear_pending(storage: &RepoStorage) {
+    let _ = fs::remove_file(&storage.rebase_ambiguities);
+}
+
+/// Credit `lines` of `file` to the AI session `hash`, or with `None` to
+/// nobody, which leaves them to the commit's human author
+pub fn assign_lines(
+    log: &mut AuthorshipLog,
+    file: &str,
+    lines: &BTreeSet<u32>,
+    hash: Option<&str>,
+) {
+    let lines: Vec<u32> = lines.iter().copied().collect();
+    let ranges = LineRange::compress_lines(&lines);
+    for attestation in log
+        .attestations
+        .iter_mut()
+        .filter(|attestation| attestation.file_path == file)
+    {
+        for entry in &mut attestation.entries {
+            entry.remove_line_ranges(&ranges);
+        }
+        attestation
+            .entries
+            .retain(|entry| !entry.line_ranges.is_empty());
+    }
+
+    if let Some(hash) = hash {
+        let attestation = log.get_or_create_file(file);
+        let existing = attestation
+            .entries
+            .iter_mut()
+            .find(|entry| entry.hash == hash && entry.overrode.is_none());
+        match existing {
+            Some(entry) => {
+                let mut all: HashSet<u32> = entry
+                    .line_ranges
+                    .iter()
+                    .flat_map(LineRange::expand)
+                    .collect();
+                all.extend(&lines);
+                let mut all: Vec<u32> = all.into_iter().collect();
+                all.sort_unstable();
+                entry.line_ranges = LineRange::compress_lines(&all);
+            }
+            None => attestation.add_entry(AttestationEntry::new(hash.to_string(), ranges)),
+        }
+    }
+    log.attestations
+        .retain(|attestation| !attestation.entries.is_empty());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attr(start_line: u32, end_line: u32, author_id: &str) -> LineAttribution {
+        LineAttribution {
+            start_line,
+            end_line,
+            author_id: author_id.to_string(),
+            overrode: None,
+        }
+    }
+
+    #[test]
+    fn test_ambiguous_regions() {
+        let old = "fn a() {}\nlet x = 1;\nlet y = ai(x);\nlet z = ai(y);\nfn b() {}\n";
+        let old_attrs = vec![attr(1, 2, "human"), attr(3, 4, "abc1234")];
+        // The rebase edited the human line above the AI ones and one of the
+        // AI lines, which lost its attribution; the other kept it
+        let new = "fn a() {}\nlet x = 10;\nlet y = ai_checked(x)?;\nlet z = ai(y);\nfn b() {}\n";
+        let new_attrs = vec![attr(1, 2, "human"), attr(4, 4, "abc1234")];
+        assert_eq!(
+            ambiguous_regions(old, &old_attrs, new, &new_attrs),
+            vec![(3, 3, vec!["abc1234".to_string()])]
+        );
+
+        // Lines that only moved or were added aren't ambiguous
+        let added = format!("{}fn c() {{}}\n", old);
+        assert!(ambiguous_regions(old, &old_attrs, &added, &old_attrs).is_empty());
+
+        // Rewritten beyond recognition, whatever the attribution says now
+        let new = "fn a() {}\nlet x = 1;\nreturn sum(&[x]);\n\nfn b() {}\n";
+        let new_attrs = vec![attr(1, 2, "human"), attr(3, 3, "abc1234")];
+        assert_eq!(
+            ambiguous_regions(old, &old_attrs, new, &new_attrs),
+            vec![(3, 3, vec!["abc1234".to_string()])]
+        );
+    }
+
+    #[test]
+    fn test_similarity() {
+        assert_eq!(similarity("let x = 1;", "let x = 1;"), 1.0);
+        assert_eq!(similarity("abc", "xyz"), 0.0);
+        assert!(similarity("let y = ai(x);", "let y = ai_checked(x)?;") > 0.5);
+    }
+
+    #[test]
+    fn test_assign_lines() {
+        let mut log = AuthorshipLog::new();
+        log.get_or_create_file("a.rs")
+            .add_entry(AttestationEntry::new(
+                "abc1234".to_string(),
+                vec![LineRange::Range(1, 4)],
+            ));
+
+        assign_lines(&mut log, "a.rs", &BTreeSet::from([6, 7]), Some("abc1234"));
+        assert_eq!(
+            log.attestations[0].entries[0].line_ranges,
+            vec![LineRange::Range(1, 4), LineRange::Range(6, 7)]
+        );
+
+        assign_lines(&mut log, "a.rs", &BTreeSet::from([1, 2, 3, 4, 6, 7]), None);
+        assert!(log.attestations.is_empty());
+    }
+}
@@ -0,0 +1,293 @@
+//! How lines moved between AI and human over a commit range (`stats
+//! --attribution-flow`), rather than only where the percentages ended up.
+//! Each file the range changed is diffed between the two ends, and lines
+//! on either side take their category from blame at that end. Within a
+//! changed hunk, old and new lines pair up in order as edits; the rest are
+//! additions or deletions.
+
+use crate::authorship::attribution_tracker::attribution_diff_algorithm;
+use crate::authorship::imara_diff_utils::{DiffOp, capture_diff_slices_with};
+use crate::authorship::range_authorship::should_ignore_file;
+use crate::authorship::working_log::CheckpointKind;
+use crate::commands::blame::{GitAiBlameOptions, overlay_ai_authorship};
+use crate::error::GitAiError;
+use crate::git::repository::{Repository, exec_git};
+use crate::utils::debug_log;
+use serde::Serialize;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct AttributionFlow {
+    pub start: String,
+    pub end: String,
+    pub files: u32,
+    /// AI lines a human edited
+    pub ai_to_human: u32,
+    /// Human lines an agent rewrote
+    pub human_to_ai: u32,
+    pub ai_to_ai: u32,
+    pub human_to_human: u32,
+    pub added_ai: u32,
+    pub added_human: u32,
+    pub deleted_ai: u32,
+    pub deleted_human: u32,
+}
+
+impl AttributionFlow {
+    /// Count `old` lines replaced by `new` ones, pairing them in order
+    fn record_edit(&mut self, old: &[bool], new: &[bool]) {
+        for (old_ai, new_ai) in old.iter().zip(new) {
+            match (old_ai, new_ai) {
+                (true, false) => self.ai_to_human += 1,
+                (false, true) => self.human_to_ai += 1,
+                (true, true) => self.ai_to_ai += 1,
+                (false, false) => self.human_to_human += 1,
+            }
+        }
+        let paired = old.len().min(new.len());
+        self.record_deleted(&old[paired..]);
+        self.record_added(&new[paired..]);
+    }
+
+    fn record_added(&mut self, lines: &[bool]) {
+        let ai = lines.iter().filter(|ai| **ai).count() as u32;
+        self.added_ai += ai;
+        self.added_human += lines.len() as u32 - ai;
+    }
+
+    fn record_deleted(&mut self, lines: &[bool]) {
+        let ai = lines.iter().filter(|ai| **ai).count() as u32;
+        self.deleted_ai += ai;
+        self.deleted_human += lines.len() as u32 - ai;
+    }
+
+    /// Tally one file, given its lines and whether each is AI at either end
+    pub fn record_file(
+        &mut self,
+        old_lines: &[&str],
+        old_ai: &[bool],
+        new_lines: &[&str],
+        new_ai: &[bool],
+    ) {
+        self.files += 1;
+        for op in capture_diff_slices_with(attribution_diff_algorithm(), old_lines, new_lines) {
+            match op {
+                DiffOp::Equal { .. } => {}
+                DiffOp::Delete {
+                    old_index, old_len, ..
+                } => self.record_deleted(&old_ai[old_index..old_index + old_len]),
+                DiffOp::Insert {
+                    new_index, new_len, ..
+                } => self.record_added(&new_ai[new_index..new_index + new_len]),
+                DiffOp::Replace {
+                    old_index,
+                    old_len,
+                    new_index,
+                    new_len,
+                } => self.record_edit(
+                    &old_ai[old_index..old_index + old_len],
+                    &new_ai[new_index..new_index + new_len],
+                ),
+            }
+        }
+    }
+}
+
+/// A changed file's path at the start and end of the range; a side is None
+/// where the file doesn't exist
+type FilePaths = (Option<String>, Option<String>);
+
+/// Files changed between `start` and `end`
+fn changed_files(repo: &Repository, start: &str, end: &str) -> Result<Vec<FilePaths>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(
+        ["diff", "--name-status", "-M", "-z", start, end, "--"]
+            .iter()
+            .map(|s| s.to_string()),
+    );
+    let output = exec_git(&args)?;
+    let stdout = String::from_utf8(output.stdout)?;
+    let mut fields = stdout.split('\0').filter(|field| !field.is_empty());
+
+    let mut files = Vec::new();
+    while let Some(status) = fields.next() {
+        let Some(path) = fields.next().map(str::to_string) else {
+            break;
+        };
+        let file = match status.chars().next() {
+            Some('R') => (Some(path), fields.next().map(str::to_string)),
+            Some('C') => (None, fields.next().map(str::to_string)),
+            Some('A') => (None, Some(path)),
+            Some('D') => (Some(path), None),
+            _ => (Some(path.clone()), Some(path)),
+        };
+        files.push(file);
+    }
+    Ok(files)
+}
+
+/// `path`'s lines at `commit` and whether each is AI-authored; lines an
+/// agent wrote and a human then touched count as AI. Empty for binary files.
+fn file_lines(
+    repo: &Repository,
+    commit: &str,
+    path: &str,
+) -> Result<(String, Vec<bool>), GitAiError> {
+    let content = repo.get_file_content(path, commit)?;
+    if content.contains(&0u8) {
+        return Ok((String::new(), Vec::new()));
+    }
+    let content = String::from_utf8_lossy(&content).to_string();
+    let line_count = content.lines().count() as u32;
+    let mut ai = vec![false; line_count as usize];
+    if line_count == 0 {
+        return Ok((content, ai));
+    }
+
+    let options = GitAiBlameOptions {
+        newest_commit: Some(commit.to_string()),
+        use_prompt_hashes_as_names: true,
+        no_output: true,
+        ..Default::default()
+    };
+    let hunks = repo.blame_hunks(path, 1, line_count, &options)?;
+    let (line_authors, prompts) = overlay_ai_authorship(repo, &hunks, path, &options)?;
+    let mixed = CheckpointKind::Mixed.to_str();
+    for (line, author) in line_authors {
+        if let Some(is_ai) = ai.get_mut(line as usize - 1) {
+            *is_ai = prompts.contains_key(&author) || author == mixed;
+        }
+    }
+    Ok((content, ai))
+}
+
+/// Lines that changed category, or came and went, between `start` and `end`
+pub fn attribution_flow(
+    repo: &Repository,
+    start: &str,
+    end: &str,
+    ignore_patterns: &[String],
+) -> Result<AttributionFlow, GitAiError> {
+    let mut flow = AttributionFlow {
+        start: start.to_string(),
+        end: end.to_string(),
+        ..Default::default()
+    };
+
+    for (old_path, new_path) in changed_files(repo, start, end)? {
+        let ignored = [&old_path, &new_path]
+            .into_iter()
+            .flatten()
+            .any(|path| should_ignore_file(path, ignore_patterns));
+        if ignored {
+            continue;
+        }
+        let read = |commit: &str, path: &Option<String>| match path {
+            Some(path) => file_lines(repo, commit, path),
+            None => Ok((String::new(), Vec::new())),
+        };
+        let (old, new) = match (read(start, &old_path), read(end, &new_path)) {
+            (Ok(old), Ok(new)) => (old, new),
+            (Err(e), _) | (_, Err(e)) => {
+                debug_log(&format!(
+                    "Skipping attribution flow for {:?} -> {:?}: {}",
+                    old_path, new_path, e
+                ));
+                continue;
+            }
+        };
+        let old_lines: Vec<&str> = old.0.lines().collect();
+        let new_lines: Vec<&str> = new.0.lines().collect();
+        flow.record_file(&old_lines, &old.1, &new_lines, &new.1);
+    }
+    Ok(flow)
+}
+
+pub fn write_attribution_flow_to_terminal(flow: &AttributionFlow) {
+    let short = |sha: &str| sha[..sha.len().min(8)].to_string();
+    println!(
+        "Attribution flow {}..{} ({} files changed)",
+        short(&flow.start),
+        short(&flow.end),
+        flow.files
+    );
+    let rows = [
+        ("AI -> human", flow.ai_to_human, "AI lines a human edited"),
+        (
+            "human -> AI",
+            flow.human_to_ai,
+            "human lines an agent rewrote",
+        ),
+        ("AI -> AI", flow.ai_to_ai, "AI lines an agent rewrote"),
+        (
+            "human -> human",
+            flow.human_to_human,
+            "human lines a human edited",
+        ),
+        ("added AI", flow.added_ai, ""),
+        ("added human", flow.added_human, ""),
+        ("deleted AI", flow.deleted_ai, ""),
+        ("deleted human", flow.deleted_human, ""),
+    ];
+    for (label, lines, note) in rows {
+        let row = format!("  {:<16} {:>7}  {}", label, lines, note);
+        println!("{}", row.trim_end());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_record_file_pairs_edits_in_order() {
+        let mut flow = AttributionFlow::default();
+        flow.record_file(
+            &["keep", "a1", "a2", "a3"],
+            &[false, true, true, true],
+            &["keep (agent)", "A1 edited", "a3", "h2"],
+            &[true, false, true, false],
+        );
+        assert_eq!(
+            flow,
+            AttributionFlow {
+                files: 1,
+                human_to_ai: 1,
+                ai_to_human: 1,
+                deleted_ai: 1,
+                added_human: 1,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_attribution_flow_over_range() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let mut file = tmp_repo.write_file("app.py", "keep\n", true).unwrap();
+        tmp_repo.trigger_checkpoint_with_author("dev").unwrap();
+        file.append("a1\na2\na3\n").unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI adds lines").unwrap();
+        let start = tmp_repo.head_commit_sha().unwrap();
+
+        // A human reworks and trims the AI lines, then adds one of their own
+        file.update("keep\nA1 edited\na3\nh2\n").unwrap();
+        tmp_repo.trigger_checkpoint_with_author("dev").unwrap();
+        tmp_repo.commit_with_message("Human edits").unwrap();
+        let end = tmp_repo.head_commit_sha().unwrap();
+
+        let flow = attribution_flow(tmp_repo.gitai_repo(), &start, &end, &[]).unwrap();
+        assert_eq!(flow.files, 1);
+        assert_eq!(flow.ai_to_human, 1);
+        assert_eq!(flow.deleted_ai, 1);
+        assert_eq!(flow.added_human, 1);
+        assert_eq!(flow.human_to_ai + flow.added_ai + flow.deleted_human, 0);
+
+        let ignored =
+            attribution_flow(tmp_repo.gitai_repo(), &start, &end, &["*.py".to_string()]).unwrap();
+        assert_eq!(ignored.files, 0);
+    }
+}
@@ -3,16 +3,40 @@
 //! This library maintains attribution ranges as files are edited, preserving
 //! authorship information even through moves, edits, and whitespace changes.
 
-use crate::authorship::imara_diff_utils::{ByteDiff, ByteDiffOp, DiffOp, capture_diff_slices};
+use crate::authorship::imara_diff_utils::{
+    ByteDiff, ByteDiffOp, DiffAlgorithm, DiffOp, capture_diff_slices, capture_diff_slices_with,
+};
 use crate::authorship::move_detection::{DeletedLine, InsertedLine, detect_moves};
 use crate::authorship::working_log::CheckpointKind;
+use crate::config::Config;
 use crate::error::GitAiError;
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
+use std::sync::OnceLock;
 
 pub const INITIAL_ATTRIBUTION_TS: u128 = 42;
 
+static DIFF_ALGORITHM_OVERRIDE: OnceLock<DiffAlgorithm> = OnceLock::new();
+
+/// Set from the global `--diff-algorithm` flag
+pub fn set_diff_algorithm(algorithm: DiffAlgorithm) {
+    let _ = DIFF_ALGORITHM_OVERRIDE.set(algorithm);
+}
+
+/// Line diff algorithm for attribution: `--diff-algorithm`, then
+/// `GIT_AI_DIFF_ALGORITHM` (which also reaches hook subprocesses), then the
+/// `diff_algorithm` config key
+pub fn attribution_diff_algorithm() -> DiffAlgorithm {
+    if let Some(algorithm) = DIFF_ALGORITHM_OVERRIDE.get() {
+        return *algorithm;
+    }
+    std::env::var("GIT_AI_DIFF_ALGORITHM")
+        .ok()
+        .and_then(|name| DiffAlgorithm::parse(&name))
+        .unwrap_or_else(|| Config::get().diff_algorithm())
+}
+
 /// Represents a single attribution range in the file.
 /// Ranges can overlap (multiple authors can be attributed to the same text).
 #[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
@@ -261,12 +285,15 @@ struct DiffComputation {
 /// Configuration for the attribution tracker
 pub struct AttributionConfig {
     move_lines_threshold: usize,
+    /// Algorithm for the line diff; changed lines are always diffed by token with Myers
+    diff_algorithm: DiffAlgorithm,
 }
 
 impl Default for AttributionConfig {
     fn default() -> Self {
         AttributionConfig {
             move_lines_threshold: 3,
+            diff_algorithm: DiffAlgorithm::default(),
         }
     }
 }
@@ -277,10 +304,14 @@ pub struct AttributionTracker {
 }
 
 impl AttributionTracker {
-    /// Create a new attribution tracker with default configuration
+    /// Create a new attribution tracker with default configuration and the
+    /// user's choice of diff algorithm
     pub fn new() -> Self {
         AttributionTracker {
-            config: AttributionConfig::default(),
+            config: AttributionConfig {
+                diff_algorithm: attribution_diff_algorithm(),
+                ..AttributionConfig::default()
+            },
         }
     }
 
@@ -307,7 +338,11 @@ impl AttributionTracker {
             .map(|line| &new_content[line.start..line.end])
             .collect();
 
-        let line_ops = capture_diff_slices(&old_line_slices, &new_line_slices);
+        let line_ops = capture_diff_slices_with(
+            self.config.diff_algorithm,
+            &old_line_slices,
+            &new_line_slices,
+        );
 
         let mut computation = DiffComputation::default();
         let mut pending_changed: Vec<DiffOp> = Vec::new();
@@ -1448,6 +1483,125 @@ pub fn line_attributions_to_attributions(
     result
 }
 
+/// Keep the attributions `author_id` got in this update (`ts`) only where they
+/// fall on `line_ranges` (1-indexed, inclusive, in `content`); the rest of
+/// those edits go to `other_author_id`. Earlier attributions are untouched.
+pub fn restrict_attributions_to_lines(
+    attributions: Vec<Attribution>,
+    content: &str,
+    line_ranges: &[(u32, u32)],
+    author_id: &str,
+    other_author_id: &str,
+    ts: u128,
+) -> Vec<Attribution> {
+    let boundaries = LineBoundaries::new(content);
+    let mut allowed: Vec<(usize, usize)> = line_ranges
+        .iter()
+        .filter_map(|&(start, end)| {
+            let end = end.min(boundaries.line_count());
+            let (start_char, _) = boundaries.get_line_range(start.max(1))?;
+            let (_, end_char) = boundaries.get_line_range(end)?;
+            (start_char < end_char).then_some((start_char, end_char))
+        })
+        .collect();
+    allowed.sort_unstable();
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in allowed {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+
+    let mut result = Vec::new();
+    for attr in attributions {
+        if attr.author_id != author_id || attr.ts != ts {
+            result.push(attr);
+            continue;
+        }
+        let mut pos = attr.start;
+        for &(start, end) in &merged {
+            let start = start.max(pos);
+            let end = end.min(attr.end);
+            if start >= end {
+                continue;
+            }
+            if pos < start {
+                result.push(Attribution::new(
+                    pos,
+                    start,
+                    other_author_id.to_string(),
+                    ts,
+                ));
+            }
+            result.push(Attribution::new(start, end, author_id.to_string(), ts));
+            pos = end;
+        }
+        if pos < attr.end {
+            result.push(Attribution::new(
+                pos,
+                attr.end,
+                other_author_id.to_string(),
+                ts,
+            ));
+        }
+    }
+    result
+}
+
+/// Combine the attributions of a checkpoint computed without seeing some
+/// concurrent checkpoints (`stale`) with theirs (`concurrent`, already mapped
+/// onto the stale checkpoint's content). Text a concurrent checkpoint
+/// introduced, stamped with one of `concurrent_ts`, stays credited to it: the
+/// first checkpoint to record a change is the one that introduced it. The
+/// rest keeps the stale checkpoint's attributions.
+pub fn merge_concurrent_attributions(
+    stale: Vec<Attribution>,
+    concurrent: &[Attribution],
+    concurrent_ts: &[u128],
+) -> Vec<Attribution> {
+    let mut protected: Vec<&Attribution> = concurrent
+        .iter()
+        .filter(|attr| concurrent_ts.contains(&attr.ts) && attr.start < attr.end)
+        .collect();
+    if protected.is_empty() {
+        return stale;
+    }
+    protected.sort_by_key(|attr| attr.start);
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for attr in &protected {
+        match merged.last_mut() {
+            Some(last) if attr.start <= last.1 => last.1 = last.1.max(attr.end),
+            _ => merged.push((attr.start, attr.end)),
+        }
+    }
+
+    let mut result = Vec::new();
+    for attr in stale {
+        let mut pos = attr.start;
+        for &(start, end) in &merged {
+            if end <= pos || start >= attr.end {
+                continue;
+            }
+            if pos < start {
+                result.push(Attribution::new(
+                    pos,
+                    start,
+                    attr.author_id.clone(),
+                    attr.ts,
+                ));
+            }
+            pos = pos.max(end);
+        }
+        if pos < attr.end {
+            result.push(Attribution::new(pos, attr.end, attr.author_id, attr.ts));
+        }
+    }
+    result.extend(protected.into_iter().cloned());
+    result.sort_by_key(|attr| (attr.start, attr.end));
+    result
+}
+
 /// Convert character-based attributions to line-based attributions.
 /// For each line, selects the "dominant" author based on who contributed
 /// the most non-whitespace characters to that line.
@@ -1723,11 +1877,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn anchored_diff_algorithms_keep_moved_functions_with_their_author() {
+        let function = |n: usize| format!("fn f{}() {{\n    step{}();\n}}\n\n", n, n);
+        let old: String = [0, 1, 2, 3].map(function).concat();
+        // f2 moved to the top, and a line added to f3
+        let new = [2, 0, 1, 3]
+            .map(function)
+            .concat()
+            .replace("    step3();\n", "    step3();\n    log();\n");
+        let old_attrs = vec![Attribution::new(0, old.len(), "Alice".into(), TEST_TS)];
+
+        let authors_of_moved_function = |diff_algorithm| {
+            let tracker = AttributionTracker::with_config(AttributionConfig {
+                diff_algorithm,
+                ..AttributionConfig::default()
+            });
+            let updated = tracker
+                .update_attributions(&old, &new, &old_attrs, "Charlie", TEST_TS + 1)
+                .unwrap();
+            attributions_to_line_attributions(&updated, &new)
+                .into_iter()
+                .filter(|attr| attr.start_line <= 3)
+                .map(|attr| attr.author_id)
+                .collect::<Vec<_>>()
+        };
+
+        // Myers lines the moved function up with the braces it left behind
+        assert_eq!(authors_of_moved_function(DiffAlgorithm::Myers), ["Charlie"]);
+        assert_eq!(
+            authors_of_moved_function(DiffAlgorithm::Histogram),
+            ["Alice"]
+        );
+        assert_eq!(
+            authors_of_moved_function(DiffAlgorithm::Patience),
+            ["Alice"]
+        );
+    }
+
     #[test]
     fn move_block_preserves_original_authors_one_line_threshold() {
         let tracker = AttributionTracker::with_config(AttributionConfig {
             // Test with a one-line threshold
             move_lines_threshold: 1,
+            ..AttributionConfig::default()
         });
         let old = "fn helper() { println!(\"helper\"); }\nfn main() { println!(\"main\"); }\n";
         let new = "fn main() { println!(\"main\"); }\nfn helper() { println!(\"helper\"); }\n";
@@ -1894,4 +2087,60 @@ mod tests {
         assert_eq!(ai_block.start_line, 2);
         assert_eq!(ai_block.end_line, 17);
     }
+
+    #[test]
+    fn test_merge_concurrent_attributions() {
+        let stale = vec![
+            Attribution::new(0, 5, "human".to_string(), 1),
+            Attribution::new(5, 20, "b".to_string(), 3),
+        ];
+        let concurrent = vec![
+            Attribution::new(0, 5, "human".to_string(), 1),
+            Attribution::new(5, 12, "a".to_string(), 2),
+            Attribution::new(12, 20, "".to_string(), 0),
+        ];
+        let merged = merge_concurrent_attributions(stale.clone(), &concurrent, &[2]);
+        assert_eq!(
+            merged,
+            vec![
+                Attribution::new(0, 5, "human".to_string(), 1),
+                Attribution::new(5, 12, "a".to_string(), 2),
+                Attribution::new(12, 20, "b".to_string(), 3),
+            ]
+        );
+        assert_eq!(
+            merge_concurrent_attributions(stale.clone(), &concurrent, &[]),
+            stale
+        );
+    }
+
+    #[test]
+    fn test_restrict_attributions_to_lines() {
+        let content = "one\ntwo\nthree\nfour\n";
+        let attributions = vec![
+            Attribution::new(0, 4, "human".to_string(), 1),
+            Attribution::new(4, 19, "ai".to_string(), 2),
+        ];
+        let restricted =
+            restrict_attributions_to_lines(attributions, content, &[(3, 3)], "ai", "human", 2);
+        assert_eq!(
+            restricted,
+            vec![
+                Attribution::new(0, 4, "human".to_string(), 1),
+                Attribution::new(4, 8, "human".to_string(), 2),
+                Attribution::new(8, 14, "ai".to_string(), 2),
+                Attribution::new(14, 19, "human".to_string(), 2),
+            ]
+        );
+
+        let line_attributions = attributions_to_line_attributions(&restricted, content);
+        assert_eq!(
+            line_attributions
+                .iter()
+                .filter(|attr| attr.author_id == "ai")
+                .map(|attr| (attr.start_line, attr.end_line))
+                .collect::<Vec<_>>(),
+            vec![(3, 3)]
+        );
+    }
 }
@@ -1,7 +1,9 @@
 use crate::authorship::transcript::Message;
-use crate::authorship::working_log::AgentId;
+use crate::authorship::working_log::{AgentId, CheckpointKind};
 use serde::{Deserialize, Serialize};
-use std::fmt;
+use std::collections::{BTreeMap, HashMap};
+
+pub use crate::attribution_core::LineRange;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Author {
@@ -9,197 +11,151 @@ pub struct Author {
     pub email: String,
 }
 
-/// Represents either a single line or a range of lines
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
-pub enum LineRange {
-    Single(u32),
-    Range(u32, u32), // start, end (inclusive)
+/// Prompt session details stored in the top-level prompts map keyed by short hash (agent_id + tool)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptRecord {
+    pub agent_id: AgentId,
+    pub human_author: Option<String>,
+    pub messages: Vec<Message>,
+    #[serde(default)]
+    pub total_additions: u32,
+    #[serde(default)]
+    pub total_deletions: u32,
+    #[serde(default)]
+    pub accepted_lines: u32,
+    #[serde(default)]
+    pub overriden_lines: u32,
+    /// Written by a code generator, not an AI model
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub generated: bool,
+    /// Checkpoint kind that produced the session (e.g. "ai_tab", "imported");
+    /// absent for ordinary agent sessions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    /// `git-ai checkpoint --message` notes from the session, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+    /// `git-ai checkpoint --tag` labels from the session, e.g. "refactor pass"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Agent details reported by the preset, see [`agent_metadata_envelope`]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub agent_metadata: BTreeMap<String, String>,
 }
 
-impl LineRange {
-    pub fn contains(&self, line: u32) -> bool {
-        match self {
-            LineRange::Single(l) => *l == line,
-            LineRange::Range(start, end) => line >= *start && line <= *end,
-        }
-    }
-
-    #[allow(dead_code)]
-    pub fn overlaps(&self, other: &LineRange) -> bool {
-        match (self, other) {
-            (LineRange::Single(l1), LineRange::Single(l2)) => l1 == l2,
-            (LineRange::Single(l), LineRange::Range(start, end)) => *l >= *start && *l <= *end,
-            (LineRange::Range(start, end), LineRange::Single(l)) => *l >= *start && *l <= *end,
-            (LineRange::Range(start1, end1), LineRange::Range(start2, end2)) => {
-                start1 <= end2 && start2 <= end1
-            }
-        }
-    }
+/// `agent_metadata` keys that are kept in the authorship log. Everything else
+/// a preset reports (transcript paths and the like) only makes sense on the
+/// machine that made the checkpoint.
+pub const PRESERVED_AGENT_METADATA_KEYS: &[&str] = &[
+    "session_id",
+    "conversation_url",
+    "ide_version",
+    "provider",
+    "temperature",
+    "prompt_tokens",
+    "completion_tokens",
+    "transcript_sha256",
+    "transcript_bytes",
+];
 
-    /// Remove a line or range from this range, returning the remaining parts
-    #[allow(dead_code)]
-    pub fn remove(&self, to_remove: &LineRange) -> Vec<LineRange> {
-        match (self, to_remove) {
-            (LineRange::Single(l), LineRange::Single(r)) => {
-                if l == r {
-                    vec![]
-                } else {
-                    vec![self.clone()]
-                }
-            }
-            (LineRange::Single(l), LineRange::Range(start, end)) => {
-                if *l >= *start && *l <= *end {
-                    vec![]
-                } else {
-                    vec![self.clone()]
-                }
-            }
-            (LineRange::Range(start, end), LineRange::Single(r)) => {
-                if *r < *start || *r > *end {
-                    vec![self.clone()]
-                } else if *r == *start && *r == *end {
-                    vec![]
-                } else if *r == *start {
-                    vec![LineRange::Range(*start + 1, *end)]
-                } else if *r == *end {
-                    vec![LineRange::Range(*start, *end - 1)]
-                } else {
-                    vec![
-                        LineRange::Range(*start, *r - 1),
-                        LineRange::Range(*r + 1, *end),
-                    ]
-                }
-            }
-            (LineRange::Range(start1, end1), LineRange::Range(start2, end2)) => {
-                if *start2 > *end1 || *end2 < *start1 {
-                    // No overlap
-                    vec![self.clone()]
-                } else {
-                    let mut result = Vec::new();
-                    // Left part
-                    if *start1 < *start2 {
-                        result.push(LineRange::Range(*start1, *start2 - 1));
-                    }
-                    // Right part
-                    if *end1 > *end2 {
-                        result.push(LineRange::Range(*end2 + 1, *end1));
-                    }
-                    result
-                }
-            }
-        }
-    }
+/// `agent_metadata` keys counting the tokens one checkpoint used. A session's
+/// record holds the total over its checkpoints rather than the latest count.
+pub const TOKEN_COUNT_METADATA_KEYS: &[&str] = &["prompt_tokens", "completion_tokens"];
 
-    /// Convert a sorted list of line numbers into compressed ranges
-    pub fn compress_lines(lines: &[u32]) -> Vec<LineRange> {
-        if lines.is_empty() {
-            return vec![];
-        }
+/// Longest value kept for a single key, in characters
+const MAX_AGENT_METADATA_VALUE_CHARS: usize = 256;
 
-        let mut ranges = Vec::new();
-        let mut current_start = lines[0];
-        let mut current_end = lines[0];
+/// Upper bound on the whole envelope, in bytes, so a misbehaving preset can't
+/// bloat every note it touches
+const MAX_AGENT_METADATA_BYTES: usize = 1024;
 
-        for &line in &lines[1..] {
-            if line == current_end + 1 {
-                current_end = line;
-            } else {
-                // End current range and start new one
-                if current_start == current_end {
-                    ranges.push(LineRange::Single(current_start));
-                } else {
-                    ranges.push(LineRange::Range(current_start, current_end));
-                }
-                current_start = line;
-                current_end = line;
-            }
+/// The preserved subset of a checkpoint's `agent_metadata`: known keys only,
+/// trimmed, with long values cut short and the whole map size-capped
+pub fn agent_metadata_envelope<'a>(
+    metadata: impl IntoIterator<Item = (&'a String, &'a String)>,
+) -> BTreeMap<String, String> {
+    let metadata: BTreeMap<&String, &String> = metadata.into_iter().collect();
+    let mut envelope = BTreeMap::new();
+    let mut size = 0;
+    for key in PRESERVED_AGENT_METADATA_KEYS {
+        let Some(value) = metadata.get(&key.to_string()) else {
+            continue;
+        };
+        let value: String = value
+            .trim()
+            .chars()
+            .take(MAX_AGENT_METADATA_VALUE_CHARS)
+            .collect();
+        if value.is_empty() || size + key.len() + value.len() > MAX_AGENT_METADATA_BYTES {
+            continue;
         }
+        size += key.len() + value.len();
+        envelope.insert(key.to_string(), value);
+    }
+    envelope
+}
 
-        // Add the last range
-        if current_start == current_end {
-            ranges.push(LineRange::Single(current_start));
+/// A session's metadata after one more of its checkpoints: later values
+/// override earlier ones, except token counts, which add up
+pub fn merge_agent_metadata(
+    session: &BTreeMap<String, String>,
+    checkpoint: &HashMap<String, String>,
+) -> BTreeMap<String, String> {
+    let mut merged = session.clone();
+    for (key, value) in agent_metadata_envelope(checkpoint) {
+        if TOKEN_COUNT_METADATA_KEYS.contains(&key.as_str()) {
+            let Ok(tokens) = value.parse::<u64>() else {
+                continue;
+            };
+            let total = session
+                .get(&key)
+                .and_then(|total| total.parse::<u64>().ok())
+                .unwrap_or(0)
+                + tokens;
+            merged.insert(key, total.to_string());
         } else {
-            ranges.push(LineRange::Range(current_start, current_end));
+            merged.insert(key, value);
         }
-
-        ranges
     }
+    agent_metadata_envelope(&merged)
+}
 
-    #[allow(dead_code)]
-    pub fn expand(&self) -> Vec<u32> {
-        match self {
-            LineRange::Single(l) => vec![*l],
-            LineRange::Range(start, end) => (*start..=*end).collect(),
+impl PromptRecord {
+    /// Value stored in `kind` for a checkpoint kind; agent sessions and
+    /// generators (see `generated`) store nothing
+    pub fn kind_label(kind: CheckpointKind) -> Option<String> {
+        match kind {
+            CheckpointKind::AiAgent | CheckpointKind::Generated | CheckpointKind::Human => None,
+            other => Some(other.to_str()),
         }
     }
 
-    /// Shift line numbers by a given offset
-    /// - For insertions: offset is positive (shift lines down/forward)
-    /// - For deletions: offset is negative (shift lines up/backward)
-    /// - insertion_point: the line number where the change occurred
-    #[allow(dead_code)]
-    pub fn shift(&self, insertion_point: u32, offset: i32) -> Option<LineRange> {
-        match self {
-            LineRange::Single(l) => {
-                if *l >= insertion_point {
-                    let new_line = (*l as i32 + offset) as u32;
-                    Some(LineRange::Single(new_line))
-                } else {
-                    Some(LineRange::Single(*l))
-                }
-            }
-            LineRange::Range(start, end) => {
-                let new_start = if *start >= insertion_point {
-                    (*start as i32 + offset) as u32
-                } else {
-                    *start
-                };
-                let new_end = if *end >= insertion_point {
-                    (*end as i32 + offset) as u32
-                } else {
-                    *end
-                };
-
-                // Ensure the range is still valid
-                if new_start <= new_end {
-                    if new_start == new_end {
-                        Some(LineRange::Single(new_start))
-                    } else {
-                        Some(LineRange::Range(new_start, new_end))
-                    }
-                } else {
-                    None
-                }
-            }
+    /// The checkpoint kind behind this session, `AiAgent` unless recorded otherwise
+    pub fn checkpoint_kind(&self) -> CheckpointKind {
+        if self.generated {
+            return CheckpointKind::Generated;
         }
+        self.kind
+            .as_deref()
+            .and_then(CheckpointKind::parse)
+            .unwrap_or(CheckpointKind::AiAgent)
     }
-}
 
-impl fmt::Display for LineRange {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            LineRange::Single(l) => write!(f, "{}", l),
-            LineRange::Range(start, end) => write!(f, "[{}, {}]", start, end),
+    /// Add a checkpoint's note and tags, skipping ones the session already has
+    pub fn annotate(&mut self, message: Option<&str>, tags: &[String]) {
+        if let Some(message) = message
+            && !self.notes.iter().any(|note| note == message)
+        {
+            self.notes.push(message.to_string());
+        }
+        for tag in tags {
+            if !self.tags.contains(tag) {
+                self.tags.push(tag.clone());
+            }
         }
     }
 }
 
-/// Prompt session details stored in the top-level prompts map keyed by short hash (agent_id + tool)
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct PromptRecord {
-    pub agent_id: AgentId,
-    pub human_author: Option<String>,
-    pub messages: Vec<Message>,
-    #[serde(default)]
-    pub total_additions: u32,
-    #[serde(default)]
-    pub total_deletions: u32,
-    #[serde(default)]
-    pub accepted_lines: u32,
-    #[serde(default)]
-    pub overriden_lines: u32,
-}
-
 impl Eq for PromptRecord {}
 
 impl PartialOrd for PromptRecord {
@@ -250,6 +206,11 @@ mod tests {
             total_deletions: deletions,
             accepted_lines: 0,
             overriden_lines: 0,
+            generated: false,
+            kind: None,
+            notes: Vec::new(),
+            tags: Vec::new(),
+            agent_metadata: BTreeMap::new(),
         }
     }
 
@@ -277,4 +238,79 @@ mod tests {
                 || records[1].total_deletions > 0
         );
     }
+
+    #[test]
+    fn test_agent_metadata_envelope() {
+        let raw: BTreeMap<String, String> = [
+            ("session_id", " abc-123 "),
+            ("conversation_url", ""),
+            ("ide_version", &"9".repeat(400)),
+            ("transcript_path", "/home/me/.claude/session.jsonl"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let envelope = agent_metadata_envelope(&raw);
+        assert_eq!(envelope.len(), 2);
+        assert_eq!(envelope["session_id"], "abc-123");
+        assert_eq!(
+            envelope["ide_version"].len(),
+            MAX_AGENT_METADATA_VALUE_CHARS
+        );
+
+        // Old notes without the field still parse, and empty maps aren't written
+        let record = create_prompt_record(0, 1, 0);
+        let json = serde_json::to_string(&record).unwrap();
+        assert!(!json.contains("agent_metadata"));
+        let parsed: PromptRecord = serde_json::from_str(&json).unwrap();
+        assert!(parsed.agent_metadata.is_empty());
+    }
+
+    #[test]
+    fn test_merge_agent_metadata_sums_token_counts() {
+        let checkpoint = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        let session = merge_agent_metadata(
+            &BTreeMap::new(),
+            &checkpoint(&[
+                ("provider", "anthropic"),
+                ("temperature", "0.2"),
+                ("prompt_tokens", "1200"),
+                ("completion_tokens", "300"),
+            ]),
+        );
+        let session = merge_agent_metadata(
+            &session,
+            &checkpoint(&[
+                ("temperature", "0.7"),
+                ("prompt_tokens", "800"),
+                ("completion_tokens", "lots"),
+            ]),
+        );
+        assert_eq!(session["provider"], "anthropic");
+        assert_eq!(session["temperature"], "0.7");
+        assert_eq!(session["prompt_tokens"], "2000");
+        // Counts that aren't numbers are ignored
+        assert_eq!(session["completion_tokens"], "300");
+    }
+
+    #[test]
+    fn test_annotate_merges_session_notes_and_tags() {
+        let mut record = create_prompt_record(0, 1, 0);
+        record.annotate(Some("extract parser"), &["refactor pass".to_string()]);
+        record.annotate(None, &["refactor pass".to_string(), "perf".to_string()]);
+        record.annotate(Some("extract parser"), &[]);
+        record.annotate(Some("inline helpers"), &[]);
+
+        assert_eq!(record.notes, vec!["extract parser", "inline helpers"]);
+        assert_eq!(record.tags, vec!["refactor pass", "perf"]);
+
+        let json = serde_json::to_string(&create_prompt_record(0, 1, 0)).unwrap();
+        assert!(!json.contains("notes") && !json.contains("tags"));
+    }
 }
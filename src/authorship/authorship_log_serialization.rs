@@ -1,4 +1,7 @@
+use crate::attribution_core;
 use crate::authorship::authorship_log::{Author, LineRange, PromptRecord};
+use crate::authorship::commit_message::MessageAttribution;
+use crate::authorship::review::ReviewRecord;
 use crate::authorship::working_log::CheckpointKind;
 use crate::git::repository::Repository;
 use serde::{Deserialize, Serialize};
@@ -27,6 +30,29 @@ pub struct AuthorshipMetadata {
     pub git_ai_version: Option<String>,
     pub base_commit_sha: String,
     pub prompts: BTreeMap<String, PromptRecord>,
+    /// Issue-tracker references (e.g. "PROJ-123", "#456") from the commit message and branch
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tickets: Vec<String>,
+    /// Prompt hashes reconstructed after the fact by `git-ai import` rather than recorded live
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub backfilled_prompts: Vec<String>,
+    /// Lines added by each set of humans (e.g. "alice, bob" for a pair session), when
+    /// checkpoints named their authors
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub human_authors: BTreeMap<String, u32>,
+    /// Human reviews of this commit's AI lines, added by `git-ai review mark`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reviews: Vec<ReviewRecord>,
+    /// Commit this one was cherry-picked from, when its AI lines came across with it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cherry_picked_from: Option<String>,
+    /// Binary and oversized files, which get no line attestations: the author
+    /// id ("human" or a prompt hash) credited with each whole file
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub file_authors: BTreeMap<String, String>,
+    /// The agent that drafted the commit message, when the commit used its draft
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_message: Option<MessageAttribution>,
 }
 
 impl AuthorshipMetadata {
@@ -36,6 +62,13 @@ impl AuthorshipMetadata {
             git_ai_version: Some(GIT_AI_VERSION.to_string()),
             base_commit_sha: String::new(),
             prompts: BTreeMap::new(),
+            tickets: Vec::new(),
+            backfilled_prompts: Vec::new(),
+            human_authors: BTreeMap::new(),
+            reviews: Vec::new(),
+            cherry_picked_from: None,
+            file_authors: BTreeMap::new(),
+            commit_message: None,
         }
     }
 }
@@ -216,21 +249,10 @@ impl AuthorshipLog {
 
     /// Deserialize from the new text format
     pub fn deserialize_from_string(content: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        let lines: Vec<&str> = content.lines().collect();
-
-        // Find the divider
-        let divider_pos = lines
-            .iter()
-            .position(|&line| line == "---")
-            .ok_or("Missing divider '---' in authorship log")?;
-
-        // Parse attestation section (before divider)
-        let attestation_lines = &lines[..divider_pos];
-        let attestations = parse_attestation_section(attestation_lines)?;
+        let (attestation_lines, json_content) = attribution_core::split_note(content)?;
+        let attestations = parse_attestation_section(&attestation_lines)?;
 
         // Parse JSON metadata section (after divider)
-        let json_lines = &lines[divider_pos + 1..];
-        let json_content = json_lines.join("\n");
         let metadata: AuthorshipMetadata = serde_json::from_str(&json_content)?;
 
         Ok(Self {
@@ -551,106 +573,24 @@ fn format_line_ranges(ranges: &[LineRange]) -> String {
         .join(",")
 }
 
-/// Parse line ranges from a string like "1,2,19-222"
-/// No spaces are expected in the format
-fn parse_line_ranges(input: &str) -> Result<Vec<LineRange>, Box<dyn std::error::Error>> {
-    let mut ranges = Vec::new();
-
-    for part in input.split(',') {
-        if part.is_empty() {
-            continue;
-        }
-
-        if let Some(dash_pos) = part.find('-') {
-            // Range format: "start-end"
-            let start_str = &part[..dash_pos];
-            let end_str = &part[dash_pos + 1..];
-            let start: u32 = start_str.parse()?;
-            let end: u32 = end_str.parse()?;
-            ranges.push(LineRange::Range(start, end));
-        } else {
-            // Single line format: "line"
-            let line: u32 = part.parse()?;
-            ranges.push(LineRange::Single(line));
-        }
-    }
-
-    Ok(ranges)
-}
-
 /// Parse the attestation section (before the divider)
 fn parse_attestation_section(
     lines: &[&str],
 ) -> Result<Vec<FileAttestation>, Box<dyn std::error::Error>> {
-    let mut attestations = Vec::new();
-    let mut current_file: Option<FileAttestation> = None;
-
-    for line in lines {
-        let line = line.trim_end(); // Remove trailing whitespace but preserve leading
-
-        if line.is_empty() {
-            continue;
-        }
-
-        if line.starts_with("  ") {
-            // Attestation entry line (indented)
-            let entry_line = &line[2..]; // Remove "  " prefix
-
-            // Split on first space to separate hash from line ranges
-            if let Some(space_pos) = entry_line.find(' ') {
-                let hash = entry_line[..space_pos].to_string();
-                let rest = &entry_line[space_pos + 1..];
-
-                // Check if there's an "overrode:" suffix
-                let (ranges_str, overrode) = if let Some(overrode_pos) = rest.find(" overrode:") {
-                    let ranges_str = &rest[..overrode_pos];
-                    let overrode_hash = &rest[overrode_pos + 9..]; // Skip " overrode:"
-                    (ranges_str, Some(overrode_hash.to_string()))
-                } else {
-                    (rest, None)
-                };
-
-                let line_ranges = parse_line_ranges(ranges_str)?;
-
-                let entry = AttestationEntry::with_overrode(hash, line_ranges, overrode);
-
-                if let Some(ref mut file_attestation) = current_file {
-                    file_attestation.add_entry(entry);
-                } else {
-                    return Err("Attestation entry found without a file path".into());
-                }
-            } else {
-                return Err(format!("Invalid attestation entry format: {}", entry_line).into());
-            }
-        } else {
-            // File path line (not indented)
-            if let Some(file_attestation) = current_file.take() {
-                if !file_attestation.entries.is_empty() {
-                    attestations.push(file_attestation);
-                }
-            }
-
-            // Parse file path, handling quoted paths
-            let file_path = if line.starts_with('"') && line.ends_with('"') {
-                // Quoted path - remove quotes (no unescaping needed since quotes aren't allowed in file names)
-                line[1..line.len() - 1].to_string()
-            } else {
-                // Unquoted path
-                line.to_string()
-            };
-
-            current_file = Some(FileAttestation::new(file_path));
-        }
-    }
-
-    // Don't forget the last file
-    if let Some(file_attestation) = current_file {
-        if !file_attestation.entries.is_empty() {
-            attestations.push(file_attestation);
-        }
-    }
-
-    Ok(attestations)
+    let files = attribution_core::parse_attestation_section(lines)?;
+    Ok(files
+        .into_iter()
+        .map(|file| FileAttestation {
+            file_path: file.path,
+            entries: file
+                .entries
+                .into_iter()
+                .map(|entry| {
+                    AttestationEntry::with_overrode(entry.hash, entry.line_ranges, entry.overrode)
+                })
+                .collect(),
+        })
+        .collect())
 }
 
 /// Check if a file path needs quoting (contains spaces or whitespace)
@@ -671,6 +611,7 @@ pub fn generate_short_hash(agent_id: &str, tool: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::attribution_core::parse_line_ranges;
     use insta::assert_debug_snapshot;
 
     #[test]
@@ -807,6 +748,11 @@ mod tests {
                 total_deletions: 0,
                 accepted_lines: 0,
                 overriden_lines: 0,
+                generated: false,
+                kind: None,
+                notes: Vec::new(),
+                tags: Vec::new(),
+                agent_metadata: BTreeMap::new(),
             },
         );
 
@@ -873,6 +819,11 @@ mod tests {
                 total_deletions: 0,
                 accepted_lines: 0,
                 overriden_lines: 0,
+                generated: false,
+                kind: None,
+                notes: Vec::new(),
+                tags: Vec::new(),
+                agent_metadata: BTreeMap::new(),
             },
         );
 
@@ -921,6 +872,11 @@ mod tests {
                 total_deletions: 0,
                 accepted_lines: 0,
                 overriden_lines: 0,
+                generated: false,
+                kind: None,
+                notes: Vec::new(),
+                tags: Vec::new(),
+                agent_metadata: BTreeMap::new(),
             },
         );
 
@@ -1099,6 +1055,11 @@ mod tests {
                 total_deletions: 3,
                 accepted_lines: 11,
                 overriden_lines: 0,
+                generated: false,
+                kind: None,
+                notes: Vec::new(),
+                tags: Vec::new(),
+                agent_metadata: BTreeMap::new(),
             },
         );
 
@@ -1269,6 +1230,11 @@ mod tests {
                 total_deletions: 0,
                 accepted_lines: 10,
                 overriden_lines: 0,
+                generated: false,
+                kind: None,
+                notes: Vec::new(),
+                tags: Vec::new(),
+                agent_metadata: BTreeMap::new(),
             },
         );
 
@@ -1292,6 +1258,11 @@ mod tests {
                 total_deletions: 0,
                 accepted_lines: 20,
                 overriden_lines: 0,
+                generated: false,
+                kind: None,
+                notes: Vec::new(),
+                tags: Vec::new(),
+                agent_metadata: BTreeMap::new(),
             },
         );
 
@@ -0,0 +1,162 @@
+use crate::authorship::working_log::{Checkpoint, CheckpointKind};
+use crate::error::GitAiError;
+use crate::git::repository::Repository;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// Built-in `Name <email>` identities for agents that publish one
+const DEFAULT_IDENTITIES: &[(&str, &str)] = &[
+    ("claude", "Claude <noreply@anthropic.com>"),
+    ("cursor", "Cursor Agent <cursoragent@cursor.com>"),
+    (
+        "github-copilot",
+        "Copilot <175728472+Copilot@users.noreply.github.com>",
+    ),
+];
+
+/// The `co_author_trailers` section of ~/.git-ai/config.json
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CoAuthorTrailersConfig {
+    /// Append `Co-authored-by:` trailers for contributing agents (off by default)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Per-tool `Name <email>` overrides, keyed by agent tool (e.g. "claude")
+    #[serde(default)]
+    pub identities: BTreeMap<String, String>,
+}
+
+impl CoAuthorTrailersConfig {
+    /// Identity to credit for `tool`. Tools without a configured or built-in
+    /// identity get no trailer.
+    pub fn identity_for(&self, tool: &str) -> Option<String> {
+        if let Some(identity) = self.identities.get(tool) {
+            return Some(identity.clone());
+        }
+        DEFAULT_IDENTITIES
+            .iter()
+            .find(|(t, _)| *t == tool)
+            .map(|(_, identity)| identity.to_string())
+    }
+}
+
+/// Agent tools with AI checkpoints touching `files` (all files when `None`),
+/// in the order they first appear in the working log.
+pub fn contributing_tools(
+    checkpoints: &[Checkpoint],
+    files: Option<&HashSet<String>>,
+) -> Vec<String> {
+    let mut tools: Vec<String> = Vec::new();
+    for checkpoint in checkpoints {
+        // Code generators aren't co-authors
+        if matches!(
+            checkpoint.kind,
+            CheckpointKind::Human | CheckpointKind::Generated
+        ) {
+            continue;
+        }
+        let Some(agent_id) = &checkpoint.agent_id else {
+            continue;
+        };
+        let touches_commit = checkpoint
+            .entries
+            .iter()
+            .any(|entry| files.is_none_or(|files| files.contains(&entry.file)));
+        if touches_commit && !tools.contains(&agent_id.tool) {
+            tools.push(agent_id.tool.clone());
+        }
+    }
+    tools
+}
+
+/// `Co-authored-by:` trailers for the commit about to be created on top of HEAD.
+///
+/// `include_unstaged` should be set for `git commit -a`, where tracked changes
+/// are staged by git itself after this runs.
+pub fn co_author_trailers(
+    repo: &Repository,
+    config: &CoAuthorTrailersConfig,
+    include_unstaged: bool,
+) -> Result<Vec<String>, GitAiError> {
+    let base_commit = repo
+        .head()
+        .ok()
+        .and_then(|head| head.target().ok())
+        .unwrap_or_else(|| "initial".to_string());
+    let checkpoints = repo
+        .storage
+        .working_log_for_base_commit(&base_commit)
+        .read_all_checkpoints()?;
+
+    let files = if include_unstaged {
+        repo.get_staged_and_unstaged_filenames()?
+    } else {
+        repo.get_staged_filenames()?
+    };
+
+    let mut trailers: Vec<String> = Vec::new();
+    for tool in contributing_tools(&checkpoints, Some(&files)) {
+        if let Some(identity) = config.identity_for(&tool) {
+            let trailer = format!("Co-authored-by: {}", identity);
+            if !trailers.contains(&trailer) {
+                trailers.push(trailer);
+            }
+        }
+    }
+    Ok(trailers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::working_log::{AgentId, WorkingLogEntry};
+
+    fn checkpoint(kind: CheckpointKind, tool: Option<&str>, file: &str) -> Checkpoint {
+        let entry = WorkingLogEntry::new(file.to_string(), String::new(), vec![], vec![]);
+        let mut checkpoint = Checkpoint::new(kind, String::new(), "dev".to_string(), vec![entry]);
+        checkpoint.agent_id = tool.map(|tool| AgentId {
+            tool: tool.to_string(),
+            id: "session".to_string(),
+            model: "model".to_string(),
+        });
+        checkpoint
+    }
+
+    #[test]
+    fn test_contributing_tools_filters_by_committed_files() {
+        let checkpoints = vec![
+            checkpoint(CheckpointKind::AiAgent, Some("cursor"), "a.rs"),
+            checkpoint(CheckpointKind::Human, None, "a.rs"),
+            checkpoint(CheckpointKind::AiAgent, Some("claude"), "b.rs"),
+            checkpoint(CheckpointKind::AiTab, Some("cursor"), "b.rs"),
+        ];
+
+        let only_b: HashSet<String> = ["b.rs".to_string()].into_iter().collect();
+        assert_eq!(
+            contributing_tools(&checkpoints, Some(&only_b)),
+            vec!["claude", "cursor"]
+        );
+        assert_eq!(
+            contributing_tools(&checkpoints, None),
+            vec!["cursor", "claude"]
+        );
+    }
+
+    #[test]
+    fn test_identity_overrides_and_unknown_tools() {
+        let mut config = CoAuthorTrailersConfig::default();
+        assert_eq!(
+            config.identity_for("claude").as_deref(),
+            Some("Claude <noreply@anthropic.com>")
+        );
+        assert!(config.identity_for("some-editor").is_none());
+
+        config.identities.insert(
+            "some-editor".to_string(),
+            "Bot <bot@example.com>".to_string(),
+        );
+        assert_eq!(
+            config.identity_for("some-editor").as_deref(),
+            Some("Bot <bot@example.com>")
+        );
+    }
+}
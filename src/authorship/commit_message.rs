@@ -0,0 +1,153 @@
+//! Authorship of commit messages. An agent checkpoint can carry the commit
+//! message the agent drafted (`--commit-message`, or `commit_message` in the
+//! agent-v1 hook input); it is kept as a [`CommitMessageDraft`] until the next
+//! commit, whose authorship log then credits the message to the agent with a
+//! [`MessageAttribution`] if the committed message is (close to) the draft.
+
+use crate::error::GitAiError;
+use crate::git::repo_storage::RepoStorage;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// Share of the draft's lines the committed message has to keep for an edited
+/// message to still count as the agent's
+const MIN_KEPT_LINES: f64 = 0.5;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitMessageDraft {
+    pub message: String,
+    /// Prompt hash of the session that drafted it
+    pub author_id: String,
+    pub tool: String,
+    /// Unix seconds
+    pub timestamp: u64,
+}
+
+/// Who wrote a commit's message, stored in its authorship log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageAttribution {
+    /// Prompt hash of the session that drafted the message
+    pub author_id: String,
+    pub tool: String,
+    /// A human changed the draft before committing it
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub edited: bool,
+}
+
+/// Replaces any earlier draft: only the latest one can be the next commit's message
+pub fn save_draft(storage: &RepoStorage, draft: &CommitMessageDraft) -> Result<(), GitAiError> {
+    if let Some(dir) = storage.commit_message_draft.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&storage.commit_message_draft, serde_json::to_vec(draft)?)?;
+    Ok(())
+}
+
+/// The pending draft, removed so it can only be credited to one commit
+pub fn take_draft(storage: &RepoStorage) -> Option<CommitMessageDraft> {
+    let data = fs::read(&storage.commit_message_draft).ok()?;
+    let _ = fs::remove_file(&storage.commit_message_draft);
+    serde_json::from_slice(&data).ok()
+}
+
+/// Credit `committed` to the draft's agent if it is the draft, give or take
+/// whitespace and trailers appended after it. A message that kept at least
+/// half of the draft's lines counts as the agent's, edited; anything else is
+/// the human's own.
+pub fn attribute(draft: &CommitMessageDraft, committed: &str) -> Option<MessageAttribution> {
+    let draft_lines = normalized_lines(&draft.message);
+    if draft_lines.is_empty() {
+        return None;
+    }
+    let committed_lines = normalized_lines(committed);
+
+    let edited = if committed_lines.starts_with(&draft_lines)
+        && committed_lines[draft_lines.len()..]
+            .iter()
+            .all(|line| is_trailer(line))
+    {
+        false
+    } else {
+        let kept = draft_lines
+            .iter()
+            .filter(|line| committed_lines.contains(line))
+            .count();
+        if (kept as f64) < draft_lines.len() as f64 * MIN_KEPT_LINES {
+            return None;
+        }
+        true
+    };
+    Some(MessageAttribution {
+        author_id: draft.author_id.clone(),
+        tool: draft.tool.clone(),
+        edited,
+    })
+}
+
+/// Non-blank lines without trailing whitespace or `#` comments
+fn normalized_lines(message: &str) -> Vec<&str> {
+    message
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+}
+
+/// `Token: value` lines such as `Co-authored-by:` or `Signed-off-by:`
+fn is_trailer(line: &str) -> bool {
+    line.split_once(": ").is_some_and(|(token, _)| {
+        !token.is_empty() && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attribute_commit_message() {
+        let draft = CommitMessageDraft {
+            message: "Fix parser crash\n\nHandle empty input.\nAdd a test.\n".to_string(),
+            author_id: "abc123".to_string(),
+            tool: "claude".to_string(),
+            timestamp: 0,
+        };
+
+        let unedited = attribute(
+            &draft,
+            "Fix parser crash\n\nHandle empty input.  \nAdd a test.\n\nCo-authored-by: Bot <bot@example.com>\n",
+        )
+        .unwrap();
+        assert_eq!(unedited.tool, "claude");
+        assert!(!unedited.edited);
+
+        let edited = attribute(
+            &draft,
+            "Fix parser crash on empty input\n\nHandle empty input.\nAdd a test.\n",
+        )
+        .unwrap();
+        assert!(edited.edited);
+
+        assert_eq!(attribute(&draft, "WIP\n"), None);
+        let empty = CommitMessageDraft {
+            message: "\n".to_string(),
+            ..draft
+        };
+        assert_eq!(attribute(&empty, "\n"), None);
+    }
+
+    #[test]
+    fn test_take_draft_consumes_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = RepoStorage::for_repo_path(&dir.path().join(".git"), dir.path());
+        let draft = CommitMessageDraft {
+            message: "Add feature".to_string(),
+            author_id: "abc123".to_string(),
+            tool: "cursor".to_string(),
+            timestamp: 1,
+        };
+        save_draft(&storage, &draft).unwrap();
+        assert_eq!(take_draft(&storage), Some(draft));
+        assert_eq!(take_draft(&storage), None);
+    }
+}
@@ -0,0 +1,242 @@
+//! Template variables for commit messages, so AI involvement can show up in the
+//! message itself. Placeholders are only filled in where a message or
+//! `commit.template` asks for them:
+//!
+//! - `{{ai_pct}}` - share of added lines written by AI, 0-100
+//! - `{{ai_lines}}` / `{{human_lines}}` - added line counts
+//! - `{{agents}}` - comma-separated agent tools, or `none`
+//!
+//! Numbers come from the checkpoints in the working log for the files being
+//! committed.
+
+use crate::authorship::co_authors::contributing_tools;
+use crate::authorship::working_log::{Checkpoint, CheckpointKind};
+use crate::error::GitAiError;
+use crate::git::repository::Repository;
+use std::collections::HashSet;
+use std::path::Path;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TemplateVars {
+    pub ai_lines: u32,
+    pub human_lines: u32,
+    pub agents: Vec<String>,
+}
+
+impl TemplateVars {
+    pub fn from_checkpoints(checkpoints: &[Checkpoint], files: Option<&HashSet<String>>) -> Self {
+        let mut vars = TemplateVars {
+            agents: contributing_tools(checkpoints, files),
+            ..Default::default()
+        };
+        for checkpoint in checkpoints {
+            let additions = additions_in(checkpoint, files);
+            match checkpoint.kind {
+                CheckpointKind::Human => vars.human_lines += additions,
+                CheckpointKind::Generated => {}
+                _ => vars.ai_lines += additions,
+            }
+        }
+        vars
+    }
+
+    pub fn ai_pct(&self) -> u32 {
+        let total = self.ai_lines + self.human_lines;
+        if total == 0 {
+            0
+        } else {
+            ((self.ai_lines as f64 / total as f64) * 100.0).round() as u32
+        }
+    }
+
+    fn value(&self, name: &str) -> Option<String> {
+        match name {
+            "ai_pct" => Some(self.ai_pct().to_string()),
+            "ai_lines" => Some(self.ai_lines.to_string()),
+            "human_lines" => Some(self.human_lines.to_string()),
+            "agents" if self.agents.is_empty() => Some("none".to_string()),
+            "agents" => Some(self.agents.join(", ")),
+            _ => None,
+        }
+    }
+}
+
+/// Lines `checkpoint` added to `files`. Entries from older logs carry no
+/// per-file count; the checkpoint total is used when every file it touched is
+/// included, and nothing otherwise.
+fn additions_in(checkpoint: &Checkpoint, files: Option<&HashSet<String>>) -> u32 {
+    let Some(files) = files else {
+        return checkpoint.line_stats.additions;
+    };
+    let included = |file: &String| files.contains(file);
+    if checkpoint
+        .entries
+        .iter()
+        .all(|entry| entry.additions.is_none())
+    {
+        let all_included = checkpoint.entries.iter().all(|entry| included(&entry.file));
+        return if all_included {
+            checkpoint.line_stats.additions
+        } else {
+            0
+        };
+    }
+    checkpoint
+        .entries
+        .iter()
+        .filter(|entry| included(&entry.file))
+        .filter_map(|entry| entry.additions)
+        .sum()
+}
+
+/// Fill in known `{{ name }}` placeholders. Returns None when `text` has none,
+/// so callers can leave the message untouched. Unknown placeholders are kept.
+pub fn render(text: &str, vars: &TemplateVars) -> Option<String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    let mut replaced = false;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + len].trim();
+        out.push_str(&rest[..start]);
+        match vars.value(name) {
+            Some(value) => {
+                out.push_str(&value);
+                replaced = true;
+            }
+            None => out.push_str(&rest[start..start + len + 2]),
+        }
+        rest = &rest[start + len + 2..];
+    }
+    out.push_str(rest);
+    replaced.then_some(out)
+}
+
+/// Variables for the commit about to be created on top of HEAD.
+///
+/// `include_unstaged` should be set for `git commit -a`, where tracked changes
+/// are staged by git itself after this runs.
+pub fn commit_template_vars(
+    repo: &Repository,
+    include_unstaged: bool,
+) -> Result<TemplateVars, GitAiError> {
+    let base_commit = repo
+        .head()
+        .ok()
+        .and_then(|head| head.target().ok())
+        .unwrap_or_else(|| "initial".to_string());
+    let checkpoints = repo
+        .storage
+        .working_log_for_base_commit(&base_commit)
+        .read_all_checkpoints()?;
+    let files = if include_unstaged {
+        repo.get_staged_and_unstaged_filenames()?
+    } else {
+        repo.get_staged_filenames()?
+    };
+    Ok(TemplateVars::from_checkpoints(&checkpoints, Some(&files)))
+}
+
+/// Render the placeholders in a message file in place (the `prepare-commit-msg`
+/// hook). Returns whether anything changed.
+pub fn render_message_file(repo: &Repository, path: &Path) -> Result<bool, GitAiError> {
+    let message = std::fs::read_to_string(path)?;
+    if !message.contains("{{") {
+        return Ok(false);
+    }
+    let vars = commit_template_vars(repo, false)?;
+    match render(&message, &vars) {
+        Some(rendered) => {
+            std::fs::write(path, rendered)?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::working_log::{AgentId, WorkingLogEntry};
+
+    fn checkpoint(kind: CheckpointKind, tool: Option<&str>, file: &str, added: u32) -> Checkpoint {
+        let entry = WorkingLogEntry::new(file.to_string(), String::new(), vec![], vec![]);
+        let mut checkpoint = Checkpoint::new(kind, String::new(), "dev".to_string(), vec![entry]);
+        checkpoint.agent_id = tool.map(|tool| AgentId {
+            tool: tool.to_string(),
+            id: "session".to_string(),
+            model: "model".to_string(),
+        });
+        checkpoint.line_stats.additions = added;
+        checkpoint
+    }
+
+    #[test]
+    fn test_vars_from_checkpoints() {
+        let checkpoints = vec![
+            checkpoint(CheckpointKind::AiAgent, Some("claude"), "a.rs", 30),
+            checkpoint(CheckpointKind::Human, None, "a.rs", 10),
+            checkpoint(CheckpointKind::AiTab, Some("cursor"), "b.rs", 50),
+        ];
+        let only_a: HashSet<String> = ["a.rs".to_string()].into_iter().collect();
+
+        let vars = TemplateVars::from_checkpoints(&checkpoints, Some(&only_a));
+        assert_eq!(vars.ai_lines, 30);
+        assert_eq!(vars.human_lines, 10);
+        assert_eq!(vars.ai_pct(), 75);
+        assert_eq!(vars.agents, vec!["claude"]);
+        assert_eq!(TemplateVars::default().ai_pct(), 0);
+    }
+
+    #[test]
+    fn test_vars_count_only_staged_files_of_a_checkpoint() {
+        let mut both = checkpoint(CheckpointKind::AiAgent, Some("claude"), "a.rs", 50);
+        let mut unstaged = WorkingLogEntry::new("b.rs".to_string(), String::new(), vec![], vec![]);
+        unstaged.additions = Some(45);
+        both.entries[0].additions = Some(5);
+        both.entries.push(unstaged);
+        let only_a: HashSet<String> = ["a.rs".to_string()].into_iter().collect();
+
+        let vars = TemplateVars::from_checkpoints(&[both.clone()], Some(&only_a));
+        assert_eq!(vars.ai_lines, 5);
+        assert_eq!(TemplateVars::from_checkpoints(&[both], None).ai_lines, 50);
+
+        // Older logs without per-file counts can't be split
+        let mut legacy = checkpoint(CheckpointKind::AiAgent, Some("claude"), "a.rs", 50);
+        legacy.entries.push(WorkingLogEntry::new(
+            "b.rs".to_string(),
+            String::new(),
+            vec![],
+            vec![],
+        ));
+        assert_eq!(
+            TemplateVars::from_checkpoints(&[legacy], Some(&only_a)).ai_lines,
+            0
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let vars = TemplateVars {
+            ai_lines: 3,
+            human_lines: 1,
+            agents: vec!["claude".to_string(), "cursor".to_string()],
+        };
+        assert_eq!(
+            render(
+                "feat: x\n\nAI: {{ai_pct}}% ({{ agents }}) {{unknown}}",
+                &vars
+            )
+            .as_deref(),
+            Some("feat: x\n\nAI: 75% (claude, cursor) {{unknown}}")
+        );
+        assert_eq!(render("plain message", &vars), None);
+        assert_eq!(render("{{unknown}} and {{", &vars), None);
+        assert_eq!(
+            render("{{agents}}", &TemplateVars::default()).as_deref(),
+            Some("none")
+        );
+    }
+}
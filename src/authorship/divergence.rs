@@ -0,0 +1,170 @@
+//! Working-log divergence: files whose working-tree content no longer matches the
+//! last snapshot git-ai took of them, although they were edited before the most
+//! recent checkpoint ran, e.g. while no checkpoint hook was running. Without a
+//! check those lines would be diffed against the previous checkpoint and could be
+//! credited to the wrong author, so they are recorded as a human checkpoint by the
+//! `UNTRACKED_HUMAN_EDIT` author. Edits made after the most recent checkpoint are
+//! ordinary pending work for the next checkpoint to attribute.
+
+use crate::authorship::working_log::{AgentId, CheckpointKind};
+use crate::commands::checkpoint::{self, CheckpointOptions};
+use crate::commands::checkpoint_agent::agent_presets::AgentRunResult;
+use crate::error::GitAiError;
+use crate::git::repo_storage::PersistedWorkingLog;
+use crate::git::repository::Repository;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::time::UNIX_EPOCH;
+
+/// Author set recorded for edits git-ai did not see happen
+pub const UNTRACKED_HUMAN_EDIT: &str = "untracked human edit";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DivergentFile {
+    pub path: String,
+    /// Content hash in the most recent checkpoint touching the file
+    pub snapshot_sha: String,
+    /// Content hash in the working tree, `None` if the file is gone
+    pub worktree_sha: Option<String>,
+}
+
+/// Compare each file's latest working-log snapshot against the working tree,
+/// keeping the files last modified before the most recent checkpoint
+pub fn detect_divergence(
+    working_log: &PersistedWorkingLog,
+) -> Result<Vec<DivergentFile>, GitAiError> {
+    let checkpoints = working_log.read_all_checkpoints()?;
+    let Some(last_checkpoint_at) = checkpoints.last().map(|checkpoint| checkpoint.timestamp) else {
+        return Ok(Vec::new());
+    };
+    // Later checkpoints overwrite earlier snapshots of the same file
+    let mut snapshots: BTreeMap<String, String> = BTreeMap::new();
+    for checkpoint in checkpoints {
+        for entry in checkpoint.entries {
+            snapshots.insert(entry.file, entry.blob_sha);
+        }
+    }
+
+    let mut divergent = Vec::new();
+    for (path, snapshot_sha) in snapshots {
+        let abs_path = working_log.to_repo_absolute_path(&path);
+        let metadata = std::fs::metadata(&abs_path).ok();
+        // Checkpoint timestamps are whole seconds, so an edit in the same second
+        // counts as after it
+        if let Some(modified) = metadata.as_ref().and_then(|m| m.modified().ok())
+            && modified
+                .duration_since(UNIX_EPOCH)
+                .is_ok_and(|age| age.as_secs() >= last_checkpoint_at)
+        {
+            continue;
+        }
+        // Hashed the way checkpoints hash it, so unreadable files compare as empty
+        let worktree_sha = metadata.map(|_| {
+            let content = std::fs::read_to_string(&abs_path).unwrap_or_default();
+            format!("{:x}", Sha256::digest(content.as_bytes()))
+        });
+        let unchanged = match &worktree_sha {
+            Some(sha) => *sha == snapshot_sha,
+            // A deleted file matches a snapshot of an empty one
+            None => snapshot_sha == format!("{:x}", Sha256::digest(b"")),
+        };
+        if !unchanged {
+            divergent.push(DivergentFile {
+                path,
+                snapshot_sha,
+                worktree_sha,
+            });
+        }
+    }
+    Ok(divergent)
+}
+
+/// Record a human checkpoint, scoped to the divergent files, attributing their
+/// changes since the last snapshot to `UNTRACKED_HUMAN_EDIT`
+pub fn record_untracked_edits(
+    repo: &Repository,
+    author: &str,
+    files: &[DivergentFile],
+) -> Result<(), GitAiError> {
+    if files.is_empty() {
+        return Ok(());
+    }
+    let scope = AgentRunResult {
+        agent_id: AgentId {
+            tool: "git-ai".to_string(),
+            id: "divergence".to_string(),
+            model: "unknown".to_string(),
+        },
+        agent_metadata: None,
+        checkpoint_kind: CheckpointKind::Human,
+        transcript: None,
+        repo_working_dir: None,
+        edited_filepaths: None,
+        will_edit_filepaths: Some(files.iter().map(|file| file.path.clone()).collect()),
+        dirty_files: None,
+        commit_message: None,
+        edited_line_ranges: None,
+        append_transcript: false,
+    };
+    checkpoint::run_with_options(
+        repo,
+        author,
+        CheckpointKind::Human,
+        Some(scope),
+        CheckpointOptions {
+            human_authors: vec![UNTRACKED_HUMAN_EDIT.to_string()],
+            quiet: true,
+            ..Default::default()
+        },
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_untracked_edit_is_detected_and_recorded() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "one\n", true).unwrap();
+        tmp_repo.commit_with_message("initial").unwrap();
+
+        tmp_repo
+            .write_file("a.txt", "one\nfrom ai\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", None, None)
+            .unwrap();
+        let repo = tmp_repo.gitai_repo();
+        let working_log = repo.storage.working_log_for_base_commit("initial");
+        assert!(detect_divergence(&working_log).unwrap().is_empty());
+
+        // An edit after the last checkpoint is left for the next one
+        tmp_repo
+            .write_file("a.txt", "one\nfrom ai\nby hand\n", true)
+            .unwrap();
+        assert!(detect_divergence(&working_log).unwrap().is_empty());
+
+        // One from before it was made while no checkpoint hook was running
+        let checkpoints = working_log.read_all_checkpoints().unwrap();
+        let last_checkpoint_at = checkpoints.last().unwrap().timestamp;
+        filetime::set_file_mtime(
+            tmp_repo.path().join("a.txt"),
+            filetime::FileTime::from_unix_time(last_checkpoint_at as i64 - 60, 0),
+        )
+        .unwrap();
+        let divergent = detect_divergence(&working_log).unwrap();
+        assert_eq!(divergent.len(), 1);
+        assert_eq!(divergent[0].path, "a.txt");
+
+        record_untracked_edits(repo, "Test User <test@example.com>", &divergent).unwrap();
+        assert!(detect_divergence(&working_log).unwrap().is_empty());
+        let checkpoints = working_log.read_all_checkpoints().unwrap();
+        let last = checkpoints.last().unwrap();
+        assert_eq!(last.kind, CheckpointKind::Human);
+        assert_eq!(last.human_authors, vec![UNTRACKED_HUMAN_EDIT.to_string()]);
+        assert_eq!(last.line_stats.additions, 1);
+    }
+}
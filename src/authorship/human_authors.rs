@@ -0,0 +1,153 @@
+//! Pair and mob sessions: human checkpoints can name the set of people at the
+//! keyboard (`git-ai checkpoint --human --authors "alice,bob"`). The lines
+//! each set added are recorded in the authorship note so stats can split human
+//! lines between them.
+
+use crate::authorship::working_log::{Checkpoint, CheckpointKind};
+use std::collections::{BTreeMap, HashSet};
+
+/// Parse a comma-separated `--authors` value, dropping blanks and repeats
+pub fn parse_authors(value: &str) -> Vec<String> {
+    let mut authors: Vec<String> = Vec::new();
+    for author in value.split(',').map(str::trim) {
+        if !author.is_empty() && !authors.iter().any(|a| a == author) {
+            authors.push(author.to_string());
+        }
+    }
+    authors
+}
+
+/// Stable key for a set of authors, independent of the order they were given in
+pub fn author_set_key(authors: &[String]) -> String {
+    let mut sorted: Vec<&str> = authors.iter().map(String::as_str).collect();
+    sorted.sort_unstable();
+    sorted.dedup();
+    sorted.join(", ")
+}
+
+/// Lines added by human checkpoints touching `files` (all files when `None`),
+/// keyed by author set. Checkpoints without an explicit set count for their
+/// own author. Empty unless at least one checkpoint named its authors, so solo
+/// work doesn't add anything to the note.
+pub fn human_author_lines(
+    checkpoints: &[Checkpoint],
+    files: Option<&HashSet<String>>,
+) -> BTreeMap<String, u32> {
+    let human = || {
+        checkpoints.iter().filter(|checkpoint| {
+            checkpoint.kind == CheckpointKind::Human
+                && checkpoint
+                    .entries
+                    .iter()
+                    .any(|entry| files.is_none_or(|files| files.contains(&entry.file)))
+        })
+    };
+    if !human().any(|checkpoint| !checkpoint.human_authors.is_empty()) {
+        return BTreeMap::new();
+    }
+
+    let mut lines = BTreeMap::new();
+    for checkpoint in human() {
+        let key = if checkpoint.human_authors.is_empty() {
+            checkpoint.author.clone()
+        } else {
+            author_set_key(&checkpoint.human_authors)
+        };
+        *lines.entry(key).or_default() += checkpoint.line_stats.additions;
+    }
+    lines.retain(|_, added| *added > 0);
+    lines
+}
+
+/// Split a commit's human additions between author sets in proportion to what
+/// each set added while working. Shares always sum to `human_additions`.
+pub fn split_human_additions(
+    human_additions: u32,
+    lines_by_set: &BTreeMap<String, u32>,
+) -> BTreeMap<String, u32> {
+    let total: u64 = lines_by_set.values().map(|&lines| lines as u64).sum();
+    if total == 0 {
+        return BTreeMap::new();
+    }
+
+    // Largest remainder: floor every share, then hand out what's left
+    let mut shares: Vec<(&String, u32, u64)> = lines_by_set
+        .iter()
+        .map(|(set, &lines)| {
+            let exact = human_additions as u64 * lines as u64;
+            (set, (exact / total) as u32, exact % total)
+        })
+        .collect();
+    let assigned: u32 = shares.iter().map(|(_, share, _)| share).sum();
+    let mut by_remainder: Vec<usize> = (0..shares.len()).collect();
+    by_remainder.sort_by(|&a, &b| shares[b].2.cmp(&shares[a].2));
+    for &i in by_remainder
+        .iter()
+        .take((human_additions - assigned) as usize)
+    {
+        shares[i].1 += 1;
+    }
+
+    shares
+        .into_iter()
+        .map(|(set, share, _)| (set.clone(), share))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::working_log::WorkingLogEntry;
+
+    fn checkpoint(kind: CheckpointKind, author: &str, authors: &[&str], added: u32) -> Checkpoint {
+        let entry = WorkingLogEntry::new("a.rs".to_string(), String::new(), vec![], vec![]);
+        let mut checkpoint = Checkpoint::new(kind, String::new(), author.to_string(), vec![entry]);
+        checkpoint.human_authors = authors.iter().map(|a| a.to_string()).collect();
+        checkpoint.line_stats.additions = added;
+        checkpoint
+    }
+
+    #[test]
+    fn test_parse_authors() {
+        assert_eq!(parse_authors(" bob, alice,,bob "), vec!["bob", "alice"]);
+        assert!(parse_authors(" , ").is_empty());
+        assert_eq!(
+            author_set_key(&parse_authors("bob,alice")),
+            author_set_key(&parse_authors("alice, bob"))
+        );
+    }
+
+    #[test]
+    fn test_human_author_lines() {
+        // Solo work records nothing
+        let solo = vec![checkpoint(CheckpointKind::Human, "alice", &[], 5)];
+        assert!(human_author_lines(&solo, None).is_empty());
+
+        let checkpoints = vec![
+            checkpoint(CheckpointKind::Human, "alice", &["bob", "alice"], 30),
+            checkpoint(CheckpointKind::Human, "alice", &["alice", "bob"], 10),
+            checkpoint(CheckpointKind::Human, "carol", &[], 20),
+            checkpoint(CheckpointKind::AiAgent, "carol", &[], 100),
+        ];
+        let lines = human_author_lines(&checkpoints, None);
+        assert_eq!(lines.get("alice, bob"), Some(&40));
+        assert_eq!(lines.get("carol"), Some(&20));
+        assert_eq!(lines.len(), 2);
+
+        let other_file: HashSet<String> = ["b.rs".to_string()].into_iter().collect();
+        assert!(human_author_lines(&checkpoints, Some(&other_file)).is_empty());
+    }
+
+    #[test]
+    fn test_split_human_additions() {
+        let lines: BTreeMap<String, u32> =
+            [("alice, bob".to_string(), 2), ("carol".to_string(), 1)]
+                .into_iter()
+                .collect();
+        let split = split_human_additions(10, &lines);
+        assert_eq!(split.get("alice, bob"), Some(&7));
+        assert_eq!(split.get("carol"), Some(&3));
+        assert_eq!(split_human_additions(0, &lines).values().sum::<u32>(), 0);
+        assert!(split_human_additions(10, &BTreeMap::new()).is_empty());
+    }
+}
@@ -4,8 +4,42 @@
 //! for accurate line attribution tracking.
 
 use imara_diff::{Algorithm, Diff, InternedInput, TokenSource};
+use std::collections::HashMap;
 use std::hash::Hash;
 
+/// Algorithm for line diffs whose result decides attribution. Myers finds
+/// the shortest edit script; histogram and patience anchor on lines that
+/// occur once, so a moved block shows up as moved rather than as edits of
+/// whatever lines it happened to line up with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiffAlgorithm {
+    #[default]
+    Myers,
+    Histogram,
+    Patience,
+}
+
+impl DiffAlgorithm {
+    pub const NAMES: &[&str] = &["myers", "histogram", "patience"];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "myers" => Some(DiffAlgorithm::Myers),
+            "histogram" => Some(DiffAlgorithm::Histogram),
+            "patience" => Some(DiffAlgorithm::Patience),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiffAlgorithm::Myers => "myers",
+            DiffAlgorithm::Histogram => "histogram",
+            DiffAlgorithm::Patience => "patience",
+        }
+    }
+}
+
 // ============================================================================
 // Byte-level diff types (replacing diff_match_patch_rs)
 // ============================================================================
@@ -130,11 +164,179 @@ impl<'a, T: Clone + Hash + Eq> TokenSource for SliceTokenSource<'a, T> {
 /// # Returns
 /// A vector of `DiffOp` representing the changes between old and new.
 pub fn capture_diff_slices<T: Hash + Eq + Clone>(old: &[T], new: &[T]) -> Vec<DiffOp> {
+    capture_diff_slices_with(DiffAlgorithm::Myers, old, new)
+}
+
+/// [`capture_diff_slices`] with a choice of algorithm
+pub fn capture_diff_slices_with<T: Hash + Eq + Clone>(
+    algorithm: DiffAlgorithm,
+    old: &[T],
+    new: &[T],
+) -> Vec<DiffOp> {
+    let algorithm = match algorithm {
+        DiffAlgorithm::Myers => Algorithm::Myers,
+        DiffAlgorithm::Histogram => Algorithm::Histogram,
+        DiffAlgorithm::Patience => {
+            let mut matches = Vec::new();
+            patience_matches(old, new, (0, old.len()), (0, new.len()), &mut matches);
+            return matches_to_diff_ops(&matches, old.len(), new.len());
+        }
+    };
     let input = InternedInput::new(SliceTokenSource::new(old), SliceTokenSource::new(new));
-    let diff = Diff::compute(Algorithm::Myers, &input);
+    let diff = Diff::compute(algorithm, &input);
     hunks_to_diff_ops(&diff, old.len(), new.len())
 }
 
+/// Collects the (old, new) index pairs patience diff keeps equal within
+/// `old[old_range]` and `new[new_range]`, in order. Lines that occur exactly
+/// once on each side anchor the diff; the gaps between anchors are diffed
+/// the same way, and gaps without any unique line fall back to Myers.
+fn patience_matches<T: Hash + Eq + Clone>(
+    old: &[T],
+    new: &[T],
+    (mut old_start, mut old_end): (usize, usize),
+    (mut new_start, mut new_end): (usize, usize),
+    matches: &mut Vec<(usize, usize)>,
+) {
+    while old_start < old_end && new_start < new_end && old[old_start] == new[new_start] {
+        matches.push((old_start, new_start));
+        old_start += 1;
+        new_start += 1;
+    }
+    let mut suffix = Vec::new();
+    while old_start < old_end && new_start < new_end && old[old_end - 1] == new[new_end - 1] {
+        old_end -= 1;
+        new_end -= 1;
+        suffix.push((old_end, new_end));
+    }
+
+    // Occurrences in old and new, and where the line is on each side
+    let mut counts: HashMap<&T, (usize, usize, usize, usize)> = HashMap::new();
+    for (i, line) in old.iter().enumerate().take(old_end).skip(old_start) {
+        let entry = counts.entry(line).or_default();
+        entry.0 += 1;
+        entry.2 = i;
+    }
+    for (j, line) in new.iter().enumerate().take(new_end).skip(new_start) {
+        if let Some(entry) = counts.get_mut(line) {
+            entry.1 += 1;
+            entry.3 = j;
+        }
+    }
+    let mut unique: Vec<(usize, usize)> = counts
+        .into_values()
+        .filter(|(in_old, in_new, _, _)| *in_old == 1 && *in_new == 1)
+        .map(|(_, _, i, j)| (i, j))
+        .collect();
+    unique.sort_unstable();
+    let anchors = longest_increasing_by_new(&unique);
+
+    if anchors.is_empty() {
+        let ops = capture_diff_slices_with(
+            DiffAlgorithm::Myers,
+            &old[old_start..old_end],
+            &new[new_start..new_end],
+        );
+        for op in ops {
+            if let DiffOp::Equal {
+                old_index,
+                new_index,
+                len,
+            } = op
+            {
+                matches.extend(
+                    (0..len).map(|k| (old_start + old_index + k, new_start + new_index + k)),
+                );
+            }
+        }
+    } else {
+        let (mut old_pos, mut new_pos) = (old_start, new_start);
+        for (i, j) in anchors {
+            patience_matches(old, new, (old_pos, i), (new_pos, j), matches);
+            matches.push((i, j));
+            (old_pos, new_pos) = (i + 1, j + 1);
+        }
+        patience_matches(old, new, (old_pos, old_end), (new_pos, new_end), matches);
+    }
+    matches.extend(suffix.into_iter().rev());
+}
+
+/// Longest subsequence of `pairs` (sorted by old index) whose new indices
+/// increase too
+fn longest_increasing_by_new(pairs: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    // tails[k]: index in `pairs` of the smallest tail of an increasing run of length k + 1
+    let mut tails: Vec<usize> = Vec::new();
+    let mut previous: Vec<Option<usize>> = vec![None; pairs.len()];
+    for (index, (_, new_index)) in pairs.iter().enumerate() {
+        let position = tails.partition_point(|&tail| pairs[tail].1 < *new_index);
+        previous[index] = position.checked_sub(1).map(|p| tails[p]);
+        if position == tails.len() {
+            tails.push(index);
+        } else {
+            tails[position] = index;
+        }
+    }
+    let mut run = Vec::with_capacity(tails.len());
+    let mut current = tails.last().copied();
+    while let Some(index) = current {
+        run.push(pairs[index]);
+        current = previous[index];
+    }
+    run.reverse();
+    run
+}
+
+/// Converts equal (old, new) index pairs, in order, to a vector of DiffOp
+fn matches_to_diff_ops(matches: &[(usize, usize)], old_len: usize, new_len: usize) -> Vec<DiffOp> {
+    let mut ops = Vec::new();
+    let (mut old_idx, mut new_idx) = (0, 0);
+    for &(i, j) in matches {
+        push_change(&mut ops, (old_idx, i), (new_idx, j));
+        match ops.last_mut() {
+            Some(DiffOp::Equal {
+                old_index,
+                new_index,
+                len,
+            }) if *old_index + *len == i && *new_index + *len == j => *len += 1,
+            _ => ops.push(DiffOp::Equal {
+                old_index: i,
+                new_index: j,
+                len: 1,
+            }),
+        }
+        (old_idx, new_idx) = (i + 1, j + 1);
+    }
+    push_change(&mut ops, (old_idx, old_len), (new_idx, new_len));
+    ops
+}
+
+/// Pushes the op for the unmatched `[start, end)` ranges of old and new, if any
+fn push_change(
+    ops: &mut Vec<DiffOp>,
+    (old_index, old_end): (usize, usize),
+    (new_index, new_end): (usize, usize),
+) {
+    match (old_end - old_index, new_end - new_index) {
+        (0, 0) => {}
+        (old_len, 0) => ops.push(DiffOp::Delete {
+            old_index,
+            old_len,
+            new_index,
+        }),
+        (0, new_len) => ops.push(DiffOp::Insert {
+            old_index,
+            new_index,
+            new_len,
+        }),
+        (old_len, new_len) => ops.push(DiffOp::Replace {
+            old_index,
+            old_len,
+            new_index,
+            new_len,
+        }),
+    }
+}
+
 /// Represents a change in a line-based diff.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum LineChangeTag {
@@ -440,6 +642,115 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_diff_algorithms_on_moved_block() {
+        // A function moved below another one that shares its braces and
+        // blank lines
+        let old = vec![
+            "fn a() {",
+            "    one();",
+            "}",
+            "",
+            "fn b() {",
+            "    two();",
+            "}",
+            "",
+        ];
+        let new = vec![
+            "fn b() {",
+            "    two();",
+            "}",
+            "",
+            "fn a() {",
+            "    one();",
+            "}",
+            "",
+        ];
+
+        for algorithm in [
+            DiffAlgorithm::Myers,
+            DiffAlgorithm::Histogram,
+            DiffAlgorithm::Patience,
+        ] {
+            let ops = capture_diff_slices_with(algorithm, &old, &new);
+            // Every op covers the inputs without gaps
+            let (mut old_pos, mut new_pos) = (0, 0);
+            for op in &ops {
+                let (old_index, old_len, new_index, new_len) = match *op {
+                    DiffOp::Equal {
+                        old_index,
+                        new_index,
+                        len,
+                    } => {
+                        assert_eq!(
+                            old[old_index..old_index + len],
+                            new[new_index..new_index + len]
+                        );
+                        (old_index, len, new_index, len)
+                    }
+                    DiffOp::Delete {
+                        old_index,
+                        old_len,
+                        new_index,
+                    } => (old_index, old_len, new_index, 0),
+                    DiffOp::Insert {
+                        old_index,
+                        new_index,
+                        new_len,
+                    } => (old_index, 0, new_index, new_len),
+                    DiffOp::Replace {
+                        old_index,
+                        old_len,
+                        new_index,
+                        new_len,
+                    } => (old_index, old_len, new_index, new_len),
+                };
+                assert_eq!(
+                    (old_index, new_index),
+                    (old_pos, new_pos),
+                    "{:?}",
+                    algorithm
+                );
+                (old_pos, new_pos) = (old_index + old_len, new_index + new_len);
+            }
+            assert_eq!((old_pos, new_pos), (old.len(), new.len()));
+        }
+
+        // Patience anchors on the unique `fn b() {` and `two();` lines, so
+        // `fn a()` is what moved; the shared closing lines match as a suffix
+        let ops = capture_diff_slices_with(DiffAlgorithm::Patience, &old, &new);
+        assert_eq!(
+            ops,
+            vec![
+                DiffOp::Delete {
+                    old_index: 0,
+                    old_len: 4,
+                    new_index: 0,
+                },
+                DiffOp::Equal {
+                    old_index: 4,
+                    new_index: 0,
+                    len: 2,
+                },
+                DiffOp::Insert {
+                    old_index: 6,
+                    new_index: 2,
+                    new_len: 4,
+                },
+                DiffOp::Equal {
+                    old_index: 6,
+                    new_index: 6,
+                    len: 2,
+                },
+            ]
+        );
+        assert_eq!(
+            DiffAlgorithm::parse(" Histogram"),
+            Some(DiffAlgorithm::Histogram)
+        );
+        assert_eq!(DiffAlgorithm::parse("minimal"), None);
+    }
+
     #[test]
     fn test_compute_line_changes() {
         let old = "line1\nline2\nline3\n";
@@ -0,0 +1,212 @@
+//! Surviving lines by age (`stats --age-buckets`), to compare how long AI
+//! code lasts against human code. An AI line's age comes from its prompt's
+//! last message timestamp in the authorship log, falling back to the time
+//! of the commit blame credits it to; a human line's age is always that
+//! commit's. AI lines a human has since edited count as AI.
+
+use crate::authorship::authorship_log::PromptRecord;
+use crate::authorship::range_authorship::should_ignore_file;
+use crate::authorship::working_log::CheckpointKind;
+use crate::commands::blame::{GitAiBlameOptions, overlay_ai_authorship};
+use crate::commands::codeowners::text_files;
+use crate::error::GitAiError;
+use crate::git::repository::Repository;
+use crate::utils::debug_log;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+const DAY_SECS: i64 = 24 * 60 * 60;
+
+/// Upper bounds of every bucket but the last, in days
+const BUCKET_LIMITS_DAYS: &[i64] = &[7, 30];
+const BUCKET_LABELS: &[&str] = &["0-1 week", "1-4 weeks", "over 1 month"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AgeBucket {
+    pub label: &'static str,
+    pub ai_lines: u32,
+    pub human_lines: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AgeReport {
+    pub commit: String,
+    pub buckets: Vec<AgeBucket>,
+}
+
+/// Index into [`BUCKET_LABELS`] for a line written `age_secs` ago
+fn bucket_index(age_secs: i64) -> usize {
+    BUCKET_LIMITS_DAYS
+        .iter()
+        .position(|days| age_secs < days * DAY_SECS)
+        .unwrap_or(BUCKET_LIMITS_DAYS.len())
+}
+
+/// When the prompt's session last said anything, as a unix timestamp
+fn prompt_time(prompt: &PromptRecord) -> Option<i64> {
+    prompt
+        .messages
+        .iter()
+        .filter_map(|message| message.timestamp())
+        .filter_map(|timestamp| DateTime::parse_from_rfc3339(timestamp).ok())
+        .map(|time| time.timestamp())
+        .max()
+}
+
+/// Count the lines of every text file at `commit_sha` by author kind and age
+/// relative to `now`
+pub fn age_buckets(
+    repo: &Repository,
+    commit_sha: &str,
+    ignore_patterns: &[String],
+    now: DateTime<Utc>,
+) -> Result<AgeReport, GitAiError> {
+    let options = GitAiBlameOptions {
+        newest_commit: Some(commit_sha.to_string()),
+        use_prompt_hashes_as_names: true,
+        no_output: true,
+        ..Default::default()
+    };
+    let mixed = CheckpointKind::Mixed.to_str();
+    let mut buckets: Vec<AgeBucket> = BUCKET_LABELS
+        .iter()
+        .map(|label| AgeBucket {
+            label,
+            ai_lines: 0,
+            human_lines: 0,
+        })
+        .collect();
+
+    for (path, line_count) in text_files(repo, commit_sha)? {
+        if line_count == 0 || should_ignore_file(&path, ignore_patterns) {
+            continue;
+        }
+        let hunks = match repo.blame_hunks(&path, 1, line_count, &options) {
+            Ok(hunks) => hunks,
+            Err(e) => {
+                debug_log(&format!("Skipping line ages for {}: {}", path, e));
+                continue;
+            }
+        };
+        let (line_authors, prompts) = overlay_ai_authorship(repo, &hunks, &path, &options)?;
+        for hunk in &hunks {
+            for line in hunk.range.0..=hunk.range.1 {
+                let author = line_authors.get(&line);
+                let prompt = author.and_then(|hash| prompts.get(hash));
+                let is_ai = prompt.is_some() || author.is_some_and(|name| *name == mixed);
+                let written = prompt.and_then(prompt_time).unwrap_or(hunk.author_time);
+                let bucket = &mut buckets[bucket_index(now.timestamp() - written)];
+                if is_ai {
+                    bucket.ai_lines += 1;
+                } else {
+                    bucket.human_lines += 1;
+                }
+            }
+        }
+    }
+
+    Ok(AgeReport {
+        commit: commit_sha.to_string(),
+        buckets,
+    })
+}
+
+pub fn write_age_buckets_to_terminal(report: &AgeReport) {
+    let ai_total: u32 = report.buckets.iter().map(|bucket| bucket.ai_lines).sum();
+    let human_total: u32 = report.buckets.iter().map(|bucket| bucket.human_lines).sum();
+    if ai_total + human_total == 0 {
+        println!(
+            "No lines at {}",
+            &report.commit[..report.commit.len().min(8)]
+        );
+        return;
+    }
+    let share = |lines: u32, total: u32| lines * 100 / total.max(1);
+    println!(
+        "Surviving lines at {} by age",
+        &report.commit[..report.commit.len().min(8)]
+    );
+    println!("  {:<14}  {:>16}  {:>16}", "written", "AI", "human");
+    for bucket in &report.buckets {
+        println!(
+            "  {:<14}  {:>9} ({:>3}%)  {:>9} ({:>3}%)",
+            bucket.label,
+            bucket.ai_lines,
+            share(bucket.ai_lines, ai_total),
+            bucket.human_lines,
+            share(bucket.human_lines, human_total)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_age_buckets() {
+        assert_eq!(bucket_index(0), 0);
+        assert_eq!(bucket_index(7 * DAY_SECS - 1), 0);
+        assert_eq!(bucket_index(7 * DAY_SECS), 1);
+        assert_eq!(bucket_index(45 * DAY_SECS), 2);
+        // Clock skew between machines
+        assert_eq!(bucket_index(-60), 0);
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        let mut file = tmp_repo.write_file("app.py", "import os\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        file.append("def helper():\n    return 1\n").unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("add helper").unwrap();
+
+        let sha = tmp_repo.head_commit_sha().unwrap();
+        // TmpRepo commits at a fixed time
+        let committed = DateTime::from_timestamp(1672574400, 0).unwrap();
+        let in_ten_days = committed + chrono::Duration::days(10);
+        let report = age_buckets(tmp_repo.gitai_repo(), &sha, &[], in_ten_days).unwrap();
+        assert_eq!(report.commit, sha);
+        assert_eq!(
+            report.buckets[1],
+            AgeBucket {
+                label: "1-4 weeks",
+                ai_lines: 2,
+                human_lines: 1,
+            }
+        );
+        assert_eq!(report.buckets[0].ai_lines + report.buckets[2].ai_lines, 0);
+
+        let ignored = age_buckets(
+            tmp_repo.gitai_repo(),
+            &sha,
+            &["*.py".to_string()],
+            in_ten_days,
+        );
+        assert!(
+            ignored
+                .unwrap()
+                .buckets
+                .iter()
+                .all(|bucket| bucket.ai_lines + bucket.human_lines == 0)
+        );
+    }
+
+    #[test]
+    fn test_prompt_time_uses_last_message() {
+        let prompt: PromptRecord = serde_json::from_value(serde_json::json!({
+            "agent_id": {"tool": "cursor", "id": "s1", "model": "m"},
+            "human_author": null,
+            "messages": [
+                {"type": "user", "text": "hi", "timestamp": "2025-01-01T10:00:00Z"},
+                {"type": "assistant", "text": "ok", "timestamp": "2025-01-02T10:00:00+00:00"},
+                {"type": "assistant", "text": "no time"}
+            ]
+        }))
+        .unwrap();
+        assert_eq!(prompt_time(&prompt), Some(1735812000));
+    }
+}
@@ -1,13 +1,31 @@
+pub mod anonymize;
+pub mod attribution_conflicts;
+pub mod attribution_flow;
 pub mod attribution_tracker;
 pub mod authorship_log;
 pub mod authorship_log_serialization;
+pub mod co_authors;
+pub mod commit_message;
+pub mod commit_template;
+pub mod divergence;
+pub mod human_authors;
 pub mod imara_diff_utils;
+pub mod line_age;
 pub mod move_detection;
 pub mod post_commit;
 pub mod pre_commit;
+pub mod prompt_index;
 pub mod range_authorship;
 pub mod rebase_authorship;
+pub mod redaction;
+pub mod restack;
+pub mod review;
+pub mod sessions;
 pub mod stats;
+pub mod stats_baseline;
+pub mod symbols;
+pub mod tickets;
 pub mod transcript;
+pub mod transcript_summary;
 pub mod virtual_attribution;
 pub mod working_log;
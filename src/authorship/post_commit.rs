@@ -1,16 +1,30 @@
 use crate::authorship::authorship_log::PromptRecord;
-use crate::authorship::authorship_log_serialization::AuthorshipLog;
-use crate::authorship::stats::{stats_for_commit_stats, write_stats_to_terminal};
+use crate::authorship::authorship_log_serialization::{AuthorshipLog, generate_short_hash};
+use crate::authorship::commit_message::{self, MessageAttribution, take_draft};
+use crate::authorship::human_authors::human_author_lines;
+use crate::authorship::prompt_index;
+use crate::authorship::redaction;
+use crate::authorship::stats::{
+    format_commit_summary, stats_for_commit_stats, write_stats_to_terminal,
+};
+use crate::authorship::tickets::extract_ticket_refs;
+use crate::authorship::transcript_summary;
 use crate::authorship::virtual_attribution::VirtualAttributions;
 use crate::authorship::working_log::Checkpoint;
 use crate::commands::checkpoint_agent::agent_presets::{
-    ClaudePreset, ContinueCliPreset, CursorPreset, GeminiPreset, GithubCopilotPreset,
+    AiderPreset, ClaudePreset, ClinePreset, ContinueCliPreset, CursorPreset, GeminiPreset,
+    GithubCopilotPreset,
 };
+use crate::commands::output;
 use crate::config::Config;
 use crate::error::GitAiError;
+use crate::event_hooks::{self, HookEvent};
 use crate::git::refs::notes_add;
+use crate::git::repo_storage::PersistedWorkingLog;
 use crate::git::repository::Repository;
-use std::collections::{HashMap, HashSet};
+use crate::notifications::NotificationEvent;
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io::IsTerminal;
 
 pub fn post_commit(
@@ -40,6 +54,7 @@ pub fn post_commit(
     // Update prompts/transcripts to their latest versions and persist to disk
     // Do this BEFORE filtering so that all checkpoints (including untracked files) are updated
     update_prompts_to_latest(&mut parent_working_log)?;
+    assemble_transcript_chunks(&working_log, &mut parent_working_log);
     working_log.write_all_checkpoints(&parent_working_log)?;
 
     // Filter out untracked files from the working log
@@ -71,6 +86,10 @@ pub fn post_commit(
         )?;
 
     authorship_log.metadata.base_commit_sha = commit_sha.clone();
+    authorship_log.metadata.tickets = collect_commit_tickets(repo, &commit_sha);
+    authorship_log.metadata.human_authors = human_author_lines(&filtered_working_log, None);
+    authorship_log.metadata.file_authors = whole_file_authors(&filtered_working_log);
+    authorship_log.metadata.commit_message = commit_message_attribution(repo, &commit_sha);
 
     // Strip prompt messages if ignore_prompts is enabled
     if Config::get().ignore_prompts() {
@@ -83,6 +102,26 @@ pub fn post_commit(
         .map_err(|_| GitAiError::Generic("Failed to serialize authorship log".to_string()))?;
 
     notes_add(repo, &commit_sha, &authorship_json)?;
+    prompt_index::index_commit(repo, &commit_sha, &authorship_log);
+    event_hooks::emit(
+        HookEvent::AuthorshipWritten,
+        repo,
+        json!({
+            "commit": commit_sha,
+            "parent": parent_sha,
+            "files": authorship_log
+                .attestations
+                .iter()
+                .map(|a| &a.file_path)
+                .collect::<Vec<_>>(),
+            "agents": authorship_log
+                .metadata
+                .prompts
+                .values()
+                .map(|p| &p.agent_id)
+                .collect::<Vec<_>>(),
+        }),
+    );
 
     // Write INITIAL file for uncommitted AI attributions (if any)
     if !initial_attributions.files.is_empty() {
@@ -99,15 +138,77 @@ pub fn post_commit(
     // To clean up old working logs, users can run:
     //   git-ai flush-logs --before <commit-sha>
 
-    if !supress_output {
+    let policy = Config::get().policy();
+    let check_policy = policy.is_enabled()
+        && Config::get()
+            .notifications()
+            .has_subscribers(NotificationEvent::PolicyViolation);
+
+    let supress_output = supress_output || output::quiet();
+    if !supress_output || check_policy {
         let stats = stats_for_commit_stats(repo, &commit_sha, &[])?;
+        if check_policy {
+            policy.notify_on_commit_violation(&commit_sha, &stats);
+        }
         // Only print stats if we're in an interactive terminal
         let is_interactive = std::io::stdout().is_terminal();
-        write_stats_to_terminal(&stats, is_interactive);
+        if !supress_output && Config::get().commit_summary_enabled() {
+            if is_interactive {
+                print!("{}", format_commit_summary(&commit_sha, &stats));
+            }
+        } else if !supress_output {
+            write_stats_to_terminal(&stats, is_interactive);
+        }
     }
     Ok((commit_sha.to_string(), authorship_log))
 }
 
+/// Ticket references from the commit message, then the current branch name.
+fn collect_commit_tickets(repo: &Repository, commit_sha: &str) -> Vec<String> {
+    let mut tickets = repo
+        .find_commit(commit_sha.to_string())
+        .and_then(|commit| commit.message())
+        .map(|message| extract_ticket_refs(&message))
+        .unwrap_or_default();
+
+    if let Ok(head) = repo.head()
+        && head.is_branch()
+        && let Ok(branch) = head.shorthand()
+    {
+        for ticket in extract_ticket_refs(&branch) {
+            if !tickets.contains(&ticket) {
+                tickets.push(ticket);
+            }
+        }
+    }
+
+    tickets
+}
+
+/// The agent credited with the commit's message, if one drafted it. The draft
+/// is used up either way.
+fn commit_message_attribution(repo: &Repository, commit_sha: &str) -> Option<MessageAttribution> {
+    let draft = take_draft(&repo.storage)?;
+    let message = repo
+        .find_commit(commit_sha.to_string())
+        .and_then(|commit| commit.message())
+        .ok()?;
+    commit_message::attribute(&draft, &message)
+}
+
+/// Author of each committed binary or oversized file: whoever last changed it.
+/// A later text checkpoint of the same file (e.g. it shrank) drops it again.
+pub fn whole_file_authors(checkpoints: &[Checkpoint]) -> BTreeMap<String, String> {
+    let mut authors = BTreeMap::new();
+    for entry in checkpoints.iter().flat_map(|cp| &cp.entries) {
+        match &entry.file_author {
+            Some(author) => authors.insert(entry.file.clone(), author.clone()),
+            None => authors.remove(&entry.file),
+        };
+    }
+    authors
+}
+
 /// Filter out working log entries for untracked files
 pub fn filter_untracked_files(
     repo: &Repository,
@@ -274,6 +375,30 @@ fn update_prompts_to_latest(checkpoints: &mut [Checkpoint]) -> Result<(), GitAiE
                         None
                     }
                 }
+                "aider" => {
+                    // The chat history file keeps growing as the session goes on
+                    checkpoint
+                        .agent_metadata
+                        .as_ref()
+                        .and_then(|metadata| metadata.get("transcript_path"))
+                        .and_then(|path| AiderPreset::session_from_chat_history(path).ok())
+                        .map(|session| {
+                            (
+                                session.transcript,
+                                session.model.unwrap_or_else(|| agent_id.model.clone()),
+                            )
+                        })
+                }
+                "cline" | "roo-code" => checkpoint
+                    .agent_metadata
+                    .as_ref()
+                    .and_then(|metadata| metadata.get("transcript_path"))
+                    .and_then(|path| {
+                        ClinePreset::transcript_and_model_from_cline_history(path).ok()
+                    })
+                    .map(|(transcript, model)| {
+                        (transcript, model.unwrap_or_else(|| agent_id.model.clone()))
+                    }),
                 "continue-cli" => {
                     // Try to load transcript from agent_metadata if available
                     if let Some(metadata) = &checkpoint.agent_metadata {
@@ -308,8 +433,14 @@ fn update_prompts_to_latest(checkpoints: &mut [Checkpoint]) -> Result<(), GitAiE
             };
 
             // Apply the update to the last checkpoint only
-            if let Some((latest_transcript, latest_model)) = updated_data {
+            if let Some((mut latest_transcript, latest_model)) = updated_data {
+                // Re-read from the agent's own files, so not scrubbed or capped yet
+                redaction::redact_transcript(&mut latest_transcript);
                 let checkpoint = &mut checkpoints[last_idx];
+                transcript_summary::cap_transcript(
+                    &mut latest_transcript,
+                    &mut checkpoint.agent_metadata,
+                );
                 checkpoint.transcript = Some(latest_transcript);
                 if let Some(agent_id) = &mut checkpoint.agent_id {
                     agent_id.model = latest_model;
@@ -321,6 +452,26 @@ fn update_prompts_to_latest(checkpoints: &mut [Checkpoint]) -> Result<(), GitAiE
     Ok(())
 }
 
+/// Give each session that sent its transcript in chunks (agent-v1
+/// `append_transcript`) the whole transcript, on its first checkpoint since
+/// that's the one the prompt record takes its messages from
+fn assemble_transcript_chunks(working_log: &PersistedWorkingLog, checkpoints: &mut [Checkpoint]) {
+    let mut seen = HashSet::new();
+    for checkpoint in checkpoints.iter_mut() {
+        let Some(agent_id) = &checkpoint.agent_id else {
+            continue;
+        };
+        let author_id = generate_short_hash(&agent_id.id, &agent_id.tool);
+        if !seen.insert(author_id.clone()) {
+            continue;
+        }
+        if let Some(mut transcript) = working_log.read_transcript_chunks(&author_id) {
+            transcript_summary::cap_transcript(&mut transcript, &mut checkpoint.agent_metadata);
+            checkpoint.transcript = Some(transcript);
+        }
+    }
+}
+
 /// Strip messages from prompts if ignore_prompts config is enabled
 /// This is called only in post_commit when writing prompts to git history
 fn strip_prompt_messages(prompts: &mut std::collections::BTreeMap<String, PromptRecord>) {
@@ -422,4 +573,69 @@ mod tests {
             "Should have empty attestations when no checkpoints exist"
         );
     }
+
+    #[test]
+    fn test_post_commit_records_ticket_refs() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "one\n", true).unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+
+        tmp_repo.create_branch("feature/PROJ-7-login").unwrap();
+        tmp_repo.write_file("a.txt", "one\ntwo\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", None, None)
+            .unwrap();
+        let authorship_log = tmp_repo
+            .commit_with_message("Fix login redirect (#42)")
+            .unwrap();
+
+        assert_eq!(authorship_log.metadata.tickets, vec!["#42", "PROJ-7"]);
+    }
+
+    #[test]
+    fn test_post_commit_assembles_transcript_chunks() {
+        use crate::authorship::transcript::{AiTranscript, Message};
+        use crate::authorship::working_log::{AgentId, CheckpointKind};
+        use crate::commands::checkpoint_agent::agent_presets::AgentRunResult;
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        let file_path = tmp_repo.path().join("a.txt").to_string_lossy().to_string();
+        let chunk = |text: &str, edited: Vec<String>| AgentRunResult {
+            agent_id: AgentId {
+                tool: "my-agent".to_string(),
+                id: "run-1".to_string(),
+                model: "test_model".to_string(),
+            },
+            agent_metadata: None,
+            transcript: Some(AiTranscript {
+                messages: vec![Message::assistant(text.to_string(), None)],
+            }),
+            checkpoint_kind: CheckpointKind::AiAgent,
+            repo_working_dir: None,
+            edited_filepaths: Some(edited),
+            will_edit_filepaths: None,
+            dirty_files: None,
+            commit_message: None,
+            edited_line_ranges: None,
+            append_transcript: true,
+        };
+
+        tmp_repo.write_file("a.txt", "one\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_agent_result("agent", Some(chunk("first", vec![file_path])))
+            .unwrap();
+        // Only carries messages, so it doesn't add a checkpoint
+        tmp_repo
+            .trigger_checkpoint_with_agent_result("agent", Some(chunk("second", Vec::new())))
+            .unwrap();
+        let authorship_log = tmp_repo.commit_with_message("Initial commit").unwrap();
+
+        let prompt = authorship_log.metadata.prompts.values().next().unwrap();
+        let texts: Vec<&str> = prompt
+            .messages
+            .iter()
+            .filter_map(|message| message.text().map(String::as_str))
+            .collect();
+        assert_eq!(texts, vec!["first", "second"]);
+    }
 }
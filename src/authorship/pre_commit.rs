@@ -1,6 +1,8 @@
+use crate::authorship::divergence;
 use crate::authorship::working_log::CheckpointKind;
 use crate::error::GitAiError;
 use crate::git::repository::Repository;
+use crate::utils::debug_log;
 
 /// pre-commit 钩子函数：在提交前创建人工编辑检查点
 ///
@@ -22,6 +24,26 @@ use crate::git::repository::Repository;
 /// - 表示这是一个人工编辑检查点，用于区分 AI 生成的代码和人工修改的代码
 /// - 通过对比人工检查点与 AI 检查点的差异，可以准确判断每行代码的来源
 pub fn pre_commit(repo: &Repository, default_author: String) -> Result<(), GitAiError> {
+    // 先检查工作日志快照与工作区是否一致：git-ai 未运行期间被修改的文件
+    // 单独记为 "untracked human edit"，避免被算到上一个 AI 检查点头上
+    let working_log = repo.storage.working_log_for_base_commit("initial");
+    if working_log
+        .all_ai_touched_files()
+        .is_ok_and(|files| !files.is_empty())
+    {
+        // 出错只记日志，不影响下面的人工检查点和提交本身
+        match divergence::detect_divergence(&working_log) {
+            Ok(divergent) => {
+                if let Err(e) =
+                    divergence::record_untracked_edits(repo, &default_author, &divergent)
+                {
+                    debug_log(&format!("Failed to record untracked edits: {}", e));
+                }
+            }
+            Err(e) => debug_log(&format!("Failed to check for untracked edits: {}", e)),
+        }
+    }
+
     // 运行 checkpoint 命令创建人工编辑检查点
     // 参数说明：
     // - repo: 仓库对象
@@ -0,0 +1,303 @@
+//! Full-text index over the prompt sessions stored in authorship notes, kept in
+//! `.git/ai/prompt_index.json` and used by `git-ai prompts search`.
+//!
+//! It's a plain inverted index (term -> prompts containing it, with term
+//! frequency) ranked with BM25. Commits are added as they are committed; notes
+//! that arrive another way (fetch, rewrite) are picked up by `refresh` before a
+//! search.
+
+use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::authorship::transcript::Message;
+use crate::error::GitAiError;
+use crate::git::refs::{get_authorship, grep_ai_notes};
+use crate::git::repository::Repository;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+const INDEX_VERSION: u32 = 1;
+const EXCERPT_CHARS: usize = 160;
+
+// BM25 parameters
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexedPrompt {
+    pub commit: String,
+    pub prompt_id: String,
+    pub tool: String,
+    pub model: String,
+    pub files: Vec<String>,
+    /// Start of the first user message
+    pub excerpt: String,
+    /// Number of indexed terms, for length normalisation
+    length: u32,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PromptIndex {
+    version: u32,
+    docs: Vec<IndexedPrompt>,
+    /// term -> (doc index, term frequency)
+    postings: BTreeMap<String, Vec<(u32, u32)>>,
+    indexed_commits: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    #[serde(flatten)]
+    pub prompt: IndexedPrompt,
+    pub score: f64,
+}
+
+/// Lowercased alphanumeric words of two or more characters
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| word.chars().count() > 1)
+        .map(str::to_lowercase)
+}
+
+impl PromptIndex {
+    /// Load the index, starting over if it is missing, unreadable or from another version
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<PromptIndex>(&content).ok())
+            .filter(|index| index.version == INDEX_VERSION)
+            .unwrap_or_else(|| PromptIndex {
+                version: INDEX_VERSION,
+                ..Default::default()
+            })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), GitAiError> {
+        std::fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn contains_commit(&self, commit: &str) -> bool {
+        self.indexed_commits.contains(commit)
+    }
+
+    pub fn len(&self) -> usize {
+        self.docs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.docs.is_empty()
+    }
+
+    /// Index every prompt in `log`. Returns false if the commit was already indexed.
+    pub fn add_commit(&mut self, commit: &str, log: &AuthorshipLog) -> bool {
+        if !self.indexed_commits.insert(commit.to_string()) {
+            return false;
+        }
+
+        for (prompt_id, prompt) in &log.metadata.prompts {
+            let files: Vec<String> = log
+                .attestations
+                .iter()
+                .filter(|file| file.entries.iter().any(|entry| &entry.hash == prompt_id))
+                .map(|file| file.file_path.clone())
+                .collect();
+
+            let mut terms: HashMap<String, u32> = HashMap::new();
+            let mut length = 0;
+            let texts = prompt.messages.iter().filter_map(|message| match message {
+                Message::User { text, .. } | Message::Assistant { text, .. } => Some(text.as_str()),
+                Message::ToolUse { .. } => None,
+            });
+            let fields = [
+                prompt.agent_id.tool.as_str(),
+                prompt.agent_id.model.as_str(),
+            ];
+            for term in texts
+                .chain(fields)
+                .chain(files.iter().map(String::as_str))
+                .flat_map(tokenize)
+            {
+                *terms.entry(term).or_default() += 1;
+                length += 1;
+            }
+
+            let excerpt = prompt
+                .messages
+                .iter()
+                .find_map(|message| match message {
+                    Message::User { text, .. } => Some(text.trim()),
+                    _ => None,
+                })
+                .map(|text| text.chars().take(EXCERPT_CHARS).collect())
+                .unwrap_or_default();
+
+            let doc = self.docs.len() as u32;
+            self.docs.push(IndexedPrompt {
+                commit: commit.to_string(),
+                prompt_id: prompt_id.clone(),
+                tool: prompt.agent_id.tool.clone(),
+                model: prompt.agent_id.model.clone(),
+                files,
+                excerpt,
+                length,
+            });
+            for (term, tf) in terms {
+                self.postings.entry(term).or_default().push((doc, tf));
+            }
+        }
+        true
+    }
+
+    /// Prompts matching any word of `query`, best match first
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        if self.docs.is_empty() {
+            return Vec::new();
+        }
+        let doc_count = self.docs.len() as f64;
+        let avg_length = self.docs.iter().map(|d| d.length as f64).sum::<f64>() / doc_count;
+
+        let query_terms: BTreeSet<String> = tokenize(query).collect();
+        let mut scores: HashMap<u32, f64> = HashMap::new();
+        for term in &query_terms {
+            let Some(postings) = self.postings.get(term) else {
+                continue;
+            };
+            let matching = postings.len() as f64;
+            let idf = (1.0 + (doc_count - matching + 0.5) / (matching + 0.5)).ln();
+            for &(doc, tf) in postings {
+                let tf = tf as f64;
+                let length = self.docs[doc as usize].length as f64;
+                let norm = K1 * (1.0 - B + B * length / avg_length.max(1.0));
+                *scores.entry(doc).or_default() += idf * tf * (K1 + 1.0) / (tf + norm);
+            }
+        }
+
+        let mut hits: Vec<SearchHit> = scores
+            .into_iter()
+            .map(|(doc, score)| SearchHit {
+                prompt: self.docs[doc as usize].clone(),
+                score,
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.prompt.commit.cmp(&b.prompt.commit))
+        });
+        hits.truncate(limit);
+        hits
+    }
+}
+
+/// Add a freshly committed authorship log to the repository's index. Best
+/// effort: a broken index only costs a rebuild on the next search.
+pub fn index_commit(repo: &Repository, commit: &str, log: &AuthorshipLog) {
+    let path = &repo.storage.prompt_index;
+    let mut index = PromptIndex::load(path);
+    if index.add_commit(commit, log) {
+        let _ = index.save(path);
+    }
+}
+
+/// Load the index and add any noted commits it hasn't seen yet
+pub fn refresh(repo: &Repository) -> Result<PromptIndex, GitAiError> {
+    let path = &repo.storage.prompt_index;
+    let mut index = PromptIndex::load(path);
+    let mut changed = false;
+    for sha in grep_ai_notes(repo, "\"schema_version\"").unwrap_or_default() {
+        if index.contains_commit(&sha) {
+            continue;
+        }
+        if let Some(log) = get_authorship(repo, &sha) {
+            changed |= index.add_commit(&sha, &log);
+        }
+    }
+    if changed {
+        index.save(path)?;
+    }
+    Ok(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::authorship_log::PromptRecord;
+    use crate::authorship::authorship_log_serialization::AttestationEntry;
+    use crate::authorship::working_log::AgentId;
+
+    fn prompt(tool: &str, messages: &[&str]) -> PromptRecord {
+        PromptRecord {
+            agent_id: AgentId {
+                tool: tool.to_string(),
+                id: "session".to_string(),
+                model: "model-x".to_string(),
+            },
+            human_author: None,
+            messages: messages
+                .iter()
+                .map(|text| Message::user(text.to_string(), None))
+                .collect(),
+            total_additions: 0,
+            total_deletions: 0,
+            accepted_lines: 0,
+            overriden_lines: 0,
+            generated: false,
+            kind: None,
+            notes: Vec::new(),
+            tags: Vec::new(),
+            agent_metadata: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_search_ranks_and_links_files() {
+        let mut log = AuthorshipLog::new();
+        log.metadata.prompts.insert(
+            "p1".to_string(),
+            prompt(
+                "claude",
+                &["Add retry logic to the HTTP client", "retry with backoff"],
+            ),
+        );
+        log.metadata.prompts.insert(
+            "p2".to_string(),
+            prompt("cursor", &["Rename the config loader"]),
+        );
+        log.get_or_create_file("src/http.rs")
+            .add_entry(AttestationEntry::new("p1".to_string(), vec![]));
+
+        let mut index = PromptIndex::load(Path::new("/nonexistent/index.json"));
+        assert!(index.add_commit("abc", &log));
+        assert!(!index.add_commit("abc", &log));
+        assert_eq!(index.len(), 2);
+
+        let hits = index.search("Retry backoff", 10);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].prompt.prompt_id, "p1");
+        assert_eq!(hits[0].prompt.files, vec!["src/http.rs"]);
+        assert_eq!(hits[0].prompt.excerpt, "Add retry logic to the HTTP client");
+
+        // Tool names and file paths are searchable too
+        assert_eq!(index.search("cursor", 10)[0].prompt.prompt_id, "p2");
+        assert_eq!(index.search("http", 10)[0].prompt.prompt_id, "p1");
+        assert!(index.search("nothing-here", 10).is_empty());
+    }
+
+    #[test]
+    fn test_index_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prompt_index.json");
+        let mut log = AuthorshipLog::new();
+        log.metadata
+            .prompts
+            .insert("p1".to_string(), prompt("claude", &["parser fix"]));
+
+        let mut index = PromptIndex::load(&path);
+        index.add_commit("abc", &log);
+        index.save(&path).unwrap();
+
+        let loaded = PromptIndex::load(&path);
+        assert!(loaded.contains_commit("abc"));
+        assert_eq!(loaded.search("parser", 5).len(), 1);
+    }
+}
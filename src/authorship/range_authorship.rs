@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
@@ -5,7 +6,10 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use crate::authorship::rebase_authorship::filter_pathspecs_to_ai_touched_files;
-use crate::authorship::stats::{CommitStats, stats_for_commit_stats, stats_from_authorship_log};
+use crate::authorship::stats::{
+    CommitStats, file_breakdown, parse_numstat, stats_for_commit_stats, stats_from_authorship_log,
+    sum_diff_stats,
+};
 use crate::error::GitAiError;
 use crate::git::refs::{CommitAuthorship, get_commits_with_notes_from_list};
 use crate::git::repository::{CommitRange, Repository};
@@ -131,8 +135,16 @@ pub fn range_authorship(
     let commit_authorship = get_commits_with_notes_from_list(repository, &commit_shas)?;
 
     // Calculate range stats - now just pass start, end, and commits
-    let range_stats =
+    let mut range_stats =
         calculate_range_stats_direct(repository, commit_range_clone, ignore_patterns)?;
+    // The squashed log has no message of its own, so count the commits' messages
+    range_stats.ai_message_commits = commit_authorship
+        .iter()
+        .filter(|ca| {
+            matches!(ca, CommitAuthorship::Log { authorship_log, .. }
+                if authorship_log.metadata.commit_message.is_some())
+        })
+        .count() as u32;
 
     Ok(RangeAuthorshipStats {
         authorship_stats: RangeAuthorshipStatsData {
@@ -178,7 +190,7 @@ pub fn range_authorship(
 
 /// Create an in-memory authorship log for a commit range by treating it as a squash
 /// Similar to rewrite_authorship_after_squash_or_rebase but tailored for ranges
-fn create_authorship_log_for_range(
+pub fn create_authorship_log_for_range(
     repo: &Repository,
     start_sha: &str,
     end_sha: &str,
@@ -218,6 +230,13 @@ fn create_authorship_log_for_range(
                     ),
                     base_commit_sha: end_sha.to_string(),
                     prompts: std::collections::BTreeMap::new(),
+                    tickets: Vec::new(),
+                    backfilled_prompts: Vec::new(),
+                    human_authors: std::collections::BTreeMap::new(),
+                    reviews: Vec::new(),
+                    cherry_picked_from: None,
+                    file_authors: std::collections::BTreeMap::new(),
+                    commit_message: None,
                 },
             },
         );
@@ -345,13 +364,13 @@ fn get_committed_files_content(
     Ok(files)
 }
 
-/// Get git diff statistics for a commit range (start..end)
-fn get_git_diff_stats_for_range(
+/// Get per-file git diff statistics for a commit range (start..end)
+fn get_git_diff_file_stats_for_range(
     repo: &Repository,
     start_sha: &str,
     end_sha: &str,
     ignore_patterns: &[String],
-) -> Result<(u32, u32), GitAiError> {
+) -> Result<BTreeMap<String, (u32, u32)>, GitAiError> {
     // Use git diff --numstat to get diff statistics for the range
     let mut args = repo.global_args_for_exec();
     args.push("diff".to_string());
@@ -360,40 +379,9 @@ fn get_git_diff_stats_for_range(
 
     let output = crate::git::repository::exec_git(&args)?;
     let stdout = String::from_utf8(output.stdout)?;
-
-    let mut added_lines = 0u32;
-    let mut deleted_lines = 0u32;
-
-    // Parse numstat output
-    for line in stdout.lines() {
-        if line.trim().is_empty() {
-            continue;
-        }
-
-        // Parse numstat format: "added\tdeleted\tfilename"
-        let parts: Vec<&str> = line.split('\t').collect();
-        if parts.len() >= 3 {
-            // Check if this file should be ignored and skip it
-            let filename = parts[2];
-            if should_ignore_file(filename, ignore_patterns) {
-                continue;
-            }
-
-            // Parse added lines
-            if let Ok(added) = parts[0].parse::<u32>() {
-                added_lines += added;
-            }
-
-            // Parse deleted lines (handle "-" for binary files)
-            if parts[1] != "-" {
-                if let Ok(deleted) = parts[1].parse::<u32>() {
-                    deleted_lines += deleted;
-                }
-            }
-        }
-    }
-
-    Ok((added_lines, deleted_lines))
+    Ok(parse_numstat(&stdout, |filename| {
+        !should_ignore_file(filename, ignore_patterns)
+    }))
 }
 
 /// Calculate AI vs human line contributions for a commit range
@@ -411,8 +399,9 @@ fn calculate_range_stats_direct(
     }
 
     // Step 1: Get git diff stats between start and end
-    let (git_diff_added_lines, git_diff_deleted_lines) =
-        get_git_diff_stats_for_range(repo, &start_sha, &end_sha, ignore_patterns)?;
+    let file_diff_stats =
+        get_git_diff_file_stats_for_range(repo, &start_sha, &end_sha, ignore_patterns)?;
+    let (git_diff_added_lines, git_diff_deleted_lines) = sum_diff_stats(&file_diff_stats);
 
     // Step 2: Create in-memory authorship log for the range, filtered to only commits in the range
     let commit_shas = commit_range.clone().all_commits();
@@ -420,11 +409,12 @@ fn calculate_range_stats_direct(
         create_authorship_log_for_range(repo, &start_sha, &end_sha, &commit_shas, ignore_patterns)?;
 
     // Step 3: Calculate stats from the authorship log
-    let stats = stats_from_authorship_log(
+    let mut stats = stats_from_authorship_log(
         Some(&authorship_log),
         git_diff_added_lines,
         git_diff_deleted_lines,
     );
+    stats.file_breakdown = file_breakdown(Some(&authorship_log), &file_diff_stats);
 
     Ok(stats)
 }
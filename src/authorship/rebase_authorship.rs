@@ -1,9 +1,12 @@
+use crate::authorship::attribution_conflicts::{
+    AmbiguousRegion, PendingResolution, ambiguous_regions, clear_pending, save_pending,
+};
 use crate::authorship::authorship_log_serialization::AuthorshipLog;
 use crate::authorship::post_commit;
 use crate::error::GitAiError;
 use crate::git::authorship_traversal::load_ai_touched_files_for_commits;
 use crate::git::refs::get_reference_as_authorship_log_v3;
-use crate::git::repository::{CommitRange, Repository};
+use crate::git::repository::{CommitRange, Repository, exec_git, exec_git_stdin};
 use crate::git::rewrite_log::RewriteLogEvent;
 use crate::utils::debug_log;
 use std::collections::{HashMap, HashSet};
@@ -60,6 +63,20 @@ pub fn rewrite_authorship_if_needed(
             ));
         }
         RewriteLogEvent::RebaseComplete { rebase_complete } => {
+            if rebase_complete.onto.is_some() {
+                // The new base may share no history with the old one, so carry logs
+                // over for commits whose patch survived unchanged; v2 below skips
+                // those and only reconstructs the rest.
+                let transplanted = transplant_authorship_by_patch_id(
+                    repo,
+                    &rebase_complete.original_commits,
+                    &rebase_complete.new_commits,
+                )?;
+                debug_log(&format!(
+                    "✓ Transplanted {} authorship logs by patch-id",
+                    transplanted
+                ));
+            }
             rewrite_authorship_after_rebase_v2(
                 repo,
                 &rebase_complete.original_head,
@@ -82,6 +99,21 @@ pub fn rewrite_authorship_if_needed(
                 &cherry_pick_complete.new_commits,
                 &commit_author,
             )?;
+            // Provenance is a nicety; the rewritten authorship above is what matters
+            match record_cherry_pick_provenance(
+                repo,
+                &cherry_pick_complete.source_commits,
+                &cherry_pick_complete.new_commits,
+            ) {
+                Ok(linked) => debug_log(&format!(
+                    "✓ Linked {} cherry-picked commits to their sources",
+                    linked
+                )),
+                Err(e) => debug_log(&format!(
+                    "Failed to link cherry-picked commits to their sources: {}",
+                    e
+                )),
+            }
 
             debug_log(&format!(
                 "✓ Rewrote authorship for {} cherry-picked commits",
@@ -512,6 +544,45 @@ pub fn rewrite_authorship_after_rebase_v2(
         ));
     }
 
+    // Keep AI hunks the rebase rewrote beyond recognition for resolve-attribution
+    let mut regions = Vec::new();
+    for file in &pathspecs {
+        let (Some(old_content), Some(old_attrs), Some(new_content), Some(new_attrs)) = (
+            original_head_state_va.get_file_content(file),
+            original_head_state_va.get_line_attributions(file),
+            current_va.get_file_content(file),
+            current_va.get_line_attributions(file),
+        ) else {
+            continue;
+        };
+        regions.extend(
+            ambiguous_regions(old_content, old_attrs, new_content, new_attrs)
+                .into_iter()
+                .map(|(start_line, end_line, candidates)| AmbiguousRegion {
+                    file: file.clone(),
+                    start_line,
+                    end_line,
+                    candidates,
+                }),
+        );
+    }
+    if regions.is_empty() {
+        clear_pending(&repo.storage);
+    } else {
+        debug_log(&format!(
+            "{} ambiguous attribution regions after rebase",
+            regions.len()
+        ));
+        save_pending(
+            &repo.storage,
+            &PendingResolution {
+                head: new_commits[new_commits.len() - 1].clone(),
+                commits: commits_to_process,
+                regions,
+            },
+        )?;
+    }
+
     Ok(())
 }
 
@@ -820,6 +891,132 @@ pub fn rewrite_authorship_after_commit_amend(
     Ok(authorship_log)
 }
 
+/// `git patch-id --stable` for the changes introduced by `commit`. None for
+/// commits without a diff (e.g. empty commits).
+pub fn patch_id(repo: &Repository, commit: &str) -> Result<Option<String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(
+        ["diff-tree", "-p", "--root", "--no-color", commit]
+            .iter()
+            .map(|s| s.to_string()),
+    );
+    let diff = exec_git(&args)?.stdout;
+
+    let mut args = repo.global_args_for_exec();
+    args.extend(["patch-id".to_string(), "--stable".to_string()]);
+    let output = exec_git_stdin(&args, &diff)?;
+    Ok(String::from_utf8(output.stdout)?
+        .split_whitespace()
+        .next()
+        .map(str::to_string))
+}
+
+/// Copy authorship logs from `original_commits` to the `new_commits` with the
+/// same patch-id, for commits that don't have a log yet. Returns how many were
+/// copied.
+pub fn transplant_authorship_by_patch_id(
+    repo: &Repository,
+    original_commits: &[String],
+    new_commits: &[String],
+) -> Result<usize, GitAiError> {
+    let mut originals_by_patch_id: HashMap<String, &String> = HashMap::new();
+    for commit in original_commits {
+        if let Some(id) = patch_id(repo, commit)? {
+            originals_by_patch_id.entry(id).or_insert(commit);
+        }
+    }
+
+    let mut copied = 0;
+    for new_commit in new_commits {
+        if get_reference_as_authorship_log_v3(repo, new_commit).is_ok() {
+            continue;
+        }
+        let Some(original) =
+            patch_id(repo, new_commit)?.and_then(|id| originals_by_patch_id.get(&id).copied())
+        else {
+            continue;
+        };
+        let Ok(mut authorship_log) = get_reference_as_authorship_log_v3(repo, original) else {
+            continue;
+        };
+        authorship_log.metadata.base_commit_sha = new_commit.clone();
+        let authorship_json = authorship_log
+            .serialize_to_string()
+            .map_err(|_| GitAiError::Generic("Failed to serialize authorship log".to_string()))?;
+        crate::git::refs::notes_add(repo, new_commit, &authorship_json)?;
+        debug_log(&format!(
+            "Transplanted authorship log {} -> {}",
+            original, new_commit
+        ));
+        copied += 1;
+    }
+
+    Ok(copied)
+}
+
+/// Pair each cherry-picked commit with the commit it was picked from, as
+/// `(source, new)`. Sources map in order when every one produced a commit;
+/// otherwise (some were skipped as empty) they're matched by patch-id.
+pub fn cherry_pick_pairs(
+    repo: &Repository,
+    source_commits: &[String],
+    new_commits: &[String],
+) -> Result<Vec<(String, String)>, GitAiError> {
+    if source_commits.len() == new_commits.len() {
+        return Ok(source_commits
+            .iter()
+            .cloned()
+            .zip(new_commits.iter().cloned())
+            .collect());
+    }
+
+    let mut sources_by_patch_id: HashMap<String, &String> = HashMap::new();
+    for commit in source_commits {
+        if let Some(id) = patch_id(repo, commit)? {
+            sources_by_patch_id.entry(id).or_insert(commit);
+        }
+    }
+    let mut pairs = Vec::new();
+    for new_commit in new_commits {
+        if let Some(source) =
+            patch_id(repo, new_commit)?.and_then(|id| sources_by_patch_id.get(&id).copied())
+        {
+            pairs.push((source.clone(), new_commit.clone()));
+        }
+    }
+    Ok(pairs)
+}
+
+/// Link cherry-picked commits back to their sources via `cherry_picked_from` in
+/// their authorship logs. Commits without a log (no AI lines came across) are
+/// left alone, as are ones whose note can't be rewritten. Returns how many
+/// were linked.
+pub fn record_cherry_pick_provenance(
+    repo: &Repository,
+    source_commits: &[String],
+    new_commits: &[String],
+) -> Result<usize, GitAiError> {
+    let mut linked = 0;
+    for (source, new_commit) in cherry_pick_pairs(repo, source_commits, new_commits)? {
+        let Ok(mut authorship_log) = get_reference_as_authorship_log_v3(repo, &new_commit) else {
+            continue;
+        };
+        authorship_log.metadata.cherry_picked_from = Some(source.clone());
+        let authorship_json = authorship_log
+            .serialize_to_string()
+            .map_err(|_| GitAiError::Generic("Failed to serialize authorship log".to_string()))?;
+        if let Err(e) = crate::git::refs::notes_add(repo, &new_commit, &authorship_json) {
+            debug_log(&format!(
+                "Failed to record that {} was cherry-picked from {}: {}",
+                new_commit, source, e
+            ));
+            continue;
+        }
+        linked += 1;
+    }
+    Ok(linked)
+}
+
 pub fn walk_commits_to_base(
     repository: &Repository,
     head: &str,
@@ -1262,3 +1459,89 @@ fn transform_attributions_to_final_state(
         ts,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::authorship_log::LineRange;
+    use crate::authorship::authorship_log_serialization::AttestationEntry;
+    use crate::git::test_utils::TmpRepo;
+
+    fn commit_file(tmp_repo: &TmpRepo, file: &str, content: &str) -> String {
+        std::fs::write(tmp_repo.path().join(file), content).unwrap();
+        tmp_repo.git_command(&["add", file]).unwrap();
+        tmp_repo.git_command(&["commit", "-q", "-m", file]).unwrap();
+        tmp_repo.head_commit_sha().unwrap()
+    }
+
+    #[test]
+    fn test_transplant_authorship_by_patch_id() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let repo = tmp_repo.gitai_repo();
+        commit_file(&tmp_repo, "base.txt", "base\n");
+        tmp_repo
+            .git_command(&["checkout", "-q", "-b", "topic"])
+            .unwrap();
+        let original = commit_file(&tmp_repo, "feature.txt", "ai line\n");
+
+        let mut log = AuthorshipLog::new();
+        log.metadata.base_commit_sha = original.clone();
+        log.get_or_create_file("feature.txt")
+            .add_entry(AttestationEntry::new(
+                "abc123".to_string(),
+                vec![LineRange::Single(1)],
+            ));
+        crate::git::refs::notes_add(repo, &original, &log.serialize_to_string().unwrap()).unwrap();
+
+        // Replay the commit on a base that shares nothing with its old parent
+        tmp_repo
+            .git_command(&["checkout", "-q", "--orphan", "other"])
+            .unwrap();
+        tmp_repo.git_command(&["rm", "-q", "-rf", "."]).unwrap();
+        commit_file(&tmp_repo, "other.txt", "other\n");
+        tmp_repo.git_command(&["cherry-pick", &original]).unwrap();
+        let transplanted = tmp_repo.head_commit_sha().unwrap();
+
+        let copied =
+            transplant_authorship_by_patch_id(repo, &[original], &[transplanted.clone()]).unwrap();
+        assert_eq!(copied, 1);
+        let log = get_reference_as_authorship_log_v3(repo, &transplanted).unwrap();
+        assert_eq!(log.metadata.base_commit_sha, transplanted);
+        assert_eq!(log.attestations[0].file_path, "feature.txt");
+
+        // Already has a log now, so nothing more to do
+        let copied = transplant_authorship_by_patch_id(repo, &[], &[transplanted]).unwrap();
+        assert_eq!(copied, 0);
+    }
+
+    #[test]
+    fn test_record_cherry_pick_provenance() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let repo = tmp_repo.gitai_repo();
+        commit_file(&tmp_repo, "base.txt", "base\n");
+        let main = tmp_repo.current_branch().unwrap();
+        tmp_repo
+            .git_command(&["checkout", "-q", "-b", "topic"])
+            .unwrap();
+        let first = commit_file(&tmp_repo, "one.txt", "one\n");
+        let second = commit_file(&tmp_repo, "two.txt", "ai line\n");
+
+        tmp_repo.git_command(&["checkout", "-q", &main]).unwrap();
+        tmp_repo.git_command(&["cherry-pick", &second]).unwrap();
+        let picked = tmp_repo.head_commit_sha().unwrap();
+        let mut log = AuthorshipLog::new();
+        log.metadata.base_commit_sha = picked.clone();
+        crate::git::refs::notes_add(repo, &picked, &log.serialize_to_string().unwrap()).unwrap();
+
+        // `first` produced no commit here, so the pick is matched by patch-id
+        let sources = vec![first, second.clone()];
+        let pairs = cherry_pick_pairs(repo, &sources, std::slice::from_ref(&picked)).unwrap();
+        assert_eq!(pairs, vec![(second.clone(), picked.clone())]);
+
+        let linked =
+            record_cherry_pick_provenance(repo, &sources, std::slice::from_ref(&picked)).unwrap();
+        assert_eq!(linked, 1);
+        let log = get_reference_as_authorship_log_v3(repo, &picked).unwrap();
+        assert_eq!(log.metadata.cherry_picked_from, Some(second));
+    }
+}
@@ -0,0 +1,732 @@
+//! Authorship across restacks by stacked-diff tools. git-branchless, jj and
+//! Graphite rewrite whole stacks of commits without going through the git
+//! wrapper, so the rewritten commits end up with no authorship log and every
+//! restack looks like an unexplained history rewrite.
+//!
+//! Rewrites are found two ways: a branch (or HEAD) whose reflog moves to a
+//! commit that doesn't descend from the previous tip, and jj's `change-id`
+//! commit header, which every rewrite of a change keeps. Commits on either
+//! side of a rewrite are paired by change-id, then by author, author date
+//! and subject (which rebases keep), then by patch-id. A rewritten commit
+//! without a log gets a copy of its original's when the tree is unchanged,
+//! and goes through the rebase remap otherwise.
+
+use crate::authorship::rebase_authorship::{patch_id, rewrite_authorship_after_rebase_v2};
+use crate::error::GitAiError;
+use crate::git::refs::{
+    commits_with_notes_in_ref, get_reference_as_authorship_log_v3, notes_add, notes_remove,
+};
+use crate::git::repository::{Repository, exec_git, exec_git_stdin};
+use crate::utils::debug_log;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+/// Reflog entries read per ref
+const MAX_REFLOG_ENTRIES: usize = 200;
+
+/// Commits read on either side of one rewrite
+const MAX_COMMITS_PER_REWRITE: usize = 100;
+
+/// Unannotated local commits matched against annotated ones by change-id
+const MAX_CHANGE_ID_CANDIDATES: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StackTool {
+    Branchless,
+    Jujutsu,
+    Graphite,
+}
+
+impl StackTool {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StackTool::Branchless => "git-branchless",
+            StackTool::Jujutsu => "jj",
+            StackTool::Graphite => "Graphite",
+        }
+    }
+}
+
+/// Stacked-diff tools set up in this repository
+pub fn detect_stack_tools(repo: &Repository) -> Vec<StackTool> {
+    let mut tools = Vec::new();
+    if repo.path().join("branchless").is_dir() {
+        tools.push(StackTool::Branchless);
+    }
+    if repo
+        .workdir()
+        .is_ok_and(|workdir| workdir.join(".jj").is_dir())
+    {
+        tools.push(StackTool::Jujutsu);
+    }
+    if repo.path().join(".graphite_repo_config").exists() || has_graphite_metadata(repo) {
+        tools.push(StackTool::Graphite);
+    }
+    tools
+}
+
+/// Graphite keeps each tracked branch's parent in `refs/branch-metadata/`
+fn has_graphite_metadata(repo: &Repository) -> bool {
+    let mut args = repo.global_args_for_exec();
+    args.extend(
+        [
+            "for-each-ref",
+            "--count=1",
+            "--format=%(refname)",
+            "refs/branch-metadata/",
+        ]
+        .map(String::from),
+    );
+    exec_git(&args).is_ok_and(|output| !output.stdout.is_empty())
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct RestackReport {
+    pub tools: Vec<StackTool>,
+    /// History rewrites found in reflogs
+    pub rewrites: usize,
+    /// (original, rewritten) commits whose authorship log was copied
+    pub remapped: Vec<(String, String)>,
+}
+
+/// Find restacks in the reflogs of `refs` (every local branch and HEAD when
+/// None) and carry authorship onto the rewritten commits that lack it. With
+/// `dry_run`, only report what would be remapped.
+pub fn remap_restacks(
+    repo: &Repository,
+    refs: Option<&[String]>,
+    dry_run: bool,
+) -> Result<RestackReport, GitAiError> {
+    let mut report = RestackReport {
+        tools: detect_stack_tools(repo),
+        ..Default::default()
+    };
+    let annotated = commits_with_notes_in_ref(repo, "refs/notes/ai")?;
+    let refs = match refs {
+        Some(refs) => refs.to_vec(),
+        None => rewritable_refs(repo)?,
+    };
+
+    let mut seen = HashSet::new();
+    for refname in refs {
+        for (old_tip, new_tip) in reflog_rewrites(repo, &refname)? {
+            if !seen.insert((old_tip.clone(), new_tip.clone())) {
+                continue;
+            }
+            report.rewrites += 1;
+            let originals: Vec<String> = commits_between(repo, &old_tip, &new_tip)?
+                .into_iter()
+                .filter(|commit| annotated.contains(commit))
+                .collect();
+            if originals.is_empty() {
+                continue;
+            }
+            // Oldest first, the order the rebase remap replays them in
+            let rewritten: Vec<String> = commits_between(repo, &new_tip, &old_tip)?
+                .into_iter()
+                .rev()
+                .filter(|commit| !annotated.contains(commit))
+                .collect();
+            let pairs = pair_rewritten(repo, &originals, &rewritten)?;
+            report
+                .remapped
+                .extend(remap_rewritten_commits(repo, &old_tip, &pairs, dry_run)?);
+        }
+    }
+
+    // jj doesn't always leave a reflog behind, but its change-ids survive
+    if report.tools.contains(&StackTool::Jujutsu) {
+        let candidates: Vec<String> = local_commits(repo)?
+            .into_iter()
+            .filter(|commit| !annotated.contains(commit))
+            .collect();
+        let pairs = pair_by_change_id(
+            &annotated_change_ids(repo, &annotated)?,
+            &read_commits(repo, &candidates)?,
+        );
+        // Each change was rewritten on its own, so each is remapped from its original
+        for pair in pairs {
+            if report
+                .remapped
+                .iter()
+                .any(|(_, rewritten)| *rewritten == pair.1)
+            {
+                continue;
+            }
+            let original = pair.0.clone();
+            report
+                .remapped
+                .extend(remap_rewritten_commits(repo, &original, &[pair], dry_run)?);
+        }
+    }
+    Ok(report)
+}
+
+/// Carry authorship from each original in `pairs` to its rewritten commit,
+/// oldest first, where `original_head` is the tip the originals were
+/// rewritten from. A commit that kept its original's tree gets a copy of the
+/// log; the rest go through the rebase remap, which follows the changed
+/// lines. Returns the pairs whose rewritten commit got a log.
+pub fn remap_rewritten_commits(
+    repo: &Repository,
+    original_head: &str,
+    pairs: &[(String, String)],
+    dry_run: bool,
+) -> Result<Vec<(String, String)>, GitAiError> {
+    let mut remapped = Vec::new();
+    let mut changed = Vec::new();
+    for (original, rewritten) in pairs {
+        if original == rewritten || has_attribution(repo, rewritten) {
+            continue;
+        }
+        if !same_tree(repo, original, rewritten)? {
+            changed.push((original.clone(), rewritten.clone()));
+        } else if dry_run || copy_authorship_log(repo, original, rewritten)? {
+            remapped.push((original.clone(), rewritten.clone()));
+        }
+    }
+    if changed.is_empty() || dry_run {
+        remapped.extend(changed);
+        return Ok(remapped);
+    }
+
+    let (originals, rewritten): (Vec<String>, Vec<String>) = changed.iter().cloned().unzip();
+    // The rebase remap skips commits that have any log, empty ones included
+    for commit in &rewritten {
+        notes_remove(repo, commit)?;
+    }
+    rewrite_authorship_after_rebase_v2(repo, original_head, &originals, &rewritten, "")?;
+    remapped.extend(
+        changed
+            .into_iter()
+            .filter(|(_, rewritten)| get_reference_as_authorship_log_v3(repo, rewritten).is_ok()),
+    );
+    Ok(remapped)
+}
+
+/// Whether a log written for one commit describes the other's files too
+fn same_tree(repo: &Repository, a: &str, b: &str) -> Result<bool, GitAiError> {
+    let tree = |sha: &str| -> Result<String, GitAiError> {
+        Ok(repo.find_commit(sha.to_string())?.tree()?.id())
+    };
+    Ok(tree(a)? == tree(b)?)
+}
+
+/// Whether `commit` has a log that attributes anything; a post-commit hook
+/// run mid-rebase leaves empty ones behind
+fn has_attribution(repo: &Repository, commit: &str) -> bool {
+    get_reference_as_authorship_log_v3(repo, commit)
+        .is_ok_and(|log| !log.attestations.is_empty() || !log.metadata.prompts.is_empty())
+}
+
+/// Copy `original`'s authorship log to `rewritten` unless it already has
+/// one that attributes anything. Returns whether it was copied.
+fn copy_authorship_log(
+    repo: &Repository,
+    original: &str,
+    rewritten: &str,
+) -> Result<bool, GitAiError> {
+    if original == rewritten || has_attribution(repo, rewritten) {
+        return Ok(false);
+    }
+    let Ok(mut authorship_log) = get_reference_as_authorship_log_v3(repo, original) else {
+        return Ok(false);
+    };
+    authorship_log.metadata.base_commit_sha = rewritten.to_string();
+    let authorship_json = authorship_log
+        .serialize_to_string()
+        .map_err(|_| GitAiError::Generic("Failed to serialize authorship log".to_string()))?;
+    notes_add(repo, rewritten, &authorship_json)?;
+    debug_log(&format!(
+        "Remapped authorship log {} -> {}",
+        original, rewritten
+    ));
+    Ok(true)
+}
+
+/// Local branches, plus HEAD for tools that restack detached
+fn rewritable_refs(repo: &Repository) -> Result<Vec<String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(["for-each-ref", "--format=%(refname)", "refs/heads/"].map(String::from));
+    let output = exec_git(&args)?;
+    let mut refs: Vec<String> = String::from_utf8(output.stdout)?
+        .lines()
+        .map(str::to_string)
+        .collect();
+    refs.push("HEAD".to_string());
+    Ok(refs)
+}
+
+/// Reflog moves of `refname` to a commit that doesn't descend from the
+/// previous tip, newest first, as (old tip, new tip). HEAD moving between
+/// branches on checkout isn't a rewrite.
+fn reflog_rewrites(repo: &Repository, refname: &str) -> Result<Vec<(String, String)>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend([
+        "reflog".to_string(),
+        "show".to_string(),
+        format!("-n{}", MAX_REFLOG_ENTRIES),
+        "--format=%H%x1f%gs".to_string(),
+        refname.to_string(),
+        "--".to_string(),
+    ]);
+    // Refs without a reflog
+    let Ok(output) = exec_git(&args) else {
+        return Ok(Vec::new());
+    };
+    let stdout = String::from_utf8(output.stdout)?;
+    let entries: Vec<(&str, &str)> = stdout
+        .lines()
+        .filter_map(|line| line.split_once('\x1f'))
+        .collect();
+
+    Ok(entries
+        .windows(2)
+        .filter(|window| !window[0].1.starts_with("checkout: "))
+        .map(|window| (window[1].0, window[0].0))
+        .filter(|(old_tip, new_tip)| old_tip != new_tip && !is_ancestor(repo, old_tip, new_tip))
+        .map(|(old_tip, new_tip)| (old_tip.to_string(), new_tip.to_string()))
+        .collect())
+}
+
+fn is_ancestor(repo: &Repository, ancestor: &str, descendant: &str) -> bool {
+    let mut args = repo.global_args_for_exec();
+    args.extend(["merge-base", "--is-ancestor", ancestor, descendant].map(String::from));
+    exec_git(&args).is_ok()
+}
+
+/// Non-merge commits reachable from `tip` but not from `exclude`
+fn commits_between(repo: &Repository, tip: &str, exclude: &str) -> Result<Vec<String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend([
+        "rev-list".to_string(),
+        "--no-merges".to_string(),
+        format!("--max-count={}", MAX_COMMITS_PER_REWRITE),
+        tip.to_string(),
+        "--not".to_string(),
+        exclude.to_string(),
+        "--".to_string(),
+    ]);
+    // A tip since garbage collected
+    let Ok(output) = exec_git(&args) else {
+        return Ok(Vec::new());
+    };
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .map(str::to_string)
+        .collect())
+}
+
+/// Commits on local branches that no remote has yet
+fn local_commits(repo: &Repository) -> Result<Vec<String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend([
+        "rev-list".to_string(),
+        "--no-merges".to_string(),
+        format!("--max-count={}", MAX_CHANGE_ID_CANDIDATES),
+        "--branches".to_string(),
+        "--not".to_string(),
+        "--remotes".to_string(),
+        "--".to_string(),
+    ]);
+    let output = exec_git(&args)?;
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .map(str::to_string)
+        .collect())
+}
+
+/// The annotated commits that have a jj change-id. Commits never change, so
+/// each one's change-id is read once and kept in `.git/ai/change_ids.json`.
+fn annotated_change_ids(
+    repo: &Repository,
+    annotated: &HashSet<String>,
+) -> Result<Vec<CommitIdentity>, GitAiError> {
+    let path = &repo.storage.change_ids;
+    let mut cache: HashMap<String, Option<String>> = fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    let unread: Vec<String> = annotated
+        .iter()
+        .filter(|commit| !cache.contains_key(*commit))
+        .cloned()
+        .collect();
+    if !unread.is_empty() || cache.len() > annotated.len() {
+        for commit in read_commits(repo, &unread)? {
+            cache.insert(commit.sha, commit.change_id);
+        }
+        // Missing objects too, so they aren't asked for again
+        for commit in unread {
+            cache.entry(commit).or_insert(None);
+        }
+        cache.retain(|commit, _| annotated.contains(commit));
+        if let Err(e) = serde_json::to_string(&cache)
+            .map_err(GitAiError::from)
+            .and_then(|json| fs::write(path, json).map_err(GitAiError::from))
+        {
+            debug_log(&format!("Failed to write change-id cache: {}", e));
+        }
+    }
+    Ok(cache
+        .into_iter()
+        .filter_map(|(sha, change_id)| {
+            Some(CommitIdentity {
+                sha,
+                change_id: Some(change_id?),
+                ..Default::default()
+            })
+        })
+        .collect())
+}
+
+/// What a rebase keeps of a commit
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitIdentity {
+    pub sha: String,
+    /// jj's `change-id` header
+    pub change_id: Option<String>,
+    /// The whole `author` header: name, email and date
+    pub author: String,
+    pub subject: String,
+}
+
+impl CommitIdentity {
+    /// Parse a raw commit object as printed by `git cat-file commit`
+    pub fn parse(sha: &str, raw: &str) -> Self {
+        let (headers, message) = raw.split_once("\n\n").unwrap_or((raw, ""));
+        let mut identity = CommitIdentity {
+            sha: sha.to_string(),
+            subject: message.lines().next().unwrap_or_default().to_string(),
+            ..Default::default()
+        };
+        for line in headers.lines() {
+            if let Some(author) = line.strip_prefix("author ") {
+                identity.author = author.to_string();
+            } else if let Some(change_id) = line.strip_prefix("change-id ") {
+                identity.change_id = Some(change_id.to_string());
+            }
+        }
+        identity
+    }
+}
+
+/// Read `commits` with one `git cat-file --batch`
+fn read_commits(repo: &Repository, commits: &[String]) -> Result<Vec<CommitIdentity>, GitAiError> {
+    if commits.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut args = repo.global_args_for_exec();
+    args.extend(["cat-file".to_string(), "--batch".to_string()]);
+    let stdin = commits.join("\n") + "\n";
+    let data = exec_git_stdin(&args, stdin.as_bytes())?.stdout;
+
+    // Each object is "<oid> <type> <size>\n<content>\n", or "<name> missing\n"
+    let mut identities = Vec::new();
+    let mut pos = 0;
+    while let Some(header_len) = data[pos..].iter().position(|&b| b == b'\n') {
+        let header = String::from_utf8_lossy(&data[pos..pos + header_len]).to_string();
+        pos += header_len + 1;
+        let fields: Vec<&str> = header.split(' ').collect();
+        let [sha, kind, size] = fields[..] else {
+            continue;
+        };
+        let Ok(size) = size.parse::<usize>() else {
+            continue;
+        };
+        let end = (pos + size).min(data.len());
+        if kind == "commit" {
+            let raw = String::from_utf8_lossy(&data[pos..end]);
+            identities.push(CommitIdentity::parse(sha, &raw));
+        }
+        pos = end + 1;
+        if pos >= data.len() {
+            break;
+        }
+    }
+    Ok(identities)
+}
+
+/// Pair rewritten commits with originals sharing their change-id. A
+/// change-id shared by several originals maps to whichever comes first.
+pub fn pair_by_change_id(
+    originals: &[CommitIdentity],
+    rewritten: &[CommitIdentity],
+) -> Vec<(String, String)> {
+    let mut by_change_id: HashMap<&str, &str> = HashMap::new();
+    for original in originals {
+        if let Some(change_id) = &original.change_id {
+            by_change_id.entry(change_id).or_insert(&original.sha);
+        }
+    }
+    rewritten
+        .iter()
+        .filter_map(|commit| {
+            let original = by_change_id.get(commit.change_id.as_deref()?)?;
+            Some((original.to_string(), commit.sha.clone()))
+        })
+        .collect()
+}
+
+/// Pair rewritten commits with originals by author and subject, for keys
+/// only one original has
+pub fn pair_by_author_and_subject(
+    originals: &[CommitIdentity],
+    rewritten: &[CommitIdentity],
+) -> Vec<(String, String)> {
+    let mut by_key: HashMap<(&str, &str), Option<&str>> = HashMap::new();
+    for original in originals {
+        by_key
+            .entry((&original.author, &original.subject))
+            .and_modify(|sha| *sha = None)
+            .or_insert(Some(&original.sha));
+    }
+    rewritten
+        .iter()
+        .filter_map(|commit| {
+            let original = (*by_key.get(&(commit.author.as_str(), commit.subject.as_str()))?)?;
+            Some((original.to_string(), commit.sha.clone()))
+        })
+        .collect()
+}
+
+/// Pair each rewritten commit with its original: by change-id, then author
+/// and subject, then patch-id
+fn pair_rewritten(
+    repo: &Repository,
+    originals: &[String],
+    rewritten: &[String],
+) -> Result<Vec<(String, String)>, GitAiError> {
+    let original_ids = read_commits(repo, originals)?;
+    let mut unpaired = read_commits(repo, rewritten)?;
+    let mut pairs = Vec::new();
+    for pair_by in [pair_by_change_id, pair_by_author_and_subject] {
+        let found = pair_by(&original_ids, &unpaired);
+        unpaired.retain(|commit| !found.iter().any(|(_, new)| *new == commit.sha));
+        pairs.extend(found);
+    }
+    if unpaired.is_empty() {
+        return Ok(pairs);
+    }
+
+    let mut by_patch_id: HashMap<String, &String> = HashMap::new();
+    for original in originals {
+        if let Some(id) = patch_id(repo, original)? {
+            by_patch_id.entry(id).or_insert(original);
+        }
+    }
+    for commit in unpaired {
+        if let Some(original) =
+            patch_id(repo, &commit.sha)?.and_then(|id| by_patch_id.get(&id).copied())
+        {
+            pairs.push((original.clone(), commit.sha));
+        }
+    }
+    Ok(pairs)
+}
+
+/// Pairs from git's `post-rewrite` hook input: "<old> <new> [<extra>]" lines
+pub fn parse_post_rewrite_input(input: &str) -> Vec<(String, String)> {
+    input
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            Some((fields.next()?.to_string(), fields.next()?.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    fn identity(sha: &str, change_id: Option<&str>, author: &str, subject: &str) -> CommitIdentity {
+        CommitIdentity {
+            sha: sha.to_string(),
+            change_id: change_id.map(str::to_string),
+            author: author.to_string(),
+            subject: subject.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_commit_identity_and_pairing() {
+        let raw = "tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\
+                   parent 1111111111111111111111111111111111111111\n\
+                   author Dev <dev@example.com> 1700000000 +0000\n\
+                   committer Dev <dev@example.com> 1700000500 +0000\n\
+                   change-id zyxwvutsrqponmlkzyxwvutsrqponmlk\n\
+                   \n\
+                   Add retries\n\nLonger description\n";
+        assert_eq!(
+            CommitIdentity::parse("abc", raw),
+            identity(
+                "abc",
+                Some("zyxwvutsrqponmlkzyxwvutsrqponmlk"),
+                "Dev <dev@example.com> 1700000000 +0000",
+                "Add retries"
+            )
+        );
+
+        let originals = vec![
+            identity("o1", Some("c1"), "A 1 +0000", "one"),
+            identity("o2", None, "A 2 +0000", "two"),
+            identity("o3", None, "A 3 +0000", "dup"),
+            identity("o4", None, "A 3 +0000", "dup"),
+        ];
+        let rewritten = vec![
+            identity("n1", Some("c1"), "A 1 +0000", "one, reworded"),
+            identity("n2", None, "A 2 +0000", "two"),
+            identity("n3", None, "A 3 +0000", "dup"),
+        ];
+        assert_eq!(
+            pair_by_change_id(&originals, &rewritten),
+            vec![("o1".to_string(), "n1".to_string())]
+        );
+        // Ambiguous keys pair with nothing
+        assert_eq!(
+            pair_by_author_and_subject(&originals, &rewritten),
+            vec![("o2".to_string(), "n2".to_string())]
+        );
+
+        assert_eq!(
+            parse_post_rewrite_input("aaa bbb\nccc ddd extra\n\n"),
+            vec![
+                ("aaa".to_string(), "bbb".to_string()),
+                ("ccc".to_string(), "ddd".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_annotated_change_ids_are_read_once() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "one\n", true).unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let repo = tmp_repo.gitai_repo();
+        let plain = tmp_repo.head_commit_sha().unwrap();
+
+        let raw = "tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\
+                   author Dev <dev@example.com> 1700000000 +0000\n\
+                   committer Dev <dev@example.com> 1700000000 +0000\n\
+                   change-id zyxwvutsrqponmlkzyxwvutsrqponmlk\n\
+                   \n\
+                   From jj\n";
+        let mut args = repo.global_args_for_exec();
+        args.extend(["hash-object", "-t", "commit", "-w", "--stdin"].map(String::from));
+        let output = exec_git_stdin(&args, raw.as_bytes()).unwrap();
+        let jj = String::from_utf8(output.stdout).unwrap().trim().to_string();
+
+        let annotated = HashSet::from([plain.clone(), jj.clone()]);
+        let identities = annotated_change_ids(repo, &annotated).unwrap();
+        assert_eq!(identities.len(), 1);
+        assert_eq!(identities[0].sha, jj);
+        assert_eq!(
+            identities[0].change_id.as_deref(),
+            Some("zyxwvutsrqponmlkzyxwvutsrqponmlk")
+        );
+
+        // Later runs take change-ids from the cache instead of the objects
+        let cached = serde_json::json!({ &plain: "cached", &jj: null });
+        fs::write(&repo.storage.change_ids, cached.to_string()).unwrap();
+        let identities = annotated_change_ids(repo, &annotated).unwrap();
+        assert_eq!(identities.len(), 1);
+        assert_eq!(identities[0].sha, plain);
+        assert_eq!(identities[0].change_id.as_deref(), Some("cached"));
+    }
+
+    #[test]
+    fn test_remap_restacks_from_reflog() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let mut file = tmp_repo.write_file("a.txt", "one\n", true).unwrap();
+        tmp_repo.trigger_checkpoint_with_author("dev").unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let main = tmp_repo.current_branch().unwrap();
+
+        tmp_repo.create_branch("stack").unwrap();
+        file.append("two\n").unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("Add two").unwrap();
+        let original = tmp_repo.head_commit_sha().unwrap();
+
+        // A restack outside git-ai: main moves on, the stack is rebased with
+        // the real git and no hooks
+        tmp_repo.git_command(&["checkout", "-f", &main]).unwrap();
+        tmp_repo
+            .git_command(&["commit", "--allow-empty", "-m", "Unrelated"])
+            .unwrap();
+        tmp_repo.git_command(&["checkout", "-f", "stack"]).unwrap();
+        tmp_repo.git_command(&["rebase", &main]).unwrap();
+        let rewritten = tmp_repo.head_commit_sha().unwrap();
+        assert_ne!(original, rewritten);
+        let repo = tmp_repo.gitai_repo();
+        assert!(get_reference_as_authorship_log_v3(repo, &rewritten).is_err());
+
+        let dry_run = remap_restacks(repo, None, true).unwrap();
+        assert_eq!(
+            dry_run.remapped,
+            vec![(original.clone(), rewritten.clone())]
+        );
+        assert!(get_reference_as_authorship_log_v3(repo, &rewritten).is_err());
+
+        let report = remap_restacks(repo, None, false).unwrap();
+        assert!(report.rewrites >= 1);
+        assert_eq!(report.remapped, vec![(original, rewritten.clone())]);
+        let log = get_reference_as_authorship_log_v3(repo, &rewritten).unwrap();
+        assert_eq!(log.metadata.base_commit_sha, rewritten);
+
+        // Nothing left to do
+        assert!(
+            remap_restacks(repo, None, false)
+                .unwrap()
+                .remapped
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_remap_restack_onto_changed_base() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "one\ntwo\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("Add a").unwrap();
+        let stack = tmp_repo.current_branch().unwrap();
+        let original = tmp_repo.head_commit_sha().unwrap();
+
+        // The stack is restacked onto a base that adds another file, so the
+        // rebased commit gets a new tree
+        tmp_repo
+            .git_command(&["checkout", "--orphan", "base"])
+            .unwrap();
+        tmp_repo.git_command(&["rm", "-rfq", "."]).unwrap();
+        tmp_repo.write_file("b.txt", "base\n", false).unwrap();
+        tmp_repo.git_command(&["add", "b.txt"]).unwrap();
+        tmp_repo.git_command(&["commit", "-m", "Base"]).unwrap();
+        tmp_repo
+            .git_command(&["rebase", "--onto", "base", "--root", &stack])
+            .unwrap();
+        let rewritten = tmp_repo.head_commit_sha().unwrap();
+        let repo = tmp_repo.gitai_repo();
+        assert!(!same_tree(repo, &original, &rewritten).unwrap());
+
+        // Only the named refs are searched
+        let base_only = ["refs/heads/base".to_string()];
+        let report = remap_restacks(repo, Some(&base_only), false).unwrap();
+        assert!(report.remapped.is_empty());
+
+        let stack_only = [format!("refs/heads/{}", stack)];
+        let report = remap_restacks(repo, Some(&stack_only), false).unwrap();
+        assert_eq!(report.remapped, vec![(original, rewritten.clone())]);
+        let log = get_reference_as_authorship_log_v3(repo, &rewritten).unwrap();
+        assert_eq!(log.metadata.base_commit_sha, rewritten);
+        assert_eq!(log.attestations.len(), 1);
+        assert_eq!(log.attestations[0].file_path, "a.txt");
+    }
+}
@@ -0,0 +1,156 @@
+//! Human review of AI-authored lines. `git-ai review mark` appends a
+//! [`ReviewRecord`] to the note of each commit that introduced the reviewed AI
+//! lines, using that commit's line numbers so later edits don't shift them.
+
+use crate::authorship::authorship_log::LineRange;
+use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewRecord {
+    pub reviewer: String,
+    pub file: String,
+    /// Line numbers as of the commit the note belongs to
+    pub lines: Vec<LineRange>,
+    /// Unix seconds
+    pub timestamp: u64,
+}
+
+/// AI lines per file in `log` (excluding generated code)
+pub fn ai_lines_by_file(log: &AuthorshipLog) -> HashMap<String, BTreeSet<u32>> {
+    let mut files: HashMap<String, BTreeSet<u32>> = HashMap::new();
+    for file in &log.attestations {
+        for entry in &file.entries {
+            let is_ai = log
+                .metadata
+                .prompts
+                .get(&entry.hash)
+                .is_some_and(|prompt| !prompt.generated);
+            if is_ai {
+                files
+                    .entry(file.file_path.clone())
+                    .or_default()
+                    .extend(entry.line_ranges.iter().flat_map(LineRange::expand));
+            }
+        }
+    }
+    files
+}
+
+/// Number of AI lines in `log` covered by at least one review
+pub fn reviewed_ai_lines(log: &AuthorshipLog) -> u32 {
+    if log.metadata.reviews.is_empty() {
+        return 0;
+    }
+    let ai_lines = ai_lines_by_file(log);
+    let mut reviewed: HashMap<&str, BTreeSet<u32>> = HashMap::new();
+    for review in &log.metadata.reviews {
+        let Some(file_ai_lines) = ai_lines.get(&review.file) else {
+            continue;
+        };
+        reviewed.entry(&review.file).or_default().extend(
+            review
+                .lines
+                .iter()
+                .flat_map(LineRange::expand)
+                .filter(|line| file_ai_lines.contains(line)),
+        );
+    }
+    reviewed.values().map(|lines| lines.len() as u32).sum()
+}
+
+/// Lines of `file` covered by a review recorded in `log`
+pub fn reviewed_lines(log: &AuthorshipLog, file: &str) -> BTreeSet<u32> {
+    log.metadata
+        .reviews
+        .iter()
+        .filter(|review| review.file == file)
+        .flat_map(|review| review.lines.iter().flat_map(LineRange::expand))
+        .collect()
+}
+
+/// Record a review of `lines` in `file`, keeping only the lines that are AI
+/// authored in this commit. Returns how many AI lines the review covers.
+pub fn add_review(
+    log: &mut AuthorshipLog,
+    file: &str,
+    lines: &BTreeSet<u32>,
+    reviewer: &str,
+    timestamp: u64,
+) -> u32 {
+    let ai_lines = ai_lines_by_file(log);
+    let covered: Vec<u32> = match ai_lines.get(file) {
+        Some(file_ai_lines) => lines.intersection(file_ai_lines).copied().collect(),
+        None => Vec::new(),
+    };
+    if covered.is_empty() {
+        return 0;
+    }
+    log.metadata.reviews.push(ReviewRecord {
+        reviewer: reviewer.to_string(),
+        file: file.to_string(),
+        lines: LineRange::compress_lines(&covered),
+        timestamp,
+    });
+    covered.len() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::authorship_log::PromptRecord;
+    use crate::authorship::authorship_log_serialization::AttestationEntry;
+    use crate::authorship::working_log::AgentId;
+
+    fn log_with_ai_lines() -> AuthorshipLog {
+        let mut log = AuthorshipLog::new();
+        log.metadata.prompts.insert(
+            "p1".to_string(),
+            PromptRecord {
+                agent_id: AgentId {
+                    tool: "claude".to_string(),
+                    id: "session".to_string(),
+                    model: "model".to_string(),
+                },
+                human_author: None,
+                messages: vec![],
+                total_additions: 0,
+                total_deletions: 0,
+                accepted_lines: 0,
+                overriden_lines: 0,
+                generated: false,
+                kind: None,
+                notes: Vec::new(),
+                tags: Vec::new(),
+                agent_metadata: Default::default(),
+            },
+        );
+        log.get_or_create_file("src/lib.rs")
+            .add_entry(AttestationEntry::new(
+                "p1".to_string(),
+                vec![LineRange::Range(1, 5), LineRange::Single(10)],
+            ));
+        log
+    }
+
+    #[test]
+    fn test_reviews_only_count_ai_lines() {
+        let mut log = log_with_ai_lines();
+        assert_eq!(reviewed_ai_lines(&log), 0);
+
+        // Lines 4-8: only 4 and 5 are AI-authored
+        let lines: BTreeSet<u32> = (4..=8).collect();
+        assert_eq!(add_review(&mut log, "src/lib.rs", &lines, "alice", 1), 2);
+        assert_eq!(log.metadata.reviews[0].lines, vec![LineRange::Range(4, 5)]);
+
+        // Overlapping reviews don't double count
+        let lines: BTreeSet<u32> = [1, 5, 10].into_iter().collect();
+        assert_eq!(add_review(&mut log, "src/lib.rs", &lines, "bob", 2), 3);
+        assert_eq!(reviewed_ai_lines(&log), 4);
+
+        // Nothing AI-authored to review
+        assert_eq!(add_review(&mut log, "README.md", &lines, "bob", 3), 0);
+        assert_eq!(log.metadata.reviews.len(), 2);
+    }
+}
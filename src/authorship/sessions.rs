@@ -0,0 +1,212 @@
+//! Agent sessions: AI checkpoints grouped per agent run. Checkpoints from the
+//! same agent id belong to one session until the agent goes quiet for longer
+//! than the gap, after which the next checkpoint opens a new session. Lines the
+//! session's prompt got accepted into commits are credited back to it so a
+//! report can show how much of each run survived.
+
+use crate::authorship::authorship_log_serialization::generate_short_hash;
+use crate::authorship::working_log::Checkpoint;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Idle time after which the next checkpoint from an agent starts a new session
+pub const DEFAULT_SESSION_GAP_SECS: u64 = 30 * 60;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SessionCommit {
+    pub sha: String,
+    pub lines: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentSession {
+    /// `<prompt id prefix>-<n>`, stable for a given working log and gap
+    pub id: String,
+    pub tool: String,
+    /// Session id reported by the agent itself
+    pub agent_session_id: String,
+    pub model: String,
+    /// Prompt hash the session's lines are attributed to in authorship logs
+    pub prompt_id: String,
+    pub started_at: u64,
+    pub ended_at: u64,
+    pub checkpoints: usize,
+    pub files: BTreeSet<String>,
+    pub additions: u32,
+    pub deletions: u32,
+    /// Commits that kept lines written during the session
+    pub committed: Vec<SessionCommit>,
+}
+
+impl AgentSession {
+    pub fn duration_secs(&self) -> u64 {
+        self.ended_at.saturating_sub(self.started_at)
+    }
+
+    pub fn committed_lines(&self) -> u32 {
+        self.committed.iter().map(|c| c.lines).sum()
+    }
+
+    /// Share of the session's added lines that made it into commits
+    pub fn survival_percent(&self) -> f64 {
+        if self.additions == 0 {
+            return 0.0;
+        }
+        (self.committed_lines() as f64 / self.additions as f64 * 100.0).min(100.0)
+    }
+}
+
+/// Group AI checkpoints into sessions, ordered by start time
+pub fn group_sessions(checkpoints: &[Checkpoint], gap_secs: u64) -> Vec<AgentSession> {
+    let mut ai_checkpoints: Vec<&Checkpoint> = checkpoints
+        .iter()
+        .filter(|c| c.kind.is_ai() && c.agent_id.is_some())
+        .collect();
+    ai_checkpoints.sort_by_key(|c| c.timestamp);
+
+    // Index of the open session for each (tool, agent session id)
+    let mut open: HashMap<(String, String), usize> = HashMap::new();
+    let mut per_prompt: HashMap<String, usize> = HashMap::new();
+    let mut sessions: Vec<AgentSession> = Vec::new();
+
+    for checkpoint in ai_checkpoints {
+        let agent_id = checkpoint.agent_id.as_ref().unwrap();
+        let key = (agent_id.tool.clone(), agent_id.id.clone());
+        let index = match open.get(&key) {
+            Some(&i) if checkpoint.timestamp.saturating_sub(sessions[i].ended_at) <= gap_secs => i,
+            _ => {
+                let prompt_id = generate_short_hash(&agent_id.id, &agent_id.tool);
+                let n = per_prompt.entry(prompt_id.clone()).or_insert(0);
+                *n += 1;
+                sessions.push(AgentSession {
+                    id: format!("{}-{}", &prompt_id[..8], n),
+                    tool: agent_id.tool.clone(),
+                    agent_session_id: agent_id.id.clone(),
+                    model: agent_id.model.clone(),
+                    prompt_id,
+                    started_at: checkpoint.timestamp,
+                    ended_at: checkpoint.timestamp,
+                    checkpoints: 0,
+                    files: BTreeSet::new(),
+                    additions: 0,
+                    deletions: 0,
+                    committed: Vec::new(),
+                });
+                open.insert(key, sessions.len() - 1);
+                sessions.len() - 1
+            }
+        };
+
+        let session = &mut sessions[index];
+        session.ended_at = checkpoint.timestamp;
+        session.checkpoints += 1;
+        session.additions += checkpoint.line_stats.additions;
+        session.deletions += checkpoint.line_stats.deletions;
+        session
+            .files
+            .extend(checkpoint.entries.iter().map(|e| e.file.clone()));
+        // Agents sometimes only learn the model after the first checkpoint
+        if session.model.is_empty() || session.model == "unknown" {
+            session.model = agent_id.model.clone();
+        }
+    }
+
+    sessions
+}
+
+/// A commit's accepted AI lines per prompt id
+pub struct CommittedPrompts {
+    pub sha: String,
+    pub commit_time: u64,
+    pub accepted_lines: BTreeMap<String, u32>,
+}
+
+/// Credit each commit's accepted lines to the latest session of the same prompt
+/// that started before the commit was made
+pub fn attribute_commits(sessions: &mut [AgentSession], commits: &[CommittedPrompts]) {
+    for commit in commits {
+        for (prompt_id, &lines) in &commit.accepted_lines {
+            if lines == 0 {
+                continue;
+            }
+            let target = sessions
+                .iter_mut()
+                .filter(|s| &s.prompt_id == prompt_id && s.started_at <= commit.commit_time)
+                .max_by_key(|s| s.started_at);
+            if let Some(session) = target {
+                session.committed.push(SessionCommit {
+                    sha: commit.sha.clone(),
+                    lines,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::working_log::{AgentId, CheckpointKind, WorkingLogEntry};
+
+    fn ai_checkpoint(agent: &str, timestamp: u64, file: &str, additions: u32) -> Checkpoint {
+        let entry = WorkingLogEntry::new(file.to_string(), "sha".to_string(), vec![], vec![]);
+        let mut checkpoint = Checkpoint::new(
+            CheckpointKind::AiAgent,
+            String::new(),
+            "test".to_string(),
+            vec![entry],
+        );
+        checkpoint.timestamp = timestamp;
+        checkpoint.line_stats.additions = additions;
+        checkpoint.agent_id = Some(AgentId {
+            tool: "claude".to_string(),
+            id: agent.to_string(),
+            model: "sonnet".to_string(),
+        });
+        checkpoint
+    }
+
+    #[test]
+    fn test_group_sessions_splits_on_gap_and_credits_commits() {
+        let human = Checkpoint::new(CheckpointKind::Human, String::new(), "me".into(), vec![]);
+        let checkpoints = vec![
+            ai_checkpoint("a", 1_000, "src/lib.rs", 10),
+            human,
+            ai_checkpoint("b", 1_100, "src/main.rs", 4),
+            ai_checkpoint("a", 1_500, "src/util.rs", 6),
+            // More than an hour later: a new run of the same agent session
+            ai_checkpoint("a", 6_000, "src/lib.rs", 20),
+        ];
+
+        let mut sessions = group_sessions(&checkpoints, 3_600);
+        assert_eq!(sessions.len(), 3);
+        let first = &sessions[0];
+        assert_eq!(first.agent_session_id, "a");
+        assert_eq!(first.checkpoints, 2);
+        assert_eq!(first.duration_secs(), 500);
+        assert_eq!(first.additions, 16);
+        assert_eq!(first.files.len(), 2);
+        assert!(first.id.ends_with("-1"));
+        assert!(sessions[2].id.ends_with("-2"));
+        assert_eq!(sessions[2].prompt_id, first.prompt_id);
+
+        let prompt_a = first.prompt_id.clone();
+        let commits = vec![
+            CommittedPrompts {
+                sha: "c1".to_string(),
+                commit_time: 2_000,
+                accepted_lines: BTreeMap::from([(prompt_a.clone(), 12)]),
+            },
+            CommittedPrompts {
+                sha: "c2".to_string(),
+                commit_time: 7_000,
+                accepted_lines: BTreeMap::from([(prompt_a, 5)]),
+            },
+        ];
+        attribute_commits(&mut sessions, &commits);
+        assert_eq!(sessions[0].committed_lines(), 12);
+        assert_eq!(sessions[2].committed_lines(), 5);
+        assert_eq!(sessions[1].committed_lines(), 0);
+        assert_eq!(sessions[0].survival_percent(), 75.0);
+    }
+}
@@ -1,10 +1,18 @@
+use crate::authorship::anonymize::anonymize_stats;
+use crate::authorship::human_authors::split_human_additions;
+use crate::authorship::review::reviewed_ai_lines;
+use crate::authorship::stats_baseline::print_comparison_with_baseline;
 use crate::authorship::transcript::Message;
+use crate::authorship::working_log::CheckpointKind;
+use crate::commands::output::{self, paint};
+use crate::commands::renderers;
 use crate::error::GitAiError;
 use crate::git::refs::get_authorship;
 use crate::git::repository::Repository;
 use crate::{authorship::authorship_log::LineRange, utils::debug_log};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
+use std::path::Path;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ToolModelHeadlineStats {
@@ -39,11 +47,46 @@ pub struct CommitStats {
     #[serde(default)]
     pub time_waiting_for_ai: u64, // seconds
     #[serde(default)]
+    pub reviewed_ai_additions: u32, // Number of AI lines a human has marked as reviewed (`git-ai review mark`)
+    #[serde(default)]
+    pub generated_additions: u32, // Number of lines committed from code generators, counted as neither AI nor human
+    #[serde(default)]
     pub git_diff_deleted_lines: u32,
     #[serde(default)]
     pub git_diff_added_lines: u32,
     #[serde(default)]
     pub tool_model_breakdown: BTreeMap<String, ToolModelHeadlineStats>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub file_breakdown: BTreeMap<String, FileHeadlineStats>,
+    /// Human additions split by author set, for commits from pair or mob sessions
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub human_breakdown: BTreeMap<String, u32>,
+    /// AI additions by checkpoint kind (`ai_agent`, `ai_tab`, `ai_refactor`, `imported`),
+    /// only filled when some AI lines came from something other than an agent session
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub kind_breakdown: BTreeMap<String, u32>,
+    /// Binary and oversized files, which have no line counts: the agent tool
+    /// (or "human") credited with each whole file
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub whole_file_breakdown: BTreeMap<String, String>,
+    /// Commits whose message an agent drafted
+    #[serde(default)]
+    pub ai_message_commits: u32,
+    /// AI lines left out by `--ignore-trivial` / `--min-hunk-size`
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub trivial_filtered_lines: u32,
+}
+
+fn is_zero(count: &u32) -> bool {
+    *count == 0
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct FileHeadlineStats {
+    #[serde(default)]
+    pub ai_additions: u32, // Lines in this file committed with AI attribution
+    #[serde(default)]
+    pub git_diff_added_lines: u32,
 }
 
 impl Default for CommitStats {
@@ -56,9 +99,17 @@ impl Default for CommitStats {
             total_ai_additions: 0,
             total_ai_deletions: 0,
             time_waiting_for_ai: 0,
+            reviewed_ai_additions: 0,
+            generated_additions: 0,
             git_diff_deleted_lines: 0,
             git_diff_added_lines: 0,
             tool_model_breakdown: BTreeMap::new(),
+            file_breakdown: BTreeMap::new(),
+            human_breakdown: BTreeMap::new(),
+            kind_breakdown: BTreeMap::new(),
+            whole_file_breakdown: BTreeMap::new(),
+            ai_message_commits: 0,
+            trivial_filtered_lines: 0,
         }
     }
 }
@@ -68,6 +119,8 @@ pub fn stats_command(
     commit_sha: Option<&str>,
     json: bool,
     ignore_patterns: &[String],
+    baseline: Option<&Path>,
+    trivial: TrivialFilter,
 ) -> Result<(), GitAiError> {
     let (target, refname) = if let Some(sha) = commit_sha {
         // Validate that the commit exists using revparse_single
@@ -96,11 +149,18 @@ pub fn stats_command(
         target, refname
     ));
 
-    let stats = stats_for_commit_stats(repo, &target, ignore_patterns)?;
+    let mut stats = stats_for_commit_stats_filtered(repo, &target, ignore_patterns, trivial)?;
+    if output::anonymize() {
+        anonymize_stats(&mut stats);
+    }
 
-    if json {
+    if let Some(baseline) = baseline {
+        print_comparison_with_baseline(&stats, baseline, json)?;
+    } else if json {
         let json_str = serde_json::to_string(&stats)?;
         println!("{}", json_str);
+    } else if let Some(renderer) = output::renderer() {
+        renderers::print_report(&renderer, "stats", &serde_json::to_value(&stats)?)?;
     } else {
         write_stats_to_terminal(&stats, true);
     }
@@ -109,6 +169,37 @@ pub fn stats_command(
 }
 
 pub fn write_stats_to_terminal(stats: &CommitStats, print: bool) -> String {
+    let mut output = write_line_stats_to_terminal(stats, print);
+    let mut note = |text: &str| {
+        let line = format!("     {}", paint("\x1b[90m", text));
+        output.push_str(&line);
+        output.push('\n');
+        if print {
+            println!("{}", line);
+        }
+    };
+    if !stats.whole_file_breakdown.is_empty() {
+        let files: Vec<String> = stats
+            .whole_file_breakdown
+            .iter()
+            .map(|(file, author)| format!("{} ({})", file, author))
+            .collect();
+        note(&format!("whole files: {}", files.join(" | ")));
+    }
+    match stats.ai_message_commits {
+        0 => {}
+        1 => note("commit message written by AI"),
+        count => note(&format!("{} commit messages written by AI", count)),
+    }
+    match stats.trivial_filtered_lines {
+        0 => {}
+        1 => note("1 trivial AI line filtered out"),
+        count => note(&format!("{} trivial AI lines filtered out", count)),
+    }
+    output
+}
+
+fn write_line_stats_to_terminal(stats: &CommitStats, print: bool) -> String {
     let mut output = String::new();
 
     // Set maximum bar width to 40 characters
@@ -119,9 +210,7 @@ pub fn write_stats_to_terminal(stats: &CommitStats, print: bool) -> String {
         // Show gray bar for deletion-only commit
         let mut progress_bar = String::new();
         progress_bar.push_str("you  ");
-        progress_bar.push_str("\x1b[90m"); // Gray color
-        progress_bar.push_str(&" ".repeat(bar_width)); // Gray bar
-        progress_bar.push_str("\x1b[0m"); // Reset color
+        progress_bar.push_str(&paint("\x1b[90m", &" ".repeat(bar_width))); // Gray bar
         progress_bar.push_str(" ai");
 
         output.push_str(&progress_bar);
@@ -131,7 +220,10 @@ pub fn write_stats_to_terminal(stats: &CommitStats, print: bool) -> String {
         }
 
         // Show "(no additions)" message below the bar
-        let no_additions_msg = format!("     \x1b[90m{:^40}\x1b[0m", "(no additions)");
+        let no_additions_msg = format!(
+            "     {}",
+            paint("\x1b[90m", &format!("{:^40}", "(no additions)"))
+        );
         output.push_str(&no_additions_msg);
         output.push('\n');
         if print {
@@ -298,9 +390,24 @@ pub fn write_stats_to_terminal(stats: &CommitStats, print: bool) -> String {
             "".to_string()
         };
 
+        let review_str = if stats.reviewed_ai_additions > 0 {
+            format!(
+                " | {}/{} AI lines reviewed",
+                stats.reviewed_ai_additions, stats.ai_additions
+            )
+        } else {
+            "".to_string()
+        };
+
         let ai_acceptance_str = format!(
-            "     \x1b[90m{:.0}% AI code accepted{}\x1b[0m",
-            _ai_acceptance_percentage, waiting_time_str
+            "     {}",
+            paint(
+                "\x1b[90m",
+                &format!(
+                    "{:.0}% AI code accepted{}{}",
+                    _ai_acceptance_percentage, waiting_time_str, review_str
+                )
+            )
         );
         output.push_str(&ai_acceptance_str);
         output.push('\n');
@@ -308,10 +415,101 @@ pub fn write_stats_to_terminal(stats: &CommitStats, print: bool) -> String {
             println!("{}", ai_acceptance_str);
         }
     }
+
+    if !stats.kind_breakdown.is_empty() {
+        let kinds: Vec<String> = stats
+            .kind_breakdown
+            .iter()
+            .map(|(kind, lines)| format!("{} {}", kind, lines))
+            .collect();
+        let kinds_str = format!(
+            "     {}",
+            paint(
+                "\x1b[90m",
+                &format!("ai lines by kind: {}", kinds.join(" | "))
+            )
+        );
+        output.push_str(&kinds_str);
+        output.push('\n');
+        if print {
+            println!("{}", kinds_str);
+        }
+    }
+
+    if !stats.human_breakdown.is_empty() {
+        let humans: Vec<String> = stats
+            .human_breakdown
+            .iter()
+            .map(|(authors, lines)| format!("{} {}", authors, lines))
+            .collect();
+        let humans_str = format!(
+            "     {}",
+            paint("\x1b[90m", &format!("human lines: {}", humans.join(" | ")))
+        );
+        output.push_str(&humans_str);
+        output.push('\n');
+        if print {
+            println!("{}", humans_str);
+        }
+    }
+
+    if stats.generated_additions > 0 {
+        let generated_str = format!(
+            "     {}",
+            paint(
+                "\x1b[90m",
+                &format!("{} generated lines not counted", stats.generated_additions)
+            )
+        );
+        output.push_str(&generated_str);
+        output.push('\n');
+        if print {
+            println!("{}", generated_str);
+        }
+    }
     return output;
 }
 
-#[allow(dead_code)]
+/// Compact per-commit block the commit hook prints to a terminal when
+/// `commit_summary` is set: files touched, added lines by origin and the agents
+/// involved
+pub fn format_commit_summary(commit_sha: &str, stats: &CommitStats) -> String {
+    let short_sha = &commit_sha[..commit_sha.len().min(7)];
+    let files = stats.file_breakdown.len();
+    let pure_human = stats.human_additions.saturating_sub(stats.mixed_additions);
+    let mut summary = format!(
+        "{} {}: {} file{}, +{} lines ({} AI, {} human, {} mixed)\n",
+        paint("\x1b[1;35m", "git-ai"),
+        short_sha,
+        files,
+        if files == 1 { "" } else { "s" },
+        stats.git_diff_added_lines,
+        stats.ai_additions,
+        pure_human,
+        stats.mixed_additions
+    );
+    let agents: Vec<String> = stats
+        .tool_model_breakdown
+        .iter()
+        .filter(|(_, tool)| tool.ai_additions > 0)
+        .map(|(key, _)| match key.split_once("::") {
+            Some((tool, model)) if !model.is_empty() && model != "unknown" => {
+                format!("{} ({})", tool, model)
+            }
+            Some((tool, _)) => tool.to_string(),
+            None => key.clone(),
+        })
+        .collect();
+    if !agents.is_empty() {
+        summary.push_str(&format!(
+            "{}agents: {}\n",
+            " ".repeat(7),
+            paint("\x1b[90m", &agents.join(", "))
+        ));
+    }
+    summary
+}
+
 pub fn write_stats_to_markdown(stats: &CommitStats) -> String {
     let mut output = String::new();
 
@@ -477,13 +675,23 @@ pub fn stats_from_authorship_log(
         total_ai_additions: 0,
         total_ai_deletions: 0,
         time_waiting_for_ai: 0,
+        reviewed_ai_additions: 0,
+        generated_additions: 0,
         tool_model_breakdown: BTreeMap::new(),
+        file_breakdown: BTreeMap::new(),
+        human_breakdown: BTreeMap::new(),
+        kind_breakdown: BTreeMap::new(),
+        whole_file_breakdown: BTreeMap::new(),
+        ai_message_commits: 0,
+        trivial_filtered_lines: 0,
         git_diff_deleted_lines,
         git_diff_added_lines,
     };
 
     // Process authorship log if present
     if let Some(log) = authorship_log {
+        commit_stats.ai_message_commits = log.metadata.commit_message.is_some() as u32;
+        let mut kind_lines: BTreeMap<String, u32> = BTreeMap::new();
         // Count lines by author type
         for file_attestation in &log.attestations {
             for entry in &file_attestation.entries {
@@ -499,8 +707,16 @@ pub fn stats_from_authorship_log(
 
                 // Check if this is an AI-generated entry
                 if let Some(prompt_record) = log.metadata.prompts.get(&entry.hash) {
+                    if prompt_record.generated {
+                        commit_stats.generated_additions += lines_in_entry;
+                        continue;
+                    }
+
                     // Count accepted lines (lines that were accepted by the user without any human edits)
                     commit_stats.ai_accepted += lines_in_entry;
+                    *kind_lines
+                        .entry(prompt_record.checkpoint_kind().to_str())
+                        .or_default() += lines_in_entry;
 
                     let key = format!(
                         "{}::{}",
@@ -512,10 +728,13 @@ pub fn stats_from_authorship_log(
             }
         }
 
-        for prompt_record in log.metadata.prompts.values() {
+        for prompt_record in log.metadata.prompts.values().filter(|p| !p.generated) {
             commit_stats.total_ai_additions += prompt_record.total_additions;
             commit_stats.total_ai_deletions += prompt_record.total_deletions;
             commit_stats.mixed_additions += prompt_record.overriden_lines;
+            *kind_lines
+                .entry(prompt_record.checkpoint_kind().to_str())
+                .or_default() += prompt_record.overriden_lines;
 
             let key = format!(
                 "{}::{}",
@@ -542,6 +761,14 @@ pub fn stats_from_authorship_log(
             git_diff_added_lines,
         );
 
+        commit_stats.reviewed_ai_additions = reviewed_ai_lines(log).min(commit_stats.ai_additions);
+
+        kind_lines.retain(|_, lines| *lines > 0);
+        let agent = CheckpointKind::AiAgent.to_str();
+        if kind_lines.keys().any(|kind| *kind != agent) {
+            commit_stats.kind_breakdown = kind_lines;
+        }
+
         // Calculate ai_additions for each tool following the same contract: ai_additions = ai_accepted + mixed_additions
         for tool_stats in commit_stats.tool_model_breakdown.values_mut() {
             tool_stats.ai_additions = tool_stats.ai_accepted + tool_stats.mixed_additions;
@@ -550,10 +777,17 @@ pub fn stats_from_authorship_log(
 
     // Human additions are the difference between total git diff and AI accepted lines (ensure non-negative)
     // This includes mixed lines (AI-generated but human-edited) as human additions
+    commit_stats.generated_additions = commit_stats.generated_additions.min(git_diff_added_lines);
     commit_stats.human_additions = std::cmp::max(
         0,
-        git_diff_added_lines.saturating_sub(commit_stats.ai_accepted),
+        git_diff_added_lines
+            .saturating_sub(commit_stats.ai_accepted)
+            .saturating_sub(commit_stats.generated_additions),
     );
+    if let Some(log) = authorship_log {
+        commit_stats.human_breakdown =
+            split_human_additions(commit_stats.human_additions, &log.metadata.human_authors);
+    }
 
     commit_stats
 }
@@ -562,22 +796,201 @@ pub fn stats_for_commit_stats(
     repo: &Repository,
     commit_sha: &str,
     ignore_patterns: &[String],
+) -> Result<CommitStats, GitAiError> {
+    stats_for_commit_stats_filtered(repo, commit_sha, ignore_patterns, TrivialFilter::default())
+}
+
+/// Like [`stats_for_commit_stats`], leaving out AI lines `trivial` rejects. Those
+/// lines are dropped from the diff totals too, so they count as neither AI nor human.
+pub fn stats_for_commit_stats_filtered(
+    repo: &Repository,
+    commit_sha: &str,
+    ignore_patterns: &[String],
+    trivial: TrivialFilter,
 ) -> Result<CommitStats, GitAiError> {
     // Step 1: get the diff between this commit and its parent ON refname (if more than one parent)
     // If initial than everything is additions
     // We want the count here git shows +111 -55
-    let (git_diff_added_lines, git_diff_deleted_lines) =
-        get_git_diff_stats(repo, commit_sha, ignore_patterns)?;
+    let mut file_diff_stats = get_git_diff_file_stats(repo, commit_sha, |filename| {
+        !crate::authorship::range_authorship::should_ignore_file(filename, ignore_patterns)
+    })?;
 
     // Step 2: get the authorship log for this commit
-    let authorship_log = get_authorship(repo, &commit_sha);
+    let mut authorship_log = get_authorship(repo, commit_sha);
+    let mut filtered_lines = 0;
+    if let Some(log) = authorship_log.as_mut().filter(|_| trivial.is_active()) {
+        let dropped = trivial.apply(log, |path| {
+            let content = repo.get_file_content(path, commit_sha).ok()?;
+            Some(
+                String::from_utf8_lossy(&content)
+                    .lines()
+                    .map(str::to_string)
+                    .collect(),
+            )
+        });
+        for (path, lines) in dropped {
+            filtered_lines += lines;
+            if let Some((added, _)) = file_diff_stats.get_mut(&path) {
+                *added = added.saturating_sub(lines);
+            }
+        }
+    }
+    let (git_diff_added_lines, git_diff_deleted_lines) = sum_diff_stats(&file_diff_stats);
 
     // Step 3: Calculate stats from authorship log
-    Ok(stats_from_authorship_log(
+    let mut stats = stats_from_authorship_log(
         authorship_log.as_ref(),
         git_diff_added_lines,
         git_diff_deleted_lines,
-    ))
+    );
+    stats.file_breakdown = file_breakdown(authorship_log.as_ref(), &file_diff_stats);
+    stats.trivial_filtered_lines = filtered_lines;
+    if let Some(log) = &authorship_log {
+        stats.whole_file_breakdown = whole_file_breakdown(log, ignore_patterns);
+    }
+    Ok(stats)
+}
+
+/// Who each binary or oversized file in the commit is credited to, by agent
+/// tool or "human"
+pub fn whole_file_breakdown(
+    authorship_log: &crate::authorship::authorship_log_serialization::AuthorshipLog,
+    ignore_patterns: &[String],
+) -> BTreeMap<String, String> {
+    authorship_log
+        .metadata
+        .file_authors
+        .iter()
+        .filter(|(file, _)| {
+            !crate::authorship::range_authorship::should_ignore_file(file, ignore_patterns)
+        })
+        .map(|(file, author_id)| {
+            let author = authorship_log
+                .metadata
+                .prompts
+                .get(author_id)
+                .map(|prompt| prompt.agent_id.tool.clone())
+                .unwrap_or_else(|| author_id.clone());
+            (file.clone(), author)
+        })
+        .collect()
+}
+
+/// Which AI attributions are too small to count towards stats
+/// (`stats --ignore-trivial`, `stats --min-hunk-size <n>`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TrivialFilter {
+    /// Drop whitespace-only lines and lines with a single non-whitespace character
+    pub ignore_trivial: bool,
+    /// Drop runs of consecutive attributed lines shorter than this
+    pub min_hunk_size: u32,
+}
+
+impl TrivialFilter {
+    pub fn is_active(&self) -> bool {
+        self.ignore_trivial || self.min_hunk_size > 1
+    }
+
+    fn is_trivial_line(line: &str) -> bool {
+        line.chars().filter(|c| !c.is_whitespace()).count() <= 1
+    }
+
+    /// Remove rejected lines from `log`'s attestations and return how many were
+    /// dropped per file. `file_lines` gives a file's content at the commit; when
+    /// it is unavailable only the hunk size check applies.
+    pub fn apply(
+        &self,
+        log: &mut crate::authorship::authorship_log_serialization::AuthorshipLog,
+        file_lines: impl Fn(&str) -> Option<Vec<String>>,
+    ) -> BTreeMap<String, u32> {
+        let mut dropped = BTreeMap::new();
+        for file in &mut log.attestations {
+            let content = if self.ignore_trivial {
+                file_lines(&file.file_path)
+            } else {
+                None
+            };
+            let mut file_dropped = 0;
+            for entry in &mut file.entries {
+                let mut lines: Vec<u32> =
+                    entry.line_ranges.iter().flat_map(|r| r.expand()).collect();
+                let before = lines.len() as u32;
+                if let Some(content) = &content {
+                    lines.retain(|line| {
+                        (*line as usize)
+                            .checked_sub(1)
+                            .and_then(|index| content.get(index))
+                            .is_none_or(|text| !Self::is_trivial_line(text))
+                    });
+                }
+                lines.sort_unstable();
+                lines.dedup();
+                let ranges: Vec<LineRange> = LineRange::compress_lines(&lines)
+                    .into_iter()
+                    .filter(|range| match range {
+                        LineRange::Single(_) => self.min_hunk_size <= 1,
+                        LineRange::Range(start, end) => end - start + 1 >= self.min_hunk_size,
+                    })
+                    .collect();
+                let after: u32 = ranges.iter().map(|range| range.expand().len() as u32).sum();
+                file_dropped += before.saturating_sub(after);
+                entry.line_ranges = ranges;
+            }
+            file.entries.retain(|entry| !entry.line_ranges.is_empty());
+            if file_dropped > 0 {
+                dropped.insert(file.file_path.clone(), file_dropped);
+            }
+        }
+        log.attestations.retain(|file| !file.entries.is_empty());
+        dropped
+    }
+}
+
+/// Per-file AI lines for the files in a diff. A file's AI lines are capped at
+/// the lines the diff added to it, like the commit-wide total.
+pub fn file_breakdown(
+    authorship_log: Option<&crate::authorship::authorship_log_serialization::AuthorshipLog>,
+    file_diff_stats: &BTreeMap<String, (u32, u32)>,
+) -> BTreeMap<String, FileHeadlineStats> {
+    let mut breakdown: BTreeMap<String, FileHeadlineStats> = file_diff_stats
+        .iter()
+        .map(|(file, (added, _))| {
+            let stats = FileHeadlineStats {
+                ai_additions: 0,
+                git_diff_added_lines: *added,
+            };
+            (file.clone(), stats)
+        })
+        .collect();
+
+    if let Some(log) = authorship_log {
+        for file_attestation in &log.attestations {
+            let Some(file_stats) = breakdown.get_mut(&file_attestation.file_path) else {
+                continue;
+            };
+            for entry in &file_attestation.entries {
+                if log
+                    .metadata
+                    .prompts
+                    .get(&entry.hash)
+                    .is_none_or(|prompt| prompt.generated)
+                {
+                    continue;
+                }
+                let lines: u32 = entry
+                    .line_ranges
+                    .iter()
+                    .map(|range| match range {
+                        LineRange::Single(_) => 1,
+                        LineRange::Range(start, end) => end - start + 1,
+                    })
+                    .sum();
+                file_stats.ai_additions += lines;
+            }
+            file_stats.ai_additions = file_stats.ai_additions.min(file_stats.git_diff_added_lines);
+        }
+    }
+    breakdown
 }
 
 /// Get git diff statistics between commit and its parent
@@ -586,6 +999,28 @@ pub fn get_git_diff_stats(
     commit_sha: &str,
     ignore_patterns: &[String],
 ) -> Result<(u32, u32), GitAiError> {
+    get_git_diff_stats_matching(repo, commit_sha, |filename| {
+        !crate::authorship::range_authorship::should_ignore_file(filename, ignore_patterns)
+    })
+}
+
+/// Like [`get_git_diff_stats`], counting only files for which `include` returns true
+pub fn get_git_diff_stats_matching(
+    repo: &Repository,
+    commit_sha: &str,
+    include: impl Fn(&str) -> bool,
+) -> Result<(u32, u32), GitAiError> {
+    Ok(sum_diff_stats(&get_git_diff_file_stats(
+        repo, commit_sha, include,
+    )?))
+}
+
+/// Added and deleted lines per file between commit and its parent
+pub fn get_git_diff_file_stats(
+    repo: &Repository,
+    commit_sha: &str,
+    include: impl Fn(&str) -> bool,
+) -> Result<BTreeMap<String, (u32, u32)>, GitAiError> {
     // Use git show --numstat to get diff statistics
     let mut args = repo.global_args_for_exec();
     args.push("show".to_string());
@@ -595,11 +1030,36 @@ pub fn get_git_diff_stats(
 
     let output = crate::git::repository::exec_git(&args)?;
     let stdout = String::from_utf8(output.stdout)?;
+    Ok(parse_numstat(&stdout, include))
+}
 
-    let mut added_lines = 0u32;
-    let mut deleted_lines = 0u32;
+/// The post-image path of a `--numstat` entry. Renames are printed as
+/// `old => new` or `dir/{old => new}/file`, which path filters can't match.
+fn numstat_path(raw: &str) -> String {
+    if !raw.contains(" => ") {
+        return raw.to_string();
+    }
+    if let (Some(open), Some(close)) = (raw.find('{'), raw.rfind('}'))
+        && open < close
+    {
+        let inner = &raw[open + 1..close];
+        let new = inner.split_once(" => ").map_or(inner, |(_, new)| new);
+        // `{ => dir}/file` and `dir/{old => }` leave a doubled or leading slash
+        let joined = format!("{}{}{}", &raw[..open], new, &raw[close + 1..]);
+        return joined
+            .replace("//", "/")
+            .trim_start_matches('/')
+            .to_string();
+    }
+    raw.split_once(" => ")
+        .map_or(raw, |(_, new)| new)
+        .to_string()
+}
+
+/// Parse `--numstat` output into added and deleted lines per file
+pub fn parse_numstat(stdout: &str, include: impl Fn(&str) -> bool) -> BTreeMap<String, (u32, u32)> {
+    let mut files = BTreeMap::new();
 
-    // Parse numstat output
     for line in stdout.lines() {
         if line.trim().is_empty() {
             continue;
@@ -614,26 +1074,28 @@ pub fn get_git_diff_stats(
         let parts: Vec<&str> = line.split('\t').collect();
         if parts.len() >= 3 {
             // Check if this file should be ignored
-            let filename = parts[2];
-            if crate::authorship::range_authorship::should_ignore_file(filename, ignore_patterns) {
+            let filename = numstat_path(parts[2]);
+            if !include(&filename) {
                 continue;
             }
 
-            // Parse added lines
-            if let Ok(added) = parts[0].parse::<u32>() {
-                added_lines += added;
-            }
-
-            // Parse deleted lines (handle "-" for binary files)
-            if parts[1] != "-" {
-                if let Ok(deleted) = parts[1].parse::<u32>() {
-                    deleted_lines += deleted;
-                }
-            }
+            let added = parts[0].parse::<u32>().unwrap_or(0);
+            // Handle "-" for binary files
+            let deleted = parts[1].parse::<u32>().unwrap_or(0);
+            let file_stats: &mut (u32, u32) = files.entry(filename).or_default();
+            file_stats.0 += added;
+            file_stats.1 += deleted;
         }
     }
 
-    Ok((added_lines, deleted_lines))
+    files
+}
+
+/// Total added and deleted lines across files
+pub fn sum_diff_stats(files: &BTreeMap<String, (u32, u32)>) -> (u32, u32) {
+    files
+        .values()
+        .fold((0, 0), |(added, deleted), (a, d)| (added + a, deleted + d))
 }
 
 /// Calculate time waiting for AI from transcript messages
@@ -692,6 +1154,20 @@ mod tests {
     use super::*;
     use crate::git::test_utils::TmpRepo;
 
+    #[test]
+    fn test_parse_numstat_uses_post_rename_paths() {
+        let stdout =
+            "3\t1\tsrc/{old => new}/lib.rs\n2\t0\ta.rs => b.rs\n1\t1\t{ => docs}/README.md\n";
+        let files = parse_numstat(stdout, |path| {
+            path.starts_with("src/new/") || path == "b.rs"
+        });
+        assert_eq!(files.get("src/new/lib.rs"), Some(&(3, 1)));
+        assert_eq!(files.get("b.rs"), Some(&(2, 0)));
+        assert_eq!(files.len(), 2);
+        assert_eq!(numstat_path("{ => docs}/README.md"), "docs/README.md");
+        assert_eq!(numstat_path("src/{lib => }/mod.rs"), "src/mod.rs");
+    }
+
     #[test]
     fn test_terminal_stats_display() {
         // Test with mixed human/AI stats
@@ -705,7 +1181,15 @@ mod tests {
             git_diff_added_lines: 80,
             total_ai_additions: 100,
             total_ai_deletions: 0,
+            reviewed_ai_additions: 0,
+            generated_additions: 0,
             tool_model_breakdown: BTreeMap::new(),
+            file_breakdown: BTreeMap::new(),
+            human_breakdown: BTreeMap::new(),
+            kind_breakdown: BTreeMap::new(),
+            whole_file_breakdown: BTreeMap::new(),
+            ai_message_commits: 0,
+            trivial_filtered_lines: 0,
         };
 
         let mixed_output = write_stats_to_terminal(&stats, true);
@@ -722,7 +1206,15 @@ mod tests {
             git_diff_added_lines: 100,
             total_ai_additions: 100,
             total_ai_deletions: 0,
+            reviewed_ai_additions: 0,
+            generated_additions: 0,
             tool_model_breakdown: BTreeMap::new(),
+            file_breakdown: BTreeMap::new(),
+            human_breakdown: BTreeMap::new(),
+            kind_breakdown: BTreeMap::new(),
+            whole_file_breakdown: BTreeMap::new(),
+            ai_message_commits: 0,
+            trivial_filtered_lines: 0,
         };
 
         let ai_only_output = write_stats_to_terminal(&ai_stats, true);
@@ -739,7 +1231,15 @@ mod tests {
             git_diff_added_lines: 75,
             total_ai_additions: 0,
             total_ai_deletions: 0,
+            reviewed_ai_additions: 0,
+            generated_additions: 0,
             tool_model_breakdown: BTreeMap::new(),
+            file_breakdown: BTreeMap::new(),
+            human_breakdown: BTreeMap::new(),
+            kind_breakdown: BTreeMap::new(),
+            whole_file_breakdown: BTreeMap::new(),
+            ai_message_commits: 0,
+            trivial_filtered_lines: 0,
         };
 
         let human_only_output = write_stats_to_terminal(&human_stats, true);
@@ -756,7 +1256,15 @@ mod tests {
             git_diff_added_lines: 102,
             total_ai_additions: 100,
             total_ai_deletions: 0,
+            reviewed_ai_additions: 0,
+            generated_additions: 0,
             tool_model_breakdown: BTreeMap::new(),
+            file_breakdown: BTreeMap::new(),
+            human_breakdown: BTreeMap::new(),
+            kind_breakdown: BTreeMap::new(),
+            whole_file_breakdown: BTreeMap::new(),
+            ai_message_commits: 0,
+            trivial_filtered_lines: 0,
         };
 
         let minimal_human_output = write_stats_to_terminal(&minimal_human_stats, true);
@@ -773,7 +1281,15 @@ mod tests {
             git_diff_added_lines: 0,
             total_ai_additions: 0,
             total_ai_deletions: 0,
+            reviewed_ai_additions: 0,
+            generated_additions: 0,
             tool_model_breakdown: BTreeMap::new(),
+            file_breakdown: BTreeMap::new(),
+            human_breakdown: BTreeMap::new(),
+            kind_breakdown: BTreeMap::new(),
+            whole_file_breakdown: BTreeMap::new(),
+            ai_message_commits: 0,
+            trivial_filtered_lines: 0,
         };
 
         let deletion_only_output = write_stats_to_terminal(&deletion_only_stats, true);
@@ -793,7 +1309,15 @@ mod tests {
             git_diff_added_lines: 80,
             total_ai_additions: 100,
             total_ai_deletions: 0,
+            reviewed_ai_additions: 0,
+            generated_additions: 0,
             tool_model_breakdown: BTreeMap::new(),
+            file_breakdown: BTreeMap::new(),
+            human_breakdown: BTreeMap::new(),
+            kind_breakdown: BTreeMap::new(),
+            whole_file_breakdown: BTreeMap::new(),
+            ai_message_commits: 0,
+            trivial_filtered_lines: 0,
         };
 
         let mixed_output = write_stats_to_markdown(&stats);
@@ -810,7 +1334,15 @@ mod tests {
             git_diff_added_lines: 100,
             total_ai_additions: 100,
             total_ai_deletions: 0,
+            reviewed_ai_additions: 0,
+            generated_additions: 0,
             tool_model_breakdown: BTreeMap::new(),
+            file_breakdown: BTreeMap::new(),
+            human_breakdown: BTreeMap::new(),
+            kind_breakdown: BTreeMap::new(),
+            whole_file_breakdown: BTreeMap::new(),
+            ai_message_commits: 0,
+            trivial_filtered_lines: 0,
         };
 
         let ai_only_output = write_stats_to_markdown(&ai_stats);
@@ -827,7 +1359,15 @@ mod tests {
             git_diff_added_lines: 75,
             total_ai_additions: 0,
             total_ai_deletions: 0,
+            reviewed_ai_additions: 0,
+            generated_additions: 0,
             tool_model_breakdown: BTreeMap::new(),
+            file_breakdown: BTreeMap::new(),
+            human_breakdown: BTreeMap::new(),
+            kind_breakdown: BTreeMap::new(),
+            whole_file_breakdown: BTreeMap::new(),
+            ai_message_commits: 0,
+            trivial_filtered_lines: 0,
         };
 
         let human_only_output = write_stats_to_markdown(&human_stats);
@@ -844,7 +1384,15 @@ mod tests {
             git_diff_added_lines: 102,
             total_ai_additions: 100,
             total_ai_deletions: 0,
+            reviewed_ai_additions: 0,
+            generated_additions: 0,
             tool_model_breakdown: BTreeMap::new(),
+            file_breakdown: BTreeMap::new(),
+            human_breakdown: BTreeMap::new(),
+            kind_breakdown: BTreeMap::new(),
+            whole_file_breakdown: BTreeMap::new(),
+            ai_message_commits: 0,
+            trivial_filtered_lines: 0,
         };
 
         let minimal_human_output = write_stats_to_markdown(&minimal_human_stats);
@@ -861,7 +1409,15 @@ mod tests {
             git_diff_added_lines: 0,
             total_ai_additions: 0,
             total_ai_deletions: 0,
+            reviewed_ai_additions: 0,
+            generated_additions: 0,
             tool_model_breakdown: BTreeMap::new(),
+            file_breakdown: BTreeMap::new(),
+            human_breakdown: BTreeMap::new(),
+            kind_breakdown: BTreeMap::new(),
+            whole_file_breakdown: BTreeMap::new(),
+            ai_message_commits: 0,
+            trivial_filtered_lines: 0,
         };
 
         let deletion_only_output = write_stats_to_markdown(&deletion_only_stats);
@@ -1208,4 +1764,254 @@ mod tests {
         assert_eq!(stats_filtered.git_diff_added_lines, 1);
         assert_eq!(stats_filtered.ai_additions, 1);
     }
+
+    #[test]
+    fn test_generated_lines_are_neither_ai_nor_human() {
+        use crate::authorship::authorship_log::PromptRecord;
+        use crate::authorship::authorship_log_serialization::{AttestationEntry, AuthorshipLog};
+        use crate::authorship::working_log::AgentId;
+
+        let record = |tool: &str, generated: bool| PromptRecord {
+            agent_id: AgentId {
+                tool: tool.to_string(),
+                id: tool.to_string(),
+                model: "model".to_string(),
+            },
+            human_author: None,
+            messages: vec![],
+            total_additions: 0,
+            total_deletions: 0,
+            accepted_lines: 0,
+            overriden_lines: 0,
+            generated,
+            kind: None,
+            notes: Vec::new(),
+            tags: Vec::new(),
+            agent_metadata: BTreeMap::new(),
+        };
+        let mut log = AuthorshipLog::new();
+        log.metadata
+            .prompts
+            .insert("ai".to_string(), record("claude", false));
+        log.metadata
+            .prompts
+            .insert("gen".to_string(), record("protoc", true));
+        log.get_or_create_file("src/lib.rs")
+            .add_entry(AttestationEntry::new(
+                "ai".to_string(),
+                vec![LineRange::Range(1, 3)],
+            ));
+        log.get_or_create_file("gen/api.pb.go")
+            .add_entry(AttestationEntry::new(
+                "gen".to_string(),
+                vec![LineRange::Range(1, 10)],
+            ));
+
+        let stats = stats_from_authorship_log(Some(&log), 15, 0);
+        assert_eq!(stats.ai_additions, 3);
+        assert_eq!(stats.generated_additions, 10);
+        assert_eq!(stats.human_additions, 2);
+        assert!(
+            !stats
+                .tool_model_breakdown
+                .keys()
+                .any(|k| k.starts_with("protoc"))
+        );
+        assert!(write_stats_to_terminal(&stats, false).contains("10 generated lines not counted"));
+    }
+
+    #[test]
+    fn test_human_additions_split_by_author_set() {
+        use crate::authorship::authorship_log_serialization::AuthorshipLog;
+
+        let mut log = AuthorshipLog::new();
+        log.metadata
+            .human_authors
+            .insert("alice, bob".to_string(), 30);
+        log.metadata.human_authors.insert("carol".to_string(), 10);
+
+        let stats = stats_from_authorship_log(Some(&log), 8, 0);
+        assert_eq!(stats.human_additions, 8);
+        assert_eq!(stats.human_breakdown.get("alice, bob"), Some(&6));
+        assert_eq!(stats.human_breakdown.get("carol"), Some(&2));
+        assert!(
+            write_stats_to_terminal(&stats, false).contains("human lines: alice, bob 6 | carol 2")
+        );
+
+        let solo = stats_from_authorship_log(Some(&AuthorshipLog::new()), 8, 0);
+        assert!(solo.human_breakdown.is_empty());
+    }
+
+    #[test]
+    fn test_ai_lines_split_by_checkpoint_kind() {
+        use crate::authorship::authorship_log_serialization::AuthorshipLog;
+
+        let note = r#"src/lib.rs
+  aaa1111 1-6
+  bbb2222 7-8
+---
+{
+  "schema_version": "authorship/3.0.0",
+  "base_commit_sha": "0123abcd",
+  "prompts": {
+    "aaa1111": {
+      "agent_id": {"tool": "claude", "id": "s1", "model": "opus"},
+      "human_author": null,
+      "messages": [],
+      "total_additions": 6,
+      "total_deletions": 0,
+      "accepted_lines": 6,
+      "overriden_lines": 0
+    },
+    "bbb2222": {
+      "agent_id": {"tool": "cursor", "id": "s2", "model": "tab"},
+      "human_author": null,
+      "messages": [],
+      "total_additions": 2,
+      "total_deletions": 0,
+      "accepted_lines": 2,
+      "overriden_lines": 0,
+      "kind": "ai_tab"
+    }
+  }
+}"#;
+        let log = AuthorshipLog::deserialize_from_string(note).unwrap();
+        let stats = stats_from_authorship_log(Some(&log), 10, 0);
+        assert_eq!(stats.ai_additions, 8);
+        assert_eq!(stats.kind_breakdown.get("ai_agent"), Some(&6));
+        assert_eq!(stats.kind_breakdown.get("ai_tab"), Some(&2));
+        assert!(
+            write_stats_to_terminal(&stats, false)
+                .contains("ai lines by kind: ai_agent 6 | ai_tab 2")
+        );
+
+        // Agent sessions alone don't need a breakdown
+        let mut log = log;
+        log.metadata.prompts.remove("bbb2222");
+        log.attestations[0].entries.truncate(1);
+        assert!(
+            stats_from_authorship_log(Some(&log), 10, 0)
+                .kind_breakdown
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_format_commit_summary() {
+        let mut stats = CommitStats {
+            human_additions: 12,
+            mixed_additions: 4,
+            ai_additions: 30,
+            git_diff_added_lines: 42,
+            ..Default::default()
+        };
+        stats
+            .file_breakdown
+            .insert("src/a.rs".to_string(), FileHeadlineStats::default());
+        stats
+            .file_breakdown
+            .insert("src/b.rs".to_string(), FileHeadlineStats::default());
+        stats.tool_model_breakdown.insert(
+            "claude::claude-sonnet-4".to_string(),
+            ToolModelHeadlineStats {
+                ai_additions: 30,
+                ..Default::default()
+            },
+        );
+        stats.tool_model_breakdown.insert(
+            "mock_ai::unknown".to_string(),
+            ToolModelHeadlineStats::default(),
+        );
+
+        let summary = format_commit_summary("0123456789abcdef", &stats);
+        assert!(summary.contains(" 0123456: 2 files, +42 lines (30 AI, 8 human, 4 mixed)\n"));
+        assert!(summary.contains("agents: "));
+        assert!(summary.contains("claude (claude-sonnet-4)"));
+        assert!(!summary.contains("mock_ai"));
+        assert_eq!(summary.lines().count(), 2);
+    }
+
+    #[test]
+    fn test_trivial_filter_drops_small_ai_attributions() {
+        use crate::authorship::authorship_log_serialization::AuthorshipLog;
+
+        let note = r#"src/lib.rs
+  aaa1111 1-4,6
+  bbb2222 8-9
+---
+{
+  "schema_version": "authorship/3.0.0",
+  "base_commit_sha": "0123abcd",
+  "prompts": {}
+}"#;
+        let content: Vec<String> = [
+            "use a;",
+            "",
+            "fn f() {",
+            "}",
+            "x",
+            "let y = 1;",
+            "z",
+            "a",
+            "b",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+
+        let mut log = AuthorshipLog::deserialize_from_string(note).unwrap();
+        let filter = TrivialFilter {
+            ignore_trivial: true,
+            min_hunk_size: 0,
+        };
+        let dropped = filter.apply(&mut log, |_| Some(content.clone()));
+        // Blank line 2, "}" on 4 and the single-letter lines 8-9 go
+        assert_eq!(dropped["src/lib.rs"], 4);
+        assert_eq!(log.attestations[0].entries.len(), 1);
+        assert_eq!(
+            log.attestations[0].entries[0].line_ranges,
+            vec![
+                LineRange::Single(1),
+                LineRange::Single(3),
+                LineRange::Single(6)
+            ]
+        );
+
+        let mut log = AuthorshipLog::deserialize_from_string(note).unwrap();
+        let filter = TrivialFilter {
+            ignore_trivial: false,
+            min_hunk_size: 3,
+        };
+        let dropped = filter.apply(&mut log, |_| None);
+        assert_eq!(dropped["src/lib.rs"], 3);
+        assert_eq!(
+            log.attestations[0].entries[0].line_ranges,
+            vec![LineRange::Range(1, 4)]
+        );
+        assert!(!TrivialFilter::default().is_active());
+
+        // A corrupt note claiming line 0 must not underflow the line index
+        let mut log =
+            AuthorshipLog::deserialize_from_string(&note.replace("1-4,6", "0-1")).unwrap();
+        let filter = TrivialFilter {
+            ignore_trivial: true,
+            min_hunk_size: 0,
+        };
+        filter.apply(&mut log, |_| Some(content.clone()));
+        assert_eq!(
+            log.attestations[0].entries[0].line_ranges,
+            vec![LineRange::Range(0, 1)]
+        );
+
+        let stats = CommitStats {
+            trivial_filtered_lines: 4,
+            ..Default::default()
+        };
+        assert!(write_stats_to_terminal(&stats, false).contains("4 trivial AI lines filtered out"));
+        assert!(
+            !serde_json::to_string(&CommitStats::default())
+                .unwrap()
+                .contains("trivial_filtered_lines")
+        );
+    }
 }
@@ -0,0 +1,272 @@
+//! `git-ai stats --baseline <file.json>`: compare the current stats against a
+//! report saved earlier with `stats --json`, e.g. to see how AI usage moved
+//! between two sprints. Both commit and range reports are accepted.
+
+use crate::authorship::stats::CommitStats;
+use crate::error::GitAiError;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// AI lines out of all added lines, for one file, tool or the whole report
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Share {
+    pub ai_lines: u32,
+    pub total_lines: u32,
+}
+
+impl Share {
+    pub fn ai_pct(&self) -> f64 {
+        if self.total_lines == 0 {
+            0.0
+        } else {
+            self.ai_lines as f64 / self.total_lines as f64 * 100.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ShareDelta {
+    pub key: String,
+    /// None when the key is missing from that report
+    pub baseline: Option<Share>,
+    pub current: Option<Share>,
+    /// Change in AI percentage points
+    pub ai_pct_delta: f64,
+}
+
+impl ShareDelta {
+    fn new(key: String, baseline: Option<Share>, current: Option<Share>) -> Self {
+        let pct = |share: Option<Share>| share.map_or(0.0, |s| s.ai_pct());
+        ShareDelta {
+            key,
+            baseline,
+            current,
+            ai_pct_delta: pct(current) - pct(baseline),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatsComparison {
+    pub overall: ShareDelta,
+    /// Only files whose numbers changed, biggest swing first
+    pub files: Vec<ShareDelta>,
+    /// Per `tool::model`, as a share of all added lines
+    pub tools: Vec<ShareDelta>,
+}
+
+/// Read a report written by `stats --json`, for a commit or a range
+pub fn load_baseline(path: &Path) -> Result<CommitStats, GitAiError> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        GitAiError::Generic(format!("Failed to read baseline {}: {}", path.display(), e))
+    })?;
+    parse_baseline(&content)
+}
+
+pub fn parse_baseline(content: &str) -> Result<CommitStats, GitAiError> {
+    let mut value: serde_json::Value = serde_json::from_str(content)?;
+    if let Some(range_stats) = value.get_mut("range_stats") {
+        value = range_stats.take();
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+fn overall_share(stats: &CommitStats) -> Share {
+    Share {
+        ai_lines: stats.ai_additions,
+        total_lines: stats.git_diff_added_lines,
+    }
+}
+
+fn compare_maps(
+    baseline: &BTreeMap<String, Share>,
+    current: &BTreeMap<String, Share>,
+) -> Vec<ShareDelta> {
+    let keys: BTreeSet<&String> = baseline.keys().chain(current.keys()).collect();
+    let mut deltas: Vec<ShareDelta> = keys
+        .into_iter()
+        .filter(|key| baseline.get(*key) != current.get(*key))
+        .map(|key| {
+            ShareDelta::new(
+                key.clone(),
+                baseline.get(key).copied(),
+                current.get(key).copied(),
+            )
+        })
+        .collect();
+    deltas.sort_by(|a, b| {
+        b.ai_pct_delta
+            .abs()
+            .total_cmp(&a.ai_pct_delta.abs())
+            .then_with(|| a.key.cmp(&b.key))
+    });
+    deltas
+}
+
+pub fn compare(baseline: &CommitStats, current: &CommitStats) -> StatsComparison {
+    let files = |stats: &CommitStats| -> BTreeMap<String, Share> {
+        stats
+            .file_breakdown
+            .iter()
+            .map(|(file, s)| {
+                let share = Share {
+                    ai_lines: s.ai_additions,
+                    total_lines: s.git_diff_added_lines,
+                };
+                (file.clone(), share)
+            })
+            .collect()
+    };
+    let tools = |stats: &CommitStats| -> BTreeMap<String, Share> {
+        stats
+            .tool_model_breakdown
+            .iter()
+            .map(|(tool, s)| {
+                let share = Share {
+                    ai_lines: s.ai_additions,
+                    total_lines: stats.git_diff_added_lines,
+                };
+                (tool.clone(), share)
+            })
+            .collect()
+    };
+
+    StatsComparison {
+        overall: ShareDelta::new(
+            "overall".to_string(),
+            Some(overall_share(baseline)),
+            Some(overall_share(current)),
+        ),
+        files: compare_maps(&files(baseline), &files(current)),
+        tools: compare_maps(&tools(baseline), &tools(current)),
+    }
+}
+
+fn format_share(share: Option<Share>) -> String {
+    match share {
+        Some(share) => format!("{:5.1}%", share.ai_pct()),
+        None => "     -".to_string(),
+    }
+}
+
+fn format_row(delta: &ShareDelta) -> String {
+    format!(
+        "  {} -> {}  {:+6.1} pts  {}",
+        format_share(delta.baseline),
+        format_share(delta.current),
+        delta.ai_pct_delta,
+        delta.key
+    )
+}
+
+pub fn write_comparison_to_terminal(comparison: &StatsComparison) -> String {
+    let overall = &comparison.overall;
+    let lines = |share: Option<Share>| share.map_or(0, |s| s.ai_lines);
+    let mut output = format!(
+        "AI share: {:.1}% -> {:.1}% ({:+.1} pts), AI lines: {} -> {}\n",
+        overall.baseline.unwrap_or_default().ai_pct(),
+        overall.current.unwrap_or_default().ai_pct(),
+        overall.ai_pct_delta,
+        lines(overall.baseline),
+        lines(overall.current),
+    );
+
+    for (title, deltas) in [
+        ("By file", &comparison.files),
+        ("By tool", &comparison.tools),
+    ] {
+        if deltas.is_empty() {
+            continue;
+        }
+        output.push_str(&format!("\n{}:\n", title));
+        for delta in deltas {
+            output.push_str(&format_row(delta));
+            output.push('\n');
+        }
+    }
+    output
+}
+
+/// Load the baseline, compare and print, as text or JSON
+pub fn print_comparison_with_baseline(
+    current: &CommitStats,
+    baseline_path: &Path,
+    json: bool,
+) -> Result<(), GitAiError> {
+    let baseline = load_baseline(baseline_path)?;
+    let comparison = compare(&baseline, current);
+    if json {
+        println!("{}", serde_json::to_string(&comparison)?);
+    } else {
+        print!("{}", write_comparison_to_terminal(&comparison));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::stats::{FileHeadlineStats, ToolModelHeadlineStats};
+
+    fn stats(ai: u32, added: u32, files: &[(&str, u32, u32)]) -> CommitStats {
+        let mut stats = CommitStats {
+            ai_additions: ai,
+            git_diff_added_lines: added,
+            ..Default::default()
+        };
+        for (file, ai_additions, git_diff_added_lines) in files {
+            stats.file_breakdown.insert(
+                file.to_string(),
+                FileHeadlineStats {
+                    ai_additions: *ai_additions,
+                    git_diff_added_lines: *git_diff_added_lines,
+                },
+            );
+        }
+        stats
+    }
+
+    #[test]
+    fn test_compare_reports() {
+        let mut baseline = stats(20, 100, &[("a.rs", 10, 50), ("b.rs", 10, 50)]);
+        baseline.tool_model_breakdown.insert(
+            "claude::sonnet".to_string(),
+            ToolModelHeadlineStats {
+                ai_additions: 20,
+                ..Default::default()
+            },
+        );
+        let current = stats(60, 100, &[("a.rs", 40, 50), ("b.rs", 10, 50)]);
+
+        let comparison = compare(&baseline, &current);
+        assert!((comparison.overall.ai_pct_delta - 40.0).abs() < 1e-9);
+
+        // Unchanged files are left out
+        assert_eq!(comparison.files.len(), 1);
+        assert_eq!(comparison.files[0].key, "a.rs");
+        assert!((comparison.files[0].ai_pct_delta - 60.0).abs() < 1e-9);
+
+        // A tool that stopped contributing drops to zero
+        assert_eq!(comparison.tools.len(), 1);
+        assert!(comparison.tools[0].current.is_none());
+        assert!((comparison.tools[0].ai_pct_delta + 20.0).abs() < 1e-9);
+
+        let text = write_comparison_to_terminal(&comparison);
+        assert!(text.starts_with("AI share: 20.0% -> 60.0% (+40.0 pts), AI lines: 20 -> 60"));
+        assert!(text.contains(" 20.0% ->  80.0%   +60.0 pts  a.rs"));
+    }
+
+    #[test]
+    fn test_parse_baseline_accepts_range_reports() {
+        let range = r#"{"authorship_stats": {}, "range_stats": {"ai_additions": 5, "git_diff_added_lines": 10}}"#;
+        let parsed = parse_baseline(range).unwrap();
+        assert_eq!(parsed.ai_additions, 5);
+        assert_eq!(parsed.git_diff_added_lines, 10);
+
+        // Reports saved before per-file numbers existed still load
+        let commit = r#"{"ai_additions": 1, "git_diff_added_lines": 4}"#;
+        assert!(parse_baseline(commit).unwrap().file_breakdown.is_empty());
+        assert!(parse_baseline("not json").is_err());
+    }
+}
@@ -0,0 +1,411 @@
+//! Attribution rolled up to functions, classes and other symbols
+//! (`stats --by-symbol`), so a commit's stats can say which functions are
+//! mostly AI-written rather than only how many lines are. Symbols are found
+//! with tree-sitter, which is only built in with the `symbols` feature.
+
+use crate::authorship::range_authorship::should_ignore_file;
+use crate::commands::blame::GitAiBlameOptions;
+use crate::commands::output::paint;
+use crate::error::GitAiError;
+use crate::git::repository::Repository;
+use crate::utils::debug_log;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Tree `git diff` compares a root commit against
+const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+/// A function, class or similar definition in a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    /// `function`, `method`, `class`, `struct`, `impl`, ...
+    pub kind: &'static str,
+    /// Qualified with the enclosing symbols, e.g. `Parser::parse`
+    pub name: String,
+    /// 1-indexed, inclusive
+    pub start_line: u32,
+    pub end_line: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SymbolStats {
+    pub file: String,
+    pub kind: String,
+    pub name: String,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub lines: u32,
+    pub ai_lines: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SymbolReport {
+    pub commit: String,
+    pub symbols: Vec<SymbolStats>,
+}
+
+/// Line counts for each of `symbols` that contains one of the `touched` lines.
+/// Enclosing symbols are counted too, so a class and its methods each get a row.
+pub fn rollup(
+    file: &str,
+    symbols: &[Symbol],
+    ai_lines: &HashSet<u32>,
+    touched: &HashSet<u32>,
+) -> Vec<SymbolStats> {
+    symbols
+        .iter()
+        .filter(|symbol| (symbol.start_line..=symbol.end_line).any(|l| touched.contains(&l)))
+        .map(|symbol| SymbolStats {
+            file: file.to_string(),
+            kind: symbol.kind.to_string(),
+            name: symbol.name.clone(),
+            start_line: symbol.start_line,
+            end_line: symbol.end_line,
+            lines: symbol.end_line - symbol.start_line + 1,
+            ai_lines: (symbol.start_line..=symbol.end_line)
+                .filter(|line| ai_lines.contains(line))
+                .count() as u32,
+        })
+        .collect()
+}
+
+/// The symbols a commit added or changed lines in, with how much of each is
+/// AI-written as of that commit
+pub fn symbol_stats(
+    repo: &Repository,
+    commit_sha: &str,
+    ignore_patterns: &[String],
+) -> Result<SymbolReport, GitAiError> {
+    if !cfg!(feature = "symbols") {
+        return Err(unavailable());
+    }
+    let commit = repo.find_commit(commit_sha.to_string())?;
+    let parent = match commit.parent(0) {
+        Ok(parent) => parent.id(),
+        Err(_) => EMPTY_TREE.to_string(),
+    };
+    let mut added = repo.diff_added_lines(&parent, commit_sha, None)?;
+    let mut paths: Vec<String> = added
+        .keys()
+        .filter(|path| language_for_path(path).is_some())
+        .filter(|path| !should_ignore_file(path, ignore_patterns))
+        .cloned()
+        .collect();
+    paths.sort();
+
+    let options = GitAiBlameOptions {
+        newest_commit: Some(commit_sha.to_string()),
+        use_prompt_hashes_as_names: true,
+        return_human_authors_as_human: true,
+        no_output: true,
+        ..Default::default()
+    };
+    let mut symbols = Vec::new();
+    for path in paths {
+        let touched: HashSet<u32> = added
+            .remove(&path)
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        let content =
+            String::from_utf8_lossy(&repo.get_file_content(&path, commit_sha)?).to_string();
+        let file_symbols = match parse_symbols(&path, &content)? {
+            Some(file_symbols) if !file_symbols.is_empty() => file_symbols,
+            _ => continue,
+        };
+        let ai_lines: HashSet<u32> = match repo.blame(&path, &options) {
+            Ok((line_authors, prompts)) => line_authors
+                .into_iter()
+                .filter(|(_, hash)| prompts.contains_key(hash))
+                .map(|(line, _)| line)
+                .collect(),
+            Err(e) => {
+                debug_log(&format!("Skipping symbol stats for {}: {}", path, e));
+                continue;
+            }
+        };
+        symbols.extend(rollup(&path, &file_symbols, &ai_lines, &touched));
+    }
+    Ok(SymbolReport {
+        commit: commit_sha.to_string(),
+        symbols,
+    })
+}
+
+pub fn write_symbol_stats_to_terminal(report: &SymbolReport) {
+    if report.symbols.is_empty() {
+        println!("No functions or classes changed in this commit");
+        return;
+    }
+    let mut current_file = None;
+    for symbol in &report.symbols {
+        if current_file != Some(&symbol.file) {
+            println!("{}", paint("\x1b[1m", &symbol.file));
+            current_file = Some(&symbol.file);
+        }
+        let percent = symbol.ai_lines * 100 / symbol.lines.max(1);
+        let share = format!("{:>3}% AI", percent);
+        println!(
+            "  {:<8}  {:<40}  {:>5}-{:<5}  {:>4}/{:<4} lines  {}",
+            symbol.kind,
+            symbol.name,
+            symbol.start_line,
+            symbol.end_line,
+            symbol.ai_lines,
+            symbol.lines,
+            if percent >= 50 {
+                paint("\x1b[35m", &share)
+            } else {
+                share
+            }
+        );
+    }
+}
+
+/// Languages symbols can be found in, by file extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Tsx,
+    Go,
+}
+
+pub fn language_for_path(path: &str) -> Option<Language> {
+    let extension = std::path::Path::new(path).extension()?.to_str()?;
+    match extension {
+        "rs" => Some(Language::Rust),
+        "py" | "pyi" => Some(Language::Python),
+        "js" | "jsx" | "mjs" | "cjs" => Some(Language::JavaScript),
+        "ts" | "mts" | "cts" => Some(Language::TypeScript),
+        "tsx" => Some(Language::Tsx),
+        "go" => Some(Language::Go),
+        _ => None,
+    }
+}
+
+/// Symbols defined in `content`, or `None` for a language tree-sitter isn't
+/// set up for
+#[cfg(feature = "symbols")]
+pub fn parse_symbols(path: &str, content: &str) -> Result<Option<Vec<Symbol>>, GitAiError> {
+    let Some(language) = language_for_path(path) else {
+        return Ok(None);
+    };
+    let grammar: tree_sitter::Language = match language {
+        Language::Rust => tree_sitter_rust::LANGUAGE.into(),
+        Language::Python => tree_sitter_python::LANGUAGE.into(),
+        Language::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+        Language::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+        Language::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+        Language::Go => tree_sitter_go::LANGUAGE.into(),
+    };
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&grammar).map_err(|e| {
+        GitAiError::Generic(format!("Failed to load {:?} grammar: {}", language, e))
+    })?;
+    let Some(tree) = parser.parse(content, None) else {
+        return Err(GitAiError::Generic(format!("Failed to parse {}", path)));
+    };
+
+    let mut symbols = Vec::new();
+    collect_symbols(
+        tree.root_node(),
+        content.as_bytes(),
+        language,
+        &mut Vec::new(),
+        &mut symbols,
+    );
+    Ok(Some(symbols))
+}
+
+#[cfg(not(feature = "symbols"))]
+pub fn parse_symbols(_path: &str, _content: &str) -> Result<Option<Vec<Symbol>>, GitAiError> {
+    Err(unavailable())
+}
+
+fn unavailable() -> GitAiError {
+    GitAiError::Generic(
+        "git-ai was built without symbol support; rebuild with `--features symbols`".to_string(),
+    )
+}
+
+#[cfg(feature = "symbols")]
+fn collect_symbols(
+    node: tree_sitter::Node,
+    source: &[u8],
+    language: Language,
+    scope: &mut Vec<(&'static str, String)>,
+    symbols: &mut Vec<Symbol>,
+) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        let Some((mut kind, name)) = symbol_at(child, source, language) else {
+            collect_symbols(child, source, language, scope, symbols);
+            continue;
+        };
+        let separator = if language == Language::Rust {
+            "::"
+        } else {
+            "."
+        };
+        let qualified = scope
+            .iter()
+            .map(|(_, name)| name)
+            .chain(std::iter::once(&name))
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(separator);
+        if kind == "function"
+            && scope
+                .last()
+                .is_some_and(|(parent, _)| matches!(*parent, "class" | "impl" | "trait"))
+        {
+            kind = "method";
+        }
+        symbols.push(Symbol {
+            kind,
+            name: qualified,
+            start_line: child.start_position().row as u32 + 1,
+            end_line: child.end_position().row as u32 + 1,
+        });
+        scope.push((kind, name));
+        collect_symbols(child, source, language, scope, symbols);
+        scope.pop();
+    }
+}
+
+/// The kind and name of the symbol `node` defines, if it's a definition
+#[cfg(feature = "symbols")]
+fn symbol_at(
+    node: tree_sitter::Node,
+    source: &[u8],
+    language: Language,
+) -> Option<(&'static str, String)> {
+    use Language::*;
+    let field = |name: &str| {
+        node.child_by_field_name(name)
+            .and_then(|field| field.utf8_text(source).ok())
+            .map(str::to_string)
+    };
+    match (language, node.kind()) {
+        (Rust, "function_item") => Some(("function", field("name")?)),
+        (Rust, "struct_item") => Some(("struct", field("name")?)),
+        (Rust, "enum_item") => Some(("enum", field("name")?)),
+        (Rust, "trait_item") => Some(("trait", field("name")?)),
+        (Rust, "mod_item") => Some(("module", field("name")?)),
+        (Rust, "impl_item") => Some(("impl", field("type")?)),
+        (Python, "function_definition") => Some(("function", field("name")?)),
+        (Python, "class_definition") => Some(("class", field("name")?)),
+        (JavaScript | TypeScript | Tsx, "function_declaration")
+        | (JavaScript | TypeScript | Tsx, "generator_function_declaration") => {
+            Some(("function", field("name")?))
+        }
+        (JavaScript | TypeScript | Tsx, "class_declaration")
+        | (TypeScript | Tsx, "abstract_class_declaration") => Some(("class", field("name")?)),
+        (JavaScript | TypeScript | Tsx, "method_definition") => Some(("method", field("name")?)),
+        (TypeScript | Tsx, "interface_declaration") => Some(("interface", field("name")?)),
+        // `const handler = () => {...}`
+        (JavaScript | TypeScript | Tsx, "variable_declarator")
+            if node.child_by_field_name("value").is_some_and(|value| {
+                matches!(value.kind(), "arrow_function" | "function_expression")
+            }) =>
+        {
+            Some(("function", field("name")?))
+        }
+        (Go, "function_declaration") => Some(("function", field("name")?)),
+        (Go, "type_spec") => Some(("type", field("name")?)),
+        // `func (t *T) Run()` is named `T.Run`
+        (Go, "method_declaration") => {
+            let receiver = field("receiver")?;
+            let receiver_type = receiver
+                .trim_matches(|c| c == '(' || c == ')')
+                .split_whitespace()
+                .last()?
+                .trim_start_matches('*');
+            let receiver_type = receiver_type.split('[').next().unwrap_or(receiver_type);
+            Some(("method", format!("{}.{}", receiver_type, field("name")?)))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rollup_counts_touched_symbols() {
+        let symbols = vec![
+            Symbol {
+                kind: "class",
+                name: "Parser".to_string(),
+                start_line: 1,
+                end_line: 10,
+            },
+            Symbol {
+                kind: "method",
+                name: "Parser.parse".to_string(),
+                start_line: 2,
+                end_line: 5,
+            },
+            Symbol {
+                kind: "method",
+                name: "Parser.reset".to_string(),
+                start_line: 7,
+                end_line: 9,
+            },
+        ];
+        let ai_lines: HashSet<u32> = [2, 3, 4, 8].into_iter().collect();
+        let touched: HashSet<u32> = [3].into_iter().collect();
+
+        let stats = rollup("parser.py", &symbols, &ai_lines, &touched);
+        let rows: Vec<(&str, u32, u32)> = stats
+            .iter()
+            .map(|s| (s.name.as_str(), s.ai_lines, s.lines))
+            .collect();
+        // `reset` wasn't touched by the commit
+        assert_eq!(rows, vec![("Parser", 4, 10), ("Parser.parse", 3, 4)]);
+    }
+
+    #[test]
+    fn test_language_for_path() {
+        assert_eq!(language_for_path("src/main.rs"), Some(Language::Rust));
+        assert_eq!(language_for_path("web/App.tsx"), Some(Language::Tsx));
+        assert_eq!(language_for_path("README.md"), None);
+        assert_eq!(language_for_path("Makefile"), None);
+    }
+
+    #[cfg(feature = "symbols")]
+    #[test]
+    fn test_parse_symbols() {
+        let rust =
+            "struct Parser;\n\nimpl Parser {\n    fn parse(&self) {\n        todo!()\n    }\n}\n";
+        let symbols = parse_symbols("lib.rs", rust).unwrap().unwrap();
+        let names: Vec<(&str, &str, u32, u32)> = symbols
+            .iter()
+            .map(|s| (s.kind, s.name.as_str(), s.start_line, s.end_line))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("struct", "Parser", 1, 1),
+                ("impl", "Parser", 3, 7),
+                ("method", "Parser::parse", 4, 6),
+            ]
+        );
+
+        let python = "class A:\n    def f(self):\n        pass\n\ndef g():\n    pass\n";
+        let symbols = parse_symbols("a.py", python).unwrap().unwrap();
+        let names: Vec<&str> = symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["A", "A.f", "g"]);
+
+        let go = "package p\n\ntype T struct{}\n\nfunc (t *T) Run() {}\n";
+        let symbols = parse_symbols("p.go", go).unwrap().unwrap();
+        let names: Vec<&str> = symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["T", "T.Run"]);
+
+        assert_eq!(parse_symbols("notes.txt", "hello").unwrap(), None);
+    }
+}
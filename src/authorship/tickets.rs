@@ -0,0 +1,100 @@
+/// Extract issue-tracker references from free text such as a commit message or branch name.
+///
+/// Recognizes JIRA-style keys (`PROJ-123`, uppercase project key) and GitHub-style
+/// issue numbers (`#456`). Results keep first-seen order and contain no duplicates.
+pub fn extract_ticket_refs(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tickets: Vec<String> = Vec::new();
+    let mut push = |ticket: String| {
+        if !tickets.contains(&ticket) {
+            tickets.push(ticket);
+        }
+    };
+
+    let is_word = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut i = 0;
+    while i < chars.len() {
+        let at_boundary = i == 0 || !is_word(chars[i - 1]);
+
+        // GitHub-style: #456
+        if chars[i] == '#' && at_boundary {
+            let digits: String = chars[i + 1..]
+                .iter()
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            let end = i + 1 + digits.len();
+            if !digits.is_empty() && (end == chars.len() || !is_word(chars[end])) {
+                push(format!("#{}", digits));
+                i = end;
+                continue;
+            }
+        }
+
+        // JIRA-style: PROJ-123 (key starts with an uppercase letter)
+        if chars[i].is_ascii_uppercase() && at_boundary {
+            let key: String = chars[i..]
+                .iter()
+                .take_while(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+                .collect();
+            let dash = i + key.len();
+            if key.len() >= 2 && dash < chars.len() && chars[dash] == '-' {
+                let digits: String = chars[dash + 1..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect();
+                let end = dash + 1 + digits.len();
+                if !digits.is_empty() && (end == chars.len() || !chars[end].is_ascii_alphanumeric())
+                {
+                    push(format!("{}-{}", key, digits));
+                    i = end;
+                    continue;
+                }
+            }
+        }
+
+        i += 1;
+    }
+
+    tickets
+}
+
+/// Compare two ticket references, ignoring case (`proj-1` matches `PROJ-1`).
+pub fn ticket_matches(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extracts_jira_and_github_refs() {
+        let refs = extract_ticket_refs("PROJ-123: fix login (closes #456, see AB2-7)");
+        assert_eq!(refs, vec!["PROJ-123", "#456", "AB2-7"]);
+    }
+
+    #[test]
+    fn test_extracts_from_branch_names() {
+        assert_eq!(
+            extract_ticket_refs("feature/PROJ-42-add-oauth"),
+            vec!["PROJ-42"]
+        );
+        assert_eq!(extract_ticket_refs("fix/#99"), vec!["#99"]);
+    }
+
+    #[test]
+    fn test_ignores_lookalikes_and_duplicates() {
+        assert!(extract_ticket_refs("utf-8 and abc#12 and X-1 and PROJ-").is_empty());
+        assert!(extract_ticket_refs("issue#4 COLOR#fff").is_empty());
+        assert_eq!(
+            extract_ticket_refs("PROJ-1 again PROJ-1 and #2 #2"),
+            vec!["PROJ-1", "#2"]
+        );
+    }
+
+    #[test]
+    fn test_ticket_matches_ignores_case() {
+        assert!(ticket_matches("proj-1", "PROJ-1"));
+        assert!(!ticket_matches("PROJ-1", "PROJ-12"));
+    }
+}
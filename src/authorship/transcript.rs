@@ -51,6 +51,14 @@ impl Message {
         }
     }
 
+    pub fn timestamp(&self) -> Option<&String> {
+        match self {
+            Message::User { timestamp, .. }
+            | Message::Assistant { timestamp, .. }
+            | Message::ToolUse { timestamp, .. } => timestamp.as_ref(),
+        }
+    }
+
     /// Check if this is a tool use message
     #[allow(dead_code)]
     pub fn is_tool_use(&self) -> bool {
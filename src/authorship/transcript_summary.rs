@@ -0,0 +1,234 @@
+//! Size cap for stored transcripts. A transcript over `max_bytes` is
+//! replaced by an extractive summary that keeps what the agent was asked,
+//! and its SHA-256 goes into the prompt's `agent_metadata` so the full text
+//! can still be checked against a copy kept elsewhere, such as `store_dir`.
+//! Off unless `max_bytes` is set, since summaries drop most of the text.
+
+use crate::authorship::transcript::{AiTranscript, Message};
+use crate::config::Config;
+use crate::utils::{debug_log, expand_home};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+
+/// Longest user message kept in a summary, in characters
+const SUMMARY_USER_MESSAGE_CHARS: usize = 2000;
+
+/// Longest assistant message kept in a summary, in characters
+const SUMMARY_ASSISTANT_MESSAGE_CHARS: usize = 300;
+
+/// `agent_metadata` keys describing the transcript a summary stands for
+pub const TRANSCRIPT_SHA256_KEY: &str = "transcript_sha256";
+pub const TRANSCRIPT_BYTES_KEY: &str = "transcript_bytes";
+
+/// The `transcript_summary` section of ~/.git-ai/config.json
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TranscriptSummaryConfig {
+    /// Transcripts bigger than this, as JSON, are summarized; 0, the default,
+    /// keeps them whole
+    pub max_bytes: u64,
+    /// Directory the full text of summarized transcripts is kept in, as
+    /// `<sha256>.json`; not kept anywhere when unset
+    pub store_dir: Option<String>,
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// Messages of `transcript` that fit in `max_bytes`, in their original
+/// order. User messages come first since they say what the agent was asked,
+/// then the agent's final answer, then the opening line of its other
+/// replies, then the names of the tools it used.
+pub fn summarize(transcript: &AiTranscript, max_bytes: usize) -> AiTranscript {
+    let last_assistant = transcript
+        .messages
+        .iter()
+        .rposition(|message| matches!(message, Message::Assistant { .. }));
+    let mut candidates: Vec<(u8, usize, Message)> = transcript
+        .messages
+        .iter()
+        .enumerate()
+        .map(|(index, message)| match message {
+            Message::User { text, timestamp } => (
+                0,
+                index,
+                Message::user(
+                    truncate(text, SUMMARY_USER_MESSAGE_CHARS),
+                    timestamp.clone(),
+                ),
+            ),
+            Message::Assistant { text, timestamp } => {
+                let (priority, text) = if Some(index) == last_assistant {
+                    (1, text.as_str())
+                } else {
+                    (
+                        2,
+                        text.lines()
+                            .find(|line| !line.trim().is_empty())
+                            .unwrap_or(""),
+                    )
+                };
+                let text = truncate(text, SUMMARY_ASSISTANT_MESSAGE_CHARS);
+                (priority, index, Message::assistant(text, timestamp.clone()))
+            }
+            Message::ToolUse {
+                name, timestamp, ..
+            } => (
+                3,
+                index,
+                Message::ToolUse {
+                    name: name.clone(),
+                    input: serde_json::Value::Null,
+                    timestamp: timestamp.clone(),
+                },
+            ),
+        })
+        .collect();
+    candidates.sort_by_key(|(priority, index, _)| (*priority, *index));
+
+    let mut size = serde_json::to_vec(&AiTranscript::new())
+        .map(|json| json.len())
+        .unwrap_or_default();
+    let mut kept: Vec<(usize, Message)> = Vec::new();
+    for (_, index, message) in candidates {
+        let message_size = serde_json::to_vec(&message)
+            .map(|json| json.len() + 1)
+            .unwrap_or(usize::MAX);
+        if size.saturating_add(message_size) > max_bytes {
+            continue;
+        }
+        size += message_size;
+        kept.push((index, message));
+    }
+    kept.sort_by_key(|(index, _)| *index);
+
+    AiTranscript {
+        messages: kept.into_iter().map(|(_, message)| message).collect(),
+    }
+}
+
+/// Summarize `transcript` if it's over the configured size, keeping its
+/// hash and size in `metadata`
+pub fn cap_transcript(
+    transcript: &mut AiTranscript,
+    metadata: &mut Option<HashMap<String, String>>,
+) {
+    cap_transcript_with(Config::get().transcript_summary(), transcript, metadata);
+}
+
+fn cap_transcript_with(
+    config: &TranscriptSummaryConfig,
+    transcript: &mut AiTranscript,
+    metadata: &mut Option<HashMap<String, String>>,
+) {
+    if config.max_bytes == 0 {
+        return;
+    }
+    let Ok(full) = serde_json::to_vec(&*transcript) else {
+        return;
+    };
+    if full.len() as u64 <= config.max_bytes {
+        return;
+    }
+
+    let sha = format!("{:x}", Sha256::digest(&full));
+    if let Some(store_dir) = &config.store_dir {
+        let store_dir = expand_home(store_dir);
+        let path = store_dir.join(format!("{}.json", sha));
+        let stored = path.exists()
+            || fs::create_dir_all(&store_dir)
+                .and_then(|_| fs::write(&path, &full))
+                .is_ok();
+        if !stored {
+            debug_log(&format!(
+                "Failed to keep the full transcript in {}",
+                path.display()
+            ));
+        }
+    }
+
+    *transcript = summarize(transcript, config.max_bytes as usize);
+    let metadata = metadata.get_or_insert_default();
+    metadata.insert(TRANSCRIPT_SHA256_KEY.to_string(), sha);
+    metadata.insert(TRANSCRIPT_BYTES_KEY.to_string(), full.len().to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn long_transcript() -> AiTranscript {
+        let mut transcript = AiTranscript::new();
+        transcript.add_message(Message::user("add retries to the client".to_string(), None));
+        for i in 0..50 {
+            transcript.add_message(Message::tool_use(
+                "read_file".to_string(),
+                serde_json::json!({"content": "x".repeat(500), "i": i}),
+            ));
+            transcript.add_message(Message::assistant(
+                format!("Step {}\n{}", i, "details ".repeat(50)),
+                None,
+            ));
+        }
+        transcript.add_message(Message::user("also log each retry".to_string(), None));
+        transcript.add_message(Message::assistant("Done.".to_string(), None));
+        transcript
+    }
+
+    #[test]
+    fn test_summarize_keeps_user_messages_first() {
+        let transcript = long_transcript();
+        let summary = summarize(&transcript, 1024);
+        assert!(serde_json::to_vec(&summary).unwrap().len() <= 1024);
+
+        let texts: Vec<&str> = summary
+            .messages
+            .iter()
+            .filter_map(|message| message.text().map(String::as_str))
+            .collect();
+        assert_eq!(texts[0], "add retries to the client");
+        assert!(texts.contains(&"also log each retry"));
+        assert_eq!(*texts.last().unwrap(), "Done.");
+        // Other replies are cut to their opening line
+        assert!(texts.contains(&"Step 0"));
+        assert!(summary.messages.iter().all(|message| match message {
+            Message::ToolUse { input, .. } => input.is_null(),
+            _ => true,
+        }));
+    }
+
+    #[test]
+    fn test_cap_transcript() {
+        let store = tempfile::tempdir().unwrap();
+        let config = TranscriptSummaryConfig {
+            max_bytes: 4096,
+            store_dir: Some(store.path().to_string_lossy().to_string()),
+        };
+
+        let mut small = AiTranscript::new();
+        small.add_message(Message::user("fix the typo".to_string(), None));
+        let mut metadata = None;
+        cap_transcript_with(&config, &mut small, &mut metadata);
+        assert_eq!(small.messages.len(), 1);
+        assert!(metadata.is_none());
+
+        let full = long_transcript();
+        let full_json = serde_json::to_vec(&full).unwrap();
+        let mut transcript = full.clone();
+        cap_transcript_with(&config, &mut transcript, &mut metadata);
+        assert!(serde_json::to_vec(&transcript).unwrap().len() <= 4096);
+
+        let metadata = metadata.unwrap();
+        let sha = &metadata[TRANSCRIPT_SHA256_KEY];
+        assert_eq!(*sha, format!("{:x}", Sha256::digest(&full_json)));
+        assert_eq!(metadata[TRANSCRIPT_BYTES_KEY], full_json.len().to_string());
+        let stored = fs::read(store.path().join(format!("{}.json", sha))).unwrap();
+        assert_eq!(stored, full_json);
+    }
+}
@@ -2,11 +2,12 @@ use crate::authorship::attribution_tracker::{
     Attribution, LineAttribution, line_attributions_to_attributions,
     attributions_to_line_attributions,
 };
-use crate::authorship::authorship_log::{LineRange, PromptRecord};
+use crate::authorship::authorship_log::{LineRange, PromptRecord, merge_agent_metadata};
 use crate::authorship::working_log::CheckpointKind;
 use crate::commands::blame::{GitAiBlameOptions, OLDEST_AI_BLAME_DATE};
 use crate::error::GitAiError;
-use crate::git::repository::Repository;
+use crate::git::repo_storage::PersistedWorkingLog;
+use crate::git::repository::{HunkSpan, Repository, WorkdirDiff, new_line_to_old_line};
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -340,8 +341,11 @@ impl VirtualAttributions {
             }
         }
 
+        // Checkpoint index and content blob behind each file's attributions so far
+        let mut latest_entries: HashMap<String, (usize, String)> = HashMap::new();
+
         // Collect attributions from all checkpoints (later checkpoints override earlier ones)
-        for checkpoint in &checkpoints {
+        for (index, checkpoint) in checkpoints.iter().enumerate() {
             // Add prompts from checkpoint
             if let Some(agent_id) = &checkpoint.agent_id {
                 let author_id =
@@ -350,7 +354,7 @@ impl VirtualAttributions {
                         &agent_id.tool,
                     );
                 // For working log checkpoints, use empty string as commit_sha since they're uncommitted
-                prompts
+                let record = prompts
                     .entry(author_id.clone())
                     .or_insert_with(BTreeMap::new)
                     .entry(String::new())
@@ -366,7 +370,17 @@ impl VirtualAttributions {
                         total_deletions: 0,
                         accepted_lines: 0,
                         overriden_lines: 0,
+                        generated: checkpoint.kind == CheckpointKind::Generated,
+                        kind: PromptRecord::kind_label(checkpoint.kind),
+                        notes: Vec::new(),
+                        tags: Vec::new(),
+                        agent_metadata: BTreeMap::new(),
                     });
+                // Later checkpoints in the session override earlier metadata
+                if let Some(metadata) = &checkpoint.agent_metadata {
+                    record.agent_metadata = merge_agent_metadata(&record.agent_metadata, metadata);
+                }
+                record.annotate(checkpoint.message.as_deref(), &checkpoint.tags);
 
                 // Track additions and deletions from checkpoint line_stats
                 *session_additions.entry(author_id.clone()).or_insert(0) +=
@@ -377,6 +391,13 @@ impl VirtualAttributions {
 
             // Collect attributions from checkpoint entries
             for entry in &checkpoint.entries {
+                // Binary and oversized files have no lines to attribute; post_commit
+                // records their whole-file author in the metadata instead
+                if entry.file_author.is_some() {
+                    file_contents.insert(entry.file.clone(), String::new());
+                    attributions.insert(entry.file.clone(), (Vec::new(), Vec::new()));
+                    continue;
+                }
                 // Get the latest file content from working directory
                 if let Ok(workdir) = repo.workdir() {
                     let abs_path = workdir.join(&entry.file);
@@ -398,6 +419,31 @@ impl VirtualAttributions {
                     line_attributions_to_attributions(&entry.line_attributions, &file_content, 0)
                 };
 
+                // A checkpoint computed before another session's checkpoint of
+                // the same file was appended only overrides what it saw
+                let char_attrs = match (checkpoint.based_on, latest_entries.get(&entry.file)) {
+                    (Some(based_on), Some((latest, blob_sha))) if *latest >= based_on => {
+                        let concurrent_ts: Vec<u128> = checkpoints[based_on..index]
+                            .iter()
+                            .filter_map(|cp| cp.attribution_ts)
+                            .collect();
+                        let concurrent_attrs = attributions
+                            .get(&entry.file)
+                            .map(|(attrs, _)| attrs.as_slice())
+                            .unwrap_or_default();
+                        merge_concurrent_entry(
+                            &working_log,
+                            &entry.blob_sha,
+                            char_attrs,
+                            blob_sha,
+                            concurrent_attrs,
+                            &concurrent_ts,
+                        )
+                    }
+                    _ => char_attrs,
+                };
+                latest_entries.insert(entry.file.clone(), (index, entry.blob_sha.clone()));
+
                 // Convert char_attrs to line_attrs for consistency
                 let file_content = file_contents.get(&entry.file).cloned().unwrap_or_default();
                 let line_attrs = attributions_to_line_attributions(&char_attrs, &file_content);
@@ -662,8 +708,9 @@ fn collect_committed_hunks(
 }
 
 /// Helper function to collect unstaged line ranges (lines in working directory but not in commit)
-/// Returns (unstaged_hunks, pure_insertion_hunks)
+/// Returns (unstaged_hunks, pure_insertion_hunks, hunk_spans)
 /// pure_insertion_hunks contains lines that were purely inserted (old_count=0), not modifications
+/// hunk_spans are the raw diff hunks, used to map working directory lines back to the commit
 fn collect_unstaged_hunks(
     repo: &Repository,
     commit_sha: &str,
@@ -672,6 +719,7 @@ fn collect_unstaged_hunks(
     (
         HashMap<String, Vec<LineRange>>,
         HashMap<String, Vec<LineRange>>,
+        HashMap<String, Vec<HunkSpan>>,
     ),
     GitAiError,
 > {
@@ -679,8 +727,11 @@ fn collect_unstaged_hunks(
     let mut pure_insertion_hunks: HashMap<String, Vec<LineRange>> = HashMap::new();
 
     // Use git diff to get added lines in working directory vs commit, with insertion tracking
-    let (added_lines, insertion_lines) =
-        repo.diff_workdir_added_lines_with_insertions(commit_sha, pathspecs)?;
+    let WorkdirDiff {
+        added_lines,
+        insertion_lines,
+        hunks,
+    } = repo.diff_workdir_added_lines_with_insertions(commit_sha, pathspecs)?;
 
     for (file_path, lines) in added_lines {
         if !lines.is_empty() {
@@ -730,7 +781,7 @@ fn collect_unstaged_hunks(
         }
     }
 
-    Ok((unstaged_hunks, pure_insertion_hunks))
+    Ok((unstaged_hunks, pure_insertion_hunks, hunks))
 }
 
 impl VirtualAttributions {
@@ -776,7 +827,7 @@ impl VirtualAttributions {
 
         // Get committed hunks (in commit coordinates) and unstaged hunks (in working directory coordinates)
         let committed_hunks = collect_committed_hunks(repo, parent_sha, commit_sha, pathspecs)?;
-        let (mut unstaged_hunks, pure_insertion_hunks) =
+        let (mut unstaged_hunks, pure_insertion_hunks, unstaged_spans) =
             collect_unstaged_hunks(repo, commit_sha, pathspecs)?;
 
         // IMPORTANT: If a line appears in both committed_hunks and unstaged_hunks, it means:
@@ -874,13 +925,13 @@ impl VirtualAttributions {
                             .push(workdir_line_num);
                         referenced_prompts.insert(line_attr.author_id.clone());
                     } else {
-                        // Convert working directory line number to commit line number
-                        // by subtracting the count of unstaged lines before this line
-                        let adjustment = unstaged_lines
-                            .iter()
-                            .filter(|&&l| l < workdir_line_num)
-                            .count() as u32;
-                        let commit_line_num = workdir_line_num - adjustment;
+                        // Convert working directory line number to commit line number,
+                        // accounting for unstaged hunks above it that add, remove or
+                        // rewrite lines (e.g. after `git add -p`)
+                        let commit_line_num = new_line_to_old_line(
+                            unstaged_spans.get(file_path).map_or(&[], Vec::as_slice),
+                            workdir_line_num,
+                        );
 
                         // Check if this commit line number is in any committed hunk
                         let is_committed = if let Some(hunks) = file_committed_hunks {
@@ -1353,6 +1404,46 @@ impl VirtualAttributions {
         }
     }
 }
+/// Carry the attributions concurrent checkpoints left for a file (recorded
+/// against the content stored as `concurrent_blob`) onto the content of a
+/// checkpoint that didn't see them, and keep the changes they introduced
+fn merge_concurrent_entry(
+    working_log: &PersistedWorkingLog,
+    stale_blob: &str,
+    stale_attrs: Vec<Attribution>,
+    concurrent_blob: &str,
+    concurrent_attrs: &[Attribution],
+    concurrent_ts: &[u128],
+) -> Vec<Attribution> {
+    use crate::authorship::attribution_tracker::{
+        AttributionTracker, merge_concurrent_attributions,
+    };
+
+    let concurrent_attrs = if concurrent_blob == stale_blob {
+        concurrent_attrs.to_vec()
+    } else {
+        let (Ok(concurrent_content), Ok(stale_content)) = (
+            working_log.get_file_version(concurrent_blob),
+            working_log.get_file_version(stale_blob),
+        ) else {
+            return stale_attrs;
+        };
+        // Text only the stale checkpoint saw gets a placeholder author at ts 0,
+        // which never counts as a concurrent change
+        match AttributionTracker::new().update_attributions(
+            &concurrent_content,
+            &stale_content,
+            concurrent_attrs,
+            "",
+            0,
+        ) {
+            Ok(attrs) => attrs,
+            Err(_) => return stale_attrs,
+        }
+    };
+    merge_concurrent_attributions(stale_attrs, &concurrent_attrs, concurrent_ts)
+}
+
 /// Merge two VirtualAttributions, favoring the primary for overlaps
 pub fn merge_attributions_favoring_first(
     primary: VirtualAttributions,
@@ -1708,4 +1799,116 @@ mod tests {
 
         assert!(!virtual_attributions.files().is_empty());
     }
+
+    #[test]
+    fn test_partial_stage_commits_only_staged_hunks() {
+        use crate::git::test_utils::TmpRepo;
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        let base: String = (b'a'..=b'j').map(|c| format!("{}\n", c as char)).collect();
+        let file = tmp_repo.write_file("notes.txt", &base, true).unwrap();
+        tmp_repo.trigger_checkpoint_with_author("test_user").unwrap();
+        tmp_repo.commit_with_message("base").unwrap();
+
+        // The agent rewrites two lines in place and appends two more
+        let edited = base.replace("b\n", "b-ai\n").replace("e\n", "e-ai\n") + "k-ai\nl-ai\n";
+        std::fs::write(tmp_repo.path().join("notes.txt"), &edited).unwrap();
+        tmp_repo.trigger_checkpoint_with_ai("Claude", None, None).unwrap();
+
+        // Checkpoints land under "initial"; post_commit looks them up by parent sha
+        let storage = &tmp_repo.gitai_repo().storage;
+        let parent = tmp_repo.get_head_commit_sha().unwrap();
+        std::fs::remove_dir_all(storage.working_logs.join(&parent)).ok();
+        std::fs::rename(
+            storage.working_logs.join("initial"),
+            storage.working_logs.join(&parent),
+        )
+        .unwrap();
+
+        // `git add -p` the appended hunk only
+        tmp_repo.stage_lines_from_file(&file, &[(11, 12)]).unwrap();
+        let log = tmp_repo.commit_staged_with_message("append").unwrap();
+
+        let committed: Vec<u32> = log
+            .attestations
+            .iter()
+            .filter(|f| f.file_path == "notes.txt")
+            .flat_map(|f| f.entries.iter().flat_map(|e| e.line_ranges.iter()))
+            .flat_map(|r| r.expand())
+            .collect();
+        assert_eq!(committed, vec![11, 12]);
+
+        // The in-place rewrites stay in the working log for the next commit
+        let head = tmp_repo.get_head_commit_sha().unwrap();
+        let initial = tmp_repo
+            .gitai_repo()
+            .storage
+            .working_log_for_base_commit(&head)
+            .read_initial_attributions();
+        let mut pending: Vec<u32> = initial.files["notes.txt"]
+            .iter()
+            .flat_map(|attr| attr.start_line..=attr.end_line)
+            .collect();
+        pending.sort_unstable();
+        assert_eq!(pending, vec![2, 5]);
+    }
+
+    #[test]
+    fn test_concurrent_sessions_keep_their_own_lines() {
+        use crate::authorship::authorship_log_serialization::generate_short_hash;
+        use crate::git::test_utils::TmpRepo;
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("app.py", "base\n", true).unwrap();
+        tmp_repo.trigger_checkpoint_with_author("test_user").unwrap();
+        let working_log = tmp_repo
+            .gitai_repo()
+            .storage
+            .working_log_for_base_commit("initial");
+
+        // Session 1 checkpoints its line...
+        std::fs::write(tmp_repo.path().join("app.py"), "base\nsession 1\n").unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("ai_session_1", None, None)
+            .unwrap();
+        let mut checkpoints = working_log.read_all_checkpoints().unwrap();
+        let session_1 = checkpoints.pop().unwrap();
+        working_log.write_all_checkpoints(&checkpoints).unwrap();
+
+        // ...while session 2, not seeing that checkpoint yet, claims both lines
+        std::fs::write(
+            tmp_repo.path().join("app.py"),
+            "base\nsession 1\nsession 2\n",
+        )
+        .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("ai_session_2", None, None)
+            .unwrap();
+        let mut checkpoints = working_log.read_all_checkpoints().unwrap();
+        checkpoints.insert(1, session_1);
+        assert_eq!(checkpoints[2].based_on, Some(1));
+        working_log.write_all_checkpoints(&checkpoints).unwrap();
+
+        let va = VirtualAttributions::from_just_working_log(
+            tmp_repo.gitai_repo().clone(),
+            "initial".to_string(),
+            None,
+        )
+        .unwrap();
+        let (_, line_attrs) = va.get_attributions("app.py").unwrap();
+        let author_of = |line: u32| {
+            line_attrs
+                .iter()
+                .find(|attr| attr.start_line <= line && line <= attr.end_line)
+                .map(|attr| attr.author_id.clone())
+        };
+        assert_eq!(
+            author_of(2),
+            Some(generate_short_hash("ai_session_1", "test_tool"))
+        );
+        assert_eq!(
+            author_of(3),
+            Some(generate_short_hash("ai_session_2", "test_tool"))
+        );
+    }
 }
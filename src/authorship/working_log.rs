@@ -19,6 +19,14 @@ pub struct WorkingLogEntry {
     pub attributions: Vec<Attribution>,
     #[serde(default)]
     pub line_attributions: Vec<LineAttribution>,
+    /// Set for binary and oversized files, which carry no character or line
+    /// attributions: the author id ("human" or a prompt hash) of the whole file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_author: Option<String>,
+    /// Lines this checkpoint added to the file. Missing in logs written before
+    /// it was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub additions: Option<u32>,
 }
 
 impl WorkingLogEntry {
@@ -34,6 +42,16 @@ impl WorkingLogEntry {
             blob_sha,
             attributions,
             line_attributions,
+            file_author: None,
+            additions: None,
+        }
+    }
+
+    /// Entry attributing the whole file to `author_id`
+    pub fn whole_file(file: String, blob_sha: String, author_id: String) -> Self {
+        Self {
+            file_author: Some(author_id),
+            ..Self::new(file, blob_sha, Vec::new(), Vec::new())
         }
     }
 }
@@ -50,7 +68,10 @@ pub enum CheckpointKind {
     Human,
     AiAgent,
     AiTab,
-    Mixed, // AI and human mixed authorship
+    Mixed,          // AI and human mixed authorship
+    Generated, // Output of a code generator (protoc, openapi-generator, ...), kept out of AI and human numbers
+    AiRefactorTool, // Mechanical AI rewrite (rename, extract, codemod) rather than a conversation
+    Imported,  // AI attribution backfilled from another tool's history
 }
 
 impl fmt::Display for CheckpointKind {
@@ -67,16 +88,49 @@ impl CheckpointKind {
             "ai_agent" => CheckpointKind::AiAgent,
             "ai_tab" => CheckpointKind::AiTab,
             "mixed" => CheckpointKind::Mixed,
+            "generated" => CheckpointKind::Generated,
+            "ai_refactor" => CheckpointKind::AiRefactorTool,
+            "imported" => CheckpointKind::Imported,
             _ => panic!("Invalid checkpoint kind: {}", s),
         }
     }
 
+    /// Like `from_str`, but `None` for unknown names instead of panicking
+    pub fn parse(s: &str) -> Option<Self> {
+        [
+            CheckpointKind::Human,
+            CheckpointKind::AiAgent,
+            CheckpointKind::AiTab,
+            CheckpointKind::Mixed,
+            CheckpointKind::Generated,
+            CheckpointKind::AiRefactorTool,
+            CheckpointKind::Imported,
+        ]
+        .into_iter()
+        .find(|kind| kind.to_str() == s)
+    }
+
+    /// Lines from this kind of checkpoint count as AI in stats
+    pub fn is_ai(&self) -> bool {
+        matches!(
+            self,
+            CheckpointKind::AiAgent
+                | CheckpointKind::AiTab
+                | CheckpointKind::Mixed
+                | CheckpointKind::AiRefactorTool
+                | CheckpointKind::Imported
+        )
+    }
+
     pub fn to_str(&self) -> String {
         match self {
             CheckpointKind::Human => "human".to_string(),
             CheckpointKind::AiAgent => "ai_agent".to_string(),
             CheckpointKind::AiTab => "ai_tab".to_string(),
             CheckpointKind::Mixed => "mixed".to_string(),
+            CheckpointKind::Generated => "generated".to_string(),
+            CheckpointKind::AiRefactorTool => "ai_refactor".to_string(),
+            CheckpointKind::Imported => "imported".to_string(),
         }
     }
 
@@ -116,6 +170,30 @@ pub struct Checkpoint {
     pub line_stats: CheckpointLineStats,
     #[serde(default)]
     pub api_version: String,
+    /// Everyone behind a human checkpoint in a pair or mob session
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub human_authors: Vec<String>,
+    /// Note given with `git-ai checkpoint --message`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Labels given with `git-ai checkpoint --tag`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Number of checkpoints in the working log when this one was computed.
+    /// Fewer than the checkpoints ahead of it means another session appended
+    /// checkpoints this one never saw.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub based_on: Option<usize>,
+    /// Timestamp (ms) stamped on the attributions this checkpoint introduced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attribution_ts: Option<u128>,
+}
+
+/// `--message` and `--tag` values for a checkpoint about to be recorded
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CheckpointAnnotation {
+    pub message: Option<String>,
+    pub tags: Vec<String>,
 }
 
 impl Checkpoint {
@@ -141,6 +219,11 @@ impl Checkpoint {
             agent_metadata: None,
             line_stats: CheckpointLineStats::default(),
             api_version: CHECKPOINT_API_VERSION.to_string(),
+            human_authors: Vec::new(),
+            message: None,
+            tags: Vec::new(),
+            based_on: None,
+            attribution_ts: None,
         }
     }
 }
@@ -112,6 +112,17 @@ pub fn get_github_ci_context() -> Result<Option<CiContext>, GitAiError> {
     }))
 }
 
+/// Read the base and head SHAs of the pull request that triggered the current workflow, if any.
+pub fn get_github_pull_request_shas() -> Option<(String, String)> {
+    let env_event_path = std::env::var("GITHUB_EVENT_PATH").ok()?;
+    let event_payload = serde_json::from_str::<GithubCiEventPayload>(
+        &std::fs::read_to_string(env_event_path).ok()?,
+    )
+    .ok()?;
+    let pull_request = event_payload.pull_request?;
+    Some((pull_request.base.sha, pull_request.head.sha))
+}
+
 /// Install or update the GitHub Actions workflow in the current repository
 /// Writes the embedded template to .github/workflows/git-ai.yaml at the repo root
 pub fn install_github_ci_workflow() -> Result<PathBuf, GitAiError> {
@@ -0,0 +1,402 @@
+use crate::commands::blame::GitAiBlameOptions;
+use crate::commands::diff::get_diff_with_line_numbers;
+use crate::config::Config;
+use crate::error::GitAiError;
+use crate::git::repository::Repository;
+use crate::policy::PolicyConfig;
+use crate::utils::debug_log;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+
+const CHECK_RUN_NAME: &str = "git-ai";
+const DEFAULT_GITHUB_API_URL: &str = "https://api.github.com";
+/// GitHub rejects Check Run updates carrying more than 50 annotations
+const MAX_ANNOTATIONS_PER_REQUEST: usize = 50;
+
+/// Added lines of a single file in the checked range, and which of them are AI-authored
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileAttribution {
+    pub path: String,
+    pub added_lines: Vec<u32>,
+    /// (line, tool) for every added line attributed to an AI agent
+    pub ai_lines: Vec<(u32, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CheckAnnotation {
+    pub path: String,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub annotation_level: &'static str,
+    pub title: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckReport {
+    pub annotations: Vec<CheckAnnotation>,
+    pub ai_lines: u32,
+    pub added_lines: u32,
+    pub violations: Vec<String>,
+}
+
+impl CheckReport {
+    pub fn conclusion(&self) -> &'static str {
+        if self.violations.is_empty() {
+            "success"
+        } else {
+            "failure"
+        }
+    }
+
+    pub fn title(&self) -> String {
+        format!(
+            "{} of {} added lines AI-authored",
+            self.ai_lines, self.added_lines
+        )
+    }
+
+    pub fn summary(&self) -> String {
+        let pct = if self.added_lines == 0 {
+            0.0
+        } else {
+            self.ai_lines as f64 / self.added_lines as f64 * 100.0
+        };
+        let mut summary = format!(
+            "**AI-authored lines:** {} of {} added lines ({:.1}%)\n",
+            self.ai_lines, self.added_lines, pct
+        );
+        if !self.violations.is_empty() {
+            summary.push_str("\n**Policy violations:**\n");
+            for violation in &self.violations {
+                summary.push_str(&format!("- {}\n", violation));
+            }
+        }
+        summary
+    }
+}
+
+/// Collapse AI-authored lines into contiguous (start, end, tool) ranges.
+fn ai_line_ranges(ai_lines: &[(u32, String)]) -> Vec<(u32, u32, String)> {
+    let mut sorted: Vec<&(u32, String)> = ai_lines.iter().collect();
+    sorted.sort_by_key(|(line, _)| *line);
+
+    let mut ranges: Vec<(u32, u32, String)> = Vec::new();
+    for (line, tool) in sorted {
+        if let Some(last) = ranges.last_mut()
+            && last.1 + 1 == *line
+            && &last.2 == tool
+        {
+            last.1 = *line;
+            continue;
+        }
+        ranges.push((*line, *line, tool.clone()));
+    }
+    ranges
+}
+
+pub fn build_check_report(files: &[FileAttribution], policy: &PolicyConfig) -> CheckReport {
+    let mut annotations = Vec::new();
+    let mut violations = Vec::new();
+    let mut ai_total = 0u32;
+    let mut added_total = 0u32;
+
+    for file in files {
+        let ai_count = file.ai_lines.len() as u32;
+        let added_count = file.added_lines.len() as u32;
+        ai_total += ai_count;
+        added_total += added_count;
+
+        if let Some(violation) = policy.check_ai_share(ai_count, added_count) {
+            let first_line = file.added_lines.iter().min().copied().unwrap_or(1);
+            annotations.push(CheckAnnotation {
+                path: file.path.clone(),
+                start_line: first_line,
+                end_line: first_line,
+                annotation_level: "failure",
+                title: format!("Policy violation: {}", violation.rule),
+                message: violation.message.clone(),
+            });
+            violations.push(format!("`{}`: {}", file.path, violation.message));
+        }
+
+        for (start, end, tool) in ai_line_ranges(&file.ai_lines) {
+            let message = if start == end {
+                format!("Line {} was written by {}", start, tool)
+            } else {
+                format!("Lines {}-{} were written by {}", start, end, tool)
+            };
+            annotations.push(CheckAnnotation {
+                path: file.path.clone(),
+                start_line: start,
+                end_line: end,
+                annotation_level: "notice",
+                title: format!("AI-authored ({})", tool),
+                message,
+            });
+        }
+    }
+
+    if let Some(violation) = policy.check_ai_share(ai_total, added_total) {
+        violations.push(format!("Overall: {}", violation.message));
+    }
+
+    CheckReport {
+        annotations,
+        ai_lines: ai_total,
+        added_lines: added_total,
+        violations,
+    }
+}
+
+/// Attribute every line added between the merge base of `base_sha`/`head_sha` and `head_sha`.
+pub fn collect_file_attributions(
+    repo: &Repository,
+    base_sha: &str,
+    head_sha: &str,
+) -> Result<Vec<FileAttribution>, GitAiError> {
+    let merge_base = repo.merge_base(base_sha.to_string(), head_sha.to_string())?;
+    let hunks = get_diff_with_line_numbers(repo, &merge_base, head_sha)?;
+
+    let mut added_by_file: BTreeMap<String, Vec<u32>> = BTreeMap::new();
+    for hunk in hunks {
+        if hunk.added_lines.is_empty() {
+            continue;
+        }
+        added_by_file
+            .entry(hunk.file_path)
+            .or_default()
+            .extend(hunk.added_lines);
+    }
+
+    let mut files = Vec::new();
+    for (path, added_lines) in added_by_file {
+        let options = GitAiBlameOptions {
+            line_ranges: contiguous_ranges(&added_lines),
+            newest_commit: Some(head_sha.to_string()),
+            use_prompt_hashes_as_names: true,
+            return_human_authors_as_human: true,
+            no_output: true,
+            ..Default::default()
+        };
+
+        let ai_lines = match repo.blame(&path, &options) {
+            Ok((line_authors, prompt_records)) => added_lines
+                .iter()
+                .filter_map(|line| {
+                    let hash = line_authors.get(line)?;
+                    let prompt = prompt_records.get(hash)?;
+                    Some((*line, prompt.agent_id.tool.clone()))
+                })
+                .collect(),
+            Err(e) => {
+                debug_log(&format!("Skipping attribution for {}: {}", path, e));
+                Vec::new()
+            }
+        };
+
+        files.push(FileAttribution {
+            path,
+            added_lines,
+            ai_lines,
+        });
+    }
+
+    Ok(files)
+}
+
+fn contiguous_ranges(lines: &[u32]) -> Vec<(u32, u32)> {
+    let mut sorted = lines.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+
+    let mut ranges: Vec<(u32, u32)> = Vec::new();
+    for line in sorted {
+        match ranges.last_mut() {
+            Some(last) if last.1 + 1 == line => last.1 = line,
+            _ => ranges.push((line, line)),
+        }
+    }
+    ranges
+}
+
+/// Create a completed Check Run for `head_sha` describing AI attribution in `base_sha..head_sha`.
+/// Annotations beyond the first 50 are appended with follow-up updates to the same run.
+pub fn run_github_check(
+    repo: &Repository,
+    github_repository: &str,
+    token: &str,
+    base_sha: &str,
+    head_sha: &str,
+) -> Result<CheckReport, GitAiError> {
+    let files = collect_file_attributions(repo, base_sha, head_sha)?;
+    let report = build_check_report(&files, Config::get().policy());
+
+    let api_url = std::env::var("GITHUB_API_URL")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| DEFAULT_GITHUB_API_URL.to_string());
+    let check_runs_url = format!(
+        "{}/repos/{}/check-runs",
+        api_url.trim_end_matches('/'),
+        github_repository
+    );
+
+    let mut batches = report.annotations.chunks(MAX_ANNOTATIONS_PER_REQUEST);
+    let first_batch = batches.next().unwrap_or(&[]);
+
+    let created = send_github_request(
+        minreq::post(&check_runs_url),
+        token,
+        &json!({
+            "name": CHECK_RUN_NAME,
+            "head_sha": head_sha,
+            "status": "completed",
+            "conclusion": report.conclusion(),
+            "output": {
+                "title": report.title(),
+                "summary": report.summary(),
+                "annotations": first_batch,
+            },
+        }),
+    )?;
+
+    let check_run_id = created
+        .get("id")
+        .and_then(|id| id.as_u64())
+        .ok_or_else(|| {
+            GitAiError::Generic("GitHub response did not include a check run id".into())
+        })?;
+
+    for batch in batches {
+        send_github_request(
+            minreq::patch(format!("{}/{}", check_runs_url, check_run_id)),
+            token,
+            &json!({
+                "output": {
+                    "title": report.title(),
+                    "summary": report.summary(),
+                    "annotations": batch,
+                },
+            }),
+        )?;
+    }
+
+    Ok(report)
+}
+
+fn send_github_request(
+    request: minreq::Request,
+    token: &str,
+    body: &Value,
+) -> Result<Value, GitAiError> {
+    let response = request
+        .with_header("Authorization", format!("Bearer {}", token))
+        .with_header("Accept", "application/vnd.github+json")
+        .with_header("X-GitHub-Api-Version", "2022-11-28")
+        .with_header(
+            "User-Agent",
+            format!("git-ai/{}", env!("CARGO_PKG_VERSION")),
+        )
+        .with_header("Content-Type", "application/json")
+        .with_body(serde_json::to_string(body)?)
+        .with_timeout(30)
+        .send()
+        .map_err(|e| GitAiError::Generic(format!("GitHub API request failed: {}", e)))?;
+
+    let text = response.as_str().unwrap_or_default().to_string();
+    if !(200..300).contains(&response.status_code) {
+        return Err(GitAiError::Generic(format!(
+            "GitHub API returned {}: {}",
+            response.status_code, text
+        )));
+    }
+
+    Ok(serde_json::from_str(&text).unwrap_or(Value::Null))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, added: &[u32], ai: &[(u32, &str)]) -> FileAttribution {
+        FileAttribution {
+            path: path.to_string(),
+            added_lines: added.to_vec(),
+            ai_lines: ai.iter().map(|(l, t)| (*l, t.to_string())).collect(),
+        }
+    }
+
+    #[test]
+    fn test_ai_lines_grouped_into_ranges_per_tool() {
+        let ranges = ai_line_ranges(&[
+            (3, "cursor".to_string()),
+            (1, "cursor".to_string()),
+            (2, "cursor".to_string()),
+            (4, "claude".to_string()),
+            (7, "claude".to_string()),
+        ]);
+        assert_eq!(
+            ranges,
+            vec![
+                (1, 3, "cursor".to_string()),
+                (4, 4, "claude".to_string()),
+                (7, 7, "claude".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_contiguous_ranges() {
+        assert_eq!(
+            contiguous_ranges(&[5, 1, 2, 3, 9, 10]),
+            vec![(1, 3), (5, 5), (9, 10)]
+        );
+        assert!(contiguous_ranges(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_report_without_policy_is_success() {
+        let files = vec![file(
+            "src/lib.rs",
+            &[1, 2, 3, 4],
+            &[(1, "cursor"), (2, "cursor")],
+        )];
+        let report = build_check_report(&files, &PolicyConfig::default());
+
+        assert_eq!(report.conclusion(), "success");
+        assert_eq!(report.ai_lines, 2);
+        assert_eq!(report.added_lines, 4);
+        assert_eq!(report.annotations.len(), 1);
+        assert_eq!(report.annotations[0].annotation_level, "notice");
+        assert_eq!(report.annotations[0].start_line, 1);
+        assert_eq!(report.annotations[0].end_line, 2);
+        assert_eq!(
+            report.annotations[0].message,
+            "Lines 1-2 were written by cursor"
+        );
+    }
+
+    #[test]
+    fn test_report_flags_policy_violations() {
+        let policy = PolicyConfig {
+            max_ai_percentage: Some(50.0),
+            ..Default::default()
+        };
+        let files = vec![
+            file("a.rs", &[10, 11], &[(10, "claude"), (11, "claude")]),
+            file("b.rs", &[1, 2, 3, 4], &[]),
+        ];
+        let report = build_check_report(&files, &policy);
+
+        assert_eq!(report.conclusion(), "failure");
+        // Only a.rs breaches the per-file limit; overall is 2/6
+        assert_eq!(report.violations.len(), 1);
+        let failure = &report.annotations[0];
+        assert_eq!(failure.annotation_level, "failure");
+        assert_eq!(failure.path, "a.rs");
+        assert_eq!(failure.start_line, 10);
+        assert!(report.summary().contains("Policy violations"));
+    }
+}
@@ -1,2 +1,3 @@
 pub mod ci_context;
 pub mod github;
+pub mod github_check;
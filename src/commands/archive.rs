@@ -0,0 +1,417 @@
+//! `git-ai archive`: `git archive` with an `ATTRIBUTION.json` manifest of
+//! per-file AI/human line counts at the archived revision, so source drops
+//! handed to customers or auditors carry their provenance with them.
+
+use crate::authorship::authorship_log_serialization::GIT_AI_VERSION;
+use crate::authorship::working_log::CheckpointKind;
+use crate::commands::blame::GitAiBlameOptions;
+use crate::commands::export::{LineCounts, file_at, tree_files};
+use crate::commands::output;
+use crate::error::{GitAiError, exit_with_error};
+use crate::git::find_repository;
+use crate::git::repository::{Repository, exec_git, exec_git_stdin_with_env};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST_NAME: &str = "ATTRIBUTION.json";
+
+#[derive(Debug, PartialEq)]
+pub struct ArchiveArgs {
+    pub rev: String,
+    /// None streams the archive to stdout, like `git archive`
+    pub output: Option<PathBuf>,
+    pub format: Option<String>,
+    pub prefix: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArchiveManifest {
+    pub version: u32,
+    pub commit: String,
+    pub generated_by: String,
+    pub totals: LineCounts,
+    pub ai_percent: u32,
+    /// AI lines by "tool/model" across the tree
+    pub agents: BTreeMap<String, u32>,
+    pub files: Vec<ManifestFile>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ManifestFile {
+    pub path: String,
+    #[serde(flatten)]
+    pub lines: LineCounts,
+    /// Binary files are listed with no lines
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub binary: bool,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub agents: BTreeMap<String, u32>,
+}
+
+/// Handle the `archive` command
+///
+/// Usage: git-ai archive [<rev>] [-o <file>] [--format <fmt>] [--prefix <dir>/]
+///
+/// Runs `git archive` for `<rev>` (default HEAD) and adds `ATTRIBUTION.json`
+/// at the archive root (under `--prefix` if given). The format follows the
+/// output file's extension unless `--format` is passed.
+pub fn handle_archive(args: &[String]) {
+    let parsed = match parse_archive_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => exit_with_error("Error", &GitAiError::InvalidArguments(e)),
+    };
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => exit_with_error("Failed to find repository", &e),
+    };
+
+    match create_archive(&repo, &parsed) {
+        Ok(manifest) => {
+            if let Some(path) = &parsed.output
+                && !output::quiet()
+            {
+                eprintln!(
+                    "Archived {} files at {} to {} ({}% AI lines)",
+                    manifest.files.len(),
+                    &manifest.commit[..manifest.commit.len().min(8)],
+                    path.display(),
+                    manifest.ai_percent
+                );
+            }
+        }
+        Err(e) => exit_with_error("Archive failed", &e),
+    }
+}
+
+pub fn parse_archive_args(args: &[String]) -> Result<ArchiveArgs, String> {
+    let mut rev = None;
+    let mut output = None;
+    let mut format = None;
+    let mut prefix = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        let (flag, inline_value) = match args[i].split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+            _ => (args[i].as_str(), None),
+        };
+        if !flag.starts_with('-') {
+            if rev.is_some() {
+                return Err(format!("Unexpected archive argument: {}", args[i]));
+            }
+            rev = Some(args[i].clone());
+            i += 1;
+            continue;
+        }
+        if !matches!(flag, "-o" | "--output" | "--format" | "--prefix") {
+            return Err(format!("Unknown archive argument: {}", args[i]));
+        }
+        let value = match inline_value {
+            Some(value) => value,
+            None => {
+                i += 1;
+                args.get(i)
+                    .cloned()
+                    .ok_or_else(|| format!("{} requires a value", flag))?
+            }
+        };
+        match flag {
+            "--format" => format = Some(value),
+            "--prefix" => prefix = Some(value),
+            _ => output = Some(PathBuf::from(value)),
+        }
+        i += 1;
+    }
+
+    Ok(ArchiveArgs {
+        rev: rev.unwrap_or_else(|| "HEAD".to_string()),
+        output,
+        format,
+        prefix,
+    })
+}
+
+/// The manifest for the tree at `sha`
+pub fn build_manifest(repo: &Repository, sha: &str) -> Result<ArchiveManifest, GitAiError> {
+    let options = GitAiBlameOptions {
+        newest_commit: Some(sha.to_string()),
+        use_prompt_hashes_as_names: true,
+        no_output: true,
+        ..Default::default()
+    };
+    let mixed = CheckpointKind::Mixed.to_str();
+
+    let mut manifest = ArchiveManifest {
+        version: 1,
+        commit: sha.to_string(),
+        generated_by: format!("git-ai {}", GIT_AI_VERSION),
+        totals: LineCounts::default(),
+        ai_percent: 0,
+        agents: BTreeMap::new(),
+        files: Vec::new(),
+    };
+    for path in exported_files(repo, sha)? {
+        let content = file_at(repo, sha, &path)?;
+        let mut file = ManifestFile {
+            path,
+            lines: LineCounts::default(),
+            binary: content.contains(&0u8),
+            agents: BTreeMap::new(),
+        };
+        let line_count = String::from_utf8_lossy(&content).lines().count();
+        if !file.binary && line_count > 0 {
+            let (line_authors, prompts) = match repo.blame(&file.path, &options) {
+                Ok(blame) => blame,
+                Err(e) => {
+                    eprintln!("Warning: no attribution for {}: {}", file.path, e);
+                    Default::default()
+                }
+            };
+            for line in 1..=line_count as u32 {
+                match line_authors.get(&line) {
+                    Some(name) if *name == mixed => file.lines.mixed += 1,
+                    Some(name) if prompts.contains_key(name) => {
+                        let agent = &prompts[name].agent_id;
+                        file.lines.ai += 1;
+                        *file
+                            .agents
+                            .entry(format!("{}/{}", agent.tool, agent.model))
+                            .or_insert(0) += 1;
+                    }
+                    _ => file.lines.human += 1,
+                }
+            }
+        }
+        manifest.totals.add(file.lines);
+        for (agent, lines) in &file.agents {
+            *manifest.agents.entry(agent.clone()).or_insert(0) += lines;
+        }
+        manifest.files.push(file);
+    }
+
+    manifest.ai_percent = manifest.totals.ai_percent();
+    Ok(manifest)
+}
+
+/// The files `git archive` writes for the tree at `sha`: those not marked
+/// `export-ignore` by the tree's own `.gitattributes`, directly or through a
+/// parent directory
+fn exported_files(repo: &Repository, sha: &str) -> Result<Vec<String>, GitAiError> {
+    let files = tree_files(repo, sha)?;
+    let mut paths: Vec<String> = Vec::new();
+    for file in &files {
+        let mut dir = Path::new(file).parent();
+        while let Some(parent) = dir.filter(|parent| !parent.as_os_str().is_empty()) {
+            paths.push(format!("{}/", parent.to_string_lossy()));
+            dir = parent.parent();
+        }
+        paths.push(file.clone());
+    }
+    paths.sort();
+    paths.dedup();
+
+    // git archive reads attributes from the archived tree, so check-attr has
+    // to see that tree's .gitattributes rather than the working copy's
+    let ai_dir = repo.storage.repo_path.join("ai");
+    fs::create_dir_all(&ai_dir)?;
+    let index = ai_dir.join(format!("archive-{}.index", std::process::id()));
+    let env = vec![(
+        "GIT_INDEX_FILE".to_string(),
+        index.to_string_lossy().to_string(),
+    )];
+    let mut read_tree = repo.global_args_for_exec();
+    read_tree.extend(["read-tree".to_string(), sha.to_string()]);
+    let mut check_attr = repo.global_args_for_exec();
+    check_attr.extend(
+        ["check-attr", "--cached", "-z", "--stdin", "export-ignore"]
+            .iter()
+            .map(|s| s.to_string()),
+    );
+    let stdin = paths.join("\0");
+    let output = exec_git_stdin_with_env(&read_tree, &env, &[])
+        .and_then(|_| exec_git_stdin_with_env(&check_attr, &env, stdin.as_bytes()));
+    let _ = fs::remove_file(&index);
+    let output = output?;
+
+    // "<path>\0<attribute>\0<value>\0" per path
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let fields: Vec<&str> = stdout.split('\0').collect();
+    let ignored: HashSet<&str> = fields
+        .chunks_exact(3)
+        .filter(|record| record[2] == "set")
+        .map(|record| record[0])
+        .collect();
+    Ok(files
+        .into_iter()
+        .filter(|file| {
+            !ignored.contains(file.as_str())
+                && !Path::new(file)
+                    .ancestors()
+                    .skip(1)
+                    .any(|dir| ignored.contains(format!("{}/", dir.to_string_lossy()).as_str()))
+        })
+        .collect())
+}
+
+/// Write the archive with its manifest, returning the manifest
+pub fn create_archive(
+    repo: &Repository,
+    args: &ArchiveArgs,
+) -> Result<ArchiveManifest, GitAiError> {
+    let commit = repo.revparse_single(&args.rev)?.peel_to_commit()?;
+    // Checked before blaming the whole tree, since the archive can't be written
+    if commit.tree()?.get_path(Path::new(MANIFEST_NAME)).is_ok() {
+        return Err(GitAiError::Generic(format!(
+            "{} already exists at the root of {}; it would clash with the manifest",
+            MANIFEST_NAME, args.rev
+        )));
+    }
+    let sha = commit.id();
+    let manifest = build_manifest(repo, &sha)?;
+
+    // --add-file names the entry after the file, so it gets a directory of its own
+    let staging = repo
+        .storage
+        .repo_path
+        .join("ai")
+        .join(format!("archive-{}", std::process::id()));
+    fs::create_dir_all(&staging)?;
+    let manifest_path = staging.join(MANIFEST_NAME);
+    let result = fs::write(&manifest_path, serde_json::to_vec_pretty(&manifest)?)
+        .map_err(GitAiError::from)
+        .and_then(|_| run_git_archive(repo, args, &sha, &manifest_path));
+    let _ = fs::remove_dir_all(&staging);
+    result.map(|_| manifest)
+}
+
+fn run_git_archive(
+    repo: &Repository,
+    args: &ArchiveArgs,
+    sha: &str,
+    manifest_path: &Path,
+) -> Result<(), GitAiError> {
+    let mut git_args = repo.global_args_for_exec();
+    git_args.push("archive".to_string());
+    if let Some(format) = &args.format {
+        git_args.push(format!("--format={}", format));
+    }
+    // --prefix only applies to --add-file entries that come after it
+    if let Some(prefix) = &args.prefix {
+        git_args.push(format!("--prefix={}", prefix));
+    }
+    git_args.push(format!("--add-file={}", manifest_path.display()));
+    if let Some(path) = &args.output {
+        // git runs in the repository root, so relative paths must be resolved here
+        let path = std::env::current_dir()?.join(path);
+        git_args.push(format!("--output={}", path.display()));
+    }
+    git_args.push(sha.to_string());
+
+    let output = exec_git(&git_args)?;
+    if args.output.is_none() {
+        std::io::stdout().write_all(&output.stdout)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_parse_archive_args() {
+        let args: Vec<String> = ["v1.0", "-o", "src.tar.gz", "--prefix=src/"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            parse_archive_args(&args).unwrap(),
+            ArchiveArgs {
+                rev: "v1.0".to_string(),
+                output: Some(PathBuf::from("src.tar.gz")),
+                format: None,
+                prefix: Some("src/".to_string()),
+            }
+        );
+        assert_eq!(parse_archive_args(&[]).unwrap().rev, "HEAD");
+        assert!(parse_archive_args(&["a".to_string(), "b".to_string()]).is_err());
+        assert!(parse_archive_args(&["--format".to_string()]).is_err());
+        assert!(parse_archive_args(&["--worktree-attributes".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_archive_includes_manifest() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("README.md", "# demo\n", true).unwrap();
+        tmp_repo.trigger_checkpoint_with_author("dev").unwrap();
+        tmp_repo.write_file("app.py", "a1\na2\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("Initial").unwrap();
+        let sha = tmp_repo.head_commit_sha().unwrap();
+
+        let manifest = build_manifest(tmp_repo.gitai_repo(), &sha).unwrap();
+        assert_eq!(manifest.files.len(), 2);
+        assert_eq!(manifest.totals.ai, 2);
+        assert_eq!(manifest.totals.human, 1);
+        assert_eq!(manifest.ai_percent, 66);
+        assert_eq!(manifest.agents.values().sum::<u32>(), 2);
+        let json = serde_json::to_value(&manifest).unwrap();
+        assert_eq!(json["files"][0]["path"], "README.md");
+        assert_eq!(json["files"][0]["human"], 1);
+        assert!(json["files"][0].get("binary").is_none());
+
+        let out = tmp_repo.path().join("out.tar");
+        let args = ArchiveArgs {
+            rev: "HEAD".to_string(),
+            output: Some(out.clone()),
+            format: None,
+            prefix: Some("demo/".to_string()),
+        };
+        create_archive(tmp_repo.gitai_repo(), &args).unwrap();
+        let tar = fs::read(&out).unwrap();
+        let contains = |needle: &str| tar.windows(needle.len()).any(|w| w == needle.as_bytes());
+        assert!(contains("demo/ATTRIBUTION.json"));
+        assert!(contains("demo/app.py"));
+        assert!(contains("\"ai_percent\": 66"));
+
+        tmp_repo.write_file(MANIFEST_NAME, "{}\n", true).unwrap();
+        tmp_repo.trigger_checkpoint_with_author("dev").unwrap();
+        tmp_repo.commit_with_message("Add a manifest").unwrap();
+        let err = create_archive(tmp_repo.gitai_repo(), &args).unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+    }
+
+    #[test]
+    fn test_manifest_skips_export_ignored_files() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo
+            .write_file(
+                ".gitattributes",
+                "tests/ export-ignore\n*.log export-ignore\n",
+                true,
+            )
+            .unwrap();
+        tmp_repo.write_file("app.py", "a1\n", true).unwrap();
+        tmp_repo
+            .write_file("tests/unit/test_app.py", "t1\n", true)
+            .unwrap();
+        tmp_repo.write_file("build.log", "l1\n", true).unwrap();
+        tmp_repo.trigger_checkpoint_with_author("dev").unwrap();
+        tmp_repo.commit_with_message("Initial").unwrap();
+        let sha = tmp_repo.head_commit_sha().unwrap();
+
+        // Only the archived tree's attributes count
+        tmp_repo.write_file(".gitattributes", "", true).unwrap();
+
+        let manifest = build_manifest(tmp_repo.gitai_repo(), &sha).unwrap();
+        let paths: Vec<&str> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec![".gitattributes", "app.py"]);
+        assert_eq!(manifest.totals.human, 3);
+    }
+}
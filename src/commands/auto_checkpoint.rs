@@ -0,0 +1,171 @@
+use crate::authorship::working_log::CheckpointKind;
+use crate::commands::checkpoint;
+use crate::config::Config;
+use crate::error::GitAiError;
+use crate::git::repository::{Repository, exec_git};
+use crate::utils::debug_log;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Whether edits have waited `human_checkpoint_after` for an agent to claim
+/// them: the oldest unrecorded edit and the last AI checkpoint (if any) are
+/// both at least that old. Nothing unrecorded means nothing is due.
+pub fn is_due(
+    since_first_edit: Option<Duration>,
+    since_ai_checkpoint: Option<Duration>,
+    after: Duration,
+) -> bool {
+    since_first_edit.is_some_and(|since| since >= after)
+        && since_ai_checkpoint.is_none_or(|since| since >= after)
+}
+
+/// Modified tracked files and untracked ones, relative to the repository root
+fn changed_files(repo: &Repository) -> Result<Vec<String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(
+        [
+            "ls-files",
+            "--modified",
+            "--others",
+            "--exclude-standard",
+            "-z",
+        ]
+        .map(String::from),
+    );
+    let output = exec_git(&args)?;
+    Ok(output
+        .stdout
+        .split(|byte| *byte == 0)
+        .filter(|path| !path.is_empty())
+        .map(|path| String::from_utf8_lossy(path).into_owned())
+        .collect())
+}
+
+/// When the oldest edit no checkpoint has recorded was made: the earliest
+/// mtime among changed files modified since the last checkpoint
+fn first_unrecorded_edit(
+    repo: &Repository,
+    last_checkpoint_at: Option<u64>,
+) -> Result<Option<SystemTime>, GitAiError> {
+    let workdir = repo.workdir()?;
+    Ok(changed_files(repo)?
+        .iter()
+        .filter_map(|path| std::fs::metadata(workdir.join(path)).ok()?.modified().ok())
+        // Checkpoint timestamps are whole seconds, so an edit in the same
+        // second counts as after it
+        .filter(|modified| {
+            last_checkpoint_at.is_none_or(|at| {
+                modified
+                    .duration_since(UNIX_EPOCH)
+                    .is_ok_and(|since_epoch| since_epoch.as_secs() >= at)
+            })
+        })
+        .min())
+}
+
+/// Called by `git-ai sync-daemon` on each round: once edits have gone
+/// `human_checkpoint_after` without an agent checkpointing, no agent is
+/// behind them, so record them as a Human checkpoint before an agent's next
+/// checkpoint folds them into its own. `git-ai watch` does the same from its
+/// file events.
+pub fn record_if_due(repo: &Repository) {
+    let Some(after) = Config::get().human_checkpoint_after() else {
+        return;
+    };
+    let working_log = repo.storage.working_log_for_base_commit("initial");
+    let checkpoints = match working_log.read_all_checkpoints() {
+        Ok(checkpoints) => checkpoints,
+        Err(e) => {
+            debug_log(&format!("Failed to read checkpoints: {}", e));
+            return;
+        }
+    };
+    let first_edit = match first_unrecorded_edit(repo, checkpoints.last().map(|c| c.timestamp)) {
+        Ok(first_edit) => first_edit,
+        Err(e) => {
+            debug_log(&format!("Failed to list unrecorded edits: {}", e));
+            return;
+        }
+    };
+    let since_ai_checkpoint = checkpoints
+        .iter()
+        .rev()
+        .find(|checkpoint| checkpoint.kind.is_ai())
+        .map(|checkpoint| {
+            let at = UNIX_EPOCH + Duration::from_secs(checkpoint.timestamp);
+            at.elapsed().unwrap_or_default()
+        });
+    let since_first_edit = first_edit.map(|at| at.elapsed().unwrap_or_default());
+    if !is_due(since_first_edit, since_ai_checkpoint, after) {
+        return;
+    }
+
+    let author = match repo.config_get_str("user.name") {
+        Ok(Some(name)) if !name.trim().is_empty() => name,
+        _ => "unknown".to_string(),
+    };
+    match checkpoint::run(
+        repo,
+        &author,
+        CheckpointKind::Human,
+        false,
+        false,
+        true,
+        None,
+        false,
+    ) {
+        Ok((entries, _, _)) => debug_log(&format!(
+            "Automatic human checkpoint recorded {} file(s)",
+            entries
+        )),
+        Err(e) => debug_log(&format!("Automatic human checkpoint failed: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_is_due() {
+        let after = Duration::from_secs(600);
+        let mins = |m: u64| Some(Duration::from_secs(m * 60));
+        assert!(is_due(mins(15), None, after));
+        assert!(!is_due(mins(5), None, after));
+        // An agent that checkpointed recently may still claim the edits
+        assert!(!is_due(mins(15), mins(2), after));
+        assert!(is_due(mins(15), mins(30), after));
+        // Nothing unrecorded
+        assert!(!is_due(None, mins(30), after));
+        assert!(!is_due(None, None, after));
+    }
+
+    #[test]
+    fn test_first_unrecorded_edit_skips_recorded_files() {
+        use filetime::{FileTime, set_file_mtime};
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "a\n", true).unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let repo = tmp_repo.gitai_repo();
+        assert_eq!(first_unrecorded_edit(repo, None).unwrap(), None);
+
+        let now = FileTime::now().unix_seconds();
+        let edited = tmp_repo.path().join("a.txt");
+        std::fs::write(&edited, "a\nb\n").unwrap();
+        set_file_mtime(&edited, FileTime::from_unix_time(now - 3600, 0)).unwrap();
+        let untracked = tmp_repo.path().join("new.txt");
+        std::fs::write(&untracked, "new\n").unwrap();
+        set_file_mtime(&untracked, FileTime::from_unix_time(now - 60, 0)).unwrap();
+
+        let oldest = |last_checkpoint_at: Option<i64>| {
+            first_unrecorded_edit(repo, last_checkpoint_at.map(|at| at as u64))
+                .unwrap()
+                .map(|at| FileTime::from_system_time(at).unix_seconds())
+        };
+        assert_eq!(oldest(None), Some(now - 3600));
+        // a.txt was edited before the last checkpoint, which recorded it
+        assert_eq!(oldest(Some(now - 600)), Some(now - 60));
+        assert_eq!(oldest(Some(now)), None);
+    }
+}
@@ -1,8 +1,11 @@
+use crate::authorship::anonymize::{anonymize_blame_hunk, anonymize_prompt, hash_identity};
 use crate::authorship::authorship_log::PromptRecord;
 use crate::authorship::authorship_log_serialization::AuthorshipLog;
 use crate::authorship::working_log::CheckpointKind;
+use crate::commands::blame_colors::LineColors;
+use crate::config::Config;
 use crate::error::GitAiError;
-use crate::git::refs::get_reference_as_authorship_log_v3;
+use crate::git::refs::{get_authorship, get_reference_as_authorship_log_v3};
 use crate::git::repository::Repository;
 use crate::git::repository::exec_git;
 #[cfg(windows)]
@@ -103,7 +106,6 @@ pub struct GitAiBlameOptions {
     pub contents_file: Option<String>,
 
     // Revision options
-    #[allow(dead_code)]
     pub reverse: Option<String>,
     pub first_parent: bool,
 
@@ -121,6 +123,12 @@ pub struct GitAiBlameOptions {
 
     // Ignore whitespace
     pub ignore_whitespace: bool,
+
+    // Read NUL-delimited paths from stdin and print a JSON line per file
+    pub batch: bool,
+
+    // Hash human identities and strip prompt text (`--anonymize`)
+    pub anonymize: bool,
 }
 
 impl Default for GitAiBlameOptions {
@@ -160,16 +168,40 @@ impl Default for GitAiBlameOptions {
             return_human_authors_as_human: false,
             no_output: false,
             ignore_whitespace: false,
+            batch: false,
+            anonymize: false,
         }
     }
 }
 
+/// Author of each line, and the prompts AI-authored lines refer to
+pub type BlameAuthors = (HashMap<u32, String>, HashMap<String, PromptRecord>);
+
+/// Authorship logs and foreign prompts looked up while blaming. One cache can
+/// be shared by every file blamed in a process, so commits touching many of
+/// them are only read once.
+#[derive(Default)]
+pub struct BlameCache {
+    commit_authorship: HashMap<String, Option<AuthorshipLog>>,
+    foreign_prompts: HashMap<String, Option<PromptRecord>>,
+}
+
 impl Repository {
     pub fn blame(
         &self,
         file_path: &str,
         options: &GitAiBlameOptions,
-    ) -> Result<(HashMap<u32, String>, HashMap<String, PromptRecord>), GitAiError> {
+    ) -> Result<BlameAuthors, GitAiError> {
+        self.blame_with_cache(file_path, options, &mut BlameCache::default())
+    }
+
+    /// [`Repository::blame`] reusing authorship looked up for earlier files
+    pub fn blame_with_cache(
+        &self,
+        file_path: &str,
+        options: &GitAiBlameOptions,
+        cache: &mut BlameCache,
+    ) -> Result<BlameAuthors, GitAiError> {
         // Use repo root for file system operations
         let repo_root = self.workdir().or_else(|e| {
             Err(GitAiError::Generic(format!(
@@ -302,25 +334,69 @@ impl Repository {
             let hunks = self.blame_hunks(&relative_file_path, *start_line, *end_line, options)?;
             all_blame_hunks.extend(hunks);
         }
+        if options.anonymize {
+            all_blame_hunks.iter_mut().for_each(anonymize_blame_hunk);
+        }
 
         // Step 2: Overlay AI authorship information
-        let (line_authors, prompt_records) =
-            overlay_ai_authorship(self, &all_blame_hunks, &relative_file_path, options)?;
+        let porcelain = options.porcelain || options.line_porcelain;
+        let colored = !options.no_output
+            && !porcelain
+            && !options.incremental
+            && !options.use_prompt_hashes_as_names
+            && io::stdout().is_terminal()
+            && crate::commands::output::color_enabled()
+            && Config::get().blame_colors().enabled;
+        let porcelain_options;
+        let overlay_options = if (porcelain || colored) && !options.no_output {
+            // Porcelain output and colors need the prompt behind each AI line
+            porcelain_options = GitAiBlameOptions {
+                use_prompt_hashes_as_names: true,
+                ..options.clone()
+            };
+            &porcelain_options
+        } else {
+            options
+        };
+        let (mut line_authors, mut prompt_records) = overlay_ai_authorship_with_cache(
+            self,
+            &all_blame_hunks,
+            &relative_file_path,
+            overlay_options,
+            cache,
+        )?;
+        let line_colors = colored.then(|| {
+            let colors =
+                LineColors::new(Config::get().blame_colors(), &line_authors, &prompt_records);
+            // Back to the names blame normally shows
+            for author in line_authors.values_mut() {
+                if let Some(prompt_record) = prompt_records.get(author.as_str()) {
+                    *author = ai_author_name(prompt_record);
+                }
+            }
+            colors
+        });
+        if options.anonymize {
+            prompt_records.values_mut().for_each(anonymize_prompt);
+        }
 
         if options.no_output {
             return Ok((line_authors, prompt_records));
         }
 
         // Output based on format
-        if options.porcelain || options.line_porcelain {
+        if porcelain {
+            let authors = (line_authors, prompt_records);
             output_porcelain_format(
+                &mut io::stdout().lock(),
                 self,
-                &line_authors,
+                &authors,
                 &relative_file_path,
                 &lines,
                 &line_ranges,
                 options,
             )?;
+            return Ok(authors);
         } else if options.incremental {
             output_incremental_format(
                 self,
@@ -338,6 +414,7 @@ impl Repository {
                 &lines,
                 &line_ranges,
                 options,
+                line_colors.as_ref(),
             )?;
         }
 
@@ -624,18 +701,34 @@ impl Repository {
     }
 }
 
-fn overlay_ai_authorship(
+pub(crate) fn overlay_ai_authorship(
     repo: &Repository,
     blame_hunks: &[BlameHunk],
     file_path: &str,
     options: &GitAiBlameOptions,
-) -> Result<(HashMap<u32, String>, HashMap<String, PromptRecord>), GitAiError> {
+) -> Result<BlameAuthors, GitAiError> {
+    overlay_ai_authorship_with_cache(
+        repo,
+        blame_hunks,
+        file_path,
+        options,
+        &mut BlameCache::default(),
+    )
+}
+
+fn overlay_ai_authorship_with_cache(
+    repo: &Repository,
+    blame_hunks: &[BlameHunk],
+    file_path: &str,
+    options: &GitAiBlameOptions,
+    cache: &mut BlameCache,
+) -> Result<BlameAuthors, GitAiError> {
     let mut line_authors: HashMap<u32, String> = HashMap::new();
     let mut prompt_records: HashMap<String, PromptRecord> = HashMap::new();
 
     // Cache for authorship logs and foreign prompts to avoid repeated lookups
-    let mut commit_authorship_cache: HashMap<String, Option<AuthorshipLog>> = HashMap::new();
-    let mut foreign_prompts_cache: HashMap<String, Option<PromptRecord>> = HashMap::new();
+    let commit_authorship_cache = &mut cache.commit_authorship;
+    let foreign_prompts_cache = &mut cache.foreign_prompts;
 
     // Process each hunk
     for hunk in blame_hunks {
@@ -661,12 +754,7 @@ fn overlay_ai_authorship(
             let final_author = if let Some(ref authorship_log) = authorship_log {
                 // Check if this line has AI authorship in the latest commit
                 if let Some((author, prompt_hash, prompt, overrode)) = authorship_log
-                    .get_line_attribution(
-                        repo,
-                        file_path,
-                        orig_line_num,
-                        &mut foreign_prompts_cache,
-                    )
+                    .get_line_attribution(repo, file_path, orig_line_num, foreign_prompts_cache)
                 {
                     let latest_is_ai = prompt.is_some();
                     // If overrode is Some, it means this line in current commit has been marked as overridden
@@ -678,8 +766,8 @@ fn overlay_ai_authorship(
                         file_path,
                         current_line_num,
                         &hunk.commit_sha,
-                        &mut commit_authorship_cache,
-                        &mut foreign_prompts_cache,
+                        commit_authorship_cache,
+                        foreign_prompts_cache,
                     )?;
 
                     // Apply the authorship rules based on overrode field:
@@ -697,15 +785,14 @@ fn overlay_ai_authorship(
                                 prompt_records.insert(prompt_hash.clone(), prompt_record.clone());
                                 prompt_hash
                             } else {
-                                let tool = prompt_record.agent_id.tool.clone();
                                 prompt_records.insert(prompt_hash, prompt_record.clone());
-                                tool
+                                ai_author_name(&prompt_record)
                             }
                         } else {
                             if options.return_human_authors_as_human {
                                 CheckpointKind::Human.to_str().to_string()
                             } else {
-                                author.username.clone()
+                                human_name(&author.username, options)
                             }
                         }
                     } else {
@@ -713,7 +800,7 @@ fn overlay_ai_authorship(
                         if options.return_human_authors_as_human {
                             CheckpointKind::Human.to_str().to_string()
                         } else {
-                            author.username.clone()
+                            human_name(&author.username, options)
                         }
                     }
                 } else {
@@ -740,6 +827,30 @@ fn overlay_ai_authorship(
     Ok((line_authors, prompt_records))
 }
 
+/// The name blame shows for a line a human wrote
+fn human_name(username: &str, options: &GitAiBlameOptions) -> String {
+    if options.anonymize {
+        hash_identity(username)
+    } else {
+        username.to_string()
+    }
+}
+
+/// The name blame shows for an AI line written under `prompt_record`
+fn ai_author_name(prompt_record: &PromptRecord) -> String {
+    // 非完整 agent 会话（如 tab 补全）在工具名后标注类型
+    let tool = match &prompt_record.kind {
+        Some(kind) => format!("{} ({})", prompt_record.agent_id.tool, kind),
+        None => prompt_record.agent_id.tool.clone(),
+    };
+    // checkpoint --tag 打的标签，如 [refactor pass]
+    if prompt_record.tags.is_empty() {
+        tool
+    } else {
+        format!("{} [{}]", tool, prompt_record.tags.join(", "))
+    }
+}
+
 /// Find the first author of a line by tracing back through git history
 /// Returns true if the first author was AI, false if Human
 fn find_first_author(
@@ -811,26 +922,50 @@ fn find_first_author(
     Ok(false)
 }
 
-fn output_porcelain_format(
+/// The git blame hunk behind each line, for the output formats
+fn hunks_by_line(
     repo: &Repository,
-    _line_authors: &HashMap<u32, String>,
     file_path: &str,
-    lines: &[&str],
     line_ranges: &[(u32, u32)],
     options: &GitAiBlameOptions,
-) -> Result<(), GitAiError> {
-    // Build a map from line number to BlameHunk for fast lookup
+) -> Result<HashMap<u32, BlameHunk>, GitAiError> {
     let mut line_to_hunk: HashMap<u32, BlameHunk> = HashMap::new();
     for (start_line, end_line) in line_ranges {
-        let h = repo.blame_hunks(file_path, *start_line, *end_line, options)?;
-        for hunk in h {
+        for mut hunk in repo.blame_hunks(file_path, *start_line, *end_line, options)? {
+            if options.anonymize {
+                anonymize_blame_hunk(&mut hunk);
+            }
             for line_num in hunk.range.0..=hunk.range.1 {
                 line_to_hunk.insert(line_num, hunk.clone());
             }
         }
     }
+    Ok(line_to_hunk)
+}
 
-    let mut last_hunk_id = None;
+/// `--porcelain` and `--line-porcelain` output, as git prints it plus
+/// `ai-tool`, `ai-model` and `prompt-hash` lines for AI-written lines. A git
+/// hunk whose lines come from different prompts is split into one group per
+/// prompt, so each header block describes every line in its group.
+fn output_porcelain_format(
+    out: &mut impl Write,
+    repo: &Repository,
+    (line_authors, prompt_records): &BlameAuthors,
+    file_path: &str,
+    lines: &[&str],
+    line_ranges: &[(u32, u32)],
+    options: &GitAiBlameOptions,
+) -> Result<(), GitAiError> {
+    let line_to_hunk = hunks_by_line(repo, file_path, line_ranges, options)?;
+
+    // The prompt behind an AI line, as (hash, record)
+    let ai_prompt = |line_num: u32| {
+        line_authors
+            .get(&line_num)
+            .and_then(|author| prompt_records.get_key_value(author))
+    };
+
+    let mut last_group_id = None;
     for (start_line, end_line) in line_ranges {
         for line_num in *start_line..=*end_line {
             let line_index = (line_num - 1) as usize;
@@ -841,83 +976,55 @@ fn output_porcelain_format(
             };
 
             if let Some(hunk) = line_to_hunk.get(&line_num) {
-                let author_name = &hunk.original_author;
                 let commit_sha = &hunk.commit_sha;
-                let author_email = &hunk.author_email;
-                let author_time = hunk.author_time;
-                let author_tz = &hunk.author_tz;
-                let committer_name = &hunk.committer;
-                let committer_email = &hunk.committer_email;
-                let committer_time = hunk.committer_time;
-                let committer_tz = &hunk.committer_tz;
-                let boundary = hunk.is_boundary;
-                let filename = file_path;
-
-                // Retrieve the commit summary directly from the commit object
-                let commit = repo.find_commit(commit_sha.clone())?;
-                let summary = commit.summary()?;
-
-                let hunk_id = (commit_sha.clone(), hunk.range.0);
-                if options.line_porcelain {
-                    if last_hunk_id.as_ref() != Some(&hunk_id) {
-                        // First line of hunk: 4-field header
-                        println!(
-                            "{} {} {} {}",
-                            commit_sha,
-                            line_num,
-                            line_num,
-                            hunk.range.1 - hunk.range.0 + 1
-                        );
-                        last_hunk_id = Some(hunk_id);
-                    } else {
-                        // Subsequent lines: 3-field header
-                        println!("{} {} {}", commit_sha, line_num, line_num);
-                    }
-                    println!("author {}", author_name);
-                    println!("author-mail <{}>", author_email);
-                    println!("author-time {}", author_time);
-                    println!("author-tz {}", author_tz);
-                    println!("committer {}", committer_name);
-                    println!("committer-mail <{}>", committer_email);
-                    println!("committer-time {}", committer_time);
-                    println!("committer-tz {}", committer_tz);
-                    println!("summary {}", summary);
-                    if boundary {
-                        println!("boundary");
+                let prompt = ai_prompt(line_num);
+                let group_id = (
+                    commit_sha.clone(),
+                    hunk.range.0,
+                    prompt.map(|(hash, _)| hash),
+                );
+                let starts_group = last_group_id.as_ref() != Some(&group_id);
+                if starts_group {
+                    let group_end = hunk.range.1.min(*end_line);
+                    let group_len = (line_num..=group_end)
+                        .take_while(|line| ai_prompt(*line).map(|(hash, _)| hash) == group_id.2)
+                        .count();
+                    writeln!(
+                        out,
+                        "{} {} {} {}",
+                        commit_sha, line_num, line_num, group_len
+                    )?;
+                } else {
+                    writeln!(out, "{} {} {}", commit_sha, line_num, line_num)?;
+                }
+
+                // git repeats the metadata on every line with --line-porcelain
+                if starts_group || options.line_porcelain {
+                    // Retrieve the commit summary directly from the commit object
+                    let commit = repo.find_commit(commit_sha.clone())?;
+                    let summary = commit.summary()?;
+
+                    writeln!(out, "author {}", hunk.original_author)?;
+                    writeln!(out, "author-mail <{}>", hunk.author_email)?;
+                    writeln!(out, "author-time {}", hunk.author_time)?;
+                    writeln!(out, "author-tz {}", hunk.author_tz)?;
+                    writeln!(out, "committer {}", hunk.committer)?;
+                    writeln!(out, "committer-mail <{}>", hunk.committer_email)?;
+                    writeln!(out, "committer-time {}", hunk.committer_time)?;
+                    writeln!(out, "committer-tz {}", hunk.committer_tz)?;
+                    writeln!(out, "summary {}", summary)?;
+                    if hunk.is_boundary {
+                        writeln!(out, "boundary")?;
                     }
-                    println!("filename {}", filename);
-                    println!("\t{}", line_content);
-                } else if options.porcelain {
-                    if last_hunk_id.as_ref() != Some(&hunk_id) {
-                        // Print full block for first line of hunk
-                        println!(
-                            "{} {} {} {}",
-                            commit_sha,
-                            line_num,
-                            line_num,
-                            hunk.range.1 - hunk.range.0 + 1
-                        );
-                        println!("author {}", author_name);
-                        println!("author-mail <{}>", author_email);
-                        println!("author-time {}", author_time);
-                        println!("author-tz {}", author_tz);
-                        println!("committer {}", committer_name);
-                        println!("committer-mail <{}>", committer_email);
-                        println!("committer-time {}", committer_time);
-                        println!("committer-tz {}", committer_tz);
-                        println!("summary {}", summary);
-                        if boundary {
-                            println!("boundary");
-                        }
-                        println!("filename {}", filename);
-                        println!("\t{}", line_content);
-                        last_hunk_id = Some(hunk_id);
-                    } else {
-                        // For subsequent lines, print only the header and content (no metadata block)
-                        println!("{} {} {}", commit_sha, line_num, line_num);
-                        println!("\t{}", line_content);
+                    if let Some((hash, record)) = prompt {
+                        writeln!(out, "ai-tool {}", record.agent_id.tool)?;
+                        writeln!(out, "ai-model {}", record.agent_id.model)?;
+                        writeln!(out, "prompt-hash {}", hash)?;
                     }
+                    writeln!(out, "filename {}", file_path)?;
                 }
+                writeln!(out, "\t{}", line_content)?;
+                last_group_id = Some(group_id);
             }
         }
     }
@@ -932,16 +1039,7 @@ fn output_incremental_format(
     line_ranges: &[(u32, u32)],
     options: &GitAiBlameOptions,
 ) -> Result<(), GitAiError> {
-    // Build a map from line number to BlameHunk for fast lookup
-    let mut line_to_hunk: HashMap<u32, BlameHunk> = HashMap::new();
-    for (start_line, end_line) in line_ranges {
-        let h = repo.blame_hunks(file_path, *start_line, *end_line, options)?;
-        for hunk in h {
-            for line_num in hunk.range.0..=hunk.range.1 {
-                line_to_hunk.insert(line_num, hunk.clone());
-            }
-        }
-    }
+    let line_to_hunk = hunks_by_line(repo, file_path, line_ranges, options)?;
 
     let mut last_hunk_id = None;
     for (start_line, end_line) in line_ranges {
@@ -1014,19 +1112,11 @@ fn output_default_format(
     lines: &[&str],
     line_ranges: &[(u32, u32)],
     options: &GitAiBlameOptions,
+    line_colors: Option<&LineColors>,
 ) -> Result<(), GitAiError> {
     let mut output = String::new();
 
-    // Build a map from line number to BlameHunk for fast lookup
-    let mut line_to_hunk: HashMap<u32, BlameHunk> = HashMap::new();
-    for (start_line, end_line) in line_ranges {
-        let h = repo.blame_hunks(file_path, *start_line, *end_line, options)?;
-        for hunk in h {
-            for line_num in hunk.range.0..=hunk.range.1 {
-                line_to_hunk.insert(line_num, hunk.clone());
-            }
-        }
-    }
+    let line_to_hunk = hunks_by_line(repo, file_path, line_ranges, options)?;
 
     // Calculate the maximum line number width for proper padding
     let max_line_num = lines.len() as u32;
@@ -1121,48 +1211,51 @@ fn output_default_format(
                 };
 
                 // Format exactly like git blame: sha (author date line) code
-                if options.suppress_author {
+                let annotation = if options.suppress_author {
                     // Suppress author format: sha line_number) code
-                    output.push_str(&format!("{} {}) {}\n", full_sha, line_num, line_content));
+                    format!("{} {})", full_sha, line_num)
                 } else {
                     // Normal format: sha (author date line) code
                     if options.show_name {
                         // Show filename format: sha filename (author date line) code
-                        output.push_str(&format!(
-                            "{} {} ({} {} {:>width$}) {}\n",
+                        format!(
+                            "{} {} ({} {} {:>width$})",
                             full_sha,
                             file_path,
                             padded_author,
                             date_str,
                             line_num,
-                            line_content,
                             width = line_num_width
-                        ));
+                        )
                     } else if options.show_number {
                         // Show number format: sha line_number (author date line) code (matches git's -n output)
-                        output.push_str(&format!(
-                            "{} {} ({} {} {:>width$}) {}\n",
+                        format!(
+                            "{} {} ({} {} {:>width$})",
                             full_sha,
                             line_num,
                             padded_author,
                             date_str,
                             line_num,
-                            line_content,
                             width = line_num_width
-                        ));
+                        )
                     } else {
                         // Normal format: sha (author date line) code
-                        output.push_str(&format!(
-                            "{} ({} {} {:>width$}) {}\n",
+                        format!(
+                            "{} ({} {} {:>width$})",
                             full_sha,
                             padded_author,
                             date_str,
                             line_num,
-                            line_content,
                             width = line_num_width
-                        ));
+                        )
                     }
-                }
+                };
+                // Only the annotation is colored, so the code stays readable
+                let annotation = match line_colors {
+                    Some(colors) => colors.paint(line_num, &annotation),
+                    None => annotation,
+                };
+                output.push_str(&format!("{} {}\n", annotation, line_content));
             } else {
                 // Fallback for lines without blame info
                 output.push_str(&format!(
@@ -1176,6 +1269,10 @@ fn output_default_format(
         }
     }
 
+    if let Some(colors) = line_colors {
+        output.push_str(&format!("\n{}\n", colors.legend()));
+    }
+
     // Print stats if requested (at the end, like git blame)
     if options.show_stats {
         // Append git-like stats lines to output string
@@ -1193,10 +1290,12 @@ fn output_default_format(
         print!("{}", output);
     } else if io::stdout().is_terminal() {
         // Try to use the specified pager
-        match std::process::Command::new(&pager)
-            .stdin(std::process::Stdio::piped())
-            .spawn()
-        {
+        let mut command = std::process::Command::new(&pager);
+        // Let less pass colors through, as git does when LESS is unset
+        if line_colors.is_some() && std::env::var_os("LESS").is_none() {
+            command.env("LESS", "FRX");
+        }
+        match command.stdin(std::process::Stdio::piped()).spawn() {
             Ok(mut child) => {
                 if let Some(stdin) = child.stdin.as_mut() {
                     if stdin.write_all(output.as_bytes()).is_ok() {
@@ -1282,6 +1381,10 @@ pub fn parse_blame_args(args: &[String]) -> Result<(String, GitAiBlameOptions),
                 options.porcelain = true;
                 i += 1;
             }
+            "--batch" => {
+                options.batch = true;
+                i += 1;
+            }
             "--line-porcelain" => {
                 options.line_porcelain = true;
                 options.porcelain = true; // Implies --porcelain
@@ -1495,6 +1598,16 @@ pub fn parse_blame_args(args: &[String]) -> Result<(String, GitAiBlameOptions),
         }
     }
 
+    // Batch mode reads its paths from stdin
+    if options.batch {
+        if file_path.is_some() {
+            return Err(GitAiError::Generic(
+                "--batch reads file paths from stdin, not from arguments".to_string(),
+            ));
+        }
+        return Ok((String::new(), options));
+    }
+
     let file_path =
         file_path.ok_or_else(|| GitAiError::Generic("No file path specified".to_string()))?;
 
@@ -1515,3 +1628,288 @@ fn parse_line_range(range_str: &str) -> Option<(u32, u32)> {
 
     None
 }
+
+/// A binary or oversized file, credited as a whole by the commit that last changed it
+#[derive(Debug, Clone)]
+pub struct WholeFileBlame {
+    pub file: String,
+    pub commit: String,
+    /// "human" or a prompt hash
+    pub author_id: String,
+    pub prompt: Option<PromptRecord>,
+}
+
+/// `None` for files blamed line by line: the commit that last changed the file
+/// didn't record a whole-file author for it
+pub fn whole_file_blame(
+    repo: &Repository,
+    file_path: &str,
+    newest_commit: Option<&str>,
+) -> Option<WholeFileBlame> {
+    let file = repo.repo_relative_path(file_path).ok()?;
+    let mut args = repo.global_args_for_exec();
+    args.extend([
+        "log".to_string(),
+        "-1".to_string(),
+        "--format=%H".to_string(),
+        newest_commit.unwrap_or("HEAD").to_string(),
+        "--".to_string(),
+        file.clone(),
+    ]);
+    let output = exec_git(&args).ok()?;
+    let commit = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if commit.is_empty() {
+        return None;
+    }
+    let log = get_authorship(repo, &commit)?;
+    let author_id = log.metadata.file_authors.get(&file)?.clone();
+    Some(WholeFileBlame {
+        prompt: log.metadata.prompts.get(&author_id).cloned(),
+        file,
+        commit,
+        author_id,
+    })
+}
+
+impl WholeFileBlame {
+    pub fn to_json(&self) -> serde_json::Value {
+        let whole_file = match &self.prompt {
+            Some(prompt) => serde_json::json!({
+                "kind": "ai",
+                "commit": self.commit,
+                "author": prompt.human_author,
+                "prompt_id": self.author_id,
+                "tool": prompt.agent_id.tool,
+                "model": prompt.agent_id.model,
+            }),
+            None => serde_json::json!({
+                "kind": "human",
+                "commit": self.commit,
+            }),
+        };
+        serde_json::json!({ "file": self.file, "whole_file": whole_file, "lines": [] })
+    }
+
+    pub fn describe(&self) -> String {
+        let author = match &self.prompt {
+            Some(prompt) if prompt.agent_id.model.is_empty() => prompt.agent_id.tool.clone(),
+            Some(prompt) => format!("{} ({})", prompt.agent_id.tool, prompt.agent_id.model),
+            None => "human".to_string(),
+        };
+        format!(
+            "{}: binary or oversized file, attributed as a whole to {} in {}",
+            self.file,
+            author,
+            &self.commit[..self.commit.len().min(8)]
+        )
+    }
+}
+
+/// `--format json` blame output. Expects authors from a blame run with
+/// `use_prompt_hashes_as_names`, so AI lines carry a key into `prompt_records`.
+pub fn blame_json(
+    file_path: &str,
+    line_authors: &HashMap<u32, String>,
+    prompt_records: &HashMap<String, PromptRecord>,
+) -> serde_json::Value {
+    let mut line_numbers: Vec<&u32> = line_authors.keys().collect();
+    line_numbers.sort();
+
+    let mixed = CheckpointKind::Mixed.to_str();
+    let lines: Vec<serde_json::Value> = line_numbers
+        .into_iter()
+        .map(|line| {
+            let author = &line_authors[line];
+            match prompt_records.get(author) {
+                Some(prompt) => serde_json::json!({
+                    "line": line,
+                    "kind": "ai",
+                    "author": prompt.human_author,
+                    "prompt_id": author,
+                    "tool": prompt.agent_id.tool,
+                    "model": prompt.agent_id.model,
+                    "checkpoint_kind": prompt.checkpoint_kind().to_str(),
+                    "notes": prompt.notes,
+                    "tags": prompt.tags,
+                }),
+                None if *author == mixed => serde_json::json!({
+                    "line": line,
+                    "kind": "mixed",
+                }),
+                None => serde_json::json!({
+                    "line": line,
+                    "kind": "human",
+                    "author": author,
+                }),
+            }
+        })
+        .collect();
+
+    serde_json::json!({ "file": file_path, "lines": lines })
+}
+
+/// `blame --batch`: blames each NUL-delimited path read from `input` and writes
+/// its JSON (as `--format json` would print it) on its own line to `out` as
+/// soon as it is ready. Authorship logs are loaded once for the whole batch,
+/// and a file that can't be blamed gets an `error` entry instead of ending it.
+pub fn blame_batch(
+    repo: &Repository,
+    options: &GitAiBlameOptions,
+    mut input: impl io::BufRead,
+    mut out: impl Write,
+) -> Result<(), GitAiError> {
+    let options = GitAiBlameOptions {
+        use_prompt_hashes_as_names: true,
+        no_output: true,
+        ..options.clone()
+    };
+    let mut cache = BlameCache::default();
+    let mut entry = Vec::new();
+    loop {
+        entry.clear();
+        if input.read_until(b'\0', &mut entry)? == 0 {
+            return Ok(());
+        }
+        if entry.last() == Some(&b'\0') {
+            entry.pop();
+        }
+        if entry.is_empty() {
+            continue;
+        }
+        let file_path = String::from_utf8_lossy(&entry).to_string();
+
+        let json = match whole_file_blame(repo, &file_path, options.newest_commit.as_deref()) {
+            Some(mut whole_file) => {
+                if options.anonymize {
+                    whole_file.prompt.iter_mut().for_each(anonymize_prompt);
+                }
+                whole_file.to_json()
+            }
+            None => match repo.blame_with_cache(&file_path, &options, &mut cache) {
+                Ok((line_authors, prompt_records)) => {
+                    blame_json(&file_path, &line_authors, &prompt_records)
+                }
+                Err(e) => serde_json::json!({ "file": file_path, "error": e.to_string() }),
+            },
+        };
+        writeln!(out, "{}", serde_json::to_string(&json)?)?;
+        out.flush()?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_blame_batch_streams_a_json_line_per_file() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo
+            .write_file("app.py", "def helper():\n    return 1\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", None, None)
+            .unwrap();
+        tmp_repo.write_file("README.md", "# demo\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("add helper").unwrap();
+
+        let input = b"app.py\0README.md\0missing.txt\0";
+        let mut out = Vec::new();
+        blame_batch(
+            tmp_repo.gitai_repo(),
+            &GitAiBlameOptions::default(),
+            &input[..],
+            &mut out,
+        )
+        .unwrap();
+
+        let results: Vec<serde_json::Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0]["file"], "app.py");
+        assert_eq!(results[0]["lines"].as_array().unwrap().len(), 2);
+        assert_eq!(results[0]["lines"][0]["kind"], "ai");
+        assert_eq!(results[1]["lines"][0]["kind"], "human");
+        assert_eq!(results[2]["file"], "missing.txt");
+        assert!(results[2]["error"].is_string());
+
+        let options = GitAiBlameOptions {
+            anonymize: true,
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        blame_batch(
+            tmp_repo.gitai_repo(),
+            &options,
+            &b"README.md\0"[..],
+            &mut out,
+        )
+        .unwrap();
+        let result: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        let author = results[1]["lines"][0]["author"].as_str().unwrap();
+        assert_eq!(result["lines"][0]["author"], hash_identity(author));
+    }
+
+    #[test]
+    fn test_porcelain_output_carries_ai_fields() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let mut file = tmp_repo.write_file("app.py", "import os\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        file.append("def helper():\n    return 1\n").unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("add helper").unwrap();
+
+        let repo = tmp_repo.gitai_repo();
+        let options = GitAiBlameOptions {
+            porcelain: true,
+            use_prompt_hashes_as_names: true,
+            no_output: true,
+            ..Default::default()
+        };
+        let authors = repo.blame("app.py", &options).unwrap();
+        let mut out = Vec::new();
+        output_porcelain_format(
+            &mut out,
+            repo,
+            &authors,
+            "app.py",
+            &["import os", "def helper():", "    return 1"],
+            &[(1, 3)],
+            &options,
+        )
+        .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+
+        // The human line and the AI lines are separate groups of one commit
+        let sha = tmp_repo.head_commit_sha().unwrap();
+        assert_eq!(lines[0], format!("{} 1 1 1", sha));
+        let ai_header = lines
+            .iter()
+            .position(|line| *line == format!("{} 2 2 2", sha))
+            .unwrap();
+        assert!(
+            !lines[..ai_header]
+                .iter()
+                .any(|line| line.starts_with("ai-"))
+        );
+        assert!(lines.contains(&"ai-tool cursor"));
+        assert!(lines.contains(&"ai-model claude-3-sonnet"));
+        let hash = authors.0[&2].clone();
+        assert!(lines.contains(&format!("prompt-hash {}", hash).as_str()));
+        // The second AI line only gets a short header
+        assert_eq!(lines[lines.len() - 2], format!("{} 3 3", sha));
+        assert_eq!(lines[lines.len() - 1], "\t    return 1");
+    }
+}
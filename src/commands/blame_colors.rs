@@ -0,0 +1,213 @@
+//! Color-coding for terminal blame output: each line's annotation takes the
+//! color of whoever wrote it (an AI tool and model, mixed, or human), with a
+//! legend of the colors used after the last line.
+
+use crate::authorship::authorship_log::PromptRecord;
+use crate::authorship::working_log::CheckpointKind;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Colors handed out to AI tools/models the config doesn't name, picked by
+/// a hash of the name so a model keeps its color between runs
+const AI_PALETTE: &[&str] = &["34", "35", "36", "94", "95", "96", "33;1", "38;5;208"];
+
+/// The `colors` section of ~/.git-ai/config.json
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlameColorsConfig {
+    /// Color `git-ai blame` when stdout is a terminal
+    pub enabled: bool,
+    pub human: String,
+    pub mixed: String,
+    /// AI colors keyed by tool ("claude") or tool and model
+    /// ("claude/claude-sonnet-4"); the tool-and-model key wins
+    pub agents: BTreeMap<String, String>,
+}
+
+impl Default for BlameColorsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            human: "green".to_string(),
+            mixed: "yellow".to_string(),
+            agents: BTreeMap::new(),
+        }
+    }
+}
+
+/// The SGR parameters for a color name ("red", "bright-blue", "gray") or for
+/// raw parameters such as "38;5;208"; None for "none", "" and unknown names
+pub fn parse_color(spec: &str) -> Option<String> {
+    let spec = spec.trim().to_lowercase();
+    if !spec.is_empty() && spec.chars().all(|c| c.is_ascii_digit() || c == ';') {
+        return Some(spec);
+    }
+    let (bright, name) = match spec.strip_prefix("bright-") {
+        Some(name) => (true, name),
+        None => (false, spec.as_str()),
+    };
+    let base = match name {
+        "black" => 0,
+        "red" => 1,
+        "green" => 2,
+        "yellow" => 3,
+        "blue" => 4,
+        "magenta" => 5,
+        "cyan" => 6,
+        "white" => 7,
+        "gray" | "grey" => return Some("90".to_string()),
+        _ => return None,
+    };
+    Some(format!("{}", if bright { 90 } else { 30 } + base))
+}
+
+/// A stable palette color for an AI tool/model the config doesn't name
+fn palette_color(key: &str) -> String {
+    // FNV-1a; std's hasher is randomly seeded per process
+    let hash = key.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    AI_PALETTE[(hash % AI_PALETTE.len() as u64) as usize].to_string()
+}
+
+/// Who a blame line belongs to, for coloring: a legend label and its SGR
+/// parameters (None leaves the line uncolored)
+fn line_owner(
+    config: &BlameColorsConfig,
+    author: Option<&String>,
+    prompt_records: &HashMap<String, PromptRecord>,
+) -> (String, Option<String>) {
+    if let Some(record) = author.and_then(|hash| prompt_records.get(hash)) {
+        let tool = &record.agent_id.tool;
+        let key = format!("{}/{}", tool, record.agent_id.model);
+        let color = match config.agents.get(&key).or_else(|| config.agents.get(tool)) {
+            Some(spec) => parse_color(spec),
+            None => Some(palette_color(&key)),
+        };
+        return (key, color);
+    }
+    let mixed = CheckpointKind::Mixed.to_str();
+    if author.is_some_and(|name| *name == mixed) {
+        (mixed, parse_color(&config.mixed))
+    } else {
+        ("human".to_string(), parse_color(&config.human))
+    }
+}
+
+/// Colors for the lines of one blame, in the order their owners first appear
+pub struct LineColors {
+    by_line: HashMap<u32, usize>,
+    legend: Vec<(String, Option<String>)>,
+}
+
+impl LineColors {
+    /// `line_authors` must name AI lines by prompt hash
+    /// (`use_prompt_hashes_as_names`)
+    pub fn new(
+        config: &BlameColorsConfig,
+        line_authors: &HashMap<u32, String>,
+        prompt_records: &HashMap<String, PromptRecord>,
+    ) -> Self {
+        let mut lines: Vec<u32> = line_authors.keys().copied().collect();
+        lines.sort_unstable();
+
+        let mut by_line = HashMap::new();
+        let mut legend: Vec<(String, Option<String>)> = Vec::new();
+        for line in lines {
+            let owner = line_owner(config, line_authors.get(&line), prompt_records);
+            let index = match legend.iter().position(|entry| entry.0 == owner.0) {
+                Some(index) => index,
+                None => {
+                    legend.push(owner);
+                    legend.len() - 1
+                }
+            };
+            by_line.insert(line, index);
+        }
+        Self { by_line, legend }
+    }
+
+    /// `text` in the color of `line`'s owner
+    pub fn paint(&self, line: u32, text: &str) -> String {
+        let color = self
+            .by_line
+            .get(&line)
+            .and_then(|index| self.legend[*index].1.as_deref());
+        match color {
+            Some(color) => format!("\x1b[{}m{}\x1b[0m", color, text),
+            None => text.to_string(),
+        }
+    }
+
+    /// One line naming each owner in its color
+    pub fn legend(&self) -> String {
+        let entries: Vec<String> = self
+            .legend
+            .iter()
+            .map(|(label, color)| match color {
+                Some(color) => format!("\x1b[{}m■ {}\x1b[0m", color, label),
+                None => format!("■ {}", label),
+            })
+            .collect();
+        entries.join("  ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompt(tool: &str, model: &str) -> PromptRecord {
+        serde_json::from_value(serde_json::json!({
+            "agent_id": {"tool": tool, "id": "s1", "model": model},
+            "human_author": null,
+            "messages": []
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("red").as_deref(), Some("31"));
+        assert_eq!(parse_color("Bright-Blue").as_deref(), Some("94"));
+        assert_eq!(parse_color("grey").as_deref(), Some("90"));
+        assert_eq!(parse_color("38;5;208").as_deref(), Some("38;5;208"));
+        assert_eq!(parse_color("none"), None);
+        assert_eq!(parse_color(""), None);
+    }
+
+    #[test]
+    fn test_line_colors_by_owner() {
+        let config: BlameColorsConfig = serde_json::from_value(serde_json::json!({
+            "human": "none",
+            "agents": {"claude": "magenta", "copilot/gpt-4o": "cyan"}
+        }))
+        .unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.mixed, "yellow");
+
+        let prompts = HashMap::from([
+            ("p1".to_string(), prompt("claude", "claude-sonnet-4")),
+            ("p2".to_string(), prompt("copilot", "gpt-4o")),
+        ]);
+        let authors = HashMap::from([
+            (1, "Alice".to_string()),
+            (2, "p1".to_string()),
+            (3, "p2".to_string()),
+            (4, "mixed".to_string()),
+            (5, "p1".to_string()),
+        ]);
+        let colors = LineColors::new(&config, &authors, &prompts);
+
+        assert_eq!(colors.paint(1, "a"), "a");
+        assert_eq!(colors.paint(2, "a"), "\x1b[35ma\x1b[0m");
+        assert_eq!(colors.paint(3, "a"), "\x1b[36ma\x1b[0m");
+        assert_eq!(colors.paint(4, "a"), "\x1b[33ma\x1b[0m");
+        assert_eq!(
+            colors.legend(),
+            "■ human  \x1b[35m■ claude/claude-sonnet-4\x1b[0m  \
+             \x1b[36m■ copilot/gpt-4o\x1b[0m  \x1b[33m■ mixed\x1b[0m"
+        );
+        assert_eq!(palette_color("codex/o3"), palette_color("codex/o3"));
+    }
+}
@@ -0,0 +1,472 @@
+//! `git-ai blame --reverse <start>..<end> <file>`: for the lines of `file` at
+//! `start`, which commit in the range deleted each one and whether that was
+//! an AI or a human edit. git's reverse blame gives the last commit each line
+//! survived to; the deleting commit is its child on the way to `end`.
+//!
+//! A line replaced by new lines was deleted by AI when any of the new lines
+//! is AI-attributed in the deleting commit's authorship log. A line removed
+//! outright was deleted by AI when a prompt in that log deleted lines and
+//! attests lines in the same file.
+
+use crate::authorship::authorship_log::PromptRecord;
+use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::authorship::working_log::CheckpointKind;
+use crate::commands::blame::GitAiBlameOptions;
+use crate::error::GitAiError;
+use crate::git::refs::get_reference_as_authorship_log_v3;
+use crate::git::repository::{Repository, exec_git};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RemovedLine {
+    /// Line number at the start of the range
+    pub line: u32,
+    pub content: String,
+    /// "ai", "mixed" or "human": who wrote the line, as of the start
+    pub written_by: String,
+    /// The last commit that still had the line
+    pub last_seen: String,
+    pub deleted_in: String,
+    /// "ai" or "human"
+    pub deleted_by: String,
+    /// Tool of the prompt that deleted the line, for AI deletions
+    pub tool: Option<String>,
+    /// Author of the deleting commit
+    pub author: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReverseBlame {
+    pub file: String,
+    pub start: String,
+    pub end: String,
+    pub surviving: u32,
+    pub removed: Vec<RemovedLine>,
+}
+
+/// One line of `git blame --porcelain --reverse`: the last commit it was
+/// seen in, its number there and at the start, and its content
+#[derive(Debug, PartialEq)]
+struct ReverseBlameLine {
+    last_seen: String,
+    seen_line: u32,
+    start_line: u32,
+    content: String,
+}
+
+fn parse_reverse_porcelain(output: &str) -> Vec<ReverseBlameLine> {
+    let mut lines = Vec::new();
+    let mut header: Option<(String, u32, u32)> = None;
+    for row in output.lines() {
+        if let Some(content) = row.strip_prefix('\t') {
+            if let Some((last_seen, seen_line, start_line)) = header.take() {
+                lines.push(ReverseBlameLine {
+                    last_seen,
+                    seen_line,
+                    start_line,
+                    content: content.to_string(),
+                });
+            }
+            continue;
+        }
+        let mut fields = row.split(' ');
+        let (Some(sha), Some(seen), Some(start)) = (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        if sha.len() < 40 || !sha.chars().all(|c| c.is_ascii_hexdigit()) {
+            continue;
+        }
+        if let (Ok(seen), Ok(start)) = (seen.parse(), start.parse()) {
+            header = Some((sha.to_string(), seen, start));
+        }
+    }
+    lines
+}
+
+/// A hunk of a `-U0` diff as ((old_start, old_len), (new_start, new_len))
+type ZeroContextHunk = ((u32, u32), (u32, u32));
+
+/// Hunks of a `-U0` diff
+fn parse_zero_context_hunks(diff: &str) -> Vec<ZeroContextHunk> {
+    let range = |spec: &str| {
+        let (start, len) = spec.split_once(',').unwrap_or((spec, "1"));
+        Some((start.parse().ok()?, len.parse().ok()?))
+    };
+    diff.lines()
+        .filter_map(|line| line.strip_prefix("@@ -"))
+        .filter_map(|line| {
+            let (old, rest) = line.split_once(" +")?;
+            let new = rest.split(' ').next()?;
+            Some((range(old)?, range(new)?))
+        })
+        .collect()
+}
+
+/// The commit that deleted lines last seen in `last_seen`: its child on the
+/// ancestry path to `end`, the oldest one if there are several
+fn deleting_commit(
+    repo: &Repository,
+    last_seen: &str,
+    end: &str,
+) -> Result<Option<String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(
+        [
+            "rev-list",
+            "--ancestry-path",
+            "--parents",
+            &format!("{}..{}", last_seen, end),
+        ]
+        .iter()
+        .map(|s| s.to_string()),
+    );
+    let output = exec_git(&args)?;
+    let stdout = String::from_utf8(output.stdout)?;
+    // rev-list lists the newest commits first
+    Ok(stdout
+        .lines()
+        .rev()
+        .filter_map(|line| {
+            let mut shas = line.split(' ');
+            let commit = shas.next()?;
+            shas.any(|parent| parent == last_seen)
+                .then(|| commit.to_string())
+        })
+        .next())
+}
+
+/// How `file` changed from `last_seen` to `deleted_in`
+fn file_hunks(
+    repo: &Repository,
+    file: &str,
+    last_seen: &str,
+    deleted_in: &str,
+) -> Result<Vec<ZeroContextHunk>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(
+        [
+            "diff",
+            "-U0",
+            "--no-color",
+            last_seen,
+            deleted_in,
+            "--",
+            file,
+        ]
+        .iter()
+        .map(|s| s.to_string()),
+    );
+    let output = exec_git(&args)?;
+    Ok(parse_zero_context_hunks(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Who deleted the line numbered `seen_line` in the commit with `log`, given
+/// how that commit changed `file` (`hunks`): "ai" with the prompt's tool, or
+/// "human"
+fn classify_deletion(
+    repo: &Repository,
+    file: &str,
+    seen_line: u32,
+    hunks: &[ZeroContextHunk],
+    log: &AuthorshipLog,
+    foreign_prompts: &mut HashMap<String, Option<PromptRecord>>,
+) -> (String, Option<String>) {
+    let human = ("human".to_string(), None);
+    let hunk = hunks
+        .iter()
+        .find(|((start, len), _)| seen_line >= *start && seen_line < start + len);
+
+    if let Some((_, (new_start, new_len))) = hunk.filter(|(_, (_, new_len))| *new_len > 0) {
+        // Replaced: AI if an agent wrote any of the replacement lines
+        for line in *new_start..new_start + new_len {
+            if let Some((_, _, Some(prompt), _)) =
+                log.get_line_attribution(repo, file, line, foreign_prompts)
+            {
+                return ("ai".to_string(), Some(prompt.agent_id.tool));
+            }
+        }
+        return human;
+    }
+
+    // Removed outright: a prompt's deletion count is for its whole session,
+    // so it only counts for a file the prompt also has lines in
+    let attests_file = |hash: &str| {
+        log.attestations.iter().any(|file_attestation| {
+            file_attestation.file_path == file
+                && file_attestation
+                    .entries
+                    .iter()
+                    .any(|entry| entry.hash == hash)
+        })
+    };
+    let deleter = log
+        .metadata
+        .prompts
+        .iter()
+        .find(|(hash, prompt)| prompt.total_deletions > 0 && attests_file(hash));
+    match deleter {
+        Some((_, prompt)) => ("ai".to_string(), Some(prompt.agent_id.tool.clone())),
+        None => human,
+    }
+}
+
+/// Reverse blame of `file` over `start..end` (resolved commit shas)
+pub fn reverse_blame(
+    repo: &Repository,
+    file: &str,
+    start: &str,
+    end: &str,
+) -> Result<ReverseBlame, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(
+        [
+            "blame",
+            "--porcelain",
+            "--reverse",
+            &format!("{}..{}", start, end),
+            "--",
+            file,
+        ]
+        .iter()
+        .map(|s| s.to_string()),
+    );
+    let output = exec_git(&args)?;
+    let blamed = parse_reverse_porcelain(&String::from_utf8_lossy(&output.stdout));
+
+    // Who wrote each line at the start of the range
+    let options = GitAiBlameOptions {
+        newest_commit: Some(start.to_string()),
+        use_prompt_hashes_as_names: true,
+        no_output: true,
+        ..Default::default()
+    };
+    let (line_authors, prompts) = if blamed.is_empty() {
+        Default::default()
+    } else {
+        repo.blame(file, &options)?
+    };
+    let mixed = CheckpointKind::Mixed.to_str();
+
+    let mut report = ReverseBlame {
+        file: file.to_string(),
+        start: start.to_string(),
+        end: end.to_string(),
+        surviving: 0,
+        removed: Vec::new(),
+    };
+    let mut deleters: HashMap<String, Option<String>> = HashMap::new();
+    let mut logs: HashMap<String, Option<AuthorshipLog>> = HashMap::new();
+    let mut hunks: HashMap<(String, String), Vec<ZeroContextHunk>> = HashMap::new();
+    let mut foreign_prompts = HashMap::new();
+    for line in blamed {
+        if line.last_seen == end {
+            report.surviving += 1;
+            continue;
+        }
+        let deleted_in = match deleters.get(&line.last_seen) {
+            Some(deleted_in) => deleted_in.clone(),
+            None => {
+                let deleted_in = deleting_commit(repo, &line.last_seen, end)?;
+                deleters.insert(line.last_seen.clone(), deleted_in.clone());
+                deleted_in
+            }
+        };
+        let Some(deleted_in) = deleted_in else {
+            report.surviving += 1;
+            continue;
+        };
+        let log = logs
+            .entry(deleted_in.clone())
+            .or_insert_with(|| get_reference_as_authorship_log_v3(repo, &deleted_in).ok());
+        let (deleted_by, tool) = match log {
+            Some(log) => {
+                let key = (line.last_seen.clone(), deleted_in.clone());
+                let hunks = match hunks.entry(key) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        entry.insert(file_hunks(repo, file, &line.last_seen, &deleted_in)?)
+                    }
+                };
+                classify_deletion(repo, file, line.seen_line, hunks, log, &mut foreign_prompts)
+            }
+            None => ("human".to_string(), None),
+        };
+
+        let written_by = match line_authors.get(&line.start_line) {
+            Some(author) if prompts.contains_key(author) => "ai",
+            Some(author) if *author == mixed => "mixed",
+            _ => "human",
+        };
+        let commit = repo.find_commit(deleted_in.clone())?;
+        let author = commit.author()?.name().unwrap_or_default().to_string();
+        report.removed.push(RemovedLine {
+            line: line.start_line,
+            content: line.content,
+            written_by: written_by.to_string(),
+            last_seen: line.last_seen,
+            deleted_in,
+            deleted_by,
+            tool,
+            author,
+        });
+    }
+    Ok(report)
+}
+
+pub fn print_reverse_blame(report: &ReverseBlame) {
+    let short = |sha: &str| sha[..sha.len().min(8)].to_string();
+    println!(
+        "{}: {} of {} lines at {} removed by {}",
+        report.file,
+        report.removed.len(),
+        report.removed.len() as u32 + report.surviving,
+        short(&report.start),
+        short(&report.end)
+    );
+    for line in &report.removed {
+        let deleted_by = match &line.tool {
+            Some(tool) => format!("AI ({})", tool),
+            None => format!("human ({})", line.author),
+        };
+        println!(
+            "{:>6} {:<6} {} {:<24} {}",
+            line.line,
+            line.written_by,
+            short(&line.deleted_in),
+            deleted_by,
+            line.content
+        );
+    }
+
+    let count = |written: &str, deleted: &str| {
+        report
+            .removed
+            .iter()
+            .filter(|line| line.written_by == written && line.deleted_by == deleted)
+            .count()
+    };
+    if !report.removed.is_empty() {
+        println!();
+        for written in ["ai", "mixed", "human"] {
+            let (by_ai, by_human) = (count(written, "ai"), count(written, "human"));
+            if by_ai + by_human > 0 {
+                println!(
+                    "{} lines removed: {} by AI, {} by humans",
+                    written, by_ai, by_human
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attribution_core::LineRange;
+    use crate::authorship::authorship_log_serialization::AttestationEntry;
+    use crate::git::refs::notes_add;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_parse_reverse_porcelain_and_hunks() {
+        let sha = "a".repeat(40);
+        let output = format!("{sha} 1 1 2\nauthor Dev\nfilename f.txt\n\tone\n{sha} 3 2\n\ttwo\n");
+        let lines = parse_reverse_porcelain(&output);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[1],
+            ReverseBlameLine {
+                last_seen: sha,
+                seen_line: 3,
+                start_line: 2,
+                content: "two".to_string(),
+            }
+        );
+
+        let diff = "@@ -2 +1,0 @@\n-gone\n@@ -5,2 +4 @@ fn main\n-a\n-b\n+c\n";
+        assert_eq!(
+            parse_zero_context_hunks(diff),
+            vec![((2, 1), (1, 0)), ((5, 2), (4, 1))]
+        );
+    }
+
+    /// Record `prompt` as the authorship of `commit`, attesting `lines` of app.py
+    fn note_ai_commit(tmp_repo: &TmpRepo, commit: &str, prompt: serde_json::Value, lines: &[u32]) {
+        let mut log = AuthorshipLog::new();
+        log.metadata.base_commit_sha = commit.to_string();
+        log.metadata
+            .prompts
+            .insert("p1".to_string(), serde_json::from_value(prompt).unwrap());
+        if !lines.is_empty() {
+            let ranges = lines.iter().map(|line| LineRange::Single(*line)).collect();
+            log.get_or_create_file("app.py")
+                .add_entry(AttestationEntry::new("p1".to_string(), ranges));
+        }
+        let note = log.serialize_to_string().unwrap();
+        notes_add(tmp_repo.gitai_repo(), commit, &note).unwrap();
+    }
+
+    #[test]
+    fn test_reverse_blame_finds_deleting_commit() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let mut file = tmp_repo
+            .write_file("app.py", "keep\nh1\nh2\nh3\n", true)
+            .unwrap();
+        tmp_repo.commit_with_message("Initial").unwrap();
+        let start = tmp_repo.head_commit_sha().unwrap();
+
+        // An agent rewrites h1, a human drops h2 while an agent session that
+        // never touched app.py deleted elsewhere, then an agent drops h3
+        file.update("keep\nagent\nh2\nh3\n").unwrap();
+        tmp_repo.commit_with_message("Agent rewrite").unwrap();
+        let rewrite = tmp_repo.head_commit_sha().unwrap();
+        let agent = |additions: u32, deletions: u32| {
+            serde_json::json!({
+                "agent_id": {"tool": "cursor", "id": "s1", "model": "m"},
+                "human_author": null,
+                "messages": [],
+                "total_additions": additions,
+                "total_deletions": deletions
+            })
+        };
+        note_ai_commit(&tmp_repo, &rewrite, agent(1, 1), &[2]);
+        file.update("keep\nagent\nh3\n").unwrap();
+        tmp_repo.commit_with_message("Human removal").unwrap();
+        let removal = tmp_repo.head_commit_sha().unwrap();
+        note_ai_commit(&tmp_repo, &removal, agent(0, 3), &[]);
+        file.update("top\nkeep\nagent\n").unwrap();
+        tmp_repo.commit_with_message("Agent removal").unwrap();
+        let end = tmp_repo.head_commit_sha().unwrap();
+        note_ai_commit(&tmp_repo, &end, agent(1, 1), &[1]);
+
+        let report = reverse_blame(tmp_repo.gitai_repo(), "app.py", &start, &end).unwrap();
+        assert_eq!(report.surviving, 1);
+        let removed: Vec<_> = report
+            .removed
+            .iter()
+            .map(|line| {
+                (
+                    line.line,
+                    line.content.as_str(),
+                    line.deleted_in.as_str(),
+                    line.deleted_by.as_str(),
+                    line.tool.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            removed,
+            vec![
+                (2, "h1", rewrite.as_str(), "ai", Some("cursor")),
+                (3, "h2", removal.as_str(), "human", None),
+                (4, "h3", end.as_str(), "ai", Some("cursor")),
+            ]
+        );
+        assert!(report.removed.iter().all(|line| line.written_by == "human"));
+    }
+}
@@ -2,22 +2,31 @@ use crate::authorship::attribution_tracker::{
     Attribution, AttributionTracker, INITIAL_ATTRIBUTION_TS, LineAttribution,
 };
 use crate::authorship::authorship_log::PromptRecord;
+use crate::authorship::commit_message::{CommitMessageDraft, save_draft};
 use crate::authorship::imara_diff_utils::{LineChangeTag, compute_line_changes};
+use crate::authorship::redaction;
+use crate::authorship::transcript::AiTranscript;
+use crate::authorship::transcript_summary;
 use crate::authorship::working_log::CheckpointKind;
-use crate::authorship::working_log::{Checkpoint, WorkingLogEntry};
+use crate::authorship::working_log::{Checkpoint, CheckpointAnnotation, WorkingLogEntry};
 use crate::commands::blame::{GitAiBlameOptions, OLDEST_AI_BLAME_DATE};
 use crate::commands::checkpoint_agent::agent_presets::AgentRunResult;
 use crate::config::Config;
 use crate::error::GitAiError;
-use crate::git::repo_storage::{PersistedWorkingLog, RepoStorage};
+use crate::event_hooks::{self, HookEvent};
+use crate::git::ai_ignore::AiIgnore;
+use crate::git::repo_storage::{FileStamp, PersistedWorkingLog, RepoStorage, blob_sha};
 use crate::git::repository::Repository;
 use crate::git::status::{EntryKind, StatusCode};
+use crate::observability::metrics;
 use crate::utils::{debug_log, normalize_to_posix};
 use futures::stream::{self, StreamExt};
+use serde::Serialize;
+use serde_json::json;
 use sha2::{Digest, Sha256};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 /// Per-file line statistics (in-memory only, not persisted)
 #[derive(Debug, Clone, Default)]
@@ -28,6 +37,45 @@ struct FileLineStats {
     deletions_sloc: u32,
 }
 
+/// Entries recorded, files looked at and checkpoints in the working log
+type CheckpointCounts = (usize, usize, usize);
+
+/// What [`run_with_options`] takes beyond who and what is being checkpointed
+#[derive(Debug, Clone, Default)]
+pub struct CheckpointOptions {
+    /// People a human checkpoint's edits are credited to (`--human-author`)
+    pub human_authors: Vec<String>,
+    /// `--message` and `--tag`
+    pub annotation: CheckpointAnnotation,
+    pub show_working_log: bool,
+    pub reset: bool,
+    pub quiet: bool,
+    pub is_pre_commit: bool,
+    /// Compute the checkpoint without recording anything
+    pub dry_run: bool,
+}
+
+/// What `checkpoint --dry-run` would have recorded
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckpointPreview {
+    /// `CheckpointKind::to_str`
+    pub kind: String,
+    /// "human" or the session's prompt hash
+    pub author_id: String,
+    pub files: Vec<FilePreview>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FilePreview {
+    pub file: String,
+    pub additions: u32,
+    pub deletions: u32,
+    /// Line ranges credited to the checkpoint's author
+    pub attributed_lines: Vec<(u32, u32)>,
+    /// Binary or oversized file, credited as a whole
+    pub whole_file: bool,
+}
+
 pub fn run(
     repo: &Repository,
     author: &str,
@@ -38,6 +86,69 @@ pub fn run(
     agent_run_result: Option<AgentRunResult>,
     is_pre_commit: bool,
 ) -> Result<(usize, usize, usize), GitAiError> {
+    run_with_options(
+        repo,
+        author,
+        kind,
+        agent_run_result,
+        CheckpointOptions {
+            show_working_log,
+            reset,
+            quiet,
+            is_pre_commit,
+            ..Default::default()
+        },
+    )
+}
+
+pub fn run_with_options(
+    repo: &Repository,
+    author: &str,
+    kind: CheckpointKind,
+    agent_run_result: Option<AgentRunResult>,
+    options: CheckpointOptions,
+) -> Result<(usize, usize, usize), GitAiError> {
+    let options = CheckpointOptions {
+        dry_run: false,
+        ..options
+    };
+    run_checkpoint(repo, author, kind, agent_run_result, options).map(|(counts, _)| counts)
+}
+
+/// Computes the checkpoint [`run`] would append without recording anything:
+/// no working log entry, reset, commit message draft, metric or hook event.
+/// `None` if nothing changed since the last checkpoint.
+pub fn dry_run(
+    repo: &Repository,
+    author: &str,
+    kind: CheckpointKind,
+    agent_run_result: Option<AgentRunResult>,
+    options: CheckpointOptions,
+) -> Result<Option<CheckpointPreview>, GitAiError> {
+    let options = CheckpointOptions {
+        quiet: true,
+        dry_run: true,
+        ..options
+    };
+    run_checkpoint(repo, author, kind, agent_run_result, options).map(|(_, preview)| preview)
+}
+
+fn run_checkpoint(
+    repo: &Repository,
+    author: &str,
+    kind: CheckpointKind,
+    mut agent_run_result: Option<AgentRunResult>,
+    options: CheckpointOptions,
+) -> Result<(CheckpointCounts, Option<CheckpointPreview>), GitAiError> {
+    let CheckpointOptions {
+        human_authors,
+        annotation,
+        show_working_log,
+        reset,
+        quiet,
+        is_pre_commit,
+        dry_run,
+    } = options;
     let checkpoint_start = Instant::now();
     debug_log(&format!("[BENCHMARK] Starting checkpoint run"));
 
@@ -81,7 +192,18 @@ pub fn run(
             && !Config::get().get_feature_flags().inter_commit_move
         {
             debug_log("No AI edits,in pre-commit checkpoint, skipping");
-            return Ok((0, 0, 0));
+            return Ok(((0, 0, 0), None));
+        }
+    }
+
+    // Scrub the transcript before any of it is written to .git/ai, and cap
+    // its size unless it's a chunk of one assembled at commit time
+    if let Some(result) = agent_run_result.as_mut()
+        && let Some(transcript) = result.transcript.as_mut()
+    {
+        redaction::redact_transcript(transcript);
+        if !result.append_transcript {
+            transcript_summary::cap_transcript(transcript, &mut result.agent_metadata);
         }
     }
 
@@ -90,6 +212,14 @@ pub fn run(
         .as_ref()
         .and_then(|result| result.dirty_files.clone())
     {
+        let dirty_files = dirty_files
+            .into_iter()
+            .filter_map(|(path, content)| {
+                repo.repo_relative_path(&path)
+                    .ok()
+                    .map(|relative| (relative, content))
+            })
+            .collect();
         working_log.set_dirty_files(Some(dirty_files));
     }
 
@@ -99,6 +229,47 @@ pub fn run(
         .unwrap_or_default()
         .as_millis();
 
+    // A drafted commit message waits for the next commit, even if no files changed
+    if !dry_run
+        && kind.is_ai()
+        && let Some(result) = agent_run_result.as_ref()
+        && let Some(message) = result
+            .commit_message
+            .as_ref()
+            .filter(|message| !message.trim().is_empty())
+    {
+        let draft = CommitMessageDraft {
+            message: message.clone(),
+            author_id: checkpoint_author_id(kind, Some(result)),
+            tool: result.agent_id.tool.clone(),
+            timestamp: (ts / 1000) as u64,
+        };
+        save_draft(&repo_storage, &draft)?;
+    }
+
+    // Transcripts sent in chunks are kept beside the working log until commit
+    if !dry_run
+        && kind.is_ai()
+        && let Some(result) = agent_run_result
+            .as_ref()
+            .filter(|result| result.append_transcript)
+    {
+        if let Some(transcript) = &result.transcript {
+            working_log.append_transcript_chunk(
+                &checkpoint_author_id(kind, Some(result)),
+                transcript.messages(),
+            )?;
+        }
+        // A call that only carries messages has no edits to record
+        if result
+            .edited_filepaths
+            .as_ref()
+            .is_none_or(|files| files.is_empty())
+        {
+            return Ok(((0, 0, 0), None));
+        }
+    }
+
     // Extract edited filepaths from agent_run_result if available
     // For human checkpoints, use will_edit_filepaths to narrow git status scope
     // For AI checkpoints, use edited_filepaths
@@ -113,44 +284,14 @@ pub fn run(
         };
 
         paths.and_then(|p| {
-            let repo_workdir = repo.workdir().ok()?;
-
             let filtered: Vec<String> = p
                 .iter()
-                .filter_map(|path| {
-                    let path_buf = if std::path::Path::new(path).is_absolute() {
-                        // Absolute path - check directly
-                        std::path::PathBuf::from(path)
-                    } else {
-                        // Relative path - join with workdir
-                        repo_workdir.join(path)
-                    };
-
-                    // Use centralized path comparison (handles Windows canonical paths correctly)
-                    if repo.path_is_in_workdir(&path_buf) {
-                        // Convert to relative path for git operations
-                        if std::path::Path::new(path).is_absolute() {
-                            if let Ok(relative) = path_buf.strip_prefix(&repo_workdir) {
-                                // Normalize path separators to forward slashes for git
-                                Some(normalize_to_posix(&relative.to_string_lossy()))
-                            } else {
-                                // Fallback: try with canonical paths
-                                let canonical_workdir = repo_workdir.canonicalize().ok()?;
-                                let canonical_path = path_buf.canonicalize().ok()?;
-                                if let Ok(relative) =
-                                    canonical_path.strip_prefix(&canonical_workdir)
-                                {
-                                    // Normalize path separators to forward slashes for git
-                                    Some(normalize_to_posix(&relative.to_string_lossy()))
-                                } else {
-                                    None
-                                }
-                            }
-                        } else {
-                            // Normalize path separators to forward slashes for git
-                            Some(normalize_to_posix(path))
+                .filter_map(|path| match repo.repo_relative_path(path) {
+                    Ok(relative) => Some(relative),
+                    Err(e) => {
+                        if !quiet {
+                            eprintln!("Error: {}, skipping it", e);
                         }
-                    } else {
                         None
                     }
                 })
@@ -186,7 +327,9 @@ pub fn run(
     let read_checkpoints_start = Instant::now();
     let mut checkpoints = if reset {
         // If reset flag is set, start with an empty working log
-        working_log.reset_working_log()?;
+        if !dry_run {
+            working_log.reset_working_log()?;
+        }
         Vec::new()
     } else {
         working_log.read_all_checkpoints()?
@@ -249,17 +392,36 @@ pub fn run(
                 debug_log("");
             }
         }
-        return Ok((0, files.len(), checkpoints.len()));
+        return Ok(((0, files.len(), checkpoints.len()), None));
     }
 
-    // Save current file states and get content hashes
+    // Binary and oversized files skip the blob store and character attribution
+    let (files, whole_files): (Vec<String>, Vec<String>) = files
+        .into_iter()
+        .partition(|file| file_mode(&working_log, file) != Some(FileMode::WholeFile));
+
+    // Read and hash current file states, then store their blobs in one batch
     let save_states_start = Instant::now();
-    let file_content_hashes = save_current_file_states(&working_log, &files)?;
+    let file_states = read_current_file_states(&working_log, &files);
+    if !dry_run {
+        let written = working_log.persist_blobs(
+            file_states
+                .values()
+                .map(|state| (state.blob_sha.as_str(), state.content.as_str())),
+        )?;
+        debug_log(&format!("[BENCHMARK] Wrote {} new blobs", written));
+    }
     debug_log(&format!(
-        "[BENCHMARK] save_current_file_states for {} files took {:?}",
+        "[BENCHMARK] Saving current file states for {} files took {:?}",
         files.len(),
         save_states_start.elapsed()
     ));
+    let mut file_content_hashes: HashMap<String, String> = file_states
+        .iter()
+        .map(|(file, state)| (file.clone(), state.blob_sha.clone()))
+        .collect();
+    let whole_file_hashes = whole_file_hashes(&working_log, &whole_files, dry_run);
+    file_content_hashes.extend(whole_file_hashes.clone());
 
     // Order file hashes by key and create a hash of the ordered hashes
     let hash_compute_start = Instant::now();
@@ -282,16 +444,22 @@ pub fn run(
 
     // Get checkpoint entries using unified function that handles both initial and subsequent checkpoints
     let entries_start = Instant::now();
-    let (entries, file_stats) = smol::block_on(get_checkpoint_entries(
+    let (mut entries, file_stats) = smol::block_on(get_checkpoint_entries(
         kind,
         repo,
         &working_log,
         &files,
-        &file_content_hashes,
+        file_states,
         &checkpoints,
         agent_run_result.as_ref(),
         ts,
     ))?;
+    entries.extend(get_whole_file_entries(
+        repo,
+        &whole_file_hashes,
+        &checkpoints,
+        &checkpoint_author_id(kind, agent_run_result.as_ref()),
+    ));
     debug_log(&format!(
         "[BENCHMARK] get_checkpoint_entries generated {} entries, took {:?}",
         entries.len(),
@@ -310,12 +478,24 @@ pub fn run(
 
         // Aggregate line stats from in-memory stats (computed during entry creation)
         checkpoint.line_stats = compute_line_stats(&file_stats)?;
+        if kind == CheckpointKind::Human {
+            checkpoint.human_authors = human_authors;
+        }
+        checkpoint.message = annotation.message;
+        checkpoint.tags = annotation.tags;
+        checkpoint.based_on = Some(checkpoints.len());
+        checkpoint.attribution_ts = Some(ts);
 
         // Set transcript and agent_id if provided and not a human checkpoint
         if kind != CheckpointKind::Human
             && let Some(agent_run) = &agent_run_result
         {
-            checkpoint.transcript = Some(agent_run.transcript.clone().unwrap_or_default());
+            // Chunked transcripts are assembled from the chunk file at commit time
+            checkpoint.transcript = if agent_run.append_transcript {
+                Some(AiTranscript::new())
+            } else {
+                Some(agent_run.transcript.clone().unwrap_or_default())
+            };
             checkpoint.agent_id = Some(agent_run.agent_id.clone());
             checkpoint.agent_metadata = agent_run.agent_metadata.clone();
         }
@@ -324,6 +504,46 @@ pub fn run(
             checkpoint_create_start.elapsed()
         ));
 
+        if dry_run {
+            let author_id = checkpoint_author_id(kind, agent_run_result.as_ref());
+            let file_previews = checkpoint
+                .entries
+                .iter()
+                .enumerate()
+                .map(|(i, entry)| {
+                    let (additions, deletions) = file_stats
+                        .get(i)
+                        .map(|stats| (stats.additions, stats.deletions))
+                        .unwrap_or_default();
+                    FilePreview {
+                        file: entry.file.clone(),
+                        additions,
+                        deletions,
+                        attributed_lines: entry
+                            .line_attributions
+                            .iter()
+                            .filter(|attr| attr.author_id == author_id)
+                            .map(|attr| (attr.start_line, attr.end_line))
+                            .collect(),
+                        whole_file: entry.file_author.is_some(),
+                    }
+                })
+                .collect();
+            let counts = (
+                entries.len(),
+                files.len() + whole_files.len(),
+                checkpoints.len(),
+            );
+            return Ok((
+                counts,
+                Some(CheckpointPreview {
+                    kind: kind.to_str(),
+                    author_id,
+                    files: file_previews,
+                }),
+            ));
+        }
+
         // Append checkpoint to the working log
         let append_start = Instant::now();
         working_log.append_checkpoint(&checkpoint)?;
@@ -331,6 +551,23 @@ pub fn run(
             "[BENCHMARK] Appending checkpoint to working log took {:?}",
             append_start.elapsed()
         ));
+        metrics::record_checkpoint(
+            &repo_storage.metrics,
+            &kind.to_str(),
+            checkpoint.line_stats.additions,
+        );
+        event_hooks::emit(
+            HookEvent::Checkpoint,
+            repo,
+            json!({
+                "kind": kind.to_str(),
+                "author": checkpoint.author,
+                "agent": checkpoint.agent_id,
+                "files": checkpoint.entries.iter().map(|e| &e.file).collect::<Vec<_>>(),
+                "additions": checkpoint.line_stats.additions,
+                "deletions": checkpoint.line_stats.deletions,
+            }),
+        );
         checkpoints.push(checkpoint);
     }
 
@@ -358,7 +595,7 @@ pub fn run(
         // Only count files that actually have checkpoint entries to avoid confusion.
         // Files that were previously checkpointed but have no new changes won't have entries.
         let files_with_entries = entries.len();
-        let total_uncommitted_files = files.len();
+        let total_uncommitted_files = files.len() + whole_files.len();
 
         if files_with_entries == total_uncommitted_files {
             // All files with changes got entries
@@ -388,7 +625,14 @@ pub fn run(
         "[BENCHMARK] Total checkpoint run took {:?}",
         checkpoint_start.elapsed()
     ));
-    Ok((entries.len(), files.len(), checkpoints.len()))
+    Ok((
+        (
+            entries.len(),
+            files.len() + whole_files.len(),
+            checkpoints.len(),
+        ),
+        None,
+    ))
 }
 
 // Gets tracked changes AND
@@ -436,13 +680,13 @@ fn get_status_of_files(
             let is_deleted =
                 entry.staged == StatusCode::Deleted || entry.unstaged == StatusCode::Deleted;
 
-            let is_text = if is_deleted {
+            let is_tracked = if is_deleted {
                 is_text_file_in_head(repo, &entry.path)
             } else {
-                is_text_file(working_log, &entry.path)
+                file_mode(working_log, &entry.path).is_some()
             };
 
-            if is_text {
+            if is_tracked {
                 files.push(entry.path.clone());
             }
         }
@@ -468,7 +712,7 @@ fn get_all_tracked_files(
     for file in working_log.read_initial_attributions().files.keys() {
         // Normalize path separators to forward slashes
         let normalized_path = normalize_to_posix(file);
-        if is_text_file(working_log, &normalized_path) {
+        if file_mode(working_log, &normalized_path).is_some() {
             files.insert(normalized_path);
         }
     }
@@ -484,8 +728,8 @@ fn get_all_tracked_files(
                 // Normalize path separators to forward slashes
                 let normalized_path = normalize_to_posix(&entry.file);
                 if !files.contains(&normalized_path) {
-                    // Check if it's a text file before adding
-                    if is_text_file(working_log, &normalized_path) {
+                    // Check it's still a regular file before adding
+                    if file_mode(working_log, &normalized_path).is_some() {
                         files.insert(normalized_path);
                     }
                 }
@@ -499,7 +743,11 @@ fn get_all_tracked_files(
 
     let has_ai_checkpoints = if let Ok(working_log_data) = working_log.read_all_checkpoints() {
         working_log_data.iter().any(|checkpoint| {
-            checkpoint.kind == CheckpointKind::AiAgent || checkpoint.kind == CheckpointKind::AiTab
+            checkpoint.kind == CheckpointKind::AiAgent
+                || checkpoint.kind == CheckpointKind::AiTab
+                || checkpoint.kind == CheckpointKind::AiRefactorTool
+                || checkpoint.kind == CheckpointKind::Imported
+                || checkpoint.kind == CheckpointKind::Generated
         })
     } else {
         false
@@ -523,115 +771,91 @@ fn get_all_tracked_files(
             let normalized_path = normalize_to_posix(file_path);
             // Only add if not already in the files list
             if !results_for_tracked_files.contains(&normalized_path) {
-                // Check if it's a text file before adding
-                if is_text_file(working_log, &normalized_path) {
+                // Check it's a regular file before adding
+                if file_mode(working_log, &normalized_path).is_some() {
                     results_for_tracked_files.push(normalized_path);
                 }
             }
         }
     }
 
+    // Paths in .gitaiignore are never attributed, whoever reported them
+    let ai_ignore = AiIgnore::load(&working_log.repo_workdir);
+    if !ai_ignore.is_empty() {
+        results_for_tracked_files = ai_ignore.filter(results_for_tracked_files);
+    }
+
     Ok(results_for_tracked_files)
 }
 
-fn save_current_file_states(
+/// A file as it is now, read once and shared by hashing, the blob store and
+/// the diff against its previous version
+struct FileState {
+    blob_sha: String,
+    content: String,
+}
+
+/// Read and hash `files` in parallel. Nothing is written here, so the blobs
+/// can be stored in one batch afterwards.
+fn read_current_file_states(
     working_log: &PersistedWorkingLog,
     files: &[String],
-) -> Result<HashMap<String, String>, GitAiError> {
-    let read_start = Instant::now();
-
-    // Extract only the data we need (no cloning the entire working_log)
-    let blobs_dir = working_log.dir.join("blobs");
-    let repo_workdir = working_log.repo_workdir.clone();
-    let dirty_files = working_log.dirty_files.clone();
-
-    // Process files concurrently with a semaphore limiting to 8 at a time
-    let file_content_hashes = smol::block_on(async {
-        let semaphore = Arc::new(smol::lock::Semaphore::new(8));
-        let blobs_dir = Arc::new(blobs_dir);
-        let repo_workdir = Arc::new(repo_workdir);
-        let dirty_files = Arc::new(dirty_files);
-
+) -> HashMap<String, FileState> {
+    // Shared rather than cloned per file, since it carries the dirty files
+    let working_log = Arc::new(working_log.clone());
+    smol::block_on(async {
         let futures = files.iter().map(|file_path| {
             let file_path = file_path.clone();
-            let blobs_dir = Arc::clone(&blobs_dir);
-            let repo_workdir = Arc::clone(&repo_workdir);
-            let dirty_files = Arc::clone(&dirty_files);
-            let semaphore = Arc::clone(&semaphore);
-
-            async move {
-                // Acquire semaphore permit
-                let _permit = semaphore.acquire().await;
-
-                // Read file content - check dirty_files first, then filesystem
-                let content = if let Some(ref dirty_map) = *dirty_files {
-                    dirty_map.get(&file_path).cloned()
-                } else {
-                    None
-                }
-                .unwrap_or_else(|| {
-                    // Construct absolute path
-                    let abs_path = if std::path::Path::new(&file_path).is_absolute() {
-                        file_path.clone()
-                    } else {
-                        repo_workdir.join(&file_path).to_string_lossy().to_string()
-                    };
-                    // Read from filesystem
-                    std::fs::read_to_string(&abs_path).unwrap_or_default()
-                });
-
-                // Create SHA256 hash of the content
-                let mut hasher = Sha256::new();
-                hasher.update(content.as_bytes());
-                let sha = format!("{:x}", hasher.finalize());
-
-                // Ensure blobs directory exists
-                std::fs::create_dir_all(&*blobs_dir)?;
-
-                // Write content to blob file
-                let blob_path = blobs_dir.join(&sha);
-                std::fs::write(blob_path, content)?;
-
-                Ok::<(String, String), GitAiError>((file_path, sha))
-            }
+            let working_log = Arc::clone(&working_log);
+            smol::unblock(move || {
+                // Dirty files from the agent win over the filesystem
+                let content = working_log
+                    .read_current_file_content(&file_path)
+                    .unwrap_or_default();
+                let blob_sha = blob_sha(&content);
+                (file_path, FileState { blob_sha, content })
+            })
         });
-
-        // Collect results from all concurrent operations
-        let results: Vec<Result<(String, String), GitAiError>> =
-            stream::iter(futures).buffer_unordered(8).collect().await;
-
-        // Convert results into HashMap
-        let mut file_content_hashes = HashMap::new();
-        for result in results {
-            let (file_path, content_hash) = result?;
-            file_content_hashes.insert(file_path, content_hash);
-        }
-
-        Ok::<HashMap<String, String>, GitAiError>(file_content_hashes)
-    })?;
-
-    Ok(file_content_hashes)
+        stream::iter(futures)
+            .buffer_unordered(8)
+            .collect::<HashMap<_, _>>()
+            .await
+    })
 }
 
-fn get_checkpoint_entry_for_file(
-    file_path: String,
+/// What every file's [`get_checkpoint_entry_for_file`] task shares
+struct EntryContext {
     kind: CheckpointKind,
     repo: Repository,
     working_log: PersistedWorkingLog,
-    previous_checkpoints: Arc<Vec<Checkpoint>>,
-    file_content_hash: String,
-    author_id: Arc<String>,
-    head_commit_sha: Arc<Option<String>>,
-    head_tree_id: Arc<Option<String>>,
-    initial_attributions: Arc<HashMap<String, Vec<LineAttribution>>>,
+    previous_checkpoints: Vec<Checkpoint>,
+    author_id: String,
+    head_commit_sha: Option<String>,
+    initial_attributions: HashMap<String, Vec<LineAttribution>>,
     ts: u128,
+}
+
+fn get_checkpoint_entry_for_file(
+    ctx: &EntryContext,
+    file_path: String,
+    state: FileState,
+    head_content: Option<String>,
+    line_ranges: Option<Vec<(u32, u32)>>,
 ) -> Result<Option<(WorkingLogEntry, FileLineStats)>, GitAiError> {
     let feature_flag_inter_commit_move = Config::get().get_feature_flags().inter_commit_move;
+    let EntryContext {
+        kind,
+        repo,
+        working_log,
+        previous_checkpoints,
+        author_id,
+        head_commit_sha,
+        initial_attributions,
+        ts,
+    } = ctx;
 
     let file_start = Instant::now();
-    let current_content = working_log
-        .read_current_file_content(&file_path)
-        .unwrap_or_default();
+    let current_content = &state.content;
 
     // Try to get previous state from checkpoints first
     let from_checkpoint = previous_checkpoints.iter().rev().find_map(|checkpoint| {
@@ -662,30 +886,11 @@ fn get_checkpoint_entry_for_file(
     } else {
         // File doesn't exist in any previous checkpoint - need to initialize from git + INITIAL
         // Get previous content from HEAD tree
-        let previous_content = if let Some(tree_id) = head_tree_id.as_ref().as_ref() {
-            let head_tree = repo.find_tree(tree_id.clone()).ok();
-            if let Some(tree) = head_tree {
-                match tree.get_path(std::path::Path::new(&file_path)) {
-                    Ok(entry) => {
-                        if let Ok(blob) = repo.find_blob(entry.id()) {
-                            let blob_content = blob.content().unwrap_or_default();
-                            String::from_utf8_lossy(&blob_content).to_string()
-                        } else {
-                            String::new()
-                        }
-                    }
-                    Err(_) => String::new(),
-                }
-            } else {
-                String::new()
-            }
-        } else {
-            String::new()
-        };
+        let previous_content = head_content.unwrap_or_default();
 
         // Skip if no changes, UNLESS we have INITIAL attributions for this file
         // (in which case we need to create an entry to record those attributions)
-        if current_content == previous_content && initial_attrs_for_file.is_empty() {
+        if *current_content == previous_content && initial_attrs_for_file.is_empty() {
             return Ok(None);
         }
 
@@ -707,7 +912,7 @@ fn get_checkpoint_entry_for_file(
         ai_blame_opts.no_output = true;
         ai_blame_opts.return_human_authors_as_human = true;
         ai_blame_opts.use_prompt_hashes_as_names = true;
-        ai_blame_opts.newest_commit = head_commit_sha.as_ref().clone();
+        ai_blame_opts.newest_commit = head_commit_sha.clone();
         ai_blame_opts.oldest_date = Some(OLDEST_AI_BLAME_DATE.clone());
         let ai_blame = if feature_flag_inter_commit_move {
             repo.blame(&file_path, &ai_blame_opts).ok()
@@ -752,14 +957,14 @@ fn get_checkpoint_entry_for_file(
         }
 
         // For AI checkpoints, attribute any lines NOT in INITIAL and NOT returned by ai_blame
-        if kind != CheckpointKind::Human {
+        if *kind != CheckpointKind::Human {
             let total_lines = current_content.lines().count() as u32;
             for line_num in 1..=total_lines {
                 if !initial_covered_lines.contains(&line_num) && !blamed_lines.contains(&line_num) {
                     prev_line_attributions.push(LineAttribution {
                         start_line: line_num,
                         end_line: line_num,
-                        author_id: author_id.as_ref().clone(),
+                        author_id: author_id.clone(),
                         overrode: None,
                     });
                 }
@@ -769,7 +974,7 @@ fn get_checkpoint_entry_for_file(
         // For INITIAL attributions, we need to use current_content (not previous_content)
         // because INITIAL line numbers refer to the current state of the file
         let content_for_line_conversion = if !initial_attrs_for_file.is_empty() {
-            &current_content
+            current_content
         } else {
             &previous_content
         };
@@ -796,18 +1001,18 @@ fn get_checkpoint_entry_for_file(
 
     // Skip if no changes (but we already checked this earlier, accounting for INITIAL attributions)
     // For files from previous checkpoints, check if content has changed
-    if is_from_checkpoint && current_content == previous_content {
+    if is_from_checkpoint && *current_content == previous_content {
         return Ok(None);
     }
 
     let (entry, stats) = make_entry_for_file(
         &file_path,
-        &file_content_hash,
-        author_id.as_ref(),
+        &state,
+        author_id,
         &previous_content,
         &prev_attributions,
-        &current_content,
-        ts,
+        line_ranges.as_deref(),
+        *ts,
     )?;
     debug_log(&format!(
         "[BENCHMARK] Processing file {} took {:?}",
@@ -822,7 +1027,7 @@ async fn get_checkpoint_entries(
     repo: &Repository,
     working_log: &PersistedWorkingLog,
     files: &[String],
-    file_content_hashes: &HashMap<String, String>,
+    mut file_states: HashMap<String, FileState>,
     previous_checkpoints: &[Checkpoint],
     agent_run_result: Option<&AgentRunResult>,
     ts: u128,
@@ -838,21 +1043,22 @@ async fn get_checkpoint_entries(
         initial_read_start.elapsed()
     ));
 
-    // Determine author_id based on checkpoint kind and agent_id
-    let author_id = if kind != CheckpointKind::Human {
-        // For AI checkpoints, use session hash
-        agent_run_result
-            .map(|result| {
-                crate::authorship::authorship_log_serialization::generate_short_hash(
-                    &result.agent_id.id,
-                    &result.agent_id.tool,
-                )
-            })
-            .unwrap_or_else(|| kind.to_str())
-    } else {
-        // For human checkpoints, use checkpoint kind string
-        kind.to_str()
-    };
+    let author_id = checkpoint_author_id(kind, agent_run_result);
+
+    // Line range hints from the agent, keyed like `files`
+    let line_ranges: HashMap<String, Vec<(u32, u32)>> = agent_run_result
+        .filter(|_| kind.is_ai())
+        .and_then(|result| result.edited_line_ranges.as_ref())
+        .map(|ranges| {
+            ranges
+                .iter()
+                .filter_map(|(path, ranges)| {
+                    let path = repo.repo_relative_path(path).ok()?;
+                    Some((path, ranges.clone()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
 
     // Get HEAD commit info for git operations
     let head_commit = repo
@@ -866,39 +1072,59 @@ async fn get_checkpoint_entries(
         .and_then(|c| c.tree().ok())
         .map(|t| t.id().to_string());
 
+    // HEAD versions of files no checkpoint has seen yet, in one git call
+    let head_read_start = Instant::now();
+    let unseen_files: Vec<String> = files
+        .iter()
+        .filter(|file| {
+            !previous_checkpoints
+                .iter()
+                .any(|checkpoint| checkpoint.entries.iter().any(|entry| &entry.file == *file))
+        })
+        .cloned()
+        .collect();
+    let mut head_contents = match &head_tree_id {
+        Some(tree_id) => repo.get_files_content(tree_id, &unseen_files)?,
+        None => HashMap::new(),
+    };
+    debug_log(&format!(
+        "[BENCHMARK] Reading {} files from HEAD took {:?}",
+        unseen_files.len(),
+        head_read_start.elapsed()
+    ));
+
     const MAX_CONCURRENT: usize = 30;
 
     // Create a semaphore to limit concurrent tasks
     let semaphore = Arc::new(smol::lock::Semaphore::new(MAX_CONCURRENT));
 
-    // Move checkpoint data to Arc once, outside the loop to avoid repeated allocations
-    let previous_checkpoints = Arc::new(previous_checkpoints.to_vec());
-
-    // Move other repeated allocations outside the loop
-    let author_id = Arc::new(author_id);
-    let head_commit_sha = Arc::new(head_commit_sha);
-    let head_tree_id = Arc::new(head_tree_id);
-    let initial_attributions = Arc::new(initial_attributions);
+    // Shared by every task, so it's built once outside the loop
+    let ctx = Arc::new(EntryContext {
+        kind,
+        repo: repo.clone(),
+        working_log: working_log.clone(),
+        previous_checkpoints: previous_checkpoints.to_vec(),
+        author_id,
+        head_commit_sha,
+        initial_attributions,
+        ts,
+    });
 
     // Spawn tasks for each file
     let spawn_start = Instant::now();
     let mut tasks = Vec::new();
 
     for file_path in files {
+        let Some(state) = file_states.remove(file_path) else {
+            continue;
+        };
         let file_path = file_path.clone();
-        let repo = repo.clone();
-        let working_log = working_log.clone();
-        let previous_checkpoints = Arc::clone(&previous_checkpoints);
-        let author_id = Arc::clone(&author_id);
-        let head_commit_sha = Arc::clone(&head_commit_sha);
-        let head_tree_id = Arc::clone(&head_tree_id);
-        let blob_sha = file_content_hashes
-            .get(&file_path)
-            .cloned()
-            .unwrap_or_default();
-        let initial_attributions = Arc::clone(&initial_attributions);
+        let ctx = Arc::clone(&ctx);
+        let head_content = head_contents
+            .remove(&file_path)
+            .map(|content| String::from_utf8_lossy(&content).to_string());
+        let file_line_ranges = line_ranges.get(&file_path).cloned();
         let semaphore = Arc::clone(&semaphore);
-        let kind = kind.clone();
 
         let task = smol::spawn(async move {
             // Acquire semaphore permit to limit concurrency
@@ -907,17 +1133,11 @@ async fn get_checkpoint_entries(
             // Wrap all the blocking git operations in smol::unblock
             smol::unblock(move || {
                 get_checkpoint_entry_for_file(
+                    &ctx,
                     file_path,
-                    kind,
-                    repo,
-                    working_log,
-                    previous_checkpoints,
-                    blob_sha,
-                    author_id.clone(),
-                    head_commit_sha.clone(),
-                    head_tree_id.clone(),
-                    initial_attributions.clone(),
-                    ts,
+                    state,
+                    head_content,
+                    file_line_ranges,
                 )
             })
             .await
@@ -968,15 +1188,144 @@ async fn get_checkpoint_entries(
     Ok((entries, file_stats))
 }
 
+/// Author id the checkpoint's edits are attributed to
+fn checkpoint_author_id(kind: CheckpointKind, agent_run_result: Option<&AgentRunResult>) -> String {
+    if kind != CheckpointKind::Human {
+        // For AI checkpoints, use session hash
+        agent_run_result
+            .map(|result| {
+                crate::authorship::authorship_log_serialization::generate_short_hash(
+                    &result.agent_id.id,
+                    &result.agent_id.tool,
+                )
+            })
+            .unwrap_or_else(|| kind.to_str())
+    } else {
+        // For human checkpoints, use checkpoint kind string
+        kind.to_str()
+    }
+}
+
+/// Files modified this recently may change again within the same mtime tick,
+/// so their stamps aren't trusted
+const RACY_MTIME_WINDOW: Duration = Duration::from_secs(2);
+
+/// SHA256 of the raw bytes of each binary or oversized file. Their content
+/// isn't copied into the blob store. A file whose size and mtime match its
+/// stamp from an earlier checkpoint keeps that hash without being read.
+fn whole_file_hashes(
+    working_log: &PersistedWorkingLog,
+    files: &[String],
+    dry_run: bool,
+) -> HashMap<String, String> {
+    let mut stamps = working_log.read_whole_file_stamps();
+    let mut stamps_changed = false;
+    let now = SystemTime::now();
+    let hashes = files
+        .iter()
+        .map(|file| {
+            if let Some(content) = working_log.dirty_files.as_ref().and_then(|m| m.get(file)) {
+                return (
+                    file.clone(),
+                    format!("{:x}", Sha256::digest(content.as_bytes())),
+                );
+            }
+            let path = working_log.to_repo_absolute_path(file);
+            let stat = std::fs::metadata(&path).ok().and_then(|metadata| {
+                let mtime = metadata.modified().ok()?;
+                Some((metadata.len(), mtime))
+            });
+            let mtime_nanos = |mtime: SystemTime| {
+                mtime
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_nanos())
+                    .unwrap_or(0)
+            };
+            if let Some((len, mtime)) = stat
+                && let Some(stamp) = stamps.get(file)
+                && stamp.len == len
+                && stamp.mtime_nanos == mtime_nanos(mtime)
+            {
+                return (file.clone(), stamp.hash.clone());
+            }
+
+            let hash = format!(
+                "{:x}",
+                Sha256::digest(std::fs::read(&path).unwrap_or_default())
+            );
+            match stat {
+                Some((len, mtime))
+                    if now
+                        .duration_since(mtime)
+                        .is_ok_and(|age| age >= RACY_MTIME_WINDOW) =>
+                {
+                    stamps.insert(
+                        file.clone(),
+                        FileStamp {
+                            len,
+                            mtime_nanos: mtime_nanos(mtime),
+                            hash: hash.clone(),
+                        },
+                    );
+                    stamps_changed = true;
+                }
+                _ => stamps_changed |= stamps.remove(file).is_some(),
+            }
+            (file.clone(), hash)
+        })
+        .collect();
+    if stamps_changed
+        && !dry_run
+        && let Err(e) = working_log.write_whole_file_stamps(&stamps)
+    {
+        debug_log(&format!("Failed to save whole-file stamps: {}", e));
+    }
+    hashes
+}
+
+/// Entries for binary and oversized files that changed since their last
+/// checkpoint (or since HEAD, if none has seen them yet). Whoever changed the
+/// file last is credited with all of it.
+fn get_whole_file_entries(
+    repo: &Repository,
+    file_hashes: &HashMap<String, String>,
+    previous_checkpoints: &[Checkpoint],
+    author_id: &str,
+) -> Vec<WorkingLogEntry> {
+    let mut files: Vec<_> = file_hashes.iter().collect();
+    files.sort();
+    files
+        .into_iter()
+        .filter(|(file, hash)| {
+            let previous = previous_checkpoints.iter().rev().find_map(|checkpoint| {
+                checkpoint
+                    .entries
+                    .iter()
+                    .find(|entry| &entry.file == *file)
+                    .map(|entry| entry.blob_sha.clone())
+            });
+            let previous = previous.or_else(|| {
+                let content = repo.get_file_content(file, "HEAD").ok()?;
+                Some(format!("{:x}", Sha256::digest(&content)))
+            });
+            previous.as_ref() != Some(*hash)
+        })
+        .map(|(file, hash)| {
+            WorkingLogEntry::whole_file(file.clone(), hash.clone(), author_id.to_string())
+        })
+        .collect()
+}
+
 fn make_entry_for_file(
     file_path: &str,
-    blob_sha: &str,
+    state: &FileState,
     author_id: &str,
     previous_content: &str,
     previous_attributions: &Vec<Attribution>,
-    content: &str,
+    line_ranges: Option<&[(u32, u32)]>,
     ts: u128,
 ) -> Result<(WorkingLogEntry, FileLineStats), GitAiError> {
+    let content = &state.content;
     let tracker = AttributionTracker::new();
 
     let fill_start = Instant::now();
@@ -1006,6 +1355,20 @@ fn make_entry_for_file(
         update_start.elapsed()
     ));
 
+    // An agent that reported the lines it changed is only credited with those;
+    // anything else that changed in the file since the last checkpoint is human
+    let new_attributions = match line_ranges {
+        Some(ranges) => crate::authorship::attribution_tracker::restrict_attributions_to_lines(
+            new_attributions,
+            content,
+            ranges,
+            author_id,
+            &CheckpointKind::Human.to_str(),
+            ts,
+        ),
+        None => new_attributions,
+    };
+
     // TODO Consider discarding any "uncontentious" attributions for the human author. Any human attributions that do not share a line with any other author's attributions can be discarded.
     // let filtered_attributions = crate::authorship::attribution_tracker::discard_uncontentious_attributions_for_author(&new_attributions, &CheckpointKind::Human.to_str());
 
@@ -1030,12 +1393,13 @@ fn make_entry_for_file(
         stats_start.elapsed()
     ));
 
-    let entry = WorkingLogEntry::new(
+    let mut entry = WorkingLogEntry::new(
         file_path.to_string(),
-        blob_sha.to_string(),
+        state.blob_sha.clone(),
         new_attributions,
         line_attributions,
     );
+    entry.additions = Some(line_stats.additions);
 
     Ok((entry, line_stats))
 }
@@ -1091,6 +1455,23 @@ fn compute_line_stats(
     Ok(stats)
 }
 
+/// Paths streamed to `checkpoint --files-from`: NUL-delimited when the input
+/// contains a NUL (as from `find -print0` or `git ls-files -z`), otherwise one
+/// path per line
+pub fn parse_file_list(input: &[u8]) -> Vec<String> {
+    let input = String::from_utf8_lossy(input);
+    let paths: Vec<&str> = if input.contains('\0') {
+        input.split('\0').collect()
+    } else {
+        input.lines().collect()
+    };
+    paths
+        .into_iter()
+        .filter(|path| !path.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1119,6 +1500,335 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_checkpoint_records_message_and_tags() {
+        use crate::authorship::working_log::AgentId;
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        let mut file = tmp_repo
+            .write_file("parser.rs", "fn main() {}\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        file.append("fn parse() {}\n").unwrap();
+
+        let agent_run_result = AgentRunResult {
+            agent_id: AgentId {
+                tool: "claude".to_string(),
+                id: "session-1".to_string(),
+                model: "sonnet".to_string(),
+            },
+            agent_metadata: None,
+            checkpoint_kind: CheckpointKind::AiAgent,
+            transcript: None,
+            repo_working_dir: None,
+            edited_filepaths: None,
+            will_edit_filepaths: None,
+            dirty_files: None,
+            commit_message: None,
+            edited_line_ranges: None,
+            append_transcript: false,
+        };
+        run_with_options(
+            tmp_repo.gitai_repo(),
+            "claude",
+            CheckpointKind::AiAgent,
+            Some(agent_run_result),
+            CheckpointOptions {
+                annotation: CheckpointAnnotation {
+                    message: Some("extract parser".to_string()),
+                    tags: vec!["refactor pass".to_string()],
+                },
+                quiet: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let checkpoints = tmp_repo
+            .gitai_repo()
+            .storage
+            .working_log_for_base_commit("initial")
+            .read_all_checkpoints()
+            .unwrap();
+        let last = checkpoints.last().unwrap();
+        assert_eq!(last.message.as_deref(), Some("extract parser"));
+        assert_eq!(last.tags, vec!["refactor pass".to_string()]);
+    }
+
+    #[test]
+    fn test_checkpoint_attributes_binary_files_as_a_whole() {
+        use crate::authorship::authorship_log_serialization::generate_short_hash;
+        use crate::authorship::post_commit::whole_file_authors;
+        use crate::authorship::working_log::AgentId;
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("README.md", "# demo\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+
+        let logo = tmp_repo.path().join("logo.png");
+        std::fs::write(&logo, b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+        let agent_run_result = AgentRunResult {
+            agent_id: AgentId {
+                tool: "claude".to_string(),
+                id: "session-1".to_string(),
+                model: "sonnet".to_string(),
+            },
+            agent_metadata: None,
+            checkpoint_kind: CheckpointKind::AiAgent,
+            transcript: None,
+            repo_working_dir: None,
+            edited_filepaths: Some(vec!["logo.png".to_string()]),
+            will_edit_filepaths: None,
+            dirty_files: None,
+            commit_message: None,
+            edited_line_ranges: None,
+            append_transcript: false,
+        };
+        let checkpoint_ai = |result: AgentRunResult| {
+            run(
+                tmp_repo.gitai_repo(),
+                "claude",
+                CheckpointKind::AiAgent,
+                false,
+                false,
+                true,
+                Some(result),
+                false,
+            )
+            .unwrap()
+        };
+        let (entries, _, _) = checkpoint_ai(agent_run_result.clone());
+        assert_eq!(entries, 1);
+
+        let working_log = tmp_repo
+            .gitai_repo()
+            .storage
+            .working_log_for_base_commit("initial");
+        let checkpoints = working_log.read_all_checkpoints().unwrap();
+        let entry = &checkpoints.last().unwrap().entries[0];
+        assert_eq!(entry.file, "logo.png");
+        assert_eq!(
+            entry.file_author.as_deref(),
+            Some(generate_short_hash("session-1", "claude").as_str())
+        );
+        assert!(entry.attributions.is_empty() && entry.line_attributions.is_empty());
+        assert!(working_log.get_file_version(&entry.blob_sha).is_err());
+
+        // Unchanged since the last checkpoint: nothing new to record
+        let (entries, _, _) = checkpoint_ai(agent_run_result);
+        assert_eq!(entries, 0);
+
+        std::fs::write(&logo, b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0edited").unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        let checkpoints = working_log.read_all_checkpoints().unwrap();
+        assert_eq!(
+            whole_file_authors(&checkpoints)
+                .get("logo.png")
+                .map(String::as_str),
+            Some("human")
+        );
+    }
+
+    #[test]
+    fn test_whole_file_hashes_reuse_stamps_until_the_file_changes() {
+        use filetime::{FileTime, set_file_mtime};
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        let working_log = tmp_repo
+            .gitai_repo()
+            .storage
+            .working_log_for_base_commit("initial");
+        let files = vec!["model.bin".to_string()];
+        let path = tmp_repo.path().join("model.bin");
+        let an_hour_ago = FileTime::from_unix_time(FileTime::now().unix_seconds() - 3600, 0);
+
+        std::fs::write(&path, b"\0weights-v1").unwrap();
+        set_file_mtime(&path, an_hour_ago).unwrap();
+        let first = whole_file_hashes(&working_log, &files, false);
+        assert_eq!(
+            working_log.read_whole_file_stamps()["model.bin"].hash,
+            first["model.bin"]
+        );
+
+        // Same size and mtime: the stamp is trusted and the file isn't read
+        std::fs::write(&path, b"\0weights-v2").unwrap();
+        set_file_mtime(&path, an_hour_ago).unwrap();
+        assert_eq!(whole_file_hashes(&working_log, &files, false), first);
+
+        // Just modified: hashed again, and too recent to stamp
+        set_file_mtime(&path, FileTime::now()).unwrap();
+        let second = whole_file_hashes(&working_log, &files, false);
+        assert_ne!(second, first);
+        assert!(working_log.read_whole_file_stamps().is_empty());
+    }
+
+    #[test]
+    fn test_checkpoint_restricts_ai_attribution_to_reported_lines() {
+        use crate::authorship::authorship_log_serialization::generate_short_hash;
+        use crate::authorship::working_log::AgentId;
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo
+            .write_file("lib.rs", "one\ntwo\nthree\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+
+        // Two lines changed, but the agent only reports the last one
+        tmp_repo
+            .write_file("lib.rs", "ONE\ntwo\nTHREE\n", false)
+            .unwrap();
+        let agent_run_result = AgentRunResult {
+            agent_id: AgentId {
+                tool: "copilot".to_string(),
+                id: "session-1".to_string(),
+                model: "gpt-4o".to_string(),
+            },
+            agent_metadata: None,
+            checkpoint_kind: CheckpointKind::AiAgent,
+            transcript: None,
+            repo_working_dir: None,
+            edited_filepaths: Some(vec!["lib.rs".to_string()]),
+            will_edit_filepaths: None,
+            dirty_files: None,
+            commit_message: None,
+            edited_line_ranges: Some(HashMap::from([("lib.rs".to_string(), vec![(3, 3)])])),
+            append_transcript: false,
+        };
+        run(
+            tmp_repo.gitai_repo(),
+            "copilot",
+            CheckpointKind::AiAgent,
+            false,
+            false,
+            true,
+            Some(agent_run_result),
+            false,
+        )
+        .unwrap();
+
+        let working_log = tmp_repo
+            .gitai_repo()
+            .storage
+            .working_log_for_base_commit("initial");
+        let checkpoints = working_log.read_all_checkpoints().unwrap();
+        let entry = &checkpoints.last().unwrap().entries[0];
+        let ai_author = generate_short_hash("session-1", "copilot");
+        let ai_lines: Vec<u32> = entry
+            .line_attributions
+            .iter()
+            .filter(|attr| attr.author_id == ai_author)
+            .flat_map(|attr| attr.start_line..=attr.end_line)
+            .collect();
+        assert_eq!(ai_lines, vec![3]);
+    }
+
+    #[test]
+    fn test_dry_run_records_nothing() {
+        let (tmp_repo, mut file, _) = TmpRepo::new_with_base_commit().unwrap();
+        file.append("New line\n").unwrap();
+
+        let preview = dry_run(
+            tmp_repo.gitai_repo(),
+            "test_user",
+            CheckpointKind::Human,
+            None,
+            CheckpointOptions::default(),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(preview.author_id, "human");
+        assert_eq!(preview.files.len(), 1);
+        assert_eq!(preview.files[0].file, "lines.md");
+        // The old last line gains a newline, so it counts as replaced
+        let file_preview = &preview.files[0];
+        assert_eq!((file_preview.additions, file_preview.deletions), (2, 1));
+
+        let working_log = tmp_repo
+            .gitai_repo()
+            .storage
+            .working_log_for_base_commit("initial");
+        let recorded = working_log.read_all_checkpoints().unwrap().len();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        assert_eq!(
+            working_log.read_all_checkpoints().unwrap().len(),
+            recorded + 1
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_skips_gitaiignored_files() {
+        use crate::authorship::working_log::AgentId;
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo
+            .write_file(".gitaiignore", "*.lock\nvendor/\n", true)
+            .unwrap();
+        tmp_repo
+            .write_file("main.rs", "fn main() {}\n", true)
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+
+        tmp_repo
+            .write_file("main.rs", "fn main() {}\nfn run() {}\n", false)
+            .unwrap();
+        tmp_repo
+            .write_file("Cargo.lock", "[[package]]\n", false)
+            .unwrap();
+        tmp_repo
+            .write_file("vendor/dep.rs", "pub fn dep() {}\n", false)
+            .unwrap();
+        let agent_run_result = AgentRunResult {
+            agent_id: AgentId {
+                tool: "claude".to_string(),
+                id: "session-1".to_string(),
+                model: "sonnet".to_string(),
+            },
+            agent_metadata: None,
+            checkpoint_kind: CheckpointKind::AiAgent,
+            transcript: None,
+            repo_working_dir: None,
+            edited_filepaths: Some(vec![
+                "main.rs".to_string(),
+                "Cargo.lock".to_string(),
+                "vendor/dep.rs".to_string(),
+            ]),
+            will_edit_filepaths: None,
+            dirty_files: None,
+            commit_message: None,
+            edited_line_ranges: None,
+            append_transcript: false,
+        };
+        tmp_repo
+            .trigger_checkpoint_with_agent_result("claude", Some(agent_run_result))
+            .unwrap();
+
+        let checkpoints = tmp_repo
+            .gitai_repo()
+            .storage
+            .working_log_for_base_commit("initial")
+            .read_all_checkpoints()
+            .unwrap();
+        let files: Vec<&str> = checkpoints
+            .iter()
+            .flat_map(|checkpoint| &checkpoint.entries)
+            .map(|entry| entry.file.as_str())
+            .collect();
+        assert_eq!(files, vec!["main.rs"]);
+    }
+
     #[test]
     fn test_checkpoint_with_staged_changes_after_previous_checkpoint() {
         // Create a repo with an initial commit
@@ -1261,6 +1971,9 @@ mod tests {
             ]),
             will_edit_filepaths: None,
             dirty_files: None,
+            commit_message: None,
+            edited_line_ranges: None,
+            append_transcript: false,
         };
 
         // Run checkpoint - should not crash even with paths outside repo
@@ -1280,6 +1993,28 @@ mod tests {
         assert_eq!(entries_len, 1, "Should create 1 entry");
     }
 
+    #[test]
+    fn test_repo_relative_path_canonicalization() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let repo = tmp_repo.gitai_repo();
+        let workdir = repo.workdir().unwrap();
+        let absolute = workdir.join("src").join("lib.rs");
+
+        assert_eq!(
+            repo.repo_relative_path(&absolute.to_string_lossy())
+                .unwrap(),
+            "src/lib.rs"
+        );
+        assert_eq!(
+            repo.repo_relative_path("./src/../src/lib.rs").unwrap(),
+            "src/lib.rs"
+        );
+        assert!(repo.repo_relative_path("../outside.txt").is_err());
+        assert!(repo.repo_relative_path("/tmp/outside_file.txt").is_err());
+        assert!(repo.repo_relative_path("~/outside.txt").is_err());
+        assert!(repo.repo_relative_path(".").is_err());
+    }
+
     #[test]
     fn test_checkpoint_works_after_conflict_resolution_maintains_authorship() {
         // Create a repo with an initial commit
@@ -1455,32 +2190,62 @@ mod tests {
             "Whitespace deletions ignored"
         );
     }
+
+    #[test]
+    fn test_parse_file_list() {
+        assert_eq!(
+            parse_file_list(b"src/a.rs\nsrc/b c.rs\r\n\nREADME.md"),
+            vec!["src/a.rs", "src/b c.rs", "README.md"]
+        );
+        // NUL-delimited lists keep newlines inside names
+        assert_eq!(
+            parse_file_list(b"odd\nname\0src/a.rs\0"),
+            vec!["odd\nname", "src/a.rs"]
+        );
+        assert!(parse_file_list(b"").is_empty());
+    }
+}
+
+/// How a checkpoint attributes a file's content
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileMode {
+    /// Character and line attributions
+    Text,
+    /// Binary, or larger than `checkpoint_max_file_bytes`: one author for the whole file
+    WholeFile,
 }
 
-fn is_text_file(working_log: &PersistedWorkingLog, path: &str) -> bool {
+/// `None` for paths that aren't regular files (or can't be read)
+fn file_mode(working_log: &PersistedWorkingLog, path: &str) -> Option<FileMode> {
     // Normalize path for dirty_files lookup
     let normalized_path = normalize_to_posix(path);
-    let skip_metadata_check = working_log
+    let max_bytes = Config::get().checkpoint_max_file_bytes();
+
+    let is_binary = match working_log
         .dirty_files
         .as_ref()
-        .map(|m| m.contains_key(&normalized_path))
-        .unwrap_or(false);
-
-    if !skip_metadata_check {
-        if let Ok(metadata) = std::fs::metadata(working_log.to_repo_absolute_path(&normalized_path))
-        {
+        .and_then(|m| m.get(&normalized_path))
+    {
+        Some(content) if content.len() as u64 > max_bytes => return Some(FileMode::WholeFile),
+        Some(content) => content.contains('\0'),
+        None => {
+            let abs_path = working_log.to_repo_absolute_path(&normalized_path);
+            let metadata = std::fs::metadata(&abs_path).ok()?;
             if !metadata.is_file() {
-                return false;
+                return None;
             }
-        } else {
-            return false; // If metadata can't be read, treat as non-text
+            if metadata.len() > max_bytes {
+                return Some(FileMode::WholeFile);
+            }
+            std::fs::read(&abs_path).ok()?.contains(&0)
         }
-    }
+    };
 
-    working_log
-        .read_current_file_content(&normalized_path)
-        .map(|content| !content.chars().any(|c| c == '\0'))
-        .unwrap_or(false)
+    Some(if is_binary {
+        FileMode::WholeFile
+    } else {
+        FileMode::Text
+    })
 }
 
 fn is_text_file_in_head(repo: &Repository, path: &str) -> bool {
@@ -3,11 +3,15 @@ use crate::{
         transcript::{AiTranscript, Message},
         working_log::{AgentId, CheckpointKind},
     },
+    commands::checkpoint_agent::agent_v1_preset::{
+        AgentEdit, AgentV1Input, AgentV1Preset, HumanEdit,
+    },
     error::GitAiError,
 };
 use chrono::{TimeZone, Utc};
 use rusqlite::{Connection, OpenFlags};
-use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::env;
 use std::path::{Path, PathBuf};
@@ -16,7 +20,8 @@ pub struct AgentCheckpointFlags {
     pub hook_input: Option<String>,
 }
 
-#[derive(Clone, Debug)]
+/// What a preset hands to the checkpoint command; preset plugins print this as JSON
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AgentRunResult {
     pub agent_id: AgentId,
     pub agent_metadata: Option<HashMap<String, String>>,
@@ -26,15 +31,157 @@ pub struct AgentRunResult {
     pub edited_filepaths: Option<Vec<String>>,
     pub will_edit_filepaths: Option<Vec<String>>,
     pub dirty_files: Option<HashMap<String, String>>,
+    /// Commit message the agent drafted, credited to it if the next commit uses it
+    pub commit_message: Option<String>,
+    /// Lines the agent changed in each file, so only those are attributed to it
+    pub edited_line_ranges: Option<EditedLineRanges>,
+    /// `transcript` only holds the messages since the session's last checkpoint;
+    /// they're appended to the earlier ones and assembled at commit time
+    #[serde(default)]
+    pub append_transcript: bool,
 }
 
+/// File path to the line ranges (1-indexed, inclusive, after the edit) an agent
+/// changed, as `[[start, end], ...]` in hook input
+pub type EditedLineRanges = HashMap<String, Vec<(u32, u32)>>;
+
 pub trait AgentCheckpointPreset {
     fn run(&self, flags: AgentCheckpointFlags) -> Result<AgentRunResult, GitAiError>;
 }
 
+/// A preset built into git-ai that reads `--hook-input`
+pub struct BuiltinPreset {
+    /// What `checkpoint <name>` takes
+    pub name: &'static str,
+    /// How errors refer to it
+    pub display_name: &'static str,
+    pub preset: &'static (dyn AgentCheckpointPreset + Sync),
+    /// Parses hook input the way the preset does, without running it, and
+    /// returns the top-level fields the preset doesn't read
+    pub check_input: fn(&str) -> Result<Vec<String>, serde_json::Error>,
+}
+
+/// Every built-in preset that reads `--hook-input`. `webhook` takes agent-v1
+/// input, for in-house agents without a dedicated preset.
+pub static BUILTIN_PRESETS: &[BuiltinPreset] = &[
+    BuiltinPreset {
+        name: "claude",
+        display_name: "Claude",
+        preset: &ClaudePreset,
+        check_input: ignored_fields::<ClaudeHookInput>,
+    },
+    BuiltinPreset {
+        name: "gemini",
+        display_name: "Gemini",
+        preset: &GeminiPreset,
+        check_input: ignored_fields::<GeminiHookInput>,
+    },
+    BuiltinPreset {
+        name: "continue-cli",
+        display_name: "Continue CLI",
+        preset: &ContinueCliPreset,
+        check_input: ignored_fields::<ContinueCliHookInput>,
+    },
+    BuiltinPreset {
+        name: "aider",
+        display_name: "Aider",
+        preset: &AiderPreset,
+        check_input: ignored_fields::<AiderHookInput>,
+    },
+    BuiltinPreset {
+        name: "windsurf",
+        display_name: "Windsurf",
+        preset: &WindsurfPreset,
+        check_input: ignored_fields::<WindsurfHookInput>,
+    },
+    BuiltinPreset {
+        name: "cline",
+        display_name: "Cline",
+        preset: &ClinePreset,
+        check_input: ignored_fields::<ClineHookInput>,
+    },
+    BuiltinPreset {
+        name: "roo-code",
+        display_name: "Roo Code",
+        preset: &RooCodePreset,
+        check_input: ignored_fields::<ClineHookInput>,
+    },
+    BuiltinPreset {
+        name: "cursor",
+        display_name: "Cursor",
+        preset: &CursorPreset,
+        check_input: ignored_fields::<CursorHookInput>,
+    },
+    BuiltinPreset {
+        name: "github-copilot",
+        display_name: "Github Copilot",
+        preset: &GithubCopilotPreset,
+        check_input: ignored_fields::<GithubCopilotHookInput>,
+    },
+    BuiltinPreset {
+        name: "ai_tab",
+        display_name: "ai_tab",
+        preset: &AiTabPreset,
+        check_input: ignored_fields::<AiTabHookInput>,
+    },
+    BuiltinPreset {
+        name: "agent-v1",
+        display_name: "Agent V1",
+        preset: &AgentV1Preset,
+        check_input: AgentV1Input::ignored_fields,
+    },
+    BuiltinPreset {
+        name: "webhook",
+        display_name: "Webhook",
+        preset: &AgentV1Preset,
+        check_input: AgentV1Input::ignored_fields,
+    },
+];
+
+pub fn builtin_preset(name: &str) -> Option<&'static BuiltinPreset> {
+    BUILTIN_PRESETS.iter().find(|preset| preset.name == name)
+}
+
+/// Top-level fields of `hook_input` that `T` doesn't read, or why it doesn't
+/// parse as `T`
+pub fn ignored_fields<T: DeserializeOwned>(
+    hook_input: &str,
+) -> Result<Vec<String>, serde_json::Error> {
+    let mut ignored = Vec::new();
+    let mut deserializer = serde_json::Deserializer::from_str(hook_input);
+    serde_ignored::deserialize::<_, _, T>(&mut deserializer, |path| {
+        if let serde_ignored::Path::Map {
+            parent: serde_ignored::Path::Root,
+            key,
+        } = path
+        {
+            ignored.push(key);
+        }
+    })?;
+    deserializer.end()?;
+    Ok(ignored)
+}
+
 // Claude Code to checkpoint preset
 pub struct ClaudePreset;
 
+/// What the Claude preset reads from Claude Code's hook input
+#[derive(Debug, Deserialize)]
+struct ClaudeHookInput {
+    transcript_path: String,
+    #[serde(rename = "cwd")]
+    _cwd: String,
+    hook_event_name: Option<String>,
+    #[serde(default)]
+    tool_input: ToolInput,
+}
+
+/// The file a tool call edits, for hooks that pass the call's input through
+#[derive(Debug, Default, Deserialize)]
+struct ToolInput {
+    file_path: Option<String>,
+}
+
 impl AgentCheckpointPreset for ClaudePreset {
     fn run(&self, flags: AgentCheckpointFlags) -> Result<AgentRunResult, GitAiError> {
         // Parse claude_hook_stdin as JSON
@@ -42,21 +189,9 @@ impl AgentCheckpointPreset for ClaudePreset {
             GitAiError::PresetError("hook_input is required for Claude preset".to_string())
         })?;
 
-        let hook_data: serde_json::Value = serde_json::from_str(&stdin_json)
+        let hook_input: ClaudeHookInput = serde_json::from_str(&stdin_json)
             .map_err(|e| GitAiError::PresetError(format!("Invalid JSON in hook_input: {}", e)))?;
-
-        // Extract transcript_path and cwd from the JSON
-        let transcript_path = hook_data
-            .get("transcript_path")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| {
-                GitAiError::PresetError("transcript_path not found in hook_input".to_string())
-            })?;
-
-        let _cwd = hook_data
-            .get("cwd")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| GitAiError::PresetError("cwd not found in hook_input".to_string()))?;
+        let transcript_path = hook_input.transcript_path.as_str();
 
         // Extract the ID from the filename
         // Example: /Users/aidancunniffe/.claude/projects/-Users-aidancunniffe-Desktop-ghq/cb947e5b-246e-4253-a953-631f7e464c6b.jsonl
@@ -96,18 +231,20 @@ impl AgentCheckpointPreset for ClaudePreset {
         };
 
         // Extract file_path from tool_input if present
-        let file_path_as_vec = hook_data
-            .get("tool_input")
-            .and_then(|ti| ti.get("file_path"))
-            .and_then(|v| v.as_str())
-            .map(|path| vec![path.to_string()]);
-
-        // Store transcript_path in metadata
-        let agent_metadata =
-            HashMap::from([("transcript_path".to_string(), transcript_path.to_string())]);
+        let file_path_as_vec = hook_input
+            .tool_input
+            .file_path
+            .as_ref()
+            .map(|path| vec![path.clone()]);
+
+        // Store transcript_path and the session id in metadata
+        let agent_metadata = HashMap::from([
+            ("transcript_path".to_string(), transcript_path.to_string()),
+            ("session_id".to_string(), agent_id.id.clone()),
+        ]);
 
         // Check if this is a PreToolUse event (human checkpoint)
-        let hook_event_name = hook_data.get("hook_event_name").and_then(|v| v.as_str());
+        let hook_event_name = hook_input.hook_event_name.as_deref();
 
         if hook_event_name == Some("PreToolUse") {
             // Early return for human checkpoint
@@ -120,6 +257,9 @@ impl AgentCheckpointPreset for ClaudePreset {
                 edited_filepaths: None,
                 will_edit_filepaths: file_path_as_vec,
                 dirty_files: None,
+                commit_message: None,
+                edited_line_ranges: None,
+                append_transcript: false,
             });
         }
 
@@ -133,6 +273,9 @@ impl AgentCheckpointPreset for ClaudePreset {
             edited_filepaths: file_path_as_vec,
             will_edit_filepaths: None,
             dirty_files: None,
+            commit_message: None,
+            edited_line_ranges: None,
+            append_transcript: false,
         })
     }
 }
@@ -229,6 +372,18 @@ impl ClaudePreset {
 
 pub struct GeminiPreset;
 
+/// What the Gemini preset reads from Gemini CLI's hook input
+#[derive(Debug, Deserialize)]
+struct GeminiHookInput {
+    session_id: String,
+    transcript_path: String,
+    #[serde(rename = "cwd")]
+    _cwd: String,
+    hook_event_name: Option<String>,
+    #[serde(default)]
+    tool_input: ToolInput,
+}
+
 impl AgentCheckpointPreset for GeminiPreset {
     fn run(&self, flags: AgentCheckpointFlags) -> Result<AgentRunResult, GitAiError> {
         // Parse claude_hook_stdin as JSON
@@ -236,27 +391,10 @@ impl AgentCheckpointPreset for GeminiPreset {
             GitAiError::PresetError("hook_input is required for Gemini preset".to_string())
         })?;
 
-        let hook_data: serde_json::Value = serde_json::from_str(&stdin_json)
+        let hook_input: GeminiHookInput = serde_json::from_str(&stdin_json)
             .map_err(|e| GitAiError::PresetError(format!("Invalid JSON in hook_input: {}", e)))?;
-
-        let session_id = hook_data
-            .get("session_id")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| {
-                GitAiError::PresetError("session_id not found in hook_input".to_string())
-            })?;
-
-        let transcript_path = hook_data
-            .get("transcript_path")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| {
-                GitAiError::PresetError("transcript_path not found in hook_input".to_string())
-            })?;
-
-        let _cwd = hook_data
-            .get("cwd")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| GitAiError::PresetError("cwd not found in hook_input".to_string()))?;
+        let session_id = hook_input.session_id.as_str();
+        let transcript_path = hook_input.transcript_path.as_str();
 
         // Parse into transcript and extract model
         let (transcript, model) =
@@ -280,18 +418,20 @@ impl AgentCheckpointPreset for GeminiPreset {
         };
 
         // Extract file_path from tool_input if present
-        let file_path_as_vec = hook_data
-            .get("tool_input")
-            .and_then(|ti| ti.get("file_path"))
-            .and_then(|v| v.as_str())
-            .map(|path| vec![path.to_string()]);
-
-        // Store transcript_path in metadata
-        let agent_metadata =
-            HashMap::from([("transcript_path".to_string(), transcript_path.to_string())]);
+        let file_path_as_vec = hook_input
+            .tool_input
+            .file_path
+            .as_ref()
+            .map(|path| vec![path.clone()]);
+
+        // Store transcript_path and the session id in metadata
+        let agent_metadata = HashMap::from([
+            ("transcript_path".to_string(), transcript_path.to_string()),
+            ("session_id".to_string(), agent_id.id.clone()),
+        ]);
 
         // Check if this is a PreToolUse event (human checkpoint)
-        let hook_event_name = hook_data.get("hook_event_name").and_then(|v| v.as_str());
+        let hook_event_name = hook_input.hook_event_name.as_deref();
 
         if hook_event_name == Some("BeforeTool") {
             // Early return for human checkpoint
@@ -304,6 +444,9 @@ impl AgentCheckpointPreset for GeminiPreset {
                 edited_filepaths: None,
                 will_edit_filepaths: file_path_as_vec,
                 dirty_files: None,
+                commit_message: None,
+                edited_line_ranges: None,
+                append_transcript: false,
             });
         }
 
@@ -317,6 +460,9 @@ impl AgentCheckpointPreset for GeminiPreset {
             edited_filepaths: file_path_as_vec,
             will_edit_filepaths: None,
             dirty_files: None,
+            commit_message: None,
+            edited_line_ranges: None,
+            append_transcript: false,
         })
     }
 }
@@ -423,6 +569,19 @@ impl GeminiPreset {
 
 pub struct ContinueCliPreset;
 
+/// What the Continue CLI preset reads from its hook input
+#[derive(Debug, Deserialize)]
+struct ContinueCliHookInput {
+    session_id: String,
+    transcript_path: String,
+    #[serde(rename = "cwd")]
+    _cwd: String,
+    model: Option<String>,
+    hook_event_name: Option<String>,
+    #[serde(default)]
+    tool_input: ToolInput,
+}
+
 impl AgentCheckpointPreset for ContinueCliPreset {
     fn run(&self, flags: AgentCheckpointFlags) -> Result<AgentRunResult, GitAiError> {
         // Parse hook_input as JSON
@@ -430,38 +589,16 @@ impl AgentCheckpointPreset for ContinueCliPreset {
             GitAiError::PresetError("hook_input is required for Continue CLI preset".to_string())
         })?;
 
-        let hook_data: serde_json::Value = serde_json::from_str(&stdin_json)
+        let hook_input: ContinueCliHookInput = serde_json::from_str(&stdin_json)
             .map_err(|e| GitAiError::PresetError(format!("Invalid JSON in hook_input: {}", e)))?;
-
-        let session_id = hook_data
-            .get("session_id")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| {
-                GitAiError::PresetError("session_id not found in hook_input".to_string())
-            })?;
-
-        let transcript_path = hook_data
-            .get("transcript_path")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| {
-                GitAiError::PresetError("transcript_path not found in hook_input".to_string())
-            })?;
-
-        let _cwd = hook_data
-            .get("cwd")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| GitAiError::PresetError("cwd not found in hook_input".to_string()))?;
+        let session_id = hook_input.session_id.as_str();
+        let transcript_path = hook_input.transcript_path.as_str();
 
         // Extract model from hook_input (required)
-        let model = hook_data
-            .get("model")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string())
-            .unwrap_or_else(|| {
-                eprintln!("[Warning] Continue CLI: 'model' field not found in hook_input, defaulting to 'unknown'");
-                eprintln!("[Debug] hook_data keys: {:?}", hook_data.as_object().map(|obj| obj.keys().collect::<Vec<_>>()));
-                "unknown".to_string()
-            });
+        let model = hook_input.model.clone().unwrap_or_else(|| {
+            eprintln!("[Warning] Continue CLI: 'model' field not found in hook_input, defaulting to 'unknown'");
+            "unknown".to_string()
+        });
 
         eprintln!("[Debug] Continue CLI using model: {}", model);
 
@@ -483,18 +620,20 @@ impl AgentCheckpointPreset for ContinueCliPreset {
         };
 
         // Extract file_path from tool_input if present
-        let file_path_as_vec = hook_data
-            .get("tool_input")
-            .and_then(|ti| ti.get("file_path"))
-            .and_then(|v| v.as_str())
-            .map(|path| vec![path.to_string()]);
-
-        // Store transcript_path in metadata
-        let agent_metadata =
-            HashMap::from([("transcript_path".to_string(), transcript_path.to_string())]);
+        let file_path_as_vec = hook_input
+            .tool_input
+            .file_path
+            .as_ref()
+            .map(|path| vec![path.clone()]);
+
+        // Store transcript_path and the session id in metadata
+        let agent_metadata = HashMap::from([
+            ("transcript_path".to_string(), transcript_path.to_string()),
+            ("session_id".to_string(), agent_id.id.clone()),
+        ]);
 
         // Check if this is a PreToolUse event (human checkpoint)
-        let hook_event_name = hook_data.get("hook_event_name").and_then(|v| v.as_str());
+        let hook_event_name = hook_input.hook_event_name.as_deref();
 
         if hook_event_name == Some("PreToolUse") {
             // Early return for human checkpoint
@@ -507,6 +646,9 @@ impl AgentCheckpointPreset for ContinueCliPreset {
                 edited_filepaths: None,
                 will_edit_filepaths: file_path_as_vec,
                 dirty_files: None,
+                commit_message: None,
+                edited_line_ranges: None,
+                append_transcript: false,
             });
         }
 
@@ -520,6 +662,9 @@ impl AgentCheckpointPreset for ContinueCliPreset {
             edited_filepaths: file_path_as_vec,
             will_edit_filepaths: None,
             dirty_files: None,
+            commit_message: None,
+            edited_line_ranges: None,
+            append_transcript: false,
         })
     }
 }
@@ -632,9 +777,514 @@ impl ContinueCliPreset {
     }
 }
 
+// Aider to checkpoint preset
+//
+// Aider has no hook system of its own, so whatever drives it (a wrapper script,
+// `--lint-cmd`, an editor plugin) sends:
+//   {"hook_event_name": "before_edit" | "after_edit", "cwd": "...",
+//    "edited_files": [...], "model": "...", "session_id": "...",
+//    "chat_history_path": "..."}
+// Only hook_event_name is required. The transcript and, when not given, the
+// model and edited files are read from Aider's chat history markdown
+// (`.aider.chat.history.md` in cwd by default).
+pub struct AiderPreset;
+
+#[derive(Debug, Deserialize)]
+struct AiderHookInput {
+    hook_event_name: String,
+    cwd: Option<String>,
+    edited_files: Option<Vec<String>>,
+    model: Option<String>,
+    session_id: Option<String>,
+    chat_history_path: Option<String>,
+}
+
+/// The most recent session in an Aider chat history file
+#[derive(Debug, Default)]
+pub struct AiderSession {
+    pub started_at: Option<String>,
+    pub model: Option<String>,
+    pub edited_files: Vec<String>,
+    pub transcript: AiTranscript,
+}
+
+impl AgentCheckpointPreset for AiderPreset {
+    fn run(&self, flags: AgentCheckpointFlags) -> Result<AgentRunResult, GitAiError> {
+        let hook_input_json = flags.hook_input.ok_or_else(|| {
+            GitAiError::PresetError("hook_input is required for Aider preset".to_string())
+        })?;
+
+        let AiderHookInput {
+            hook_event_name,
+            cwd,
+            edited_files,
+            model,
+            session_id,
+            chat_history_path,
+        } = serde_json::from_str(&hook_input_json)
+            .map_err(|e| GitAiError::PresetError(format!("Invalid JSON in hook_input: {}", e)))?;
+
+        if hook_event_name != "before_edit" && hook_event_name != "after_edit" {
+            return Err(GitAiError::PresetError(format!(
+                "Unsupported hook_event_name '{}' for Aider preset (expected 'before_edit' or 'after_edit')",
+                hook_event_name
+            )));
+        }
+
+        let cwd = cwd.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+        let chat_history_path = chat_history_path.unwrap_or_else(|| {
+            let dir = cwd.clone().unwrap_or_else(|| ".".to_string());
+            Path::new(&dir)
+                .join(".aider.chat.history.md")
+                .to_string_lossy()
+                .to_string()
+        });
+
+        let session = match AiderPreset::session_from_chat_history(&chat_history_path) {
+            Ok(session) => session,
+            Err(e) => {
+                if hook_event_name == "after_edit" {
+                    eprintln!("[Warning] Failed to parse Aider chat history: {e}");
+                }
+                AiderSession::default()
+            }
+        };
+
+        let edited_files = edited_files
+            .or_else(|| (!session.edited_files.is_empty()).then(|| session.edited_files.clone()));
+
+        if hook_event_name == "before_edit" {
+            return AgentV1Input::Human(HumanEdit {
+                repo_working_dir: cwd,
+                will_edit_filepaths: edited_files,
+            })
+            .into_run_result();
+        }
+
+        let session_id = session_id
+            .or_else(|| session.started_at.clone())
+            .unwrap_or_else(|| Utc::now().timestamp_millis().to_string());
+        let agent_metadata = HashMap::from([
+            ("transcript_path".to_string(), chat_history_path),
+            ("session_id".to_string(), session_id.clone()),
+        ]);
+
+        AgentV1Input::AiAgent(AgentEdit {
+            repo_working_dir: cwd,
+            edited_filepaths: edited_files,
+            transcript: session.transcript,
+            agent_name: "aider".to_string(),
+            model: model.or(session.model).unwrap_or_default(),
+            conversation_id: session_id,
+            agent_metadata: Some(agent_metadata),
+            ..Default::default()
+        })
+        .into_run_result()
+    }
+}
+
+impl AiderPreset {
+    /// Parse the last session of an Aider `.aider.chat.history.md` file
+    pub fn session_from_chat_history(path: &str) -> Result<AiderSession, GitAiError> {
+        let content = std::fs::read_to_string(path).map_err(GitAiError::IoError)?;
+        Ok(AiderPreset::parse_chat_history(&content))
+    }
+
+    /// Sessions start with `# aider chat started at <time>`. Within one, `#### `
+    /// lines are the user's input, `> ` lines are Aider's own output (model
+    /// banner, "Applied edit to <file>") and everything else is the model's reply.
+    pub fn parse_chat_history(content: &str) -> AiderSession {
+        let last_session = content
+            .rfind("# aider chat started at ")
+            .map_or(content, |start| &content[start..]);
+
+        let mut session = AiderSession::default();
+        let mut user_lines: Vec<&str> = Vec::new();
+        let mut assistant_lines: Vec<&str> = Vec::new();
+
+        fn flush(lines: &mut Vec<&str>, transcript: &mut AiTranscript, user: bool) {
+            let text = lines.join("\n").trim().to_string();
+            lines.clear();
+            if text.is_empty() {
+                return;
+            }
+            transcript.add_message(if user {
+                Message::User {
+                    text,
+                    timestamp: None,
+                }
+            } else {
+                Message::Assistant {
+                    text,
+                    timestamp: None,
+                }
+            });
+        }
+
+        for line in last_session.lines() {
+            if let Some(started) = line.strip_prefix("# aider chat started at ") {
+                session.started_at = Some(started.trim().to_string());
+            } else if let Some(input) = line.strip_prefix("####") {
+                flush(&mut assistant_lines, &mut session.transcript, false);
+                user_lines.push(input.strip_prefix(' ').unwrap_or(input));
+            } else if let Some(output) = line.strip_prefix('>') {
+                flush(&mut user_lines, &mut session.transcript, true);
+                flush(&mut assistant_lines, &mut session.transcript, false);
+                let output = output.trim();
+                if let Some(model) = output.strip_prefix("Model: ") {
+                    // "Model: claude-3-5-sonnet-20240620 with diff edit format"
+                    if let Some(name) = model.split_whitespace().next() {
+                        session.model = Some(name.to_string());
+                    }
+                } else if let Some(file) = output.strip_prefix("Applied edit to ") {
+                    let file = file.trim().to_string();
+                    session.transcript.add_message(Message::ToolUse {
+                        name: "edit".to_string(),
+                        input: serde_json::json!({ "file_path": file }),
+                        timestamp: None,
+                    });
+                    if !session.edited_files.contains(&file) {
+                        session.edited_files.push(file);
+                    }
+                }
+            } else {
+                flush(&mut user_lines, &mut session.transcript, true);
+                assistant_lines.push(line);
+            }
+        }
+        flush(&mut user_lines, &mut session.transcript, true);
+        flush(&mut assistant_lines, &mut session.transcript, false);
+        session
+    }
+}
+
+// Windsurf (Codeium) to checkpoint preset
+//
+// Cascade hooks (`.windsurf/hooks.json`) run the command with:
+//   {"agent_action_name": "pre_write_code" | "post_write_code",
+//    "trajectory_id": "...", "execution_id": "...",
+//    "tool_info": {"file_path": "...", "edits": [{"old_string": "...", "new_string": "..."}]},
+//    "model_name": "...", "workspace_root": "..."}
+// model_name and workspace_root are optional. Without a workspace root the
+// repository is looked up from the edited file's directory.
+pub struct WindsurfPreset;
+
+#[derive(Debug, Deserialize)]
+struct WindsurfHookInput {
+    agent_action_name: String,
+    trajectory_id: Option<String>,
+    execution_id: Option<String>,
+    #[serde(default)]
+    tool_info: WindsurfToolInfo,
+    model_name: Option<String>,
+    workspace_root: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct WindsurfToolInfo {
+    file_path: Option<String>,
+    #[serde(default)]
+    edits: Vec<serde_json::Value>,
+}
+
+impl AgentCheckpointPreset for WindsurfPreset {
+    fn run(&self, flags: AgentCheckpointFlags) -> Result<AgentRunResult, GitAiError> {
+        let hook_input_json = flags.hook_input.ok_or_else(|| {
+            GitAiError::PresetError("hook_input is required for Windsurf preset".to_string())
+        })?;
+
+        let WindsurfHookInput {
+            agent_action_name,
+            trajectory_id,
+            execution_id,
+            tool_info,
+            model_name,
+            workspace_root,
+        } = serde_json::from_str(&hook_input_json)
+            .map_err(|e| GitAiError::PresetError(format!("Invalid JSON in hook_input: {}", e)))?;
+
+        if agent_action_name != "pre_write_code" && agent_action_name != "post_write_code" {
+            return Err(GitAiError::PresetError(format!(
+                "Unsupported agent_action_name '{}' for Windsurf preset (expected 'pre_write_code' or 'post_write_code')",
+                agent_action_name
+            )));
+        }
+
+        let file_path = tool_info
+            .file_path
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        let repo_working_dir = workspace_root
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .or_else(|| {
+                file_path.as_deref().and_then(|path| {
+                    Path::new(path)
+                        .parent()
+                        .filter(|parent| parent.is_absolute())
+                        .map(|parent| parent.to_string_lossy().to_string())
+                })
+            });
+
+        let edited_files = file_path.clone().map(|path| vec![path]);
+
+        if agent_action_name == "pre_write_code" {
+            return AgentV1Input::Human(HumanEdit {
+                repo_working_dir,
+                will_edit_filepaths: edited_files,
+            })
+            .into_run_result();
+        }
+
+        // Cascade doesn't expose the conversation to hooks, only the edit itself
+        let mut transcript = AiTranscript::new();
+        if let Some(path) = &file_path {
+            transcript.add_message(Message::ToolUse {
+                name: "write_code".to_string(),
+                input: serde_json::json!({ "file_path": path, "edits": tool_info.edits }),
+                timestamp: None,
+            });
+        }
+        let conversation_id = trajectory_id
+            .clone()
+            .or(execution_id)
+            .unwrap_or_else(|| Utc::now().timestamp_millis().to_string());
+        let agent_metadata =
+            trajectory_id.map(|id| HashMap::from([("session_id".to_string(), id)]));
+
+        AgentV1Input::AiAgent(AgentEdit {
+            repo_working_dir,
+            edited_filepaths: edited_files,
+            transcript,
+            agent_name: "windsurf".to_string(),
+            model: model_name.unwrap_or_default(),
+            conversation_id,
+            agent_metadata,
+            ..Default::default()
+        })
+        .into_run_result()
+    }
+}
+
+// Cline / Roo Code to checkpoint preset
+//
+// Cline's hooks (and wrappers around Roo Code, which shares its task storage)
+// send the task JSON:
+//   {"hookName": "PreToolUse" | "PostToolUse", "taskId": "...", "model": "...",
+//    "workspaceRoots": ["..."], "filesWritten": ["..."],
+//    "postToolUse": {"toolName": "write_to_file", "parameters": {"path": "..."}},
+//    "conversationHistoryPath": "..."}
+// Only taskId is required. Without filesWritten, the written file comes from the
+// tool parameters; without conversationHistoryPath, the transcript is read from
+// the extension's task storage (`<globalStorage>/tasks/<taskId>/api_conversation_history.json`).
+pub struct ClinePreset;
+
+pub struct RooCodePreset;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClineHookInput {
+    hook_name: Option<String>,
+    task_id: String,
+    model: Option<String>,
+    #[serde(default)]
+    workspace_roots: Vec<String>,
+    files_written: Option<Vec<String>>,
+    pre_tool_use: Option<ClineToolUse>,
+    post_tool_use: Option<ClineToolUse>,
+    conversation_history_path: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClineToolUse {
+    #[serde(default)]
+    parameters: serde_json::Value,
+}
+
+impl AgentCheckpointPreset for ClinePreset {
+    fn run(&self, flags: AgentCheckpointFlags) -> Result<AgentRunResult, GitAiError> {
+        ClinePreset::run_for_extension(flags, "cline", "saoudrizwan.claude-dev")
+    }
+}
+
+impl AgentCheckpointPreset for RooCodePreset {
+    fn run(&self, flags: AgentCheckpointFlags) -> Result<AgentRunResult, GitAiError> {
+        ClinePreset::run_for_extension(flags, "roo-code", "rooveterinaryinc.roo-cline")
+    }
+}
+
+impl ClinePreset {
+    fn run_for_extension(
+        flags: AgentCheckpointFlags,
+        tool: &str,
+        extension_id: &str,
+    ) -> Result<AgentRunResult, GitAiError> {
+        let hook_input_json = flags.hook_input.ok_or_else(|| {
+            GitAiError::PresetError(format!("hook_input is required for {} preset", tool))
+        })?;
+        let input: ClineHookInput = serde_json::from_str(&hook_input_json)
+            .map_err(|e| GitAiError::PresetError(format!("Invalid JSON in hook_input: {}", e)))?;
+
+        let repo_working_dir = input.workspace_roots.first().cloned();
+        let is_pre_tool_use = input.hook_name.as_deref() == Some("PreToolUse");
+        let tool_use = if is_pre_tool_use {
+            input.pre_tool_use.as_ref()
+        } else {
+            input.post_tool_use.as_ref()
+        };
+        let files = input.files_written.clone().or_else(|| {
+            tool_use
+                .and_then(|tool_use| tool_use.parameters.get("path"))
+                .and_then(|path| path.as_str())
+                .map(|path| vec![path.to_string()])
+        });
+
+        if is_pre_tool_use {
+            return AgentV1Input::Human(HumanEdit {
+                repo_working_dir,
+                will_edit_filepaths: files,
+            })
+            .into_run_result();
+        }
+
+        let history_path = input.conversation_history_path.or_else(|| {
+            ClinePreset::task_history_path(extension_id, &input.task_id)
+                .map(|path| path.to_string_lossy().to_string())
+        });
+        let (transcript, history_model) = match history_path
+            .as_deref()
+            .map(ClinePreset::transcript_and_model_from_cline_history)
+        {
+            Some(Ok(parsed)) => parsed,
+            Some(Err(e)) => {
+                eprintln!("[Warning] Failed to parse {} task history: {e}", tool);
+                (AiTranscript::new(), None)
+            }
+            None => (AiTranscript::new(), None),
+        };
+
+        let mut agent_metadata = HashMap::from([("session_id".to_string(), input.task_id.clone())]);
+        if let Some(path) = history_path {
+            agent_metadata.insert("transcript_path".to_string(), path);
+        }
+
+        AgentV1Input::AiAgent(AgentEdit {
+            repo_working_dir,
+            edited_filepaths: files,
+            transcript,
+            agent_name: tool.to_string(),
+            model: input.model.or(history_model).unwrap_or_default(),
+            conversation_id: input.task_id,
+            agent_metadata: Some(agent_metadata),
+            ..Default::default()
+        })
+        .into_run_result()
+    }
+
+    /// `api_conversation_history.json` for a task in VS Code's global storage,
+    /// if it exists
+    fn task_history_path(extension_id: &str, task_id: &str) -> Option<PathBuf> {
+        let config_dir = dirs::config_dir()?;
+        ["Code", "Code - Insiders", "VSCodium"]
+            .iter()
+            .map(|app| {
+                config_dir
+                    .join(app)
+                    .join("User")
+                    .join("globalStorage")
+                    .join(extension_id)
+                    .join("tasks")
+                    .join(task_id)
+                    .join("api_conversation_history.json")
+            })
+            .find(|path| path.exists())
+    }
+
+    /// Parse a Cline/Roo Code `api_conversation_history.json` (Anthropic-style
+    /// messages) into a transcript and the most recent model, if recorded
+    pub fn transcript_and_model_from_cline_history(
+        path: &str,
+    ) -> Result<(AiTranscript, Option<String>), GitAiError> {
+        let content = std::fs::read_to_string(path).map_err(GitAiError::IoError)?;
+        let history: Vec<serde_json::Value> =
+            serde_json::from_str(&content).map_err(GitAiError::JsonError)?;
+
+        let mut transcript = AiTranscript::new();
+        let mut model = None;
+        for message in &history {
+            let role = message.get("role").and_then(|v| v.as_str());
+            let timestamp = message
+                .get("ts")
+                .and_then(|v| v.as_i64())
+                .and_then(|ms| Utc.timestamp_millis_opt(ms).single())
+                .map(|ts| ts.to_rfc3339());
+            if let Some(id) = message
+                .get("modelInfo")
+                .and_then(|info| info.get("modelId"))
+                .and_then(|v| v.as_str())
+                .filter(|_| role == Some("assistant"))
+            {
+                model = Some(id.to_string());
+            }
+
+            let blocks = match message.get("content") {
+                Some(serde_json::Value::String(text)) => {
+                    vec![serde_json::json!({ "type": "text", "text": text })]
+                }
+                Some(serde_json::Value::Array(blocks)) => blocks.clone(),
+                _ => continue,
+            };
+            for block in blocks {
+                match block.get("type").and_then(|v| v.as_str()) {
+                    Some("text") => {
+                        let text = block
+                            .get("text")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or_default()
+                            .trim();
+                        // Cline appends editor state to every user turn
+                        if text.is_empty() || text.starts_with("<environment_details>") {
+                            continue;
+                        }
+                        let text = text.to_string();
+                        let timestamp = timestamp.clone();
+                        transcript.add_message(match role {
+                            Some("user") => Message::User { text, timestamp },
+                            _ => Message::Assistant { text, timestamp },
+                        });
+                    }
+                    Some("tool_use") => transcript.add_message(Message::ToolUse {
+                        name: block
+                            .get("name")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("unknown")
+                            .to_string(),
+                        input: block.get("input").cloned().unwrap_or_default(),
+                        timestamp: timestamp.clone(),
+                    }),
+                    // Tool results and images aren't kept
+                    _ => {}
+                }
+            }
+        }
+        Ok((transcript, model))
+    }
+}
+
 // Cursor to checkpoint preset
 pub struct CursorPreset;
 
+/// What the Cursor preset reads from Cursor's hook input
+#[derive(Debug, Deserialize)]
+struct CursorHookInput {
+    conversation_id: String,
+    workspace_roots: Vec<String>,
+    hook_event_name: String,
+    model: Option<String>,
+    file_path: Option<String>,
+}
+
 impl AgentCheckpointPreset for CursorPreset {
     fn run(&self, flags: AgentCheckpointFlags) -> Result<AgentRunResult, GitAiError> {
         // Parse hook_input JSON to extract workspace_roots and conversation_id
@@ -642,42 +1292,17 @@ impl AgentCheckpointPreset for CursorPreset {
             GitAiError::PresetError("hook_input is required for Cursor preset".to_string())
         })?;
 
-        let hook_data: serde_json::Value = serde_json::from_str(&hook_input_json)
+        let CursorHookInput {
+            conversation_id,
+            workspace_roots,
+            hook_event_name,
+            model,
+            file_path,
+        } = serde_json::from_str(&hook_input_json)
             .map_err(|e| GitAiError::PresetError(format!("Invalid JSON in hook_input: {}", e)))?;
 
-        // Extract conversation_id and workspace_roots from the JSON
-        let conversation_id = hook_data
-            .get("conversation_id")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| {
-                GitAiError::PresetError("conversation_id not found in hook_input".to_string())
-            })?
-            .to_string();
-
-        let workspace_roots = hook_data
-            .get("workspace_roots")
-            .and_then(|v| v.as_array())
-            .ok_or_else(|| {
-                GitAiError::PresetError("workspace_roots not found in hook_input".to_string())
-            })?
-            .iter()
-            .filter_map(|v| v.as_str().map(|s| s.to_string()))
-            .collect::<Vec<String>>();
-
-        let hook_event_name = hook_data
-            .get("hook_event_name")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| {
-                GitAiError::PresetError("hook_event_name not found in hook_input".to_string())
-            })?
-            .to_string();
-
-        // Extract model from hook input (Cursor provides this directly)
-        let model = hook_data
-            .get("model")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string())
-            .unwrap_or_else(|| "unknown".to_string());
+        // Cursor provides the model directly
+        let model = model.unwrap_or_else(|| "unknown".to_string());
 
         // Validate hook_event_name
         if hook_event_name != "beforeSubmitPrompt" && hook_event_name != "afterFileEdit" {
@@ -706,6 +1331,9 @@ impl AgentCheckpointPreset for CursorPreset {
                 edited_filepaths: None,
                 will_edit_filepaths: None,
                 dirty_files: None,
+                commit_message: None,
+                edited_line_ranges: None,
+                append_transcript: false,
             });
         }
 
@@ -750,14 +1378,9 @@ impl AgentCheckpointPreset for CursorPreset {
         };
 
         // Extract edited filepaths
-        let mut edited_filepaths: Option<Vec<String>> = None;
-        let file_path = hook_data
-            .get("file_path")
-            .and_then(|v| v.as_str())
-            .unwrap_or("");
-        if !file_path.is_empty() {
-            edited_filepaths = Some(vec![file_path.to_string()]);
-        }
+        let edited_filepaths = file_path
+            .filter(|file_path| !file_path.is_empty())
+            .map(|file_path| vec![file_path]);
 
         let agent_id = AgentId {
             tool: "cursor".to_string(),
@@ -774,6 +1397,9 @@ impl AgentCheckpointPreset for CursorPreset {
             edited_filepaths,
             will_edit_filepaths: None,
             dirty_files: None,
+            commit_message: None,
+            edited_line_ranges: None,
+            append_transcript: false,
         })
     }
 }
@@ -802,7 +1428,7 @@ impl CursorPreset {
     }
 
     // Get the Cursor database path
-    fn cursor_global_database_path() -> Result<PathBuf, GitAiError> {
+    pub fn cursor_global_database_path() -> Result<PathBuf, GitAiError> {
         if let Ok(global_db_path) = std::env::var("GIT_AI_CURSOR_GLOBAL_DB_PATH") {
             return Ok(PathBuf::from(global_db_path));
         }
@@ -845,6 +1471,23 @@ impl CursorPreset {
             .map_err(|e| GitAiError::Generic(format!("Failed to open {:?}: {}", path, e)))
     }
 
+    /// List the IDs of every composer (chat session) stored in the database
+    pub fn list_composer_ids(global_db_path: &Path) -> Result<Vec<String>, GitAiError> {
+        let conn = Self::open_sqlite_readonly(global_db_path)?;
+        let mut stmt = conn
+            .prepare("SELECT key FROM cursorDiskKV WHERE key LIKE 'composerData:%'")
+            .map_err(|e| GitAiError::Generic(format!("Query failed: {}", e)))?;
+
+        let keys = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| GitAiError::Generic(format!("Query failed: {}", e)))?;
+
+        Ok(keys
+            .filter_map(|key| key.ok())
+            .filter_map(|key| key.strip_prefix("composerData:").map(str::to_string))
+            .collect())
+    }
+
     pub fn fetch_composer_payload(
         global_db_path: &Path,
         composer_id: &str,
@@ -1029,6 +1672,34 @@ impl CursorPreset {
 
 pub struct GithubCopilotPreset;
 
+/// What the GitHub Copilot preset reads from the VS Code extension's hook
+/// input. Older extension versions send camelCase names, which are still
+/// accepted as fallbacks.
+#[derive(Debug, Deserialize)]
+struct GithubCopilotHookInput {
+    hook_event_name: Option<String>,
+    workspace_folder: Option<String>,
+    #[serde(rename = "workspaceFolder")]
+    workspace_folder_camel: Option<String>,
+    dirty_files: Option<HashMap<String, String>>,
+    #[serde(rename = "dirtyFiles")]
+    dirty_files_camel: Option<HashMap<String, String>>,
+    will_edit_filepaths: Option<Vec<String>>,
+    edited_filepaths: Option<Vec<String>>,
+    chat_session_path: Option<String>,
+    #[serde(rename = "chatSessionPath")]
+    chat_session_path_camel: Option<String>,
+    chat_session_id: Option<String>,
+    session_id: Option<String>,
+    #[serde(rename = "chatSessionId")]
+    chat_session_id_camel: Option<String>,
+    #[serde(rename = "sessionId")]
+    session_id_camel: Option<String>,
+    edited_line_ranges: Option<serde_json::Value>,
+    #[serde(rename = "editedLineRanges")]
+    edited_line_ranges_camel: Option<serde_json::Value>,
+}
+
 impl AgentCheckpointPreset for GithubCopilotPreset {
     fn run(&self, flags: AgentCheckpointFlags) -> Result<AgentRunResult, GitAiError> {
         // Parse hook_input JSON to extract chat session information
@@ -1036,14 +1707,14 @@ impl AgentCheckpointPreset for GithubCopilotPreset {
             GitAiError::PresetError("hook_input is required for GitHub Copilot preset".to_string())
         })?;
 
-        let hook_data: serde_json::Value = serde_json::from_str(&hook_input_json)
+        let hook_input: GithubCopilotHookInput = serde_json::from_str(&hook_input_json)
             .map_err(|e| GitAiError::PresetError(format!("Invalid JSON in hook_input: {}", e)))?;
 
         // Extract hook_event_name to determine checkpoint type
         // Fallback to "after_edit" if not set (for older versions of the VS Code extension)
-        let hook_event_name = hook_data
-            .get("hook_event_name")
-            .and_then(|v| v.as_str())
+        let hook_event_name = hook_input
+            .hook_event_name
+            .as_deref()
             .unwrap_or("after_edit");
 
         // Validate hook_event_name
@@ -1056,49 +1727,30 @@ impl AgentCheckpointPreset for GithubCopilotPreset {
 
         // Required working directory provided by the extension
         // Accept snake_case (new) with fallback to camelCase (old) for backward compatibility
-        let repo_working_dir: String = hook_data
-            .get("workspace_folder")
-            .and_then(|v| v.as_str())
-            .or_else(|| hook_data.get("workspaceFolder").and_then(|v| v.as_str()))
+        let repo_working_dir: String = hook_input
+            .workspace_folder
+            .clone()
+            .or_else(|| hook_input.workspace_folder_camel.clone())
             .ok_or_else(|| {
                 GitAiError::PresetError(
                     "workspace_folder or workspaceFolder not found in hook_input for GitHub Copilot preset".to_string(),
                 )
-            })?
-            .to_string();
+            })?;
 
         // Extract dirty_files if available (snake_case with fallback to camelCase)
-        let dirty_files = hook_data
-            .get("dirty_files")
-            .and_then(|v| v.as_object())
-            .or_else(|| hook_data.get("dirtyFiles").and_then(|v| v.as_object()))
-            .map(|obj| {
-                obj.iter()
-                    .filter_map(|(key, value)| {
-                        value
-                            .as_str()
-                            .map(|content| (key.clone(), content.to_string()))
-                    })
-                    .collect::<HashMap<String, String>>()
-            });
+        let dirty_files = hook_input
+            .dirty_files
+            .clone()
+            .or_else(|| hook_input.dirty_files_camel.clone());
 
         // Handle before_edit (human checkpoint)
         if hook_event_name == "before_edit" {
             // Extract will_edit_filepaths (required for human checkpoints)
-            let will_edit_filepaths = hook_data
-                .get("will_edit_filepaths")
-                .and_then(|v| v.as_array())
-                .map(|arr| {
-                    arr.iter()
-                        .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                        .collect::<Vec<String>>()
-                })
-                .ok_or_else(|| {
-                    GitAiError::PresetError(
-                        "will_edit_filepaths is required for before_edit hook_event_name"
-                            .to_string(),
-                    )
-                })?;
+            let will_edit_filepaths = hook_input.will_edit_filepaths.clone().ok_or_else(|| {
+                GitAiError::PresetError(
+                    "will_edit_filepaths is required for before_edit hook_event_name".to_string(),
+                )
+            })?;
 
             if will_edit_filepaths.is_empty() {
                 return Err(GitAiError::PresetError(
@@ -1120,15 +1772,18 @@ impl AgentCheckpointPreset for GithubCopilotPreset {
                 edited_filepaths: None,
                 will_edit_filepaths: Some(will_edit_filepaths),
                 dirty_files,
+                commit_message: None,
+                edited_line_ranges: None,
+                append_transcript: false,
             });
         }
 
         // Handle after_edit (AI checkpoint)
         // Accept snake_case (new) with fallback to camelCase (old) for backward compatibility
-        let chat_session_path = hook_data
-            .get("chat_session_path")
-            .and_then(|v| v.as_str())
-            .or_else(|| hook_data.get("chatSessionPath").and_then(|v| v.as_str()))
+        let chat_session_path = hook_input
+            .chat_session_path
+            .as_deref()
+            .or(hook_input.chat_session_path_camel.as_deref())
             .ok_or_else(|| {
                 GitAiError::PresetError(
                     "chat_session_path or chatSessionPath not found in hook_input for after_edit"
@@ -1136,32 +1791,20 @@ impl AgentCheckpointPreset for GithubCopilotPreset {
                 )
             })?;
 
-        let agent_metadata = HashMap::from([(
-            "chat_session_path".to_string(),
-            chat_session_path.to_string(),
-        )]);
-
         // Accept snake_case (new) with fallback to camelCase (old) for backward compatibility
         // Accept either chat_session_id/session_id (new) or chatSessionId/sessionId (old)
-        let chat_session_id = hook_data
-            .get("chat_session_id")
-            .and_then(|v| v.as_str())
-            .or_else(|| hook_data.get("session_id").and_then(|v| v.as_str()))
-            .or_else(|| hook_data.get("chatSessionId").and_then(|v| v.as_str()))
-            .or_else(|| hook_data.get("sessionId").and_then(|v| v.as_str()))
+        let chat_session_id = hook_input
+            .chat_session_id
+            .as_deref()
+            .or(hook_input.session_id.as_deref())
+            .or(hook_input.chat_session_id_camel.as_deref())
+            .or(hook_input.session_id_camel.as_deref())
             .unwrap_or("unknown")
             .to_string();
 
         // TODO Make edited_filepaths required in future versions (after old extensions are updated)
         // Optionally take edited_filepaths from hook_data if present (from extension)
-        let edited_filepaths = hook_data
-            .get("edited_filepaths")
-            .and_then(|val| val.as_array())
-            .map(|arr| {
-                arr.iter()
-                    .filter_map(|v| v.as_str().map(str::to_string))
-                    .collect::<Vec<String>>()
-            });
+        let edited_filepaths = hook_input.edited_filepaths.clone();
 
         // Read the Copilot chat session JSON (ignore errors)
         let (transcript, detected_model, detected_edited_filepaths) =
@@ -1177,6 +1820,14 @@ impl AgentCheckpointPreset for GithubCopilotPreset {
                     (None, None, None)
                 });
 
+        let agent_metadata = HashMap::from([
+            (
+                "chat_session_path".to_string(),
+                chat_session_path.to_string(),
+            ),
+            ("session_id".to_string(), chat_session_id.clone()),
+        ]);
+
         let agent_id = AgentId {
             tool: "github-copilot".to_string(),
             id: chat_session_id,
@@ -1193,10 +1844,23 @@ impl AgentCheckpointPreset for GithubCopilotPreset {
             edited_filepaths: edited_filepaths.or_else(|| detected_edited_filepaths),
             will_edit_filepaths: None,
             dirty_files,
+            commit_message: None,
+            edited_line_ranges: edited_line_ranges(
+                hook_input
+                    .edited_line_ranges
+                    .or(hook_input.edited_line_ranges_camel),
+            ),
+            append_transcript: false,
         })
     }
 }
 
+/// `edited_line_ranges` (or `editedLineRanges`) from hook input:
+/// `{"path": [[start, end], ...]}`. Malformed ranges are ignored.
+fn edited_line_ranges(ranges: Option<serde_json::Value>) -> Option<EditedLineRanges> {
+    serde_json::from_value(ranges?).ok()
+}
+
 impl GithubCopilotPreset {
     /// Translate a GitHub Copilot chat session JSON file into an AiTranscript, optional model, and edited filepaths.
     /// Returns an empty transcript if running in Codespaces or Remote Containers.
@@ -1511,6 +2175,9 @@ impl AgentCheckpointPreset for AiTabPreset {
                 edited_filepaths: None,
                 will_edit_filepaths,
                 dirty_files,
+                commit_message: None,
+                edited_line_ranges: None,
+                append_transcript: false,
             });
         }
 
@@ -1523,6 +2190,220 @@ impl AgentCheckpointPreset for AiTabPreset {
             edited_filepaths,
             will_edit_filepaths: None,
             dirty_files,
+            commit_message: None,
+            edited_line_ranges: None,
+            append_transcript: false,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_aider_chat_history() {
+        let history = "\
+# aider chat started at 2024-05-01 09:00:00
+
+#### an older session
+
+# aider chat started at 2024-05-02 10:00:00
+
+> /usr/local/bin/aider --model sonnet
+> Model: claude-3-5-sonnet-20240620 with diff edit format
+
+#### add a hello function
+#### to hello.py
+
+Here is the change:
+
+hello.py
+> Applied edit to hello.py
+> Commit 1a2b3c4 feat: add hello function
+";
+        let session = AiderPreset::parse_chat_history(history);
+        assert_eq!(session.started_at.as_deref(), Some("2024-05-02 10:00:00"));
+        assert_eq!(session.model.as_deref(), Some("claude-3-5-sonnet-20240620"));
+        assert_eq!(session.edited_files, vec!["hello.py".to_string()]);
+
+        let messages = session.transcript.messages();
+        assert_eq!(messages.len(), 3);
+        assert!(
+            matches!(&messages[0], Message::User { text, .. } if text == "add a hello function\nto hello.py")
+        );
+        assert!(
+            matches!(&messages[1], Message::Assistant { text, .. } if text == "Here is the change:\n\nhello.py")
+        );
+        assert!(matches!(&messages[2], Message::ToolUse { name, .. } if name == "edit"));
+    }
+
+    #[test]
+    fn test_windsurf_post_write_code() {
+        let input = r#"{
+            "agent_action_name": "post_write_code",
+            "trajectory_id": "traj-1",
+            "execution_id": "exec-1",
+            "tool_info": {
+                "file_path": "/work/project/src/main.rs",
+                "edits": [{"old_string": "a", "new_string": "b"}]
+            },
+            "model_name": "SWE-1"
+        }"#;
+        let result = WindsurfPreset
+            .run(AgentCheckpointFlags {
+                hook_input: Some(input.to_string()),
+            })
+            .unwrap();
+        assert_eq!(result.checkpoint_kind, CheckpointKind::AiAgent);
+        assert_eq!(result.agent_id.tool, "windsurf");
+        assert_eq!(result.agent_id.id, "traj-1");
+        assert_eq!(result.agent_id.model, "SWE-1");
+        assert_eq!(
+            result.repo_working_dir.as_deref(),
+            Some("/work/project/src")
+        );
+        assert_eq!(
+            result.edited_filepaths,
+            Some(vec!["/work/project/src/main.rs".to_string()])
+        );
+        assert_eq!(result.transcript.unwrap().messages().len(), 1);
+
+        let input = r#"{
+            "agent_action_name": "pre_write_code",
+            "tool_info": {"file_path": "/work/project/src/main.rs"},
+            "workspace_root": "/work/project"
+        }"#;
+        let result = WindsurfPreset
+            .run(AgentCheckpointFlags {
+                hook_input: Some(input.to_string()),
+            })
+            .unwrap();
+        assert_eq!(result.checkpoint_kind, CheckpointKind::Human);
+        assert_eq!(result.repo_working_dir.as_deref(), Some("/work/project"));
+
+        let bad = r#"{"agent_action_name": "pre_user_prompt"}"#;
+        assert!(
+            WindsurfPreset
+                .run(AgentCheckpointFlags {
+                    hook_input: Some(bad.to_string()),
+                })
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_webhook_takes_agent_v1_input() {
+        let webhook = builtin_preset("webhook").unwrap();
+        let input = r#"{
+            "type": "ai_agent",
+            "agent_name": "acme-agent",
+            "conversation_id": "run-42",
+            "repo_working_dir": "/work/project",
+            "edited_filepaths": ["src/lib.rs"],
+            "transcript": {"messages": [
+                {"type": "user", "text": "add a parser"},
+                {"type": "assistant", "text": "done"}
+            ]}
+        }"#;
+        let result = webhook
+            .preset
+            .run(AgentCheckpointFlags {
+                hook_input: Some(input.to_string()),
+            })
+            .unwrap();
+        assert_eq!(result.checkpoint_kind, CheckpointKind::AiAgent);
+        assert_eq!(result.agent_id.tool, "acme-agent");
+        assert_eq!(result.agent_id.id, "run-42");
+        assert_eq!(result.agent_id.model, "unknown");
+        assert_eq!(result.repo_working_dir.as_deref(), Some("/work/project"));
+        assert_eq!(result.transcript.unwrap().messages().len(), 2);
+
+        let input = r#"{"type": "human", "will_edit_filepaths": ["src/lib.rs"]}"#;
+        let result = webhook
+            .preset
+            .run(AgentCheckpointFlags {
+                hook_input: Some(input.to_string()),
+            })
+            .unwrap();
+        assert_eq!(result.checkpoint_kind, CheckpointKind::Human);
+        assert_eq!(result.repo_working_dir, None);
+
+        for bad in [
+            r#"{"type": "ai_agent", "agent_name": "", "conversation_id": "x"}"#,
+            r#"{"type": "ai_agent", "agent_name": "acme", "conversation_id": " "}"#,
+            r#"{"type": "ai_agent", "agent_name": "acme", "conversation_id": "x", "kind": "human"}"#,
+        ] {
+            assert!(
+                webhook
+                    .preset
+                    .run(AgentCheckpointFlags {
+                        hook_input: Some(bad.to_string()),
+                    })
+                    .is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_cline_task_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let history_path = dir.path().join("api_conversation_history.json");
+        std::fs::write(
+            &history_path,
+            r#"[
+                {"role": "user", "content": [
+                    {"type": "text", "text": "<task>\nadd a health check\n</task>"},
+                    {"type": "text", "text": "<environment_details>\n# VSCode Open Tabs\n</environment_details>"}
+                ], "ts": 1730000000000},
+                {"role": "assistant", "content": [
+                    {"type": "text", "text": "I'll add the endpoint."},
+                    {"type": "tool_use", "name": "write_to_file", "input": {"path": "src/health.rs"}}
+                ], "modelInfo": {"modelId": "claude-sonnet-4", "providerId": "anthropic"}},
+                {"role": "user", "content": [{"type": "tool_result", "content": "ok"}]}
+            ]"#,
+        )
+        .unwrap();
+
+        let input = serde_json::json!({
+            "hookName": "PostToolUse",
+            "taskId": "1730000000000",
+            "workspaceRoots": ["/work/service"],
+            "postToolUse": {"toolName": "write_to_file", "parameters": {"path": "src/health.rs"}},
+            "conversationHistoryPath": history_path.to_string_lossy(),
+        });
+        let result = ClinePreset
+            .run(AgentCheckpointFlags {
+                hook_input: Some(input.to_string()),
+            })
+            .unwrap();
+        assert_eq!(result.checkpoint_kind, CheckpointKind::AiAgent);
+        assert_eq!(result.agent_id.tool, "cline");
+        assert_eq!(result.agent_id.id, "1730000000000");
+        assert_eq!(result.agent_id.model, "claude-sonnet-4");
+        assert_eq!(result.repo_working_dir.as_deref(), Some("/work/service"));
+        assert_eq!(
+            result.edited_filepaths,
+            Some(vec!["src/health.rs".to_string()])
+        );
+        let messages = result.transcript.unwrap().messages().to_vec();
+        assert_eq!(messages.len(), 3);
+        assert!(
+            matches!(&messages[0], Message::User { text, timestamp: Some(_) } if text.contains("add a health check"))
+        );
+        assert!(matches!(&messages[2], Message::ToolUse { name, .. } if name == "write_to_file"));
+
+        let input = serde_json::json!({
+            "hookName": "PreToolUse",
+            "taskId": "t2",
+            "filesWritten": ["a.rs"],
+        });
+        let result = RooCodePreset
+            .run(AgentCheckpointFlags {
+                hook_input: Some(input.to_string()),
+            })
+            .unwrap();
+        assert_eq!(result.checkpoint_kind, CheckpointKind::Human);
+        assert_eq!(result.will_edit_filepaths, Some(vec!["a.rs".to_string()]));
+    }
+}
@@ -1,91 +1,196 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use crate::{
     authorship::{
         transcript::AiTranscript,
         working_log::{AgentId, CheckpointKind},
     },
-    commands::checkpoint_agent::agent_presets::{AgentCheckpointPreset, AgentRunResult},
+    commands::checkpoint_agent::agent_presets::{
+        AgentCheckpointPreset, AgentRunResult, EditedLineRanges, ignored_fields,
+    },
 };
 
 pub struct AgentV1Preset;
 
+/// The agent-v1 hook input. Presets for agents with their own hook format
+/// translate it into this and share [`AgentV1Input::into_run_result`].
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
-enum AgentV1Input {
-    Human {
-        repo_working_dir: String,
-        will_edit_filepaths: Option<Vec<String>>,
-    },
-    AiAgent {
-        repo_working_dir: String,
-        edited_filepaths: Option<Vec<String>>,
-        transcript: AiTranscript,
-        agent_name: String,
-        model: String,
-        conversation_id: String,
-    },
+pub enum AgentV1Input {
+    Human(HumanEdit),
+    AiAgent(AgentEdit),
     // AiTab
 }
 
-impl AgentCheckpointPreset for AgentV1Preset {
-    fn run(
-        &self,
-        flags: super::agent_presets::AgentCheckpointFlags,
-    ) -> Result<super::agent_presets::AgentRunResult, crate::error::GitAiError> {
-        // Parse hook_input as AgentV1Input, error if it's not valid
-        let hook_input_json = flags.hook_input.ok_or_else(|| {
-            crate::error::GitAiError::PresetError(
-                "--hook-input is required for AgentV1 preset".to_string(),
-            )
-        })?;
+/// The human's pending changes to files an agent is about to edit
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HumanEdit {
+    pub repo_working_dir: Option<String>,
+    pub will_edit_filepaths: Option<Vec<String>>,
+}
 
-        let agent_v1_input: AgentV1Input = serde_json::from_str(&hook_input_json).map_err(|e| {
-            crate::error::GitAiError::PresetError(format!(
-                "Invalid AgentV1Input JSON. Format is documented here: https://github.com/acunniffe/git-ai/blob/main/docs/add-your-agent.mdx: \n\n Error: {}",
-                e
-            ))
-        })?;
+/// An edit an agent made
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AgentEdit {
+    pub repo_working_dir: Option<String>,
+    pub edited_filepaths: Option<Vec<String>>,
+    #[serde(default)]
+    pub transcript: AiTranscript,
+    pub agent_name: String,
+    #[serde(default)]
+    pub model: String,
+    pub conversation_id: String,
+    /// Optional details such as `session_id`, `conversation_url`,
+    /// `ide_version`, `provider`, `temperature` and this invocation's
+    /// `prompt_tokens`/`completion_tokens`; the recognised keys end up in
+    /// the authorship log, with token counts summed over the session
+    #[serde(default)]
+    pub agent_metadata: Option<HashMap<String, String>>,
+    /// `ai_agent` (default), `ai_tab` or `ai_refactor`, so tab-complete
+    /// assists and refactoring tools can be reported apart from agent sessions
+    #[serde(default)]
+    pub kind: Option<String>,
+    /// Commit message the agent drafted for the upcoming commit
+    #[serde(default)]
+    pub commit_message: Option<String>,
+    /// Lines the agent changed in each file, `{"path": [[start, end], ...]}`
+    #[serde(default)]
+    pub edited_line_ranges: Option<EditedLineRanges>,
+    /// `transcript` only holds the messages since this conversation's last
+    /// invocation, for runs too long to resend in full every time. Without
+    /// `edited_filepaths` the invocation just adds messages.
+    #[serde(default)]
+    pub append_transcript: bool,
+}
 
-        match agent_v1_input {
-            AgentV1Input::Human {
+impl AgentV1Input {
+    pub fn into_run_result(self) -> Result<AgentRunResult, crate::error::GitAiError> {
+        match self {
+            AgentV1Input::Human(HumanEdit {
                 repo_working_dir,
                 will_edit_filepaths,
-            } => Ok(AgentRunResult {
+            }) => Ok(AgentRunResult {
                 agent_id: AgentId {
                     tool: "human".to_string(),
                     id: "human".to_string(),
                     model: "human".to_string(),
                 },
                 agent_metadata: None,
-                will_edit_filepaths: will_edit_filepaths,
+                will_edit_filepaths,
                 checkpoint_kind: CheckpointKind::Human,
                 transcript: None,
-                repo_working_dir: Some(repo_working_dir),
+                repo_working_dir: non_empty(repo_working_dir),
                 edited_filepaths: None,
                 dirty_files: None,
+                commit_message: None,
+                edited_line_ranges: None,
+                append_transcript: false,
             }),
-            AgentV1Input::AiAgent {
+            AgentV1Input::AiAgent(AgentEdit {
                 edited_filepaths,
                 transcript,
                 agent_name,
                 model,
                 conversation_id,
                 repo_working_dir,
-            } => Ok(AgentRunResult {
-                agent_id: AgentId {
-                    tool: agent_name,
-                    id: conversation_id,
-                    model,
-                },
-                agent_metadata: None,
-                repo_working_dir: Some(repo_working_dir),
-                transcript: Some(transcript),
-                checkpoint_kind: CheckpointKind::AiAgent,
-                edited_filepaths: edited_filepaths,
-                will_edit_filepaths: None,
-                dirty_files: None,
-            }),
+                agent_metadata,
+                kind,
+                commit_message,
+                edited_line_ranges,
+                append_transcript,
+            }) => {
+                let agent_name = agent_name.trim().to_string();
+                if agent_name.is_empty() || agent_name == "human" {
+                    return Err(crate::error::GitAiError::PresetError(
+                        "agent_name must be non-empty and not 'human'".to_string(),
+                    ));
+                }
+                let conversation_id = conversation_id.trim().to_string();
+                if conversation_id.is_empty() {
+                    return Err(crate::error::GitAiError::PresetError(
+                        "conversation_id must be non-empty".to_string(),
+                    ));
+                }
+                Ok(AgentRunResult {
+                    agent_id: AgentId {
+                        tool: agent_name,
+                        id: conversation_id,
+                        model: non_empty(Some(model)).unwrap_or_else(|| "unknown".to_string()),
+                    },
+                    agent_metadata,
+                    repo_working_dir: non_empty(repo_working_dir),
+                    transcript: Some(transcript),
+                    checkpoint_kind: agent_checkpoint_kind(kind.as_deref())?,
+                    edited_filepaths,
+                    will_edit_filepaths: None,
+                    dirty_files: None,
+                    commit_message,
+                    edited_line_ranges,
+                    append_transcript,
+                })
+            }
         }
     }
 }
+
+impl AgentV1Input {
+    /// Top-level fields the input's `type` doesn't read. The tagged enum hides
+    /// them from serde_ignored, so the variant's struct is checked directly.
+    pub fn ignored_fields(hook_input: &str) -> Result<Vec<String>, serde_json::Error> {
+        let mut input: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(hook_input)?;
+        let kind = input.remove("type");
+        let fields = serde_json::Value::Object(input).to_string();
+        match kind.as_ref().and_then(serde_json::Value::as_str) {
+            Some("human") => ignored_fields::<HumanEdit>(&fields),
+            Some("ai_agent") => ignored_fields::<AgentEdit>(&fields),
+            // Let serde describe the missing or unknown tag
+            _ => serde_json::from_str::<AgentV1Input>(hook_input).map(|_| Vec::new()),
+        }
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+impl AgentCheckpointPreset for AgentV1Preset {
+    fn run(
+        &self,
+        flags: super::agent_presets::AgentCheckpointFlags,
+    ) -> Result<super::agent_presets::AgentRunResult, crate::error::GitAiError> {
+        // Parse hook_input as AgentV1Input, error if it's not valid
+        let hook_input_json = flags.hook_input.ok_or_else(|| {
+            crate::error::GitAiError::PresetError(
+                "--hook-input is required for AgentV1 preset".to_string(),
+            )
+        })?;
+
+        let agent_v1_input: AgentV1Input = serde_json::from_str(&hook_input_json).map_err(|e| {
+            crate::error::GitAiError::PresetError(format!(
+                "Invalid AgentV1Input JSON. Format is documented here: https://github.com/acunniffe/git-ai/blob/main/docs/add-your-agent.mdx: \n\n Error: {}",
+                e
+            ))
+        })?;
+
+        agent_v1_input.into_run_result()
+    }
+}
+
+fn agent_checkpoint_kind(kind: Option<&str>) -> Result<CheckpointKind, crate::error::GitAiError> {
+    match kind.map(CheckpointKind::parse) {
+        None => Ok(CheckpointKind::AiAgent),
+        Some(Some(
+            kind @ (CheckpointKind::AiAgent
+            | CheckpointKind::AiTab
+            | CheckpointKind::AiRefactorTool),
+        )) => Ok(kind),
+        Some(_) => Err(crate::error::GitAiError::PresetError(format!(
+            "Invalid AgentV1Input kind '{}': expected ai_agent, ai_tab or ai_refactor",
+            kind.unwrap_or_default()
+        ))),
+    }
+}
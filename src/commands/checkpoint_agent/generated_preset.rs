@@ -0,0 +1,92 @@
+use crate::{
+    authorship::working_log::{AgentId, CheckpointKind},
+    commands::checkpoint_agent::agent_presets::AgentRunResult,
+    error::GitAiError,
+};
+
+/// `git-ai checkpoint generated --tool <generator> [pathspecs...]`
+///
+/// Run after a code generator writes its output. The changed lines are
+/// attributed to the generator, so they count as neither AI nor human.
+pub struct GeneratedPreset;
+
+impl GeneratedPreset {
+    /// `args` are the checkpoint arguments after `generated`
+    pub fn run(&self, args: &[String]) -> Result<AgentRunResult, GitAiError> {
+        let mut tool = None;
+        let mut paths = Vec::new();
+        let mut i = 0;
+        while i < args.len() {
+            match args[i].as_str() {
+                "--tool" => {
+                    tool = args.get(i + 1).filter(|t| !t.trim().is_empty()).cloned();
+                    i += 1;
+                }
+                // `--hook-input <value>` is handled by the checkpoint command
+                "--hook-input" => i += 1,
+                arg if arg.starts_with("--") => {}
+                path => paths.push(path.to_string()),
+            }
+            i += 1;
+        }
+        let tool = tool.ok_or_else(|| {
+            GitAiError::PresetError(
+                "--tool <name> is required for generated checkpoints (e.g. --tool protoc)"
+                    .to_string(),
+            )
+        })?;
+
+        Ok(AgentRunResult {
+            // One stable id per generator, so every run shares a single record
+            agent_id: AgentId {
+                tool: tool.clone(),
+                id: tool,
+                model: "generated".to_string(),
+            },
+            agent_metadata: None,
+            checkpoint_kind: CheckpointKind::Generated,
+            transcript: None,
+            repo_working_dir: None,
+            edited_filepaths: if paths.is_empty() { None } else { Some(paths) },
+            will_edit_filepaths: None,
+            dirty_files: None,
+            commit_message: None,
+            edited_line_ranges: None,
+            append_transcript: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_generated_preset() {
+        let result = GeneratedPreset
+            .run(&args(&[
+                "--tool",
+                "protoc",
+                "gen/api.pb.go",
+                "gen/api_grpc.pb.go",
+            ]))
+            .unwrap();
+        assert_eq!(result.checkpoint_kind, CheckpointKind::Generated);
+        assert_eq!(result.agent_id.tool, "protoc");
+        assert_eq!(result.agent_id.id, "protoc");
+        assert_eq!(
+            result.edited_filepaths,
+            Some(args(&["gen/api.pb.go", "gen/api_grpc.pb.go"]))
+        );
+
+        let all_files = GeneratedPreset.run(&args(&["--tool", "sqlc"])).unwrap();
+        assert_eq!(all_files.edited_filepaths, None);
+
+        assert!(GeneratedPreset.run(&args(&["gen/api.pb.go"])).is_err());
+        assert!(GeneratedPreset.run(&args(&["--tool"])).is_err());
+    }
+}
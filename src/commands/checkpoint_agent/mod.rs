@@ -1,2 +1,4 @@
 pub mod agent_presets;
 pub mod agent_v1_preset;
+pub mod generated_preset;
+pub mod plugin_preset;
@@ -0,0 +1,178 @@
+use crate::{
+    commands::checkpoint_agent::agent_presets::{
+        AgentCheckpointFlags, AgentCheckpointPreset, AgentRunResult,
+    },
+    error::GitAiError,
+};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+
+/// A third-party preset from the `preset_plugins` section of ~/.git-ai/config.json,
+/// keyed by the name used on the command line (`git-ai checkpoint <name>`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PresetPlugin {
+    /// Executable to run; the hook input is written to its stdin and an
+    /// `AgentRunResult` JSON document is read back from its stdout
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Seconds to wait before the plugin is killed
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+/// Runs a configured plugin in place of a built-in preset. Built-in preset
+/// names always win, so a plugin can't shadow e.g. `claude`.
+pub struct PluginPreset<'a> {
+    pub name: &'a str,
+    pub plugin: &'a PresetPlugin,
+}
+
+impl AgentCheckpointPreset for PluginPreset<'_> {
+    fn run(&self, flags: AgentCheckpointFlags) -> Result<AgentRunResult, GitAiError> {
+        let mut cmd = Command::new(&self.plugin.command);
+        cmd.args(&self.plugin.args)
+            .env("GIT_AI_PRESET", self.name)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit());
+        let timeout = Duration::from_secs(self.plugin.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+        let stdout =
+            run_plugin(cmd, flags.hook_input.as_deref().unwrap_or(""), timeout).map_err(|e| {
+                GitAiError::PresetError(format!(
+                    "Preset plugin '{}' ({}) failed: {}",
+                    self.name, self.plugin.command, e
+                ))
+            })?;
+
+        serde_json::from_slice(&stdout).map_err(|e| {
+            GitAiError::PresetError(format!(
+                "Preset plugin '{}' printed invalid AgentRunResult JSON: {}",
+                self.name, e
+            ))
+        })
+    }
+}
+
+pub(crate) fn run_plugin(
+    mut cmd: Command,
+    input: &str,
+    timeout: Duration,
+) -> Result<Vec<u8>, String> {
+    let mut child = cmd.spawn().map_err(|e| e.to_string())?;
+
+    // Drain stdout on a thread so a large result can't fill the pipe and stall the plugin
+    let mut stdout = child.stdout.take().ok_or("stdout not captured")?;
+    let reader = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        stdout.read_to_end(&mut buf).map(|_| buf)
+    });
+    // Written from another thread too, so a plugin that never reads its
+    // input is still held to the deadline. The thread isn't joined: it
+    // finishes when the pipe closes.
+    if let Some(mut stdin) = child.stdin.take() {
+        let input = input.to_string();
+        std::thread::spawn(move || {
+            // A plugin that ignores its input closes the pipe early; that's fine
+            let _ = stdin.write_all(input.as_bytes());
+        });
+    }
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait().map_err(|e| e.to_string())? {
+            Some(status) if status.success() => break,
+            Some(status) => return Err(format!("exited with {}", status)),
+            None if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("timed out after {:?}", timeout));
+            }
+            None => std::thread::sleep(Duration::from_millis(20)),
+        }
+    }
+
+    reader
+        .join()
+        .map_err(|_| "failed to read stdout".to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::working_log::CheckpointKind;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_plugin_result_is_read_from_stdout() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("preset.sh");
+        let input_copy = dir.path().join("input.json");
+        std::fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\ncat > {}\necho '{}'\n",
+                input_copy.display(),
+                r#"{"agent_id":{"tool":"acme","id":"s1","model":"m"},"checkpoint_kind":"AiAgent","edited_filepaths":["src/lib.rs"]}"#
+            ),
+        )
+        .unwrap();
+        let plugin = PresetPlugin {
+            command: "sh".to_string(),
+            args: vec![script.display().to_string()],
+            timeout_secs: None,
+        };
+
+        let result = PluginPreset {
+            name: "acme",
+            plugin: &plugin,
+        }
+        .run(AgentCheckpointFlags {
+            hook_input: Some(r#"{"session":"s1"}"#.to_string()),
+        })
+        .unwrap();
+        assert_eq!(result.agent_id.tool, "acme");
+        assert_eq!(result.checkpoint_kind, CheckpointKind::AiAgent);
+        assert_eq!(
+            result.edited_filepaths,
+            Some(vec!["src/lib.rs".to_string()])
+        );
+        assert!(result.transcript.is_none());
+        assert_eq!(
+            std::fs::read_to_string(&input_copy).unwrap(),
+            r#"{"session":"s1"}"#
+        );
+
+        let failing = PresetPlugin {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), "echo not-json".to_string()],
+            timeout_secs: None,
+        };
+        assert!(
+            PluginPreset {
+                name: "broken",
+                plugin: &failing,
+            }
+            .run(AgentCheckpointFlags { hook_input: None })
+            .is_err()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_plugin_ignoring_large_input_still_times_out() {
+        let mut cmd = Command::new("sleep");
+        cmd.arg("5").stdin(Stdio::piped()).stdout(Stdio::piped());
+        let input = "x".repeat(1 << 20);
+
+        let started = Instant::now();
+        let err = run_plugin(cmd, &input, Duration::from_millis(200)).unwrap_err();
+        assert!(err.starts_with("timed out"), "{}", err);
+        assert!(started.elapsed() < Duration::from_secs(4));
+    }
+}
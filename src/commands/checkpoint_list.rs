@@ -0,0 +1,346 @@
+use crate::authorship::authorship_log_serialization::generate_short_hash;
+use crate::authorship::range_authorship::should_ignore_file;
+use crate::authorship::working_log::{Checkpoint, CheckpointKind};
+use crate::commands::output;
+use crate::commands::sync_daemon::parse_interval;
+use crate::error::{GitAiError, exit_with_error};
+use crate::git::find_repository;
+use serde_json::{Value, json};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Narrow `checkpoint list` to matching working log entries
+#[derive(Debug, Default, PartialEq)]
+pub struct CheckpointFilters {
+    /// Agent tool name (case-insensitive); "human" matches human checkpoints
+    pub agent: Option<String>,
+    /// Unix timestamp; older checkpoints are skipped
+    pub since: Option<u64>,
+    /// Glob matched against the file path or file name
+    pub file: Option<String>,
+}
+
+impl CheckpointFilters {
+    pub fn keeps(&self, checkpoint: &Checkpoint) -> bool {
+        let agent_matches = self
+            .agent
+            .as_ref()
+            .is_none_or(|agent| match &checkpoint.agent_id {
+                Some(id) => id.tool.eq_ignore_ascii_case(agent),
+                None => checkpoint.kind == CheckpointKind::Human && agent == "human",
+            });
+        let since_matches = self.since.is_none_or(|since| checkpoint.timestamp >= since);
+        let file_matches = self.file.as_ref().is_none_or(|pattern| {
+            checkpoint
+                .entries
+                .iter()
+                .any(|entry| should_ignore_file(&entry.file, std::slice::from_ref(pattern)))
+        });
+        agent_matches && since_matches && file_matches
+    }
+}
+
+/// `--since` takes an interval back from now ("30m", "2h") or an RFC 3339 date
+pub fn parse_since(value: &str, now: u64) -> Result<u64, String> {
+    if let Ok(interval) = parse_interval(value) {
+        return Ok(now.saturating_sub(interval.as_secs()));
+    }
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|date| date.timestamp().max(0) as u64)
+        .map_err(|_| {
+            format!(
+                "Invalid --since '{}' (use e.g. 30m, 2h or an RFC 3339 date)",
+                value
+            )
+        })
+}
+
+pub fn parse_list_args(args: &[String], now: u64) -> Result<CheckpointFilters, String> {
+    let mut filters = CheckpointFilters::default();
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            flag @ ("--agent" | "--since" | "--file") => {
+                let value = args
+                    .get(i + 1)
+                    .ok_or_else(|| format!("{} requires a value", flag))?;
+                match flag {
+                    "--agent" => filters.agent = Some(value.to_lowercase()),
+                    "--since" => filters.since = Some(parse_since(value, now)?),
+                    _ => {
+                        glob::Pattern::new(value)
+                            .map_err(|e| format!("Invalid --file glob '{}': {}", value, e))?;
+                        filters.file = Some(value.clone());
+                    }
+                }
+                i += 1;
+            }
+            other => return Err(format!("Unknown checkpoint list argument: {}", other)),
+        }
+        i += 1;
+    }
+    Ok(filters)
+}
+
+/// Handle `git-ai checkpoint list [--agent <tool>] [--since <when>] [--file <glob>]`
+///
+/// Checkpoint ids are their 1-based position in the working log, the same
+/// numbering `--show-working-log` uses.
+pub fn handle_list(args: &[String]) {
+    let filters = match parse_list_args(args, now()) {
+        Ok(filters) => filters,
+        Err(e) => exit_with_error("Error", &GitAiError::InvalidArguments(e)),
+    };
+    let checkpoints = read_checkpoints();
+    let matching: Vec<(usize, &Checkpoint)> = checkpoints
+        .iter()
+        .enumerate()
+        .map(|(index, checkpoint)| (index + 1, checkpoint))
+        .filter(|(_, checkpoint)| filters.keeps(checkpoint))
+        .collect();
+
+    if output::json_requested(false) {
+        let json: Vec<Value> = matching
+            .iter()
+            .map(|(id, checkpoint)| summary_json(*id, checkpoint))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&json).unwrap());
+        return;
+    }
+    if matching.is_empty() {
+        println!("No matching checkpoints in the working log");
+        return;
+    }
+    println!(
+        "{:>4}  {:<16}  {:<10}  {:<24}  {:>5}  {:>11}  PROMPT",
+        "ID", "TIME", "KIND", "AGENT", "FILES", "LINES"
+    );
+    for (id, checkpoint) in matching {
+        println!(
+            "{:>4}  {:<16}  {:<10}  {:<24}  {:>5}  {:>11}  {}",
+            id,
+            format_time(checkpoint.timestamp),
+            checkpoint.kind.to_str(),
+            agent_label(checkpoint),
+            checkpoint.entries.len(),
+            format!(
+                "+{} -{}",
+                checkpoint.line_stats.additions, checkpoint.line_stats.deletions
+            ),
+            prompt_hash(checkpoint).unwrap_or_else(|| "-".to_string()),
+        );
+    }
+}
+
+/// Handle `git-ai checkpoint show <id>`
+pub fn handle_show(args: &[String]) {
+    let id = match args {
+        [id] => match id.trim_start_matches('#').parse::<usize>() {
+            Ok(id) if id > 0 => id,
+            _ => exit_with_error(
+                "Error",
+                &GitAiError::InvalidArguments(format!("Invalid checkpoint id: {}", id)),
+            ),
+        },
+        _ => exit_with_error(
+            "Error",
+            &GitAiError::InvalidArguments("Usage: git-ai checkpoint show <id>".to_string()),
+        ),
+    };
+    let checkpoints = read_checkpoints();
+    let Some(checkpoint) = checkpoints.get(id - 1) else {
+        exit_with_error(
+            "Error",
+            &GitAiError::InvalidArguments(format!(
+                "No checkpoint {} (the working log has {})",
+                id,
+                checkpoints.len()
+            )),
+        );
+    };
+
+    let files = file_lines(checkpoint);
+    if output::json_requested(false) {
+        let mut json = summary_json(id, checkpoint);
+        json["author"] = json!(checkpoint.author);
+        json["message"] = json!(checkpoint.message);
+        json["tags"] = json!(checkpoint.tags);
+        json["files"] = files
+            .iter()
+            .map(|(file, lines)| json!({ "path": file, "attributed_lines": lines }))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&json).unwrap());
+        return;
+    }
+
+    println!("Checkpoint {}", id);
+    println!("  time:    {}", format_time(checkpoint.timestamp));
+    println!("  kind:    {}", checkpoint.kind.to_str());
+    println!("  author:  {}", checkpoint.author);
+    if let Some(agent_id) = &checkpoint.agent_id {
+        println!("  agent:   {}", agent_label(checkpoint));
+        println!("  session: {}", agent_id.id);
+    }
+    if let Some(hash) = prompt_hash(checkpoint) {
+        println!("  prompt:  {}", hash);
+    }
+    if let Some(message) = &checkpoint.message {
+        println!("  message: {}", message);
+    }
+    if !checkpoint.tags.is_empty() {
+        println!("  tags:    {}", checkpoint.tags.join(", "));
+    }
+    println!(
+        "  lines:   +{} -{}",
+        checkpoint.line_stats.additions, checkpoint.line_stats.deletions
+    );
+    println!("  files:");
+    for (file, lines) in &files {
+        println!("    {}  {} attributed lines", file, lines);
+    }
+}
+
+fn read_checkpoints() -> Vec<Checkpoint> {
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => exit_with_error("Failed to find repository", &e),
+    };
+    match repo
+        .storage
+        .working_log_for_base_commit("initial")
+        .read_all_checkpoints()
+    {
+        Ok(checkpoints) => checkpoints,
+        Err(e) => exit_with_error("Failed to read working log", &e),
+    }
+}
+
+/// Prompt id the checkpoint's lines are attributed to, for AI checkpoints
+pub fn prompt_hash(checkpoint: &Checkpoint) -> Option<String> {
+    checkpoint
+        .agent_id
+        .as_ref()
+        .filter(|_| checkpoint.kind.is_ai())
+        .map(|id| generate_short_hash(&id.id, &id.tool))
+}
+
+/// Lines each file attributes to the checkpoint's author once it was recorded
+pub fn file_lines(checkpoint: &Checkpoint) -> Vec<(String, u32)> {
+    let author_id = prompt_hash(checkpoint).unwrap_or_else(|| checkpoint.kind.to_str());
+    checkpoint
+        .entries
+        .iter()
+        .map(|entry| {
+            let lines = entry
+                .line_attributions
+                .iter()
+                .filter(|attr| attr.author_id == author_id)
+                .map(|attr| attr.end_line - attr.start_line + 1)
+                .sum();
+            (entry.file.clone(), lines)
+        })
+        .collect()
+}
+
+fn summary_json(id: usize, checkpoint: &Checkpoint) -> Value {
+    json!({
+        "id": id,
+        "timestamp": checkpoint.timestamp,
+        "kind": checkpoint.kind.to_str(),
+        "agent": checkpoint.agent_id,
+        "prompt_id": prompt_hash(checkpoint),
+        "additions": checkpoint.line_stats.additions,
+        "deletions": checkpoint.line_stats.deletions,
+        "files": checkpoint.entries.iter().map(|e| &e.file).collect::<Vec<_>>(),
+    })
+}
+
+fn agent_label(checkpoint: &Checkpoint) -> String {
+    match &checkpoint.agent_id {
+        Some(id) if id.model.is_empty() => id.tool.clone(),
+        Some(id) => format!("{} ({})", id.tool, id.model),
+        None => "-".to_string(),
+    }
+}
+
+fn format_time(timestamp: u64) -> String {
+    chrono::DateTime::from_timestamp(timestamp as i64, 0)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::attribution_tracker::LineAttribution;
+    use crate::authorship::working_log::{AgentId, WorkingLogEntry};
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_checkpoint_filters() {
+        let filters = parse_list_args(
+            &args(&["--agent", "Claude", "--since", "1h", "--file", "*.rs"]),
+            10_000,
+        )
+        .unwrap();
+        assert_eq!(filters.agent.as_deref(), Some("claude"));
+        assert_eq!(filters.since, Some(6_400));
+        assert_eq!(parse_since("2024-01-01T00:00:00Z", 0), Ok(1_704_067_200));
+        assert!(parse_list_args(&args(&["--since", "yesterday"]), 0).is_err());
+        assert!(parse_list_args(&args(&["--bogus"]), 0).is_err());
+
+        let mut entry =
+            WorkingLogEntry::new("src/lib.rs".to_string(), "sha".to_string(), vec![], vec![]);
+        let mut checkpoint = Checkpoint::new(
+            CheckpointKind::AiAgent,
+            String::new(),
+            "me".to_string(),
+            vec![],
+        );
+        checkpoint.timestamp = 7_000;
+        checkpoint.agent_id = Some(AgentId {
+            tool: "claude".to_string(),
+            id: "s1".to_string(),
+            model: "sonnet".to_string(),
+        });
+        let hash = prompt_hash(&checkpoint).unwrap();
+        entry.line_attributions = vec![
+            LineAttribution::new(1, 3, hash.clone(), None),
+            LineAttribution::new(4, 4, "human".to_string(), None),
+            LineAttribution::new(8, 9, hash, None),
+        ];
+        checkpoint.entries = vec![entry];
+        assert!(filters.keeps(&checkpoint));
+        assert_eq!(file_lines(&checkpoint), vec![("src/lib.rs".to_string(), 5)]);
+
+        checkpoint.timestamp = 6_000;
+        assert!(!filters.keeps(&checkpoint));
+
+        let human = Checkpoint::new(
+            CheckpointKind::Human,
+            String::new(),
+            "me".to_string(),
+            vec![],
+        );
+        assert!(prompt_hash(&human).is_none());
+        let humans_only = CheckpointFilters {
+            agent: Some("human".to_string()),
+            ..Default::default()
+        };
+        assert!(humans_only.keeps(&human));
+        assert!(!humans_only.keeps(&checkpoint));
+    }
+}
@@ -0,0 +1,215 @@
+use crate::commands::checkpoint_agent::agent_presets::{
+    AgentCheckpointFlags, BuiltinPreset, builtin_preset,
+};
+use crate::commands::output;
+use crate::error::{GitAiError, exit_with_error};
+use serde::Serialize;
+use std::io::Read;
+
+#[derive(Debug, Serialize)]
+struct ValidationReport {
+    preset: String,
+    valid: bool,
+    /// Why the payload doesn't parse as the preset's input: invalid JSON, a
+    /// missing required field or a field of the wrong type
+    #[serde(skip_serializing_if = "Option::is_none")]
+    input_error: Option<String>,
+    unknown_fields: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    preset_error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<ValidatedRun>,
+}
+
+/// What the checkpoint would have been recorded as
+#[derive(Debug, Serialize)]
+struct ValidatedRun {
+    kind: String,
+    tool: String,
+    model: String,
+    session_id: String,
+    repo_working_dir: Option<String>,
+    edited_filepaths: Option<Vec<String>>,
+    will_edit_filepaths: Option<Vec<String>>,
+    transcript_messages: usize,
+}
+
+fn validate(preset: &BuiltinPreset, hook_input: String) -> ValidationReport {
+    let mut report = ValidationReport {
+        preset: preset.name.to_string(),
+        valid: false,
+        input_error: None,
+        unknown_fields: Vec::new(),
+        preset_error: None,
+        result: None,
+    };
+    match (preset.check_input)(&hook_input) {
+        Ok(ignored) => report.unknown_fields = ignored,
+        Err(e) => {
+            report.input_error = Some(e.to_string());
+            return report;
+        }
+    }
+
+    let flags = AgentCheckpointFlags {
+        hook_input: Some(hook_input),
+    };
+    match preset.preset.run(flags) {
+        Ok(run) => {
+            report.result = Some(ValidatedRun {
+                kind: run.checkpoint_kind.to_str(),
+                tool: run.agent_id.tool,
+                model: run.agent_id.model,
+                session_id: run.agent_id.id,
+                repo_working_dir: run.repo_working_dir,
+                edited_filepaths: run.edited_filepaths,
+                will_edit_filepaths: run.will_edit_filepaths,
+                transcript_messages: run.transcript.map_or(0, |t| t.messages().len()),
+            });
+        }
+        Err(e) => report.preset_error = Some(e.to_string()),
+    }
+    report.valid = report.preset_error.is_none();
+    report
+}
+
+/// Handle `checkpoint validate-input`
+///
+/// Usage: git-ai checkpoint validate-input <preset> --hook-input <json|stdin>
+///
+/// Parses the payload with the preset's input struct and reports what doesn't
+/// fit and which fields it ignores, without creating a checkpoint. Preset
+/// plugins aren't run. Exits 1 if the payload wouldn't work.
+pub fn handle_validate_input(args: &[String]) {
+    let usage = || -> ! {
+        exit_with_error(
+            "Error",
+            &GitAiError::InvalidArguments(
+                "Usage: git-ai checkpoint validate-input <preset> --hook-input <json|stdin>"
+                    .to_string(),
+            ),
+        )
+    };
+    let (name, hook_input) = match args {
+        [name, flag, value] if flag == "--hook-input" => (name.as_str(), value),
+        _ => usage(),
+    };
+    let Some(preset) = builtin_preset(name) else {
+        exit_with_error(
+            "Error",
+            &GitAiError::InvalidArguments(format!(
+                "{} is not a built-in preset that takes --hook-input",
+                name
+            )),
+        );
+    };
+    let hook_input = if hook_input == "stdin" {
+        let mut buffer = String::new();
+        if let Err(e) = std::io::stdin().read_to_string(&mut buffer) {
+            exit_with_error(
+                "Failed to read stdin for hook input",
+                &GitAiError::IoError(e),
+            );
+        }
+        buffer
+    } else {
+        hook_input.clone()
+    };
+
+    let report = validate(preset, hook_input);
+    if output::json_requested(false) {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else {
+        print_report(&report);
+    }
+    if !report.valid {
+        std::process::exit(1);
+    }
+}
+
+fn print_report(report: &ValidationReport) {
+    if let Some(error) = &report.input_error {
+        println!("Invalid hook input: {}", error);
+    }
+    for field in &report.unknown_fields {
+        println!(
+            "unknown  {} (ignored by the {} preset)",
+            field, report.preset
+        );
+    }
+    if let Some(error) = &report.preset_error {
+        println!("Preset error: {}", error);
+    }
+    if let Some(run) = &report.result {
+        println!(
+            "{} checkpoint by {} ({}), session {}",
+            run.kind, run.tool, run.model, run.session_id
+        );
+        let files = run
+            .edited_filepaths
+            .as_ref()
+            .or(run.will_edit_filepaths.as_ref());
+        if let Some(files) = files.filter(|files| !files.is_empty()) {
+            println!("  files: {}", files.join(", "));
+        }
+        if run.transcript_messages > 0 {
+            println!("  transcript: {} messages", run.transcript_messages);
+        }
+    }
+    println!(
+        "{}",
+        if report.valid {
+            "Hook input is valid"
+        } else {
+            "Hook input is invalid"
+        }
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn check(preset: &str, input: serde_json::Value) -> Result<Vec<String>, String> {
+        (builtin_preset(preset).unwrap().check_input)(&input.to_string()).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_check_input() {
+        let input = json!({"transcript_path": "/tmp/t.jsonl", "session_id": "s", "extra": 1});
+        let error = check("claude", input).unwrap_err();
+        assert!(error.contains("missing field `cwd`"), "{}", error);
+
+        let input = json!({"transcript_path": "/tmp/t.jsonl", "cwd": "/repo", "extra": 1});
+        assert_eq!(check("claude", input).unwrap(), vec!["extra"]);
+
+        // Either spelling is read
+        let input = json!({"hook_event_name": "after_edit", "workspaceFolder": "/repo"});
+        assert_eq!(
+            check("github-copilot", input).unwrap(),
+            Vec::<String>::new()
+        );
+
+        let input = json!({"type": "human", "repo_working_dir": "/repo", "model": "x"});
+        assert_eq!(check("agent-v1", input).unwrap(), vec!["model"]);
+        let input = json!({"type": "ai_agent", "agent_name": "bot"});
+        let error = check("webhook", input).unwrap_err();
+        assert!(
+            error.contains("missing field `conversation_id`"),
+            "{}",
+            error
+        );
+        let error = check("agent-v1", json!({"type": "robot"})).unwrap_err();
+        assert!(error.contains("unknown variant `robot`"), "{}", error);
+    }
+
+    #[test]
+    fn test_validate_reports_input_errors_without_running_the_preset() {
+        let preset = builtin_preset("ai_tab").unwrap();
+        let report = validate(preset, "{\"tool\": 1}".to_string());
+        assert!(!report.valid);
+        assert!(report.input_error.is_some());
+        assert!(report.result.is_none() && report.preset_error.is_none());
+    }
+}
@@ -1,7 +1,14 @@
 use crate::ci::ci_context::{CiContext, CiEvent};
-use crate::ci::github::{get_github_ci_context, install_github_ci_workflow};
+use crate::ci::github::{
+    get_github_ci_context, get_github_pull_request_shas, install_github_ci_workflow,
+};
+use crate::ci::github_check::run_github_check;
+use crate::error::{GitAiError, exit_with_error};
 use crate::git::repository::find_repository_in_path;
+use crate::git::sync_authorship::fetch_authorship_notes;
+use crate::notifications::{self, NotificationEvent};
 use crate::utils::debug_log;
+use serde_json::json;
 
 pub fn handle_ci(args: &[String]) {
     if args.is_empty() {
@@ -35,13 +42,16 @@ fn handle_ci_github(args: &[String]) {
                 Ok(Some(ci_context)) => {
                     debug_log(&format!("GitHub CI context: {:?}", ci_context));
                     if let Err(e) = ci_context.run() {
-                        eprintln!("Error running GitHub CI context: {}", e);
-                        std::process::exit(1);
+                        notifications::notify_blocking(
+                            NotificationEvent::CiFailure,
+                            &format!("git-ai GitHub CI run failed: {}", e),
+                            json!({ "event": format!("{:?}", ci_context.event), "error": e.to_string() }),
+                        );
+                        exit_with_error("Error running GitHub CI context", &e);
                     }
                     if !no_cleanup {
                         if let Err(e) = ci_context.teardown() {
-                            eprintln!("Error tearing down GitHub CI context: {}", e);
-                            std::process::exit(1);
+                            exit_with_error("Error tearing down GitHub CI context", &e);
                         }
                         debug_log("GitHub CI context teared down");
                     } else {
@@ -50,23 +60,24 @@ fn handle_ci_github(args: &[String]) {
                     std::process::exit(0);
                 }
                 Err(e) => {
-                    eprintln!("Failed to get GitHub CI context: {}", e);
-                    std::process::exit(1);
+                    exit_with_error("Failed to get GitHub CI context", &e);
                 }
                 Ok(None) => {
-                    eprintln!("No GitHub CI context found");
-                    std::process::exit(1);
+                    exit_with_error(
+                        "Error",
+                        &GitAiError::Generic("No GitHub CI context found".to_string()),
+                    );
                 }
             }
         }
+        "check" => handle_ci_github_check(&args[1..]),
         "install" => match install_github_ci_workflow() {
             Ok(path) => {
                 println!("Installed GitHub Actions workflow to {}", path.display());
                 std::process::exit(0);
             }
             Err(e) => {
-                eprintln!("Failed to install GitHub CI workflow: {}", e);
-                std::process::exit(1);
+                exit_with_error("Failed to install GitHub CI workflow", &e);
             }
         },
         other => {
@@ -76,6 +87,120 @@ fn handle_ci_github(args: &[String]) {
     }
 }
 
+fn handle_ci_github_check(args: &[String]) {
+    let mut base_sha: Option<String> = None;
+    let mut head_sha: Option<String> = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--base-sha" | "--head-sha" => {
+                if i + 1 >= args.len() {
+                    exit_with_error(
+                        "Error",
+                        &GitAiError::InvalidArguments(format!(
+                            "Missing value for flag {}",
+                            args[i]
+                        )),
+                    );
+                }
+                if args[i] == "--base-sha" {
+                    base_sha = Some(args[i + 1].clone());
+                } else {
+                    head_sha = Some(args[i + 1].clone());
+                }
+                i += 2;
+            }
+            other => {
+                eprintln!("Unknown argument: {}", other);
+                print_ci_github_help_and_exit();
+            }
+        }
+    }
+
+    // Fall back to the pull request that triggered the workflow
+    if (base_sha.is_none() || head_sha.is_none())
+        && let Some((event_base, event_head)) = get_github_pull_request_shas()
+    {
+        base_sha = base_sha.or(Some(event_base));
+        head_sha = head_sha.or(Some(event_head));
+    }
+
+    let (base_sha, head_sha) = match (base_sha, head_sha) {
+        (Some(base), Some(head)) => (base, head),
+        _ => {
+            exit_with_error("Error", &GitAiError::InvalidArguments("Could not determine base/head SHAs. Run from a pull_request workflow or pass --base-sha and --head-sha".to_string()));
+        }
+    };
+
+    let github_repository = std::env::var("GITHUB_REPOSITORY").unwrap_or_default();
+    let token = std::env::var("GITHUB_TOKEN").unwrap_or_default();
+    if github_repository.is_empty() || token.is_empty() {
+        exit_with_error(
+            "Error",
+            &GitAiError::InvalidArguments(
+                "GITHUB_REPOSITORY and GITHUB_TOKEN must be set".to_string(),
+            ),
+        );
+    }
+
+    let repo = match find_repository_in_path(".") {
+        Ok(r) => r,
+        Err(e) => {
+            exit_with_error("Failed to open repository in current directory", &e);
+        }
+    };
+
+    // Authorship notes are not fetched by actions/checkout
+    if let Err(e) = fetch_authorship_notes(&repo, "origin") {
+        debug_log(&format!("Failed to fetch authorship notes: {}", e));
+    }
+
+    match run_github_check(&repo, &github_repository, &token, &base_sha, &head_sha) {
+        Ok(report) => {
+            println!(
+                "Created check run: {} ({} annotations, conclusion: {})",
+                report.title(),
+                report.annotations.len(),
+                report.conclusion()
+            );
+            if !report.violations.is_empty() {
+                let details = json!({
+                    "repository": github_repository,
+                    "head_sha": head_sha,
+                    "violations": report.violations,
+                });
+                notifications::notify_blocking(
+                    NotificationEvent::PolicyViolation,
+                    &format!(
+                        "{} policy violation(s) in {} at {}",
+                        report.violations.len(),
+                        github_repository,
+                        head_sha
+                    ),
+                    details,
+                );
+            }
+            std::process::exit(0);
+        }
+        Err(e) => {
+            notifications::notify_blocking(
+                NotificationEvent::CiFailure,
+                &format!(
+                    "git-ai check could not run for {}: {}",
+                    github_repository, e
+                ),
+                json!({
+                    "repository": github_repository,
+                    "head_sha": head_sha,
+                    "error": e.to_string(),
+                }),
+            );
+            exit_with_error("Failed to create GitHub check run", &e);
+        }
+    }
+}
+
 fn handle_ci_local(args: &[String]) {
     if args.is_empty() {
         print_ci_local_help_and_exit();
@@ -92,8 +217,10 @@ fn handle_ci_local(args: &[String]) {
                 if i + 1 < event_args.len() {
                     return Some(event_args[i + 1].clone());
                 } else {
-                    eprintln!("Missing value for flag {}", name);
-                    std::process::exit(1);
+                    exit_with_error(
+                        "Error",
+                        &GitAiError::InvalidArguments(format!("Missing value for flag {}", name)),
+                    );
                 }
             }
             i += 1;
@@ -105,8 +232,7 @@ fn handle_ci_local(args: &[String]) {
     let repo = match find_repository_in_path(".") {
         Ok(r) => r,
         Err(e) => {
-            eprintln!("Failed to open repository in current directory: {}", e);
-            std::process::exit(1);
+            exit_with_error("Failed to open repository in current directory", &e);
         }
     };
 
@@ -116,16 +242,22 @@ fn handle_ci_local(args: &[String]) {
             let merge_commit_sha = match flag("--merge-commit-sha") {
                 Some(v) => v,
                 None => {
-                    eprintln!("--merge-commit-sha is required");
-                    std::process::exit(1);
+                    exit_with_error(
+                        "Error",
+                        &GitAiError::InvalidArguments("--merge-commit-sha is required".to_string()),
+                    );
                 }
             };
 
             let base_ref = match flag("--base-ref") {
                 Some(v) => v,
                 None => {
-                    eprintln!("--base-ref is required (e.g., main)");
-                    std::process::exit(1);
+                    exit_with_error(
+                        "Error",
+                        &GitAiError::InvalidArguments(
+                            "--base-ref is required (e.g., main)".to_string(),
+                        ),
+                    );
                 }
             };
 
@@ -133,24 +265,30 @@ fn handle_ci_local(args: &[String]) {
             let head_ref = match flag("--head-ref") {
                 Some(v) => v,
                 None => {
-                    eprintln!("--head-ref is required");
-                    std::process::exit(1);
+                    exit_with_error(
+                        "Error",
+                        &GitAiError::InvalidArguments("--head-ref is required".to_string()),
+                    );
                 }
             };
 
             let head_sha = match flag("--head-sha") {
                 Some(v) => v,
                 None => {
-                    eprintln!("--head-sha is required");
-                    std::process::exit(1);
+                    exit_with_error(
+                        "Error",
+                        &GitAiError::InvalidArguments("--head-sha is required".to_string()),
+                    );
                 }
             };
 
             let base_sha = match flag("--base-sha") {
                 Some(v) => v,
                 None => {
-                    eprintln!("--base-sha is required");
-                    std::process::exit(1);
+                    exit_with_error(
+                        "Error",
+                        &GitAiError::InvalidArguments("--base-sha is required".to_string()),
+                    );
                 }
             };
 
@@ -169,8 +307,7 @@ fn handle_ci_local(args: &[String]) {
 
             debug_log(&format!("Local CI context: {:?}", ctx));
             if let Err(e) = ctx.run() {
-                eprintln!("Error running local CI: {}", e);
-                std::process::exit(1);
+                exit_with_error("Error running local CI", &e);
             }
 
             println!("Local CI (merge) completed successfully");
@@ -191,6 +328,8 @@ fn print_ci_help_and_exit() -> ! {
     eprintln!("Subcommands:");
     eprintln!("  github           GitHub CI");
     eprintln!("    run [--no-cleanup]  Run GitHub CI in current repo");
+    eprintln!("    check [--base-sha <sha>] [--head-sha <sha>]");
+    eprintln!("                   Create a check run annotating AI-authored lines");
     eprintln!("    install        Install/update workflow in current repo");
     eprintln!("  local            Run CI locally by event name and flags");
     eprintln!("                   Usage: git-ai ci local <event> [flags]");
@@ -221,6 +360,10 @@ fn print_ci_github_help_and_exit() -> ! {
     eprintln!("Subcommands:");
     eprintln!("  run [--no-cleanup]   Run GitHub CI in current repo");
     eprintln!("                       --no-cleanup  Skip teardown after run");
+    eprintln!("  check                Create a check run annotating AI-authored lines");
+    eprintln!("                       and policy violations on the pull request");
+    eprintln!("                       --base-sha <sha>  Base commit (default: from event)");
+    eprintln!("                       --head-sha <sha>  Head commit (default: from event)");
     eprintln!("  install              Install/update workflow in current repo");
     std::process::exit(1);
 }
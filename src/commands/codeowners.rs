@@ -0,0 +1,411 @@
+use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::authorship::working_log::CheckpointKind;
+use crate::commands::blame::{GitAiBlameOptions, overlay_ai_authorship};
+use crate::commands::output;
+use crate::error::{GitAiError, exit_with_error};
+use crate::git::find_repository;
+use crate::git::refs::get_authorship;
+use crate::git::repository::{Repository, exec_git};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, PartialEq)]
+pub struct SuggestArgs {
+    pub rev: String,
+    /// Directory levels to assign owners at (1 = top-level directories)
+    pub depth: usize,
+    pub max_owners: usize,
+    /// Minimum share of a directory's human lines, in percent
+    pub min_share: u32,
+}
+
+impl Default for SuggestArgs {
+    fn default() -> Self {
+        SuggestArgs {
+            rev: "HEAD".to_string(),
+            depth: 2,
+            max_owners: 3,
+            min_share: 20,
+        }
+    }
+}
+
+/// Surviving human-owned lines per person in one directory
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DirectoryLines {
+    /// Lines written by hand, keyed by author email
+    pub written: BTreeMap<String, u32>,
+    /// AI lines a person signed off with `git-ai review mark`
+    pub reviewed: BTreeMap<String, u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OwnerRule {
+    /// CODEOWNERS pattern, e.g. `/src/commands/` or `*`
+    pub pattern: String,
+    pub owners: Vec<String>,
+    /// Human-owned lines in the directory
+    pub lines: u32,
+    /// Share of those lines per owner, in percent
+    pub shares: Vec<u32>,
+}
+
+/// Handle the `codeowners` command
+///
+/// Usage: git-ai codeowners suggest [--rev <rev>] [--depth <n>] [--max-owners <n>] [--min-share <pct>]
+///
+/// Prints a draft CODEOWNERS that assigns each directory to whoever wrote most
+/// of its surviving non-AI lines, counting AI lines they reviewed as theirs too.
+pub fn handle_codeowners(args: &[String]) {
+    match args.first().map(String::as_str) {
+        Some("suggest") => {}
+        _ => {
+            exit_with_error("Error", &GitAiError::InvalidArguments("Usage: git-ai codeowners suggest [--rev <rev>] [--depth <n>] [--max-owners <n>] [--min-share <pct>]".to_string()));
+        }
+    }
+    let parsed = match parse_suggest_args(&args[1..]) {
+        Ok(parsed) => parsed,
+        Err(e) => exit_with_error("Error", &GitAiError::InvalidArguments(e)),
+    };
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => exit_with_error("Failed to find repository", &e),
+    };
+
+    let directories = match collect_directory_lines(&repo, &parsed.rev, parsed.depth) {
+        Ok(directories) => directories,
+        Err(e) => exit_with_error("codeowners suggest failed", &e),
+    };
+    let rules = suggest_owners(&directories, parsed.max_owners, parsed.min_share);
+
+    if output::json_requested(false) {
+        println!("{}", serde_json::to_string_pretty(&rules).unwrap());
+    } else {
+        print!("{}", format_codeowners(&rules, &parsed.rev));
+    }
+}
+
+pub fn parse_suggest_args(args: &[String]) -> Result<SuggestArgs, String> {
+    let mut parsed = SuggestArgs::default();
+    let mut i = 0;
+    while i < args.len() {
+        let flag = args[i].as_str();
+        if !matches!(flag, "--rev" | "--depth" | "--max-owners" | "--min-share") {
+            return Err(format!("Unknown codeowners argument: {}", flag));
+        }
+        let value = args
+            .get(i + 1)
+            .ok_or_else(|| format!("{} requires a value", flag))?;
+        let number = || {
+            value
+                .parse::<usize>()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or_else(|| format!("{} requires a positive number", flag))
+        };
+        match flag {
+            "--rev" => parsed.rev = value.clone(),
+            "--depth" => parsed.depth = number()?,
+            "--max-owners" => parsed.max_owners = number()?,
+            _ => {
+                parsed.min_share = value
+                    .trim_end_matches('%')
+                    .parse()
+                    .ok()
+                    .filter(|share| *share <= 100)
+                    .ok_or("--min-share requires a percentage between 0 and 100")?
+            }
+        }
+        i += 2;
+    }
+    Ok(parsed)
+}
+
+/// The directory a file's lines are counted under: its parent, cut to `depth` levels
+pub fn owning_directory(path: &str, depth: usize) -> String {
+    let components: Vec<&str> = path.split('/').collect();
+    let dirs = &components[..components.len() - 1];
+    dirs[..dirs.len().min(depth)].join("/")
+}
+
+/// Blame every file at `rev`, crediting human lines to their git author and
+/// reviewed AI lines to their reviewer
+pub fn collect_directory_lines(
+    repo: &Repository,
+    rev: &str,
+    depth: usize,
+) -> Result<BTreeMap<String, DirectoryLines>, GitAiError> {
+    let sha = repo.revparse_single(rev)?.peel_to_commit()?.id();
+    let options = GitAiBlameOptions {
+        newest_commit: Some(sha.clone()),
+        use_prompt_hashes_as_names: true,
+        no_output: true,
+        ..Default::default()
+    };
+    let mixed = CheckpointKind::Mixed.to_str();
+
+    let mut directories: BTreeMap<String, DirectoryLines> = BTreeMap::new();
+    let mut notes: HashMap<String, Option<AuthorshipLog>> = HashMap::new();
+    // Reviews record `user.name`; map it to an email seen in blame
+    let mut emails_by_name: HashMap<String, String> = HashMap::new();
+    let mut reviewed_by_name: Vec<(String, String)> = Vec::new();
+
+    for (path, line_count) in text_files(repo, &sha)? {
+        if line_count == 0 {
+            continue;
+        }
+        let hunks = match repo.blame_hunks(&path, 1, line_count, &options) {
+            Ok(hunks) => hunks,
+            Err(e) => {
+                eprintln!("Warning: skipping {}: {}", path, e);
+                continue;
+            }
+        };
+        let (line_authors, prompts) = overlay_ai_authorship(repo, &hunks, &path, &options)?;
+        let directory = owning_directory(&path, depth);
+
+        for hunk in &hunks {
+            emails_by_name
+                .entry(hunk.original_author.clone())
+                .or_insert_with(|| hunk.author_email.clone());
+            for line in hunk.range.0..=hunk.range.1 {
+                let is_ai = line_authors
+                    .get(&line)
+                    .is_some_and(|name| *name == mixed || prompts.contains_key(name));
+                if !is_ai {
+                    *directories
+                        .entry(directory.clone())
+                        .or_default()
+                        .written
+                        .entry(hunk.author_email.clone())
+                        .or_insert(0) += 1;
+                    continue;
+                }
+                let note = notes
+                    .entry(hunk.commit_sha.clone())
+                    .or_insert_with(|| get_authorship(repo, &hunk.commit_sha));
+                let orig_line = hunk.orig_range.0 + (line - hunk.range.0);
+                let reviewer = note.as_ref().and_then(|note| {
+                    note.metadata.reviews.iter().find(|review| {
+                        review.file == path
+                            && review.lines.iter().any(|range| range.contains(orig_line))
+                    })
+                });
+                if let Some(review) = reviewer {
+                    reviewed_by_name.push((directory.clone(), review.reviewer.clone()));
+                }
+            }
+        }
+    }
+
+    for (directory, reviewer) in reviewed_by_name {
+        // Reviewers who never wrote a line here can't be mapped to an email
+        let Some(email) = emails_by_name.get(&reviewer) else {
+            continue;
+        };
+        *directories
+            .entry(directory)
+            .or_default()
+            .reviewed
+            .entry(email.clone())
+            .or_insert(0) += 1;
+    }
+    Ok(directories)
+}
+
+/// Text files in the commit's tree with their line counts
+pub fn text_files(repo: &Repository, sha: &str) -> Result<Vec<(String, u32)>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("ls-tree".to_string());
+    args.push("-r".to_string());
+    args.push("-z".to_string());
+    args.push("--name-only".to_string());
+    args.push(sha.to_string());
+    let output = exec_git(&args)?;
+
+    let mut files = Vec::new();
+    for path in output.stdout.split(|b| *b == 0).filter(|p| !p.is_empty()) {
+        let path = String::from_utf8_lossy(path).to_string();
+        let mut args = repo.global_args_for_exec();
+        args.push("show".to_string());
+        args.push(format!("{}:{}", sha, path));
+        // Submodules and the like can't be shown; binary files have no lines to own
+        let Ok(content) = exec_git(&args) else {
+            continue;
+        };
+        if content.stdout.contains(&0u8) {
+            continue;
+        }
+        let lines = String::from_utf8_lossy(&content.stdout).lines().count() as u32;
+        files.push((path, lines));
+    }
+    Ok(files)
+}
+
+/// Pick up to `max_owners` people per directory holding at least `min_share`
+/// percent of its human lines. Directories whose owners match their parent's
+/// are left out, since the parent rule already covers them.
+pub fn suggest_owners(
+    directories: &BTreeMap<String, DirectoryLines>,
+    max_owners: usize,
+    min_share: u32,
+) -> Vec<OwnerRule> {
+    let mut rules: Vec<OwnerRule> = Vec::new();
+    let mut owners_by_directory: HashMap<&str, Vec<String>> = HashMap::new();
+
+    for (directory, lines) in directories {
+        let mut totals: BTreeMap<&str, u32> = BTreeMap::new();
+        for (email, count) in lines.written.iter().chain(&lines.reviewed) {
+            *totals.entry(email).or_insert(0) += count;
+        }
+        let total: u32 = totals.values().sum();
+        if total == 0 {
+            continue;
+        }
+        let mut ranked: Vec<(&str, u32)> = totals.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+        let picked: Vec<(String, u32)> = ranked
+            .into_iter()
+            .map(|(email, count)| (email.to_string(), count * 100 / total))
+            .filter(|(_, share)| *share >= min_share)
+            .take(max_owners)
+            .collect();
+        if picked.is_empty() {
+            continue;
+        }
+        let owners: Vec<String> = picked.iter().map(|(email, _)| email.clone()).collect();
+
+        let parent = directory
+            .rsplit_once('/')
+            .map(|(parent, _)| parent)
+            .unwrap_or("");
+        let inherited = if directory.is_empty() {
+            None
+        } else {
+            owners_by_directory.get(parent).cloned()
+        };
+        owners_by_directory.insert(directory, owners.clone());
+        if inherited.as_ref() == Some(&owners) {
+            continue;
+        }
+
+        rules.push(OwnerRule {
+            pattern: if directory.is_empty() {
+                "*".to_string()
+            } else {
+                format!("/{}/", directory)
+            },
+            owners,
+            lines: total,
+            shares: picked.iter().map(|(_, share)| *share).collect(),
+        });
+    }
+    rules
+}
+
+/// CODEOWNERS text; parents come first, so later (deeper) rules take precedence
+pub fn format_codeowners(rules: &[OwnerRule], rev: &str) -> String {
+    let mut out = format!(
+        "# Draft generated by `git-ai codeowners suggest` at {}\n\
+         # Owners wrote (or reviewed the AI code in) most surviving lines of each directory.\n\
+         # Review before committing: emails may need replacing with @user or @org/team handles.\n",
+        rev
+    );
+    for rule in rules {
+        let shares: Vec<String> = rule
+            .owners
+            .iter()
+            .zip(&rule.shares)
+            .map(|(owner, share)| format!("{} {}%", owner, share))
+            .collect();
+        out.push_str(&format!(
+            "\n# {} human lines: {}\n{} {}\n",
+            rule.lines,
+            shares.join(", "),
+            rule.pattern,
+            rule.owners.join(" ")
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(written: &[(&str, u32)], reviewed: &[(&str, u32)]) -> DirectoryLines {
+        let map = |entries: &[(&str, u32)]| {
+            entries
+                .iter()
+                .map(|(email, count)| (email.to_string(), *count))
+                .collect()
+        };
+        DirectoryLines {
+            written: map(written),
+            reviewed: map(reviewed),
+        }
+    }
+
+    #[test]
+    fn test_owning_directory() {
+        assert_eq!(owning_directory("README.md", 2), "");
+        assert_eq!(owning_directory("src/lib.rs", 2), "src");
+        assert_eq!(
+            owning_directory("src/commands/hooks/a.rs", 2),
+            "src/commands"
+        );
+        assert_eq!(owning_directory("src/commands/hooks/a.rs", 1), "src");
+    }
+
+    #[test]
+    fn test_suggest_owners() {
+        let mut directories = BTreeMap::new();
+        directories.insert("".to_string(), lines(&[("alice@x.com", 10)], &[]));
+        directories.insert(
+            "src".to_string(),
+            lines(
+                &[("bob@x.com", 60), ("carol@x.com", 25)],
+                &[("carol@x.com", 5)],
+            ),
+        );
+        directories.insert(
+            "src/util".to_string(),
+            lines(
+                &[("bob@x.com", 50), ("carol@x.com", 30), ("dan@x.com", 5)],
+                &[],
+            ),
+        );
+        directories.insert("docs".to_string(), lines(&[("alice@x.com", 4)], &[]));
+
+        let rules = suggest_owners(&directories, 3, 20);
+        let patterns: Vec<&str> = rules.iter().map(|rule| rule.pattern.as_str()).collect();
+        // docs/ matches the root rule and src/util/ matches src/
+        assert_eq!(patterns, vec!["*", "/src/"]);
+        assert_eq!(rules[1].owners, vec!["bob@x.com", "carol@x.com"]);
+        assert_eq!(rules[1].shares, vec![66, 33]);
+
+        let text = format_codeowners(&rules, "HEAD");
+        assert!(text.contains("\n/src/ bob@x.com carol@x.com\n"));
+        assert!(text.contains("# 90 human lines: bob@x.com 66%, carol@x.com 33%"));
+
+        let rules = suggest_owners(&directories, 1, 20);
+        assert_eq!(rules.last().unwrap().owners, vec!["bob@x.com"]);
+    }
+
+    #[test]
+    fn test_parse_suggest_args() {
+        let args = |values: &[&str]| values.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            parse_suggest_args(&args(&["--depth", "1", "--min-share", "30%"])).unwrap(),
+            SuggestArgs {
+                depth: 1,
+                min_share: 30,
+                ..Default::default()
+            }
+        );
+        assert!(parse_suggest_args(&args(&["--depth", "0"])).is_err());
+        assert!(parse_suggest_args(&args(&["--min-share", "120"])).is_err());
+        assert!(parse_suggest_args(&args(&["--owners"])).is_err());
+    }
+}
@@ -1,5 +1,8 @@
+use crate::authorship::anonymize::hash_identity;
 use crate::authorship::authorship_log::PromptRecord;
 use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::commands::output;
+use crate::commands::renderers;
 use crate::error::GitAiError;
 use crate::git::refs::get_reference_as_authorship_log_v3;
 use crate::git::repository::{Repository, exec_git};
@@ -54,10 +57,11 @@ pub enum Attribution {
 
 pub fn handle_diff(repo: &Repository, args: &[String]) -> Result<(), GitAiError> {
     if args.is_empty() {
-        eprintln!("Error: diff requires a commit or commit range argument");
-        eprintln!("Usage: git-ai diff <commit>");
-        eprintln!("       git-ai diff <commit1>..<commit2>");
-        std::process::exit(1);
+        return Err(GitAiError::InvalidArguments(
+            "diff requires a commit or commit range argument\n\
+             Usage: git-ai diff <commit>\n       git-ai diff <commit1>..<commit2>"
+                .to_string(),
+        ));
     }
 
     let spec = parse_diff_args(args)?;
@@ -117,10 +121,25 @@ pub fn execute_diff(repo: &Repository, spec: DiffSpec) -> Result<(), GitAiError>
     let hunks = get_diff_with_line_numbers(repo, &from_commit, &to_commit)?;
 
     // Step 2: Overlay AI attributions
-    let attributions = overlay_diff_attributions(repo, &from_commit, &to_commit, &hunks)?;
+    let mut attributions = overlay_diff_attributions(repo, &from_commit, &to_commit, &hunks)?;
+    if output::anonymize() {
+        for attribution in attributions.values_mut() {
+            if let Attribution::Human(username) = attribution {
+                *username = hash_identity(username);
+            }
+        }
+    }
 
     // Step 3: Format and output annotated diff
-    format_annotated_diff(repo, &from_commit, &to_commit, &attributions)?;
+    if output::json_requested(false) {
+        let json = diff_json(&from_commit, &to_commit, &attributions);
+        println!("{}", serde_json::to_string_pretty(&json)?);
+    } else if let Some(renderer) = output::renderer() {
+        let json = diff_json(&from_commit, &to_commit, &attributions);
+        renderers::print_report(&renderer, "diff", &json)?;
+    } else {
+        format_annotated_diff(repo, &from_commit, &to_commit, &attributions)?;
+    }
 
     Ok(())
 }
@@ -411,7 +430,7 @@ pub fn format_annotated_diff(
         .map_err(|e| GitAiError::Generic(format!("Failed to parse diff output: {}", e)))?;
 
     // Check if we should use colors
-    let use_color = std::io::stdout().is_terminal();
+    let use_color = std::io::stdout().is_terminal() && output::color_enabled();
 
     // Parse and annotate diff
     let mut current_file = String::new();
@@ -575,6 +594,40 @@ fn format_attribution(attribution: &Attribution) -> String {
     }
 }
 
+/// `--format json` output: one entry per changed line, ordered by file then line
+pub fn diff_json(
+    from_commit: &str,
+    to_commit: &str,
+    attributions: &HashMap<DiffLineKey, Attribution>,
+) -> serde_json::Value {
+    let mut keys: Vec<&DiffLineKey> = attributions.keys().collect();
+    keys.sort_by_key(|key| (&key.file, key.side == LineSide::New, key.line));
+
+    let lines: Vec<serde_json::Value> = keys
+        .into_iter()
+        .map(|key| {
+            let (kind, author) = match &attributions[key] {
+                Attribution::Ai(tool) => ("ai", Some(tool)),
+                Attribution::Human(username) => ("human", Some(username)),
+                Attribution::Mixed(tool) => ("mixed", Some(tool)),
+                Attribution::NoData => ("none", None),
+            };
+            serde_json::json!({
+                "file": key.file,
+                "side": match key.side {
+                    LineSide::Old => "old",
+                    LineSide::New => "new",
+                },
+                "line": key.line,
+                "kind": kind,
+                "author": author,
+            })
+        })
+        .collect();
+
+    serde_json::json!({ "from": from_commit, "to": to_commit, "lines": lines })
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -744,6 +797,46 @@ mod tests {
         assert_eq!(format_attribution(&attr), "[no-data]");
     }
 
+    #[test]
+    fn test_diff_json_orders_lines() {
+        let mut attributions = HashMap::new();
+        attributions.insert(
+            DiffLineKey {
+                file: "b.rs".to_string(),
+                line: 1,
+                side: LineSide::New,
+            },
+            Attribution::NoData,
+        );
+        attributions.insert(
+            DiffLineKey {
+                file: "a.rs".to_string(),
+                line: 4,
+                side: LineSide::New,
+            },
+            Attribution::Ai("cursor".to_string()),
+        );
+        attributions.insert(
+            DiffLineKey {
+                file: "a.rs".to_string(),
+                line: 9,
+                side: LineSide::Old,
+            },
+            Attribution::Human("alice".to_string()),
+        );
+
+        let json = diff_json("aaa", "bbb", &attributions);
+        assert_eq!(json["from"], "aaa");
+        let lines = json["lines"].as_array().unwrap();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["side"], "old");
+        assert_eq!(lines[0]["author"], "alice");
+        assert_eq!(lines[1]["kind"], "ai");
+        assert_eq!(lines[1]["line"], 4);
+        assert_eq!(lines[2]["file"], "b.rs");
+        assert!(lines[2]["author"].is_null());
+    }
+
     #[test]
     fn test_diff_line_key_equality() {
         let key1 = DiffLineKey {
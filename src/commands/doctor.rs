@@ -0,0 +1,174 @@
+use crate::authorship::divergence::{
+    DivergentFile, UNTRACKED_HUMAN_EDIT, detect_divergence, record_untracked_edits,
+};
+use crate::commands::hooks::commit_hooks::get_commit_default_author;
+use crate::commands::output;
+use crate::commands::safe_mode::{self, HookFailures};
+use crate::config::Config;
+use crate::error::{GitAiError, exit_with_error};
+use crate::git::find_repository;
+use crate::git::git_version::{self, MIN_SUPPORTED};
+use serde_json::json;
+
+/// Handle the `doctor` command
+///
+/// Usage: git-ai doctor [--fix] [--enable-hooks]
+///
+/// Checks that the working log still matches the working tree. Files edited while
+/// git-ai wasn't running are listed; `--fix` records them as untracked human edits
+/// now instead of waiting for the pre-commit hook to do it. The detected git
+/// version is reported too, along with any hooks it is too old to run, and
+/// whether repeated hook failures put the repository in safe mode, which
+/// `--enable-hooks` turns off.
+pub fn handle_doctor(args: &[String]) {
+    let mut fix = false;
+    let mut enable_hooks = false;
+    for arg in args {
+        match arg.as_str() {
+            "--fix" => fix = true,
+            "--enable-hooks" => enable_hooks = true,
+            other => exit_with_error(
+                "Error",
+                &GitAiError::InvalidArguments(format!("Unknown doctor argument: {}", other)),
+            ),
+        }
+    }
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => exit_with_error("Failed to find repository", &e),
+    };
+    let working_log = repo.storage.working_log_for_base_commit("initial");
+    let divergent = match detect_divergence(&working_log) {
+        Ok(divergent) => divergent,
+        Err(e) => exit_with_error("Failed to read working log", &e),
+    };
+
+    if fix && !divergent.is_empty() {
+        let author = get_commit_default_author(&repo, &[]);
+        if let Err(e) = record_untracked_edits(&repo, &author, &divergent) {
+            exit_with_error("Failed to record untracked edits", &e);
+        }
+    }
+
+    let hook_failures = HookFailures::load(&repo.storage);
+    if enable_hooks && let Err(e) = safe_mode::enable_hooks(&repo.storage) {
+        exit_with_error("Failed to enable hooks", &e);
+    }
+
+    let git_version = git_version::detected();
+    let disabled_hooks = git_version
+        .map(git_version::disabled_hooks)
+        .unwrap_or_default();
+
+    if output::json_requested(false) {
+        let files: Vec<_> = divergent.iter().map(divergent_file_json).collect();
+        let disabled: Vec<_> = disabled_hooks
+            .iter()
+            .map(|(command, reason)| json!({ "command": command, "reason": reason }))
+            .collect();
+        let report = json!({
+            "git": {
+                "path": Config::get().git_cmd(),
+                "version": git_version.map(|v| v.to_string()),
+                "min_supported": MIN_SUPPORTED.to_string(),
+                "supported": git_version.is_none_or(|v| v >= MIN_SUPPORTED),
+                "disabled_hooks": disabled,
+            },
+            "divergent_files": files,
+            "fixed": fix,
+            "hook_failures": {
+                "consecutive": hook_failures.consecutive,
+                "last_command": hook_failures.last_command,
+                "last_error": hook_failures.last_error,
+                "safe_mode": hook_failures.in_safe_mode() && !enable_hooks,
+                "safe_mode_since": hook_failures.safe_mode_since,
+            },
+            "hooks_enabled": enable_hooks,
+        });
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        return;
+    }
+
+    match git_version {
+        Some(version) => println!("git {} ({})", version, Config::get().git_cmd()),
+        None => println!("git version unknown ({})", Config::get().git_cmd()),
+    }
+    if git_version.is_some_and(|v| v < MIN_SUPPORTED) {
+        println!(
+            "  older than {}, the oldest supported version: git-ai hooks are disabled",
+            MIN_SUPPORTED
+        );
+    } else {
+        for (command, reason) in &disabled_hooks {
+            println!("  hooks for `git {}` disabled: {}", command, reason);
+        }
+        if let Some(version) = git_version
+            && version < git_version::COMMIT_TRAILER
+            && Config::get().co_author_trailers().enabled
+        {
+            println!(
+                "  co-author trailers skipped: git {} is older than {} (needs commit --trailer)",
+                version,
+                git_version::COMMIT_TRAILER
+            );
+        }
+    }
+    print_hook_failures(&hook_failures, enable_hooks);
+    println!();
+
+    if divergent.is_empty() {
+        println!("Working log matches the working tree");
+        return;
+    }
+    println!("Files changed before the last checkpoint without git-ai seeing it:");
+    for file in &divergent {
+        let state = if file.worktree_sha.is_some() {
+            "modified"
+        } else {
+            "deleted"
+        };
+        println!("  {} ({})", file.path, state);
+    }
+    println!();
+    if fix {
+        println!("Recorded as \"{}\"", UNTRACKED_HUMAN_EDIT);
+    } else {
+        println!(
+            "These will be recorded as \"{}\" at the next commit (or run `git-ai doctor --fix`)",
+            UNTRACKED_HUMAN_EDIT
+        );
+    }
+}
+
+fn print_hook_failures(failures: &HookFailures, enabled: bool) {
+    if failures.consecutive == 0 {
+        return;
+    }
+    let last = match (&failures.last_command, &failures.last_error) {
+        (Some(command), Some(error)) => format!("; last, on `git {}`: {}", command, error),
+        _ => String::new(),
+    };
+    if !failures.in_safe_mode() {
+        println!(
+            "Hooks failed on the last {} git commands{}",
+            failures.consecutive, last
+        );
+    } else if enabled {
+        println!("Safe mode off: git-ai hooks run again in this repository");
+    } else {
+        println!(
+            "Safe mode: git-ai hooks are off after {} failed runs in a row{}",
+            failures.consecutive, last
+        );
+        println!("  fix the cause, then run `git-ai doctor --enable-hooks` to turn them back on");
+    }
+}
+
+fn divergent_file_json(file: &DivergentFile) -> serde_json::Value {
+    json!({
+        "path": file.path,
+        "snapshot_sha": file.snapshot_sha,
+        "worktree_sha": file.worktree_sha,
+    })
+}
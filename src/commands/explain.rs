@@ -0,0 +1,520 @@
+//! Dry run of the git wrapper (`git-ai explain <git args...>`, or any git
+//! command with `GIT_AI_EXPLAIN=1`): goes through the same decisions as
+//! `git_handlers::handle_git` and lists the hooks it would run, what they
+//! read and write, and why, without running git or any hook. The
+//! repository prompt for unlisted repositories is never shown.
+
+use crate::commands::hooks::{CommandHooks, command_hooks};
+use crate::commands::output;
+use crate::commands::safe_mode;
+use crate::config::{Config, RepositoryAccess};
+use crate::error::{GitAiError, exit_with_error};
+use crate::git::cli_parser::{ParsedGitInvocation, is_dry_run, parse_git_cli_args};
+use crate::git::find_repository;
+use crate::git::git_version;
+use crate::git::repository::Repository;
+use serde::Serialize;
+
+/// Whether `GIT_AI_EXPLAIN` asks the wrapper to explain instead of run
+pub fn explain_requested() -> bool {
+    std::env::var("GIT_AI_EXPLAIN").is_ok_and(|v| v == "1" || v == "true")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase {
+    /// Before git runs
+    Pre,
+    /// After git exits
+    Post,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlannedHook {
+    pub phase: Phase,
+    pub name: &'static str,
+    pub action: &'static str,
+    pub reads: &'static [&'static str],
+    pub writes: &'static [&'static str],
+}
+
+#[derive(Debug, Serialize)]
+pub struct Explanation {
+    /// The git command line as it would be run
+    pub git: Vec<String>,
+    pub repository: Option<String>,
+    /// Why hooks do or don't run, in the order the wrapper decides
+    pub decisions: Vec<String>,
+    pub hooks: Vec<PlannedHook>,
+}
+
+/// Config the hook plan depends on
+#[derive(Debug, Clone, Default)]
+pub struct HookSettings {
+    /// `feature_flags.rewrite_stash`
+    pub rewrite_stash: bool,
+    pub push_summary: bool,
+}
+
+impl HookSettings {
+    fn from_config(config: &Config) -> Self {
+        Self {
+            rewrite_stash: config.feature_flags().rewrite_stash,
+            push_summary: config.push_summary_enabled(),
+        }
+    }
+}
+
+const WORKING_LOG: &str = ".git/ai/working_logs";
+const REWRITE_LOG: &str = ".git/ai/rewrite_log";
+const NOTES: &str = "refs/notes/ai";
+const SYNC_FAILURES: &str = ".git/ai/sync_failures";
+
+/// Hooks run for `parsed` once the wrapper has decided hooks are on, with
+/// notes on any the command's flags or config leave out. Follows the same
+/// [`command_hooks`] table `git_handlers` dispatches on.
+pub fn planned_hooks(
+    parsed: &ParsedGitInvocation,
+    settings: &HookSettings,
+) -> (Vec<PlannedHook>, Vec<String>) {
+    let mut hooks = Vec::new();
+    let mut notes = Vec::new();
+    let command = parsed.command.as_deref().unwrap_or_default();
+    let command_hooks = command_hooks(parsed.command.as_deref());
+
+    let dry_run = is_dry_run(&parsed.command_args);
+    if dry_run
+        && matches!(
+            command_hooks,
+            Some(
+                CommandHooks::Commit
+                    | CommandHooks::Push
+                    | CommandHooks::FetchPull
+                    | CommandHooks::Merge
+            )
+        )
+    {
+        notes.push(format!("--dry-run: the {} hooks do nothing", command));
+        return (hooks, notes);
+    }
+
+    match command_hooks {
+        Some(CommandHooks::Commit) => {
+            hooks.push(PlannedHook {
+                phase: Phase::Pre,
+                name: "commit_pre_command_hook",
+                action: "Checkpoint the working tree, add Co-authored-by trailers and fill in message templates",
+                reads: &["working tree", "index", WORKING_LOG],
+                writes: &[WORKING_LOG, ".git/ai/COMMIT_TEMPLATE"],
+            });
+            hooks.push(PlannedHook {
+                phase: Phase::Post,
+                name: "commit_post_command_hook",
+                action: if parsed.has_command_flag("--amend") {
+                    "Move the amended commit's note to the new commit, adding the working log"
+                } else {
+                    "Turn the working log into the new commit's authorship note"
+                },
+                reads: &[WORKING_LOG, NOTES],
+                writes: &[NOTES, REWRITE_LOG, WORKING_LOG],
+            });
+        }
+        Some(CommandHooks::Push) => {
+            if parsed
+                .command_args
+                .iter()
+                .any(|a| matches!(a.as_str(), "-d" | "--delete" | "--mirror"))
+            {
+                notes.push("--delete/--mirror: authorship notes aren't pushed".to_string());
+                return (hooks, notes);
+            }
+            hooks.push(PlannedHook {
+                phase: Phase::Pre,
+                name: "push_pre_command_hook",
+                action: "Push authorship notes to the same remote, alongside the push, after copying them onto commits a stack tool restacked",
+                reads: &[NOTES],
+                writes: &["<remote> refs/notes/ai", SYNC_FAILURES],
+            });
+            hooks.push(PlannedHook {
+                phase: Phase::Post,
+                name: "push_post_command_hook",
+                action: if settings.push_summary {
+                    "Wait for the notes push, then print the AI share of the pushed commits"
+                } else {
+                    "Wait for the notes push"
+                },
+                reads: &[NOTES],
+                writes: &[],
+            });
+        }
+        Some(CommandHooks::FetchPull) => {
+            hooks.push(PlannedHook {
+                phase: Phase::Pre,
+                name: "fetch_pull_pre_command_hook",
+                action: "Fetch the remote's authorship notes alongside the fetch and merge them",
+                reads: &["<remote> refs/notes/ai"],
+                writes: &["refs/notes/ai-remote/<remote>", NOTES, SYNC_FAILURES],
+            });
+            hooks.push(PlannedHook {
+                phase: Phase::Post,
+                name: "fetch_pull_post_command_hook",
+                action: "Wait for the notes fetch",
+                reads: &[],
+                writes: &[],
+            });
+        }
+        Some(CommandHooks::Reset) => {
+            hooks.push(PlannedHook {
+                phase: Phase::Pre,
+                name: "pre_reset_hook",
+                action: "Checkpoint the working tree and resolve the reset target",
+                reads: &["working tree", WORKING_LOG],
+                writes: &[WORKING_LOG],
+            });
+            hooks.push(PlannedHook {
+                phase: Phase::Post,
+                name: "post_reset_hook",
+                action: if parsed.has_command_flag("--hard") {
+                    "Delete the working log, since --hard discards uncommitted work"
+                } else {
+                    "Rebuild the working log from the notes of the commits reset away"
+                },
+                reads: &[NOTES, WORKING_LOG],
+                writes: &[WORKING_LOG, REWRITE_LOG],
+            });
+        }
+        Some(CommandHooks::Merge) => {
+            if parsed.has_command_flag("--squash") {
+                hooks.push(PlannedHook {
+                    phase: Phase::Post,
+                    name: "post_merge_hook",
+                    action: "Carry the squashed commits' authorship into the working log",
+                    reads: &[NOTES],
+                    writes: &[WORKING_LOG, REWRITE_LOG],
+                });
+            } else {
+                notes.push(
+                    "merge: only --squash needs a hook; merge commits keep their parents' notes"
+                        .to_string(),
+                );
+            }
+        }
+        Some(CommandHooks::Rebase) => {
+            hooks.push(PlannedHook {
+                phase: Phase::Pre,
+                name: "pre_rebase_hook",
+                action: "Log the original HEAD, unless continuing a rebase",
+                reads: &[REWRITE_LOG, ".git/rebase-merge"],
+                writes: &[REWRITE_LOG],
+            });
+            hooks.push(PlannedHook {
+                phase: Phase::Post,
+                name: "handle_rebase_post_command",
+                action: "Rewrite authorship notes onto the rebased commits once the rebase is done",
+                reads: &[REWRITE_LOG, NOTES],
+                writes: &[NOTES, REWRITE_LOG, ".git/ai/rebase_ambiguities.json"],
+            });
+        }
+        Some(CommandHooks::CherryPick) => {
+            hooks.push(PlannedHook {
+                phase: Phase::Pre,
+                name: "pre_cherry_pick_hook",
+                action: "Log the commits being picked, unless continuing a cherry-pick",
+                reads: &[REWRITE_LOG, ".git/CHERRY_PICK_HEAD"],
+                writes: &[REWRITE_LOG],
+            });
+            hooks.push(PlannedHook {
+                phase: Phase::Post,
+                name: "post_cherry_pick_hook",
+                action: "Copy the picked commits' authorship notes to the new commits",
+                reads: &[REWRITE_LOG, NOTES],
+                writes: &[NOTES, REWRITE_LOG],
+            });
+        }
+        Some(CommandHooks::Stash) if !settings.rewrite_stash => {
+            notes.push("stash: feature flag rewrite_stash is off".to_string());
+        }
+        Some(CommandHooks::Stash) => {
+            let subcommand = parsed.pos_command(0).unwrap_or_else(|| "push".to_string());
+            match subcommand.as_str() {
+                "pop" | "apply" => {
+                    hooks.push(PlannedHook {
+                        phase: Phase::Pre,
+                        name: "pre_stash_hook",
+                        action: "Resolve the stash being applied before git drops it",
+                        reads: &["refs/stash"],
+                        writes: &[],
+                    });
+                    hooks.push(PlannedHook {
+                        phase: Phase::Post,
+                        name: "post_stash_hook",
+                        action: "Restore the stash's attribution into the working log",
+                        reads: &["refs/notes/ai-stash"],
+                        writes: &[WORKING_LOG],
+                    });
+                }
+                "push" | "save" => {
+                    hooks.push(PlannedHook {
+                        phase: Phase::Pre,
+                        name: "pre_stash_hook",
+                        action: "Checkpoint the working tree",
+                        reads: &["working tree", WORKING_LOG],
+                        writes: &[WORKING_LOG],
+                    });
+                    hooks.push(PlannedHook {
+                        phase: Phase::Post,
+                        name: "post_stash_hook",
+                        action: "Move the stashed files' attribution from the working log to a note on the stash",
+                        reads: &[WORKING_LOG],
+                        writes: &["refs/notes/ai-stash", WORKING_LOG],
+                    });
+                }
+                other => notes.push(format!("stash {}: no hooks", other)),
+            }
+        }
+        Some(CommandHooks::Clone) => {
+            hooks.push(PlannedHook {
+                phase: Phase::Post,
+                name: "post_clone_hook",
+                action: "Fetch origin's authorship notes into the new repository",
+                reads: &["origin refs/notes/ai"],
+                writes: &[NOTES],
+            });
+        }
+        None if command.is_empty() => {}
+        None => notes.push(format!("{}: no git-ai hooks for this command", command)),
+    }
+    (hooks, notes)
+}
+
+/// Walk `handle_git`'s decisions for `args` (everything after `git`)
+pub fn explain(args: &[String]) -> Explanation {
+    let config = Config::get();
+    let parsed = parse_git_cli_args(args);
+    let mut decisions = Vec::new();
+    let mut explanation = Explanation {
+        git: parsed.to_invocation_vec(),
+        repository: None,
+        decisions: Vec::new(),
+        hooks: Vec::new(),
+    };
+
+    let command = parsed.command.clone().unwrap_or_default();
+    if config.is_read_only_command(&command) {
+        decisions.push(format!(
+            "{} is in read_only_commands: passed straight to git",
+            command
+        ));
+        explanation.decisions = decisions;
+        return explanation;
+    }
+
+    let repository = find_repository(&parsed.global_args).ok();
+    explanation.repository = repository
+        .as_ref()
+        .and_then(|repo| repo.workdir().ok())
+        .map(|path| path.display().to_string());
+
+    let hooks_on = if parsed.is_help {
+        decisions.push("help request: passed straight to git".to_string());
+        false
+    } else if command == "clone" {
+        // The clone's target doesn't exist yet; as in handle_git, the
+        // repository the command runs from decides
+        explain_repository_access(config, &repository, &mut decisions)
+    } else if repository.is_none() {
+        decisions.push("not inside a repository: passed straight to git".to_string());
+        false
+    } else if !explain_repository_access(config, &repository, &mut decisions) {
+        false
+    } else if repository
+        .as_ref()
+        .is_some_and(|repo| safe_mode::is_active(&repo.storage))
+    {
+        decisions.push(
+            "safe mode after repeated hook failures: hooks skipped until \
+             `git-ai doctor --enable-hooks`"
+                .to_string(),
+        );
+        false
+    } else {
+        true
+    };
+    if hooks_on && !command.is_empty() && command_hooks_enabled(config, &command, &mut decisions) {
+        let (hooks, notes) = planned_hooks(&parsed, &HookSettings::from_config(config));
+        decisions.extend(notes);
+        explanation.hooks = hooks;
+    }
+    explanation.decisions = decisions;
+    explanation
+}
+
+fn explain_repository_access(
+    config: &Config,
+    repository: &Option<Repository>,
+    decisions: &mut Vec<String>,
+) -> bool {
+    let allowed = config.is_allowed_repository(repository);
+    let decision = match config.repository_access(repository) {
+        RepositoryAccess::Excluded => {
+            "a remote matches exclude_repositories: hooks skipped".to_string()
+        }
+        RepositoryAccess::Allowed => "a remote matches allow_repositories".to_string(),
+        RepositoryAccess::Unlisted if allowed => {
+            "allow_repositories is empty, so any repository not excluded is allowed".to_string()
+        }
+        RepositoryAccess::Unlisted if config.prompt_unlisted_repositories() => {
+            "no remote matches allow_repositories: on a terminal you'd be asked whether to \
+             enable git-ai; hooks skipped otherwise"
+                .to_string()
+        }
+        RepositoryAccess::Unlisted => {
+            "no remote matches allow_repositories: hooks skipped".to_string()
+        }
+    };
+    decisions.push(decision);
+    allowed
+}
+
+/// Same checks as `git_handlers::hooks_enabled`, with the reason when off
+fn command_hooks_enabled(config: &Config, command: &str, decisions: &mut Vec<String>) -> bool {
+    if !config.command_hooks_enabled(command) {
+        decisions.push(format!("disabled_hooks lists {}: hooks skipped", command));
+        return false;
+    }
+    if let Some(reason) = git_version::detected()
+        .and_then(|version| git_version::unsupported_reason(version, command))
+    {
+        decisions.push(format!("{}: hooks skipped", reason));
+        return false;
+    }
+    true
+}
+
+/// Handle the `explain` command
+///
+/// Usage: git-ai explain <git args...>
+///
+/// Also what the wrapper does instead of running a git command when
+/// `GIT_AI_EXPLAIN=1` is set.
+pub fn handle_explain(args: &[String]) {
+    if args.is_empty() {
+        exit_with_error(
+            "Error",
+            &GitAiError::InvalidArguments("Usage: git-ai explain <git args...>".to_string()),
+        );
+    }
+    let explanation = explain(args);
+    if output::json_requested(false) {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&explanation).unwrap_or_default()
+        );
+    } else {
+        print_explanation(&explanation);
+    }
+}
+
+fn print_explanation(explanation: &Explanation) {
+    let git = Config::get().git_cmd();
+    println!("git {}", explanation.git.join(" "));
+    if let Some(repository) = &explanation.repository {
+        println!("Repository: {}", repository);
+    }
+    println!("Why:");
+    for decision in &explanation.decisions {
+        println!("  - {}", decision);
+    }
+    println!("Runs:");
+    let print_phase = |phase: Phase| {
+        for hook in explanation.hooks.iter().filter(|hook| hook.phase == phase) {
+            let label = match phase {
+                Phase::Pre => "pre ",
+                Phase::Post => "post",
+            };
+            println!("  {}  {}", label, hook.name);
+            println!("        {}", hook.action);
+            if !hook.reads.is_empty() {
+                println!("        reads:  {}", hook.reads.join(", "));
+            }
+            if !hook.writes.is_empty() {
+                println!("        writes: {}", hook.writes.join(", "));
+            }
+        }
+    };
+    print_phase(Phase::Pre);
+    println!("  git   {} {}", git, explanation.git.join(" "));
+    print_phase(Phase::Post);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(args: &[&str], settings: &HookSettings) -> (Vec<&'static str>, Vec<String>) {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        let (hooks, notes) = planned_hooks(&parse_git_cli_args(&args), settings);
+        (hooks.iter().map(|hook| hook.name).collect(), notes)
+    }
+
+    #[test]
+    fn test_every_command_with_hooks_is_explained() {
+        let settings = HookSettings {
+            rewrite_stash: true,
+            push_summary: false,
+        };
+        for (command, _) in crate::commands::hooks::COMMAND_HOOKS {
+            let (hooks, notes) = plan(&[command], &settings);
+            assert!(!hooks.is_empty() || !notes.is_empty(), "{}", command);
+            assert!(
+                !notes.iter().any(|note| note.contains("no git-ai hooks")),
+                "{}",
+                command
+            );
+        }
+    }
+
+    #[test]
+    fn test_planned_hooks() {
+        let settings = HookSettings::default();
+        assert_eq!(
+            plan(&["commit", "-m", "msg"], &settings).0,
+            vec!["commit_pre_command_hook", "commit_post_command_hook"]
+        );
+        let (hooks, notes) = plan(&["commit", "--dry-run"], &settings);
+        assert!(hooks.is_empty());
+        assert_eq!(notes, vec!["--dry-run: the commit hooks do nothing"]);
+        assert!(
+            plan(&["push", "origin", "--delete", "topic"], &settings)
+                .0
+                .is_empty()
+        );
+        assert_eq!(
+            plan(&["merge", "--squash", "topic"], &settings).0,
+            vec!["post_merge_hook"]
+        );
+        assert!(plan(&["merge", "topic"], &settings).0.is_empty());
+
+        // Feature flags and config
+        assert_eq!(
+            plan(&["stash"], &settings).1,
+            vec!["stash: feature flag rewrite_stash is off"]
+        );
+        let settings = HookSettings {
+            rewrite_stash: true,
+            push_summary: false,
+        };
+        assert_eq!(
+            plan(&["stash", "pop"], &settings).0,
+            vec!["pre_stash_hook", "post_stash_hook"]
+        );
+        assert_eq!(plan(&["commit"], &settings).0.len(), 2);
+        assert_eq!(
+            plan(&["switch", "main"], &settings),
+            (
+                Vec::<&str>::new(),
+                vec!["switch: no git-ai hooks for this command".to_string()]
+            )
+        );
+    }
+}
@@ -0,0 +1,511 @@
+use crate::authorship::authorship_log::PromptRecord;
+use crate::authorship::working_log::CheckpointKind;
+use crate::commands::blame::GitAiBlameOptions;
+use crate::commands::output;
+use crate::error::{GitAiError, exit_with_error};
+use crate::git::find_repository;
+use crate::git::repository::{Repository, exec_git};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const STYLE: &str = "\
+body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', sans-serif; margin: 2em; color: #24292f; }
+a { color: #0969da; text-decoration: none; }
+table { border-collapse: collapse; }
+td, th { padding: 2px 12px 2px 0; text-align: left; }
+.num { text-align: right; }
+.dir { font-weight: 600; }
+.bar { display: inline-block; width: 120px; height: 8px; background: #d0d7de; vertical-align: middle; }
+.bar span { display: block; height: 100%; background: #8250df; }
+pre { margin: 0; }
+.code td { padding: 0 8px; font-family: ui-monospace, SFMono-Regular, Menlo, monospace; font-size: 12px; white-space: pre; }
+.code .ln { color: #6e7781; text-align: right; user-select: none; }
+.code .who { color: #57606a; max-width: 16em; overflow: hidden; text-overflow: ellipsis; }
+.code tr.ai { background: #f3ecff; }
+.code tr.mixed { background: #fff4e0; }
+.legend span { padding: 0 6px; margin-right: 8px; }
+.legend .ai { background: #f3ecff; }
+.legend .mixed { background: #fff4e0; }
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    StaticSite,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ExportArgs {
+    pub format: ExportFormat,
+    pub output: PathBuf,
+    pub rev: String,
+}
+
+/// Who wrote a line, as far as the authorship overlay can tell
+#[derive(Debug, Clone, PartialEq)]
+pub enum LineOwner {
+    Ai { prompt_id: String, label: String },
+    Mixed,
+    Human(String),
+}
+
+/// Per-file counts shown on the index page
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize)]
+pub struct LineCounts {
+    pub ai: u32,
+    pub mixed: u32,
+    pub human: u32,
+}
+
+impl LineCounts {
+    pub fn add(&mut self, other: LineCounts) {
+        self.ai += other.ai;
+        self.mixed += other.mixed;
+        self.human += other.human;
+    }
+
+    pub fn total(&self) -> u32 {
+        self.ai + self.mixed + self.human
+    }
+
+    pub fn ai_percent(&self) -> u32 {
+        if self.total() == 0 {
+            0
+        } else {
+            (self.ai as u64 * 100 / self.total() as u64) as u32
+        }
+    }
+}
+
+/// Handle the `export` command
+///
+/// Usage: git-ai export --format static-site -o <dir> [--rev <rev>]
+///
+/// Writes a browsable HTML snapshot of the tree at `<rev>` with every line
+/// tinted by its AI/human attribution. The output is plain files, so it can be
+/// published on any static hosting.
+pub fn handle_export(args: &[String]) {
+    let parsed = match parse_export_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => exit_with_error("Error", &GitAiError::InvalidArguments(e)),
+    };
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => exit_with_error("Failed to find repository", &e),
+    };
+
+    match parsed.format {
+        ExportFormat::StaticSite => match export_static_site(&repo, &parsed.rev, &parsed.output) {
+            Ok(files) => {
+                if !output::quiet() {
+                    eprintln!(
+                        "Exported {} files at {} to {}",
+                        files,
+                        parsed.rev,
+                        parsed.output.join("index.html").display()
+                    );
+                }
+            }
+            Err(e) => exit_with_error("Export failed", &e),
+        },
+    }
+}
+
+pub fn parse_export_args(args: &[String]) -> Result<ExportArgs, String> {
+    let mut format = None;
+    let mut output = None;
+    let mut rev = "HEAD".to_string();
+
+    let mut i = 0;
+    while i < args.len() {
+        let (flag, inline_value) = match args[i].split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+            _ => (args[i].as_str(), None),
+        };
+        if !matches!(flag, "--format" | "-o" | "--output" | "--rev") {
+            return Err(format!("Unknown export argument: {}", args[i]));
+        }
+        let value = match inline_value {
+            Some(value) => value,
+            None => {
+                i += 1;
+                args.get(i)
+                    .cloned()
+                    .ok_or_else(|| format!("{} requires a value", flag))?
+            }
+        };
+        match flag {
+            "--format" => {
+                format = Some(match value.as_str() {
+                    "static-site" => ExportFormat::StaticSite,
+                    other => {
+                        return Err(format!(
+                            "Unknown export format '{}' (supported: static-site)",
+                            other
+                        ));
+                    }
+                })
+            }
+            "--rev" => rev = value,
+            _ => output = Some(PathBuf::from(value)),
+        }
+        i += 1;
+    }
+
+    Ok(ExportArgs {
+        format: format.ok_or("export requires --format, e.g. --format static-site")?,
+        output: output.ok_or("export requires an output directory, e.g. -o site/")?,
+        rev,
+    })
+}
+
+/// Write the site into `out_dir`, returning how many files were exported
+pub fn export_static_site(
+    repo: &Repository,
+    rev: &str,
+    out_dir: &Path,
+) -> Result<usize, GitAiError> {
+    let sha = repo.revparse_single(rev)?.peel_to_commit()?.id();
+    let short_sha = &sha[..sha.len().min(8)];
+    let title = repo
+        .workdir()
+        .ok()
+        .and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_else(|| "repository".to_string());
+
+    fs::create_dir_all(out_dir)?;
+    fs::write(out_dir.join("style.css"), STYLE)?;
+
+    let options = GitAiBlameOptions {
+        newest_commit: Some(sha.clone()),
+        use_prompt_hashes_as_names: true,
+        no_output: true,
+        anonymize: output::anonymize(),
+        ..Default::default()
+    };
+
+    let mut summaries: Vec<(String, Option<LineCounts>)> = Vec::new();
+    for path in tree_files(repo, &sha)? {
+        let content = file_at(repo, &sha, &path)?;
+        // Binary files are listed but get no page
+        if content.contains(&0u8) {
+            summaries.push((path, None));
+            continue;
+        }
+        let content = String::from_utf8_lossy(&content).to_string();
+        let lines: Vec<&str> = content.lines().collect();
+
+        let owners = if lines.is_empty() {
+            Vec::new()
+        } else {
+            match repo.blame(&path, &options) {
+                Ok((line_authors, prompts)) => line_owners(lines.len(), &line_authors, &prompts),
+                Err(e) => {
+                    eprintln!("Warning: no attribution for {}: {}", path, e);
+                    Vec::new()
+                }
+            }
+        };
+        let counts = count_owners(&owners);
+
+        let page = out_dir.join("files").join(format!("{}.html", path));
+        if let Some(parent) = page.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(
+            &page,
+            render_file_page(&title, short_sha, &path, &lines, &owners, counts),
+        )?;
+        summaries.push((path, Some(counts)));
+    }
+
+    fs::write(
+        out_dir.join("index.html"),
+        render_index(&title, short_sha, &summaries),
+    )?;
+    Ok(summaries.len())
+}
+
+/// Regular files in the commit's tree; symlinks and submodules are skipped
+pub fn tree_files(repo: &Repository, sha: &str) -> Result<Vec<String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("ls-tree".to_string());
+    args.push("-r".to_string());
+    args.push("-z".to_string());
+    args.push(sha.to_string());
+    let output = exec_git(&args)?;
+
+    let mut files = Vec::new();
+    for record in output.stdout.split(|b| *b == 0) {
+        // "<mode> <type> <object>\t<path>"
+        let record = String::from_utf8_lossy(record);
+        if let Some((meta, path)) = record.split_once('\t') {
+            let mut meta = meta.split_whitespace();
+            if meta.next() != Some("120000") && meta.next() == Some("blob") {
+                files.push(path.to_string());
+            }
+        }
+    }
+    Ok(files)
+}
+
+pub fn file_at(repo: &Repository, sha: &str, path: &str) -> Result<Vec<u8>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("show".to_string());
+    args.push(format!("{}:{}", sha, path));
+    Ok(exec_git(&args)?.stdout)
+}
+
+/// Turn blame's line -> name map into one owner per line (1-based lines in, 0-based out)
+pub fn line_owners(
+    line_count: usize,
+    line_authors: &HashMap<u32, String>,
+    prompts: &HashMap<String, PromptRecord>,
+) -> Vec<LineOwner> {
+    let mixed = CheckpointKind::Mixed.to_str();
+    (1..=line_count as u32)
+        .map(|line| match line_authors.get(&line) {
+            Some(name) if *name == mixed => LineOwner::Mixed,
+            Some(name) => match prompts.get(name) {
+                Some(prompt) => LineOwner::Ai {
+                    prompt_id: name.clone(),
+                    label: match &prompt.kind {
+                        Some(kind) => format!("{} ({})", prompt.agent_id.tool, kind),
+                        None => prompt.agent_id.tool.clone(),
+                    },
+                },
+                None => LineOwner::Human(name.clone()),
+            },
+            None => LineOwner::Human(String::new()),
+        })
+        .collect()
+}
+
+fn count_owners(owners: &[LineOwner]) -> LineCounts {
+    let mut counts = LineCounts::default();
+    for owner in owners {
+        match owner {
+            LineOwner::Ai { .. } => counts.ai += 1,
+            LineOwner::Mixed => counts.mixed += 1,
+            LineOwner::Human(_) => counts.human += 1,
+        }
+    }
+    counts
+}
+
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn page_head(title: &str, root: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<link rel=\"stylesheet\" href=\"{}style.css\">\n</head>\n<body>\n",
+        escape_html(title),
+        root
+    )
+}
+
+fn percent_bar(counts: LineCounts) -> String {
+    format!(
+        "<span class=\"bar\"><span style=\"width: {}%\"></span></span> {}% AI",
+        counts.ai_percent(),
+        counts.ai_percent()
+    )
+}
+
+fn render_file_page(
+    title: &str,
+    short_sha: &str,
+    path: &str,
+    lines: &[&str],
+    owners: &[LineOwner],
+    counts: LineCounts,
+) -> String {
+    // files/<path>.html sits one level below the root per path component
+    let root = "../".repeat(path.split('/').count());
+    let mut html = page_head(&format!("{} - {}", path, title), &root);
+    html.push_str(&format!(
+        "<p><a href=\"{}index.html\">{}</a> @ {}</p>\n<h1>{}</h1>\n",
+        root,
+        escape_html(title),
+        short_sha,
+        escape_html(path)
+    ));
+    html.push_str(&format!(
+        "<p>{} &middot; {} AI, {} mixed, {} human lines</p>\n",
+        percent_bar(counts),
+        counts.ai,
+        counts.mixed,
+        counts.human
+    ));
+    html.push_str("<p class=\"legend\"><span class=\"ai\">AI</span><span class=\"mixed\">AI, edited by a human</span></p>\n");
+    html.push_str("<table class=\"code\">\n");
+    for (index, line) in lines.iter().enumerate() {
+        let (class, who, tooltip) = match owners.get(index) {
+            Some(LineOwner::Ai { prompt_id, label }) => {
+                ("ai", label.clone(), format!("prompt {}", prompt_id))
+            }
+            Some(LineOwner::Mixed) => ("mixed", "mixed".to_string(), String::new()),
+            Some(LineOwner::Human(name)) => ("human", name.clone(), String::new()),
+            None => ("", String::new(), String::new()),
+        };
+        html.push_str(&format!(
+            "<tr class=\"{}\" id=\"L{}\"><td class=\"ln\"><a href=\"#L{}\">{}</a></td><td class=\"who\" title=\"{}\">{}</td><td>{}</td></tr>\n",
+            class,
+            index + 1,
+            index + 1,
+            index + 1,
+            escape_html(&tooltip),
+            escape_html(&who),
+            escape_html(line)
+        ));
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+    html
+}
+
+fn render_index(
+    title: &str,
+    short_sha: &str,
+    summaries: &[(String, Option<LineCounts>)],
+) -> String {
+    // Directory totals, keyed by path components so a directory sorts right
+    // before its contents
+    let mut rows: BTreeMap<Vec<&str>, Option<LineCounts>> = BTreeMap::new();
+    let mut total = LineCounts::default();
+    for (path, counts) in summaries {
+        let components: Vec<&str> = path.split('/').collect();
+        rows.insert(components.clone(), *counts);
+        let Some(counts) = counts else { continue };
+        total.add(*counts);
+        for depth in 1..components.len() {
+            rows.entry(components[..depth].to_vec())
+                .or_insert(Some(LineCounts::default()))
+                .get_or_insert_with(LineCounts::default)
+                .add(*counts);
+        }
+    }
+
+    let mut html = page_head(title, "");
+    html.push_str(&format!(
+        "<h1>{}</h1>\n<p>AI attribution at {} &middot; {}</p>\n",
+        escape_html(title),
+        short_sha,
+        percent_bar(total)
+    ));
+    html.push_str("<table>\n<tr><th>Path</th><th class=\"num\">Lines</th><th>AI</th></tr>\n");
+    for (components, counts) in &rows {
+        let name = escape_html(components.last().unwrap_or(&""));
+        let indent = "&nbsp;&nbsp;&nbsp;&nbsp;".repeat(components.len() - 1);
+        let path = components.join("/");
+        let is_file = summaries.iter().any(|(file, _)| *file == path);
+        let cell = if !is_file {
+            format!("<td class=\"dir\">{}{}/</td>", indent, name)
+        } else if counts.is_some() {
+            format!(
+                "<td>{}<a href=\"files/{}.html\">{}</a></td>",
+                indent,
+                escape_html(&path),
+                name
+            )
+        } else {
+            format!("<td>{}{} (binary)</td>", indent, name)
+        };
+        match counts {
+            Some(counts) => html.push_str(&format!(
+                "<tr>{}<td class=\"num\">{}</td><td>{}</td></tr>\n",
+                cell,
+                counts.total(),
+                percent_bar(*counts)
+            )),
+            None => html.push_str(&format!("<tr>{}<td></td><td></td></tr>\n", cell)),
+        }
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_export_args() {
+        assert_eq!(
+            parse_export_args(&args(&["--format", "static-site", "-o", "site"])).unwrap(),
+            ExportArgs {
+                format: ExportFormat::StaticSite,
+                output: PathBuf::from("site"),
+                rev: "HEAD".to_string(),
+            }
+        );
+        assert_eq!(
+            parse_export_args(&args(&[
+                "--format=static-site",
+                "--output=out",
+                "--rev",
+                "v1"
+            ]))
+            .unwrap()
+            .rev,
+            "v1"
+        );
+        assert!(parse_export_args(&args(&["--format", "pdf", "-o", "site"])).is_err());
+        assert!(parse_export_args(&args(&["--format", "static-site"])).is_err());
+        assert!(parse_export_args(&args(&["-o"])).is_err());
+    }
+
+    #[test]
+    fn test_export_static_site() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo
+            .write_file("src/lib.rs", "fn a() {}\n", true)
+            .unwrap();
+        tmp_repo.trigger_checkpoint_with_author("Alice").unwrap();
+        tmp_repo
+            .write_file("src/lib.rs", "fn a() {}\nfn b() {}\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", None, None)
+            .unwrap();
+        tmp_repo.commit_with_message("add b").unwrap();
+
+        let out = tempfile::tempdir().unwrap();
+        let exported = export_static_site(tmp_repo.gitai_repo(), "HEAD", out.path()).unwrap();
+        assert_eq!(exported, 1);
+
+        let index = fs::read_to_string(out.path().join("index.html")).unwrap();
+        assert!(index.contains("<td class=\"dir\">src/</td>"));
+        assert!(index.contains("href=\"files/src/lib.rs.html\""));
+        let page = fs::read_to_string(out.path().join("files/src/lib.rs.html")).unwrap();
+        assert!(page.contains("href=\"../../style.css\""));
+        assert!(page.contains("<tr class=\"ai\" id=\"L2\">"));
+        assert!(page.contains("<tr class=\"human\" id=\"L1\">"));
+        assert!(page.contains("1 AI, 0 mixed, 1 human lines"));
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html("if a < b && c > \"d\""),
+            "if a &lt; b &amp;&amp; c &gt; &quot;d&quot;"
+        );
+    }
+}
@@ -1,13 +1,24 @@
+use crate::authorship::anonymize;
+use crate::authorship::attribution_flow;
+use crate::authorship::attribution_tracker::set_diff_algorithm;
+use crate::authorship::human_authors;
+use crate::authorship::imara_diff_utils::DiffAlgorithm;
+use crate::authorship::line_age;
 use crate::authorship::range_authorship;
-use crate::authorship::stats::stats_command;
-use crate::authorship::working_log::{AgentId, CheckpointKind};
+use crate::authorship::stats::{TrivialFilter, stats_command};
+use crate::authorship::stats_baseline;
+use crate::authorship::symbols;
+use crate::authorship::working_log::{AgentId, CheckpointAnnotation, CheckpointKind};
 use crate::commands;
 use crate::commands::checkpoint_agent::agent_presets::{
-    AgentCheckpointFlags, AgentCheckpointPreset, AgentRunResult, AiTabPreset, ClaudePreset,
-    ContinueCliPreset, CursorPreset, GeminiPreset, GithubCopilotPreset,
+    AgentCheckpointFlags, AgentCheckpointPreset, AgentRunResult, builtin_preset,
 };
-use crate::commands::checkpoint_agent::agent_v1_preset::AgentV1Preset;
+use crate::commands::checkpoint::CheckpointOptions;
+use crate::commands::checkpoint_agent::generated_preset::GeneratedPreset;
+use crate::commands::checkpoint_agent::plugin_preset::PluginPreset;
 use crate::config;
+use crate::error::{self, GitAiError, exit_with_error};
+use crate::git::ai_ignore::AiIgnore;
 use crate::git::find_repository;
 use crate::git::find_repository_in_path;
 use crate::git::repository::CommitRange;
@@ -16,14 +27,30 @@ use crate::observability::wrapper_performance_targets::log_performance_for_check
 use std::env;
 use std::io::IsTerminal;
 use std::io::Read;
+use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 pub fn handle_git_ai(args: &[String]) {
+    let mut args = strip_global_flags(args);
     if args.is_empty() {
         print_help();
         return;
     }
 
+    if REPORTING_COMMANDS.contains(&args[0].as_str()) {
+        // `blame --porcelain` is git's porcelain format, with AI fields added
+        let keep_porcelain = args[0] == "blame";
+        match commands::output::take_format_flags(&args[1..], keep_porcelain) {
+            Ok(rest) => args.splice(1.., rest).for_each(drop),
+            Err(e) => exit_with_error("Error", &GitAiError::InvalidArguments(e)),
+        }
+    }
+
+    if commands::multi::all_repos() {
+        commands::multi::handle_all_repos(&args);
+        return;
+    }
+
     let current_dir = env::current_dir().unwrap().to_string_lossy().to_string();
     let repository_option = find_repository_in_path(&current_dir).ok();
 
@@ -34,8 +61,6 @@ pub fn handle_git_ai(args: &[String]) {
 
     let config = config::Config::get();
 
-    let allowed_repository = config.is_allowed_repository(&repository_option);
-
     match args[0].as_str() {
         "help" | "--help" | "-h" => {
             print_help();
@@ -51,21 +76,37 @@ pub fn handle_git_ai(args: &[String]) {
         "stats" => {
             handle_stats(&args[1..]);
         }
+        "working-log" => {
+            commands::working_log::handle_working_log(&args[1..]);
+        }
+        "review" => {
+            commands::review::handle_review(&args[1..]);
+        }
+        "revert-suggest" => {
+            commands::revert_suggest::handle_revert_suggest(&args[1..]);
+        }
+        "resolve-attribution" => {
+            commands::resolve_attribution::handle_resolve_attribution(&args[1..]);
+        }
+        "multi" => {
+            commands::multi::handle_multi(&args[1..]);
+        }
         "working-stats" => {
             if let Err(e) = commands::working_stats::handle_working_stats(&args[1..]) {
-                eprintln!("Working stats failed: {}", e);
-                std::process::exit(1);
+                exit_with_error("Working stats failed", &e);
             }
         }
         "show" => {
             commands::show::handle_show(&args[1..]);
         }
         "checkpoint" => {
-            if !allowed_repository {
-                eprintln!(
-                    "Skipping checkpoint because repository is excluded or not in allow_repositories list"
+            if !commands::repo_prompt::is_allowed_repository(config, &repository_option) {
+                exit_with_error(
+                    "Skipping checkpoint",
+                    &GitAiError::PolicyViolation(
+                        "repository is excluded or not in allow_repositories list".to_string(),
+                    ),
                 );
-                std::process::exit(1);
             }
             handle_checkpoint(&args[1..]);
         }
@@ -82,10 +123,32 @@ pub fn handle_git_ai(args: &[String]) {
         }
         "install-hooks" => {
             if let Err(e) = commands::install_hooks::run(&args[1..]) {
-                eprintln!("Install hooks failed: {}", e);
-                std::process::exit(1);
+                exit_with_error("Install hooks failed", &e);
             }
         }
+        "git-hook" => {
+            let Some(hook) = args.get(1) else {
+                exit_with_error(
+                    "Error",
+                    &GitAiError::InvalidArguments(
+                        "git-hook requires a hook name: \
+                         <pre-commit|prepare-commit-msg|post-commit|post-rewrite>"
+                            .to_string(),
+                    ),
+                );
+            };
+            // Never fail the user's commit because attribution couldn't be recorded
+            let result = find_repository(&Vec::<String>::new()).and_then(|mut repo| {
+                commands::git_hooks::handle_git_hook(&mut repo, hook, &args[2..])
+            });
+            if let Err(e) = result {
+                eprintln!("git-ai: {} hook failed: {}", hook, e);
+                crate::observability::local_log::error(&format!("{} hook failed: {}", hook, e));
+            }
+        }
+        "restack" => {
+            commands::restack::handle_restack(&args[1..]);
+        }
         "squash-authorship" => {
             commands::squash_authorship::handle_squash_authorship(&args[1..]);
         }
@@ -98,58 +161,331 @@ pub fn handle_git_ai(args: &[String]) {
         "flush-logs" => {
             commands::flush_logs::handle_flush_logs(&args[1..]);
         }
+        // Internal: run by event_hooks::emit in the background
+        "event-hooks" => {
+            crate::event_hooks::handle_event_hooks(&args[1..]);
+        }
+        "logs" => {
+            commands::logs::handle_logs(&args[1..]);
+        }
+        "perf" => {
+            commands::perf::handle_perf(&args[1..]);
+        }
+        "codeowners" => {
+            commands::codeowners::handle_codeowners(&args[1..]);
+        }
+        "export" => {
+            commands::export::handle_export(&args[1..]);
+        }
+        "archive" => {
+            commands::archive::handle_archive(&args[1..]);
+        }
+        "doctor" => {
+            commands::doctor::handle_doctor(&args[1..]);
+        }
+        "sessions" => {
+            commands::sessions::handle_sessions(&args[1..]);
+        }
+        "prompt-lines" => {
+            commands::prompt_lines::handle_prompt_lines(&args[1..]);
+        }
         "show-prompt" => {
             commands::show_prompt::handle_show_prompt(&args[1..]);
         }
+        "prompts" => {
+            commands::prompts::handle_prompts(&args[1..]);
+        }
+        "serve" => {
+            commands::serve::handle_serve(&args[1..]);
+        }
+        "metrics" => {
+            commands::metrics::handle_metrics(&args[1..]);
+        }
+        "ide-host" => {
+            commands::ide_host::handle_ide_host(&args[1..]);
+        }
+        "import" => {
+            commands::import::handle_import(&args[1..]);
+        }
+        "sync" => {
+            commands::sync::handle_sync(&args[1..]);
+        }
+        "explain" => {
+            commands::explain::handle_explain(&args[1..]);
+        }
+        "verify-remote" => {
+            commands::verify_remote::handle_verify_remote(&args[1..]);
+        }
+        "rewrite-trailers" => {
+            commands::rewrite_trailers::handle_rewrite_trailers(&args[1..]);
+        }
+        "sync-daemon" => {
+            commands::sync_daemon::handle_sync_daemon(&args[1..]);
+        }
+        "watch" => {
+            commands::watch::handle_watch(&args[1..]);
+        }
+        "telemetry" => {
+            commands::telemetry::handle_telemetry(&args[1..]);
+        }
         "myhelp" => {
             handle_myhelp();
         }
         "proxy" => {
-            // 直接调用 handle_git，传入剩余参数
-            let args: Vec<String> = args
-                .iter()
-                .cloned()
-                .chain(std::iter::once("--no-verify".to_string()))
-                .collect();
-            let args = commands::git_handlers::handle_git(&args[1..]);
+            commands::proxy::handle_proxy(&args[1..]);
         }
         _ => {
-            println!("Unknown git-ai command: {}", args[0]);
-            std::process::exit(1);
+            exit_with_error(
+                "Error",
+                &GitAiError::InvalidArguments(format!("Unknown git-ai command: {}", args[0])),
+            );
+        }
+    }
+}
+
+/// Reporting commands that accept `--format`/`--porcelain` among their own arguments
+const REPORTING_COMMANDS: [&str; 8] = [
+    "show",
+    "stats",
+    "blame",
+    "diff",
+    "prompts",
+    "working-stats",
+    "multi",
+    "prompt-lines",
+];
+
+/// Consume git-ai flags that come before the subcommand
+fn strip_global_flags(mut args: &[String]) -> Vec<String> {
+    while let Some(flag) = args.first() {
+        let switch: Option<fn(bool)> = match flag.as_str() {
+            "--porcelain-errors" => Some(error::set_porcelain_errors),
+            "--quiet" | "-q" => Some(commands::output::set_quiet),
+            "--no-color" => Some(commands::output::set_no_color),
+            "--anonymize" => Some(commands::output::set_anonymize),
+            "--all-repos" => Some(commands::multi::set_all_repos),
+            _ => None,
+        };
+        if let Some(enable) = switch {
+            enable(true);
+            args = &args[1..];
+            continue;
+        }
+        if flag == "--diff-algorithm" {
+            let algorithm = args.get(1).and_then(|name| DiffAlgorithm::parse(name));
+            let Some(algorithm) = algorithm else {
+                exit_with_error(
+                    "Error",
+                    &GitAiError::InvalidArguments(format!(
+                        "--diff-algorithm requires one of: {}",
+                        DiffAlgorithm::NAMES.join(", ")
+                    )),
+                );
+            };
+            set_diff_algorithm(algorithm);
+            args = &args[2..];
+            continue;
+        }
+        match commands::output::parse_format_flag(args) {
+            Some(Ok((format, used))) => {
+                commands::output::set_format(format);
+                args = &args[used..];
+            }
+            Some(Err(e)) => exit_with_error("Error", &GitAiError::InvalidArguments(e)),
+            None => break,
         }
     }
+    args.to_vec()
 }
 
 fn print_help() {
     eprintln!("git-ai - git proxy with AI authorship tracking");
     eprintln!("");
-    eprintln!("Usage: git-ai <command> [args...]");
+    eprintln!("Usage: git-ai [global options] <command> [args...]");
     eprintln!("");
+    eprintln!("Global options:");
+    eprintln!("  --porcelain-errors  Print fatal errors as JSON with a stable code on stderr");
+    eprintln!("                      (also GIT_AI_PORCELAIN_ERRORS=1)");
+    eprintln!("  --format <json|text>  Output format for show, stats, blame, diff, prompts and");
+    eprintln!("                      working-stats; may also follow the command");
+    eprintln!("  --format <renderer>   Render those reports and multi as markdown, csv, html,");
+    eprintln!("                      sarif (diff) or a format registered under `renderers`");
+    eprintln!("  --porcelain         Same as --format json (except after blame)");
+    eprintln!("  --diff-algorithm <myers|histogram|patience>  Line diff for attribution in");
+    eprintln!("                      checkpoints and rewrites (also GIT_AI_DIFF_ALGORITHM or");
+    eprintln!("                      `diff_algorithm` in the config; default myers)");
+    eprintln!("  -q, --quiet         Only print command output and errors (also GIT_AI_QUIET=1)");
+    eprintln!("  --no-color          Disable ANSI colors (also NO_COLOR)");
+    eprintln!("  --anonymize         Hash human identities and strip prompt text in show,");
+    eprintln!("                      show-prompt, stats, prompts, blame, diff, sync, export");
+    eprintln!("                      and multi output (also GIT_AI_ANONYMIZE=1)");
+    eprintln!("  --all-repos         Run the command (e.g. stats, doctor) in every repository");
+    eprintln!("                      under the current directory, like multi");
+    eprintln!();
     eprintln!("Commands:");
     eprintln!("  checkpoint         Checkpoint working changes and attribute author");
-    eprintln!("    Presets: claude, continue-cli, cursor, gemini, github-copilot, ai_tab, mock_ai");
+    eprintln!(
+        "    Presets: aider, claude, cline, continue-cli, cursor, gemini, github-copilot, roo-code, webhook, windsurf, ai_tab, mock_ai"
+    );
+    eprintln!("             or any name registered under `preset_plugins` in the config");
     eprintln!(
         "    --hook-input <json|stdin>   JSON payload required by presets, or 'stdin' to read from stdin"
     );
+    eprintln!(
+        "    --files-from <file|stdin>   Edited paths, one per line or NUL-delimited (instead of argv)"
+    );
     eprintln!("    --show-working-log          Display current working log");
     eprintln!("    --reset                     Reset working log");
+    eprintln!("    --dry-run                   Show what would be recorded without writing it");
+    eprintln!("    --human --authors <a,b>     Credit a human checkpoint to a pair or mob");
+    eprintln!(
+        "    --message <text>            Note stored with the checkpoint (shown in show and blame)"
+    );
+    eprintln!(
+        "    --tag <label>               Label the checkpoint, e.g. \"refactor pass\" (repeatable)"
+    );
+    eprintln!(
+        "    --commit-message <text>     Commit message the agent drafted, credited to it if committed"
+    );
     eprintln!("    mock_ai [pathspecs...]      Test preset accepting optional file pathspecs");
+    eprintln!("  checkpoint list    List working log checkpoints");
+    eprintln!("    --agent <tool>        Only checkpoints from this agent (or \"human\")");
+    eprintln!(
+        "    --since <when>        Only checkpoints since an interval ago (30m, 2h) or a date"
+    );
+    eprintln!("    --file <glob>         Only checkpoints touching matching files");
+    eprintln!("  checkpoint show <id>  Files, line counts and prompt of one checkpoint");
+    eprintln!(
+        "  checkpoint validate-input <preset> --hook-input <json|stdin>  Check a hook payload"
+    );
+    eprintln!("    Reports missing and unknown fields without creating a checkpoint");
+    eprintln!("    generated --tool <name> [paths...]  Attribute codegen output to the generator");
     eprintln!("  blame <file>       Git blame with AI authorship overlay");
+    eprintln!("                     On a terminal, lines are colored by AI tool/model, mixed or");
+    eprintln!("                     human; set colors in the `colors` config section");
+    eprintln!("    --porcelain           git blame --porcelain, plus ai-tool, ai-model and");
+    eprintln!("                          prompt-hash lines for AI-written lines");
+    eprintln!("    --line-porcelain      The same, repeating every header for each line");
+    eprintln!("    --reverse <start>..<end>  Which commit removed each line of <start>, and");
+    eprintln!("                          whether an agent or a human removed it");
+    eprintln!(
+        "    --batch               Read NUL-delimited paths from stdin, print a JSON line per file"
+    );
     eprintln!("  diff <commit|range>  Show diff with AI authorship annotations");
     eprintln!("    <commit>              Diff from commit's parent to commit");
     eprintln!("    <commit1>..<commit2>  Diff between two commits");
     eprintln!("  stats [commit]     Show AI authorship statistics for a commit");
     eprintln!("    --json                 Output in JSON format");
+    eprintln!("    --baseline <file>      Show changes since a report saved with --json");
+    eprintln!("    --working [--watch]    Uncommitted changes, as working-stats");
+    eprintln!(
+        "    --by-symbol            Roll attribution up to the functions and classes changed"
+    );
+    eprintln!("                           (needs a build with the `symbols` feature)");
+    eprintln!("    --age-buckets          Surviving AI and human lines by age (0-1 week, 1-4 weeks,");
+    eprintln!("                           over 1 month)");
+    eprintln!("    --attribution-flow <commit1>..<commit2>");
+    eprintln!("                           Lines that moved between AI and human over a range");
+    eprintln!("    --ignore-trivial       Skip whitespace-only and single-character AI lines");
+    eprintln!("    --min-hunk-size <n>    Skip AI attributions shorter than n consecutive lines");
     eprintln!("  working-stats      Show AI authorship statistics for uncommitted changes");
     eprintln!("    --json                 Output in JSON format");
     eprintln!("    --ignore <pattern>     Ignore files matching pattern");
+    eprintln!("    --watch                Re-render whenever files or checkpoints change");
+    eprintln!("  working-log export Save uncommitted AI checkpoints to move to another machine");
+    eprintln!("    -o <file>              Write to a file instead of stdout");
+    eprintln!("    --ref <name>           Store under refs/ai-working-log/<name> (pushable)");
+    eprintln!("  working-log import [<file>|-]  Restore checkpoints onto the current HEAD");
+    eprintln!("    --ref <name>           Read from refs/ai-working-log/<name>");
+    eprintln!("    --force                Replace an existing working log");
+    eprintln!("  review mark <file>[:<lines>]  Record human review of AI lines at HEAD");
+    eprintln!("    --reviewed-by <name>   Reviewer (defaults to git user.name)");
+    eprintln!("  review check [commit|range]  Reviewed AI lines; fails if policy requires it");
+    eprintln!("  revert-suggest <file>  Patch reverting unreviewed AI hunks that break the policy");
+    eprintln!("    --commits <n>          Recent commits to look at (default 20)");
+    eprintln!("    --unreviewed           Include every unreviewed AI hunk, not just violations");
+    eprintln!("  resolve-attribution  Assign AI hunks the last rebase rewrote to AI or human");
+    eprintln!("    --list                 List the ambiguous regions");
+    eprintln!("    --assign <n>=<ai|human|hash>  Settle region n without prompting (repeatable)");
+    eprintln!("  multi --repos-from <file|-> <command...>  Run a command in each listed repo");
+    eprintln!("    --jobs <n>             Repositories to process at once");
+    eprintln!("    --format json          One report with per-repo output and summed totals");
     eprintln!("  show <rev|range>   Display authorship logs for a revision or range");
+    eprintln!("    --agent <tool>        Only attestations from this agent (e.g. cursor, claude)");
+    eprintln!("    --author <name>       Only prompts whose human author contains <name>");
+    eprintln!("    --path <glob>         Only files matching <glob>");
+    eprintln!("    --tag <label>         Only prompts tagged with `checkpoint --tag <label>`");
+    eprintln!("    --amend-history       How attribution changed across `commit --amend`s");
+    eprintln!(
+        "    --provenance          Trace a cherry-picked commit back to its original prompts"
+    );
+    eprintln!("    --rollup              Merge a range into one per-file view at its head");
     eprintln!("  show-prompt <id>   Display a prompt record by its ID");
     eprintln!("    --commit <rev>        Look in a specific commit only");
     eprintln!(
         "    --offset <n>          Skip n occurrences (0 = most recent, mutually exclusive with --commit)"
     );
+    eprintln!("  prompt-lines <id>  Lines still attributed to a prompt (inverse of blame)");
+    eprintln!("    --rev <rev>           Look at this revision instead of HEAD");
+    eprintln!("  prompts list       List prompt sessions recorded in authorship logs");
+    eprintln!("    --ticket <ref>        Only prompts linked to a ticket (e.g. PROJ-123, #456)");
+    eprintln!("    --json                Output in JSON format");
+    eprintln!("  prompts search <query>  Full-text search over prompts, linking commits and files");
+    eprintln!("    --limit <n>           Maximum results (default: 20)");
+    eprintln!("  serve              Expose Prometheus metrics for this repository");
+    eprintln!("    --bind <addr:port>    Listen address (default: 127.0.0.1:9464)");
+    eprintln!("    --port <port>         Listen on 127.0.0.1:<port>");
+    eprintln!("  metrics export     Export AI-adjusted delivery metrics (DORA-style)");
+    eprintln!("    --format json         Output format");
+    eprintln!("    --days <n>            Window size in days (default: 90)");
+    eprintln!("  ide-host           Serve the editor extension protocol (JSON lines on stdio)");
+    eprintln!("  import cursor-history  Backfill authorship from past Cursor chat sessions");
+    eprintln!("    --db <path>           Cursor state.vscdb to read");
+    eprintln!("    --dry-run             Report what would be imported without writing notes");
+    eprintln!("  sync --to <url>        Push attribution summaries to an aggregation service");
+    eprintln!("    --token <token>       Bearer token (default: $GIT_AI_SYNC_TOKEN)");
+    eprintln!("    --batch-size <n>      Records per request (default: 100)");
+    eprintln!("    --dry-run             Count records that would be sent");
+    eprintln!("  explain <git args...>  Show which hooks a git command would run and why,");
+    eprintln!("                         without running anything (also GIT_AI_EXPLAIN=1)");
+    eprintln!("  verify-remote <remote> <branch>  Check every commit to merge has authorship data");
+    eprintln!("    --base <rev>          Merge target (default: the remote's default branch)");
+    eprintln!("    --no-fetch            Don't fetch the remote's authorship notes first");
+    eprintln!("  sync-daemon        Periodically fetch and push authorship notes");
+    eprintln!("    --interval <dur>      Time between rounds, e.g. 15m, 1h (default: 15m)");
+    eprintln!("    --once                Sync once and exit");
+    eprintln!("    --repos-from <file|-> Repositories to sync (default: config or current repo)");
+    eprintln!("  watch              Record human checkpoints after edits go idle");
+    eprintln!(
+        "    --idle <dur>          Quiet time before checkpointing, e.g. 30s, 2m (default: 30s)"
+    );
+    eprintln!(
+        "    --max-pending <dur>   Checkpoint edits unrecorded this long even while still editing"
+    );
+    eprintln!("    --detach              Keep watching in the background");
+    eprintln!("  telemetry preview  Print the opt-in aggregate report exactly as it would be sent");
+    eprintln!("  telemetry enable|disable|status  Opt in or out of aggregate telemetry");
+    eprintln!("  rewrite-trailers <range>  Bake AI-Lines/AI-Tools trailers into commit messages");
+    eprintln!("    --yes                 Rewrite history (default: preview only)");
+    eprintln!("  export --format static-site -o <dir>  Browsable HTML tree with attribution");
+    eprintln!("    --rev <rev>           Revision to export (default: HEAD)");
+    eprintln!("  archive [<rev>] -o <file>  git archive plus an ATTRIBUTION.json manifest");
+    eprintln!("    --prefix <dir>/       Prefix for every path in the archive");
+    eprintln!("    --format <fmt>        tar, tgz, tar.gz or zip (default: from the file name)");
+    eprintln!("  codeowners suggest  Draft CODEOWNERS from surviving human-written lines");
+    eprintln!("    --rev <rev>           Revision to analyze (default: HEAD)");
+    eprintln!("    --depth <n>           Directory levels to assign (default: 2)");
+    eprintln!("    --max-owners <n>      Owners per directory (default: 3)");
+    eprintln!("    --min-share <pct>     Minimum share of a directory's lines (default: 20)");
+    eprintln!("  doctor             Find files edited while git-ai wasn't running");
+    eprintln!("    --fix                 Record them as untracked human edits now");
+    eprintln!("    --enable-hooks        Leave safe mode, entered after repeated hook failures");
+    eprintln!("  sessions list      Agent runs in the working log with duration and survival");
+    eprintln!("  sessions show <id> Files and commits for one agent session");
+    eprintln!("    --gap <dur>           Idle time that splits a session (default: 30m)");
     eprintln!("  install-hooks      Install git hooks for AI authorship tracking");
+    eprintln!("    --check               Report whether the git hook shims are installed");
+    eprintln!("    --force               Override chained-hook and shared hooksPath checks");
     eprintln!("  ci                 Continuous integration utilities");
     eprintln!("    github                 GitHub CI helpers");
     eprintln!("  squash-authorship  Generate authorship log for squashed commits");
@@ -157,11 +493,21 @@ fn print_help() {
         "    <base_branch> <new_sha> <old_sha>  Required: base branch, new commit SHA, old commit SHA"
     );
     eprintln!("    --dry-run             Show what would be done without making changes");
+    eprintln!("  restack            Copy authorship onto commits rewritten outside git-ai");
+    eprintln!("                       (git-branchless, jj, Graphite restacks)");
+    eprintln!("    --dry-run             Show what would be copied without copying");
     eprintln!("  git-path           Print the path to the underlying git executable");
+    eprintln!("  logs tail          Show recent entries from the local log (~/.git-ai/logs)");
+    eprintln!("    -n <lines>            Number of lines to show (default: 50)");
+    eprintln!("    -f, --follow          Keep printing new entries as they are written");
+    eprintln!("  perf report        p50/p95 timings per command (needs \"perf_records\": true)");
+    eprintln!("    --json                Print the summary as JSON");
     eprintln!("  upgrade            Check for updates and install if available");
     eprintln!("    --force               Reinstall latest version even if already up to date");
+    eprintln!("    --insecure            Install even if the release can't be verified");
     eprintln!("  proxy <git-command>  Proxy git command with git-ai hooks");
     eprintln!("    Example: git-ai proxy commit -m \"message\"");
+    eprintln!("    Extra flags/env per subcommand come from proxy.extra_args in config.json");
     eprintln!("  version, -v, --version     Print the git-ai version");
     eprintln!("  help, -h, --help           Show this help message");
     eprintln!("");
@@ -169,6 +515,15 @@ fn print_help() {
 }
 
 fn handle_checkpoint(args: &[String]) {
+    match args.first().map(String::as_str) {
+        Some("list") => return commands::checkpoint_list::handle_list(&args[1..]),
+        Some("show") => return commands::checkpoint_list::handle_show(&args[1..]),
+        Some("validate-input") => {
+            return commands::checkpoint_validate::handle_validate_input(&args[1..]);
+        }
+        _ => {}
+    }
+
     let mut repository_working_dir = std::env::current_dir()
         .unwrap()
         .to_string_lossy()
@@ -177,7 +532,26 @@ fn handle_checkpoint(args: &[String]) {
     // Parse checkpoint-specific arguments
     let mut show_working_log = false;
     let mut reset = false;
+    let mut dry_run = false;
     let mut hook_input = None;
+    let mut human = false;
+    let mut human_authors: Vec<String> = Vec::new();
+    let mut annotation = CheckpointAnnotation::default();
+    let mut files_from: Option<Vec<String>> = None;
+    let mut commit_message: Option<String> = None;
+
+    let reads_stdin = |flag: &str| {
+        args.windows(2)
+            .any(|pair| pair[0] == flag && pair[1] == "stdin")
+    };
+    if reads_stdin("--hook-input") && reads_stdin("--files-from") {
+        exit_with_error(
+            "Error",
+            &GitAiError::InvalidArguments(
+                "--hook-input and --files-from can't both read stdin".to_string(),
+            ),
+        );
+    }
 
     let mut i = 0;
     while i < args.len() {
@@ -190,6 +564,77 @@ fn handle_checkpoint(args: &[String]) {
                 reset = true;
                 i += 1;
             }
+            "--dry-run" => {
+                dry_run = true;
+                i += 1;
+            }
+            "--human" => {
+                human = true;
+                i += 1;
+            }
+            "--authors" => {
+                human_authors = args
+                    .get(i + 1)
+                    .map(|value| human_authors::parse_authors(value))
+                    .unwrap_or_default();
+                if human_authors.is_empty() {
+                    exit_with_error(
+                        "Error",
+                        &GitAiError::InvalidArguments(
+                            "--authors requires a comma-separated list of names".to_string(),
+                        ),
+                    );
+                }
+                i += 2;
+            }
+            "--message" => {
+                annotation.message = args
+                    .get(i + 1)
+                    .map(|value| value.trim().to_string())
+                    .filter(|value| !value.is_empty());
+                if annotation.message.is_none() {
+                    exit_with_error(
+                        "Error",
+                        &GitAiError::InvalidArguments("--message requires a value".to_string()),
+                    );
+                }
+                i += 2;
+            }
+            "--commit-message" => {
+                commit_message = args
+                    .get(i + 1)
+                    .filter(|value| !value.trim().is_empty())
+                    .cloned();
+                if commit_message.is_none() {
+                    exit_with_error(
+                        "Error",
+                        &GitAiError::InvalidArguments(
+                            "--commit-message requires a value".to_string(),
+                        ),
+                    );
+                }
+                i += 2;
+            }
+            "--tag" => {
+                match args
+                    .get(i + 1)
+                    .map(|value| value.trim())
+                    .filter(|value| !value.is_empty())
+                {
+                    Some(tag) => {
+                        if !annotation.tags.iter().any(|t| t == tag) {
+                            annotation.tags.push(tag.to_string());
+                        }
+                    }
+                    None => {
+                        exit_with_error(
+                            "Error",
+                            &GitAiError::InvalidArguments("--tag requires a value".to_string()),
+                        );
+                    }
+                }
+                i += 2;
+            }
             "--hook-input" => {
                 if i + 1 < args.len() {
                     hook_input = Some(args[i + 1].clone());
@@ -197,25 +642,74 @@ fn handle_checkpoint(args: &[String]) {
                         let mut stdin = std::io::stdin();
                         let mut buffer = String::new();
                         if let Err(e) = stdin.read_to_string(&mut buffer) {
-                            eprintln!("Failed to read stdin for hook input: {}", e);
-                            std::process::exit(1);
+                            exit_with_error(
+                                "Failed to read stdin for hook input",
+                                &GitAiError::IoError(e),
+                            );
                         }
                         if !buffer.trim().is_empty() {
                             hook_input = Some(buffer);
                         } else {
-                            eprintln!("No hook input provided (via --hook-input or stdin).");
-                            std::process::exit(1);
+                            exit_with_error(
+                                "Error",
+                                &GitAiError::InvalidArguments(
+                                    "No hook input provided (via --hook-input or stdin)."
+                                        .to_string(),
+                                ),
+                            );
                         }
                     } else if hook_input.as_ref().unwrap().trim().is_empty() {
-                        eprintln!("Error: --hook-input requires a value");
-                        std::process::exit(1);
+                        exit_with_error(
+                            "Error",
+                            &GitAiError::InvalidArguments(
+                                "--hook-input requires a value".to_string(),
+                            ),
+                        );
                     }
                     i += 2;
                 } else {
-                    eprintln!("Error: --hook-input requires a value or 'stdin' to read from stdin");
-                    std::process::exit(1);
+                    exit_with_error(
+                        "Error",
+                        &GitAiError::InvalidArguments(
+                            "--hook-input requires a value or 'stdin' to read from stdin"
+                                .to_string(),
+                        ),
+                    );
                 }
             }
+            "--files-from" => {
+                let source = match args.get(i + 1) {
+                    Some(source) if !source.trim().is_empty() => source,
+                    _ => {
+                        exit_with_error(
+                            "Error",
+                            &GitAiError::InvalidArguments(
+                                "--files-from requires a file or 'stdin'".to_string(),
+                            ),
+                        );
+                    }
+                };
+                let input = if source == "stdin" {
+                    let mut buffer = Vec::new();
+                    if let Err(e) = std::io::stdin().read_to_end(&mut buffer) {
+                        exit_with_error(
+                            "Failed to read stdin for --files-from",
+                            &GitAiError::IoError(e),
+                        );
+                    }
+                    buffer
+                } else {
+                    match std::fs::read(source) {
+                        Ok(buffer) => buffer,
+                        Err(e) => exit_with_error(
+                            &format!("Failed to read {}", source),
+                            &GitAiError::IoError(e),
+                        ),
+                    }
+                };
+                files_from = Some(commands::checkpoint::parse_file_list(&input));
+                i += 2;
+            }
 
             _ => {
                 i += 1;
@@ -225,26 +719,11 @@ fn handle_checkpoint(args: &[String]) {
 
     let mut agent_run_result = None;
     // Handle preset arguments after parsing all flags
-    if !args.is_empty() {
+    if !args.is_empty() && !human {
         match args[0].as_str() {
-            "claude" => {
-                match ClaudePreset.run(AgentCheckpointFlags {
-                    hook_input: hook_input.clone(),
-                }) {
-                    Ok(agent_run) => {
-                        if agent_run.repo_working_dir.is_some() {
-                            repository_working_dir = agent_run.repo_working_dir.clone().unwrap();
-                        }
-                        agent_run_result = Some(agent_run);
-                    }
-                    Err(e) => {
-                        eprintln!("Claude preset error: {}", e);
-                        std::process::exit(1);
-                    }
-                }
-            }
-            "gemini" => {
-                match GeminiPreset.run(AgentCheckpointFlags {
+            name if builtin_preset(name).is_some() => {
+                let builtin = builtin_preset(name).unwrap();
+                match builtin.preset.run(AgentCheckpointFlags {
                     hook_input: hook_input.clone(),
                 }) {
                     Ok(agent_run) => {
@@ -254,85 +733,18 @@ fn handle_checkpoint(args: &[String]) {
                         agent_run_result = Some(agent_run);
                     }
                     Err(e) => {
-                        eprintln!("Gemini preset error: {}", e);
-                        std::process::exit(1);
+                        exit_with_error(&format!("{} preset error", builtin.display_name), &e);
                     }
                 }
             }
-            "continue-cli" => {
-                match ContinueCliPreset.run(AgentCheckpointFlags {
-                    hook_input: hook_input.clone(),
-                }) {
-                    Ok(agent_run) => {
-                        if agent_run.repo_working_dir.is_some() {
-                            repository_working_dir = agent_run.repo_working_dir.clone().unwrap();
-                        }
-                        agent_run_result = Some(agent_run);
-                    }
-                    Err(e) => {
-                        eprintln!("Continue CLI preset error: {}", e);
-                        std::process::exit(1);
-                    }
+            "generated" => match GeneratedPreset.run(&args[1..]) {
+                Ok(agent_run) => {
+                    agent_run_result = Some(agent_run);
                 }
-            }
-            "cursor" => {
-                match CursorPreset.run(AgentCheckpointFlags {
-                    hook_input: hook_input.clone(),
-                }) {
-                    Ok(agent_run) => {
-                        if agent_run.repo_working_dir.is_some() {
-                            repository_working_dir = agent_run.repo_working_dir.clone().unwrap();
-                        }
-                        agent_run_result = Some(agent_run);
-                    }
-                    Err(e) => {
-                        eprintln!("Error running Cursor preset: {}", e);
-                        std::process::exit(1);
-                    }
+                Err(e) => {
+                    exit_with_error("Generated preset error", &e);
                 }
-            }
-            "github-copilot" => {
-                match GithubCopilotPreset.run(AgentCheckpointFlags {
-                    hook_input: hook_input.clone(),
-                }) {
-                    Ok(agent_run) => {
-                        agent_run_result = Some(agent_run);
-                    }
-                    Err(e) => {
-                        eprintln!("Github Copilot preset error: {}", e);
-                        std::process::exit(1);
-                    }
-                }
-            }
-            "ai_tab" => {
-                match AiTabPreset.run(AgentCheckpointFlags {
-                    hook_input: hook_input.clone(),
-                }) {
-                    Ok(agent_run) => {
-                        if agent_run.repo_working_dir.is_some() {
-                            repository_working_dir = agent_run.repo_working_dir.clone().unwrap();
-                        }
-                        agent_run_result = Some(agent_run);
-                    }
-                    Err(e) => {
-                        eprintln!("ai_tab preset error: {}", e);
-                        std::process::exit(1);
-                    }
-                }
-            }
-            "agent-v1" => {
-                match AgentV1Preset.run(AgentCheckpointFlags {
-                    hook_input: hook_input.clone(),
-                }) {
-                    Ok(agent_run) => {
-                        agent_run_result = Some(agent_run);
-                    }
-                    Err(e) => {
-                        eprintln!("Agent V1 preset error: {}", e);
-                        std::process::exit(1);
-                    }
-                }
-            }
+            },
             "mock_ai" => {
                 let mock_agent_id = format!(
                     "ai-thread-{}",
@@ -343,11 +755,23 @@ fn handle_checkpoint(args: &[String]) {
                 );
 
                 // Collect all remaining args (after mock_ai and flags) as pathspecs
-                let edited_filepaths = if args.len() > 1 {
+                let edited_filepaths = if files_from.is_some() {
+                    files_from.take()
+                } else if args.len() > 1 {
                     let mut paths = Vec::new();
-                    for arg in &args[1..] {
-                        // Skip flags
-                        if !arg.starts_with("--") {
+                    let mut rest = args[1..].iter();
+                    while let Some(arg) = rest.next() {
+                        // Skip flags, and the values of flags that take one
+                        if matches!(
+                            arg.as_str(),
+                            "--message"
+                                | "--tag"
+                                | "--hook-input"
+                                | "--files-from"
+                                | "--commit-message"
+                        ) {
+                            rest.next();
+                        } else if !arg.starts_with("--") {
                             paths.push(arg.clone());
                         }
                     }
@@ -374,9 +798,30 @@ fn handle_checkpoint(args: &[String]) {
                     edited_filepaths,
                     will_edit_filepaths: None,
                     dirty_files: None,
+                    commit_message: None,
+                    edited_line_ranges: None,
+                    append_transcript: false,
                 });
             }
-            _ => {}
+            name => {
+                // Third-party presets registered under `preset_plugins` in the config
+                if let Some(plugin) = config::Config::get().preset_plugin(name) {
+                    match (PluginPreset { name, plugin }).run(AgentCheckpointFlags {
+                        hook_input: hook_input.clone(),
+                    }) {
+                        Ok(agent_run) => {
+                            if agent_run.repo_working_dir.is_some() {
+                                repository_working_dir =
+                                    agent_run.repo_working_dir.clone().unwrap();
+                            }
+                            agent_run_result = Some(agent_run);
+                        }
+                        Err(e) => {
+                            exit_with_error("Preset plugin error", &e);
+                        }
+                    }
+                }
+            }
         }
     }
 
@@ -388,8 +833,7 @@ fn handle_checkpoint(args: &[String]) {
     let repo = match find_repository_in_path(&final_working_dir) {
         Ok(repo) => repo,
         Err(e) => {
-            eprintln!("Failed to find repository: {}", e);
-            std::process::exit(1);
+            exit_with_error("Failed to find repository", &e);
         }
     };
 
@@ -397,10 +841,28 @@ fn handle_checkpoint(args: &[String]) {
         .as_ref()
         .map(|r| r.checkpoint_kind)
         .unwrap_or(CheckpointKind::Human);
+    if !human_authors.is_empty() && checkpoint_kind != CheckpointKind::Human {
+        exit_with_error(
+            "Error",
+            &GitAiError::InvalidArguments(
+                "--authors only applies to human checkpoints".to_string(),
+            ),
+        );
+    }
+    if commit_message.is_some() && !checkpoint_kind.is_ai() {
+        exit_with_error(
+            "Error",
+            &GitAiError::InvalidArguments(
+                "--commit-message only applies to AI checkpoints".to_string(),
+            ),
+        );
+    }
 
     if CheckpointKind::Human == checkpoint_kind && agent_run_result.is_none() {
         // Parse pathspecs after `--` for human checkpoints
-        let will_edit_filepaths = if let Some(separator_pos) = args.iter().position(|a| a == "--") {
+        let will_edit_filepaths = if files_from.is_some() {
+            files_from.take()
+        } else if let Some(separator_pos) = args.iter().position(|a| a == "--") {
             let paths: Vec<String> = args[separator_pos + 1..]
                 .iter()
                 .filter(|arg| !arg.starts_with("--"))
@@ -430,9 +892,25 @@ fn handle_checkpoint(args: &[String]) {
             edited_filepaths: None,
             repo_working_dir: Some(final_working_dir),
             dirty_files: None,
+            commit_message: None,
+            edited_line_ranges: None,
+            append_transcript: false,
         });
     }
 
+    if let (Some(message), Some(agent_run)) = (commit_message, agent_run_result.as_mut()) {
+        agent_run.commit_message = Some(message);
+    }
+
+    // Streamed paths take the place of the ones the preset reported
+    if let (Some(files), Some(agent_run)) = (files_from, agent_run_result.as_mut()) {
+        if agent_run.checkpoint_kind == CheckpointKind::Human {
+            agent_run.will_edit_filepaths = Some(files);
+        } else {
+            agent_run.edited_filepaths = Some(files);
+        }
+    }
+
     // Get the current user name from git config
     let default_user_name = match repo.config_get_str("user.name") {
         Ok(Some(name)) if !name.trim().is_empty() => name,
@@ -442,27 +920,72 @@ fn handle_checkpoint(args: &[String]) {
         }
     };
 
+    if dry_run {
+        match commands::checkpoint::dry_run(
+            &repo,
+            &default_user_name,
+            checkpoint_kind,
+            agent_run_result,
+            CheckpointOptions {
+                human_authors,
+                annotation,
+                reset,
+                ..Default::default()
+            },
+        ) {
+            Ok(preview) => print_checkpoint_preview(preview.as_ref()),
+            Err(e) => exit_with_error("Checkpoint dry run failed", &e),
+        }
+        return;
+    }
+
     let checkpoint_start = std::time::Instant::now();
+    let checkpoint_started_at = std::time::SystemTime::now();
     let agent_tool = agent_run_result.as_ref().map(|r| r.agent_id.tool.clone());
-    let checkpoint_result = commands::checkpoint::run(
+    let checkpoint_result = commands::checkpoint::run_with_options(
         &repo,
         &default_user_name,
         checkpoint_kind,
-        show_working_log,
-        reset,
-        false,
         agent_run_result,
-        false,
+        CheckpointOptions {
+            human_authors,
+            annotation,
+            show_working_log,
+            reset,
+            ..Default::default()
+        },
     );
     match checkpoint_result {
         Ok((_, files_edited, _)) => {
             let elapsed = checkpoint_start.elapsed();
             log_performance_for_checkpoint(files_edited, elapsed, checkpoint_kind);
-            eprintln!("Checkpoint completed in {:?}", elapsed);
+            observability::log_span(
+                "git-ai.checkpoint",
+                checkpoint_started_at,
+                elapsed,
+                false,
+                Some(serde_json::json!({
+                    "checkpoint.kind": checkpoint_kind.to_str(),
+                    "checkpoint.agent": agent_tool.clone().unwrap_or_default(),
+                    "checkpoint.files_edited": files_edited,
+                })),
+            );
+            if !commands::output::quiet() {
+                eprintln!("Checkpoint completed in {:?}", elapsed);
+            }
         }
         Err(e) => {
             let elapsed = checkpoint_start.elapsed();
-            eprintln!("Checkpoint failed after {:?} with error {}", elapsed, e);
+            observability::log_span(
+                "git-ai.checkpoint",
+                checkpoint_started_at,
+                elapsed,
+                true,
+                Some(serde_json::json!({
+                    "checkpoint.kind": checkpoint_kind.to_str(),
+                    "checkpoint.agent": agent_tool.clone().unwrap_or_default(),
+                })),
+            );
             let context = serde_json::json!({
                 "function": "checkpoint",
                 "agent": agent_tool.unwrap_or_default(),
@@ -470,15 +993,62 @@ fn handle_checkpoint(args: &[String]) {
                 "checkpoint_kind": format!("{:?}", checkpoint_kind)
             });
             observability::log_error(&e, Some(context));
-            std::process::exit(1);
+            exit_with_error(&format!("Checkpoint failed after {:?}", elapsed), &e);
         }
     }
 }
 
+/// `checkpoint --dry-run` output: what would have been recorded
+fn print_checkpoint_preview(preview: Option<&commands::checkpoint::CheckpointPreview>) {
+    if commands::output::json_requested(false) {
+        println!("{}", serde_json::to_string_pretty(&preview).unwrap());
+        return;
+    }
+    let Some(preview) = preview else {
+        println!("Dry run: nothing changed since the last checkpoint");
+        return;
+    };
+    println!(
+        "Dry run: {} checkpoint by {} (not recorded)",
+        preview.kind, preview.author_id
+    );
+    for file in &preview.files {
+        let lines = if file.whole_file {
+            "whole file".to_string()
+        } else if file.attributed_lines.is_empty() {
+            // Human lines aren't tracked line by line
+            String::new()
+        } else {
+            let ranges: Vec<String> = file
+                .attributed_lines
+                .iter()
+                .map(|&(start, end)| {
+                    if start == end {
+                        start.to_string()
+                    } else {
+                        format!("{}-{}", start, end)
+                    }
+                })
+                .collect();
+            format!("lines {}", ranges.join(", "))
+        };
+        println!(
+            "{}",
+            format!(
+                "  {}  +{} -{}  {}",
+                file.file, file.additions, file.deletions, lines
+            )
+            .trim_end()
+        );
+    }
+}
+
 fn handle_ai_blame(args: &[String]) {
     if args.is_empty() {
-        eprintln!("Error: blame requires a file argument");
-        std::process::exit(1);
+        exit_with_error(
+            "Error",
+            &GitAiError::InvalidArguments("blame requires a file argument".to_string()),
+        );
     }
 
     // Find the git repository from current directory
@@ -489,19 +1059,67 @@ fn handle_ai_blame(args: &[String]) {
     let repo = match find_repository_in_path(&current_dir) {
         Ok(repo) => repo,
         Err(e) => {
-            eprintln!("Failed to find repository: {}", e);
-            std::process::exit(1);
+            exit_with_error("Failed to find repository", &e);
         }
     };
 
     // Parse blame arguments
-    let (file_path, options) = match commands::blame::parse_blame_args(args) {
+    let (file_path, mut options) = match commands::blame::parse_blame_args(args) {
         Ok(result) => result,
         Err(e) => {
-            eprintln!("Failed to parse blame arguments: {}", e);
-            std::process::exit(1);
+            exit_with_error("Failed to parse blame arguments", &e);
         }
     };
+    options.anonymize = commands::output::anonymize();
+
+    if options.batch {
+        let result = commands::blame::blame_batch(
+            &repo,
+            &options,
+            std::io::stdin().lock(),
+            std::io::stdout().lock(),
+        );
+        if let Err(e) = result {
+            exit_with_error("Blame failed", &e);
+        }
+        return;
+    }
+
+    if let Some(range) = options.reverse.as_deref() {
+        // `--reverse <start>` runs to HEAD, like git's
+        let (start, end) = range.split_once("..").unwrap_or((range, "HEAD"));
+        let resolve = |rev: &str| {
+            let rev = if rev.is_empty() { "HEAD" } else { rev };
+            repo.revparse_single(rev).map(|commit| commit.id())
+        };
+        let mut report = resolve(start).and_then(|start| {
+            resolve(end).and_then(|end| {
+                commands::blame_reverse::reverse_blame(&repo, &file_path, &start, &end)
+            })
+        });
+        if options.anonymize
+            && let Ok(report) = &mut report
+        {
+            for line in &mut report.removed {
+                line.author = anonymize::hash_identity(&line.author);
+            }
+        }
+        match report {
+            Ok(report) if commands::output::structured_requested(false) => {
+                if let Err(e) = commands::output::print_structured("blame-reverse", &report) {
+                    exit_with_error("Failed to write the report", &e);
+                }
+            }
+            Ok(report) => commands::blame_reverse::print_reverse_blame(&report),
+            Err(e) => exit_with_error("Reverse blame failed", &e),
+        }
+        return;
+    }
+
+    if !commands::output::color_enabled() {
+        options.color_lines = false;
+        options.color_by_age = false;
+    }
 
     // Check if this is an interactive terminal
     let is_interactive = std::io::stdout().is_terminal();
@@ -509,13 +1127,51 @@ fn handle_ai_blame(args: &[String]) {
     if is_interactive && options.incremental {
         // For incremental mode in interactive terminal, we need special handling
         // This would typically involve a pager like less
-        eprintln!("Error: incremental mode is not supported in interactive terminal");
-        std::process::exit(1);
+        exit_with_error(
+            "Error",
+            &GitAiError::InvalidArguments(
+                "incremental mode is not supported in interactive terminal".to_string(),
+            ),
+        );
+    }
+
+    // Binary and oversized files have no per-line authors, only a whole-file one
+    if let Some(mut whole_file) =
+        commands::blame::whole_file_blame(&repo, &file_path, options.newest_commit.as_deref())
+    {
+        if options.anonymize {
+            whole_file.prompt.iter_mut().for_each(anonymize::anonymize_prompt);
+        }
+        if commands::output::structured_requested(false) {
+            if let Err(e) = commands::output::print_structured("blame", &whole_file.to_json()) {
+                exit_with_error("Failed to write the report", &e);
+            }
+        } else {
+            println!("{}", whole_file.describe());
+        }
+        return;
+    }
+
+    if commands::output::structured_requested(false) {
+        let options = commands::blame::GitAiBlameOptions {
+            use_prompt_hashes_as_names: true,
+            no_output: true,
+            ..options
+        };
+        match repo.blame(&file_path, &options) {
+            Ok((line_authors, prompt_records)) => {
+                let json = commands::blame::blame_json(&file_path, &line_authors, &prompt_records);
+                if let Err(e) = commands::output::print_structured("blame", &json) {
+                    exit_with_error("Failed to write the report", &e);
+                }
+            }
+            Err(e) => exit_with_error("Blame failed", &e),
+        }
+        return;
     }
 
     if let Err(e) = repo.blame(&file_path, &options) {
-        eprintln!("Blame failed: {}", e);
-        std::process::exit(1);
+        exit_with_error("Blame failed", &e);
     }
 }
 
@@ -527,31 +1183,45 @@ fn handle_ai_diff(args: &[String]) {
     let repo = match find_repository_in_path(&current_dir) {
         Ok(repo) => repo,
         Err(e) => {
-            eprintln!("Failed to find repository: {}", e);
-            std::process::exit(1);
+            exit_with_error("Failed to find repository", &e);
         }
     };
 
     if let Err(e) = commands::diff::handle_diff(&repo, args) {
-        eprintln!("Diff failed: {}", e);
-        std::process::exit(1);
+        exit_with_error("Diff failed", &e);
     }
 }
 
 fn handle_stats(args: &[String]) {
+    // `stats --working` is the working-area view; hand it the rest of the flags
+    if args.iter().any(|arg| arg == "--working") {
+        let rest: Vec<String> = args
+            .iter()
+            .filter(|arg| *arg != "--working")
+            .cloned()
+            .collect();
+        if let Err(e) = commands::working_stats::handle_working_stats(&rest) {
+            exit_with_error("Working stats failed", &e);
+        }
+        return;
+    }
     // Find the git repository
     let repo = match find_repository(&Vec::<String>::new()) {
         Ok(repo) => repo,
         Err(e) => {
-            eprintln!("Failed to find repository: {}", e);
-            std::process::exit(1);
+            exit_with_error("Failed to find repository", &e);
         }
     };
     // Parse stats-specific arguments
-    let mut json_output = false;
+    let mut json_output = commands::output::json_requested(false);
     let mut commit_sha = None;
     let mut commit_range: Option<CommitRange> = None;
     let mut ignore_patterns: Vec<String> = Vec::new();
+    let mut baseline: Option<PathBuf> = None;
+    let mut trivial = TrivialFilter::default();
+    let mut by_symbol = false;
+    let mut age_buckets = false;
+    let mut flow_range: Option<String> = None;
 
     let mut i = 0;
     while i < args.len() {
@@ -560,6 +1230,56 @@ fn handle_stats(args: &[String]) {
                 json_output = true;
                 i += 1;
             }
+            "--baseline" => {
+                let Some(path) = args.get(i + 1) else {
+                    exit_with_error(
+                        "Error",
+                        &GitAiError::InvalidArguments(
+                            "--baseline requires a JSON report saved with `stats --json`"
+                                .to_string(),
+                        ),
+                    );
+                };
+                baseline = Some(PathBuf::from(path));
+                i += 2;
+            }
+            "--by-symbol" => {
+                by_symbol = true;
+                i += 1;
+            }
+            "--age-buckets" => {
+                age_buckets = true;
+                i += 1;
+            }
+            "--attribution-flow" => {
+                let Some(range) = args.get(i + 1).filter(|range| range.contains("..")) else {
+                    exit_with_error(
+                        "Error",
+                        &GitAiError::InvalidArguments(
+                            "--attribution-flow requires a commit range: <commit>..<commit>"
+                                .to_string(),
+                        ),
+                    );
+                };
+                flow_range = Some(range.clone());
+                i += 2;
+            }
+            "--ignore-trivial" => {
+                trivial.ignore_trivial = true;
+                i += 1;
+            }
+            "--min-hunk-size" => {
+                let Some(size) = args.get(i + 1).and_then(|n| n.parse().ok()) else {
+                    exit_with_error(
+                        "Error",
+                        &GitAiError::InvalidArguments(
+                            "--min-hunk-size requires a number of lines".to_string(),
+                        ),
+                    );
+                };
+                trivial.min_hunk_size = size;
+                i += 2;
+            }
             "--ignore" => {
                 // Collect all arguments after --ignore until we hit another flag or commit SHA
                 // This supports shell glob expansion: `--ignore *.lock` expands to `--ignore Cargo.lock package.lock`
@@ -583,8 +1303,12 @@ fn handle_stats(args: &[String]) {
                     i += 1;
                 }
                 if !found_pattern {
-                    eprintln!("--ignore requires at least one pattern argument");
-                    std::process::exit(1);
+                    exit_with_error(
+                        "Error",
+                        &GitAiError::InvalidArguments(
+                            "--ignore requires at least one pattern argument".to_string(),
+                        ),
+                    );
                 }
             }
             _ => {
@@ -606,55 +1330,164 @@ fn handle_stats(args: &[String]) {
                                     commit_range = Some(range);
                                 }
                                 Err(e) => {
-                                    eprintln!("Failed to create commit range: {}", e);
-                                    std::process::exit(1);
+                                    exit_with_error("Failed to create commit range", &e);
                                 }
                             }
                         } else {
-                            eprintln!("Invalid commit range format. Expected: <commit>..<commit>");
-                            std::process::exit(1);
+                            exit_with_error(
+                                "Error",
+                                &GitAiError::InvalidArguments(
+                                    "Invalid commit range format. Expected: <commit>..<commit>"
+                                        .to_string(),
+                                ),
+                            );
                         }
                     } else {
                         commit_sha = Some(arg.clone());
                     }
                     i += 1;
                 } else {
-                    eprintln!("Unknown stats argument: {}", args[i]);
-                    std::process::exit(1);
+                    exit_with_error(
+                        "Error",
+                        &GitAiError::InvalidArguments(format!(
+                            "Unknown stats argument: {}",
+                            args[i]
+                        )),
+                    );
                 }
             }
         }
     }
 
+    if let Some(range) = flow_range {
+        if commit_sha.is_some() || commit_range.is_some() || by_symbol || age_buckets {
+            exit_with_error(
+                "Error",
+                &GitAiError::InvalidArguments(
+                    "--attribution-flow takes its own range and can't be combined with other views"
+                        .to_string(),
+                ),
+            );
+        }
+        let (start, end) = range.split_once("..").unwrap_or_default();
+        let resolve = |rev: &str| {
+            repo.revparse_single(if rev.is_empty() { "HEAD" } else { rev })
+                .map(|commit| commit.id())
+        };
+        let flow = resolve(start).and_then(|start| {
+            resolve(end.trim_start_matches('.')).and_then(|end| {
+                attribution_flow::attribution_flow(&repo, &start, &end, &ignore_patterns)
+            })
+        });
+        match flow {
+            Ok(flow) if json_output => println!("{}", serde_json::to_string(&flow).unwrap()),
+            Ok(flow) => attribution_flow::write_attribution_flow_to_terminal(&flow),
+            Err(e) => exit_with_error("Attribution flow failed", &e),
+        }
+        return;
+    }
+
     // Handle commit range if detected
     if let Some(range) = commit_range {
+        if age_buckets {
+            exit_with_error(
+                "Error",
+                &GitAiError::InvalidArguments(
+                    "--age-buckets looks at the lines of a single commit".to_string(),
+                ),
+            );
+        }
+        if by_symbol {
+            exit_with_error(
+                "Error",
+                &GitAiError::InvalidArguments(
+                    "--by-symbol only applies to a single commit".to_string(),
+                ),
+            );
+        }
+        if trivial.is_active() {
+            exit_with_error(
+                "Error",
+                &GitAiError::InvalidArguments(
+                    "--ignore-trivial and --min-hunk-size only apply to a single commit"
+                        .to_string(),
+                ),
+            );
+        }
         match range_authorship::range_authorship(range, true, &ignore_patterns) {
-            Ok(stats) => {
-                if json_output {
+            Ok(mut stats) => {
+                if commands::output::anonymize() {
+                    anonymize::anonymize_range_stats(&mut stats);
+                }
+                if let Some(baseline) = &baseline {
+                    if let Err(e) = stats_baseline::print_comparison_with_baseline(
+                        &stats.range_stats,
+                        baseline,
+                        json_output,
+                    ) {
+                        exit_with_error("Baseline comparison failed", &e);
+                    }
+                } else if json_output {
                     let json_str = serde_json::to_string(&stats).unwrap();
                     println!("{}", json_str);
+                } else if let Some(renderer) = commands::output::renderer() {
+                    let data = serde_json::to_value(&stats).unwrap();
+                    if let Err(e) =
+                        commands::renderers::print_report(&renderer, "stats-range", &data)
+                    {
+                        exit_with_error("Failed to render stats", &e);
+                    }
                 } else {
                     range_authorship::print_range_authorship_stats(&stats);
                 }
             }
             Err(e) => {
-                eprintln!("Range authorship failed: {}", e);
-                std::process::exit(1);
+                exit_with_error("Range authorship failed", &e);
             }
         }
         return;
     }
 
-    if let Err(e) = stats_command(&repo, commit_sha.as_deref(), json_output, &ignore_patterns) {
+    if age_buckets {
+        let report = repo
+            .revparse_single(commit_sha.as_deref().unwrap_or("HEAD"))
+            .and_then(|commit| {
+                line_age::age_buckets(&repo, &commit.id(), &ignore_patterns, chrono::Utc::now())
+            });
+        match report {
+            Ok(report) if json_output => println!("{}", serde_json::to_string(&report).unwrap()),
+            Ok(report) => line_age::write_age_buckets_to_terminal(&report),
+            Err(e) => exit_with_error("Age stats failed", &e),
+        }
+        return;
+    }
+
+    if by_symbol {
+        let report = repo
+            .revparse_single(commit_sha.as_deref().unwrap_or("HEAD"))
+            .and_then(|commit| symbols::symbol_stats(&repo, &commit.id(), &ignore_patterns));
+        match report {
+            Ok(report) if json_output => println!("{}", serde_json::to_string(&report).unwrap()),
+            Ok(report) => symbols::write_symbol_stats_to_terminal(&report),
+            Err(e) => exit_with_error("Symbol stats failed", &e),
+        }
+        return;
+    }
+
+    if let Err(e) = stats_command(
+        &repo,
+        commit_sha.as_deref(),
+        json_output,
+        &ignore_patterns,
+        baseline.as_deref(),
+        trivial,
+    ) {
         match e {
-            crate::error::GitAiError::Generic(msg) if msg.starts_with("No commit found:") => {
-                eprintln!("{}", msg);
-            }
-            _ => {
-                eprintln!("Stats failed: {}", e);
+            GitAiError::Generic(msg) if msg.starts_with("No commit found:") => {
+                exit_with_error("Error", &GitAiError::InvalidArguments(msg));
             }
+            _ => exit_with_error("Stats failed", &e),
         }
-        std::process::exit(1);
     }
 }
 
@@ -668,7 +1501,10 @@ fn get_all_files_for_mock_ai(working_dir: &str) -> Vec<String> {
         }
     };
     match repo.get_staged_and_unstaged_filenames() {
-        Ok(filenames) => filenames.into_iter().collect(),
+        Ok(filenames) => match repo.workdir() {
+            Ok(workdir) => AiIgnore::load(&workdir).filter(filenames),
+            Err(_) => filenames.into_iter().collect(),
+        },
         Err(_) => Vec::new(),
     }
 }
@@ -1,3 +1,5 @@
+use crate::commands::explain;
+use crate::commands::git_hooks::WRAPPED_COMMAND_ENV;
 use crate::commands::hooks::cherry_pick_hooks;
 use crate::commands::hooks::clone_hooks;
 use crate::commands::hooks::commit_hooks;
@@ -7,14 +9,25 @@ use crate::commands::hooks::push_hooks;
 use crate::commands::hooks::rebase_hooks;
 use crate::commands::hooks::reset_hooks;
 use crate::commands::hooks::stash_hooks;
+use crate::commands::hooks::{CommandHooks, command_hooks};
+use crate::commands::output;
+use crate::commands::repo_prompt;
+use crate::commands::safe_mode;
+#[cfg(windows)]
+use crate::commands::windows_child;
 use crate::config;
+use crate::error::{GitAiError, exit_with_error};
 use crate::git::cli_parser::{ParsedGitInvocation, parse_git_cli_args};
 use crate::git::find_repository;
+use crate::git::git_version;
 use crate::git::repository::Repository;
 use crate::observability;
+use crate::observability::metrics;
 
 use crate::observability::wrapper_performance_targets::log_performance_target_if_violated;
 use crate::utils::debug_log;
+#[cfg(windows)]
+use std::io::IsTerminal;
 #[cfg(unix)]
 use std::os::unix::process::CommandExt;
 #[cfg(unix)]
@@ -27,7 +40,7 @@ use std::time::Instant;
 #[cfg(unix)]
 static CHILD_PGID: AtomicI32 = AtomicI32::new(0);
 
-/// Error type for hook panics
+/// Error type for hook errors and panics
 #[derive(Debug)]
 struct HookPanicError(String);
 
@@ -39,6 +52,8 @@ impl std::fmt::Display for HookPanicError {
 
 impl std::error::Error for HookPanicError {}
 
+type HookResult = Result<(), GitAiError>;
+
 #[cfg(unix)]
 extern "C" fn forward_signal_handler(sig: libc::c_int) {
     let pgid = CHILD_PGID.load(Ordering::Relaxed);
@@ -78,6 +93,7 @@ pub struct CommandHooksContext {
     pub fetch_authorship_handle: Option<std::thread::JoinHandle<()>>,
     pub stash_sha: Option<String>,
     pub push_authorship_handle: Option<std::thread::JoinHandle<()>>,
+    pub push_summary_commits: Option<Vec<String>>,
 }
 
 /// 处理 git 命令的主入口函数
@@ -146,11 +162,29 @@ pub fn handle_git(args: &[String]) {
         return;
     }
 
+    // GIT_AI_EXPLAIN=1：只打印这条命令会运行哪些 hooks、读写什么以及原因，
+    // 不执行 git 和任何 hook（与 `git-ai explain <git args...>` 相同）
+    if explain::explain_requested() {
+        explain::handle_explain(args);
+        std::process::exit(0);
+    }
+
     // 步骤 2: 解析 git 命令行参数
     // 将原始参数字符串数组解析为结构化的 ParsedGitInvocation 对象
     // 包含：命令名称、全局选项、命令选项、是否为 help 请求等
     let mut parsed_args = parse_git_cli_args(args);
 
+    // 只读命令快速路径：status、log、rev-parse 等命令一天要执行成百上千次，
+    // 且不会改变 git-ai 跟踪的任何内容，因此不查找仓库、不运行任何 hooks，
+    // 直接透传给真实 git 并保留其退出码（列表可通过配置 read_only_commands 修改）
+    if parsed_args
+        .command
+        .as_deref()
+        .is_some_and(|command| config::Config::get().is_read_only_command(command))
+    {
+        exit_with_status(proxy_to_git(&parsed_args.to_invocation_vec(), false));
+    }
+
     // 步骤 3: 查找 git 仓库
     // 基于全局参数（如 -C、--git-dir）尝试定位 git 仓库
     // 返回 Option<Repository>，如果不在 git 仓库中则为 None
@@ -173,6 +207,8 @@ pub fn handle_git(args: &[String]) {
     // - git_path: 实际 git 命令的路径（如 /usr/bin/git）
     // - allow_repositories: 允许使用 git-ai 的仓库白名单（支持 glob 模式）
     // - exclude_repositories: 排除使用 git-ai 的仓库黑名单（支持 glob 模式）
+    // - prompt_unlisted_repositories: 是否在终端上询问两个列表都不匹配的仓库是否启用 git-ai
+    // - read_only_commands: 跳过仓库查找和 hooks、直接透传的只读命令（见步骤 2）
     // - ignore_prompts: 是否忽略提示（通常用于自动化场景）
     // - telemetry_oss: OSS 遥测开关（"off" 表示关闭）
     // - telemetry_enterprise_dsn: 企业版遥测数据上报地址
@@ -194,7 +230,10 @@ pub fn handle_git(args: &[String]) {
     //   "allow_repositories": ["https://github.com/myorg/*"],
     //   "exclude_repositories": ["https://github.com/myorg/private-*"]
     // }
-    let skip_hooks = !config.is_allowed_repository(&repository_option);
+    //
+    // 开启 prompt_unlisted_repositories 时，两个列表都不匹配的仓库会在终端上询问一次，
+    // 并把回答写回配置
+    let skip_hooks = !repo_prompt::is_allowed_repository(config, &repository_option);
 
     if skip_hooks {
         debug_log("跳过 git-ai hooks，因为仓库在排除列表中或不在 allow_repositories 列表中");
@@ -203,7 +242,10 @@ pub fn handle_git(args: &[String]) {
     // 步骤 6: 特殊处理 clone 命令
     // clone 命令比较特殊：仓库在命令执行前不存在
     // 因此需要先执行 git clone，再在新仓库中执行 post-clone hook
-    if parsed_args.command.as_deref() == Some("clone") && !parsed_args.is_help && !skip_hooks {
+    if command_hooks(parsed_args.command.as_deref()) == Some(CommandHooks::Clone)
+        && !parsed_args.is_help
+        && !skip_hooks
+    {
         // 执行实际的 git clone 命令
         let exit_status = proxy_to_git(&parsed_args.to_invocation_vec(), false);
         // 在新创建的仓库中执行 post-clone hook（如初始化 git-ai 配置）
@@ -212,8 +254,19 @@ pub fn handle_git(args: &[String]) {
         exit_with_status(exit_status);
     }
 
+    // 安全模式：该仓库的 hooks 连续失败次数达到 safe_mode_after_failures 后，
+    // 不再运行 hooks，只执行 git 并打印一行提示，直到 `git-ai doctor --enable-hooks`
+    let safe_mode = !parsed_args.is_help
+        && !skip_hooks
+        && repository_option
+            .as_ref()
+            .is_some_and(|repo| safe_mode::is_active(&repo.storage));
+    if let Some(repo) = repository_option.as_ref().filter(|_| safe_mode) {
+        safe_mode::print_notice(&safe_mode::HookFailures::load(&repo.storage));
+    }
+
     // 步骤 7: 执行带 hooks 的 git 命令（或不带 hooks）
-    let exit_status = if !parsed_args.is_help && has_repo && !skip_hooks {
+    let exit_status = if !parsed_args.is_help && has_repo && !skip_hooks && !safe_mode {
         // 条件满足时执行完整的 pre-hook -> git -> post-hook 流程：
         // - 不是 help 请求
         // - 找到了 git 仓库
@@ -227,15 +280,26 @@ pub fn handle_git(args: &[String]) {
             fetch_authorship_handle: None, // fetch 归属数据的异步任务句柄
             stash_sha: None,               // stash 操作的 SHA
             push_authorship_handle: None,  // push 归属数据的异步任务句柄
+            push_summary_commits: None,    // 本次 push 将推送的提交（用于归属摘要）
         };
 
         let repository = repository_option.as_mut().unwrap();
 
         // 阶段 1: 执行 Pre-command Hooks
         let pre_command_start = Instant::now();
-        run_pre_command_hooks(&mut command_hooks_context, &mut parsed_args, repository);
+        let pre_command_error =
+            run_pre_command_hooks(&mut command_hooks_context, &mut parsed_args, repository);
         let pre_command_duration = pre_command_start.elapsed();
 
+        // pre-commit 出错时不执行提交（记录本次失败后退出）
+        if parsed_args.command.as_deref() == Some("commit")
+            && command_hooks_context.pre_commit_hook_result == Some(false)
+            && let Some(error) = &pre_command_error
+        {
+            record_hook_outcome(repository, "commit", Some(error), config);
+            std::process::exit(1);
+        }
+
         // 阶段 2: 代理执行实际的 git 命令
         let git_start = Instant::now();
         let exit_status = proxy_to_git(&parsed_args.to_invocation_vec(), false);
@@ -243,7 +307,7 @@ pub fn handle_git(args: &[String]) {
 
         // 阶段 3: 执行 Post-command Hooks
         let post_command_start = Instant::now();
-        run_post_command_hooks(
+        let post_command_error = run_post_command_hooks(
             &mut command_hooks_context,
             &parsed_args,
             exit_status,
@@ -251,6 +315,14 @@ pub fn handle_git(args: &[String]) {
         );
         let post_command_duration = post_command_start.elapsed();
 
+        // 记录本次 hooks 是否失败；连续失败达到阈值时进入安全模式
+        record_hook_outcome(
+            repository,
+            parsed_args.command.as_deref().unwrap_or("unknown"),
+            pre_command_error.or(post_command_error).as_deref(),
+            config,
+        );
+
         // 步骤 8: 性能监控
         // 如果任一阶段超过预设的性能目标，记录警告日志
         log_performance_target_if_violated(
@@ -259,6 +331,18 @@ pub fn handle_git(args: &[String]) {
             git_duration,
             post_command_duration,
         );
+        observability::log_command_phase_spans(
+            parsed_args.command.as_deref().unwrap_or("unknown"),
+            pre_command_duration,
+            git_duration,
+            post_command_duration,
+        );
+        metrics::record_hook_durations(
+            &repository.storage.metrics,
+            parsed_args.command.as_deref().unwrap_or("unknown"),
+            pre_command_duration,
+            post_command_duration,
+        );
 
         exit_status
     } else {
@@ -272,6 +356,46 @@ pub fn handle_git(args: &[String]) {
     exit_with_status(exit_status);
 }
 
+/// 记录本次 hooks 的结果；连续失败达到阈值时进入安全模式并打印提示
+fn record_hook_outcome(
+    repository: &Repository,
+    command: &str,
+    error: Option<&str>,
+    config: &config::Config,
+) {
+    let entered_safe_mode = safe_mode::record_hook_outcome(
+        &repository.storage,
+        command,
+        error,
+        config.safe_mode_after_failures(),
+    );
+    if entered_safe_mode {
+        safe_mode::print_notice(&safe_mode::HookFailures::load(&repository.storage));
+    }
+}
+
+/// Whether `disabled_hooks` in the config leaves this subcommand's hooks on
+///
+/// 真实 git 版本过旧、缺少钩子依赖的功能时也会跳过钩子（见 `git::git_version`）
+fn hooks_enabled(parsed_args: &ParsedGitInvocation) -> bool {
+    parsed_args.command.as_deref().is_none_or(|command| {
+        if !config::Config::get().command_hooks_enabled(command) {
+            return false;
+        }
+        // 无法确定版本时不禁用任何钩子
+        let Some(version) = git_version::detected() else {
+            return true;
+        };
+        match git_version::unsupported_reason(version, command) {
+            Some(reason) => {
+                debug_log(&format!("跳过 git {} 的 git-ai hooks: {}", command, reason));
+                false
+            }
+            None => true,
+        }
+    })
+}
+
 /// 在 git 命令执行前运行相应的 pre-command hooks
 ///
 /// # 参数
@@ -282,33 +406,39 @@ pub fn handle_git(args: &[String]) {
 /// # 功能
 /// - 根据 git 命令类型（commit, rebase, push 等）执行对应的前置钩子
 /// - 使用 panic 捕获机制确保即使钩子代码出错也不会中断 git 命令执行
-/// - 记录所有 panic 错误到日志和可观测性系统
+/// - 记录钩子返回的错误和 panic 到日志和可观测性系统，并返回错误信息（用于安全模式计数）
 fn run_pre_command_hooks(
     command_hooks_context: &mut CommandHooksContext,
     parsed_args: &mut ParsedGitInvocation,
     repository: &mut Repository,
-) {
+) -> Option<String> {
     // 使用 catch_unwind 捕获可能发生的 panic，防止整个程序崩溃
     // AssertUnwindSafe 告诉编译器这些引用在 panic 后是安全的
-    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| -> HookResult {
+        // 配置中 disabled_hooks 列出的子命令不执行任何钩子
+        if !hooks_enabled(parsed_args) {
+            return Ok(());
+        }
         // 根据 git 命令类型执行对应的 pre-hook
-        match parsed_args.command.as_deref() {
+        match command_hooks(parsed_args.command.as_deref()) {
             // commit 命令：创建 checkpoint 记录代码归属
-            Some("commit") => {
-                command_hooks_context.pre_commit_hook_result = Some(
-                    commit_hooks::commit_pre_command_hook(parsed_args, repository),
-                );
+            // 失败时记为 false，调用方据此中止提交
+            Some(CommandHooks::Commit) => {
+                let result = commit_hooks::commit_pre_command_hook(parsed_args, repository);
+                command_hooks_context.pre_commit_hook_result =
+                    Some(*result.as_ref().unwrap_or(&false));
+                result?;
             }
             // rebase 命令：保存 rebase 前的状态
-            Some("rebase") => {
+            Some(CommandHooks::Rebase) => {
                 rebase_hooks::pre_rebase_hook(parsed_args, repository, command_hooks_context);
             }
             // reset 命令：记录 reset 前的状态
-            Some("reset") => {
+            Some(CommandHooks::Reset) => {
                 reset_hooks::pre_reset_hook(parsed_args, repository);
             }
             // cherry-pick 命令：记录 cherry-pick 前的状态
-            Some("cherry-pick") => {
+            Some(CommandHooks::CherryPick) => {
                 cherry_pick_hooks::pre_cherry_pick_hook(
                     parsed_args,
                     repository,
@@ -316,54 +446,64 @@ fn run_pre_command_hooks(
                 );
             }
             // push 命令：启动异步线程处理 authorship 数据推送
-            Some("push") => {
+            Some(CommandHooks::Push) => {
+                command_hooks_context.push_summary_commits =
+                    push_hooks::commits_to_push(parsed_args, repository);
                 command_hooks_context.push_authorship_handle =
                     push_hooks::push_pre_command_hook(parsed_args, repository);
             }
             // fetch/pull 命令：启动异步线程处理 authorship 数据拉取
-            Some("fetch") | Some("pull") => {
+            Some(CommandHooks::FetchPull) => {
                 command_hooks_context.fetch_authorship_handle =
                     fetch_hooks::fetch_pull_pre_command_hook(parsed_args, repository);
             }
             // stash 命令：根据特性开关决定是否执行钩子
-            Some("stash") => {
+            Some(CommandHooks::Stash) => {
                 let config = config::Config::get();
 
                 if config.feature_flags().rewrite_stash {
                     stash_hooks::pre_stash_hook(parsed_args, repository, command_hooks_context);
                 }
             }
+            // merge 只有 post-hook；clone 由 handle_git 单独处理
+            Some(CommandHooks::Merge) | Some(CommandHooks::Clone) => {}
             // 其他命令：不需要 pre-hook
-            _ => {}
+            None => {}
         }
+        Ok(())
     }));
 
-    // 处理 panic 错误（如果发生）
-    if let Err(panic_payload) = result {
+    // 处理钩子返回的错误或 panic（如果发生）
+    let error_message = match result {
+        Ok(Ok(())) => return None,
+        Ok(Err(e)) => format!("Error in run_pre_command_hooks: {}", e),
         // 尝试提取可读的错误信息
-        let error_message = if let Some(message) = panic_payload.downcast_ref::<&str>() {
-            format!("Panic in run_pre_command_hooks: {}", message)
-        } else if let Some(message) = panic_payload.downcast_ref::<String>() {
-            format!("Panic in run_pre_command_hooks: {}", message)
-        } else {
-            "Panic in run_pre_command_hooks: unknown panic".to_string()
-        };
-
-        // 构建错误上下文信息（包含命令名和参数）
-        let command_name = parsed_args.command.as_deref().unwrap_or("unknown");
-        let context = serde_json::json!({
-            "function": "run_pre_command_hooks",
-            "command": command_name,
-            "args": parsed_args.to_invocation_vec(),
-        });
-
-        // 记录错误到调试日志和可观测性系统
-        debug_log(&error_message);
-        observability::log_error(&HookPanicError(error_message.clone()), Some(context));
+        Err(panic_payload) => {
+            if let Some(message) = panic_payload.downcast_ref::<&str>() {
+                format!("Panic in run_pre_command_hooks: {}", message)
+            } else if let Some(message) = panic_payload.downcast_ref::<String>() {
+                format!("Panic in run_pre_command_hooks: {}", message)
+            } else {
+                "Panic in run_pre_command_hooks: unknown panic".to_string()
+            }
+        }
+    };
 
-        // 注意：即使发生 panic，函数也会正常返回
-        // 这确保 git-ai 的问题不会阻止用户使用 git（优雅降级）
-    }
+    // 构建错误上下文信息（包含命令名和参数）
+    let command_name = parsed_args.command.as_deref().unwrap_or("unknown");
+    let context = serde_json::json!({
+        "function": "run_pre_command_hooks",
+        "command": command_name,
+        "args": parsed_args.to_invocation_vec(),
+    });
+
+    // 记录错误到调试日志和可观测性系统
+    debug_log(&error_message);
+    observability::log_error(&HookPanicError(error_message.clone()), Some(context));
+
+    // 注意：即使钩子出错或发生 panic，函数也会正常返回
+    // 这确保 git-ai 的问题不会阻止用户使用 git（优雅降级）
+    Some(error_message)
 }
 
 fn run_post_command_hooks(
@@ -371,43 +511,57 @@ fn run_post_command_hooks(
     parsed_args: &ParsedGitInvocation,
     exit_status: std::process::ExitStatus,
     repository: &mut Repository,
-) {
-    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+) -> Option<String> {
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| -> HookResult {
+        if !hooks_enabled(parsed_args) {
+            return Ok(());
+        }
         // Post-command hooks
-        match parsed_args.command.as_deref() {
-            Some("commit") => commit_hooks::commit_post_command_hook(
+        match command_hooks(parsed_args.command.as_deref()) {
+            Some(CommandHooks::Commit) => commit_hooks::commit_post_command_hook(
                 parsed_args,
                 exit_status,
                 repository,
                 command_hooks_context,
             ),
-            Some("fetch") | Some("pull") => fetch_hooks::fetch_pull_post_command_hook(
-                repository,
-                parsed_args,
-                exit_status,
-                command_hooks_context,
-            ),
-            Some("push") => push_hooks::push_post_command_hook(
-                repository,
-                parsed_args,
-                exit_status,
-                command_hooks_context,
-            ),
-            Some("reset") => reset_hooks::post_reset_hook(parsed_args, repository, exit_status),
-            Some("merge") => merge_hooks::post_merge_hook(parsed_args, exit_status, repository),
-            Some("rebase") => rebase_hooks::handle_rebase_post_command(
+            Some(CommandHooks::FetchPull) => {
+                fetch_hooks::fetch_pull_post_command_hook(
+                    repository,
+                    parsed_args,
+                    exit_status,
+                    command_hooks_context,
+                );
+                Ok(())
+            }
+            Some(CommandHooks::Push) => {
+                push_hooks::push_post_command_hook(
+                    repository,
+                    parsed_args,
+                    exit_status,
+                    command_hooks_context,
+                );
+                Ok(())
+            }
+            Some(CommandHooks::Reset) => {
+                reset_hooks::post_reset_hook(parsed_args, repository, exit_status);
+                Ok(())
+            }
+            Some(CommandHooks::Merge) => {
+                merge_hooks::post_merge_hook(parsed_args, exit_status, repository)
+            }
+            Some(CommandHooks::Rebase) => rebase_hooks::handle_rebase_post_command(
                 command_hooks_context,
                 parsed_args,
                 exit_status,
                 repository,
             ),
-            Some("cherry-pick") => cherry_pick_hooks::post_cherry_pick_hook(
+            Some(CommandHooks::CherryPick) => cherry_pick_hooks::post_cherry_pick_hook(
                 command_hooks_context,
                 parsed_args,
                 exit_status,
                 repository,
             ),
-            Some("stash") => {
+            Some(CommandHooks::Stash) => {
                 let config = config::Config::get();
 
                 if config.feature_flags().rewrite_stash {
@@ -418,32 +572,39 @@ fn run_post_command_hooks(
                         exit_status,
                     );
                 }
+                Ok(())
             }
-            _ => {}
+            // Clone's post hook runs from handle_git, in the new repository
+            Some(CommandHooks::Clone) | None => Ok(()),
         }
     }));
 
-    if let Err(panic_payload) = result {
-        let error_message = if let Some(message) = panic_payload.downcast_ref::<&str>() {
-            format!("Panic in run_post_command_hooks: {}", message)
-        } else if let Some(message) = panic_payload.downcast_ref::<String>() {
-            format!("Panic in run_post_command_hooks: {}", message)
-        } else {
-            "Panic in run_post_command_hooks: unknown panic".to_string()
-        };
+    let error_message = match result {
+        Ok(Ok(())) => return None,
+        Ok(Err(e)) => format!("Error in run_post_command_hooks: {}", e),
+        Err(panic_payload) => {
+            if let Some(message) = panic_payload.downcast_ref::<&str>() {
+                format!("Panic in run_post_command_hooks: {}", message)
+            } else if let Some(message) = panic_payload.downcast_ref::<String>() {
+                format!("Panic in run_post_command_hooks: {}", message)
+            } else {
+                "Panic in run_post_command_hooks: unknown panic".to_string()
+            }
+        }
+    };
 
-        let command_name = parsed_args.command.as_deref().unwrap_or("unknown");
-        let exit_code = exit_status.code().unwrap_or(-1);
-        let context = serde_json::json!({
-            "function": "run_post_command_hooks",
-            "command": command_name,
-            "exit_code": exit_code,
-            "args": parsed_args.to_invocation_vec(),
-        });
-
-        debug_log(&error_message);
-        observability::log_error(&HookPanicError(error_message.clone()), Some(context));
-    }
+    let command_name = parsed_args.command.as_deref().unwrap_or("unknown");
+    let exit_code = exit_status.code().unwrap_or(-1);
+    let context = serde_json::json!({
+        "function": "run_post_command_hooks",
+        "command": command_name,
+        "exit_code": exit_code,
+        "args": parsed_args.to_invocation_vec(),
+    });
+
+    debug_log(&error_message);
+    observability::log_error(&HookPanicError(error_message.clone()), Some(context));
+    Some(error_message)
 }
 
 /// 将 git 命令代理转发到真实的 git 可执行文件
@@ -477,7 +638,7 @@ fn run_post_command_hooks(
 /// 真实 git 命令的退出状态（ExitStatus）
 ///
 /// # 示例
-/// ```
+/// ```ignore
 /// // 用户执行: git commit -m "fix bug"
 /// // git-ai 拦截后调用:
 /// proxy_to_git(&["commit", "-m", "fix bug"], false)
@@ -488,11 +649,15 @@ fn proxy_to_git(args: &[String], exit_on_completion: bool) -> std::process::Exit
     let config = config::Config::get();
     let git_path = config.git_cmd();
     let git_source = config.git_cmd_source();
-    eprintln!("[git-ai] 真实 git 路径: {}", git_path);
-    eprintln!("[git-ai] 查找方式: {}", git_source);
+    if !output::quiet() {
+        eprintln!("[git-ai] 真实 git 路径: {}", git_path);
+        eprintln!("[git-ai] 查找方式: {}", git_source);
+    }
 
     // 检查是否为commit命令，需要禁用prepare-commit-msg钩子
     let is_commit_command = args.first().map(|s| s.as_str()) == Some("commit");
+    // 告诉 git 钩子 shim 当前命令由 wrapper 执行（wrapper 自己的 hooks 会处理 rebase 的归属）
+    let wrapped_command = parse_git_cli_args(args).command.unwrap_or_default();
 
     // 使用 spawn 方式启动子进程，支持交互式命令（如 rebase -i、commit 编辑器等）
     let child = {
@@ -506,6 +671,7 @@ fn proxy_to_git(args: &[String], exit_on_completion: bool) -> std::process::Exit
 
             let mut cmd = Command::new(config::Config::get().git_cmd());
             cmd.args(args);
+            cmd.env(WRAPPED_COMMAND_ENV, &wrapped_command);
 
             // 为commit命令设置环境变量，禁用prepare-commit-msg钩子
             if is_commit_command {
@@ -535,6 +701,15 @@ fn proxy_to_git(args: &[String], exit_on_completion: bool) -> std::process::Exit
         {
             let mut cmd = Command::new(config::Config::get().git_cmd());
             cmd.args(args);
+            cmd.env(WRAPPED_COMMAND_ENV, &wrapped_command);
+
+            // Same rule as the Unix branch: only detach git from the console
+            // process group when stdin isn't interactive
+            #[cfg(windows)]
+            windows_child::prepare_command(
+                &mut cmd,
+                windows_child::should_isolate(std::io::stdin().is_terminal()),
+            );
 
             // 为commit命令设置环境变量，禁用prepare-commit-msg钩子
             if is_commit_command {
@@ -583,20 +758,24 @@ fn proxy_to_git(args: &[String], exit_on_completion: bool) -> std::process::Exit
                             uninstall_forwarding_handlers();
                         }
                     }
-                    eprintln!("Failed to wait for git process: {}", e);
-                    std::process::exit(1);
+                    exit_with_error("Failed to wait for git process", &GitAiError::IoError(e));
                 }
             }
         }
         Err(e) => {
-            eprintln!("Failed to execute git command: {}", e);
-            std::process::exit(1);
+            exit_with_error("Failed to execute git command", &GitAiError::IoError(e));
         }
     }
 
     #[cfg(not(unix))]
     match child {
         Ok(mut child) => {
+            // Job object and Ctrl+C forwarding last until git exits
+            #[cfg(windows)]
+            let _guard = windows_child::ChildGuard::attach(
+                &child,
+                windows_child::should_isolate(std::io::stdin().is_terminal()),
+            );
             let status = child.wait();
             match status {
                 Ok(status) => {
@@ -606,14 +785,12 @@ fn proxy_to_git(args: &[String], exit_on_completion: bool) -> std::process::Exit
                     return status;
                 }
                 Err(e) => {
-                    eprintln!("Failed to wait for git process: {}", e);
-                    std::process::exit(1);
+                    exit_with_error("Failed to wait for git process", &GitAiError::IoError(e));
                 }
             }
         }
         Err(e) => {
-            eprintln!("Failed to execute git command: {}", e);
-            std::process::exit(1);
+            exit_with_error("Failed to execute git command", &GitAiError::IoError(e));
         }
     }
 }
@@ -642,3 +819,66 @@ fn in_shell_completion_context() -> bool {
         || std::env::var("COMP_POINT").is_ok()
         || std::env::var("COMP_TYPE").is_ok()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::repository::find_repository_in_path;
+    use crate::git::test_utils::TmpRepo;
+    use std::fs;
+
+    #[test]
+    fn test_post_hook_errors_count_toward_safe_mode() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "one\n", true).unwrap();
+        tmp_repo.trigger_checkpoint_with_author("dev").unwrap();
+        tmp_repo.commit_with_message("Initial").unwrap();
+
+        let mut repository = find_repository_in_path(&tmp_repo.path().to_string_lossy()).unwrap();
+        // A working log that can't be parsed fails the post-commit authorship rewrite
+        let working_log = repository
+            .storage
+            .working_log_for_base_commit(&tmp_repo.head_commit_sha().unwrap());
+        fs::write(working_log.dir.join("checkpoints.jsonl"), "not json\n").unwrap();
+        repository.pre_command_base_commit = Some(tmp_repo.head_commit_sha().unwrap());
+
+        let exit_status = Command::new(config::Config::get().git_cmd())
+            .current_dir(tmp_repo.path())
+            .args(["commit", "--allow-empty", "-m", "Second"])
+            .status()
+            .unwrap();
+        let parsed_args = parse_git_cli_args(&[
+            "commit".to_string(),
+            "--allow-empty".to_string(),
+            "-m".to_string(),
+            "Second".to_string(),
+        ]);
+        let mut context = CommandHooksContext {
+            pre_commit_hook_result: Some(true),
+            rebase_original_head: None,
+            _rebase_onto: None,
+            fetch_authorship_handle: None,
+            stash_sha: None,
+            push_authorship_handle: None,
+            push_summary_commits: None,
+        };
+
+        let error =
+            run_post_command_hooks(&mut context, &parsed_args, exit_status, &mut repository)
+                .expect("hook error is returned");
+        assert!(error.starts_with("Error in run_post_command_hooks"));
+
+        assert!(!safe_mode::record_hook_outcome(
+            &repository.storage,
+            "commit",
+            Some(&error),
+            2
+        ));
+        assert!(safe_mode::record_hook_outcome(
+            &repository.storage,
+            "commit",
+            Some(&error),
+            2
+        ));
+    }
+}
@@ -0,0 +1,572 @@
+//! Git hook shims for commits that don't go through the git-ai wrapper (IDE
+//! commit buttons, GUI clients, scripts calling the real git).
+//!
+//! `install-hooks` writes a small `pre-commit`/`prepare-commit-msg`/`post-commit`/
+//! `post-rewrite` script into the repository's effective hooks directory, which
+//! honours `core.hooksPath` (so it lands wherever a hook manager points git). A
+//! hook that was already there is kept as `<hook>.pre-git-ai` and run first by
+//! the shim. husky is the exception: its `.husky/_` dispatcher runs
+//! `.husky/<hook>` by file name, so the git-ai call is appended to that script.
+//! The wrapper disables hooks for the commits it runs itself and marks the
+//! rebases and cherry-picks it runs, so nothing is recorded twice.
+//! `post-rewrite` carries authorship over to commits rewritten by tools that
+//! run it, like git-branchless, and is only installed where such a tool is
+//! set up.
+
+use crate::authorship::commit_template::render_message_file;
+use crate::authorship::restack::{
+    detect_stack_tools, parse_post_rewrite_input, remap_rewritten_commits,
+};
+use crate::commands::hooks::commit_hooks::get_commit_default_author;
+use crate::error::GitAiError;
+use crate::git::repository::{Repository, exec_git};
+use crate::git::rewrite_log::RewriteLogEvent;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+pub const MANAGED_HOOKS: [&str; 4] = [
+    "pre-commit",
+    "prepare-commit-msg",
+    "post-commit",
+    "post-rewrite",
+];
+
+/// Set by the wrapper to the git subcommand it runs, for the shims to see
+pub const WRAPPED_COMMAND_ENV: &str = "GIT_AI_WRAPPED_COMMAND";
+
+const SHIM_MARKER: &str = "# git-ai hook shim";
+const CHAINED_SUFFIX: &str = "pre-git-ai";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookManager {
+    Husky,
+    Lefthook,
+}
+
+impl HookManager {
+    fn name(&self) -> &'static str {
+        match self {
+            HookManager::Husky => "husky",
+            HookManager::Lefthook => "lefthook",
+        }
+    }
+}
+
+/// Where git will look for hooks in this repository
+#[derive(Debug)]
+pub struct HooksLocation {
+    pub dir: PathBuf,
+    /// `core.hooksPath`, if set
+    pub hooks_path_config: Option<String>,
+    /// False when `core.hooksPath` points outside the repository (e.g. a global
+    /// hooks directory shared by every repo)
+    pub inside_repo: bool,
+    pub manager: Option<HookManager>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookState {
+    Missing,
+    /// Some other script, not chained yet
+    Foreign,
+    Installed,
+    /// Our shim, but for a different binary path
+    Outdated,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookAction {
+    Created,
+    /// Existing hook moved aside and chained
+    Chained,
+    Updated,
+    UpToDate,
+    /// git-ai call added to the end of an existing husky hook
+    Appended,
+}
+
+pub fn locate_hooks(repo: &Repository) -> Result<HooksLocation, GitAiError> {
+    let workdir = repo.workdir()?;
+    let mut args = repo.global_args_for_exec();
+    args.extend(["rev-parse", "--git-path", "hooks"].map(String::from));
+    let output = exec_git(&args)?;
+    let raw = String::from_utf8(output.stdout)?.trim().to_string();
+    let dir = if Path::new(&raw).is_absolute() {
+        PathBuf::from(raw)
+    } else {
+        workdir.join(raw)
+    };
+
+    let hooks_path_config = repo.config_get_str("core.hooksPath")?;
+    let inside_repo = dir.starts_with(&workdir) || dir.starts_with(repo.path());
+    let manager = detect_manager(&workdir, &dir);
+
+    Ok(HooksLocation {
+        dir,
+        hooks_path_config,
+        inside_repo,
+        manager,
+    })
+}
+
+fn detect_manager(workdir: &Path, hooks_dir: &Path) -> Option<HookManager> {
+    if hooks_dir.components().any(|c| c.as_os_str() == ".husky") {
+        return Some(HookManager::Husky);
+    }
+    let lefthook_configs = ["lefthook.yml", "lefthook.yaml", ".lefthook.yml"];
+    let hook_mentions_lefthook = MANAGED_HOOKS.iter().any(|name| {
+        fs::read_to_string(hooks_dir.join(name)).is_ok_and(|content| content.contains("lefthook"))
+    });
+    if hook_mentions_lefthook || lefthook_configs.iter().any(|f| workdir.join(f).exists()) {
+        return Some(HookManager::Lefthook);
+    }
+    None
+}
+
+impl HooksLocation {
+    /// The directory of husky's own hook scripts (`.husky`), when husky manages
+    /// the hooks. husky 9 points `core.hooksPath` at its `.husky/_` dispatcher.
+    fn husky_dir(&self) -> Option<PathBuf> {
+        if self.manager != Some(HookManager::Husky) {
+            return None;
+        }
+        match self.dir.file_name() {
+            Some(name) if name == "_" => self.dir.parent().map(Path::to_path_buf),
+            _ => Some(self.dir.clone()),
+        }
+    }
+
+    fn hook_state(&self, hook: &str, binary_path: &Path) -> HookState {
+        match self.husky_dir() {
+            Some(dir) => husky_hook_state(&dir, hook, binary_path),
+            None => hook_state(&self.dir, hook, binary_path),
+        }
+    }
+}
+
+/// The shims worth installing here: `post-rewrite` only matters with a
+/// stacked-diff tool, since the wrapper remaps its own rebases
+fn hooks_for(repo: &Repository) -> Vec<&'static str> {
+    let has_stack_tool = !detect_stack_tools(repo).is_empty();
+    MANAGED_HOOKS
+        .into_iter()
+        .filter(|hook| *hook != "post-rewrite" || has_stack_tool)
+        .collect()
+}
+
+pub fn shim_script(hook: &str, binary_path: &Path) -> String {
+    // post-rewrite gets its (old, new) pairs on stdin, which the chained hook
+    // would otherwise use up
+    if hook == "post-rewrite" {
+        return format!(
+            r#"#!/bin/sh
+{marker} ({hook}) - managed by `git-ai install-hooks`
+chained="$0.{suffix}"
+input="$(cat)"
+if [ -x "$chained" ]; then
+  printf '%s\n' "$input" | "$chained" "$@" || exit $?
+fi
+printf '%s\n' "$input" | "{binary}" git-hook {hook} "$@"
+"#,
+            marker = SHIM_MARKER,
+            hook = hook,
+            suffix = CHAINED_SUFFIX,
+            binary = binary_path.display(),
+        );
+    }
+    format!(
+        r#"#!/bin/sh
+{marker} ({hook}) - managed by `git-ai install-hooks`
+chained="$0.{suffix}"
+if [ -x "$chained" ]; then
+  "$chained" "$@" || exit $?
+fi
+exec "{binary}" git-hook {hook} "$@"
+"#,
+        marker = SHIM_MARKER,
+        hook = hook,
+        suffix = CHAINED_SUFFIX,
+        binary = binary_path.display(),
+    )
+}
+
+/// The lines appended to a husky hook script
+fn husky_block(hook: &str, binary_path: &Path) -> String {
+    format!(
+        "{} ({}) - managed by `git-ai install-hooks`\n\"{}\" git-hook {} \"$@\"\n",
+        SHIM_MARKER,
+        hook,
+        binary_path.display(),
+        hook
+    )
+}
+
+/// `content` without a previously appended git-ai block (the marker line and
+/// the call after it)
+fn strip_husky_block(content: &str) -> String {
+    let mut kept = String::new();
+    let mut lines = content.split_inclusive('\n');
+    while let Some(line) = lines.next() {
+        if line.starts_with(SHIM_MARKER) {
+            lines.next();
+            continue;
+        }
+        kept.push_str(line);
+    }
+    kept
+}
+
+fn husky_hook_state(dir: &Path, hook: &str, binary_path: &Path) -> HookState {
+    match fs::read_to_string(dir.join(hook)) {
+        Err(_) => HookState::Missing,
+        Ok(content) if content.contains(&husky_block(hook, binary_path)) => HookState::Installed,
+        Ok(content) if content.contains(SHIM_MARKER) => HookState::Outdated,
+        Ok(_) => HookState::Foreign,
+    }
+}
+
+/// Append the git-ai call to husky's `<dir>/<hook>`, creating the script if
+/// needed. Shims from older versions that replaced the dispatcher in
+/// `<dir>/_` are put back first.
+fn install_husky_hook(
+    dir: &Path,
+    hook: &str,
+    binary_path: &Path,
+    force: bool,
+) -> Result<HookAction, GitAiError> {
+    let dispatcher_dir = dir.join("_");
+    if hook_state(&dispatcher_dir, hook, binary_path) != HookState::Foreign
+        && chained_path(&dispatcher_dir, hook).exists()
+    {
+        fs::rename(
+            chained_path(&dispatcher_dir, hook),
+            dispatcher_dir.join(hook),
+        )?;
+    }
+
+    let path = dir.join(hook);
+    let (action, existing) = match husky_hook_state(dir, hook, binary_path) {
+        HookState::Installed if !force => return Ok(HookAction::UpToDate),
+        HookState::Installed | HookState::Outdated => (
+            HookAction::Updated,
+            strip_husky_block(&fs::read_to_string(&path)?),
+        ),
+        HookState::Missing => (HookAction::Created, String::new()),
+        HookState::Foreign => (HookAction::Appended, fs::read_to_string(&path)?),
+    };
+
+    let mut content = existing;
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(&husky_block(hook, binary_path));
+    fs::create_dir_all(dir)?;
+    fs::write(&path, content)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(action)
+}
+
+fn chained_path(dir: &Path, hook: &str) -> PathBuf {
+    dir.join(format!("{}.{}", hook, CHAINED_SUFFIX))
+}
+
+pub fn hook_state(dir: &Path, hook: &str, binary_path: &Path) -> HookState {
+    match fs::read_to_string(dir.join(hook)) {
+        Err(_) => HookState::Missing,
+        Ok(content) if content == shim_script(hook, binary_path) => HookState::Installed,
+        Ok(content) if content.contains(SHIM_MARKER) => HookState::Outdated,
+        Ok(_) => HookState::Foreign,
+    }
+}
+
+/// Install or refresh one shim. A foreign hook is chained, unless a chained hook
+/// already exists; replacing that needs `force`.
+pub fn install_hook(
+    dir: &Path,
+    hook: &str,
+    binary_path: &Path,
+    force: bool,
+) -> Result<HookAction, GitAiError> {
+    let path = dir.join(hook);
+    let action = match hook_state(dir, hook, binary_path) {
+        HookState::Installed if !force => return Ok(HookAction::UpToDate),
+        HookState::Installed | HookState::Outdated => HookAction::Updated,
+        HookState::Missing => HookAction::Created,
+        HookState::Foreign => {
+            let chained = chained_path(dir, hook);
+            if chained.exists() && !force {
+                return Err(GitAiError::Generic(format!(
+                    "{} was replaced after git-ai chained {}; rerun with --force to chain the new hook instead",
+                    path.display(),
+                    chained.display()
+                )));
+            }
+            fs::rename(&path, &chained)?;
+            HookAction::Chained
+        }
+    };
+
+    fs::create_dir_all(dir)?;
+    fs::write(&path, shim_script(hook, binary_path))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(action)
+}
+
+/// Install every managed shim and describe what happened, one line per hook.
+/// With `dry_run`, only describe what would happen.
+pub fn install_git_hooks(
+    repo: &Repository,
+    binary_path: &Path,
+    dry_run: bool,
+    force: bool,
+) -> Result<Vec<String>, GitAiError> {
+    let location = locate_hooks(repo)?;
+    if !location.inside_repo && !force {
+        return Err(GitAiError::Generic(format!(
+            "core.hooksPath points outside this repository ({}); rerun with --force to install there anyway",
+            location.dir.display()
+        )));
+    }
+
+    let mut report = Vec::new();
+    if let Some(manager) = location.manager {
+        report.push(format!(
+            "Detected {} hooks in {}",
+            manager.name(),
+            location.dir.display()
+        ));
+    }
+    let husky_dir = location.husky_dir();
+    for hook in hooks_for(repo) {
+        let state = location.hook_state(hook, binary_path);
+        let line = if dry_run {
+            match state {
+                HookState::Installed => format!("{}: up to date", hook),
+                HookState::Missing => format!("{}: would install", hook),
+                HookState::Outdated => format!("{}: would update", hook),
+                HookState::Foreign if husky_dir.is_some() => {
+                    format!("{}: would append to the existing hook", hook)
+                }
+                HookState::Foreign => format!("{}: would chain the existing hook", hook),
+            }
+        } else {
+            let action = match &husky_dir {
+                Some(dir) => install_husky_hook(dir, hook, binary_path, force)?,
+                None => install_hook(&location.dir, hook, binary_path, force)?,
+            };
+            match action {
+                HookAction::Created => format!("{}: installed", hook),
+                HookAction::Chained => format!(
+                    "{}: installed, existing hook kept as {}.{}",
+                    hook, hook, CHAINED_SUFFIX
+                ),
+                HookAction::Updated => format!("{}: updated", hook),
+                HookAction::UpToDate => format!("{}: up to date", hook),
+                HookAction::Appended => format!("{}: appended to the existing hook", hook),
+            }
+        };
+        report.push(line);
+    }
+    Ok(report)
+}
+
+/// Per-hook state, for `install-hooks --check`
+pub fn check_git_hooks(
+    repo: &Repository,
+    binary_path: &Path,
+) -> Result<Vec<(&'static str, HookState)>, GitAiError> {
+    let location = locate_hooks(repo)?;
+    Ok(hooks_for(repo)
+        .into_iter()
+        .map(|hook| (hook, location.hook_state(hook, binary_path)))
+        .collect())
+}
+
+/// Handle `git-ai git-hook <name> [hook args...]`, run by the installed shims
+pub fn handle_git_hook(
+    repo: &mut Repository,
+    hook: &str,
+    hook_args: &[String],
+) -> Result<(), GitAiError> {
+    // Commits replayed by a rebase or cherry-pick run through the wrapper are
+    // remapped by its own post-command hooks
+    if std::env::var(WRAPPED_COMMAND_ENV)
+        .is_ok_and(|command| matches!(command.as_str(), "rebase" | "cherry-pick"))
+    {
+        return Ok(());
+    }
+    let author = get_commit_default_author(repo, &[]);
+    match hook {
+        "pre-commit" => crate::authorship::pre_commit::pre_commit(repo, author),
+        "prepare-commit-msg" => {
+            let Some(message_file) = hook_args.first() else {
+                return Err(GitAiError::InvalidArguments(
+                    "prepare-commit-msg expects the message file".to_string(),
+                ));
+            };
+            render_message_file(repo, Path::new(message_file)).map(|_| ())
+        }
+        "post-commit" => {
+            let head = repo.revparse_single("HEAD")?.id();
+            let parent = repo.revparse_single("HEAD^").ok().map(|c| c.id());
+            repo.handle_rewrite_log_event(
+                RewriteLogEvent::commit(parent, head),
+                author,
+                false,
+                true,
+            )
+        }
+        "post-rewrite" => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input)?;
+            let pairs = parse_post_rewrite_input(&input);
+            // git lists the rewritten commits oldest first
+            let Some((original_head, _)) = pairs.last() else {
+                return Ok(());
+            };
+            remap_rewritten_commits(repo, original_head, &pairs, false).map(|_| ())
+        }
+        other => Err(GitAiError::InvalidArguments(format!(
+            "Unsupported git hook: {}",
+            other
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_install_chains_existing_hook() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let repo = tmp_repo.gitai_repo();
+        let binary = PathBuf::from("/opt/git-ai/bin/git-ai");
+        let location = locate_hooks(repo).unwrap();
+        assert!(location.inside_repo);
+
+        fs::create_dir_all(&location.dir).unwrap();
+        fs::write(location.dir.join("pre-commit"), "#!/bin/sh\nnpm test\n").unwrap();
+
+        // post-rewrite waits for a stacked-diff tool
+        let report = install_git_hooks(repo, &binary, false, false).unwrap();
+        assert_eq!(report.len(), MANAGED_HOOKS.len() - 1);
+        assert_eq!(
+            hook_state(&location.dir, "post-rewrite", &binary),
+            HookState::Missing
+        );
+        assert_eq!(
+            fs::read_to_string(chained_path(&location.dir, "pre-commit")).unwrap(),
+            "#!/bin/sh\nnpm test\n"
+        );
+        assert!(
+            check_git_hooks(repo, &binary)
+                .unwrap()
+                .iter()
+                .all(|(_, state)| *state == HookState::Installed)
+        );
+
+        fs::create_dir_all(repo.path().join("branchless")).unwrap();
+        let report = install_git_hooks(repo, &binary, false, false).unwrap();
+        assert!(report.contains(&"post-rewrite: installed".to_string()));
+
+        // Reinstalling is a no-op; a different binary path is reported as outdated
+        assert_eq!(
+            install_hook(&location.dir, "pre-commit", &binary, false).unwrap(),
+            HookAction::UpToDate
+        );
+        assert_eq!(
+            hook_state(&location.dir, "pre-commit", Path::new("/usr/bin/git-ai")),
+            HookState::Outdated
+        );
+
+        // Something overwrote our shim while a chained hook exists
+        fs::write(location.dir.join("pre-commit"), "#!/bin/sh\nlint\n").unwrap();
+        assert!(install_hook(&location.dir, "pre-commit", &binary, false).is_err());
+        assert_eq!(
+            install_hook(&location.dir, "pre-commit", &binary, true).unwrap(),
+            HookAction::Chained
+        );
+    }
+
+    #[test]
+    fn test_detects_husky_hooks_path() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let repo = tmp_repo.gitai_repo();
+        let mut args = repo.global_args_for_exec();
+        args.extend(["config", "core.hooksPath", ".husky/_"].map(String::from));
+        exec_git(&args).unwrap();
+
+        let location = locate_hooks(repo).unwrap();
+        assert_eq!(location.manager, Some(HookManager::Husky));
+        assert_eq!(location.hooks_path_config.as_deref(), Some(".husky/_"));
+        assert!(location.dir.ends_with(".husky/_"));
+        assert!(location.inside_repo);
+    }
+
+    #[test]
+    fn test_husky_hooks_are_appended_not_replaced() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let repo = tmp_repo.gitai_repo();
+        let binary = PathBuf::from("/opt/git-ai/bin/git-ai");
+        let mut args = repo.global_args_for_exec();
+        args.extend(["config", "core.hooksPath", ".husky/_"].map(String::from));
+        exec_git(&args).unwrap();
+
+        // husky 9 layout: `_/<hook>` dispatches to `.husky/<hook>` by file name
+        let husky = repo.workdir().unwrap().join(".husky");
+        let dispatcher = ". \"$(dirname \"$0\")/h\"\n";
+        fs::create_dir_all(husky.join("_")).unwrap();
+        fs::write(husky.join("_").join("pre-commit"), dispatcher).unwrap();
+        fs::write(husky.join("pre-commit"), "npm test").unwrap();
+
+        let report = install_git_hooks(repo, &binary, false, false).unwrap();
+        assert!(report.contains(&"pre-commit: appended to the existing hook".to_string()));
+        assert!(report.contains(&"post-commit: installed".to_string()));
+        assert_eq!(
+            fs::read_to_string(husky.join("_").join("pre-commit")).unwrap(),
+            dispatcher
+        );
+        assert_eq!(
+            fs::read_to_string(husky.join("pre-commit")).unwrap(),
+            format!("npm test\n{}", husky_block("pre-commit", &binary))
+        );
+        assert!(
+            check_git_hooks(repo, &binary)
+                .unwrap()
+                .iter()
+                .all(|(_, state)| *state == HookState::Installed)
+        );
+
+        // A new binary path replaces the block instead of adding a second one
+        let moved = PathBuf::from("/usr/local/bin/git-ai");
+        install_git_hooks(repo, &moved, false, false).unwrap();
+        assert_eq!(
+            fs::read_to_string(husky.join("pre-commit")).unwrap(),
+            format!("npm test\n{}", husky_block("pre-commit", &moved))
+        );
+
+        // A dispatcher replaced by an older shim is put back
+        fs::write(husky.join("_").join("post-commit.pre-git-ai"), dispatcher).unwrap();
+        fs::write(
+            husky.join("_").join("post-commit"),
+            shim_script("post-commit", &moved),
+        )
+        .unwrap();
+        install_git_hooks(repo, &moved, false, false).unwrap();
+        assert_eq!(
+            fs::read_to_string(husky.join("_").join("post-commit")).unwrap(),
+            dispatcher
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use crate::authorship::rebase_authorship::walk_commits_to_base;
 use crate::commands::git_handlers::CommandHooksContext;
 use crate::commands::hooks::commit_hooks::get_commit_default_author;
+use crate::error::GitAiError;
 use crate::git::cli_parser::{ParsedGitInvocation, is_dry_run};
 use crate::git::repository::Repository;
 use crate::git::rewrite_log::RewriteLogEvent;
@@ -78,7 +79,7 @@ pub fn post_cherry_pick_hook(
     parsed_args: &ParsedGitInvocation,
     exit_status: std::process::ExitStatus,
     repository: &mut Repository,
-) {
+) -> Result<(), GitAiError> {
     debug_log("=== CHERRY-PICK POST-COMMAND HOOK ===");
     debug_log(&format!("Exit status: {}", exit_status));
 
@@ -98,12 +99,12 @@ pub fn post_cherry_pick_hook(
         debug_log(
             "⏸ Cherry-pick still in progress, waiting for completion (conflict or multi-step)",
         );
-        return;
+        return Ok(());
     }
 
     if is_dry_run(&parsed_args.command_args) {
         debug_log("Skipping cherry-pick post-hook for dry-run");
-        return;
+        return Ok(());
     }
 
     // Cherry-pick is done (completed or aborted)
@@ -126,7 +127,7 @@ pub fn post_cherry_pick_hook(
         } else {
             debug_log("✗ Cherry-pick failed but couldn't determine original head");
         }
-        return;
+        return Ok(());
     }
 
     // Cherry-pick completed successfully!
@@ -136,10 +137,11 @@ pub fn post_cherry_pick_hook(
             "Processing completed cherry-pick from {}",
             original_head
         ));
-        process_completed_cherry_pick(repository, &original_head, parsed_args);
+        process_completed_cherry_pick(repository, &original_head, parsed_args)?;
     } else {
         debug_log("⚠ Cherry-pick completed but couldn't determine original head");
     }
+    Ok(())
 }
 
 /// Check if there's an active cherry-pick Start event (not followed by Complete or Abort)
@@ -294,7 +296,7 @@ fn process_completed_cherry_pick(
     repository: &mut Repository,
     original_head: &str,
     parsed_args: &ParsedGitInvocation,
-) {
+) -> Result<(), GitAiError> {
     debug_log(&format!(
         "--- Processing completed cherry-pick from {} ---",
         original_head
@@ -309,19 +311,19 @@ fn process_completed_cherry_pick(
             }
             Err(e) => {
                 debug_log(&format!("✗ Failed to get HEAD target: {}", e));
-                return;
+                return Ok(());
             }
         },
         Err(e) => {
             debug_log(&format!("✗ Failed to get HEAD: {}", e));
-            return;
+            return Ok(());
         }
     };
 
     // If HEAD didn't change, nothing to do
     if original_head == new_head {
         debug_log("Cherry-pick resulted in no changes");
-        return;
+        return Ok(());
     }
 
     // Get source commits from the Start event
@@ -332,7 +334,7 @@ fn process_completed_cherry_pick(
         }
         None => {
             debug_log("✗ Could not find source commits from CherryPickStart event");
-            return;
+            return Ok(());
         }
     };
 
@@ -353,13 +355,13 @@ fn process_completed_cherry_pick(
         }
         Err(e) => {
             debug_log(&format!("✗ Failed to build cherry-pick mappings: {}", e));
-            return;
+            return Ok(());
         }
     };
 
     if new_commits.is_empty() {
         debug_log("No commits to rewrite authorship for");
-        return;
+        return Ok(());
     }
 
     debug_log(&format!("Source commits: {:?}", source_commits));
@@ -382,9 +384,10 @@ fn process_completed_cherry_pick(
         commit_author,
         false, // don't suppress output
         true,  // save to log
-    );
+    )?;
 
     debug_log("✓ Cherry-pick authorship rewrite complete");
+    Ok(())
 }
 
 fn build_cherry_pick_commit_mappings(
@@ -1,16 +1,21 @@
+use crate::authorship::co_authors;
+use crate::authorship::commit_template;
 use crate::authorship::pre_commit;
 use crate::commands::git_handlers::CommandHooksContext;
+use crate::config::Config;
+use crate::error::GitAiError;
 use crate::git::cli_parser::{ParsedGitInvocation, is_dry_run};
-use crate::git::repository::Repository;
+use crate::git::git_version;
+use crate::git::repository::{Repository, exec_git};
 use crate::git::rewrite_log::RewriteLogEvent;
 use crate::utils::debug_log;
 
 pub fn commit_pre_command_hook(
-    parsed_args: &ParsedGitInvocation,
+    parsed_args: &mut ParsedGitInvocation,
     repository: &mut Repository,
-) -> bool {
+) -> Result<bool, GitAiError> {
     if is_dry_run(&parsed_args.command_args) {
-        return false;
+        return Ok(false);
     }
 
     // store HEAD context for post-command hook
@@ -26,12 +31,155 @@ pub fn commit_pre_command_hook(
             eprintln!(
                 "Cannot run checkpoint on bare repositories (skipping git-ai pre-commit hook)"
             );
-            return false;
+            return Ok(false);
         }
         eprintln!("Pre-commit failed: {}", e);
-        std::process::exit(1);
+        // The caller counts the failure toward safe mode before aborting the commit
+        return Err(e);
     }
-    return true;
+
+    add_co_author_trailers(parsed_args, repository);
+    render_message_templates(parsed_args, repository);
+    Ok(true)
+}
+
+/// Fill in `{{ai_pct}}`-style variables in `-m` messages and in the commit
+/// template. The wrapper disables git hooks for the commit, so the
+/// `prepare-commit-msg` shim won't do it here.
+fn render_message_templates(parsed_args: &mut ParsedGitInvocation, repository: &Repository) {
+    let include_unstaged = stages_tracked_changes(&parsed_args.command_args);
+    // Only read the working log if some message actually has placeholders
+    let mut cached = None;
+    let mut vars = || -> Option<commit_template::TemplateVars> {
+        if cached.is_none() {
+            match commit_template::commit_template_vars(repository, include_unstaged) {
+                Ok(computed) => cached = Some(computed),
+                Err(e) => debug_log(&format!("Failed to compute template variables: {}", e)),
+            }
+        }
+        cached.clone()
+    };
+
+    let args = &mut parsed_args.command_args;
+    let mut has_message = false;
+    let mut template_arg = None;
+    let mut i = 0;
+    while i < args.len() && args[i] != "--" {
+        let arg = args[i].as_str();
+        if matches!(arg, "-m" | "--message") {
+            has_message = true;
+            i += 1;
+            if let Some(message) = args.get(i).filter(|m| m.contains("{{"))
+                && let Some(vars) = vars()
+                && let Some(rendered) = commit_template::render(message, &vars)
+            {
+                args[i] = rendered;
+            }
+        } else if matches!(arg, "-F" | "--file" | "-C" | "--reuse-message" | "-c")
+            || arg.starts_with("--message=")
+            || arg.starts_with("--file=")
+            || (arg.starts_with("-m") && !arg.starts_with("--"))
+        {
+            has_message = true;
+        } else if matches!(arg, "-t" | "--template") {
+            template_arg = Some(i + 1);
+            i += 1;
+        }
+        i += 1;
+    }
+    if has_message {
+        return;
+    }
+
+    let template_path = match template_arg {
+        Some(index) => args.get(index).cloned(),
+        None => {
+            let mut config_args = repository.global_args_for_exec();
+            config_args.extend(["config", "--path", "--get", "commit.template"].map(String::from));
+            exec_git(&config_args)
+                .ok()
+                .and_then(|output| String::from_utf8(output.stdout).ok())
+                .map(|path| path.trim().to_string())
+                .filter(|path| !path.is_empty())
+        }
+    };
+    let Some(template) = template_path.and_then(|path| std::fs::read_to_string(path).ok()) else {
+        return;
+    };
+    if !template.contains("{{") {
+        return;
+    }
+    let Some(rendered) = vars().and_then(|vars| commit_template::render(&template, &vars)) else {
+        return;
+    };
+
+    let rendered_path = repository
+        .storage
+        .repo_path
+        .join("ai")
+        .join("COMMIT_TEMPLATE");
+    if let Err(e) = std::fs::write(&rendered_path, rendered) {
+        debug_log(&format!("Failed to write rendered commit template: {}", e));
+        return;
+    }
+    let rendered_path = rendered_path.to_string_lossy().to_string();
+    match template_arg {
+        Some(index) => args[index] = rendered_path,
+        None => {
+            args.insert(0, rendered_path);
+            args.insert(0, "--template".to_string());
+        }
+    }
+}
+
+/// Append `Co-authored-by:` trailers for agents in the working log when enabled in config.
+/// Uses `git commit --trailer`, so it is skipped on git older than 2.32, which would
+/// reject the commit.
+fn add_co_author_trailers(parsed_args: &mut ParsedGitInvocation, repository: &Repository) {
+    let config = Config::get().co_author_trailers();
+    if !config.enabled {
+        return;
+    }
+    // An unknown version never disables anything, as with the hook requirements
+    if let Some(version) = git_version::detected()
+        && version < git_version::COMMIT_TRAILER
+    {
+        debug_log(&format!(
+            "Skipping co-author trailers: git {} is older than {} (needs commit --trailer)",
+            version,
+            git_version::COMMIT_TRAILER
+        ));
+        return;
+    }
+
+    let include_unstaged = stages_tracked_changes(&parsed_args.command_args);
+    match co_authors::co_author_trailers(repository, config, include_unstaged) {
+        Ok(trailers) => {
+            // Prepend so the trailers stay ahead of any `--` pathspec separator
+            for trailer in trailers.into_iter().rev() {
+                parsed_args.command_args.insert(0, trailer);
+                parsed_args.command_args.insert(0, "--trailer".to_string());
+            }
+        }
+        Err(e) => debug_log(&format!("Failed to compute co-author trailers: {}", e)),
+    }
+}
+
+/// Whether `git commit` was asked to stage tracked changes itself (`-a`, `--all`, `-am`).
+fn stages_tracked_changes(args: &[String]) -> bool {
+    args.iter().take_while(|arg| *arg != "--").any(|arg| {
+        if arg == "--all" {
+            return true;
+        }
+        match arg.strip_prefix('-') {
+            // Short flags can be clustered; stop at the first one that takes a value
+            Some(cluster) if !cluster.starts_with('-') => cluster
+                .chars()
+                .take_while(|c| !"mFcCtuS".contains(*c))
+                .any(|c| c == 'a'),
+            _ => false,
+        }
+    })
 }
 
 /// commit 命令的后置钩子函数
@@ -53,17 +201,17 @@ pub fn commit_post_command_hook(
     exit_status: std::process::ExitStatus,
     repository: &mut Repository,
     command_hooks_context: &mut CommandHooksContext,
-) {
+) -> Result<(), GitAiError> {
     // 检查是否为 dry-run 模式
     // dry-run 模式下提交不会真正执行，因此跳过后置钩子
     if is_dry_run(&parsed_args.command_args) {
-        return;
+        return Ok(());
     }
 
     // 检查 git commit 命令是否执行成功
     // 如果提交失败（如有冲突、空提交等），则跳过后置钩子
     if !exit_status.success() {
-        return;
+        return Ok(());
     }
 
     // 检查 pre-commit 钩子的执行结果
@@ -71,7 +219,7 @@ pub fn commit_post_command_hook(
     if let Some(pre_commit_hook_result) = command_hooks_context.pre_commit_hook_result {
         if !pre_commit_hook_result {
             debug_log("Skipping git-ai post-commit hook because pre-commit hook failed");
-            return;
+            return Ok(());
         }
     }
 
@@ -92,7 +240,7 @@ pub fn commit_post_command_hook(
     // 处理空仓库的情况
     // 如果 new_sha 为 None，说明仓库仍然为空（首次提交失败），跳过后续处理
     if new_sha.is_none() {
-        return;
+        return Ok(());
     }
 
     // 获取提交作者信息
@@ -109,7 +257,7 @@ pub fn commit_post_command_hook(
             commit_author,
             supress_output,
             true, // 表示这是一个 commit 操作，需要将 working log 转换为 authorship log
-        );
+        )?;
     } else {
         // 普通提交：创建新提交
         // 记录 commit 事件，original_commit 可能为 None（首次提交）或 Some（常规提交）
@@ -118,11 +266,12 @@ pub fn commit_post_command_hook(
             commit_author,
             supress_output,
             true, // 表示这是一个 commit 操作，需要将 working log 转换为 authorship log
-        );
+        )?;
     }
     // 注意：handle_rewrite_log_event 的最后一个参数为 true 时，
     // 会将工作日志(working log)转换为归属日志(authorship log)，
     // 这是 git-ai 完成代码归属追踪的关键步骤
+    Ok(())
 }
 
 pub fn get_commit_default_author(repo: &Repository, args: &[String]) -> String {
@@ -3,6 +3,7 @@ use crate::commands::upgrade;
 use crate::git::cli_parser::{ParsedGitInvocation, is_dry_run};
 use crate::git::repository::{Repository, find_repository};
 use crate::git::sync_authorship::{fetch_authorship_notes, fetch_remote_from_args};
+use crate::notifications;
 use crate::utils::debug_log;
 
 pub fn fetch_pull_pre_command_hook(
@@ -38,9 +39,11 @@ pub fn fetch_pull_pre_command_hook(
         ));
         // Recreate repository in the background thread
         if let Ok(repo) = find_repository(&global_args) {
-            if let Err(e) = fetch_authorship_notes(&repo, &remote) {
+            let result = fetch_authorship_notes(&repo, &remote);
+            if let Err(e) = &result {
                 debug_log(&format!("authorship fetch failed: {}", e));
             }
+            notifications::record_sync_result(&repo, "fetch", &remote, &result);
         } else {
             debug_log("failed to open repository for authorship fetch");
         }
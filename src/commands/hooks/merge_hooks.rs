@@ -1,5 +1,6 @@
 use crate::{
     commands::hooks::commit_hooks::get_commit_default_author,
+    error::GitAiError,
     git::{
         cli_parser::{ParsedGitInvocation, is_dry_run},
         repository::Repository,
@@ -11,7 +12,7 @@ pub fn post_merge_hook(
     parsed_args: &ParsedGitInvocation,
     exit_status: std::process::ExitStatus,
     repository: &mut Repository,
-) {
+) -> Result<(), GitAiError> {
     if parsed_args.has_command_flag("--squash")
         && exit_status.success()
         && !is_dry_run(&parsed_args.command_args)
@@ -30,7 +31,7 @@ pub fn post_merge_hook(
             Ok(commit) => commit.id(),
             Err(_) => {
                 // If we can't resolve the branch, skip logging this event
-                return;
+                return Ok(());
             }
         };
 
@@ -44,6 +45,7 @@ pub fn post_merge_hook(
             commit_author,
             false,
             true,
-        );
+        )?;
     }
+    Ok(())
 }
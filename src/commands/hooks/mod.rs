@@ -7,3 +7,41 @@ pub mod push_hooks;
 pub mod rebase_hooks;
 pub mod reset_hooks;
 pub mod stash_hooks;
+
+/// Which hooks the wrapper runs around a git command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandHooks {
+    Commit,
+    Push,
+    FetchPull,
+    Reset,
+    Merge,
+    Rebase,
+    CherryPick,
+    Stash,
+    /// Only a post hook, run once the new repository exists
+    Clone,
+}
+
+/// The git commands with hooks. `git_handlers` dispatches on this and
+/// `git-ai explain` describes it, so the two agree on what runs.
+pub const COMMAND_HOOKS: &[(&str, CommandHooks)] = &[
+    ("commit", CommandHooks::Commit),
+    ("push", CommandHooks::Push),
+    ("fetch", CommandHooks::FetchPull),
+    ("pull", CommandHooks::FetchPull),
+    ("reset", CommandHooks::Reset),
+    ("merge", CommandHooks::Merge),
+    ("rebase", CommandHooks::Rebase),
+    ("cherry-pick", CommandHooks::CherryPick),
+    ("stash", CommandHooks::Stash),
+    ("clone", CommandHooks::Clone),
+];
+
+pub fn command_hooks(command: Option<&str>) -> Option<CommandHooks> {
+    let command = command?;
+    COMMAND_HOOKS
+        .iter()
+        .find(|(name, _)| *name == command)
+        .map(|(_, hooks)| *hooks)
+}
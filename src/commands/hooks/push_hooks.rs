@@ -1,9 +1,16 @@
+use crate::authorship::restack::{detect_stack_tools, remap_restacks};
+use crate::authorship::stats::stats_for_commit_stats;
 use crate::commands::git_handlers::CommandHooksContext;
+use crate::commands::output;
 use crate::commands::upgrade;
+use crate::config::Config;
+use crate::error::GitAiError;
 use crate::git::cli_parser::{ParsedGitInvocation, is_dry_run};
-use crate::git::repository::{Repository, find_repository};
+use crate::git::repository::{Repository, exec_git, find_repository};
 use crate::git::sync_authorship::push_authorship_notes;
+use crate::notifications;
 use crate::utils::debug_log;
+use std::collections::BTreeSet;
 
 pub fn push_pre_command_hook(
     parsed_args: &ParsedGitInvocation,
@@ -22,38 +29,15 @@ pub fn push_pre_command_hook(
         return None;
     }
 
-    let remotes = repository.remotes().ok();
-    let remote_names: Vec<String> = remotes
-        .as_ref()
-        .map(|r| {
-            (0..r.len())
-                .filter_map(|i| r.get(i).map(|s| s.to_string()))
-                .collect()
-        })
-        .unwrap_or_default();
-
     // Push authorship refs to the appropriate remote
-    let positional_remote = extract_remote_from_push_args(&parsed_args.command_args, &remote_names);
-
-    let specified_remote = positional_remote.or_else(|| {
-        parsed_args
-            .command_args
-            .iter()
-            .find(|a| remote_names.iter().any(|r| r == *a))
-            .cloned()
-    });
-
-    let remote = specified_remote
-        .or_else(|| repository.upstream_remote().ok().flatten())
-        .or_else(|| repository.get_default_remote().ok().flatten());
-
-    if let Some(remote) = remote {
+    if let Some(remote) = push_remote(parsed_args, repository) {
         debug_log(&format!(
             "started pushing authorship notes to remote: {}",
             remote
         ));
         // Clone what we need for the background thread
         let global_args = repository.global_args_for_exec();
+        let restack_refs = restack_refs(parsed_args, repository);
 
         crate::observability::spawn_background_flush();
 
@@ -61,9 +45,18 @@ pub fn push_pre_command_hook(
         Some(std::thread::spawn(move || {
             // Recreate repository in the background thread
             if let Ok(repo) = find_repository(&global_args) {
-                if let Err(e) = push_authorship_notes(&repo, &remote) {
+                // Restacks by stacked-diff tools bypass the wrapper; give the
+                // rewritten commits their notes before pushing them
+                if !detect_stack_tools(&repo).is_empty()
+                    && let Err(e) = remap_restacks(&repo, restack_refs.as_deref(), false)
+                {
+                    debug_log(&format!("restack remap failed: {}", e));
+                }
+                let result = push_authorship_notes(&repo, &remote);
+                if let Err(e) = &result {
                     debug_log(&format!("authorship push failed: {}", e));
                 }
+                notifications::record_sync_result(&repo, "push", &remote, &result);
             } else {
                 debug_log("failed to open repository for authorship push");
             }
@@ -76,9 +69,9 @@ pub fn push_pre_command_hook(
 }
 
 pub fn push_post_command_hook(
-    _repository: &Repository,
+    repository: &Repository,
     _parsed_args: &ParsedGitInvocation,
-    _exit_status: std::process::ExitStatus,
+    exit_status: std::process::ExitStatus,
     command_hooks_context: &mut CommandHooksContext,
 ) {
     // Always wait for the authorship push thread to complete if it was started,
@@ -87,6 +80,191 @@ pub fn push_post_command_hook(
     if let Some(handle) = command_hooks_context.push_authorship_handle.take() {
         let _ = handle.join();
     }
+
+    if let Some(commits) = command_hooks_context.push_summary_commits.take()
+        && exit_status.success()
+        && !commits.is_empty()
+        && !output::quiet()
+    {
+        match push_summary(repository, &commits) {
+            Ok(summary) => eprintln!("{}", summary),
+            Err(e) => debug_log(&format!("push summary failed: {}", e)),
+        }
+    }
+}
+
+/// The remote a push goes to: named on the command line, else upstream, else default
+fn push_remote(parsed_args: &ParsedGitInvocation, repository: &Repository) -> Option<String> {
+    let remotes = repository.remotes().ok();
+    let remote_names: Vec<String> = remotes
+        .as_ref()
+        .map(|r| {
+            (0..r.len())
+                .filter_map(|i| r.get(i).map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let positional_remote = extract_remote_from_push_args(&parsed_args.command_args, &remote_names);
+
+    let specified_remote = positional_remote.or_else(|| {
+        parsed_args
+            .command_args
+            .iter()
+            .find(|a| remote_names.iter().any(|r| r == *a))
+            .cloned()
+    });
+
+    specified_remote
+        .or_else(|| repository.upstream_remote().ok().flatten())
+        .or_else(|| repository.get_default_remote().ok().flatten())
+}
+
+/// Commits the push is about to send, i.e. reachable from the pushed refs but
+/// not from any of the remote's tracking refs. Must run before the push, which
+/// moves those tracking refs. `None` when the summary is off or doesn't apply.
+pub fn commits_to_push(
+    parsed_args: &ParsedGitInvocation,
+    repository: &Repository,
+) -> Option<Vec<String>> {
+    let args = &parsed_args.command_args;
+    if !Config::get().push_summary_enabled()
+        || is_dry_run(args)
+        || args
+            .iter()
+            .any(|a| a == "-d" || a == "--delete" || a == "--mirror")
+    {
+        return None;
+    }
+    let remote = push_remote(parsed_args, repository)?;
+
+    let mut rev_list = repository.global_args_for_exec();
+    rev_list.push("rev-list".to_string());
+    rev_list.extend(push_refspec_sources(args));
+    rev_list.push("--not".to_string());
+    rev_list.push(format!("--remotes={}", remote));
+    let output = exec_git(&rev_list).ok()?;
+    let commits: Vec<String> = String::from_utf8(output.stdout)
+        .ok()?
+        .lines()
+        .map(str::to_string)
+        .collect();
+    if commits.len() > MAX_SUMMARY_COMMITS {
+        // Typically a first push of a long history; too slow to summarise inline
+        debug_log(&format!(
+            "skipping push summary for {} commits",
+            commits.len()
+        ));
+        return None;
+    }
+    Some(commits)
+}
+
+const MAX_SUMMARY_COMMITS: usize = 200;
+
+/// Refs whose reflogs are searched for restacks before a push: the pushed
+/// branches, or every branch for `--all`
+fn restack_refs(parsed_args: &ParsedGitInvocation, repository: &Repository) -> Option<Vec<String>> {
+    let mut refs = push_refspec_sources(&parsed_args.command_args);
+    if refs.iter().any(|source| source == "--branches") {
+        return None;
+    }
+    // A restack of the checked-out branch shows up in its own reflog too
+    if refs.iter().any(|source| source == "HEAD")
+        && let Ok(head) = repository.head()
+        && let Some(branch) = head.name().filter(|name| name.starts_with("refs/heads/"))
+    {
+        refs.push(branch.to_string());
+    }
+    Some(refs)
+}
+
+/// Local revisions named by the push refspecs, `HEAD` when there are none
+fn push_refspec_sources(args: &[String]) -> Vec<String> {
+    if args.iter().any(|a| a == "--all" || a == "--branches") {
+        return vec!["--branches".to_string()];
+    }
+
+    let mut positionals = Vec::new();
+    let mut i = 0;
+    while i < args.len() {
+        let arg = &args[i];
+        if arg == "--" {
+            positionals.extend(args[i + 1..].iter().cloned());
+            break;
+        }
+        if arg.starts_with('-') {
+            if is_push_option_with_inline_value(arg).is_none()
+                && option_consumes_separate_value(arg)
+            {
+                i += 1;
+            }
+        } else {
+            positionals.push(arg.clone());
+        }
+        i += 1;
+    }
+
+    // The first positional is the remote
+    let sources: Vec<String> = positionals
+        .iter()
+        .skip(1)
+        .filter_map(|refspec| {
+            let refspec = refspec.trim_start_matches('+');
+            let source = refspec.split(':').next().unwrap_or(refspec);
+            // ":branch" deletes; "tag" is followed by the tag name
+            (!source.is_empty() && source != "tag").then(|| source.to_string())
+        })
+        .collect();
+    if sources.is_empty() {
+        vec!["HEAD".to_string()]
+    } else {
+        sources
+    }
+}
+
+/// "pushed 3 commits: 62% AI (claude), 38% human"
+fn push_summary(repository: &Repository, commits: &[String]) -> Result<String, GitAiError> {
+    let mut ai = 0u64;
+    let mut counted = 0u64;
+    let mut tools = BTreeSet::new();
+    for sha in commits {
+        let stats = stats_for_commit_stats(repository, sha, &[])?;
+        ai += stats.ai_additions as u64;
+        counted += stats
+            .git_diff_added_lines
+            .saturating_sub(stats.generated_additions) as u64;
+        for (key, tool) in &stats.tool_model_breakdown {
+            if tool.ai_additions > 0 {
+                tools.insert(key.split("::").next().unwrap_or(key).to_string());
+            }
+        }
+    }
+    Ok(format_push_summary(commits.len(), ai, counted, &tools))
+}
+
+fn format_push_summary(commits: usize, ai: u64, counted: u64, tools: &BTreeSet<String>) -> String {
+    let noun = if commits == 1 { "commit" } else { "commits" };
+    if counted == 0 {
+        return format!("pushed {} {}: no added lines", commits, noun);
+    }
+    let ai_percent = (ai.min(counted) * 100 + counted / 2) / counted;
+    let tools = if tools.is_empty() {
+        String::new()
+    } else {
+        format!(
+            " ({})",
+            tools.iter().cloned().collect::<Vec<_>>().join(", ")
+        )
+    };
+    format!(
+        "pushed {} {}: {}% AI{}, {}% human",
+        commits,
+        noun,
+        ai_percent,
+        tools,
+        100 - ai_percent
+    )
 }
 
 fn extract_remote_from_push_args(args: &[String], known_remotes: &[String]) -> Option<String> {
@@ -144,3 +322,43 @@ fn option_consumes_separate_value(arg: &str) -> bool {
         "--repo" | "--receive-pack" | "--exec" | "-o" | "--push-option" | "-c" | "-C"
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_push_refspec_sources() {
+        assert_eq!(push_refspec_sources(&args(&[])), vec!["HEAD"]);
+        assert_eq!(push_refspec_sources(&args(&["origin"])), vec!["HEAD"]);
+        assert_eq!(
+            push_refspec_sources(&args(&["-o", "ci.skip", "origin", "+main:release", ":old"])),
+            vec!["main"]
+        );
+        assert_eq!(
+            push_refspec_sources(&args(&["--all", "origin"])),
+            vec!["--branches"]
+        );
+    }
+
+    #[test]
+    fn test_format_push_summary() {
+        let tools = BTreeSet::from(["claude".to_string()]);
+        assert_eq!(
+            format_push_summary(3, 62, 100, &tools),
+            "pushed 3 commits: 62% AI (claude), 38% human"
+        );
+        assert_eq!(
+            format_push_summary(1, 0, 10, &BTreeSet::new()),
+            "pushed 1 commit: 0% AI, 100% human"
+        );
+        assert_eq!(
+            format_push_summary(2, 0, 0, &BTreeSet::new()),
+            "pushed 2 commits: no added lines"
+        );
+    }
+}
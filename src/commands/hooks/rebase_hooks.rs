@@ -1,10 +1,11 @@
 use crate::authorship::rebase_authorship::walk_commits_to_base;
 use crate::commands::git_handlers::CommandHooksContext;
 use crate::commands::hooks::commit_hooks::get_commit_default_author;
+use crate::error::GitAiError;
 use crate::git::cli_parser::ParsedGitInvocation;
 use crate::git::cli_parser::is_dry_run;
 use crate::git::repository::Repository;
-use crate::git::rewrite_log::RewriteLogEvent;
+use crate::git::rewrite_log::{RebaseStartEvent, RewriteLogEvent};
 use crate::utils::debug_log;
 
 pub fn pre_rebase_hook(
@@ -47,10 +48,33 @@ pub fn pre_rebase_hook(
 
                 debug_log(&format!("Interactive rebase: {}", is_interactive));
 
+                let mut start =
+                    crate::git::rewrite_log::RebaseStartEvent::new(target.clone(), is_interactive);
+                // Resolve --onto now: specs like HEAD~3 mean something else once the rebase moves HEAD
+                if let Some(onto) = parse_onto_args(&parsed_args.command_args) {
+                    let original = onto
+                        .branch
+                        .as_deref()
+                        .and_then(|branch| resolve_commit(repository, branch));
+                    if let Some(original) = original {
+                        command_hooks_context.rebase_original_head = Some(original.clone());
+                        start.original_head = original;
+                    }
+                    let upstream = onto.upstream.as_deref().unwrap_or("@{upstream}");
+                    match (
+                        resolve_commit(repository, &onto.onto),
+                        resolve_commit(repository, upstream),
+                    ) {
+                        (Some(onto), Some(upstream)) => {
+                            debug_log(&format!("Rebase --onto {} from {}", onto, upstream));
+                            start = start.with_onto(onto, upstream);
+                        }
+                        _ => debug_log("Could not resolve --onto target or upstream"),
+                    }
+                }
+
                 // Log the rebase start event
-                let start_event = RewriteLogEvent::rebase_start(
-                    crate::git::rewrite_log::RebaseStartEvent::new(target.clone(), is_interactive),
-                );
+                let start_event = RewriteLogEvent::rebase_start(start);
 
                 // Write to rewrite log
                 match repository.storage.append_rewrite_event(start_event) {
@@ -71,7 +95,7 @@ pub fn handle_rebase_post_command(
     parsed_args: &ParsedGitInvocation,
     exit_status: std::process::ExitStatus,
     repository: &mut Repository,
-) {
+) -> Result<(), GitAiError> {
     debug_log("=== REBASE POST-COMMAND HOOK ===");
     debug_log(&format!("Exit status: {}", exit_status));
 
@@ -89,12 +113,12 @@ pub fn handle_rebase_post_command(
     if is_in_progress {
         // Rebase still in progress (conflict or not finished)
         debug_log("⏸ Rebase still in progress, waiting for completion (conflict or multi-step)");
-        return;
+        return Ok(());
     }
 
     if is_dry_run(&parsed_args.command_args) {
         debug_log("Skipping rebase post-hook for dry-run");
-        return;
+        return Ok(());
     }
 
     // Rebase is done (completed or aborted)
@@ -123,7 +147,7 @@ pub fn handle_rebase_post_command(
         } else {
             debug_log("✗ Rebase failed but couldn't determine original head");
         }
-        return;
+        return Ok(());
     }
 
     // Rebase completed successfully!
@@ -133,10 +157,11 @@ pub fn handle_rebase_post_command(
             "Processing completed rebase from {}",
             original_head
         ));
-        process_completed_rebase(repository, &original_head, parsed_args);
+        process_completed_rebase(repository, &original_head, parsed_args)?;
     } else {
         debug_log("⚠ Rebase completed but couldn't determine original head");
     }
+    Ok(())
 }
 
 /// Check if there's an active rebase Start event (not followed by Complete or Abort)
@@ -166,26 +191,76 @@ fn has_active_rebase_start_event(repository: &Repository) -> bool {
 
 /// Find the original head from the most recent Rebase Start event in the log
 fn find_rebase_start_event_original_head(repository: &Repository) -> Option<String> {
+    find_rebase_start_event(repository).map(|start| start.original_head)
+}
+
+/// The most recent Rebase Start event (events are newest-first)
+fn find_rebase_start_event(repository: &Repository) -> Option<RebaseStartEvent> {
     let events = repository.storage.read_rewrite_events().ok()?;
+    events.into_iter().find_map(|event| match event {
+        RewriteLogEvent::RebaseStart { rebase_start } => Some(rebase_start),
+        _ => None,
+    })
+}
 
-    // Find the most recent Start event (events are newest-first)
-    for event in events {
-        match event {
-            RewriteLogEvent::RebaseStart { rebase_start } => {
-                return Some(rebase_start.original_head.clone());
-            }
-            _ => continue,
+/// Positional arguments of `git rebase --onto <newbase> [<upstream> [<branch>]]`
+#[derive(Debug, PartialEq)]
+struct OntoArgs {
+    onto: String,
+    upstream: Option<String>,
+    branch: Option<String>,
+}
+
+/// Rebase options that take their value as the next argument
+const REBASE_FLAGS_WITH_VALUE: &[&str] = &[
+    "-s",
+    "--strategy",
+    "-X",
+    "--strategy-option",
+    "-x",
+    "--exec",
+    "-C",
+    "--whitespace",
+];
+
+fn parse_onto_args(command_args: &[String]) -> Option<OntoArgs> {
+    let mut onto = None;
+    let mut positional = Vec::new();
+    let mut iter = command_args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--onto" {
+            onto = iter.next().cloned();
+        } else if let Some(value) = arg.strip_prefix("--onto=") {
+            onto = Some(value.to_string());
+        } else if arg == "--root" {
+            // Transplanting from the root has no upstream to bound the range
+            return None;
+        } else if REBASE_FLAGS_WITH_VALUE.contains(&arg.as_str()) {
+            iter.next();
+        } else if !arg.starts_with('-') {
+            positional.push(arg.clone());
         }
     }
+    let mut positional = positional.into_iter();
+    Some(OntoArgs {
+        onto: onto?,
+        upstream: positional.next(),
+        branch: positional.next(),
+    })
+}
 
-    None
+fn resolve_commit(repository: &Repository, spec: &str) -> Option<String> {
+    repository
+        .revparse_single(&format!("{}^{{commit}}", spec))
+        .ok()
+        .map(|object| object.id())
 }
 
 fn process_completed_rebase(
     repository: &mut Repository,
     original_head: &str,
     parsed_args: &ParsedGitInvocation,
-) {
+) -> Result<(), GitAiError> {
     debug_log(&format!(
         "--- Processing completed rebase from {} ---",
         original_head
@@ -200,19 +275,19 @@ fn process_completed_rebase(
             }
             Err(e) => {
                 debug_log(&format!("✗ Failed to get HEAD target: {}", e));
-                return;
+                return Ok(());
             }
         },
         Err(e) => {
             debug_log(&format!("✗ Failed to get HEAD: {}", e));
-            return;
+            return Ok(());
         }
     };
 
     // If HEAD didn't change, nothing to do
     if original_head == new_head {
         debug_log("Rebase resulted in no changes (fast-forward or empty)");
-        return;
+        return Ok(());
     }
 
     // Build commit mappings
@@ -220,25 +295,37 @@ fn process_completed_rebase(
         "Building commit mappings: {} -> {}",
         original_head, new_head
     ));
-    let (original_commits, new_commits) =
-        match build_rebase_commit_mappings(repository, original_head, &new_head) {
-            Ok(mappings) => {
-                debug_log(&format!(
-                    "✓ Built mappings: {} original commits -> {} new commits",
-                    mappings.0.len(),
-                    mappings.1.len()
-                ));
-                mappings
-            }
-            Err(e) => {
-                debug_log(&format!("✗ Failed to build rebase mappings: {}", e));
-                return;
-            }
-        };
+    let start = find_rebase_start_event(repository)
+        .filter(|start| start.original_head == original_head && start.onto.is_some());
+    let onto = start.as_ref().and_then(|start| start.onto.clone());
+    let mappings = match start.as_ref().zip(onto.as_ref()) {
+        Some((start, onto)) => build_onto_commit_mappings(
+            repository,
+            original_head,
+            &new_head,
+            start.upstream.as_deref().unwrap_or_default(),
+            onto,
+        ),
+        None => build_rebase_commit_mappings(repository, original_head, &new_head),
+    };
+    let (original_commits, new_commits) = match mappings {
+        Ok(mappings) => {
+            debug_log(&format!(
+                "✓ Built mappings: {} original commits -> {} new commits",
+                mappings.0.len(),
+                mappings.1.len()
+            ));
+            mappings
+        }
+        Err(e) => {
+            debug_log(&format!("✗ Failed to build rebase mappings: {}", e));
+            return Ok(());
+        }
+    };
 
     if original_commits.is_empty() {
         debug_log("No commits to rewrite authorship for");
-        return;
+        return Ok(());
     }
 
     debug_log(&format!("Original commits: {:?}", original_commits));
@@ -256,14 +343,17 @@ fn process_completed_rebase(
         }
     ));
 
-    let rebase_event =
-        RewriteLogEvent::rebase_complete(crate::git::rewrite_log::RebaseCompleteEvent::new(
-            original_head.to_string(),
-            new_head.clone(),
-            is_interactive,
-            original_commits.clone(),
-            new_commits.clone(),
-        ));
+    let mut complete = crate::git::rewrite_log::RebaseCompleteEvent::new(
+        original_head.to_string(),
+        new_head.clone(),
+        is_interactive,
+        original_commits.clone(),
+        new_commits.clone(),
+    );
+    if let Some(onto) = onto {
+        complete = complete.with_onto(onto);
+    }
+    let rebase_event = RewriteLogEvent::rebase_complete(complete);
 
     debug_log("Creating RebaseComplete event and rewriting authorship...");
     let commit_author = get_commit_default_author(repository, &parsed_args.command_args);
@@ -273,9 +363,11 @@ fn process_completed_rebase(
         commit_author,
         false, // don't suppress output
         true,  // save to log
-    );
+    )?;
 
     debug_log("✓ Rebase authorship rewrite complete");
+    crate::commands::resolve_attribution::prompt_after_rebase(repository);
+    Ok(())
 }
 
 fn build_rebase_commit_mappings(
@@ -314,3 +406,65 @@ fn build_rebase_commit_mappings(
     // handle many-to-one, one-to-one, and other mapping scenarios properly
     Ok((original_commits, new_commits))
 }
+
+/// For `--onto`, the transplanted range is `<upstream>..<original_head>` and the
+/// rewritten commits sit directly on top of the new base
+fn build_onto_commit_mappings(
+    repository: &Repository,
+    original_head: &str,
+    new_head: &str,
+    upstream: &str,
+    onto: &str,
+) -> Result<(Vec<String>, Vec<String>), crate::error::GitAiError> {
+    let upstream_commit = repository.find_commit(upstream.to_string())?;
+    let original_head_commit = repository.find_commit(original_head.to_string())?;
+    let fork_point = repository.merge_base(upstream_commit.id(), original_head_commit.id())?;
+
+    let mut original_commits = walk_commits_to_base(repository, original_head, &fork_point)?;
+    let mut new_commits = walk_commits_to_base(repository, new_head, onto)?;
+    original_commits.reverse();
+    new_commits.reverse();
+
+    debug_log(&format!(
+        "Onto mapping: {} original (from {}) -> {} new (onto {})",
+        original_commits.len(),
+        fork_point,
+        new_commits.len(),
+        onto
+    ));
+
+    Ok((original_commits, new_commits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_onto_args() {
+        assert_eq!(parse_onto_args(&args(&["main"])), None);
+        assert_eq!(
+            parse_onto_args(&args(&[
+                "--onto", "main", "-X", "theirs", "old-base", "topic"
+            ])),
+            Some(OntoArgs {
+                onto: "main".to_string(),
+                upstream: Some("old-base".to_string()),
+                branch: Some("topic".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_onto_args(&args(&["-i", "--onto=HEAD~3"])),
+            Some(OntoArgs {
+                onto: "HEAD~3".to_string(),
+                upstream: None,
+                branch: None,
+            })
+        );
+        assert_eq!(parse_onto_args(&args(&["--onto", "main", "--root"])), None);
+    }
+}
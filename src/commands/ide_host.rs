@@ -0,0 +1,423 @@
+//! `git-ai ide-host`: the JSON-over-stdio contract for editor extensions.
+//!
+//! Each line on stdin is a request `{"id": .., "method": "..", "params": {..}}`.
+//! Each line on stdout is either a response `{"id": .., "result": ..}` /
+//! `{"id": .., "error": {"code": "..", "message": ".."}}` or a server
+//! notification `{"method": "..", "params": {..}}` (no `id`).
+//!
+//! Clients must call `initialize` first with the protocol versions they speak;
+//! the host answers with the version it picked and the capabilities it offers.
+//! Breaking changes bump `PROTOCOL_VERSION`; additive changes add capabilities.
+
+use crate::commands::blame::GitAiBlameOptions;
+use crate::commands::show_prompt::find_prompt;
+use crate::commands::working_stats::{FileStats, calculate_working_stats};
+use crate::error::{GitAiError, exit_with_error};
+use crate::git::find_repository;
+use crate::git::repository::Repository;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+use std::sync::mpsc;
+use std::time::Duration;
+
+pub const PROTOCOL_VERSION: u32 = 1;
+
+pub const CAPABILITIES: &[&str] = &["fileAttribution", "workingStats", "prompts"];
+
+/// How often subscribed files are re-checked for attribution changes
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A contiguous run of lines with the same author
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct AttributionRange {
+    pub start: u32,
+    pub end: u32,
+    pub author: String,
+    pub prompt_id: Option<String>,
+    pub tool: Option<String>,
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileAttribution {
+    pub path: String,
+    /// Committed lines, from blame with the AI overlay
+    pub lines: Vec<AttributionRange>,
+    /// Uncommitted changes tracked in the working log
+    pub uncommitted: Option<FileStats>,
+}
+
+pub struct IdeHost {
+    repo: Repository,
+    protocol_version: Option<u32>,
+    capabilities: Vec<String>,
+    /// Subscribed path -> last attribution sent to the client
+    subscriptions: BTreeMap<String, Value>,
+    shutdown: bool,
+}
+
+impl IdeHost {
+    pub fn new(repo: Repository) -> Self {
+        IdeHost {
+            repo,
+            protocol_version: None,
+            capabilities: Vec::new(),
+            subscriptions: BTreeMap::new(),
+            shutdown: false,
+        }
+    }
+
+    pub fn is_shutdown(&self) -> bool {
+        self.shutdown
+    }
+
+    /// Handle one line of input, returning the response line
+    pub fn handle_line(&mut self, line: &str) -> Value {
+        match serde_json::from_str::<Value>(line) {
+            Ok(request) => self.handle_request(&request),
+            Err(e) => error_response(Value::Null, "parse_error", &e.to_string()),
+        }
+    }
+
+    pub fn handle_request(&mut self, request: &Value) -> Value {
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let Some(method) = request.get("method").and_then(Value::as_str) else {
+            return error_response(id, "invalid_request", "missing method");
+        };
+        let params = request.get("params").cloned().unwrap_or(json!({}));
+
+        if method != "initialize" && self.protocol_version.is_none() {
+            return error_response(id, "not_initialized", "call initialize first");
+        }
+
+        let result = match method {
+            "initialize" => self.initialize(&params),
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            }
+            "fileAttribution/subscribe" => self
+                .require_capability("fileAttribution")
+                .and_then(|_| self.subscribe(&params)),
+            "fileAttribution/unsubscribe" => self
+                .require_capability("fileAttribution")
+                .and_then(|_| self.unsubscribe(&params)),
+            "workingStats/get" => self
+                .require_capability("workingStats")
+                .and_then(|_| self.working_stats(&params)),
+            "prompt/get" => self
+                .require_capability("prompts")
+                .and_then(|_| self.prompt(&params)),
+            other => Err(("method_not_found", format!("unknown method: {}", other))),
+        };
+
+        match result {
+            Ok(result) => json!({ "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, &message),
+        }
+    }
+
+    /// Re-check subscribed files and return a notification for each one that changed
+    pub fn poll_subscriptions(&mut self) -> Vec<Value> {
+        let mut notifications = Vec::new();
+        let paths: Vec<String> = self.subscriptions.keys().cloned().collect();
+        for path in paths {
+            let current = self.attribution_value(&path);
+            if self.subscriptions.get(&path) != Some(&current) {
+                notifications.push(json!({
+                    "method": "fileAttribution/changed",
+                    "params": current.clone(),
+                }));
+                self.subscriptions.insert(path, current);
+            }
+        }
+        notifications
+    }
+
+    fn initialize(&mut self, params: &Value) -> Result<Value, (&'static str, String)> {
+        let requested: Vec<u32> = params
+            .get("protocol_versions")
+            .and_then(Value::as_array)
+            .map(|versions| {
+                versions
+                    .iter()
+                    .filter_map(|v| v.as_u64().map(|v| v as u32))
+                    .collect()
+            })
+            .unwrap_or_else(|| vec![PROTOCOL_VERSION]);
+        let Some(version) = requested
+            .into_iter()
+            .filter(|v| *v == PROTOCOL_VERSION)
+            .max()
+        else {
+            return Err((
+                "unsupported_protocol_version",
+                format!("supported protocol versions: [{}]", PROTOCOL_VERSION),
+            ));
+        };
+
+        // Without an explicit list the client gets every capability we have
+        let capabilities: Vec<String> = match params.get("capabilities").and_then(Value::as_array) {
+            Some(wanted) => CAPABILITIES
+                .iter()
+                .filter(|cap| wanted.iter().any(|w| w.as_str() == Some(**cap)))
+                .map(|cap| cap.to_string())
+                .collect(),
+            None => CAPABILITIES.iter().map(|cap| cap.to_string()).collect(),
+        };
+
+        self.protocol_version = Some(version);
+        self.capabilities = capabilities.clone();
+        Ok(json!({
+            "protocol_version": version,
+            "server_version": env!("CARGO_PKG_VERSION"),
+            "capabilities": capabilities,
+        }))
+    }
+
+    fn require_capability(&self, capability: &str) -> Result<(), (&'static str, String)> {
+        if self.capabilities.iter().any(|c| c == capability) {
+            Ok(())
+        } else {
+            Err((
+                "capability_not_negotiated",
+                format!("capability '{}' was not negotiated", capability),
+            ))
+        }
+    }
+
+    fn subscribe(&mut self, params: &Value) -> Result<Value, (&'static str, String)> {
+        let path = required_str(params, "path")?;
+        let attribution = self.attribution_value(path);
+        self.subscriptions
+            .insert(path.to_string(), attribution.clone());
+        Ok(attribution)
+    }
+
+    fn unsubscribe(&mut self, params: &Value) -> Result<Value, (&'static str, String)> {
+        let path = required_str(params, "path")?;
+        Ok(json!({ "unsubscribed": self.subscriptions.remove(path).is_some() }))
+    }
+
+    fn working_stats(&self, params: &Value) -> Result<Value, (&'static str, String)> {
+        let ignore: Vec<String> = params
+            .get("ignore")
+            .and_then(Value::as_array)
+            .map(|patterns| {
+                patterns
+                    .iter()
+                    .filter_map(|p| p.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let stats = calculate_working_stats(&self.repo, &ignore)
+            .map_err(|e| ("internal_error", e.to_string()))?;
+        serde_json::to_value(stats).map_err(|e| ("internal_error", e.to_string()))
+    }
+
+    fn prompt(&self, params: &Value) -> Result<Value, (&'static str, String)> {
+        let prompt_id = required_str(params, "id")?;
+        let commit = params.get("commit").and_then(Value::as_str);
+        let (commit_sha, prompt) = find_prompt(&self.repo, prompt_id, commit, 0)
+            .map_err(|e| ("not_found", e.to_string()))?;
+        Ok(json!({
+            "commit": commit_sha,
+            "prompt_id": prompt_id,
+            "prompt": prompt,
+        }))
+    }
+
+    fn attribution_value(&self, path: &str) -> Value {
+        serde_json::to_value(file_attribution(&self.repo, path)).unwrap_or(Value::Null)
+    }
+}
+
+fn required_str<'a>(params: &'a Value, key: &str) -> Result<&'a str, (&'static str, String)> {
+    params
+        .get(key)
+        .and_then(Value::as_str)
+        .ok_or_else(|| ("invalid_params", format!("missing string param '{}'", key)))
+}
+
+fn error_response(id: Value, code: &str, message: &str) -> Value {
+    json!({ "id": id, "error": { "code": code, "message": message } })
+}
+
+/// Attribution for `path` (relative to the repository root). Files git cannot
+/// blame yet, such as new untracked files, report no committed lines.
+pub fn file_attribution(repo: &Repository, path: &str) -> FileAttribution {
+    let options = GitAiBlameOptions {
+        use_prompt_hashes_as_names: true,
+        return_human_authors_as_human: true,
+        no_output: true,
+        ..Default::default()
+    };
+
+    let mut lines: Vec<AttributionRange> = Vec::new();
+    if let Ok((line_authors, prompts)) = repo.blame(path, &options) {
+        let mut line_numbers: Vec<&u32> = line_authors.keys().collect();
+        line_numbers.sort();
+        for line in line_numbers {
+            let author = &line_authors[line];
+            if let Some(last) = lines.last_mut()
+                && last.end + 1 == *line
+                && &last.author == author
+            {
+                last.end = *line;
+                continue;
+            }
+            let prompt = prompts.get(author);
+            lines.push(AttributionRange {
+                start: *line,
+                end: *line,
+                author: author.clone(),
+                prompt_id: prompt.map(|_| author.clone()),
+                tool: prompt.map(|p| p.agent_id.tool.clone()),
+                model: prompt.map(|p| p.agent_id.model.clone()),
+            });
+        }
+    }
+
+    let uncommitted = calculate_working_stats(repo, &[])
+        .ok()
+        .and_then(|mut stats| stats.by_file.remove(path));
+
+    FileAttribution {
+        path: path.to_string(),
+        lines,
+        uncommitted,
+    }
+}
+
+pub fn handle_ide_host(args: &[String]) {
+    if let Some(arg) = args.first() {
+        exit_with_error(
+            "Error",
+            &GitAiError::InvalidArguments(format!(
+                "Unknown ide-host argument: {}\nUsage: git-ai ide-host   (speaks JSON lines on stdin/stdout)",
+                arg
+            )),
+        );
+    }
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            exit_with_error("Failed to find repository", &e);
+        }
+    };
+
+    // Read stdin on its own thread so subscriptions can be polled between requests
+    let (tx, rx) = mpsc::channel::<String>();
+    std::thread::spawn(move || {
+        let stdin = std::io::stdin();
+        for line in stdin.lock().lines() {
+            match line {
+                Ok(line) => {
+                    if tx.send(line).is_err() {
+                        break;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+    });
+
+    let mut host = IdeHost::new(repo);
+    let mut stdout = std::io::stdout();
+    loop {
+        let messages = match rx.recv_timeout(POLL_INTERVAL) {
+            Ok(line) if line.trim().is_empty() => continue,
+            Ok(line) => vec![host.handle_line(&line)],
+            Err(mpsc::RecvTimeoutError::Timeout) => host.poll_subscriptions(),
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        };
+
+        for message in messages {
+            if writeln!(stdout, "{}", message)
+                .and_then(|_| stdout.flush())
+                .is_err()
+            {
+                return;
+            }
+        }
+
+        if host.is_shutdown() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    fn host(tmp_repo: &TmpRepo) -> IdeHost {
+        IdeHost::new(tmp_repo.gitai_repo().clone())
+    }
+
+    #[test]
+    fn test_initialize_negotiates_version_and_capabilities() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let mut host = host(&tmp_repo);
+
+        let response = host.handle_line(
+            r#"{"id":1,"method":"initialize","params":{"protocol_versions":[1,2],"capabilities":["workingStats","bogus"]}}"#,
+        );
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"]["protocol_version"], PROTOCOL_VERSION);
+        assert_eq!(response["result"]["capabilities"], json!(["workingStats"]));
+
+        let response = host.handle_line(
+            r#"{"id":2,"method":"fileAttribution/subscribe","params":{"path":"a.txt"}}"#,
+        );
+        assert_eq!(response["error"]["code"], "capability_not_negotiated");
+    }
+
+    #[test]
+    fn test_rejects_requests_before_initialize_and_bad_input() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let mut host = host(&tmp_repo);
+
+        let response = host.handle_line(r#"{"id":"a","method":"workingStats/get"}"#);
+        assert_eq!(response["error"]["code"], "not_initialized");
+
+        let response = host.handle_line("not json");
+        assert_eq!(response["error"]["code"], "parse_error");
+
+        let response = host
+            .handle_line(r#"{"id":1,"method":"initialize","params":{"protocol_versions":[9]}}"#);
+        assert_eq!(response["error"]["code"], "unsupported_protocol_version");
+    }
+
+    #[test]
+    fn test_subscription_reports_changes() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "one\n", true).unwrap();
+        tmp_repo.trigger_checkpoint_with_author("dev").unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+
+        let mut host = host(&tmp_repo);
+        host.handle_line(r#"{"id":1,"method":"initialize"}"#);
+        let response = host.handle_line(
+            r#"{"id":2,"method":"fileAttribution/subscribe","params":{"path":"a.txt"}}"#,
+        );
+        assert_eq!(response["result"]["path"], "a.txt");
+        assert!(host.poll_subscriptions().is_empty());
+
+        tmp_repo.write_file("a.txt", "one\ntwo\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", None, None)
+            .unwrap();
+        let notifications = host.poll_subscriptions();
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0]["method"], "fileAttribution/changed");
+
+        let response = host.handle_line(r#"{"id":3,"method":"shutdown"}"#);
+        assert_eq!(response["result"], Value::Null);
+        assert!(host.is_shutdown());
+    }
+}
@@ -0,0 +1,586 @@
+use crate::authorship::authorship_log::{LineRange, PromptRecord};
+use crate::authorship::authorship_log_serialization::{
+    AttestationEntry, AuthorshipLog, generate_short_hash,
+};
+use crate::authorship::transcript::AiTranscript;
+use crate::authorship::working_log::{AgentId, CheckpointKind};
+use crate::commands::checkpoint_agent::agent_presets::CursorPreset;
+use crate::commands::diff::get_diff_with_line_numbers;
+use crate::config::Config;
+use crate::error::{GitAiError, exit_with_error};
+use crate::git::find_repository;
+use crate::git::refs::{get_authorship, notes_add};
+use crate::git::repository::{Repository, exec_git};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Default time after a session's last message during which its edits may still be committed
+const DEFAULT_WINDOW_HOURS: i64 = 24;
+
+/// Handle the `import` command
+///
+/// Usage: git-ai import cursor-history [--db <path>] [--window-hours <n>] [--dry-run]
+pub fn handle_import(args: &[String]) {
+    if args.is_empty() {
+        print_import_help_and_exit();
+    }
+
+    match args[0].as_str() {
+        "cursor-history" => handle_import_cursor_history(&args[1..]),
+        other => {
+            eprintln!("Unknown import source: {}", other);
+            print_import_help_and_exit();
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CursorImportArgs {
+    pub db: Option<PathBuf>,
+    pub window_hours: i64,
+    pub dry_run: bool,
+}
+
+pub fn parse_cursor_import_args(args: &[String]) -> Result<CursorImportArgs, String> {
+    let mut parsed = CursorImportArgs {
+        db: None,
+        window_hours: DEFAULT_WINDOW_HOURS,
+        dry_run: false,
+    };
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--db" => {
+                if i + 1 >= args.len() {
+                    return Err("--db requires a path".to_string());
+                }
+                i += 1;
+                parsed.db = Some(PathBuf::from(&args[i]));
+            }
+            "--window-hours" => {
+                if i + 1 >= args.len() {
+                    return Err("--window-hours requires a value".to_string());
+                }
+                i += 1;
+                parsed.window_hours = args[i]
+                    .parse::<i64>()
+                    .ok()
+                    .filter(|hours| *hours >= 0)
+                    .ok_or_else(|| format!("Invalid --window-hours value: {}", args[i]))?;
+            }
+            "--dry-run" => parsed.dry_run = true,
+            other => return Err(format!("Unknown option: {}", other)),
+        }
+        i += 1;
+    }
+
+    Ok(parsed)
+}
+
+/// A past Cursor chat session and the lines its edit tools wrote in this repository
+#[derive(Debug, Clone)]
+pub struct ImportedSession {
+    pub composer_id: String,
+    pub model: String,
+    pub transcript: AiTranscript,
+    /// Unix seconds
+    pub started_at: i64,
+    pub ended_at: i64,
+    /// Repo-relative path -> trimmed, non-empty lines written by the agent
+    pub written_lines: BTreeMap<String, HashSet<String>>,
+}
+
+#[derive(Debug, Default)]
+pub struct ImportSummary {
+    pub sessions_scanned: usize,
+    pub sessions_matched: usize,
+    pub commits_updated: usize,
+    pub lines_attributed: u32,
+}
+
+fn handle_import_cursor_history(args: &[String]) {
+    let parsed = match parse_cursor_import_args(args) {
+        Ok(p) => p,
+        Err(e) => {
+            exit_with_error("Error", &GitAiError::InvalidArguments(e));
+        }
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            exit_with_error("Failed to find repository", &e);
+        }
+    };
+
+    let db_path = match parsed.db.clone() {
+        Some(path) => path,
+        None => match CursorPreset::cursor_global_database_path() {
+            Ok(path) => path,
+            Err(e) => {
+                exit_with_error(
+                    "Error",
+                    &GitAiError::InvalidArguments(format!(
+                        "Failed to locate Cursor database: {} (pass --db <path>)",
+                        e
+                    )),
+                );
+            }
+        },
+    };
+    if !db_path.exists() {
+        exit_with_error(
+            "Error",
+            &GitAiError::InvalidArguments(format!(
+                "Cursor database not found at {}",
+                db_path.display()
+            )),
+        );
+    }
+
+    match import_cursor_history(&repo, &db_path, parsed.window_hours, parsed.dry_run) {
+        Ok(summary) => {
+            let verb = if parsed.dry_run {
+                "Would backfill"
+            } else {
+                "Backfilled"
+            };
+            println!(
+                "{} {} AI lines in {} commits from {} of {} Cursor sessions",
+                verb,
+                summary.lines_attributed,
+                summary.commits_updated,
+                summary.sessions_matched,
+                summary.sessions_scanned
+            );
+        }
+        Err(e) => {
+            exit_with_error("Import failed", &e);
+        }
+    }
+}
+
+/// Reconstruct attribution for Cursor sessions that predate git-ai in this repository.
+///
+/// Each session is matched to the commits that touched its edited files between
+/// the session start and `window_hours` after its last message. Added lines in
+/// those commits that the agent wrote verbatim are attributed to the session,
+/// unless the commit's authorship log already attributes them.
+pub fn import_cursor_history(
+    repo: &Repository,
+    db_path: &Path,
+    window_hours: i64,
+    dry_run: bool,
+) -> Result<ImportSummary, GitAiError> {
+    let workdir = repo.workdir()?;
+    let mut summary = ImportSummary::default();
+
+    for composer_id in CursorPreset::list_composer_ids(db_path)? {
+        summary.sessions_scanned += 1;
+        let Some(session) = read_session(db_path, &composer_id, &workdir) else {
+            continue;
+        };
+
+        let mut matched = false;
+        let until = session.ended_at + window_hours * 3600;
+        for (sha, author) in commits_in_window(repo, &session, until)? {
+            let lines = backfill_commit(repo, &sha, &author, &session, dry_run)?;
+            if lines > 0 {
+                matched = true;
+                summary.commits_updated += 1;
+                summary.lines_attributed += lines;
+            }
+        }
+        if matched {
+            summary.sessions_matched += 1;
+        }
+    }
+
+    Ok(summary)
+}
+
+fn read_session(db_path: &Path, composer_id: &str, workdir: &Path) -> Option<ImportedSession> {
+    let payload = CursorPreset::fetch_composer_payload(db_path, composer_id).ok()?;
+    let started_at = payload.get("createdAt").and_then(Value::as_i64)? / 1000;
+    let ended_at = payload
+        .get("lastUpdatedAt")
+        .and_then(Value::as_i64)
+        .map(|ms| ms / 1000)
+        .unwrap_or(started_at);
+
+    let mut written_lines: BTreeMap<String, HashSet<String>> = BTreeMap::new();
+    let headers = payload
+        .get("fullConversationHeadersOnly")
+        .and_then(Value::as_array)?;
+    for bubble_id in headers
+        .iter()
+        .filter_map(|h| h.get("bubbleId").and_then(Value::as_str))
+    {
+        let Ok(Some(bubble)) =
+            CursorPreset::fetch_bubble_content_from_db(db_path, composer_id, bubble_id)
+        else {
+            continue;
+        };
+        if let Some((path, lines)) = written_lines_from_bubble(&bubble, workdir) {
+            written_lines.entry(path).or_default().extend(lines);
+        }
+    }
+    if written_lines.is_empty() {
+        return None;
+    }
+
+    let (transcript, model) =
+        CursorPreset::transcript_data_from_composer_payload(&payload, db_path, composer_id)
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| (AiTranscript::new(), "unknown".to_string()));
+
+    Some(ImportedSession {
+        composer_id: composer_id.to_string(),
+        model,
+        transcript,
+        started_at,
+        ended_at,
+        written_lines,
+    })
+}
+
+/// Extract the repo-relative file path and written lines from an edit tool call
+pub fn written_lines_from_bubble(
+    bubble: &Value,
+    workdir: &Path,
+) -> Option<(String, HashSet<String>)> {
+    let tool = bubble.get("toolFormerData")?;
+    let raw_args: Value = serde_json::from_str(tool.get("rawArgs")?.as_str()?).ok()?;
+
+    let file_path = ["file_path", "target_file", "relative_workspace_path"]
+        .iter()
+        .find_map(|key| raw_args.get(*key).and_then(Value::as_str))?;
+    let relative = match Path::new(file_path).strip_prefix(workdir) {
+        Ok(relative) => relative.to_string_lossy().replace('\\', "/"),
+        Err(_) if Path::new(file_path).is_relative() => file_path.to_string(),
+        Err(_) => return None,
+    };
+
+    let lines: HashSet<String> = ["new_string", "code_edit", "contents", "content"]
+        .iter()
+        .filter_map(|key| raw_args.get(*key).and_then(Value::as_str))
+        .flat_map(|text| text.lines())
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    (!lines.is_empty()).then_some((relative, lines))
+}
+
+/// Non-merge commits on HEAD touching the session's files, oldest first, with their authors
+fn commits_in_window(
+    repo: &Repository,
+    session: &ImportedSession,
+    until: i64,
+) -> Result<Vec<(String, String)>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("log".to_string());
+    args.push("--reverse".to_string());
+    args.push("--no-merges".to_string());
+    args.push(format!("--since={}", session.started_at));
+    args.push(format!("--until={}", until));
+    args.push("--format=%H%x1f%an <%ae>".to_string());
+    args.push("HEAD".to_string());
+    args.push("--".to_string());
+    args.extend(session.written_lines.keys().cloned());
+
+    let output = exec_git(&args)?;
+    let stdout = String::from_utf8(output.stdout)
+        .map_err(|_| GitAiError::Generic("Failed to parse git log output".to_string()))?;
+
+    Ok(stdout
+        .lines()
+        .filter_map(|line| {
+            let (sha, author) = line.split_once('\x1f')?;
+            Some((sha.to_string(), author.to_string()))
+        })
+        .collect())
+}
+
+/// Attribute the commit's added lines that `session` wrote. Returns the number of lines attributed.
+fn backfill_commit(
+    repo: &Repository,
+    sha: &str,
+    author: &str,
+    session: &ImportedSession,
+    dry_run: bool,
+) -> Result<u32, GitAiError> {
+    let hash = generate_short_hash(&session.composer_id, "cursor");
+    let mut log = get_authorship(repo, sha).unwrap_or_else(|| {
+        let mut log = AuthorshipLog::new();
+        log.metadata.base_commit_sha = sha.to_string();
+        log
+    });
+    // Sessions recorded live (or already imported) are left alone
+    if log.metadata.prompts.contains_key(&hash) {
+        return Ok(0);
+    }
+
+    // Root commits have no parent to diff against
+    let Ok(hunks) = get_diff_with_line_numbers(repo, &format!("{}^", sha), sha) else {
+        return Ok(0);
+    };
+
+    let mut attributed: BTreeMap<String, Vec<u32>> = BTreeMap::new();
+    for hunk in hunks {
+        let Some(written) = session.written_lines.get(&hunk.file_path) else {
+            continue;
+        };
+        if hunk.added_lines.is_empty() {
+            continue;
+        }
+        let Ok(content) = repo.get_file_content(&hunk.file_path, sha) else {
+            continue;
+        };
+        let content = String::from_utf8_lossy(&content);
+        let file_lines: Vec<&str> = content.lines().collect();
+        let existing = log
+            .attestations
+            .iter()
+            .find(|f| f.file_path == hunk.file_path);
+
+        // Lines like `}` or `else {` match almost any session, so a run of
+        // consecutive matching lines only counts if something in it is distinctive
+        let mut run: Vec<u32> = Vec::new();
+        let mut run_is_distinctive = false;
+        let mut previous = None;
+        for line in hunk.added_lines {
+            let text = file_lines
+                .get(line.saturating_sub(1) as usize)
+                .map(|text| text.trim());
+            let already_attributed = existing.is_some_and(|f| {
+                f.entries
+                    .iter()
+                    .any(|e| e.line_ranges.iter().any(|r| r.contains(line)))
+            });
+            let matched = text.filter(|text| !already_attributed && written.contains(*text));
+            if matched.is_none() || previous.is_some_and(|p: u32| p + 1 != line) {
+                if run_is_distinctive {
+                    attributed
+                        .entry(hunk.file_path.clone())
+                        .or_default()
+                        .append(&mut run);
+                }
+                run.clear();
+                run_is_distinctive = false;
+            }
+            if let Some(text) = matched {
+                run.push(line);
+                run_is_distinctive |= !is_trivial_line(text);
+            }
+            previous = Some(line);
+        }
+        if run_is_distinctive {
+            attributed
+                .entry(hunk.file_path.clone())
+                .or_default()
+                .append(&mut run);
+        }
+    }
+
+    let total: u32 = attributed.values().map(|lines| lines.len() as u32).sum();
+    if total == 0 || dry_run {
+        return Ok(total);
+    }
+
+    for (file, mut lines) in attributed {
+        lines.sort_unstable();
+        log.get_or_create_file(&file)
+            .add_entry(AttestationEntry::new(
+                hash.clone(),
+                LineRange::compress_lines(&lines),
+            ));
+    }
+    let messages = if Config::get().ignore_prompts() {
+        Vec::new()
+    } else {
+        session.transcript.messages.clone()
+    };
+    log.metadata.prompts.insert(
+        hash.clone(),
+        PromptRecord {
+            agent_id: AgentId {
+                tool: "cursor".to_string(),
+                id: session.composer_id.clone(),
+                model: session.model.clone(),
+            },
+            human_author: Some(author.to_string()),
+            messages,
+            total_additions: total,
+            total_deletions: 0,
+            accepted_lines: total,
+            overriden_lines: 0,
+            generated: false,
+            kind: PromptRecord::kind_label(CheckpointKind::Imported),
+            notes: Vec::new(),
+            tags: Vec::new(),
+            agent_metadata: BTreeMap::new(),
+        },
+    );
+    log.metadata.backfilled_prompts.push(hash);
+
+    let serialized = log
+        .serialize_to_string()
+        .map_err(|_| GitAiError::Generic("Failed to serialize authorship log".to_string()))?;
+    notes_add(repo, sha, &serialized)?;
+
+    Ok(total)
+}
+
+/// Punctuation and bare keywords that say nothing about who wrote a line
+fn is_trivial_line(text: &str) -> bool {
+    const KEYWORDS: [&str; 14] = [
+        "else", "end", "return", "break", "continue", "pass", "try", "do", "default", "finally",
+        "then", "fi", "done", "esac",
+    ];
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| !word.is_empty())
+        .all(|word| KEYWORDS.contains(&word))
+}
+
+fn print_import_help_and_exit() -> ! {
+    eprintln!("git-ai import - Backfill authorship from agent history recorded before git-ai");
+    eprintln!();
+    eprintln!("Usage: git-ai import cursor-history [options]");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  --db <path>          Cursor state.vscdb (default: Cursor's global storage)");
+    eprintln!(
+        "  --window-hours <n>   Hours after a session ends to look for its commits (default: {})",
+        DEFAULT_WINDOW_HOURS
+    );
+    eprintln!("  --dry-run            Report what would be imported without writing notes");
+    std::process::exit(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_cursor_import_args() {
+        let args: Vec<String> = [
+            "--db",
+            "/tmp/state.vscdb",
+            "--window-hours",
+            "4",
+            "--dry-run",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        assert_eq!(
+            parse_cursor_import_args(&args).unwrap(),
+            CursorImportArgs {
+                db: Some(PathBuf::from("/tmp/state.vscdb")),
+                window_hours: 4,
+                dry_run: true,
+            }
+        );
+        assert!(parse_cursor_import_args(&["--window-hours".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_written_lines_from_edit_bubble() {
+        let raw_args = json!({
+            "file_path": "/work/repo/src/lib.rs",
+            "old_string": "fn a() {}",
+            "new_string": "fn a() {\n    b();\n}\n",
+        });
+        let bubble = json!({
+            "toolFormerData": { "name": "search_replace", "rawArgs": raw_args.to_string() }
+        });
+
+        let (path, lines) = written_lines_from_bubble(&bubble, Path::new("/work/repo")).unwrap();
+        assert_eq!(path, "src/lib.rs");
+        assert!(lines.contains("b();"));
+        assert!(lines.contains("fn a() {"));
+        assert!(!lines.contains("fn a() {}"));
+
+        // Files outside the repository are ignored
+        assert!(written_lines_from_bubble(&bubble, Path::new("/elsewhere")).is_none());
+    }
+
+    #[test]
+    fn test_is_trivial_line() {
+        assert!(is_trivial_line("}"));
+        assert!(is_trivial_line("} else {"));
+        assert!(is_trivial_line("return;"));
+        assert!(is_trivial_line(""));
+        assert!(!is_trivial_line("42"));
+        assert!(!is_trivial_line("return value;"));
+    }
+
+    #[test]
+    fn test_import_backfills_matching_commit() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("lib.rs", "fn a() {}\n", true).unwrap();
+        tmp_repo.trigger_checkpoint_with_author("dev").unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+
+        // TmpRepo commits are stamped 2023-01-01T12:00:00Z
+        let started_ms: i64 = (1_672_574_400 - 60) * 1000;
+        let db_dir = tempfile::tempdir().unwrap();
+        let db_path = db_dir.path().join("state.vscdb");
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        conn.execute("CREATE TABLE cursorDiskKV (key TEXT, value TEXT)", [])
+            .unwrap();
+        let composer = json!({
+            "createdAt": started_ms,
+            "lastUpdatedAt": started_ms,
+            "fullConversationHeadersOnly": [{ "bubbleId": "b1", "type": 2 }],
+        });
+        let raw_args = json!({ "file_path": "lib.rs", "new_string": "fn b() {\n    42\n}" });
+        let bubble = json!({
+            "text": "Added b",
+            "toolFormerData": { "name": "search_replace", "rawArgs": raw_args.to_string() },
+        });
+        conn.execute(
+            "INSERT INTO cursorDiskKV VALUES (?1, ?2), (?3, ?4)",
+            [
+                "composerData:c1",
+                &composer.to_string(),
+                "bubbleId:c1:b1",
+                &bubble.to_string(),
+            ],
+        )
+        .unwrap();
+        drop(conn);
+
+        // Committed without any live checkpoint, as if git-ai wasn't installed yet
+        tmp_repo
+            .write_file(
+                "lib.rs",
+                "fn a() {}\nfn b() {\n    42\n}\nfn c() {\n    0\n}\n",
+                true,
+            )
+            .unwrap();
+        tmp_repo.commit_with_message("Add b and c").unwrap();
+
+        let repo = tmp_repo.gitai_repo();
+        // c's closing brace matches the session's text but sits apart from b
+        let summary = import_cursor_history(repo, &db_path, 1, false).unwrap();
+        assert_eq!(summary.sessions_matched, 1);
+        assert_eq!(summary.lines_attributed, 3);
+
+        let head = repo.head().unwrap().target().unwrap();
+        let log = get_authorship(repo, &head).unwrap();
+        let hash = generate_short_hash("c1", "cursor");
+        assert_eq!(log.metadata.backfilled_prompts, vec![hash.clone()]);
+        assert_eq!(log.metadata.prompts[&hash].accepted_lines, 3);
+
+        // Running again doesn't attribute the same session twice
+        let summary = import_cursor_history(repo, &db_path, 1, false).unwrap();
+        assert_eq!(summary.lines_attributed, 0);
+    }
+}
@@ -1,5 +1,8 @@
 use crate::authorship::imara_diff_utils::{LineChangeTag, compute_line_changes};
+use crate::commands::git_hooks::{self, HookState};
+use crate::commands::output::paint;
 use crate::error::GitAiError;
+use crate::git::repository::{Repository, find_repository};
 use crate::utils::debug_log;
 use indicatif::{ProgressBar, ProgressStyle};
 use jsonc_parser::ParseOptions;
@@ -31,19 +34,87 @@ const CURSOR_AFTER_EDIT_CMD: &str = "checkpoint cursor --hook-input stdin";
 pub fn run(args: &[String]) -> Result<(), GitAiError> {
     // Parse --dry-run flag (default: false)
     let mut dry_run = false;
+    let mut check = false;
+    let mut force = false;
     for arg in args {
         if arg == "--dry-run" || arg == "--dry-run=true" {
             dry_run = true;
+        } else if arg == "--check" {
+            check = true;
+        } else if arg == "--force" {
+            force = true;
         }
     }
 
     // Get absolute path to the current binary
     let binary_path = get_current_binary_path()?;
 
+    // Git hook shims only apply inside a repository
+    let repo = find_repository(&Vec::new()).ok();
+
+    if check {
+        return match repo {
+            Some(repo) => check_repo_git_hooks(&repo, &binary_path),
+            None => Err(GitAiError::Generic(
+                "--check must be run inside a git repository".to_string(),
+            )),
+        };
+    }
+
+    if let Some(repo) = &repo {
+        install_repo_git_hooks(repo, &binary_path, dry_run, force);
+    }
+
     // Run async operations with smol
     smol::block_on(async_run(binary_path, dry_run))
 }
 
+fn install_repo_git_hooks(repo: &Repository, binary_path: &Path, dry_run: bool, force: bool) {
+    let spinner = Spinner::new("Git hooks: checking commit hooks");
+    spinner.start();
+    match git_hooks::install_git_hooks(repo, binary_path, dry_run, force) {
+        Ok(report) => {
+            if dry_run {
+                spinner.pending("Git hooks: checked");
+            } else {
+                spinner.success("Git hooks: installed");
+            }
+            for line in report {
+                println!("  {}", line);
+            }
+        }
+        Err(e) => {
+            spinner.error("Git hooks: not installed");
+            eprintln!("  Error: {}", e);
+        }
+    }
+}
+
+fn check_repo_git_hooks(repo: &Repository, binary_path: &Path) -> Result<(), GitAiError> {
+    let states = git_hooks::check_git_hooks(repo, binary_path)?;
+    let mut missing = Vec::new();
+    for (hook, state) in states {
+        let label = match state {
+            HookState::Installed => "installed",
+            HookState::Outdated => "outdated (points at a different git-ai binary)",
+            HookState::Foreign => "not installed (another hook is in place)",
+            HookState::Missing => "not installed",
+        };
+        println!("{}: {}", hook, label);
+        if state != HookState::Installed {
+            missing.push(hook);
+        }
+    }
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(GitAiError::Generic(format!(
+            "git hooks need attention: {}; run `git-ai install-hooks` to fix",
+            missing.join(", ")
+        )))
+    }
+}
+
 async fn async_run(binary_path: PathBuf, dry_run: bool) -> Result<(), GitAiError> {
     let mut any_checked = false;
     let mut has_changes = false;
@@ -323,9 +394,18 @@ async fn async_run(binary_path: PathBuf, dry_run: bool) -> Result<(), GitAiError
     if !any_checked {
         println!("No compatible IDEs or agent configurations detected. Nothing to install.");
     } else if has_changes && dry_run {
-        println!("\n\x1b[33m⚠ Dry-run mode (default). No changes were made.\x1b[0m");
+        println!(
+            "\n{}",
+            paint(
+                "\x1b[33m",
+                "⚠ Dry-run mode (default). No changes were made."
+            )
+        );
         println!("To apply these changes, run:");
-        println!("\x1b[1m  git-ai install-hooks --dry-run=false\x1b[0m");
+        println!(
+            "{}",
+            paint("\x1b[1m", "  git-ai install-hooks --dry-run=false")
+        );
     }
 
     Ok(())
@@ -336,16 +416,16 @@ fn print_diff(diff_text: &str) {
     for line in diff_text.lines() {
         if line.starts_with("+++") || line.starts_with("---") {
             // File headers in bold
-            println!("\x1b[1m{}\x1b[0m", line);
+            println!("{}", paint("\x1b[1m", line));
         } else if line.starts_with('+') {
             // Additions in green
-            println!("\x1b[32m{}\x1b[0m", line);
+            println!("{}", paint("\x1b[32m", line));
         } else if line.starts_with('-') {
             // Deletions in red
-            println!("\x1b[31m{}\x1b[0m", line);
+            println!("{}", paint("\x1b[31m", line));
         } else if line.starts_with("@@") {
             // Hunk headers in cyan
-            println!("\x1b[36m{}\x1b[0m", line);
+            println!("{}", paint("\x1b[36m", line));
         } else {
             // Context lines normal
             println!("{}", line);
@@ -369,13 +449,13 @@ fn check_claude_code() -> Result<bool, String> {
     if has_binary {
         match get_binary_version("claude") {
             Ok(version_str) => {
-                if let Some(version) = parse_version(&version_str) {
-                    if !version_meets_requirement(version, MIN_CLAUDE_VERSION) {
-                        return Err(format!(
-                            "Claude Code version {}.{} detected, but minimum version {}.{} is required",
-                            version.0, version.1, MIN_CLAUDE_VERSION.0, MIN_CLAUDE_VERSION.1
-                        ));
-                    }
+                if let Some(version) = parse_version(&version_str)
+                    && !version_meets_requirement(version, MIN_CLAUDE_VERSION)
+                {
+                    return Err(format!(
+                        "Claude Code version {}.{} detected, but minimum version {}.{} is required",
+                        version.0, version.1, MIN_CLAUDE_VERSION.0, MIN_CLAUDE_VERSION.1
+                    ));
                 }
                 // If we can't parse, continue anyway (be permissive)
             }
@@ -407,13 +487,13 @@ fn check_cursor() -> Result<bool, String> {
     if has_binary {
         match get_binary_version("cursor") {
             Ok(version_str) => {
-                if let Some(version) = parse_version(&version_str) {
-                    if !version_meets_requirement(version, MIN_CURSOR_VERSION) {
-                        return Err(format!(
-                            "Cursor version {}.{} detected, but minimum version {}.{} is required",
-                            version.0, version.1, MIN_CURSOR_VERSION.0, MIN_CURSOR_VERSION.1
-                        ));
-                    }
+                if let Some(version) = parse_version(&version_str)
+                    && !version_meets_requirement(version, MIN_CURSOR_VERSION)
+                {
+                    return Err(format!(
+                        "Cursor version {}.{} detected, but minimum version {}.{} is required",
+                        version.0, version.1, MIN_CURSOR_VERSION.0, MIN_CURSOR_VERSION.1
+                    ));
                 }
                 // If we can't parse, continue anyway (be permissive)
             }
@@ -445,13 +525,13 @@ fn check_vscode() -> Result<bool, String> {
     if has_binary {
         match get_binary_version("code") {
             Ok(version_str) => {
-                if let Some(version) = parse_version(&version_str) {
-                    if !version_meets_requirement(version, MIN_CODE_VERSION) {
-                        return Err(format!(
-                            "VS Code version {}.{} detected, but minimum version {}.{} is required",
-                            version.0, version.1, MIN_CODE_VERSION.0, MIN_CODE_VERSION.1
-                        ));
-                    }
+                if let Some(version) = parse_version(&version_str)
+                    && !version_meets_requirement(version, MIN_CODE_VERSION)
+                {
+                    return Err(format!(
+                        "VS Code version {}.{} detected, but minimum version {}.{} is required",
+                        version.0, version.1, MIN_CODE_VERSION.0, MIN_CODE_VERSION.1
+                    ));
                 }
                 // If we can't parse, continue anyway (be permissive)
             }
@@ -623,11 +703,11 @@ fn install_claude_code_hooks(dry_run: bool) -> Result<Option<String>, GitAiError
         // Find existing matcher block for Write|Edit|MultiEdit
         let mut found_matcher_idx: Option<usize> = None;
         for (idx, item) in hook_type_array.iter().enumerate() {
-            if let Some(matcher) = item.get("matcher").and_then(|m| m.as_str()) {
-                if matcher == desired_matcher {
-                    found_matcher_idx = Some(idx);
-                    break;
-                }
+            if let Some(matcher) = item.get("matcher").and_then(|m| m.as_str())
+                && matcher == desired_matcher
+            {
+                found_matcher_idx = Some(idx);
+                break;
             }
         }
 
@@ -658,15 +738,14 @@ fn install_claude_code_hooks(dry_run: bool) -> Result<Option<String>, GitAiError
         let mut needs_update = false;
 
         for (idx, hook) in hooks_array.iter().enumerate() {
-            if let Some(cmd) = hook.get("command").and_then(|c| c.as_str()) {
-                if is_git_ai_checkpoint_command(cmd) {
-                    if found_idx.is_none() {
-                        found_idx = Some(idx);
-                        // Check if it matches exactly what we want
-                        if cmd != desired_cmd {
-                            needs_update = true;
-                        }
-                    }
+            if let Some(cmd) = hook.get("command").and_then(|c| c.as_str())
+                && is_git_ai_checkpoint_command(cmd)
+                && found_idx.is_none()
+            {
+                found_idx = Some(idx);
+                // Check if it matches exactly what we want
+                if cmd != desired_cmd {
+                    needs_update = true;
                 }
             }
         }
@@ -832,11 +911,11 @@ fn install_gemini_hooks(dry_run: bool) -> Result<Option<String>, GitAiError> {
         // Find existing matcher block for write_file|replace
         let mut found_matcher_idx: Option<usize> = None;
         for (idx, item) in hook_type_array.iter().enumerate() {
-            if let Some(matcher) = item.get("matcher").and_then(|m| m.as_str()) {
-                if matcher == desired_matcher {
-                    found_matcher_idx = Some(idx);
-                    break;
-                }
+            if let Some(matcher) = item.get("matcher").and_then(|m| m.as_str())
+                && matcher == desired_matcher
+            {
+                found_matcher_idx = Some(idx);
+                break;
             }
         }
 
@@ -867,15 +946,14 @@ fn install_gemini_hooks(dry_run: bool) -> Result<Option<String>, GitAiError> {
         let mut needs_update = false;
 
         for (idx, hook) in hooks_array.iter().enumerate() {
-            if let Some(cmd) = hook.get("command").and_then(|c| c.as_str()) {
-                if is_git_ai_checkpoint_command(cmd) {
-                    if found_idx.is_none() {
-                        found_idx = Some(idx);
-                        // Check if it matches exactly what we want
-                        if cmd != desired_cmd {
-                            needs_update = true;
-                        }
-                    }
+            if let Some(cmd) = hook.get("command").and_then(|c| c.as_str())
+                && is_git_ai_checkpoint_command(cmd)
+                && found_idx.is_none()
+            {
+                found_idx = Some(idx);
+                // Check if it matches exactly what we want
+                if cmd != desired_cmd {
+                    needs_update = true;
                 }
             }
         }
@@ -1010,10 +1088,10 @@ fn install_cursor_hooks(binary_path: &Path, dry_run: bool) -> Result<Option<Stri
     let mut merged = existing.clone();
 
     // Ensure version is set
-    if merged.get("version").is_none() {
-        if let Some(obj) = merged.as_object_mut() {
-            obj.insert("version".to_string(), json!(1));
-        }
+    if merged.get("version").is_none()
+        && let Some(obj) = merged.as_object_mut()
+    {
+        obj.insert("version".to_string(), json!(1));
     }
 
     // Merge hooks object
@@ -1378,10 +1456,10 @@ fn update_git_path_setting(
     }
 
     if !dry_run {
-        if let Some(parent) = settings_path.parent() {
-            if !parent.exists() {
-                fs::create_dir_all(parent)?;
-            }
+        if let Some(parent) = settings_path.parent()
+            && !parent.exists()
+        {
+            fs::create_dir_all(parent)?;
         }
         write_atomic(settings_path, new_content.as_bytes())?;
     }
@@ -1502,27 +1580,27 @@ impl Spinner {
     fn success(&self, message: &'static str) {
         // Clear spinner and show success with green checkmark and bold green text
         self.pb.finish_and_clear();
-        println!("\x1b[1;32m✓ {}\x1b[0m", message);
+        println!("{}", paint("\x1b[1;32m", &format!("✓ {}", message)));
     }
 
     fn pending(&self, message: &'static str) {
         // Clear spinner and show pending with yellow warning triangle and bold yellow text
         self.pb.finish_and_clear();
-        println!("\x1b[1;33m⚠ {}\x1b[0m", message);
+        println!("{}", paint("\x1b[1;33m", &format!("⚠ {}", message)));
     }
 
     #[allow(dead_code)]
     fn error(&self, message: &'static str) {
         // Clear spinner and show error with red X and bold red text
         self.pb.finish_and_clear();
-        println!("\x1b[1;31m✗ {}\x1b[0m", message);
+        println!("{}", paint("\x1b[1;31m", &format!("✗ {}", message)));
     }
 
     #[allow(dead_code)]
     fn skipped(&self, message: &'static str) {
         // Clear spinner and show skipped with gray circle and gray text
         self.pb.finish_and_clear();
-        println!("\x1b[90m○ {}\x1b[0m", message);
+        println!("{}", paint("\x1b[90m", &format!("○ {}", message)));
     }
 }
 
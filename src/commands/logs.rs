@@ -0,0 +1,134 @@
+use crate::error::{GitAiError, exit_with_error};
+use crate::observability::local_log::{self, LOG_FILE_NAME};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::time::Duration;
+
+const DEFAULT_TAIL_LINES: usize = 50;
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Handle the `logs` command
+///
+/// Usage: git-ai logs tail [-n <lines>] [-f|--follow]
+pub fn handle_logs(args: &[String]) {
+    match args.first().map(String::as_str) {
+        Some("tail") => {}
+        _ => {
+            exit_with_error(
+                "Error",
+                &GitAiError::InvalidArguments(
+                    "Usage: git-ai logs tail [-n <lines>] [-f|--follow]".to_string(),
+                ),
+            );
+        }
+    }
+
+    let parsed = match parse_tail_args(&args[1..]) {
+        Ok(parsed) => parsed,
+        Err(e) => exit_with_error("Error", &GitAiError::InvalidArguments(e)),
+    };
+
+    let Some(dir) = local_log::log_dir() else {
+        exit_with_error(
+            "Error",
+            &GitAiError::Generic("Could not determine the log directory".to_string()),
+        );
+    };
+
+    for line in local_log::tail_lines(&dir, parsed.lines) {
+        println!("{}", line);
+    }
+
+    if parsed.follow
+        && let Err(e) = follow(&dir.join(LOG_FILE_NAME))
+    {
+        exit_with_error("Failed to follow log", &e);
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct TailArgs {
+    pub lines: usize,
+    pub follow: bool,
+}
+
+pub fn parse_tail_args(args: &[String]) -> Result<TailArgs, String> {
+    let mut parsed = TailArgs {
+        lines: DEFAULT_TAIL_LINES,
+        follow: false,
+    };
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-n" | "--lines" => {
+                let value = args
+                    .get(i + 1)
+                    .ok_or_else(|| format!("{} requires a value", args[i]))?;
+                parsed.lines = value
+                    .parse()
+                    .map_err(|_| format!("Invalid line count: {}", value))?;
+                i += 1;
+            }
+            "-f" | "--follow" => parsed.follow = true,
+            other => return Err(format!("Unknown option: {}", other)),
+        }
+        i += 1;
+    }
+
+    Ok(parsed)
+}
+
+/// Print lines appended to `path` until interrupted. Starts again from the top
+/// when the file shrinks, which is what rotation looks like from here.
+fn follow(path: &std::path::Path) -> Result<(), GitAiError> {
+    let mut position = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    loop {
+        std::thread::sleep(FOLLOW_POLL_INTERVAL);
+        let Ok(mut file) = File::open(path) else {
+            continue;
+        };
+        let len = file.metadata()?.len();
+        if len < position {
+            position = 0;
+        }
+        if len == position {
+            continue;
+        }
+        file.seek(SeekFrom::Start(position))?;
+        let mut appended = String::new();
+        file.read_to_string(&mut appended)?;
+        position = len;
+        print!("{}", appended);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_tail_args() {
+        assert_eq!(
+            parse_tail_args(&[]).unwrap(),
+            TailArgs {
+                lines: DEFAULT_TAIL_LINES,
+                follow: false
+            }
+        );
+        assert_eq!(
+            parse_tail_args(&args(&["-n", "10", "-f"])).unwrap(),
+            TailArgs {
+                lines: 10,
+                follow: true
+            }
+        );
+        assert!(parse_tail_args(&args(&["-n"])).is_err());
+        assert!(parse_tail_args(&args(&["-n", "ten"])).is_err());
+        assert!(parse_tail_args(&args(&["--bogus"])).is_err());
+    }
+}
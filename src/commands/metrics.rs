@@ -0,0 +1,420 @@
+use crate::error::{GitAiError, exit_with_error};
+use crate::git::find_repository;
+use crate::git::refs::get_authorship;
+use crate::git::repository::{Repository, exec_git};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+
+const DEFAULT_WINDOW_DAYS: i64 = 90;
+
+/// Commits with at least this share of AI-authored added lines count as AI-heavy
+const AI_HEAVY_PERCENTAGE: f64 = 50.0;
+
+/// Handle the `metrics` command
+///
+/// Usage: git-ai metrics export --format json [--days <n>] [<rev>]
+pub fn handle_metrics(args: &[String]) {
+    if args.is_empty() {
+        print_metrics_help_and_exit();
+    }
+
+    match args[0].as_str() {
+        "export" => handle_metrics_export(&args[1..]),
+        other => {
+            eprintln!("Unknown metrics subcommand: {}", other);
+            print_metrics_help_and_exit();
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ExportArgs {
+    pub days: i64,
+    pub rev: String,
+}
+
+pub fn parse_export_args(args: &[String]) -> Result<ExportArgs, String> {
+    let mut parsed = ExportArgs {
+        days: DEFAULT_WINDOW_DAYS,
+        rev: "HEAD".to_string(),
+    };
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--format" => {
+                if i + 1 >= args.len() {
+                    return Err("--format requires a value".to_string());
+                }
+                i += 1;
+                if args[i] != "json" {
+                    return Err(format!("Unsupported format: {} (expected json)", args[i]));
+                }
+            }
+            "--days" => {
+                if i + 1 >= args.len() {
+                    return Err("--days requires a value".to_string());
+                }
+                i += 1;
+                parsed.days = args[i]
+                    .parse::<i64>()
+                    .ok()
+                    .filter(|days| *days > 0)
+                    .ok_or_else(|| format!("Invalid --days value: {}", args[i]))?;
+            }
+            other if other.starts_with('-') => {
+                return Err(format!("Unknown option: {}", other));
+            }
+            rev => parsed.rev = rev.to_string(),
+        }
+        i += 1;
+    }
+
+    Ok(parsed)
+}
+
+/// One non-merge commit in the export window
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommitSample {
+    pub sha: String,
+    pub author_time: i64,
+    pub commit_time: i64,
+    pub added_lines: u32,
+    pub ai_lines: u32,
+    /// SHA named by a "This reverts commit <sha>" line, if this is a revert
+    pub reverts: Option<String>,
+}
+
+impl CommitSample {
+    fn is_ai_heavy(&self) -> bool {
+        self.added_lines > 0
+            && self.ai_lines as f64 / self.added_lines as f64 * 100.0 >= AI_HEAVY_PERCENTAGE
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct WeeklyLines {
+    /// Monday (UTC) that starts the week
+    pub week_start: String,
+    pub ai_lines: u32,
+    pub added_lines: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct RevertStats {
+    pub commits: usize,
+    pub reverted: usize,
+    pub rate: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct LeadTimeStats {
+    pub commits: usize,
+    pub median_seconds: Option<i64>,
+    pub p90_seconds: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CohortMetrics<T> {
+    pub ai_heavy: T,
+    pub human: T,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct MetricsExport {
+    pub since: String,
+    pub until: String,
+    pub window_days: i64,
+    pub ai_heavy_threshold_percentage: f64,
+    pub commits: usize,
+    pub ai_lines: u32,
+    pub added_lines: u32,
+    pub ai_lines_per_week: Vec<WeeklyLines>,
+    pub revert_rate: CohortMetrics<RevertStats>,
+    /// Author date to commit date, i.e. how long a change took to land on the branch
+    pub lead_time: CohortMetrics<LeadTimeStats>,
+}
+
+fn handle_metrics_export(args: &[String]) {
+    let parsed = match parse_export_args(args) {
+        Ok(p) => p,
+        Err(e) => {
+            exit_with_error("Error", &GitAiError::InvalidArguments(e));
+        }
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            exit_with_error("Failed to find repository", &e);
+        }
+    };
+
+    let until = Utc::now();
+    let since = until - Duration::days(parsed.days);
+    let export = collect_commit_samples(&repo, &parsed.rev, since)
+        .map(|samples| compute_metrics(&samples, since, until, parsed.days));
+
+    match export {
+        Ok(export) => match serde_json::to_string_pretty(&export) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                exit_with_error("Failed to serialize metrics", &e.into());
+            }
+        },
+        Err(e) => {
+            exit_with_error("Error", &e);
+        }
+    }
+}
+
+/// Read every non-merge commit reachable from `rev` committed after `since`,
+/// with added line counts from git and AI line counts from authorship notes.
+pub fn collect_commit_samples(
+    repo: &Repository,
+    rev: &str,
+    since: DateTime<Utc>,
+) -> Result<Vec<CommitSample>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("log".to_string());
+    args.push("--no-merges".to_string());
+    args.push(format!("--since={}", since.timestamp()));
+    args.push("--format=%x1e%H%x1f%at%x1f%ct%x1f%B%x1f".to_string());
+    args.push("--numstat".to_string());
+    args.push(rev.to_string());
+    args.push("--".to_string());
+
+    let output = exec_git(&args)?;
+    let stdout = String::from_utf8(output.stdout)
+        .map_err(|_| GitAiError::Generic("Failed to parse git log output".to_string()))?;
+
+    let mut samples = Vec::new();
+    for record in stdout.split('\x1e').filter(|r| !r.trim().is_empty()) {
+        let fields: Vec<&str> = record.splitn(5, '\x1f').collect();
+        if fields.len() < 5 {
+            continue;
+        }
+        let sha = fields[0].trim().to_string();
+        let added_lines = fields[4]
+            .lines()
+            .filter_map(|line| line.split('\t').next()?.parse::<u32>().ok())
+            .sum();
+        // Accepted lines per prompt are what landed in this commit
+        let ai_lines = get_authorship(repo, &sha)
+            .map(|log| {
+                log.metadata
+                    .prompts
+                    .values()
+                    .map(|p| p.accepted_lines)
+                    .sum()
+            })
+            .unwrap_or(0);
+
+        samples.push(CommitSample {
+            author_time: fields[1].trim().parse().unwrap_or(0),
+            commit_time: fields[2].trim().parse().unwrap_or(0),
+            added_lines,
+            ai_lines,
+            reverts: parse_reverted_sha(fields[3]),
+            sha,
+        });
+    }
+
+    Ok(samples)
+}
+
+fn parse_reverted_sha(message: &str) -> Option<String> {
+    message.lines().find_map(|line| {
+        let rest = line.trim().strip_prefix("This reverts commit ")?;
+        let sha: String = rest.chars().take_while(|c| c.is_ascii_hexdigit()).collect();
+        (!sha.is_empty()).then_some(sha)
+    })
+}
+
+fn week_start(timestamp: i64) -> NaiveDate {
+    let date = DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .date_naive();
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+fn percentile(sorted: &[i64], pct: usize) -> Option<i64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let idx = (sorted.len() * pct).div_ceil(100).saturating_sub(1);
+    Some(sorted[idx.min(sorted.len() - 1)])
+}
+
+fn lead_time_stats(samples: &[&CommitSample]) -> LeadTimeStats {
+    let mut lead_times: Vec<i64> = samples
+        .iter()
+        .map(|s| (s.commit_time - s.author_time).max(0))
+        .collect();
+    lead_times.sort_unstable();
+    LeadTimeStats {
+        commits: lead_times.len(),
+        median_seconds: percentile(&lead_times, 50),
+        p90_seconds: percentile(&lead_times, 90),
+    }
+}
+
+fn revert_stats(samples: &[&CommitSample], reverted: &HashSet<&str>) -> RevertStats {
+    let reverted_count = samples
+        .iter()
+        .filter(|s| reverted.iter().any(|r| s.sha.starts_with(r)))
+        .count();
+    RevertStats {
+        commits: samples.len(),
+        reverted: reverted_count,
+        rate: if samples.is_empty() {
+            0.0
+        } else {
+            reverted_count as f64 / samples.len() as f64
+        },
+    }
+}
+
+pub fn compute_metrics(
+    samples: &[CommitSample],
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    window_days: i64,
+) -> MetricsExport {
+    let mut weeks: BTreeMap<NaiveDate, WeeklyLines> = BTreeMap::new();
+    for sample in samples {
+        let week = week_start(sample.commit_time);
+        let entry = weeks.entry(week).or_insert_with(|| WeeklyLines {
+            week_start: week.to_string(),
+            ai_lines: 0,
+            added_lines: 0,
+        });
+        entry.ai_lines += sample.ai_lines;
+        entry.added_lines += sample.added_lines;
+    }
+
+    // Revert commits are excluded from the cohorts so they don't skew lead times
+    let reverted: HashSet<&str> = samples
+        .iter()
+        .filter_map(|s| s.reverts.as_deref())
+        .collect();
+    let (ai_heavy, human): (Vec<&CommitSample>, Vec<&CommitSample>) = samples
+        .iter()
+        .filter(|s| s.reverts.is_none())
+        .partition(|s| s.is_ai_heavy());
+
+    MetricsExport {
+        since: since.to_rfc3339(),
+        until: until.to_rfc3339(),
+        window_days,
+        ai_heavy_threshold_percentage: AI_HEAVY_PERCENTAGE,
+        commits: samples.len(),
+        ai_lines: samples.iter().map(|s| s.ai_lines).sum(),
+        added_lines: samples.iter().map(|s| s.added_lines).sum(),
+        ai_lines_per_week: weeks.into_values().collect(),
+        revert_rate: CohortMetrics {
+            ai_heavy: revert_stats(&ai_heavy, &reverted),
+            human: revert_stats(&human, &reverted),
+        },
+        lead_time: CohortMetrics {
+            ai_heavy: lead_time_stats(&ai_heavy),
+            human: lead_time_stats(&human),
+        },
+    }
+}
+
+fn print_metrics_help_and_exit() -> ! {
+    eprintln!("git-ai metrics - Export AI-adjusted delivery metrics");
+    eprintln!();
+    eprintln!("Usage: git-ai metrics export --format json [--days <n>] [<rev>]");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  --format json        Output format (only json is supported)");
+    eprintln!(
+        "  --days <n>           Window size in days (default: {})",
+        DEFAULT_WINDOW_DAYS
+    );
+    eprintln!("  <rev>                Branch or commit to walk back from (default: HEAD)");
+    std::process::exit(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(sha: &str, commit_time: i64, added: u32, ai: u32) -> CommitSample {
+        CommitSample {
+            sha: sha.to_string(),
+            author_time: commit_time - 3600,
+            commit_time,
+            added_lines: added,
+            ai_lines: ai,
+            reverts: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_export_args() {
+        let args: Vec<String> = ["--format", "json", "--days", "30", "main"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            parse_export_args(&args).unwrap(),
+            ExportArgs {
+                days: 30,
+                rev: "main".to_string()
+            }
+        );
+        assert!(parse_export_args(&["--format".to_string(), "csv".to_string()]).is_err());
+        assert!(parse_export_args(&["--days".to_string(), "0".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_parse_reverted_sha() {
+        let message = "Revert \"Add thing\"\n\nThis reverts commit abc123def.\n";
+        assert_eq!(parse_reverted_sha(message).as_deref(), Some("abc123def"));
+        assert!(parse_reverted_sha("Add thing").is_none());
+    }
+
+    #[test]
+    fn test_compute_metrics() {
+        // 2024-01-01 was a Monday
+        let monday = 1_704_067_200;
+        let mut revert = sample("ccc", monday + 8 * 86_400, 0, 0);
+        revert.reverts = Some("aaa".to_string());
+        let samples = vec![
+            sample("aaa", monday + 3600, 10, 8),
+            sample("bbb", monday + 2 * 86_400, 10, 0),
+            revert,
+        ];
+
+        let until = DateTime::from_timestamp(monday + 9 * 86_400, 0).unwrap();
+        let since = until - Duration::days(30);
+        let export = compute_metrics(&samples, since, until, 30);
+
+        assert_eq!(export.commits, 3);
+        assert_eq!(export.ai_lines, 8);
+        assert_eq!(
+            export.ai_lines_per_week,
+            vec![
+                WeeklyLines {
+                    week_start: "2024-01-01".to_string(),
+                    ai_lines: 8,
+                    added_lines: 20
+                },
+                WeeklyLines {
+                    week_start: "2024-01-08".to_string(),
+                    ai_lines: 0,
+                    added_lines: 0
+                },
+            ]
+        );
+        assert_eq!(export.revert_rate.ai_heavy.reverted, 1);
+        assert_eq!(export.revert_rate.ai_heavy.rate, 1.0);
+        assert_eq!(export.revert_rate.human.commits, 1);
+        assert_eq!(export.revert_rate.human.reverted, 0);
+        assert_eq!(export.lead_time.ai_heavy.median_seconds, Some(3600));
+    }
+}
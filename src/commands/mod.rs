@@ -1,15 +1,54 @@
+pub mod archive;
+pub mod auto_checkpoint;
 pub mod blame;
+pub mod blame_colors;
+pub mod blame_reverse;
 pub mod checkpoint;
 pub mod checkpoint_agent;
+pub mod checkpoint_list;
+pub mod checkpoint_validate;
 pub mod ci_handlers;
+pub mod codeowners;
 pub mod diff;
+pub mod doctor;
+pub mod explain;
+pub mod export;
 pub mod flush_logs;
 pub mod git_ai_handlers;
 pub mod git_handlers;
+pub mod git_hooks;
 pub mod hooks;
+pub mod ide_host;
+pub mod import;
 pub mod install_hooks;
+pub mod logs;
+pub mod metrics;
+pub mod multi;
+pub mod output;
+pub mod perf;
+pub mod prompt_lines;
+pub mod prompts;
+pub mod renderers;
+pub mod repo_prompt;
+pub mod resolve_attribution;
+pub mod restack;
+pub mod proxy;
+pub mod review;
+pub mod revert_suggest;
+pub mod rewrite_trailers;
+pub mod safe_mode;
+pub mod serve;
+pub mod sessions;
 pub mod show;
 pub mod show_prompt;
 pub mod squash_authorship;
+pub mod sync;
+pub mod sync_daemon;
+pub mod telemetry;
 pub mod upgrade;
+pub mod verify_remote;
+pub mod watch;
+#[cfg(windows)]
+pub mod windows_child;
+pub mod working_log;
 pub mod working_stats;
@@ -0,0 +1,580 @@
+use crate::authorship::stats::{CommitStats, write_stats_to_terminal};
+use crate::commands::output;
+use crate::error::{GitAiError, exit_with_error};
+use crate::utils::current_git_ai_exe;
+use serde_json::{Map, Value, json};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Upper bound on the default number of repositories processed at once
+const MAX_DEFAULT_JOBS: usize = 8;
+
+/// Directory levels below the current one `--all-repos` searches
+const MAX_DISCOVERY_DEPTH: usize = 4;
+
+/// Folders `--all-repos` doesn't look for repositories in
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "vendor", "dist", "build"];
+
+static ALL_REPOS: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, PartialEq)]
+pub struct MultiArgs {
+    pub repos_from: String,
+    pub jobs: usize,
+    pub command: Vec<String>,
+}
+
+/// Outcome of running the subcommand in one repository
+#[derive(Debug)]
+struct RepoResult {
+    path: PathBuf,
+    success: bool,
+    exit_code: Option<i32>,
+    stdout: String,
+    stderr: String,
+}
+
+/// Handle the `multi` command
+///
+/// Usage: git-ai multi --repos-from <file|-> [--jobs <n>] <subcommand> [args...]
+pub fn handle_multi(args: &[String]) {
+    let parsed = match parse_multi_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => exit_with_error("Error", &GitAiError::InvalidArguments(e)),
+    };
+    let repos = match read_repo_list(&parsed.repos_from) {
+        Ok(repos) if repos.is_empty() => {
+            exit_with_error(
+                "Error",
+                &GitAiError::InvalidArguments(format!(
+                    "No repositories listed in {}",
+                    parsed.repos_from
+                )),
+            );
+        }
+        Ok(repos) => repos,
+        Err(e) => exit_with_error("Failed to read repository list", &e),
+    };
+    run_multi(&repos, &parsed);
+}
+
+/// Set by the `--all-repos` global flag
+pub fn set_all_repos(enabled: bool) {
+    ALL_REPOS.store(enabled, Ordering::Relaxed);
+}
+
+pub fn all_repos() -> bool {
+    ALL_REPOS.load(Ordering::Relaxed)
+}
+
+/// Handle `git-ai --all-repos <subcommand> [args...]`
+///
+/// Runs the subcommand in every repository found under the current directory,
+/// such as the folders of a multi-root workspace, the same way `multi` would.
+pub fn handle_all_repos(command: &[String]) {
+    if command.is_empty() {
+        exit_with_error(
+            "Error",
+            &GitAiError::InvalidArguments(
+                "Usage: git-ai --all-repos <subcommand> [args...]".to_string(),
+            ),
+        );
+    }
+    if command[0] == "multi" {
+        exit_with_error(
+            "Error",
+            &GitAiError::InvalidArguments("--all-repos can't be combined with multi".to_string()),
+        );
+    }
+    let root = match std::env::current_dir() {
+        Ok(root) => root,
+        Err(e) => exit_with_error(
+            "Failed to read the current directory",
+            &GitAiError::IoError(e),
+        ),
+    };
+    let repos = discover_repos(&root);
+    if repos.is_empty() {
+        exit_with_error(
+            "Error",
+            &GitAiError::InvalidArguments(format!(
+                "No repositories found under {}",
+                root.display()
+            )),
+        );
+    }
+    let parsed = MultiArgs {
+        repos_from: root.display().to_string(),
+        jobs: default_jobs(),
+        command: command.to_vec(),
+    };
+    run_multi(&repos, &parsed);
+}
+
+/// Repositories at or below `root`, in path order. Hidden directories and
+/// dependency or build output folders aren't searched, and the walk stops
+/// `MAX_DISCOVERY_DEPTH` levels down.
+pub fn discover_repos(root: &Path) -> Vec<PathBuf> {
+    let mut repos = Vec::new();
+    discover_repos_in(root, 0, &mut repos);
+    repos.sort();
+    repos
+}
+
+fn discover_repos_in(dir: &Path, depth: usize, repos: &mut Vec<PathBuf>) {
+    // A `.git` file marks a worktree or submodule checkout
+    if dir.join(".git").exists() {
+        repos.push(dir.to_path_buf());
+    }
+    if depth >= MAX_DISCOVERY_DEPTH {
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        // Symlinks aren't followed, so a link back up the tree can't loop
+        if !entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+            continue;
+        }
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref()) {
+            continue;
+        }
+        discover_repos_in(&entry.path(), depth + 1, repos);
+    }
+}
+
+fn run_multi(repos: &[PathBuf], parsed: &MultiArgs) {
+    let exe = match current_git_ai_exe() {
+        Ok(exe) => exe,
+        Err(e) => exit_with_error("Failed to locate git-ai", &e),
+    };
+
+    let json = output::structured_requested(false);
+    let is_stats = parsed.command[0] == "stats" && !parsed.command.contains(&"--working".into());
+    // Stats are summed across repositories, which needs the JSON form
+    let child_json = json || is_stats;
+    let mut results = run_all(&exe, repos, parsed, child_json);
+    if is_stats {
+        reject_unreadable_stats(&mut results);
+    }
+
+    let printed = if json {
+        output::print_structured("multi", &json_report(&results))
+    } else if is_stats {
+        print_stats_report(&results)
+    } else {
+        print_text_report(&results);
+        Ok(())
+    };
+    if let Err(e) = printed {
+        exit_with_error("Failed to write the report", &e);
+    }
+
+    if results.iter().any(|result| !result.success) {
+        std::process::exit(1);
+    }
+}
+
+pub fn parse_multi_args(args: &[String]) -> Result<MultiArgs, String> {
+    let mut repos_from = None;
+    let mut jobs = None;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--repos-from" => {
+                repos_from = Some(
+                    args.get(i + 1)
+                        .ok_or("--repos-from requires a file (or - for stdin)")?
+                        .clone(),
+                );
+                i += 2;
+            }
+            "--jobs" | "-j" => {
+                let n = args
+                    .get(i + 1)
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .filter(|n| *n > 0)
+                    .ok_or("--jobs requires a positive number")?;
+                jobs = Some(n);
+                i += 2;
+            }
+            other if other.starts_with('-') => {
+                return Err(format!("Unknown option: {}", other));
+            }
+            // Everything from the first positional argument on is the subcommand
+            _ => break,
+        }
+    }
+
+    let command = args[i..].to_vec();
+    if command.is_empty() {
+        return Err("Missing subcommand to run in each repository".to_string());
+    }
+    if command[0] == "multi" {
+        return Err("multi can't run itself".to_string());
+    }
+    Ok(MultiArgs {
+        repos_from: repos_from.ok_or("--repos-from <file> is required")?,
+        jobs: jobs.unwrap_or_else(default_jobs),
+        command,
+    })
+}
+
+fn default_jobs() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(MAX_DEFAULT_JOBS)
+}
+
+/// One path per line; blank lines and `#` comments are skipped. Relative
+/// paths are taken relative to the list file.
+pub fn read_repo_list(source: &str) -> Result<Vec<PathBuf>, GitAiError> {
+    let (contents, base) = if source == "-" {
+        let mut contents = String::new();
+        std::io::stdin().read_to_string(&mut contents)?;
+        (contents, std::env::current_dir()?)
+    } else {
+        let path = Path::new(source);
+        let base = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .map(Path::to_path_buf)
+            .unwrap_or(std::env::current_dir()?);
+        (std::fs::read_to_string(path)?, base)
+    };
+    Ok(parse_repo_list(&contents, &base))
+}
+
+fn parse_repo_list(contents: &str, base: &Path) -> Vec<PathBuf> {
+    let mut repos: Vec<PathBuf> = Vec::new();
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let path = base.join(line);
+        if !repos.contains(&path) {
+            repos.push(path);
+        }
+    }
+    repos
+}
+
+fn run_all(exe: &Path, repos: &[PathBuf], args: &MultiArgs, child_json: bool) -> Vec<RepoResult> {
+    let next = AtomicUsize::new(0);
+    let results: Vec<Mutex<Option<RepoResult>>> = repos.iter().map(|_| Mutex::new(None)).collect();
+
+    std::thread::scope(|scope| {
+        for _ in 0..args.jobs.min(repos.len()) {
+            scope.spawn(|| {
+                loop {
+                    let index = next.fetch_add(1, Ordering::SeqCst);
+                    let Some(repo) = repos.get(index) else {
+                        break;
+                    };
+                    let result = run_in_repo(exe, repo, &args.command, child_json);
+                    *results[index].lock().unwrap() = Some(result);
+                }
+            });
+        }
+    });
+
+    results
+        .into_iter()
+        .filter_map(|slot| slot.into_inner().ok().flatten())
+        .collect()
+}
+
+fn run_in_repo(exe: &Path, repo: &Path, command: &[String], json: bool) -> RepoResult {
+    let mut cmd = Command::new(exe);
+    if json {
+        cmd.args(["--format", "json"]);
+    }
+    if output::anonymize() {
+        cmd.arg("--anonymize");
+    }
+    cmd.args(command).current_dir(repo);
+
+    match cmd.output() {
+        Ok(output) => RepoResult {
+            path: repo.to_path_buf(),
+            success: output.status.success(),
+            exit_code: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        },
+        Err(e) => RepoResult {
+            path: repo.to_path_buf(),
+            success: false,
+            exit_code: None,
+            stdout: String::new(),
+            stderr: format!("Failed to run git-ai in {}: {}", repo.display(), e),
+        },
+    }
+}
+
+/// Add every number in `value` to the matching entry of `total`, recursing
+/// into objects. Non-numeric fields are left alone.
+fn sum_numbers(total: &mut Map<String, Value>, value: &Map<String, Value>) {
+    for (key, value) in value {
+        match value {
+            Value::Number(n) => {
+                let current = total.get(key).and_then(Value::as_f64).unwrap_or(0.0);
+                let sum = current + n.as_f64().unwrap_or(0.0);
+                let sum = if n.is_f64() {
+                    json!(sum)
+                } else {
+                    json!(sum as u64)
+                };
+                total.insert(key.clone(), sum);
+            }
+            Value::Object(inner) => {
+                let entry = total
+                    .entry(key.clone())
+                    .or_insert_with(|| Value::Object(Map::new()));
+                if let Value::Object(entry) = entry {
+                    sum_numbers(entry, inner);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A stats run that exited cleanly but printed something that isn't a stats
+/// report counts as a failure, rather than silently dropping out of the totals
+fn reject_unreadable_stats(results: &mut [RepoResult]) {
+    for result in results.iter_mut().filter(|result| result.success) {
+        if let Err(e) = serde_json::from_str::<CommitStats>(&result.stdout) {
+            result.success = false;
+            result.stderr = format!("Unreadable stats output: {}", e);
+        }
+    }
+}
+
+/// Stats fields keyed by file path, which are only unique within a repository
+const PER_FILE_FIELDS: &[&str] = &["file_breakdown", "whole_file_breakdown"];
+
+/// Sum of the numeric fields of every repository that printed a JSON object.
+/// Per-file breakdowns are keyed by `<repo>/<file>` instead of being summed.
+fn totals(results: &[RepoResult]) -> Map<String, Value> {
+    let mut totals = Map::new();
+    for result in results.iter().filter(|result| result.success) {
+        let Ok(Value::Object(mut output)) = serde_json::from_str::<Value>(&result.stdout) else {
+            continue;
+        };
+        for field in PER_FILE_FIELDS {
+            let Some(Value::Object(files)) = output.remove(*field) else {
+                continue;
+            };
+            let entry = totals
+                .entry(field.to_string())
+                .or_insert_with(|| Value::Object(Map::new()));
+            if let Value::Object(entry) = entry {
+                for (file, value) in files {
+                    entry.insert(format!("{}/{}", result.path.display(), file), value);
+                }
+            }
+        }
+        sum_numbers(&mut totals, &output);
+    }
+    totals
+}
+
+fn json_report(results: &[RepoResult]) -> Value {
+    let repositories: Vec<Value> = results
+        .iter()
+        .map(|result| {
+            let output = serde_json::from_str::<Value>(&result.stdout)
+                .unwrap_or_else(|_| Value::String(result.stdout.clone()));
+            json!({
+                "path": result.path.display().to_string(),
+                "success": result.success,
+                "exit_code": result.exit_code,
+                "output": output,
+                "error": if result.success { Value::Null } else { json!(result.stderr.trim()) },
+            })
+        })
+        .collect();
+    let succeeded = results.iter().filter(|result| result.success).count();
+    json!({
+        "repositories": repositories,
+        "succeeded": succeeded,
+        "failed": results.len() - succeeded,
+        "totals": totals(results),
+    })
+}
+
+fn print_failures(results: &[RepoResult]) {
+    for result in results.iter().filter(|result| !result.success) {
+        eprintln!("{}: {}", result.path.display(), result.stderr.trim());
+    }
+}
+
+fn print_summary(results: &[RepoResult]) {
+    let failed = results.iter().filter(|result| !result.success).count();
+    println!(
+        "{} repositories, {} succeeded, {} failed",
+        results.len(),
+        results.len() - failed,
+        failed
+    );
+}
+
+fn print_text_report(results: &[RepoResult]) {
+    for result in results {
+        println!("== {} ==", result.path.display());
+        print!("{}", result.stdout);
+        if !result.success {
+            eprint!("{}", result.stderr);
+        }
+        println!();
+    }
+    print_summary(results);
+}
+
+fn print_stats_report(results: &[RepoResult]) -> Result<(), GitAiError> {
+    let width = results
+        .iter()
+        .map(|result| result.path.display().to_string().len())
+        .max()
+        .unwrap_or(0);
+    for result in results.iter().filter(|result| result.success) {
+        let Ok(stats) = serde_json::from_str::<CommitStats>(&result.stdout) else {
+            continue;
+        };
+        let added = stats.human_additions + stats.ai_additions;
+        let ai_share = if added == 0 {
+            0.0
+        } else {
+            stats.ai_additions as f64 * 100.0 / added as f64
+        };
+        println!(
+            "{:width$}  {:>6} AI  {:>6} human  {:>5.1}% AI",
+            result.path.display().to_string(),
+            stats.ai_additions,
+            stats.human_additions,
+            ai_share,
+            width = width
+        );
+    }
+    println!();
+
+    let total: CommitStats = serde_json::from_value(Value::Object(totals(results)))?;
+    write_stats_to_terminal(&total, true);
+    print_failures(results);
+    print_summary(results);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_multi_args() {
+        let parsed = parse_multi_args(&args(&[
+            "--repos-from",
+            "repos.txt",
+            "-j",
+            "4",
+            "stats",
+            "--json",
+        ]))
+        .unwrap();
+        assert_eq!(parsed.repos_from, "repos.txt");
+        assert_eq!(parsed.jobs, 4);
+        assert_eq!(parsed.command, args(&["stats", "--json"]));
+
+        assert!(parse_multi_args(&args(&["stats"])).is_err());
+        assert!(parse_multi_args(&args(&["--repos-from", "r.txt"])).is_err());
+        assert!(
+            parse_multi_args(&args(&["--repos-from", "r.txt", "--jobs", "0", "stats"])).is_err()
+        );
+        assert!(parse_multi_args(&args(&["--repos-from", "r.txt", "multi"])).is_err());
+    }
+
+    #[test]
+    fn test_repo_list_and_totals() {
+        let repos = parse_repo_list("# team repos\n a \n\n/abs/b\na\n", Path::new("/lists"));
+        assert_eq!(
+            repos,
+            vec![PathBuf::from("/lists/a"), PathBuf::from("/abs/b")]
+        );
+
+        let result = |stdout: &str, success: bool| RepoResult {
+            path: PathBuf::from(if success { "api" } else { "web" }),
+            success,
+            exit_code: Some(if success { 0 } else { 1 }),
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+        };
+        let results = vec![
+            result(
+                r#"{"ai_additions":3,"tool_model_breakdown":{"claude":{"ai_additions":3}}}"#,
+                true,
+            ),
+            result(
+                r#"{"ai_additions":4,"human_additions":1,"tool_model_breakdown":{"claude":{"ai_additions":4}},"file_breakdown":{"src/main.rs":{"ai_additions":4}}}"#,
+                true,
+            ),
+            result(r#"{"ai_additions":100}"#, false),
+        ];
+        let totals = totals(&results);
+        assert_eq!(totals["ai_additions"], 7);
+        assert_eq!(totals["human_additions"], 1);
+        assert_eq!(totals["tool_model_breakdown"]["claude"]["ai_additions"], 7);
+        assert_eq!(
+            totals["file_breakdown"]["api/src/main.rs"]["ai_additions"],
+            4
+        );
+
+        let stats: CommitStats = serde_json::from_value(Value::Object(totals)).unwrap();
+        assert_eq!(stats.ai_additions, 7);
+
+        let mut results = vec![result("{}", true), result("not json", true)];
+        reject_unreadable_stats(&mut results);
+        assert!(results[0].success);
+        assert!(!results[1].success);
+        assert!(results[1].stderr.starts_with("Unreadable stats output"));
+    }
+
+    #[test]
+    fn test_discover_repos() {
+        let root = tempfile::tempdir().unwrap();
+        let path = root.path();
+        for dir in [
+            "api/.git",
+            "web/.git",
+            "web/packages/ui/.git",
+            "web/node_modules/dep/.git",
+            ".cache/tool/.git",
+            "docs",
+        ] {
+            std::fs::create_dir_all(path.join(dir)).unwrap();
+        }
+        // Worktrees and submodules have a `.git` file
+        std::fs::create_dir_all(path.join("lib")).unwrap();
+        std::fs::write(path.join("lib/.git"), "gitdir: ../.git/modules/lib\n").unwrap();
+
+        assert_eq!(
+            discover_repos(path),
+            vec![
+                path.join("api"),
+                path.join("lib"),
+                path.join("web"),
+                path.join("web/packages/ui"),
+            ]
+        );
+    }
+}
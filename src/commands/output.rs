@@ -0,0 +1,221 @@
+//! Output format shared by the reporting commands (`show`, `stats`, `blame`,
+//! `diff`, `prompts`, `working-stats`).
+//!
+//! Set once from `--format json|text` or `--porcelain` (an alias for
+//! `--format json`), given either before the subcommand or among its
+//! arguments, except that `--porcelain` after `blame` selects git's porcelain
+//! format. The older per-command `--json` flags still work and mean the same.
+//! Any other `--format` name selects a renderer (see `commands::renderers`),
+//! which reports hand their JSON form to instead of printing it.
+//!
+//! Also holds the global `--quiet`, `--no-color` and `--anonymize` switches. Their
+//! environment forms (`GIT_AI_QUIET=1`, `NO_COLOR`, `GIT_AI_ANONYMIZE=1`) reach the git proxy and hook
+//! subprocesses, which never see git-ai's own flags.
+
+use crate::commands::renderers;
+use crate::error::GitAiError;
+use serde::Serialize;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    /// A built-in or configured renderer, by name
+    Renderer(String),
+}
+
+impl OutputFormat {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            name if renderers::is_registered(name) => Ok(OutputFormat::Renderer(name.to_string())),
+            other => Err(format!(
+                "Unknown output format '{}' (expected json, text, {} or a configured renderer)",
+                other,
+                renderers::BUILTIN_RENDERERS.join(", ")
+            )),
+        }
+    }
+}
+
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+static RENDERER: Mutex<Option<String>> = Mutex::new(None);
+
+pub fn set_format(format: OutputFormat) {
+    JSON_OUTPUT.store(format == OutputFormat::Json, Ordering::Relaxed);
+    let renderer = match format {
+        OutputFormat::Renderer(name) => Some(name),
+        _ => None,
+    };
+    *RENDERER.lock().unwrap() = renderer;
+}
+
+pub fn format() -> OutputFormat {
+    if JSON_OUTPUT.load(Ordering::Relaxed) {
+        OutputFormat::Json
+    } else if let Some(name) = renderer() {
+        OutputFormat::Renderer(name)
+    } else {
+        OutputFormat::Text
+    }
+}
+
+/// The renderer chosen with `--format <name>`, if any
+pub fn renderer() -> Option<String> {
+    RENDERER.lock().unwrap().clone()
+}
+
+/// Whether to emit JSON, given a command's own legacy `--json` flag
+pub fn json_requested(local_json: bool) -> bool {
+    local_json || format() == OutputFormat::Json
+}
+
+/// Whether a report should be built in its JSON form: for `--format json`
+/// or to hand it to a renderer
+pub fn structured_requested(local_json: bool) -> bool {
+    json_requested(local_json) || renderer().is_some()
+}
+
+/// Print a report as pretty JSON, or through the renderer chosen with
+/// `--format <name>`
+pub fn print_structured<T: Serialize + ?Sized>(report: &str, data: &T) -> Result<(), GitAiError> {
+    match renderer() {
+        Some(name) => renderers::print_report(&name, report, &serde_json::to_value(data)?),
+        None => {
+            println!("{}", serde_json::to_string_pretty(data)?);
+            Ok(())
+        }
+    }
+}
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+static NO_COLOR: AtomicBool = AtomicBool::new(false);
+static ANONYMIZE: AtomicBool = AtomicBool::new(false);
+
+/// Suppress informational messages such as timings and post-commit stats
+pub fn set_quiet(enabled: bool) {
+    QUIET.store(enabled, Ordering::Relaxed);
+}
+
+pub fn quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+        || std::env::var("GIT_AI_QUIET").is_ok_and(|v| v == "1" || v == "true")
+}
+
+pub fn set_no_color(enabled: bool) {
+    NO_COLOR.store(enabled, Ordering::Relaxed);
+}
+
+/// False after `--no-color` or when `NO_COLOR` is set to anything non-empty
+pub fn color_enabled() -> bool {
+    !NO_COLOR.load(Ordering::Relaxed) && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+}
+
+pub fn set_anonymize(enabled: bool) {
+    ANONYMIZE.store(enabled, Ordering::Relaxed);
+}
+
+/// Hash human identities and drop prompt text in reports (see `authorship::anonymize`)
+pub fn anonymize() -> bool {
+    ANONYMIZE.load(Ordering::Relaxed)
+        || std::env::var("GIT_AI_ANONYMIZE").is_ok_and(|v| v == "1" || v == "true")
+}
+
+/// An ANSI escape sequence, or "" when color is off
+pub fn sgr(code: &'static str) -> &'static str {
+    if color_enabled() { code } else { "" }
+}
+
+/// Wrap `text` in `code` and a reset, or return it unchanged when color is off
+pub fn paint(code: &str, text: &str) -> String {
+    if color_enabled() {
+        format!("{}{}\x1b[0m", code, text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// If `args[0]` is a format flag, return the format and how many args it used
+pub fn parse_format_flag(args: &[String]) -> Option<Result<(OutputFormat, usize), String>> {
+    let flag = args.first()?;
+    if flag == "--porcelain" {
+        return Some(Ok((OutputFormat::Json, 1)));
+    }
+    if let Some(value) = flag.strip_prefix("--format=") {
+        return Some(OutputFormat::parse(value).map(|format| (format, 1)));
+    }
+    if flag == "--format" {
+        return Some(match args.get(1) {
+            Some(value) => OutputFormat::parse(value).map(|format| (format, 2)),
+            None => Err("--format requires a value (json, text or a renderer)".to_string()),
+        });
+    }
+    None
+}
+
+/// Remove format flags from a subcommand's arguments, applying the last one
+/// seen. With `keep_porcelain`, `--porcelain` is left for the subcommand.
+pub fn take_format_flags(args: &[String], keep_porcelain: bool) -> Result<Vec<String>, String> {
+    let mut rest = Vec::with_capacity(args.len());
+    let mut i = 0;
+    while i < args.len() {
+        if keep_porcelain && args[i] == "--porcelain" {
+            rest.push(args[i].clone());
+            i += 1;
+            continue;
+        }
+        match parse_format_flag(&args[i..]) {
+            Some(Ok((format, used))) => {
+                set_format(format);
+                i += used;
+            }
+            Some(Err(e)) => return Err(e),
+            None => {
+                rest.push(args[i].clone());
+                i += 1;
+            }
+        }
+    }
+    Ok(rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_format_flag() {
+        assert_eq!(
+            parse_format_flag(&args(&["--format", "json", "HEAD"])),
+            Some(Ok((OutputFormat::Json, 2)))
+        );
+        assert_eq!(
+            parse_format_flag(&args(&["--format=text"])),
+            Some(Ok((OutputFormat::Text, 1)))
+        );
+        assert_eq!(
+            parse_format_flag(&args(&["--porcelain"])),
+            Some(Ok((OutputFormat::Json, 1)))
+        );
+        assert!(matches!(
+            parse_format_flag(&args(&["--format", "yaml"])),
+            Some(Err(_))
+        ));
+        assert!(matches!(
+            parse_format_flag(&args(&["--format"])),
+            Some(Err(_))
+        ));
+        assert_eq!(
+            parse_format_flag(&args(&["--format=markdown"])),
+            Some(Ok((OutputFormat::Renderer("markdown".to_string()), 1)))
+        );
+        assert_eq!(parse_format_flag(&args(&["HEAD"])), None);
+    }
+}
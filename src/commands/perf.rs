@@ -0,0 +1,71 @@
+use crate::commands::output;
+use crate::config::Config;
+use crate::error::{GitAiError, exit_with_error};
+use crate::observability::wrapper_performance_targets::{
+    perf_records_path, read_perf_records, summarize_perf_records,
+};
+
+/// Handle the `perf` command
+///
+/// Usage: git-ai perf report [--json]
+///
+/// Summarizes the timings `perf_records` appends for each proxied git command
+/// and checkpoint: p50/p95 of the total time and of git-ai's share of it.
+pub fn handle_perf(args: &[String]) {
+    match args.first().map(String::as_str) {
+        Some("report") => {}
+        _ => {
+            exit_with_error(
+                "Error",
+                &GitAiError::InvalidArguments("Usage: git-ai perf report [--json]".to_string()),
+            );
+        }
+    }
+    let mut json = false;
+    for arg in &args[1..] {
+        match arg.as_str() {
+            "--json" => json = true,
+            other => exit_with_error(
+                "Error",
+                &GitAiError::InvalidArguments(format!("Unknown perf report argument: {}", other)),
+            ),
+        }
+    }
+
+    let Some(path) = perf_records_path() else {
+        exit_with_error(
+            "Error",
+            &GitAiError::Generic("Could not determine the log directory".to_string()),
+        );
+    };
+    let summaries = summarize_perf_records(&read_perf_records(&path));
+
+    if output::json_requested(json) {
+        println!("{}", serde_json::to_string_pretty(&summaries).unwrap());
+        return;
+    }
+    if summaries.is_empty() {
+        println!("No performance records in {}", path.display());
+        if !Config::get().perf_records_enabled() {
+            println!(
+                "Set \"perf_records\": true in ~/.git-ai/config.json to start recording them."
+            );
+        }
+        return;
+    }
+    println!(
+        "{:<16}  {:>6}  {:>9}  {:>9}  {:>12}  {:>12}",
+        "COMMAND", "COUNT", "P50", "P95", "OVERHEAD P50", "OVERHEAD P95"
+    );
+    for summary in summaries {
+        println!(
+            "{:<16}  {:>6}  {:>7}ms  {:>7}ms  {:>10}ms  {:>10}ms",
+            summary.command,
+            summary.count,
+            summary.total_p50_ms,
+            summary.total_p95_ms,
+            summary.overhead_p50_ms,
+            summary.overhead_p95_ms,
+        );
+    }
+}
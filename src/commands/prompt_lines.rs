@@ -0,0 +1,211 @@
+use crate::authorship::authorship_log::LineRange;
+use crate::commands::blame::GitAiBlameOptions;
+use crate::commands::output;
+use crate::error::{GitAiError, exit_with_error};
+use crate::git::find_repository;
+use crate::git::refs::{get_authorship, grep_ai_notes};
+use crate::git::repository::Repository;
+use serde_json::json;
+use std::collections::BTreeSet;
+
+/// Handle the `prompt-lines` command
+///
+/// Usage: git-ai prompt-lines <prompt_id> [--rev <rev>]
+///
+/// The inverse of blame: lists the lines at `rev` (HEAD by default) that are still
+/// attributed to the prompt. Only files the prompt's notes mention are blamed, so
+/// lines that were later moved to a different file are not found.
+pub fn handle_prompt_lines(args: &[String]) {
+    let (prompt_id, rev) = match parse_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => exit_with_error("Error", &GitAiError::InvalidArguments(e)),
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => exit_with_error("Failed to find repository", &e),
+    };
+
+    let files = match prompt_lines(&repo, &prompt_id, &rev) {
+        Ok(files) => files,
+        Err(e) => exit_with_error("Error", &e),
+    };
+
+    let total: u32 = files.iter().map(|(_, ranges)| range_len(ranges)).sum();
+    if output::json_requested(false) {
+        let files: Vec<_> = files
+            .iter()
+            .map(|(path, ranges)| {
+                let lines: Vec<[u32; 2]> = ranges
+                    .iter()
+                    .map(|range| match range {
+                        LineRange::Single(line) => [*line, *line],
+                        LineRange::Range(start, end) => [*start, *end],
+                    })
+                    .collect();
+                json!({ "path": path, "lines": lines })
+            })
+            .collect();
+        let report = json!({
+            "prompt_id": prompt_id,
+            "rev": rev,
+            "files": files,
+            "total_lines": total,
+        });
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        return;
+    }
+
+    if files.is_empty() {
+        println!("No lines at {} are attributed to prompt {}", rev, prompt_id);
+        return;
+    }
+    let width = files.iter().map(|(path, _)| path.len()).max().unwrap_or(0);
+    for (path, ranges) in &files {
+        let ranges: Vec<String> = ranges.iter().map(|range| range.to_string()).collect();
+        println!("{:<width$}  {}", path, ranges.join(", "), width = width);
+    }
+    println!();
+    println!(
+        "{} line(s) in {} file(s) attributed to prompt {}",
+        total,
+        files.len(),
+        prompt_id
+    );
+}
+
+pub fn parse_args(args: &[String]) -> Result<(String, String), String> {
+    let mut prompt_id: Option<String> = None;
+    let mut rev: Option<String> = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        let arg = &args[i];
+        if arg == "--rev" {
+            i += 1;
+            rev = Some(args.get(i).ok_or("--rev requires a value")?.clone());
+        } else if arg.starts_with('-') {
+            return Err(format!("Unknown option: {}", arg));
+        } else if prompt_id.is_some() {
+            return Err("Only one prompt ID can be specified".to_string());
+        } else {
+            prompt_id = Some(arg.clone());
+        }
+        i += 1;
+    }
+
+    let prompt_id = prompt_id.ok_or("prompt-lines requires a prompt ID")?;
+    Ok((prompt_id, rev.unwrap_or_else(|| "HEAD".to_string())))
+}
+
+/// Files at `rev` with lines blamed to `prompt_id`, sorted by path
+pub fn prompt_lines(
+    repo: &Repository,
+    prompt_id: &str,
+    rev: &str,
+) -> Result<Vec<(String, Vec<LineRange>)>, GitAiError> {
+    let commits = grep_ai_notes(repo, &format!("\"{}\"", prompt_id)).unwrap_or_default();
+
+    // Every path any note ever attributed to this prompt
+    let mut paths = BTreeSet::new();
+    let mut found = false;
+    for sha in &commits {
+        let Some(log) = get_authorship(repo, sha) else {
+            continue;
+        };
+        if !log.metadata.prompts.contains_key(prompt_id) {
+            continue;
+        }
+        found = true;
+        for file in &log.attestations {
+            if file.entries.iter().any(|entry| entry.hash == prompt_id) {
+                paths.insert(file.file_path.clone());
+            }
+        }
+    }
+    if !found {
+        return Err(GitAiError::NoAuthorshipData(format!(
+            "Prompt not found in history: {}",
+            prompt_id
+        )));
+    }
+
+    let options = GitAiBlameOptions {
+        newest_commit: Some(repo.revparse_single(rev)?.id()),
+        use_prompt_hashes_as_names: true,
+        no_output: true,
+        ..Default::default()
+    };
+    let mut files = Vec::new();
+    for path in paths {
+        // The file may have been deleted or renamed since
+        let Ok((line_authors, _)) = repo.blame(&path, &options) else {
+            continue;
+        };
+        let mut lines: Vec<u32> = line_authors
+            .into_iter()
+            .filter(|(_, author)| author == prompt_id)
+            .map(|(line, _)| line)
+            .collect();
+        if lines.is_empty() {
+            continue;
+        }
+        lines.sort_unstable();
+        files.push((path, LineRange::compress_lines(&lines)));
+    }
+    Ok(files)
+}
+
+fn range_len(ranges: &[LineRange]) -> u32 {
+    ranges
+        .iter()
+        .map(|range| match range {
+            LineRange::Single(_) => 1,
+            LineRange::Range(start, end) => end - start + 1,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_parse_args() {
+        let args = |values: &[&str]| values.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            parse_args(&args(&["abc123"])).unwrap(),
+            ("abc123".to_string(), "HEAD".to_string())
+        );
+        assert_eq!(
+            parse_args(&args(&["--rev", "v1.0", "abc123"])).unwrap(),
+            ("abc123".to_string(), "v1.0".to_string())
+        );
+        assert!(parse_args(&args(&[])).is_err());
+        assert!(parse_args(&args(&["abc123", "--rev"])).is_err());
+        assert!(parse_args(&args(&["abc123", "def456"])).is_err());
+    }
+
+    #[test]
+    fn test_prompt_lines_lists_current_lines() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo
+            .write_file("app.py", "def helper():\n    return 1\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", None, None)
+            .unwrap();
+        let log = tmp_repo.commit_with_message("add helper").unwrap();
+        let prompt_id = log.metadata.prompts.keys().next().unwrap().clone();
+
+        let files = prompt_lines(tmp_repo.gitai_repo(), &prompt_id, "HEAD").unwrap();
+        assert_eq!(
+            files,
+            vec![("app.py".to_string(), vec![LineRange::Range(1, 2)])]
+        );
+        assert_eq!(range_len(&files[0].1), 2);
+
+        assert!(prompt_lines(tmp_repo.gitai_repo(), "missing", "HEAD").is_err());
+    }
+}
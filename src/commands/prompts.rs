@@ -0,0 +1,337 @@
+use crate::authorship::anonymize::hash_identity;
+use crate::authorship::authorship_log::PromptRecord;
+use crate::authorship::prompt_index;
+use crate::authorship::tickets::ticket_matches;
+use crate::commands::output;
+use crate::error::{GitAiError, exit_with_error};
+use crate::git::find_repository;
+use crate::git::refs::{get_authorship, grep_ai_notes};
+use crate::git::repository::Repository;
+use serde::Serialize;
+
+/// Handle the `prompts` command
+///
+/// Usage: git-ai prompts list [--ticket <ref>] [--json]
+///        git-ai prompts search "<query>" [--limit <n>] [--json]
+pub fn handle_prompts(args: &[String]) {
+    if args.is_empty() {
+        print_prompts_help_and_exit();
+    }
+
+    match args[0].as_str() {
+        "list" => handle_prompts_list(&args[1..]),
+        "search" => handle_prompts_search(&args[1..]),
+        other => {
+            eprintln!("Unknown prompts subcommand: {}", other);
+            print_prompts_help_and_exit();
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ListArgs {
+    pub ticket: Option<String>,
+    pub json: bool,
+}
+
+pub fn parse_list_args(args: &[String]) -> Result<ListArgs, String> {
+    let mut parsed = ListArgs::default();
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--ticket" => {
+                if i + 1 >= args.len() {
+                    return Err("--ticket requires a value".to_string());
+                }
+                i += 1;
+                parsed.ticket = Some(args[i].clone());
+            }
+            "--json" => parsed.json = true,
+            other => return Err(format!("Unknown option: {}", other)),
+        }
+        i += 1;
+    }
+
+    Ok(parsed)
+}
+
+/// One prompt session as recorded in a commit's authorship log
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptListing {
+    pub commit: String,
+    pub prompt_id: String,
+    pub tool: String,
+    pub model: String,
+    pub human_author: Option<String>,
+    pub tickets: Vec<String>,
+    pub messages: usize,
+    pub total_additions: u32,
+    pub total_deletions: u32,
+    pub accepted_lines: u32,
+}
+
+impl PromptListing {
+    fn new(commit: &str, prompt_id: &str, prompt: &PromptRecord, tickets: &[String]) -> Self {
+        PromptListing {
+            commit: commit.to_string(),
+            prompt_id: prompt_id.to_string(),
+            tool: prompt.agent_id.tool.clone(),
+            model: prompt.agent_id.model.clone(),
+            human_author: prompt.human_author.clone(),
+            tickets: tickets.to_vec(),
+            messages: prompt.messages.len(),
+            total_additions: prompt.total_additions,
+            total_deletions: prompt.total_deletions,
+            accepted_lines: prompt.accepted_lines,
+        }
+    }
+}
+
+fn handle_prompts_list(args: &[String]) {
+    let parsed = match parse_list_args(args) {
+        Ok(p) => p,
+        Err(e) => {
+            exit_with_error("Error", &GitAiError::InvalidArguments(e));
+        }
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            exit_with_error("Failed to find repository", &e);
+        }
+    };
+
+    let mut listings = match list_prompts(&repo, parsed.ticket.as_deref()) {
+        Ok(listings) => listings,
+        Err(e) => {
+            exit_with_error("Error", &e);
+        }
+    };
+    if output::anonymize() {
+        for listing in &mut listings {
+            listing.human_author = listing.human_author.as_deref().map(hash_identity);
+        }
+    }
+
+    if output::structured_requested(parsed.json) {
+        if let Err(e) = output::print_structured("prompts", &listings) {
+            exit_with_error("Failed to write the report", &e);
+        }
+        return;
+    }
+
+    if listings.is_empty() {
+        match parsed.ticket {
+            Some(ticket) => eprintln!("No prompts found for ticket {}", ticket),
+            None => eprintln!("No prompts found"),
+        }
+        return;
+    }
+
+    for listing in &listings {
+        let tickets = if listing.tickets.is_empty() {
+            String::new()
+        } else {
+            format!("  [{}]", listing.tickets.join(", "))
+        };
+        println!(
+            "{}  {}  {}/{}  {}  +{} -{}{}",
+            &listing.commit[..listing.commit.len().min(8)],
+            listing.prompt_id,
+            listing.tool,
+            listing.model,
+            listing.human_author.as_deref().unwrap_or("-"),
+            listing.total_additions,
+            listing.total_deletions,
+            tickets
+        );
+    }
+}
+
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+#[derive(Debug, PartialEq)]
+pub struct SearchArgs {
+    pub query: String,
+    pub limit: usize,
+    pub json: bool,
+}
+
+pub fn parse_search_args(args: &[String]) -> Result<SearchArgs, String> {
+    let mut terms = Vec::new();
+    let mut limit = DEFAULT_SEARCH_LIMIT;
+    let mut json = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--limit" | "-n" => {
+                let value = args
+                    .get(i + 1)
+                    .ok_or_else(|| format!("{} requires a value", args[i]))?;
+                limit = value
+                    .parse()
+                    .map_err(|_| format!("Invalid limit: {}", value))?;
+                i += 1;
+            }
+            "--json" => json = true,
+            other if other.starts_with("--") => return Err(format!("Unknown option: {}", other)),
+            term => terms.push(term.to_string()),
+        }
+        i += 1;
+    }
+
+    if terms.is_empty() {
+        return Err("prompts search requires a query".to_string());
+    }
+    Ok(SearchArgs {
+        query: terms.join(" "),
+        limit,
+        json,
+    })
+}
+
+fn handle_prompts_search(args: &[String]) {
+    let parsed = match parse_search_args(args) {
+        Ok(p) => p,
+        Err(e) => {
+            exit_with_error("Error", &GitAiError::InvalidArguments(e));
+        }
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            exit_with_error("Failed to find repository", &e);
+        }
+    };
+
+    let index = match prompt_index::refresh(&repo) {
+        Ok(index) => index,
+        Err(e) => {
+            exit_with_error("Failed to update prompt index", &e);
+        }
+    };
+    let mut hits = index.search(&parsed.query, parsed.limit);
+    if output::anonymize() {
+        for hit in &mut hits {
+            hit.prompt.excerpt.clear();
+        }
+    }
+
+    if output::structured_requested(parsed.json) {
+        if let Err(e) = output::print_structured("prompts", &hits) {
+            exit_with_error("Failed to write the report", &e);
+        }
+        return;
+    }
+
+    if hits.is_empty() {
+        eprintln!("No prompts match \"{}\"", parsed.query);
+        return;
+    }
+
+    for hit in &hits {
+        let prompt = &hit.prompt;
+        println!(
+            "{}  {}  {}/{}  (score {:.2})",
+            &prompt.commit[..prompt.commit.len().min(8)],
+            prompt.prompt_id,
+            prompt.tool,
+            prompt.model,
+            hit.score
+        );
+        if !prompt.files.is_empty() {
+            println!("    files: {}", prompt.files.join(", "));
+        }
+        if !prompt.excerpt.is_empty() {
+            println!("    {}", prompt.excerpt.replace('\n', " "));
+        }
+    }
+}
+
+/// List prompts from every commit with an authorship note, newest first,
+/// optionally restricted to commits linked to `ticket`.
+pub fn list_prompts(
+    repo: &Repository,
+    ticket: Option<&str>,
+) -> Result<Vec<PromptListing>, GitAiError> {
+    // Narrow the candidate commits with git grep; the ticket filter is re-checked on the parsed log
+    let pattern = match ticket {
+        Some(ticket) => format!("\"{}\"", ticket),
+        None => "\"schema_version\"".to_string(),
+    };
+    let mut shas = grep_ai_notes(repo, &pattern).unwrap_or_default();
+    if ticket.is_some() && shas.is_empty() {
+        // git grep is case-sensitive; fall back to scanning every note
+        shas = grep_ai_notes(repo, "\"schema_version\"").unwrap_or_default();
+    }
+
+    let mut listings = Vec::new();
+    for sha in &shas {
+        let Some(log) = get_authorship(repo, sha) else {
+            continue;
+        };
+        let tickets = &log.metadata.tickets;
+        if let Some(ticket) = ticket
+            && !tickets.iter().any(|t| ticket_matches(t, ticket))
+        {
+            continue;
+        }
+        for (prompt_id, prompt) in &log.metadata.prompts {
+            listings.push(PromptListing::new(sha, prompt_id, prompt, tickets));
+        }
+    }
+
+    Ok(listings)
+}
+
+fn print_prompts_help_and_exit() -> ! {
+    eprintln!("git-ai prompts - Query prompt sessions recorded in authorship logs");
+    eprintln!();
+    eprintln!("Usage: git-ai prompts <subcommand> [args...]");
+    eprintln!();
+    eprintln!("Subcommands:");
+    eprintln!("  list                 List prompts, newest commit first");
+    eprintln!("    --ticket <ref>     Only prompts from commits linked to a ticket");
+    eprintln!("                       (e.g. PROJ-123, #456)");
+    eprintln!("    --json             Output in JSON format");
+    eprintln!("  search <query>       Full-text search over prompt transcripts, best match first");
+    eprintln!("    --limit <n>        Maximum results (default: 20)");
+    eprintln!("    --json             Output in JSON format");
+    std::process::exit(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_list_args() {
+        let parsed = parse_list_args(&args(&["--ticket", "PROJ-1", "--json"])).unwrap();
+        assert_eq!(parsed.ticket.as_deref(), Some("PROJ-1"));
+        assert!(parsed.json);
+
+        assert_eq!(parse_list_args(&[]).unwrap(), ListArgs::default());
+        assert!(parse_list_args(&args(&["--ticket"])).is_err());
+        assert!(parse_list_args(&args(&["--bogus"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_search_args() {
+        let parsed = parse_search_args(&args(&["retry", "logic", "--limit", "5"])).unwrap();
+        assert_eq!(parsed.query, "retry logic");
+        assert_eq!(parsed.limit, 5);
+        assert!(!parsed.json);
+
+        assert!(parse_search_args(&[]).is_err());
+        assert!(parse_search_args(&args(&["x", "--limit", "many"])).is_err());
+        assert!(parse_search_args(&args(&["x", "--bogus"])).is_err());
+    }
+}
@@ -0,0 +1,117 @@
+//! `git-ai proxy <git-command>`: run a git command through the git-ai hooks.
+//!
+//! Nothing is added to the command by default. Per-subcommand flags and
+//! environment variables come from the `proxy` section of
+//! `~/.git-ai/config.json`:
+//!
+//! ```json
+//! "proxy": {
+//!   "extra_args": {
+//!     "commit": { "args": ["--no-verify"], "env": { "HUSKY": "0" } },
+//!     "push": { "args": ["--no-verify"] }
+//!   }
+//! }
+//! ```
+
+use crate::git::cli_parser::parse_git_cli_args;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The `proxy` section of ~/.git-ai/config.json
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProxyConfig {
+    /// Keyed by git subcommand (e.g. "commit")
+    #[serde(default)]
+    pub extra_args: BTreeMap<String, ProxyCommandConfig>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProxyCommandConfig {
+    /// Inserted right after the subcommand, skipping any the user already passed
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Set for the git process and the hooks git-ai runs around it
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+}
+
+/// The git argv to run for `args`, and the environment to add
+pub fn build_invocation(
+    args: &[String],
+    config: &ProxyConfig,
+) -> (Vec<String>, BTreeMap<String, String>) {
+    let mut parsed = parse_git_cli_args(args);
+    let Some(command_config) = parsed
+        .command
+        .as_deref()
+        .and_then(|command| config.extra_args.get(command))
+    else {
+        return (args.to_vec(), BTreeMap::new());
+    };
+
+    let extra: Vec<String> = command_config
+        .args
+        .iter()
+        .filter(|arg| !parsed.has_command_flag(arg))
+        .cloned()
+        .collect();
+    parsed.command_args.splice(0..0, extra);
+    (parsed.to_invocation_vec(), command_config.env.clone())
+}
+
+/// Handle the `proxy` command
+pub fn handle_proxy(args: &[String]) {
+    let config = crate::config::Config::get();
+    let (git_args, env) = build_invocation(args, config.proxy());
+    for (key, value) in &env {
+        // SAFETY: called before any threads are started for this command
+        unsafe { std::env::set_var(key, value) };
+    }
+    crate::commands::git_handlers::handle_git(&git_args);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_build_invocation() {
+        let config: ProxyConfig = serde_json::from_str(
+            r#"{"extra_args": {"commit": {"args": ["--no-verify", "-q"], "env": {"HUSKY": "0"}}}}"#,
+        )
+        .unwrap();
+
+        // Unconfigured subcommands pass through untouched
+        let (argv, env) = build_invocation(&args(&["status", "-s"]), &config);
+        assert_eq!(argv, args(&["status", "-s"]));
+        assert!(env.is_empty());
+
+        let (argv, env) = build_invocation(
+            &args(&["-C", "repo", "commit", "-q", "-m", "msg", "--", "a.txt"]),
+            &config,
+        );
+        assert_eq!(
+            argv,
+            args(&[
+                "-C",
+                "repo",
+                "commit",
+                "--no-verify",
+                "-q",
+                "-m",
+                "msg",
+                "--",
+                "a.txt"
+            ])
+        );
+        assert_eq!(env.get("HUSKY").map(String::as_str), Some("0"));
+
+        // Nothing is injected without configuration
+        let (argv, _) = build_invocation(&args(&["commit", "-m", "msg"]), &ProxyConfig::default());
+        assert_eq!(argv, args(&["commit", "-m", "msg"]));
+    }
+}
@@ -0,0 +1,664 @@
+//! Output renderers for `--format <name>` beyond `json` and `text`.
+//!
+//! Reports (`stats`, `stats-range`, `diff`, `show`, `blame`, `prompts`,
+//! `working-stats`, `multi`) hand a renderer the same JSON they print for
+//! `--format json`; the renderer turns it into markdown, CSV, HTML, SARIF or
+//! anything else. Built-in renderers are looked up first, then the
+//! `renderers` section of ~/.git-ai/config.json, which maps a format name to an
+//! executable:
+//!
+//!   {"renderers": {"confluence": {"command": "render-confluence", "args": ["--wide"]}}}
+//!
+//! External renderers get `{"report", "format", "git_ai_version", "data"}` as JSON
+//! on stdin and whatever they print to stdout is passed through unchanged.
+
+use crate::authorship::stats::{CommitStats, write_stats_to_markdown};
+use crate::commands::checkpoint_agent::plugin_preset::run_plugin;
+use crate::commands::export::escape_html;
+use crate::config::Config;
+use crate::error::GitAiError;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// Built-in format names, in the order they're listed in errors and help
+pub const BUILTIN_RENDERERS: &[&str] = &["csv", "html", "markdown", "sarif"];
+
+/// A report handed to a renderer
+pub struct RenderInput<'a> {
+    /// The command that produced it, e.g. `stats` or `diff`
+    pub report: &'a str,
+    /// The report's `--format json` output
+    pub data: &'a Value,
+}
+
+pub trait Renderer {
+    fn render(&self, input: &RenderInput) -> Result<String, GitAiError>;
+}
+
+/// An executable registered under `renderers` in the config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalRenderer {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Seconds to wait before the renderer is killed
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+/// Look up a renderer by format name, built-ins first
+pub fn find_renderer(name: &str) -> Option<Box<dyn Renderer>> {
+    match name {
+        "csv" => Some(Box::new(CsvRenderer)),
+        "html" => Some(Box::new(HtmlRenderer)),
+        "markdown" | "md" => Some(Box::new(MarkdownRenderer)),
+        "sarif" => Some(Box::new(SarifRenderer)),
+        _ => Config::get()
+            .renderer_plugin(name)
+            .map(|external| -> Box<dyn Renderer> {
+                Box::new(ExternalProcessRenderer {
+                    name: name.to_string(),
+                    external: external.clone(),
+                })
+            }),
+    }
+}
+
+pub fn is_registered(name: &str) -> bool {
+    find_renderer(name).is_some()
+}
+
+/// Render a report with the named renderer and print it to stdout
+pub fn print_report(name: &str, report: &str, data: &Value) -> Result<(), GitAiError> {
+    let renderer = find_renderer(name)
+        .ok_or_else(|| GitAiError::InvalidArguments(format!("Unknown renderer '{}'", name)))?;
+    let rendered = renderer.render(&RenderInput { report, data })?;
+    print!("{}", rendered);
+    if !rendered.is_empty() && !rendered.ends_with('\n') {
+        println!();
+    }
+    Ok(())
+}
+
+/// A titled table, the common shape markdown, CSV and HTML are rendered from
+struct Table {
+    title: String,
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+const STATS_METRICS: &[(&str, &str)] = &[
+    ("human_additions", "Human lines"),
+    ("ai_additions", "AI lines"),
+    ("mixed_additions", "Mixed lines"),
+    ("ai_accepted", "AI lines accepted unchanged"),
+    ("reviewed_ai_additions", "Reviewed AI lines"),
+    ("generated_additions", "Generated lines"),
+    ("total_ai_additions", "AI lines written"),
+    ("total_ai_deletions", "AI lines deleted"),
+    ("time_waiting_for_ai", "Seconds waiting for AI"),
+    ("git_diff_added_lines", "Lines added"),
+    ("git_diff_deleted_lines", "Lines deleted"),
+];
+
+fn headers(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+fn cell(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Null) | None => String::new(),
+        Some(other) => other.to_string(),
+    }
+}
+
+fn stats_tables(stats: &Value) -> Vec<Table> {
+    let mut tables = vec![Table {
+        title: "AI authorship".to_string(),
+        headers: headers(&["Metric", "Value"]),
+        rows: STATS_METRICS
+            .iter()
+            .map(|(key, label)| vec![label.to_string(), cell(stats.get(*key))])
+            .collect(),
+    }];
+
+    if let Some(breakdown) = stats
+        .get("tool_model_breakdown")
+        .and_then(Value::as_object)
+        .filter(|b| !b.is_empty())
+    {
+        tables.push(Table {
+            title: "By tool and model".to_string(),
+            headers: headers(&["Tool::model", "AI lines", "Mixed", "Accepted", "Written"]),
+            rows: breakdown
+                .iter()
+                .map(|(tool, s)| {
+                    vec![
+                        tool.clone(),
+                        cell(s.get("ai_additions")),
+                        cell(s.get("mixed_additions")),
+                        cell(s.get("ai_accepted")),
+                        cell(s.get("total_ai_additions")),
+                    ]
+                })
+                .collect(),
+        });
+    }
+
+    if let Some(files) = stats
+        .get("file_breakdown")
+        .and_then(Value::as_object)
+        .filter(|f| !f.is_empty())
+    {
+        tables.push(Table {
+            title: "By file".to_string(),
+            headers: headers(&["File", "AI lines", "Lines added"]),
+            rows: files
+                .iter()
+                .map(|(path, s)| {
+                    vec![
+                        path.clone(),
+                        cell(s.get("ai_additions")),
+                        cell(s.get("git_diff_added_lines")),
+                    ]
+                })
+                .collect(),
+        });
+    }
+    tables
+}
+
+fn diff_tables(diff: &Value) -> Vec<Table> {
+    let lines = diff
+        .get("lines")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+
+    // [ai, human, mixed, none] per file, counting added lines only
+    let mut per_file: BTreeMap<String, [u32; 4]> = BTreeMap::new();
+    for line in lines.iter().filter(|l| cell(l.get("side")) == "new") {
+        let counts = per_file.entry(cell(line.get("file"))).or_default();
+        match line.get("kind").and_then(Value::as_str) {
+            Some("ai") => counts[0] += 1,
+            Some("human") => counts[1] += 1,
+            Some("mixed") => counts[2] += 1,
+            _ => counts[3] += 1,
+        }
+    }
+
+    vec![
+        Table {
+            title: format!(
+                "Added lines by file ({}..{})",
+                short_sha(diff.get("from")),
+                short_sha(diff.get("to"))
+            ),
+            headers: headers(&["File", "AI", "Human", "Mixed", "No data"]),
+            rows: per_file
+                .into_iter()
+                .map(|(file, c)| {
+                    let mut row = vec![file];
+                    row.extend(c.iter().map(|n| n.to_string()));
+                    row
+                })
+                .collect(),
+        },
+        Table {
+            title: "Lines".to_string(),
+            headers: headers(&["File", "Side", "Line", "Kind", "Author"]),
+            rows: lines
+                .iter()
+                .map(|line| {
+                    ["file", "side", "line", "kind", "author"]
+                        .iter()
+                        .map(|key| cell(line.get(*key)))
+                        .collect()
+                })
+                .collect(),
+        },
+    ]
+}
+
+fn short_sha(value: Option<&Value>) -> String {
+    cell(value).chars().take(7).collect()
+}
+
+/// Tables for reports without a layout of their own: a list of objects
+/// becomes one table; an object's scalar fields become a field/value table,
+/// followed by a table per field holding a list or map of objects
+fn json_tables(title: &str, data: &Value) -> Vec<Table> {
+    let mut tables = Vec::new();
+    match data {
+        Value::Array(items) => {
+            tables.push(object_table(title, items.iter().map(|item| (None, item))))
+        }
+        Value::Object(fields) => {
+            let mut scalars = Vec::new();
+            for (key, value) in fields {
+                match value {
+                    Value::Array(items)
+                        if !items.is_empty() && items.iter().all(Value::is_object) =>
+                    {
+                        tables.push(object_table(key, items.iter().map(|item| (None, item))));
+                    }
+                    Value::Object(map) if !map.is_empty() && map.values().all(Value::is_object) => {
+                        tables.push(object_table(key, map.iter().map(|(k, v)| (Some(k), v))));
+                    }
+                    _ => scalars.push(vec![key.clone(), cell(Some(value))]),
+                }
+            }
+            if !scalars.is_empty() {
+                tables.insert(
+                    0,
+                    Table {
+                        title: title.to_string(),
+                        headers: headers(&["Field", "Value"]),
+                        rows: scalars,
+                    },
+                );
+            }
+        }
+        other => tables.push(Table {
+            title: title.to_string(),
+            headers: headers(&["Value"]),
+            rows: vec![vec![cell(Some(other))]],
+        }),
+    }
+    tables
+}
+
+/// One row per object, one column per key seen in any of them. Map entries
+/// get their key as the first column.
+fn object_table<'a>(
+    title: &str,
+    items: impl Iterator<Item = (Option<&'a String>, &'a Value)> + Clone,
+) -> Table {
+    let keyed = items.clone().any(|(key, _)| key.is_some());
+    let mut columns: Vec<String> = Vec::new();
+    for (_, item) in items.clone() {
+        for key in item.as_object().into_iter().flat_map(|o| o.keys()) {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+    }
+    let rows = items
+        .map(|(key, item)| {
+            let mut row: Vec<String> = key.cloned().into_iter().collect();
+            row.extend(columns.iter().map(|column| cell(item.get(column))));
+            row
+        })
+        .collect();
+    let mut table_headers = if keyed {
+        vec![String::new()]
+    } else {
+        Vec::new()
+    };
+    table_headers.extend(columns);
+    Table {
+        title: title.to_string(),
+        headers: table_headers,
+        rows,
+    }
+}
+
+fn report_tables(input: &RenderInput) -> Result<Vec<Table>, GitAiError> {
+    match input.report {
+        "stats" => Ok(stats_tables(input.data)),
+        "stats-range" => {
+            let mut tables = stats_tables(input.data.get("range_stats").unwrap_or(&Value::Null));
+            let data = input.data.get("authorship_stats");
+            tables.insert(
+                0,
+                Table {
+                    title: "Commits".to_string(),
+                    headers: headers(&["Metric", "Value"]),
+                    rows: vec![
+                        vec![
+                            "Commits".to_string(),
+                            cell(data.and_then(|d| d.get("total_commits"))),
+                        ],
+                        vec![
+                            "Commits with authorship".to_string(),
+                            cell(data.and_then(|d| d.get("commits_with_authorship"))),
+                        ],
+                    ],
+                },
+            );
+            Ok(tables)
+        }
+        "diff" => Ok(diff_tables(input.data)),
+        other => Ok(json_tables(other, input.data)),
+    }
+}
+
+/// Stats get the same summary as commit and PR comments
+struct MarkdownRenderer;
+
+impl Renderer for MarkdownRenderer {
+    fn render(&self, input: &RenderInput) -> Result<String, GitAiError> {
+        if input.report == "stats" {
+            let stats: CommitStats = serde_json::from_value(input.data.clone())?;
+            return Ok(write_stats_to_markdown(&stats));
+        }
+        let escape = |s: &str| s.replace('|', "\\|");
+        let mut out = String::new();
+        for table in report_tables(input)? {
+            out.push_str(&format!("### {}\n\n", table.title));
+            out.push_str(&format!("| {} |\n", table.headers.join(" | ")));
+            out.push_str(&format!("|{}\n", " --- |".repeat(table.headers.len())));
+            for row in &table.rows {
+                let cells: Vec<String> = row.iter().map(|c| escape(c)).collect();
+                out.push_str(&format!("| {} |\n", cells.join(" | ")));
+            }
+            out.push('\n');
+        }
+        Ok(out)
+    }
+}
+
+/// Only the report's main table: the summary for stats, every line for diffs
+struct CsvRenderer;
+
+impl Renderer for CsvRenderer {
+    fn render(&self, input: &RenderInput) -> Result<String, GitAiError> {
+        let tables = report_tables(input)?;
+        let table = match input.report {
+            "diff" => tables.into_iter().nth(1),
+            "stats-range" => tables.into_iter().nth(1),
+            _ => tables.into_iter().next(),
+        }
+        .ok_or_else(|| GitAiError::Generic("Nothing to render".to_string()))?;
+
+        let field = |s: &str| {
+            if s.contains([',', '"', '\n']) {
+                format!("\"{}\"", s.replace('"', "\"\""))
+            } else {
+                s.to_string()
+            }
+        };
+        let mut out = String::new();
+        let headers: Vec<String> = table.headers.iter().map(|h| field(h)).collect();
+        out.push_str(&headers.join(","));
+        out.push('\n');
+        for row in &table.rows {
+            let cells: Vec<String> = row.iter().map(|c| field(c)).collect();
+            out.push_str(&cells.join(","));
+            out.push('\n');
+        }
+        Ok(out)
+    }
+}
+
+struct HtmlRenderer;
+
+impl Renderer for HtmlRenderer {
+    fn render(&self, input: &RenderInput) -> Result<String, GitAiError> {
+        let mut body = String::new();
+        for table in report_tables(input)? {
+            body.push_str(&format!(
+                "<h2>{}</h2>\n<table>\n<tr>",
+                escape_html(&table.title)
+            ));
+            for header in &table.headers {
+                body.push_str(&format!("<th>{}</th>", escape_html(header)));
+            }
+            body.push_str("</tr>\n");
+            for row in &table.rows {
+                body.push_str("<tr>");
+                for c in row {
+                    body.push_str(&format!("<td>{}</td>", escape_html(c)));
+                }
+                body.push_str("</tr>\n");
+            }
+            body.push_str("</table>\n");
+        }
+        Ok(format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>git-ai {}</title>\n\
+             <style>body{{font-family:sans-serif}}table{{border-collapse:collapse;margin-bottom:1.5em}}\
+             th,td{{border:1px solid #ccc;padding:2px 8px;text-align:left}}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+            escape_html(input.report),
+            body
+        ))
+    }
+}
+
+/// AI-written lines of a diff as SARIF notes, for code scanning dashboards
+struct SarifRenderer;
+
+impl Renderer for SarifRenderer {
+    fn render(&self, input: &RenderInput) -> Result<String, GitAiError> {
+        if input.report != "diff" {
+            return Err(GitAiError::InvalidArguments(format!(
+                "The sarif renderer only supports diff reports, not {}",
+                input.report
+            )));
+        }
+        let results: Vec<Value> = input
+            .data
+            .get("lines")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter(|line| cell(line.get("side")) == "new")
+            .filter_map(|line| {
+                let kind = line.get("kind").and_then(Value::as_str)?;
+                let rule = match kind {
+                    "ai" => "ai-authored",
+                    "mixed" => "ai-authored-human-edited",
+                    _ => return None,
+                };
+                Some(json!({
+                    "ruleId": rule,
+                    "level": "note",
+                    "message": {
+                        "text": format!("Line written by {}", cell(line.get("author")))
+                    },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": cell(line.get("file")) },
+                            "region": { "startLine": line.get("line") }
+                        }
+                    }]
+                }))
+            })
+            .collect();
+
+        let sarif = json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "git-ai",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "rules": [
+                            {
+                                "id": "ai-authored",
+                                "shortDescription": { "text": "Line written by an AI agent" }
+                            },
+                            {
+                                "id": "ai-authored-human-edited",
+                                "shortDescription": { "text": "AI-written line edited by a human" }
+                            }
+                        ]
+                    }
+                },
+                "results": results
+            }]
+        });
+        Ok(serde_json::to_string_pretty(&sarif)?)
+    }
+}
+
+struct ExternalProcessRenderer {
+    name: String,
+    external: ExternalRenderer,
+}
+
+impl Renderer for ExternalProcessRenderer {
+    fn render(&self, input: &RenderInput) -> Result<String, GitAiError> {
+        let payload = json!({
+            "report": input.report,
+            "format": self.name,
+            "git_ai_version": env!("CARGO_PKG_VERSION"),
+            "data": input.data,
+        });
+        let mut cmd = Command::new(&self.external.command);
+        cmd.args(&self.external.args)
+            .env("GIT_AI_RENDERER", &self.name)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit());
+        let timeout =
+            Duration::from_secs(self.external.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+        let stdout = run_plugin(cmd, &payload.to_string(), timeout).map_err(|e| {
+            GitAiError::Generic(format!(
+                "Renderer '{}' ({}) failed: {}",
+                self.name, self.external.command, e
+            ))
+        })?;
+        Ok(String::from_utf8_lossy(&stdout).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(name: &str, report: &str, data: &Value) -> String {
+        find_renderer(name)
+            .unwrap()
+            .render(&RenderInput { report, data })
+            .unwrap()
+    }
+
+    fn diff_report() -> Value {
+        json!({
+            "from": "aaaaaaaaaa",
+            "to": "bbbbbbbbbb",
+            "lines": [
+                {"file": "src/lib.rs", "side": "new", "line": 3, "kind": "ai", "author": "claude"},
+                {"file": "src/lib.rs", "side": "new", "line": 4, "kind": "human", "author": "alice"},
+                {"file": "src/lib.rs", "side": "old", "line": 2, "kind": "human", "author": "bob, jr"}
+            ]
+        })
+    }
+
+    #[test]
+    fn test_builtin_renderers() {
+        let stats = json!({
+            "human_additions": 4,
+            "ai_additions": 6,
+            "tool_model_breakdown": {"claude::sonnet": {"ai_additions": 6, "ai_accepted": 5}}
+        });
+        let csv = render("csv", "stats", &stats);
+        assert!(csv.contains("AI lines,6\n"));
+
+        let commit_stats = CommitStats {
+            human_additions: 4,
+            ai_additions: 6,
+            ai_accepted: 6,
+            git_diff_added_lines: 10,
+            ..Default::default()
+        };
+        assert_eq!(
+            render(
+                "markdown",
+                "stats",
+                &serde_json::to_value(&commit_stats).unwrap()
+            ),
+            write_stats_to_markdown(&commit_stats)
+        );
+
+        let csv = render("csv", "diff", &diff_report());
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows[0], "File,Side,Line,Kind,Author");
+        assert_eq!(rows[1], "src/lib.rs,new,3,ai,claude");
+        assert_eq!(rows[3], "src/lib.rs,old,2,human,\"bob, jr\"");
+
+        let html = render("html", "diff", &diff_report());
+        assert!(html.contains("<td>src/lib.rs</td><td>1</td><td>1</td><td>0</td><td>0</td>"));
+
+        let sarif: Value = serde_json::from_str(&render("sarif", "diff", &diff_report())).unwrap();
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["ruleId"], "ai-authored");
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"]["region"]["startLine"],
+            3
+        );
+        assert!(
+            find_renderer("sarif")
+                .unwrap()
+                .render(&RenderInput {
+                    report: "stats",
+                    data: &stats
+                })
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_reports_without_a_layout_render_their_json() {
+        let prompts = json!([
+            {"id": "p1", "tool": "claude", "lines": 3},
+            {"id": "p2", "tool": "cursor", "human_author": "alice"}
+        ]);
+        let csv = render("csv", "prompts", &prompts);
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            rows,
+            vec![
+                "id,lines,tool,human_author",
+                "p1,3,claude,",
+                "p2,,cursor,alice"
+            ]
+        );
+
+        let multi = json!({
+            "succeeded": 1,
+            "failed": 0,
+            "repositories": [{"path": "/a", "success": true}],
+            "totals": {"ai_additions": 2}
+        });
+        let markdown = render("markdown", "multi", &multi);
+        assert!(markdown.contains("### multi\n"));
+        assert!(markdown.contains("| succeeded | 1 |"));
+        assert!(markdown.contains("| totals | {\"ai_additions\":2} |"));
+        assert!(markdown.contains("### repositories\n\n| path | success |\n"));
+
+        let working = json!({"files": {"src/lib.rs": {"ai": 2, "human": 1}}});
+        let html = render("html", "working-stats", &working);
+        assert!(html.contains("<th></th><th>ai</th><th>human</th>"));
+        assert!(html.contains("<td>src/lib.rs</td><td>2</td><td>1</td>"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_external_renderer_receives_report_on_stdin() {
+        let renderer = ExternalProcessRenderer {
+            name: "upper".to_string(),
+            external: ExternalRenderer {
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), "tr a-z A-Z".to_string()],
+                timeout_secs: None,
+            },
+        };
+        let out = renderer
+            .render(&RenderInput {
+                report: "stats",
+                data: &json!({"ai_additions": 1}),
+            })
+            .unwrap();
+        assert!(out.contains("\"REPORT\":\"STATS\""));
+        assert!(out.contains("\"FORMAT\":\"UPPER\""));
+    }
+}
@@ -0,0 +1,107 @@
+use crate::config::{self, Config, RepositoryAccess};
+use crate::git::repository::Repository;
+use crate::utils::debug_log;
+use std::io::{BufRead, IsTerminal, Write};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    /// Enable for this repository
+    Yes,
+    /// Skip this repository
+    No,
+    /// Enable for every repository that isn't excluded
+    Always,
+}
+
+/// Anything but an explicit yes is a no
+pub fn parse_answer(input: &str) -> Answer {
+    match input.trim().to_lowercase().as_str() {
+        "y" | "yes" => Answer::Yes,
+        "a" | "always" => Answer::Always,
+        _ => Answer::No,
+    }
+}
+
+/// Whether git-ai should run in `repository`. Like
+/// [`Config::is_allowed_repository`], except that with
+/// `prompt_unlisted_repositories` set, a repository on neither list is asked
+/// about on first use and the answer saved to `allow_repositories` or
+/// `exclude_repositories`. Without a terminal to ask on, or a remote URL to
+/// save, the usual allow-list rules apply.
+pub fn is_allowed_repository(config: &Config, repository: &Option<Repository>) -> bool {
+    let allowed = config.is_allowed_repository(repository);
+    if !config.prompt_unlisted_repositories()
+        || config.repository_access(repository) != RepositoryAccess::Unlisted
+        || !std::io::stdin().is_terminal()
+        || !std::io::stderr().is_terminal()
+    {
+        return allowed;
+    }
+    let Some(url) = repository.as_ref().and_then(remote_url) else {
+        return allowed;
+    };
+
+    eprint!("Enable git-ai for this repository ({})? [y/N/always] ", url);
+    let _ = std::io::stderr().flush();
+    let mut input = String::new();
+    if std::io::stdin().lock().read_line(&mut input).is_err() {
+        return allowed;
+    }
+    let answer = parse_answer(&input);
+    if let Err(e) = save_answer(answer, &url) {
+        eprintln!("[git-ai] Failed to save your answer: {}", e);
+    }
+    answer != Answer::No
+}
+
+/// The URL answers are saved under: origin's, or the first remote's
+fn remote_url(repository: &Repository) -> Option<String> {
+    let remotes = repository.remotes_with_urls().ok()?;
+    remotes
+        .iter()
+        .find(|(name, _)| name == "origin")
+        .or_else(|| remotes.first())
+        .map(|(_, url)| url.clone())
+}
+
+fn save_answer(answer: Answer, url: &str) -> Result<(), crate::error::GitAiError> {
+    let (key, pattern) = match answer {
+        Answer::Yes => ("allow_repositories", glob::Pattern::escape(url)),
+        Answer::No => ("exclude_repositories", glob::Pattern::escape(url)),
+        Answer::Always => ("allow_repositories", "*".to_string()),
+    };
+    let path = config::update_file_config(|cfg| {
+        let list = cfg
+            .entry(key.to_string())
+            .or_insert_with(|| serde_json::Value::Array(Vec::new()));
+        if !list.is_array() {
+            *list = serde_json::Value::Array(Vec::new());
+        }
+        let list = list.as_array_mut().unwrap();
+        if !list.iter().any(|existing| existing == pattern.as_str()) {
+            list.push(serde_json::Value::String(pattern.clone()));
+        }
+    })?;
+    debug_log(&format!(
+        "Saved {} to {} in {}",
+        pattern,
+        key,
+        path.display()
+    ));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answer() {
+        assert_eq!(parse_answer("y\n"), Answer::Yes);
+        assert_eq!(parse_answer(" YES "), Answer::Yes);
+        assert_eq!(parse_answer("always\n"), Answer::Always);
+        assert_eq!(parse_answer("a"), Answer::Always);
+        assert_eq!(parse_answer("\n"), Answer::No);
+        assert_eq!(parse_answer("nope"), Answer::No);
+    }
+}
@@ -0,0 +1,407 @@
+use crate::authorship::attribution_conflicts::{
+    AmbiguousRegion, PendingResolution, assign_lines, clear_pending, load_pending, save_pending,
+};
+use crate::authorship::authorship_log::PromptRecord;
+use crate::commands::blame::GitAiBlameOptions;
+use crate::commands::output;
+use crate::error::{GitAiError, exit_with_error};
+use crate::git::find_repository;
+use crate::git::refs::{get_authorship, notes_add};
+use crate::git::repository::Repository;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{BufRead, IsTerminal, Write};
+
+/// Lines of a region shown before the prompt
+const PREVIEW_LINES: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decision {
+    /// Credit the region to this AI session
+    Ai(String),
+    Human,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ResolveArgs {
+    pub list: bool,
+    /// 1-based region number and `ai`, `human` or a session hash
+    pub assignments: Vec<(usize, String)>,
+}
+
+pub fn parse_args(args: &[String]) -> Result<ResolveArgs, String> {
+    let mut parsed = ResolveArgs::default();
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--list" => parsed.list = true,
+            "--assign" => {
+                let spec = args
+                    .get(i + 1)
+                    .ok_or("--assign requires <n>=<ai|human|hash>")?;
+                let (index, target) = spec
+                    .split_once('=')
+                    .and_then(|(n, target)| Some((n.parse::<usize>().ok()?, target)))
+                    .filter(|(n, target)| *n > 0 && !target.is_empty())
+                    .ok_or_else(|| format!("Invalid assignment '{}'", spec))?;
+                parsed.assignments.push((index, target.to_string()));
+                i += 1;
+            }
+            other => return Err(format!("Unknown option: {}", other)),
+        }
+        i += 1;
+    }
+    Ok(parsed)
+}
+
+/// `ai` stands for the region's only session; anything else must name one of them
+pub fn parse_decision(target: &str, region: &AmbiguousRegion) -> Result<Decision, String> {
+    match target {
+        "human" | "h" => Ok(Decision::Human),
+        "ai" | "a" => match region.candidates.as_slice() {
+            [only] => Ok(Decision::Ai(only.clone())),
+            candidates => Err(format!(
+                "{}:{}-{} could be from {}; pass the session hash instead of ai",
+                region.file,
+                region.start_line,
+                region.end_line,
+                candidates.join(", ")
+            )),
+        },
+        hash => region
+            .candidates
+            .iter()
+            .find(|candidate| candidate.starts_with(hash))
+            .map(|candidate| Decision::Ai(candidate.clone()))
+            .ok_or_else(|| {
+                format!(
+                    "'{}' is not one of the sessions for {}:{}-{}",
+                    hash, region.file, region.start_line, region.end_line
+                )
+            }),
+    }
+}
+
+/// Handle the `resolve-attribution` command
+///
+/// Usage: git-ai resolve-attribution [--list] [--assign <n>=<ai|human|hash>]...
+///
+/// Settles the regions the last rebase couldn't attribute, interactively or
+/// with `--assign`, by updating the notes of the rebased commits.
+pub fn handle_resolve_attribution(args: &[String]) {
+    let args = match parse_args(args) {
+        Ok(args) => args,
+        Err(e) => exit_with_error(
+            "Error",
+            &GitAiError::InvalidArguments(format!(
+                "{}\nUsage: git-ai resolve-attribution [--list] [--assign <n>=<ai|human|hash>]...",
+                e
+            )),
+        ),
+    };
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => exit_with_error("Failed to find repository", &e),
+    };
+    let Some(mut pending) = current_pending(&repo) else {
+        if output::json_requested(false) {
+            println!("[]");
+        } else {
+            println!("No ambiguous attribution to resolve");
+        }
+        return;
+    };
+
+    if args.list {
+        if output::json_requested(false) {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&pending.regions).unwrap_or_default()
+            );
+        } else {
+            let prompts = head_prompts(&repo, &pending);
+            for (i, region) in pending.regions.iter().enumerate() {
+                println!("{:>3}  {}", i + 1, describe(region, &prompts));
+            }
+        }
+        return;
+    }
+
+    let result = if !args.assignments.is_empty() {
+        let mut decisions = BTreeMap::new();
+        for (index, target) in &args.assignments {
+            let Some(region) = pending.regions.get(index - 1) else {
+                exit_with_error(
+                    "Error",
+                    &GitAiError::InvalidArguments(format!(
+                        "There are only {} ambiguous regions",
+                        pending.regions.len()
+                    )),
+                );
+            };
+            match parse_decision(target, region) {
+                Ok(decision) => decisions.insert(index - 1, decision),
+                Err(e) => exit_with_error("Error", &GitAiError::InvalidArguments(e)),
+            };
+        }
+        apply_decisions(&repo, &mut pending, &decisions)
+    } else if std::io::stdin().is_terminal() {
+        resolve_interactively(&repo, &mut pending)
+    } else {
+        exit_with_error(
+            "Error",
+            &GitAiError::InvalidArguments(
+                "Not a terminal; pass --assign <n>=<ai|human|hash> for each region".to_string(),
+            ),
+        );
+    };
+    if let Err(e) = result {
+        exit_with_error("resolve-attribution failed", &e);
+    }
+}
+
+/// Called once a rebase has rewritten authorship: asks about ambiguous regions
+/// right away in a terminal, otherwise says how to settle them later
+pub fn prompt_after_rebase(repo: &Repository) {
+    let Some(mut pending) = current_pending(repo) else {
+        return;
+    };
+    let count = pending.regions.len();
+    if std::io::stdin().is_terminal() && std::io::stderr().is_terminal() && !output::quiet() {
+        eprintln!(
+            "git-ai: the rebase rewrote {} AI-written region{} too much to tell who wrote {} now",
+            count,
+            if count == 1 { "" } else { "s" },
+            if count == 1 { "it" } else { "them" }
+        );
+        if let Err(e) = resolve_interactively(repo, &mut pending) {
+            eprintln!("git-ai: resolving attribution failed: {}", e);
+        }
+    } else {
+        eprintln!(
+            "git-ai: {} ambiguous attribution region{} after rebase; run `git-ai resolve-attribution`",
+            count,
+            if count == 1 { "" } else { "s" }
+        );
+    }
+}
+
+/// The pending regions, if they still describe HEAD
+fn current_pending(repo: &Repository) -> Option<PendingResolution> {
+    let pending = load_pending(&repo.storage)?;
+    let head = repo.head().ok()?.target().ok()?;
+    if pending.head != head || pending.regions.is_empty() {
+        // Later commits renumbered the lines; the regions can't be trusted
+        clear_pending(&repo.storage);
+        return None;
+    }
+    Some(pending)
+}
+
+fn head_prompts(repo: &Repository, pending: &PendingResolution) -> BTreeMap<String, PromptRecord> {
+    get_authorship(repo, &pending.head)
+        .map(|log| log.metadata.prompts)
+        .unwrap_or_default()
+}
+
+fn describe_session(hash: &str, prompts: &BTreeMap<String, PromptRecord>) -> String {
+    match prompts.get(hash) {
+        Some(prompt) => format!(
+            "{} ({} {})",
+            hash, prompt.agent_id.tool, prompt.agent_id.model
+        ),
+        None => hash.to_string(),
+    }
+}
+
+fn describe(region: &AmbiguousRegion, prompts: &BTreeMap<String, PromptRecord>) -> String {
+    let sessions: Vec<String> = region
+        .candidates
+        .iter()
+        .map(|hash| describe_session(hash, prompts))
+        .collect();
+    format!(
+        "{}:{}-{}  was AI: {}",
+        region.file,
+        region.start_line,
+        region.end_line,
+        sessions.join(", ")
+    )
+}
+
+fn resolve_interactively(
+    repo: &Repository,
+    pending: &mut PendingResolution,
+) -> Result<(), GitAiError> {
+    let prompts = head_prompts(repo, pending);
+    let total = pending.regions.len();
+    let mut decisions = BTreeMap::new();
+    let stdin = std::io::stdin();
+    let mut input = stdin.lock();
+    for (i, region) in pending.regions.iter().enumerate() {
+        eprintln!();
+        eprintln!("[{}/{}] {}", i + 1, total, describe(region, &prompts));
+        let content = repo
+            .get_file_content(&region.file, &pending.head)
+            .map(|content| String::from_utf8_lossy(&content).to_string())
+            .unwrap_or_default();
+        let lines: Vec<&str> = content
+            .lines()
+            .skip(region.start_line as usize - 1)
+            .take((region.end_line - region.start_line + 1) as usize)
+            .collect();
+        for (offset, line) in lines.iter().take(PREVIEW_LINES).enumerate() {
+            eprintln!("  {:>5} | {}", region.start_line as usize + offset, line);
+        }
+        if lines.len() > PREVIEW_LINES {
+            eprintln!("        ... {} more lines", lines.len() - PREVIEW_LINES);
+        }
+
+        let choices = if region.candidates.len() == 1 {
+            "[a]i, [h]uman, [s]kip, [q]uit".to_string()
+        } else {
+            format!(
+                "session hash ({}), [h]uman, [s]kip, [q]uit",
+                region.candidates.join("/")
+            )
+        };
+        loop {
+            eprint!("Assign to {}? ", choices);
+            std::io::stderr().flush()?;
+            let mut answer = String::new();
+            if input.read_line(&mut answer)? == 0 {
+                return apply_decisions(repo, pending, &decisions);
+            }
+            match answer.trim() {
+                "s" | "skip" | "" => break,
+                "q" | "quit" => return apply_decisions(repo, pending, &decisions),
+                target => match parse_decision(target, region) {
+                    Ok(decision) => {
+                        decisions.insert(i, decision);
+                        break;
+                    }
+                    Err(e) => eprintln!("{}", e),
+                },
+            }
+        }
+    }
+    apply_decisions(repo, pending, &decisions)
+}
+
+/// Write the decisions into the rebased commits' notes and drop the regions
+/// they settle from the pending list
+fn apply_decisions(
+    repo: &Repository,
+    pending: &mut PendingResolution,
+    decisions: &BTreeMap<usize, Decision>,
+) -> Result<(), GitAiError> {
+    if decisions.is_empty() {
+        return Ok(());
+    }
+    let options = GitAiBlameOptions {
+        newest_commit: Some(pending.head.clone()),
+        ..Default::default()
+    };
+    let head_prompts = head_prompts(repo, pending);
+    let mut updated = BTreeSet::new();
+    for (index, decision) in decisions {
+        let region = &pending.regions[*index];
+        // Map HEAD line numbers back to the rebased commits that introduced them
+        let mut lines_by_commit: BTreeMap<String, BTreeSet<u32>> = BTreeMap::new();
+        for hunk in repo.blame_hunks(&region.file, region.start_line, region.end_line, &options)? {
+            if !pending.commits.contains(&hunk.commit_sha) {
+                continue;
+            }
+            lines_by_commit
+                .entry(hunk.commit_sha.clone())
+                .or_default()
+                .extend(hunk.orig_range.0..=hunk.orig_range.1);
+        }
+
+        for (commit, lines) in &lines_by_commit {
+            let Some(mut log) = get_authorship(repo, commit) else {
+                continue;
+            };
+            let hash = match decision {
+                Decision::Ai(hash) => {
+                    if !log.metadata.prompts.contains_key(hash) {
+                        let Some(prompt) = head_prompts.get(hash) else {
+                            continue;
+                        };
+                        log.metadata.prompts.insert(hash.clone(), prompt.clone());
+                    }
+                    Some(hash.as_str())
+                }
+                Decision::Human => None,
+            };
+            assign_lines(&mut log, &region.file, lines, hash);
+            let note = log.serialize_to_string().map_err(|_| {
+                GitAiError::Generic("Failed to serialize authorship log".to_string())
+            })?;
+            notes_add(repo, commit, &note)?;
+            updated.insert(commit.clone());
+        }
+    }
+
+    let resolved = decisions.len();
+    let mut index = 0;
+    pending.regions.retain(|_| {
+        let keep = !decisions.contains_key(&index);
+        index += 1;
+        keep
+    });
+    if pending.regions.is_empty() {
+        clear_pending(&repo.storage);
+    } else {
+        save_pending(&repo.storage, pending)?;
+    }
+    eprintln!(
+        "Resolved {} region{} ({} commit{} updated), {} left",
+        resolved,
+        if resolved == 1 { "" } else { "s" },
+        updated.len(),
+        if updated.len() == 1 { "" } else { "s" },
+        pending.regions.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args_and_decisions() {
+        let parsed = parse_args(&args(&["--assign", "1=ai", "--assign", "2=human"])).unwrap();
+        assert_eq!(
+            parsed.assignments,
+            vec![(1, "ai".to_string()), (2, "human".to_string())]
+        );
+        assert!(parse_args(&args(&["--assign", "0=ai"])).is_err());
+        assert!(parse_args(&args(&["--assign", "ai"])).is_err());
+
+        let mut region = AmbiguousRegion {
+            file: "a.rs".to_string(),
+            start_line: 3,
+            end_line: 5,
+            candidates: vec!["abc1234".to_string()],
+        };
+        assert_eq!(
+            parse_decision("ai", &region),
+            Ok(Decision::Ai("abc1234".to_string()))
+        );
+        assert_eq!(parse_decision("h", &region), Ok(Decision::Human));
+
+        // With several sessions the hash (or a prefix of it) picks one
+        region.candidates.push("def5678".to_string());
+        assert!(parse_decision("ai", &region).is_err());
+        assert_eq!(
+            parse_decision("def", &region),
+            Ok(Decision::Ai("def5678".to_string()))
+        );
+        assert!(parse_decision("fff", &region).is_err());
+    }
+}
@@ -0,0 +1,63 @@
+use crate::authorship::restack::{RestackReport, remap_restacks};
+use crate::commands::output;
+use crate::error::{GitAiError, exit_with_error};
+use crate::git::find_repository;
+
+/// Handle the `restack` command
+///
+/// Usage: git-ai restack [--dry-run]
+///
+/// Copies authorship logs onto commits that git-branchless, jj, Graphite or
+/// any other tool rewrote without going through git-ai. The push hook does
+/// the same before pushing notes when one of those tools is set up.
+pub fn handle_restack(args: &[String]) {
+    let mut dry_run = false;
+    for arg in args {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            other => exit_with_error(
+                "Error",
+                &GitAiError::InvalidArguments(format!("Unknown restack argument: {}", other)),
+            ),
+        }
+    }
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => exit_with_error("Failed to find repository", &e),
+    };
+    let report = match remap_restacks(&repo, None, dry_run) {
+        Ok(report) => report,
+        Err(e) => exit_with_error("Failed to remap authorship", &e),
+    };
+
+    if output::json_requested(false) {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).unwrap_or_default()
+        );
+    } else {
+        print_report(&report, dry_run);
+    }
+}
+
+fn print_report(report: &RestackReport, dry_run: bool) {
+    if !report.tools.is_empty() {
+        let names: Vec<&str> = report.tools.iter().map(|tool| tool.name()).collect();
+        println!("Stack tools: {}", names.join(", "));
+    }
+    let verb = if dry_run { "Would copy" } else { "Copied" };
+    println!(
+        "{} {} authorship logs across {} history rewrites",
+        verb,
+        report.remapped.len(),
+        report.rewrites
+    );
+    for (original, rewritten) in &report.remapped {
+        println!(
+            "  {} -> {}",
+            &original[..original.len().min(8)],
+            &rewritten[..rewritten.len().min(8)]
+        );
+    }
+}
@@ -0,0 +1,483 @@
+use crate::authorship::imara_diff_utils::{DiffOp, capture_diff_slices};
+use crate::authorship::review::{ai_lines_by_file, reviewed_lines};
+use crate::authorship::stats::stats_for_commit_stats;
+use crate::commands::blame::GitAiBlameOptions;
+use crate::commands::output;
+use crate::config::Config;
+use crate::error::{GitAiError, exit_with_error};
+use crate::git::find_repository;
+use crate::git::refs::get_authorship;
+use crate::git::repository::{Repository, exec_git};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+
+/// Commits looked back through when `--commits` isn't given
+const DEFAULT_COMMITS: usize = 20;
+/// Unchanged lines around each hunk of the patch
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct RevertSuggestArgs {
+    pub file: String,
+    pub commits: usize,
+    /// Suggest every unreviewed AI hunk, not only those breaking the policy
+    pub unreviewed: bool,
+}
+
+pub fn parse_args(args: &[String]) -> Result<RevertSuggestArgs, String> {
+    let mut parsed = RevertSuggestArgs {
+        commits: DEFAULT_COMMITS,
+        ..Default::default()
+    };
+    let mut file = None;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--commits" => {
+                parsed.commits = args
+                    .get(i + 1)
+                    .and_then(|n| n.parse().ok())
+                    .filter(|n| *n > 0)
+                    .ok_or("--commits requires a number of commits")?;
+                i += 1;
+            }
+            "--unreviewed" => parsed.unreviewed = true,
+            other if other.starts_with("--") => {
+                return Err(format!("Unknown option: {}", other));
+            }
+            other if file.is_none() => file = Some(other.to_string()),
+            other => return Err(format!("Unexpected argument: {}", other)),
+        }
+        i += 1;
+    }
+    let file = file.ok_or("Missing <file>")?;
+    parsed.file = file.strip_prefix("./").unwrap_or(&file).to_string();
+    Ok(parsed)
+}
+
+/// Why a commit's AI lines are suggested, and the unreviewed ones in the
+/// commit's own numbering
+type CommitCandidates = (Vec<String>, BTreeSet<u32>);
+
+/// A commit's diff of the file, with the parent's lines it indexes into
+type CommitDiff = (Vec<DiffOp>, Vec<String>);
+
+/// Lines `start..=end` of the working tree file, to be replaced by
+/// `replacement`. Lines keep their line endings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Revert {
+    pub start: u32,
+    pub end: u32,
+    pub replacement: Vec<String>,
+    pub commit: String,
+    /// Policy rules the introducing commit breaks, or `unreviewed`
+    pub reasons: Vec<String>,
+}
+
+/// Handle the `revert-suggest` command
+///
+/// Usage: git-ai revert-suggest <file> [--commits <n>] [--unreviewed]
+///
+/// Finds the unreviewed AI-authored hunks of `<file>` that the last `n`
+/// commits introduced and that break the repository policy (too large an AI
+/// share, or `require_ai_review`), and prints a patch reverting just those
+/// hunks in the working tree, ready for `git apply`. Hunks edited since HEAD
+/// are left alone.
+pub fn handle_revert_suggest(args: &[String]) {
+    let args = match parse_args(args) {
+        Ok(args) => args,
+        Err(e) => exit_with_error(
+            "Error",
+            &GitAiError::InvalidArguments(format!(
+                "{}\nUsage: git-ai revert-suggest <file> [--commits <n>] [--unreviewed]",
+                e
+            )),
+        ),
+    };
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => exit_with_error("Failed to find repository", &e),
+    };
+    // The file is given relative to the current directory, patches name it
+    // from the repository root
+    let file = std::env::current_dir()
+        .map_err(GitAiError::IoError)
+        .and_then(|cwd| repo.repo_relative_path(&cwd.join(&args.file).to_string_lossy()));
+    let args = match file {
+        Ok(file) => RevertSuggestArgs { file, ..args },
+        Err(e) => exit_with_error("Error", &e),
+    };
+    let (working_lines, reverts) = match suggest_reverts(&repo, &args) {
+        Ok(result) => result,
+        Err(e) => exit_with_error("revert-suggest failed", &e),
+    };
+
+    let patch = build_patch(&args.file, &working_lines, &reverts);
+    if output::json_requested(false) {
+        let json = serde_json::json!({
+            "file": args.file,
+            "hunks": reverts,
+            "patch": patch,
+        });
+        println!("{}", serde_json::to_string_pretty(&json).unwrap());
+        return;
+    }
+    if reverts.is_empty() {
+        eprintln!("No AI-authored hunks to revert in {}", args.file);
+        return;
+    }
+    for revert in &reverts {
+        eprintln!(
+            "Lines {}-{} from {} ({})",
+            revert.start,
+            revert.end,
+            &revert.commit[..revert.commit.len().min(8)],
+            revert.reasons.join(", ")
+        );
+    }
+    print!("{}", patch);
+}
+
+/// The working tree's lines of the file and the hunks to revert in them
+fn suggest_reverts(
+    repo: &Repository,
+    args: &RevertSuggestArgs,
+) -> Result<(Vec<String>, Vec<Revert>), GitAiError> {
+    let file = &args.file;
+    let working_lines = match std::fs::read(repo.workdir()?.join(file)) {
+        Ok(content) => split_lines(&content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(GitAiError::IoError(e)),
+    };
+    let head_lines = file_lines(repo, file, "HEAD")?;
+    if head_lines.is_empty() || working_lines.is_empty() {
+        return Ok((working_lines, Vec::new()));
+    }
+    // Blame is of HEAD; uncommitted edits move lines around or replace them
+    let uncommitted = capture_diff_slices(&head_lines, &working_lines);
+
+    let mut rev_list = repo.global_args_for_exec();
+    rev_list.extend([
+        "rev-list".to_string(),
+        format!("--max-count={}", args.commits),
+        "HEAD".to_string(),
+    ]);
+    let recent: BTreeSet<String> = String::from_utf8(exec_git(&rev_list)?.stdout)?
+        .lines()
+        .map(str::to_string)
+        .collect();
+
+    let policy = Config::get().policy();
+    let mut candidates: HashMap<String, Option<CommitCandidates>> = HashMap::new();
+    let mut reverts = Vec::new();
+    let options = GitAiBlameOptions {
+        newest_commit: Some("HEAD".to_string()),
+        ..Default::default()
+    };
+    for hunk in repo.blame_hunks(file, 1, head_lines.len() as u32, &options)? {
+        if !recent.contains(&hunk.commit_sha) {
+            continue;
+        }
+        if !candidates.contains_key(&hunk.commit_sha) {
+            let found = get_authorship(repo, &hunk.commit_sha).and_then(|log| {
+                let mut reasons = Vec::new();
+                let stats = stats_for_commit_stats(repo, &hunk.commit_sha, &[]).ok()?;
+                if let Some(violation) =
+                    policy.check_ai_share(stats.ai_additions, stats.git_diff_added_lines)
+                {
+                    reasons.push(violation.rule.to_string());
+                }
+                if policy.require_ai_review {
+                    reasons.push("require_ai_review".to_string());
+                }
+                if reasons.is_empty() && args.unreviewed {
+                    reasons.push("unreviewed".to_string());
+                }
+                if reasons.is_empty() {
+                    return None;
+                }
+                let reviewed = reviewed_lines(&log, file);
+                let lines: BTreeSet<u32> = ai_lines_by_file(&log)
+                    .remove(file)?
+                    .difference(&reviewed)
+                    .copied()
+                    .collect();
+                Some((reasons, lines))
+            });
+            candidates.insert(hunk.commit_sha.clone(), found);
+        }
+        let Some(Some((reasons, lines))) = candidates.get(&hunk.commit_sha) else {
+            continue;
+        };
+
+        // Runs of candidate lines within the hunk, as (HEAD, commit) line pairs
+        let mut run: Vec<(u32, u32)> = Vec::new();
+        let mut runs = Vec::new();
+        for offset in 0..=(hunk.range.1 - hunk.range.0) {
+            let orig = hunk.orig_range.0 + offset;
+            if lines.contains(&orig) {
+                run.push((hunk.range.0 + offset, orig));
+            } else if !run.is_empty() {
+                runs.push(std::mem::take(&mut run));
+            }
+        }
+        if !run.is_empty() {
+            runs.push(run);
+        }
+        if runs.is_empty() {
+            continue;
+        }
+
+        let ops = commit_diff(repo, file, &hunk.commit_sha)?;
+        for run in runs {
+            let (head_start, orig_start) = run[0];
+            let (head_end, orig_end) = run[run.len() - 1];
+            let Some((start, end)) = working_range(&uncommitted, head_start, head_end) else {
+                continue;
+            };
+            let mut replacement = replaced_lines(&ops, orig_start, orig_end);
+            // The commit's parent may have ended the file on a restored line
+            if end < working_lines.len() as u32
+                && let Some(last) = replacement.last_mut()
+                && !last.ends_with('\n')
+            {
+                last.push('\n');
+            }
+            reverts.push(Revert {
+                start,
+                end,
+                replacement,
+                commit: hunk.commit_sha.clone(),
+                reasons: reasons.clone(),
+            });
+        }
+    }
+    reverts.sort_by_key(|revert| revert.start);
+    Ok((working_lines, reverts))
+}
+
+/// The file's lines, each with its line ending
+fn split_lines(content: &[u8]) -> Vec<String> {
+    String::from_utf8_lossy(content)
+        .split_inclusive('\n')
+        .map(str::to_string)
+        .collect()
+}
+
+fn file_lines(repo: &Repository, file: &str, rev: &str) -> Result<Vec<String>, GitAiError> {
+    Ok(split_lines(&repo.get_file_content(file, rev)?))
+}
+
+/// Where HEAD's lines `start..=end` (1-indexed) are in the working tree, if
+/// they're all still there, unedited and together
+pub fn working_range(uncommitted: &[DiffOp], start: u32, end: u32) -> Option<(u32, u32)> {
+    let (start, end) = (start as usize - 1, end as usize);
+    uncommitted.iter().find_map(|op| match op {
+        DiffOp::Equal {
+            old_index,
+            new_index,
+            len,
+        } if *old_index <= start && end <= old_index + len => {
+            let shift = *new_index as isize - *old_index as isize;
+            Some((
+                (start as isize + shift + 1) as u32,
+                (end as isize + shift) as u32,
+            ))
+        }
+        _ => None,
+    })
+}
+
+/// What `commit` did to `file`, with the parent's lines for restoring them
+fn commit_diff(repo: &Repository, file: &str, commit: &str) -> Result<CommitDiff, GitAiError> {
+    let old = match repo.find_commit(commit.to_string())?.parent(0) {
+        Ok(parent) => file_lines(repo, file, &parent.id()).unwrap_or_default(),
+        Err(_) => Vec::new(),
+    };
+    let new = file_lines(repo, file, commit)?;
+    Ok((capture_diff_slices(&old, &new), old))
+}
+
+/// The lines the commit replaced with its lines `start..=end` (1-indexed).
+/// Only replacements that fall entirely inside the range are restored; lines
+/// the commit inserted are simply dropped.
+pub fn replaced_lines(diff: &CommitDiff, start: u32, end: u32) -> Vec<String> {
+    let (ops, old) = diff;
+    let (start, end) = (start as usize - 1, end as usize);
+    ops.iter()
+        .filter_map(|op| match op {
+            DiffOp::Replace {
+                old_index,
+                old_len,
+                new_index,
+                new_len,
+            } if *new_index >= start && new_index + new_len <= end => {
+                Some(old[*old_index..old_index + old_len].to_vec())
+            }
+            _ => None,
+        })
+        .flatten()
+        .collect()
+}
+
+/// A unified diff from `lines` to the file with `reverts` applied
+pub fn build_patch(file: &str, lines: &[String], reverts: &[Revert]) -> String {
+    if reverts.is_empty() {
+        return String::new();
+    }
+    let mut patch = format!("--- a/{}\n+++ b/{}\n", file, file);
+    // Reverts close enough to share context go in one hunk
+    let mut groups: Vec<Vec<&Revert>> = Vec::new();
+    for revert in reverts {
+        match groups.last_mut() {
+            Some(group)
+                if revert.start as usize
+                    <= group.last().unwrap().end as usize + 2 * CONTEXT_LINES + 1 =>
+            {
+                group.push(revert)
+            }
+            _ => groups.push(vec![revert]),
+        }
+    }
+
+    // Lines the reverts before this hunk added (negative: removed)
+    let mut shift: isize = 0;
+    for group in groups {
+        let first = group[0].start as usize;
+        let last = group.last().unwrap().end as usize;
+        let from = first.saturating_sub(CONTEXT_LINES + 1);
+        let to = (last + CONTEXT_LINES).min(lines.len());
+
+        let mut body = String::new();
+        let (mut old_len, mut new_len) = (0, 0);
+        let mut line = from;
+        for revert in &group {
+            for context in &lines[line..revert.start as usize - 1] {
+                push_patch_line(&mut body, ' ', context);
+            }
+            let context = revert.start as usize - 1 - line;
+            old_len += context;
+            new_len += context;
+            for removed in &lines[revert.start as usize - 1..revert.end as usize] {
+                push_patch_line(&mut body, '-', removed);
+                old_len += 1;
+            }
+            for added in &revert.replacement {
+                push_patch_line(&mut body, '+', added);
+                new_len += 1;
+            }
+            line = revert.end as usize;
+        }
+        for context in &lines[line..to] {
+            push_patch_line(&mut body, ' ', context);
+        }
+        old_len += to - line;
+        new_len += to - line;
+
+        let old_start = if old_len == 0 { from } else { from + 1 };
+        let new_start = (from as isize + shift) as usize + usize::from(new_len > 0);
+        patch.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n{}",
+            old_start, old_len, new_start, new_len, body
+        ));
+        shift += new_len as isize - old_len as isize;
+    }
+    patch
+}
+
+/// One line of a hunk, keeping its line ending (`\r\n` stays in the patch)
+fn push_patch_line(body: &mut String, prefix: char, line: &str) {
+    body.push(prefix);
+    body.push_str(line);
+    if !line.ends_with('\n') {
+        body.push_str("\n\\ No newline at end of file\n");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn numbered(count: usize) -> Vec<String> {
+        (1..=count).map(|n| format!("{}\n", n)).collect()
+    }
+
+    fn revert(start: u32, end: u32, replacement: &[&str]) -> Revert {
+        Revert {
+            start,
+            end,
+            replacement: lines(replacement),
+            commit: "abc".to_string(),
+            reasons: vec!["unreviewed".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let parsed = parse_args(&lines(&["./src/lib.rs", "--commits", "5"])).unwrap();
+        assert_eq!(parsed.file, "src/lib.rs");
+        assert_eq!(parsed.commits, 5);
+        assert!(!parsed.unreviewed);
+        assert_eq!(
+            parse_args(&lines(&["a.rs", "--unreviewed"]))
+                .unwrap()
+                .commits,
+            DEFAULT_COMMITS
+        );
+        assert!(parse_args(&lines(&[])).is_err());
+        assert!(parse_args(&lines(&["a.rs", "--commits", "0"])).is_err());
+        assert!(parse_args(&lines(&["a.rs", "b.rs"])).is_err());
+    }
+
+    #[test]
+    fn test_replaced_lines_only_restores_whole_replacements() {
+        let old = lines(&["a", "b", "c"]);
+        let new = lines(&["a", "B", "x", "c", "y"]);
+        let diff = (capture_diff_slices(&old, &new), old);
+        // "B" replaced "b", "x" and "y" are insertions
+        assert_eq!(replaced_lines(&diff, 2, 3), lines(&["b"]));
+        assert!(replaced_lines(&diff, 5, 5).is_empty());
+    }
+
+    #[test]
+    fn test_working_range() {
+        let head = numbered(6);
+        let mut working = head.clone();
+        working.insert(0, "new\n".to_string());
+        working[4] = "edited\n".to_string();
+        let uncommitted = capture_diff_slices(&head, &working);
+        assert_eq!(working_range(&uncommitted, 1, 2), Some((2, 3)));
+        assert_eq!(working_range(&uncommitted, 5, 6), Some((6, 7)));
+        // Line 4 was edited after HEAD
+        assert_eq!(working_range(&uncommitted, 3, 4), None);
+    }
+
+    #[test]
+    fn test_build_patch() {
+        let head = numbered(12);
+        let patch = build_patch(
+            "src/a.rs",
+            &head,
+            &[revert(2, 2, &["two\n"]), revert(11, 12, &[])],
+        );
+        assert_eq!(
+            patch,
+            "--- a/src/a.rs\n+++ b/src/a.rs\n\
+             @@ -1,5 +1,5 @@\n 1\n-2\n+two\n 3\n 4\n 5\n\
+             @@ -8,5 +8,3 @@\n 8\n 9\n 10\n-11\n-12\n"
+        );
+        assert_eq!(build_patch("src/a.rs", &head, &[]), "");
+
+        // Line endings and a missing final newline are kept
+        let crlf = lines(&["1\r\n", "2\r\n", "3"]);
+        assert_eq!(
+            build_patch("a.txt", &crlf, &[revert(3, 3, &["three\r\n"])]),
+            "--- a/a.txt\n+++ b/a.txt\n\
+             @@ -1,3 +1,3 @@\n 1\r\n 2\r\n-3\n\\ No newline at end of file\n+three\r\n"
+        );
+    }
+}
@@ -0,0 +1,290 @@
+use crate::authorship::review::add_review;
+use crate::authorship::stats::stats_for_commit_stats;
+use crate::commands::blame::GitAiBlameOptions;
+use crate::config::Config;
+use crate::error::{GitAiError, exit_with_error};
+use crate::git::find_repository;
+use crate::git::refs::{get_authorship, notes_add};
+use crate::git::repository::{Repository, exec_git};
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Handle the `review` command
+///
+/// Usage: git-ai review mark <file>[:<lines>] [--reviewed-by <name>]
+///        git-ai review check [<commit>|<range>]
+pub fn handle_review(args: &[String]) {
+    let Some(subcommand) = args.first() else {
+        print_usage_and_exit();
+    };
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => exit_with_error("Failed to find repository", &e),
+    };
+
+    let result = match subcommand.as_str() {
+        "mark" => match parse_mark_args(&args[1..]) {
+            Ok(mark) => run_mark(&repo, &mark),
+            Err(e) => exit_with_error("Error", &GitAiError::InvalidArguments(e)),
+        },
+        "check" => run_check(&repo, args.get(1).map(String::as_str).unwrap_or("HEAD")),
+        _ => print_usage_and_exit(),
+    };
+    if let Err(e) = result {
+        exit_with_error(&format!("review {} failed", subcommand), &e);
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct MarkArgs {
+    pub file: String,
+    /// Line numbers at HEAD; the whole file when empty
+    pub lines: BTreeSet<u32>,
+    pub reviewer: Option<String>,
+}
+
+/// Parse `10`, `10-20` or a comma-separated list of both
+pub fn parse_line_spec(spec: &str) -> Result<BTreeSet<u32>, String> {
+    let mut lines = BTreeSet::new();
+    for part in spec.split(',').map(str::trim) {
+        let parse = |n: &str| {
+            n.trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or_else(|| format!("Invalid line number in '{}'", spec))
+        };
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (parse(start)?, parse(end)?);
+                if start > end {
+                    return Err(format!("Invalid line range '{}'", part));
+                }
+                lines.extend(start..=end);
+            }
+            None => {
+                lines.insert(parse(part)?);
+            }
+        }
+    }
+    Ok(lines)
+}
+
+pub fn parse_mark_args(args: &[String]) -> Result<MarkArgs, String> {
+    let mut parsed = MarkArgs::default();
+    let mut target = None;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--reviewed-by" => {
+                let name = args
+                    .get(i + 1)
+                    .filter(|name| !name.trim().is_empty())
+                    .ok_or("--reviewed-by requires a name")?;
+                parsed.reviewer = Some(name.trim().to_string());
+                i += 1;
+            }
+            other if other.starts_with("--") => {
+                return Err(format!("Unknown option: {}", other));
+            }
+            other if target.is_none() => target = Some(other.to_string()),
+            other => return Err(format!("Unexpected argument: {}", other)),
+        }
+        i += 1;
+    }
+
+    let target = target.ok_or("Missing <file>[:<lines>]")?;
+    // Only treat the suffix as lines when it looks like one, so paths with
+    // colons still work
+    let (file, lines) = match target.rsplit_once(':') {
+        Some((file, spec))
+            if !spec.is_empty()
+                && spec
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || matches!(c, '-' | ',' | ' ')) =>
+        {
+            (file.to_string(), parse_line_spec(spec)?)
+        }
+        _ => (target.clone(), BTreeSet::new()),
+    };
+    parsed.file = file.strip_prefix("./").unwrap_or(&file).to_string();
+    parsed.lines = lines;
+    Ok(parsed)
+}
+
+fn head_line_count(repo: &Repository, file: &str) -> Result<u32, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(["show".to_string(), format!("HEAD:{}", file)]);
+    let content = exec_git(&args)?.stdout;
+    Ok(String::from_utf8_lossy(&content).lines().count() as u32)
+}
+
+fn relative_path(repo: &Repository, file: &str) -> Result<String, GitAiError> {
+    let path = std::path::Path::new(file);
+    if !path.is_absolute() {
+        return Ok(file.to_string());
+    }
+    let workdir = repo.workdir()?;
+    let canonical = path.canonicalize()?;
+    let relative = canonical
+        .strip_prefix(workdir.canonicalize()?)
+        .map_err(|_| GitAiError::Generic(format!("{} is outside the repository", file)))?;
+    Ok(crate::utils::normalize_to_posix(
+        &relative.to_string_lossy(),
+    ))
+}
+
+fn run_mark(repo: &Repository, args: &MarkArgs) -> Result<(), GitAiError> {
+    let file = relative_path(repo, &args.file)?;
+    let reviewer = match &args.reviewer {
+        Some(reviewer) => reviewer.clone(),
+        None => repo
+            .config_get_str("user.name")?
+            .filter(|name| !name.trim().is_empty())
+            .ok_or_else(|| {
+                GitAiError::InvalidArguments(
+                    "Pass --reviewed-by <name> (git user.name is not set)".to_string(),
+                )
+            })?,
+    };
+
+    let (start, end) = match (args.lines.first(), args.lines.last()) {
+        (Some(start), Some(end)) => (*start, *end),
+        _ => (1, head_line_count(repo, &file)?),
+    };
+    if end == 0 {
+        return Err(GitAiError::Generic(format!("{} is empty at HEAD", file)));
+    }
+
+    // Map HEAD line numbers back to the commits that introduced them
+    let options = GitAiBlameOptions {
+        newest_commit: Some("HEAD".to_string()),
+        ..Default::default()
+    };
+    let mut lines_by_commit: BTreeMap<String, BTreeSet<u32>> = BTreeMap::new();
+    for hunk in repo.blame_hunks(&file, start, end, &options)? {
+        for line in hunk.range.0..=hunk.range.1 {
+            if args.lines.is_empty() || args.lines.contains(&line) {
+                lines_by_commit
+                    .entry(hunk.commit_sha.clone())
+                    .or_default()
+                    .insert(hunk.orig_range.0 + (line - hunk.range.0));
+            }
+        }
+    }
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let mut marked = 0;
+    let mut commits = 0;
+    for (commit, lines) in &lines_by_commit {
+        let Some(mut log) = get_authorship(repo, commit) else {
+            continue;
+        };
+        let covered = add_review(&mut log, &file, lines, &reviewer, timestamp);
+        if covered == 0 {
+            continue;
+        }
+        let note = log
+            .serialize_to_string()
+            .map_err(|_| GitAiError::Generic("Failed to serialize authorship log".to_string()))?;
+        notes_add(repo, commit, &note)?;
+        marked += covered;
+        commits += 1;
+    }
+
+    if marked == 0 {
+        println!("No AI-authored lines to review in {}", file);
+    } else {
+        println!(
+            "Marked {} AI line{} in {} as reviewed by {} ({} commit{})",
+            marked,
+            if marked == 1 { "" } else { "s" },
+            file,
+            reviewer,
+            commits,
+            if commits == 1 { "" } else { "s" }
+        );
+    }
+    Ok(())
+}
+
+fn run_check(repo: &Repository, spec: &str) -> Result<(), GitAiError> {
+    let commits = if spec.contains("..") {
+        let mut args = repo.global_args_for_exec();
+        args.extend([
+            "rev-list".to_string(),
+            "--reverse".to_string(),
+            spec.to_string(),
+        ]);
+        String::from_utf8(exec_git(&args)?.stdout)?
+            .lines()
+            .map(str::to_string)
+            .collect()
+    } else {
+        vec![repo.revparse_single(spec)?.id()]
+    };
+
+    let (mut reviewed, mut ai) = (0, 0);
+    for commit in &commits {
+        let stats = stats_for_commit_stats(repo, commit, &[])?;
+        if stats.ai_additions == 0 {
+            continue;
+        }
+        println!(
+            "{}  {}/{} AI lines reviewed",
+            &commit[..commit.len().min(8)],
+            stats.reviewed_ai_additions,
+            stats.ai_additions
+        );
+        reviewed += stats.reviewed_ai_additions;
+        ai += stats.ai_additions;
+    }
+    println!("Total: {}/{} AI lines reviewed", reviewed, ai);
+
+    if let Some(violation) = Config::get().policy().check_ai_review(reviewed, ai) {
+        exit_with_error(
+            "Error",
+            &GitAiError::PolicyViolation(violation.message.clone()),
+        );
+    }
+    Ok(())
+}
+
+fn print_usage_and_exit() -> ! {
+    eprintln!("git-ai review - Record human review of AI-authored code");
+    eprintln!();
+    eprintln!("Usage:");
+    eprintln!("  git-ai review mark <file>[:<lines>] [--reviewed-by <name>]");
+    eprintln!("  git-ai review check [<commit>|<range>]");
+    std::process::exit(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_mark_args() {
+        let parsed =
+            parse_mark_args(&args(&["./src/lib.rs:3-5,9", "--reviewed-by", "alice"])).unwrap();
+        assert_eq!(parsed.file, "src/lib.rs");
+        assert_eq!(parsed.lines, [3, 4, 5, 9].into_iter().collect());
+        assert_eq!(parsed.reviewer.as_deref(), Some("alice"));
+
+        let whole_file = parse_mark_args(&args(&["docs/a:b.md"])).unwrap();
+        assert_eq!(whole_file.file, "docs/a:b.md");
+        assert!(whole_file.lines.is_empty());
+
+        assert!(parse_mark_args(&args(&[])).is_err());
+        assert!(parse_mark_args(&args(&["a.rs:5-2"])).is_err());
+        assert!(parse_mark_args(&args(&["a.rs:0"])).is_err());
+        assert!(parse_mark_args(&args(&["a.rs", "--reviewed-by"])).is_err());
+    }
+}
@@ -0,0 +1,254 @@
+use crate::authorship::stats::{CommitStats, stats_for_commit_stats};
+use crate::config::Config;
+use crate::error::{GitAiError, exit_with_error};
+use crate::git::find_repository;
+use crate::git::refs::get_authorship;
+use crate::git::repository::{Repository, exec_git};
+use crate::utils::current_git_ai_exe;
+use std::io::Read;
+use std::process::Command;
+
+const WARNING: &str = "\
+WARNING: rewrite-trailers rewrites history with `git filter-branch`.
+  - Every commit in the range gets a new SHA; anyone who has fetched them must re-clone or rebase.
+  - Authorship notes stay attached to the old SHAs and will not follow the rewritten commits.
+  - Tags and other branches pointing into the range are left on the old history.
+Use it once, for archival, right before a repository stops using git-ai (e.g. before open-sourcing).";
+
+/// Handle the `rewrite-trailers` command
+///
+/// Usage: git-ai rewrite-trailers <range> [--yes]
+///
+/// Without `--yes` this only previews the trailers each commit would get.
+pub fn handle_rewrite_trailers(args: &[String]) {
+    // Internal: invoked by `git filter-branch --msg-filter` for each commit
+    if args.first().map(String::as_str) == Some("--msg-filter") {
+        if let Err(e) = run_msg_filter() {
+            exit_with_error("rewrite-trailers message filter failed", &e);
+        }
+        return;
+    }
+
+    let (range, confirmed) = match parse_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => exit_with_error("Error", &GitAiError::InvalidArguments(e)),
+    };
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => exit_with_error("Failed to find repository", &e),
+    };
+
+    eprintln!("{}", WARNING);
+    eprintln!();
+    let result = if confirmed {
+        rewrite(&repo, &range)
+    } else {
+        preview(&repo, &range)
+    };
+    if let Err(e) = result {
+        exit_with_error("rewrite-trailers failed", &e);
+    }
+}
+
+pub fn parse_args(args: &[String]) -> Result<(String, bool), String> {
+    let mut range = None;
+    let mut confirmed = false;
+    for arg in args {
+        match arg.as_str() {
+            "--yes" | "-y" => confirmed = true,
+            other if other.starts_with('-') => return Err(format!("Unknown option: {}", other)),
+            other if range.is_none() => range = Some(other.to_string()),
+            _ => return Err("rewrite-trailers accepts exactly one range".to_string()),
+        }
+    }
+    let range = range.ok_or("rewrite-trailers requires a range, e.g. main~20..main")?;
+    Ok((range, confirmed))
+}
+
+/// `AI-Lines` and `AI-Tools` trailers summarising a commit's authorship note
+pub fn attribution_trailers(stats: &CommitStats) -> Vec<String> {
+    let percent = if stats.git_diff_added_lines == 0 {
+        0
+    } else {
+        (stats.ai_additions as u64 * 100 / stats.git_diff_added_lines as u64) as u32
+    };
+    let mut trailers = vec![format!(
+        "AI-Lines: {}/{} ({}%)",
+        stats.ai_additions, stats.git_diff_added_lines, percent
+    )];
+    let tools: Vec<String> = stats
+        .tool_model_breakdown
+        .iter()
+        .filter(|(_, tool)| tool.ai_additions > 0)
+        .map(|(key, _)| key.replace("::", "/"))
+        .collect();
+    if !tools.is_empty() {
+        trailers.push(format!("AI-Tools: {}", tools.join(", ")));
+    }
+    trailers
+}
+
+/// Append trailers to a commit message, joining an existing trailer block
+pub fn append_trailers(message: &str, trailers: &[String]) -> String {
+    if trailers.is_empty() {
+        return message.to_string();
+    }
+    let body = message.trim_end();
+    let last_paragraph = body.rsplit("\n\n").next().unwrap_or("");
+    let ends_with_trailers = body.contains("\n\n")
+        && last_paragraph.lines().all(|line| {
+            line.split_once(": ")
+                .is_some_and(|(key, _)| !key.is_empty() && !key.contains(' '))
+        });
+    let separator = if ends_with_trailers { "\n" } else { "\n\n" };
+    format!("{}{}{}\n", body, separator, trailers.join("\n"))
+}
+
+fn trailers_for_commit(repo: &Repository, sha: &str) -> Result<Vec<String>, GitAiError> {
+    if get_authorship(repo, sha).is_none() {
+        return Ok(Vec::new());
+    }
+    Ok(attribution_trailers(&stats_for_commit_stats(
+        repo,
+        sha,
+        &[],
+    )?))
+}
+
+fn range_commits(repo: &Repository, range: &str) -> Result<Vec<(String, String)>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("log".to_string());
+    args.push("--reverse".to_string());
+    args.push("--format=%H %s".to_string());
+    args.push(range.to_string());
+    let output = exec_git(&args)?;
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(sha, subject)| (sha.to_string(), subject.to_string()))
+        .collect())
+}
+
+fn preview(repo: &Repository, range: &str) -> Result<(), GitAiError> {
+    let commits = range_commits(repo, range)?;
+    let mut annotated = 0;
+    for (sha, subject) in &commits {
+        let trailers = trailers_for_commit(repo, sha)?;
+        if trailers.is_empty() {
+            continue;
+        }
+        annotated += 1;
+        println!("{} {}", &sha[..sha.len().min(8)], subject);
+        for trailer in trailers {
+            println!("    {}", trailer);
+        }
+    }
+    println!();
+    println!(
+        "{} of {} commit(s) would get trailers. Re-run with --yes to rewrite {}.",
+        annotated,
+        commits.len(),
+        range
+    );
+    Ok(())
+}
+
+fn rewrite(repo: &Repository, range: &str) -> Result<(), GitAiError> {
+    let exe = current_git_ai_exe()?;
+    let msg_filter = format!(
+        "'{}' rewrite-trailers --msg-filter",
+        exe.display().to_string().replace('\'', r"'\''")
+    );
+    let mut cmd = Command::new(Config::get().git_cmd());
+    cmd.args(repo.global_args_for_exec())
+        .args([
+            "filter-branch",
+            "--force",
+            "--msg-filter",
+            &msg_filter,
+            "--",
+        ])
+        .arg(range)
+        // We already printed our own warning
+        .env("FILTER_BRANCH_SQUELCH_WARNING", "1");
+    let status = cmd.status()?;
+    if !status.success() {
+        return Err(GitAiError::Generic(format!(
+            "git filter-branch exited with {}",
+            status
+        )));
+    }
+    Ok(())
+}
+
+/// Message filter: the original message on stdin, the original commit in $GIT_COMMIT
+fn run_msg_filter() -> Result<(), GitAiError> {
+    let mut message = String::new();
+    std::io::stdin().read_to_string(&mut message)?;
+    let sha = std::env::var("GIT_COMMIT")
+        .map_err(|_| GitAiError::Generic("GIT_COMMIT is not set".to_string()))?;
+    let repo = find_repository(&Vec::<String>::new())?;
+    let trailers = trailers_for_commit(&repo, &sha)?;
+    print!("{}", append_trailers(&message, &trailers));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::stats::ToolModelHeadlineStats;
+
+    #[test]
+    fn test_attribution_trailers() {
+        let mut stats = CommitStats {
+            ai_additions: 30,
+            git_diff_added_lines: 40,
+            ..Default::default()
+        };
+        stats.tool_model_breakdown.insert(
+            "cursor::claude-3-sonnet".to_string(),
+            ToolModelHeadlineStats {
+                ai_additions: 30,
+                ..Default::default()
+            },
+        );
+        stats.tool_model_breakdown.insert(
+            "claude::opus".to_string(),
+            ToolModelHeadlineStats::default(),
+        );
+
+        assert_eq!(
+            attribution_trailers(&stats),
+            vec!["AI-Lines: 30/40 (75%)", "AI-Tools: cursor/claude-3-sonnet"]
+        );
+    }
+
+    #[test]
+    fn test_append_trailers() {
+        let trailers = vec!["AI-Lines: 1/2 (50%)".to_string()];
+        assert_eq!(
+            append_trailers("Fix bug\n", &trailers),
+            "Fix bug\n\nAI-Lines: 1/2 (50%)\n"
+        );
+        assert_eq!(
+            append_trailers("Fix bug\n\nSigned-off-by: A <a@x>\n", &trailers),
+            "Fix bug\n\nSigned-off-by: A <a@x>\nAI-Lines: 1/2 (50%)\n"
+        );
+        assert_eq!(
+            append_trailers("Fix bug\n\nThe cache was stale.\n", &trailers),
+            "Fix bug\n\nThe cache was stale.\n\nAI-Lines: 1/2 (50%)\n"
+        );
+        assert_eq!(append_trailers("Fix bug\n", &[]), "Fix bug\n");
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let args = |values: &[&str]| values.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            parse_args(&args(&["main~3..main", "--yes"])).unwrap(),
+            ("main~3..main".to_string(), true)
+        );
+        assert!(parse_args(&args(&[])).is_err());
+        assert!(parse_args(&args(&["a..b", "c..d"])).is_err());
+    }
+}
@@ -0,0 +1,132 @@
+//! Per-repository safe mode. When the wrapper's hooks panic on
+//! `safe_mode_after_failures` commands in a row, it stops running them in
+//! that repository and runs plain git, so a corrupted store can't keep
+//! degrading every git command. The streak and the safe-mode flag live in
+//! `.git/ai/hook_failures.json`; `git-ai doctor --enable-hooks` clears them.
+
+use crate::commands::output;
+use crate::error::GitAiError;
+use crate::git::repo_storage::RepoStorage;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// Failures in a row before safe mode, unless `safe_mode_after_failures` is set
+pub const DEFAULT_SAFE_MODE_AFTER_FAILURES: u32 = 3;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HookFailures {
+    pub consecutive: u32,
+    pub last_command: Option<String>,
+    pub last_error: Option<String>,
+    /// Unix time hooks were turned off; None while they still run
+    pub safe_mode_since: Option<i64>,
+}
+
+impl HookFailures {
+    pub fn load(storage: &RepoStorage) -> Self {
+        fs::read(&storage.hook_failures)
+            .ok()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn in_safe_mode(&self) -> bool {
+        self.safe_mode_since.is_some()
+    }
+}
+
+/// Whether hooks are off in this repository
+pub fn is_active(storage: &RepoStorage) -> bool {
+    storage.hook_failures.exists() && HookFailures::load(storage).in_safe_mode()
+}
+
+/// Record how `command`'s hooks went; `error` is the panic message if they
+/// failed. Returns true when this failure turned on safe mode. A threshold
+/// of 0 never does.
+pub fn record_hook_outcome(
+    storage: &RepoStorage,
+    command: &str,
+    error: Option<&str>,
+    threshold: u32,
+) -> bool {
+    let Some(error) = error else {
+        // A clean run ends the streak
+        if storage.hook_failures.exists() && !is_active(storage) {
+            let _ = fs::remove_file(&storage.hook_failures);
+        }
+        return false;
+    };
+
+    let mut failures = HookFailures::load(storage);
+    failures.consecutive += 1;
+    failures.last_command = Some(command.to_string());
+    failures.last_error = Some(error.to_string());
+    let entered = threshold > 0 && failures.consecutive >= threshold && !failures.in_safe_mode();
+    if entered {
+        failures.safe_mode_since = Some(chrono::Utc::now().timestamp());
+    }
+    if let Ok(json) = serde_json::to_vec_pretty(&failures) {
+        let _ = fs::write(&storage.hook_failures, json);
+    }
+    entered
+}
+
+/// Turn hooks back on, returning whether safe mode was on
+pub fn enable_hooks(storage: &RepoStorage) -> Result<bool, GitAiError> {
+    let was_active = is_active(storage);
+    if storage.hook_failures.exists() {
+        fs::remove_file(&storage.hook_failures)?;
+    }
+    Ok(was_active)
+}
+
+/// The one-line notice printed before git runs without hooks
+pub fn print_notice(failures: &HookFailures) {
+    if output::quiet() {
+        return;
+    }
+    eprintln!(
+        "{} safe mode: hooks are off in this repository after {} failed runs in a row; see `git-ai doctor`",
+        output::paint("\x1b[1;33m", "[git-ai]"),
+        failures.consecutive
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_safe_mode_after_consecutive_failures() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let storage = &tmp_repo.gitai_repo().storage;
+
+        assert!(!record_hook_outcome(storage, "commit", Some("boom"), 2));
+        // Success resets the streak
+        assert!(!record_hook_outcome(storage, "commit", None, 2));
+        assert_eq!(HookFailures::load(storage), HookFailures::default());
+
+        assert!(!record_hook_outcome(storage, "commit", Some("boom"), 2));
+        assert!(record_hook_outcome(storage, "push", Some("corrupt log"), 2));
+        assert!(is_active(storage));
+        let failures = HookFailures::load(storage);
+        assert_eq!(failures.consecutive, 2);
+        assert_eq!(failures.last_command.as_deref(), Some("push"));
+        assert_eq!(failures.last_error.as_deref(), Some("corrupt log"));
+
+        // Stays on until hooks are enabled again
+        assert!(!record_hook_outcome(storage, "commit", None, 2));
+        assert!(is_active(storage));
+        assert!(enable_hooks(storage).unwrap());
+        assert!(!is_active(storage));
+        assert!(!enable_hooks(storage).unwrap());
+
+        // A threshold of 0 never turns it on
+        for _ in 0..5 {
+            assert!(!record_hook_outcome(storage, "commit", Some("boom"), 0));
+        }
+        assert!(!is_active(storage));
+    }
+}
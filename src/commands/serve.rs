@@ -0,0 +1,105 @@
+use crate::error::{GitAiError, exit_with_error};
+use crate::git::find_repository_in_path;
+use crate::observability::metrics::MetricsSnapshot;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+
+const DEFAULT_BIND: &str = "127.0.0.1:9464";
+
+pub fn handle_serve(args: &[String]) {
+    let mut bind = DEFAULT_BIND.to_string();
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--bind" => {
+                if i + 1 >= args.len() {
+                    exit_with_error(
+                        "Error",
+                        &GitAiError::InvalidArguments(
+                            "--bind requires an address (e.g. 0.0.0.0:9464)".to_string(),
+                        ),
+                    );
+                }
+                bind = args[i + 1].clone();
+                i += 2;
+            }
+            "--port" => {
+                if i + 1 >= args.len() {
+                    exit_with_error(
+                        "Error",
+                        &GitAiError::InvalidArguments("--port requires a value".to_string()),
+                    );
+                }
+                bind = format!("127.0.0.1:{}", args[i + 1]);
+                i += 2;
+            }
+            other => {
+                exit_with_error(
+                    "Error",
+                    &GitAiError::InvalidArguments(format!(
+                        "Unknown serve argument: {}\nUsage: git-ai serve [--bind <addr:port>] [--port <port>]",
+                        other
+                    )),
+                );
+            }
+        }
+    }
+
+    let repo = match find_repository_in_path(".") {
+        Ok(repo) => repo,
+        Err(e) => {
+            exit_with_error("Failed to find repository", &e);
+        }
+    };
+
+    let listener = match TcpListener::bind(&bind) {
+        Ok(listener) => listener,
+        Err(e) => {
+            exit_with_error(&format!("Failed to bind {}", bind), &GitAiError::IoError(e));
+        }
+    };
+
+    eprintln!("Serving metrics on http://{}/metrics", bind);
+    serve_metrics(&listener, &repo.storage.metrics);
+}
+
+/// Answer requests on `listener` until the process is killed.
+pub fn serve_metrics(listener: &TcpListener, metrics_path: &Path) {
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                if let Err(e) = handle_connection(stream, metrics_path) {
+                    eprintln!("Failed to handle metrics request: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Failed to accept connection: {}", e),
+        }
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, metrics_path: &Path) -> std::io::Result<()> {
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+
+    let path = request_line.split_whitespace().nth(1).unwrap_or("");
+    let (status, content_type, body) = match path {
+        "/metrics" => (
+            "200 OK",
+            "text/plain; version=0.0.4",
+            MetricsSnapshot::load(metrics_path).render_prometheus(),
+        ),
+        _ => ("404 Not Found", "text/plain", "Not Found\n".to_string()),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
@@ -0,0 +1,235 @@
+use crate::authorship::sessions::{
+    AgentSession, CommittedPrompts, DEFAULT_SESSION_GAP_SECS, attribute_commits, group_sessions,
+};
+use crate::commands::output;
+use crate::commands::sync_daemon::parse_interval;
+use crate::error::{GitAiError, exit_with_error};
+use crate::git::find_repository;
+use crate::git::refs::get_authorship;
+use crate::git::repository::{Repository, exec_git};
+use std::collections::BTreeMap;
+
+/// Commits scanned for surviving session lines
+const MAX_COMMITS: usize = 1000;
+
+/// Handle the `sessions` command
+///
+/// Usage: git-ai sessions list [--gap <dur>]
+///        git-ai sessions show <id> [--gap <dur>]
+///
+/// Groups the working log's AI checkpoints into agent sessions (see
+/// [`crate::authorship::sessions`]) and reports how long each ran, what it
+/// touched, and how many of its lines ended up in commits.
+pub fn handle_sessions(args: &[String]) {
+    let (subcommand, rest) = match args.split_first() {
+        Some((sub, rest)) => (sub.as_str(), rest),
+        None => ("list", args),
+    };
+
+    let mut gap_secs = DEFAULT_SESSION_GAP_SECS;
+    let mut session_id: Option<String> = None;
+    let mut i = 0;
+    while i < rest.len() {
+        match rest[i].as_str() {
+            "--gap" => {
+                let Some(value) = rest.get(i + 1) else {
+                    exit_with_error(
+                        "Error",
+                        &GitAiError::InvalidArguments("--gap requires a value".to_string()),
+                    );
+                };
+                match parse_interval(value) {
+                    Ok(gap) => gap_secs = gap.as_secs(),
+                    Err(e) => exit_with_error("Error", &GitAiError::InvalidArguments(e)),
+                }
+                i += 1;
+            }
+            other if subcommand == "show" && session_id.is_none() && !other.starts_with('-') => {
+                session_id = Some(other.to_string());
+            }
+            other => exit_with_error(
+                "Error",
+                &GitAiError::InvalidArguments(format!("Unknown sessions argument: {}", other)),
+            ),
+        }
+        i += 1;
+    }
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => exit_with_error("Failed to find repository", &e),
+    };
+    let sessions = match load_sessions(&repo, gap_secs) {
+        Ok(sessions) => sessions,
+        Err(e) => exit_with_error("Failed to read sessions", &e),
+    };
+
+    match subcommand {
+        "list" => print_list(&sessions),
+        "show" => {
+            let Some(id) = session_id else {
+                exit_with_error(
+                    "Error",
+                    &GitAiError::InvalidArguments("Usage: git-ai sessions show <id>".to_string()),
+                );
+            };
+            let Some(session) = sessions.iter().find(|s| s.id == id) else {
+                exit_with_error(
+                    "Error",
+                    &GitAiError::InvalidArguments(format!("No session with id {}", id)),
+                );
+            };
+            print_session(session);
+        }
+        other => exit_with_error(
+            "Error",
+            &GitAiError::InvalidArguments(format!(
+                "Unknown sessions subcommand: {} (expected list or show)",
+                other
+            )),
+        ),
+    }
+}
+
+fn load_sessions(repo: &Repository, gap_secs: u64) -> Result<Vec<AgentSession>, GitAiError> {
+    let checkpoints = repo
+        .storage
+        .working_log_for_base_commit("initial")
+        .read_all_checkpoints()?;
+    let mut sessions = group_sessions(&checkpoints, gap_secs);
+    let Some(earliest) = sessions.iter().map(|s| s.started_at).min() else {
+        return Ok(sessions);
+    };
+
+    let mut args = repo.global_args_for_exec();
+    args.push("log".to_string());
+    args.push("--format=%H %ct".to_string());
+    args.push(format!("--since=@{}", earliest));
+    args.push(format!("--max-count={}", MAX_COMMITS));
+    args.push("HEAD".to_string());
+    // An unborn HEAD just means nothing was committed yet
+    let Ok(output) = exec_git(&args) else {
+        return Ok(sessions);
+    };
+
+    let mut commits = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let Some((sha, time)) = line.split_once(' ') else {
+            continue;
+        };
+        let Some(log) = get_authorship(repo, sha) else {
+            continue;
+        };
+        let accepted_lines: BTreeMap<String, u32> = log
+            .metadata
+            .prompts
+            .iter()
+            .map(|(hash, prompt)| (hash.clone(), prompt.accepted_lines))
+            .collect();
+        commits.push(CommittedPrompts {
+            sha: sha.to_string(),
+            commit_time: time.parse().unwrap_or(0),
+            accepted_lines,
+        });
+    }
+    attribute_commits(&mut sessions, &commits);
+    Ok(sessions)
+}
+
+fn print_list(sessions: &[AgentSession]) {
+    if output::json_requested(false) {
+        println!("{}", serde_json::to_string_pretty(sessions).unwrap());
+        return;
+    }
+    if sessions.is_empty() {
+        println!("No agent sessions in the working log");
+        return;
+    }
+    println!(
+        "{:<12} {:<28} {:<17} {:>8} {:>5} {:>5} {:>7} {:>14}",
+        "ID", "AGENT", "STARTED", "DURATION", "CKPTS", "FILES", "+LINES", "COMMITTED"
+    );
+    for session in sessions {
+        println!(
+            "{:<12} {:<28} {:<17} {:>8} {:>5} {:>5} {:>7} {:>14}",
+            session.id,
+            agent_label(session),
+            format_time(session.started_at),
+            format_duration(session.duration_secs()),
+            session.checkpoints,
+            session.files.len(),
+            session.additions,
+            format!(
+                "{} ({:.0}%)",
+                session.committed_lines(),
+                session.survival_percent()
+            ),
+        );
+    }
+}
+
+fn print_session(session: &AgentSession) {
+    if output::json_requested(false) {
+        println!("{}", serde_json::to_string_pretty(session).unwrap());
+        return;
+    }
+    println!("Session {}", session.id);
+    println!("  agent:       {}", agent_label(session));
+    println!("  agent id:    {}", session.agent_session_id);
+    println!("  prompt:      {}", session.prompt_id);
+    println!(
+        "  time:        {} - {} ({})",
+        format_time(session.started_at),
+        format_time(session.ended_at),
+        format_duration(session.duration_secs())
+    );
+    println!("  checkpoints: {}", session.checkpoints);
+    println!(
+        "  lines:       +{} -{}",
+        session.additions, session.deletions
+    );
+    println!(
+        "  committed:   {} lines ({:.0}%)",
+        session.committed_lines(),
+        session.survival_percent()
+    );
+    println!("  files:");
+    for file in &session.files {
+        println!("    {}", file);
+    }
+    if !session.committed.is_empty() {
+        println!("  commits:");
+        for commit in &session.committed {
+            let short = commit.sha.get(..7).unwrap_or(&commit.sha);
+            println!("    {}  {} lines", short, commit.lines);
+        }
+    }
+}
+
+fn agent_label(session: &AgentSession) -> String {
+    if session.model.is_empty() {
+        session.tool.clone()
+    } else {
+        format!("{} ({})", session.tool, session.model)
+    }
+}
+
+fn format_time(timestamp: u64) -> String {
+    chrono::DateTime::from_timestamp(timestamp as i64, 0)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+fn format_duration(secs: u64) -> String {
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}h{:02}m", secs / 3600, (secs % 3600) / 60)
+    }
+}
@@ -1,43 +1,238 @@
-use crate::error::GitAiError;
+use crate::attribution_core::LineRange;
+use crate::authorship::anonymize::anonymize_log;
+use crate::authorship::authorship_log::PromptRecord;
+use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::authorship::range_authorship::{create_authorship_log_for_range, should_ignore_file};
+use crate::authorship::stats::{get_git_diff_stats_matching, stats_from_authorship_log};
+use crate::commands::output;
+use crate::error::{GitAiError, exit_with_error};
 use crate::git::find_repository;
-use crate::git::refs::{CommitAuthorship, get_commits_with_notes_from_list};
+use crate::git::refs::{
+    CommitAuthorship, get_authorship, get_commits_with_notes_from_list, grep_ai_notes,
+};
 use crate::git::repository::{CommitRange, Repository};
+use crate::git::rewrite_log::RewriteLogEvent;
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 const NO_AUTHORSHIP_DATA_MESSAGE: &str = "No authorship data found for this revision";
 
-pub fn handle_show(args: &[String]) {
-    if args.is_empty() {
-        eprintln!("Error: show requires a revision or range");
-        std::process::exit(1);
-    }
+fn no_authorship_data() -> GitAiError {
+    GitAiError::NoAuthorshipData(NO_AUTHORSHIP_DATA_MESSAGE.to_string())
+}
 
-    if args.len() > 1 {
-        eprintln!("Error: show accepts exactly one revision or range");
-        std::process::exit(1);
-    }
+pub fn handle_show(args: &[String]) {
+    let (spec, filters, mode) = match parse_show_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => exit_with_error("Error", &GitAiError::InvalidArguments(e)),
+    };
 
     let repo = match find_repository(&Vec::<String>::new()) {
         Ok(repo) => repo,
         Err(e) => {
-            eprintln!("Failed to find repository: {}", e);
-            std::process::exit(1);
+            exit_with_error("Failed to find repository", &e);
         }
     };
 
-    if let Err(e) = show_authorship(&repo, &args[0]) {
-        eprintln!("Failed to show authorship: {}", e);
-        std::process::exit(1);
+    let result = match mode {
+        ShowMode::Authorship => show_authorship(&repo, &spec, &filters),
+        ShowMode::AmendHistory => show_amend_history(&repo, &spec, &filters),
+        ShowMode::Provenance => show_provenance(&repo, &spec, &filters),
+        ShowMode::Rollup => show_rollup(&repo, &spec, &filters),
+    };
+    if let Err(e) = result {
+        exit_with_error("Failed to show authorship", &e);
     }
 }
 
-fn show_authorship(repo: &Repository, spec: &str) -> Result<(), GitAiError> {
+/// Narrow a commit's attestations to the ones a reviewer asked for
+#[derive(Debug, Default, PartialEq)]
+pub struct ShowFilters {
+    /// Agent tool name, e.g. "cursor" (case-insensitive)
+    pub agent: Option<String>,
+    /// Substring of the prompt's human author (case-insensitive)
+    pub author: Option<String>,
+    /// Glob matched against the file path or file name
+    pub path: Option<String>,
+    /// Label given with `git-ai checkpoint --tag`
+    pub tag: Option<String>,
+}
+
+impl ShowFilters {
+    fn is_empty(&self) -> bool {
+        self.agent.is_none() && self.author.is_none() && self.path.is_none() && self.tag.is_none()
+    }
+
+    fn keeps_path(&self, path: &str) -> bool {
+        match &self.path {
+            Some(pattern) => should_ignore_file(path, std::slice::from_ref(pattern)),
+            None => true,
+        }
+    }
+
+    fn keeps_prompt(&self, prompt: &PromptRecord) -> bool {
+        let agent_matches = self
+            .agent
+            .as_ref()
+            .is_none_or(|agent| prompt.agent_id.tool.eq_ignore_ascii_case(agent));
+        let author_matches = self.author.as_ref().is_none_or(|author| {
+            prompt
+                .human_author
+                .as_ref()
+                .is_some_and(|human| human.to_lowercase().contains(&author.to_lowercase()))
+        });
+        let tag_matches = self
+            .tag
+            .as_ref()
+            .is_none_or(|tag| prompt.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)));
+        agent_matches && author_matches && tag_matches
+    }
+
+    /// `apply`, then anonymize when `--anonymize` is in effect
+    fn present(&self, log: &AuthorshipLog) -> AuthorshipLog {
+        let mut log = self.apply(log);
+        if output::anonymize() {
+            anonymize_log(&mut log);
+        }
+        log
+    }
+
+    /// Drop attestations (and then prompts) that don't match. Files left with
+    /// no entries are removed.
+    pub fn apply(&self, log: &AuthorshipLog) -> AuthorshipLog {
+        let mut filtered = log.clone();
+        if self.is_empty() {
+            return filtered;
+        }
+
+        let keep_prompt = |hash: &str| {
+            log.metadata
+                .prompts
+                .get(hash)
+                .is_some_and(|prompt| self.keeps_prompt(prompt))
+        };
+        filtered
+            .attestations
+            .retain(|file| self.keeps_path(&file.file_path));
+        for file in &mut filtered.attestations {
+            file.entries.retain(|entry| keep_prompt(&entry.hash));
+        }
+        filtered
+            .attestations
+            .retain(|file| !file.entries.is_empty());
+
+        let referenced: HashSet<&str> = filtered
+            .attestations
+            .iter()
+            .flat_map(|file| file.entries.iter().map(|entry| entry.hash.as_str()))
+            .collect();
+        let prompts = std::mem::take(&mut filtered.metadata.prompts);
+        filtered.metadata.prompts = prompts
+            .into_iter()
+            .filter(|(hash, _)| referenced.contains(hash.as_str()))
+            .collect();
+        filtered
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShowMode {
+    Authorship,
+    /// `--amend-history`: attribution across `commit --amend`s
+    AmendHistory,
+    /// `--provenance`: the cherry-pick chain back to the original commit
+    Provenance,
+    /// `--rollup`: one per-file view of a range, as attributed at its head
+    Rollup,
+}
+
+/// Returns the revision, the filters and which view was asked for
+pub fn parse_show_args(args: &[String]) -> Result<(String, ShowFilters, ShowMode), String> {
+    let mut spec: Option<String> = None;
+    let mut filters = ShowFilters::default();
+    let mut mode = ShowMode::Authorship;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            flag @ ("--agent" | "--author" | "--path" | "--tag") => {
+                let value = args
+                    .get(i + 1)
+                    .ok_or_else(|| format!("{} requires a value", flag))?
+                    .clone();
+                match flag {
+                    "--agent" => filters.agent = Some(value),
+                    "--author" => filters.author = Some(value),
+                    "--tag" => filters.tag = Some(value),
+                    _ => {
+                        glob::Pattern::new(&value)
+                            .map_err(|e| format!("Invalid --path glob '{}': {}", value, e))?;
+                        filters.path = Some(value);
+                    }
+                }
+                i += 1;
+            }
+            "--amend-history" => mode = ShowMode::AmendHistory,
+            "--provenance" => mode = ShowMode::Provenance,
+            "--rollup" => mode = ShowMode::Rollup,
+            other if other.starts_with("--") => {
+                return Err(format!("Unknown option: {}", other));
+            }
+            other => {
+                if spec.is_some() {
+                    return Err("show accepts exactly one revision or range".to_string());
+                }
+                spec = Some(other.to_string());
+            }
+        }
+        i += 1;
+    }
+
+    let spec = spec.ok_or("show requires a revision or range")?;
+    match mode {
+        ShowMode::AmendHistory if spec.contains("..") => {
+            return Err("--amend-history takes a single commit, not a range".to_string());
+        }
+        ShowMode::Provenance if spec.contains("..") => {
+            return Err("--provenance takes a single commit, not a range".to_string());
+        }
+        ShowMode::Rollup if !spec.contains("..") => {
+            return Err("--rollup takes a range, e.g. main..HEAD".to_string());
+        }
+        _ => {}
+    }
+    Ok((spec, filters, mode))
+}
+
+fn show_authorship(repo: &Repository, spec: &str, filters: &ShowFilters) -> Result<(), GitAiError> {
     let commits = resolve_commits(repo, spec)?;
+    if output::structured_requested(false) {
+        let entries = if commits.is_empty() {
+            Vec::new()
+        } else {
+            get_commits_with_notes_from_list(repo, &commits)?
+        };
+        let json = entries
+            .iter()
+            .map(|entry| commit_authorship_json(repo, entry, filters))
+            .collect::<Result<Vec<Value>, GitAiError>>()?;
+        output::print_structured("show", &json)?;
+        return if has_any_log(&entries) {
+            Ok(())
+        } else {
+            Err(no_authorship_data())
+        };
+    }
+
     if commits.is_empty() {
-        println!("{}", NO_AUTHORSHIP_DATA_MESSAGE);
-        return Ok(());
+        return Err(no_authorship_data());
     }
 
     let entries = get_commits_with_notes_from_list(repo, &commits)?;
+    // A range with some notes still prints the per-commit gaps below
+    if !has_any_log(&entries) {
+        return Err(no_authorship_data());
+    }
 
     let multiple_commits = entries.len() > 1;
     for (index, entry) in entries.iter().enumerate() {
@@ -52,11 +247,14 @@ fn show_authorship(repo: &Repository, spec: &str) -> Result<(), GitAiError> {
                 ..
             } => {
                 if multiple_commits {
-                    println!("{}", sha);
+                    println!("{}", commit_header(sha, authorship_log));
                 }
-                let serialized = authorship_log.serialize_to_string().map_err(|_| {
-                    GitAiError::Generic("Failed to serialize authorship log".to_string())
-                })?;
+                let serialized = filters
+                    .present(authorship_log)
+                    .serialize_to_string()
+                    .map_err(|_| {
+                        GitAiError::Generic("Failed to serialize authorship log".to_string())
+                    })?;
                 println!("{}", serialized);
             }
             CommitAuthorship::NoLog { sha, .. } => {
@@ -71,6 +269,353 @@ fn show_authorship(repo: &Repository, spec: &str) -> Result<(), GitAiError> {
     Ok(())
 }
 
+fn has_any_log(entries: &[CommitAuthorship]) -> bool {
+    entries
+        .iter()
+        .any(|entry| matches!(entry, CommitAuthorship::Log { .. }))
+}
+
+/// A commit's line in the range (log) view, with the commit it was
+/// cherry-picked from when the note records one
+fn commit_header(sha: &str, log: &AuthorshipLog) -> String {
+    match &log.metadata.cherry_picked_from {
+        Some(source) => format!("{} (cherry-picked from {})", sha, source),
+        None => sha.to_string(),
+    }
+}
+
+/// `{commit, files, metadata, stats}`, with `metadata: null` when the commit has no
+/// note. `stats` has the same shape as `git-ai stats --json` and respects the filters.
+fn commit_authorship_json(
+    repo: &Repository,
+    entry: &CommitAuthorship,
+    filters: &ShowFilters,
+) -> Result<Value, GitAiError> {
+    let (sha, log) = match entry {
+        CommitAuthorship::Log {
+            sha,
+            authorship_log,
+            ..
+        } => (sha, Some(filters.present(authorship_log))),
+        CommitAuthorship::NoLog { sha, .. } => (sha, None),
+    };
+    let (added, deleted) = get_git_diff_stats_matching(repo, sha, |path| filters.keeps_path(path))?;
+    let stats = stats_from_authorship_log(log.as_ref(), added, deleted);
+
+    Ok(match log {
+        Some(log) => json!({
+            "commit": sha,
+            "files": attestations_json(&log),
+            "metadata": log.metadata,
+            "stats": stats,
+        }),
+        None => json!({
+            "commit": sha,
+            "files": [],
+            "metadata": Value::Null,
+            "stats": stats,
+        }),
+    })
+}
+
+fn attestations_json(log: &AuthorshipLog) -> Value {
+    log.attestations
+        .iter()
+        .map(|file| {
+            let entries: Vec<Value> = file
+                .entries
+                .iter()
+                .map(|entry| {
+                    let lines: Vec<[u32; 2]> = entry
+                        .line_ranges
+                        .iter()
+                        .map(|range| match range {
+                            LineRange::Single(line) => [*line, *line],
+                            LineRange::Range(start, end) => [*start, *end],
+                        })
+                        .collect();
+                    json!({
+                        "prompt_id": entry.hash,
+                        "lines": lines,
+                        "overrode": entry.overrode,
+                    })
+                })
+                .collect();
+            json!({ "path": file.file_path, "entries": entries })
+        })
+        .collect()
+}
+
+/// Follow the rewrite log's `commit --amend` events back from `commit`.
+/// Returns the chain oldest first, ending with `commit` itself.
+pub fn amend_chain(events: &[RewriteLogEvent], commit: &str) -> Vec<String> {
+    // Events are newest-first, so the first amend recorded for a sha wins
+    let mut amended_from: HashMap<&str, &str> = HashMap::new();
+    for event in events {
+        if let RewriteLogEvent::CommitAmend { commit_amend } = event {
+            amended_from
+                .entry(commit_amend.amended_commit_sha.as_str())
+                .or_insert(commit_amend.original_commit.as_str());
+        }
+    }
+
+    let mut chain = vec![commit.to_string()];
+    let mut seen: HashSet<&str> = HashSet::from([commit]);
+    let mut current = commit;
+    while let Some(&original) = amended_from.get(current) {
+        if !seen.insert(original) {
+            break;
+        }
+        chain.push(original.to_string());
+        current = original;
+    }
+    chain.reverse();
+    chain
+}
+
+/// AI-attributed line count per file for one version of an amended commit
+fn ai_lines_by_file(log: &AuthorshipLog) -> BTreeMap<String, u32> {
+    log.attestations
+        .iter()
+        .map(|file| {
+            let lines = file
+                .entries
+                .iter()
+                .flat_map(|entry| entry.line_ranges.iter())
+                .map(|range| match range {
+                    LineRange::Single(_) => 1,
+                    LineRange::Range(start, end) => end - start + 1,
+                })
+                .sum();
+            (file.file_path.clone(), lines)
+        })
+        .collect()
+}
+
+fn show_amend_history(
+    repo: &Repository,
+    spec: &str,
+    filters: &ShowFilters,
+) -> Result<(), GitAiError> {
+    let commit = repo.revparse_single(spec)?.id();
+    let events = repo.storage.read_rewrite_events()?;
+    let versions: Vec<(String, Option<AuthorshipLog>)> = amend_chain(&events, &commit)
+        .into_iter()
+        .map(|sha| {
+            let log = get_authorship(repo, &sha).map(|log| filters.present(&log));
+            (sha, log)
+        })
+        .collect();
+
+    if output::structured_requested(false) {
+        let json: Vec<Value> = versions
+            .iter()
+            .map(|(sha, log)| match log {
+                Some(log) => json!({
+                    "commit": sha,
+                    "ai_lines": ai_lines_by_file(log),
+                    "prompts": log.metadata.prompts.keys().collect::<Vec<_>>(),
+                }),
+                None => json!({ "commit": sha, "ai_lines": Value::Null, "prompts": [] }),
+            })
+            .collect();
+        output::print_structured("show", &json)?;
+        return if has_any_version(&versions) {
+            Ok(())
+        } else {
+            Err(no_authorship_data())
+        };
+    }
+
+    if !has_any_version(&versions) {
+        return Err(no_authorship_data());
+    }
+    if versions.len() == 1 {
+        println!("{} has not been amended", &commit[..commit.len().min(8)]);
+    }
+
+    let mut previous: Option<BTreeMap<String, u32>> = None;
+    for (index, (sha, log)) in versions.iter().enumerate() {
+        let label = if index == 0 { "original" } else { "amend" };
+        println!("{}  {}", &sha[..sha.len().min(8)], label);
+        let Some(log) = log else {
+            println!("  {}", NO_AUTHORSHIP_DATA_MESSAGE);
+            previous = Some(BTreeMap::new());
+            continue;
+        };
+
+        let current = ai_lines_by_file(log);
+        let files: BTreeSet<&String> = current
+            .keys()
+            .chain(previous.iter().flat_map(|prev| prev.keys()))
+            .collect();
+        if files.is_empty() {
+            println!("  no AI-attributed lines");
+        }
+        for file in files {
+            let now = current.get(file).copied().unwrap_or(0);
+            let delta = previous
+                .as_ref()
+                .map(|prev| now as i64 - prev.get(file).copied().unwrap_or(0) as i64);
+            match delta {
+                Some(0) | None => println!("  {}  {} AI lines", file, now),
+                Some(delta) => println!("  {}  {} AI lines ({:+})", file, now, delta),
+            }
+        }
+        previous = Some(current);
+    }
+
+    Ok(())
+}
+
+fn has_any_version(versions: &[(String, Option<AuthorshipLog>)]) -> bool {
+    versions.iter().any(|(_, log)| log.is_some())
+}
+
+/// Follow `cherry_picked_from` links back from `commit`. Returns the chain
+/// oldest first, ending with `commit` itself.
+pub fn cherry_pick_chain(
+    commit: &str,
+    mut picked_from: impl FnMut(&str) -> Option<String>,
+) -> Vec<String> {
+    let mut chain = vec![commit.to_string()];
+    let mut seen: HashSet<String> = HashSet::from([commit.to_string()]);
+    while let Some(source) = picked_from(chain.last().unwrap()) {
+        if !seen.insert(source.clone()) {
+            break;
+        }
+        chain.push(source);
+    }
+    chain.reverse();
+    chain
+}
+
+/// Commits whose authorship logs say they were cherry-picked from `commit`
+fn cherry_picked_to(repo: &Repository, commit: &str) -> Vec<String> {
+    // git grep exits non-zero when nothing matches
+    grep_ai_notes(repo, &format!("\"cherry_picked_from\": \"{}\"", commit)).unwrap_or_default()
+}
+
+fn show_provenance(repo: &Repository, spec: &str, filters: &ShowFilters) -> Result<(), GitAiError> {
+    let commit = repo.revparse_single(spec)?.id();
+    let chain = cherry_pick_chain(&commit, |sha| {
+        get_authorship(repo, sha).and_then(|log| log.metadata.cherry_picked_from)
+    });
+    let versions: Vec<(String, Option<AuthorshipLog>)> = chain
+        .into_iter()
+        .map(|sha| {
+            let log = get_authorship(repo, &sha).map(|log| filters.present(&log));
+            (sha, log)
+        })
+        .collect();
+    let picked_to = cherry_picked_to(repo, &commit);
+
+    if output::structured_requested(false) {
+        let chain: Vec<Value> = versions
+            .iter()
+            .map(|(sha, log)| {
+                json!({
+                    "commit": sha,
+                    "cherry_picked_from": log.as_ref().and_then(|l| l.metadata.cherry_picked_from.clone()),
+                    "prompts": log.as_ref().map(|l| &l.metadata.prompts),
+                })
+            })
+            .collect();
+        let json = json!({ "commit": commit, "chain": chain, "cherry_picked_to": picked_to });
+        output::print_structured("show", &json)?;
+        return if has_any_version(&versions) {
+            Ok(())
+        } else {
+            Err(no_authorship_data())
+        };
+    }
+
+    if !has_any_version(&versions) {
+        return Err(no_authorship_data());
+    }
+    if versions.len() == 1 {
+        println!("{} was not cherry-picked", &commit[..commit.len().min(8)]);
+    }
+    for (index, (sha, log)) in versions.iter().enumerate() {
+        let label = if index == 0 {
+            "original"
+        } else {
+            "cherry-pick"
+        };
+        println!("{}  {}", &sha[..sha.len().min(8)], label);
+        let Some(log) = log else {
+            println!("  {}", NO_AUTHORSHIP_DATA_MESSAGE);
+            continue;
+        };
+        if log.metadata.prompts.is_empty() {
+            println!("  no AI prompts");
+        }
+        for (hash, prompt) in &log.metadata.prompts {
+            println!(
+                "  prompt {}  {} ({})  {} accepted lines",
+                hash, prompt.agent_id.tool, prompt.agent_id.model, prompt.accepted_lines
+            );
+        }
+    }
+    if !picked_to.is_empty() {
+        println!("Cherry-picked to:");
+        for sha in &picked_to {
+            println!("  {}", &sha[..sha.len().min(8)]);
+        }
+    }
+
+    Ok(())
+}
+
+/// Merge the range's attestations into the final attribution state at its head,
+/// one entry per file instead of one log per commit
+fn show_rollup(repo: &Repository, spec: &str, filters: &ShowFilters) -> Result<(), GitAiError> {
+    let (start, end) = spec.split_once("..").ok_or_else(|| {
+        GitAiError::Generic("Invalid commit range format. Expected <start>..<end>".to_string())
+    })?;
+    let commits = resolve_commits(repo, spec)?;
+    let start_sha = repo.revparse_single(start)?.id();
+    let end_sha = repo.revparse_single(end)?.id();
+    let rollup = filters.present(&create_authorship_log_for_range(
+        repo,
+        &start_sha,
+        &end_sha,
+        &commits,
+        &[],
+    )?);
+
+    if output::structured_requested(false) {
+        let json = json!({
+            "range": spec,
+            "commits": commits,
+            "files": attestations_json(&rollup),
+            "ai_lines": ai_lines_by_file(&rollup),
+            "metadata": rollup.metadata,
+        });
+        output::print_structured("show", &json)?;
+        return if rollup.attestations.is_empty() {
+            Err(no_authorship_data())
+        } else {
+            Ok(())
+        };
+    }
+
+    println!(
+        "{}..{} ({} commits)",
+        &start_sha[..start_sha.len().min(8)],
+        &end_sha[..end_sha.len().min(8)],
+        commits.len()
+    );
+    if rollup.attestations.is_empty() {
+        return Err(no_authorship_data());
+    }
+    let serialized = rollup
+        .serialize_to_string()
+        .map_err(|_| GitAiError::Generic("Failed to serialize authorship log".to_string()))?;
+    println!("{}", serialized);
+    Ok(())
+}
+
 fn resolve_commits(repo: &Repository, spec: &str) -> Result<Vec<String>, GitAiError> {
     if let Some((start, end)) = spec.split_once("..") {
         if start.is_empty() || end.is_empty() {
@@ -94,3 +639,171 @@ fn resolve_commits(repo: &Repository, spec: &str) -> Result<Vec<String>, GitAiEr
         Ok(vec![commit.id()])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTE: &str = r#"src/main.rs
+  aaa1111 1-3
+  bbb2222 5
+docs/guide.md
+  aaa1111 1
+---
+{
+  "schema_version": "authorship/3.0.0",
+  "base_commit_sha": "0123abcd",
+  "prompts": {
+    "aaa1111": {
+      "agent_id": {"tool": "cursor", "id": "s1", "model": "claude-3-sonnet"},
+      "human_author": "Alice <alice@example.com>",
+      "messages": []
+    },
+    "bbb2222": {
+      "agent_id": {"tool": "claude", "id": "s2", "model": "claude-3-opus"},
+      "human_author": "Bob <bob@example.com>",
+      "messages": []
+    }
+  }
+}"#;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_show_args() {
+        let (spec, filters, mode) = parse_show_args(&args(&[
+            "--agent",
+            "cursor",
+            "HEAD~2..HEAD",
+            "--path",
+            "*.rs",
+        ]))
+        .unwrap();
+        assert_eq!(spec, "HEAD~2..HEAD");
+        assert_eq!(filters.agent.as_deref(), Some("cursor"));
+        assert_eq!(filters.path.as_deref(), Some("*.rs"));
+        assert_eq!(mode, ShowMode::Authorship);
+
+        let (_, _, mode) = parse_show_args(&args(&["HEAD", "--amend-history"])).unwrap();
+        assert_eq!(mode, ShowMode::AmendHistory);
+        assert!(parse_show_args(&args(&["HEAD~1..HEAD", "--amend-history"])).is_err());
+        let (_, _, mode) = parse_show_args(&args(&["--provenance", "HEAD"])).unwrap();
+        assert_eq!(mode, ShowMode::Provenance);
+        assert!(parse_show_args(&args(&["HEAD~1..HEAD", "--provenance"])).is_err());
+        let (_, _, mode) = parse_show_args(&args(&["main..HEAD", "--rollup"])).unwrap();
+        assert_eq!(mode, ShowMode::Rollup);
+        assert!(parse_show_args(&args(&["HEAD", "--rollup"])).is_err());
+
+        assert!(parse_show_args(&args(&[])).is_err());
+        assert!(parse_show_args(&args(&["HEAD", "HEAD~1"])).is_err());
+        assert!(parse_show_args(&args(&["HEAD", "--author"])).is_err());
+        assert!(parse_show_args(&args(&["HEAD", "--path", "[oops"])).is_err());
+    }
+
+    #[test]
+    fn test_filters_prune_attestations_and_prompts() {
+        let log = AuthorshipLog::deserialize_from_string(NOTE).unwrap();
+
+        let by_agent = ShowFilters {
+            agent: Some("Claude".to_string()),
+            ..Default::default()
+        }
+        .apply(&log);
+        assert_eq!(by_agent.attestations.len(), 1);
+        assert_eq!(by_agent.attestations[0].entries[0].hash, "bbb2222");
+        assert_eq!(
+            by_agent.metadata.prompts.keys().collect::<Vec<_>>(),
+            vec!["bbb2222"]
+        );
+
+        let by_author_and_path = ShowFilters {
+            author: Some("alice".to_string()),
+            path: Some("*.md".to_string()),
+            ..Default::default()
+        }
+        .apply(&log);
+        assert_eq!(by_author_and_path.attestations.len(), 1);
+        assert_eq!(
+            by_author_and_path.attestations[0].file_path,
+            "docs/guide.md"
+        );
+
+        assert!(ShowFilters::default().apply(&log) == log);
+    }
+
+    #[test]
+    fn test_amend_chain_follows_rewrite_log() {
+        // Newest first, with an unrelated commit in between
+        let events = vec![
+            RewriteLogEvent::commit_amend("bbb".to_string(), "ccc".to_string()),
+            RewriteLogEvent::commit(Some("base".to_string()), "zzz".to_string()),
+            RewriteLogEvent::commit_amend("aaa".to_string(), "bbb".to_string()),
+        ];
+
+        assert_eq!(amend_chain(&events, "ccc"), vec!["aaa", "bbb", "ccc"]);
+        assert_eq!(amend_chain(&events, "bbb"), vec!["aaa", "bbb"]);
+        assert_eq!(amend_chain(&events, "zzz"), vec!["zzz"]);
+
+        let cycle = vec![
+            RewriteLogEvent::commit_amend("x".to_string(), "y".to_string()),
+            RewriteLogEvent::commit_amend("y".to_string(), "x".to_string()),
+        ];
+        assert_eq!(amend_chain(&cycle, "y"), vec!["x", "y"]);
+    }
+
+    #[test]
+    fn test_cherry_pick_chain_follows_links() {
+        let links = HashMap::from([("c", "b"), ("b", "a"), ("x", "y"), ("y", "x")]);
+        let picked_from = |sha: &str| links.get(sha).map(|s| s.to_string());
+
+        assert_eq!(cherry_pick_chain("c", picked_from), vec!["a", "b", "c"]);
+        assert_eq!(cherry_pick_chain("a", picked_from), vec!["a"]);
+        assert_eq!(cherry_pick_chain("y", picked_from), vec!["x", "y"]);
+    }
+
+    #[test]
+    fn test_commit_header_shows_cherry_pick_source() {
+        let mut log = AuthorshipLog::deserialize_from_string(NOTE).unwrap();
+        assert_eq!(commit_header("abc", &log), "abc");
+        log.metadata.cherry_picked_from = Some("def".to_string());
+        assert_eq!(commit_header("abc", &log), "abc (cherry-picked from def)");
+    }
+
+    #[test]
+    fn test_json_stats_respect_path_filter() {
+        use crate::git::test_utils::TmpRepo;
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("src/lib.rs", "a\nb\n", true).unwrap();
+        tmp_repo
+            .write_file("docs/guide.md", "one\ntwo\nthree\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+
+        let repo = tmp_repo.gitai_repo();
+        let head = tmp_repo.head_commit_sha().unwrap();
+        let entries = get_commits_with_notes_from_list(&repo, &[head]).unwrap();
+        let filters = ShowFilters {
+            path: Some("src/*".to_string()),
+            ..Default::default()
+        };
+        let json = commit_authorship_json(&repo, &entries[0], &filters).unwrap();
+        assert_eq!(json["stats"]["git_diff_added_lines"], 2);
+
+        let json = commit_authorship_json(&repo, &entries[0], &ShowFilters::default()).unwrap();
+        assert_eq!(json["stats"]["git_diff_added_lines"], 5);
+    }
+
+    #[test]
+    fn test_ai_lines_by_file() {
+        let log = AuthorshipLog::deserialize_from_string(NOTE).unwrap();
+        let lines = ai_lines_by_file(&log);
+        assert_eq!(lines["src/main.rs"], 4);
+        assert_eq!(lines["docs/guide.md"], 1);
+    }
+}
@@ -1,5 +1,7 @@
+use crate::authorship::anonymize::anonymize_prompt;
 use crate::authorship::authorship_log::PromptRecord;
-use crate::error::GitAiError;
+use crate::commands::output;
+use crate::error::{GitAiError, exit_with_error};
 use crate::git::find_repository;
 use crate::git::refs::{get_authorship, grep_ai_notes};
 use crate::git::repository::Repository;
@@ -14,16 +16,14 @@ pub fn handle_show_prompt(args: &[String]) {
     let parsed = match parse_args(args) {
         Ok(p) => p,
         Err(e) => {
-            eprintln!("Error: {}", e);
-            std::process::exit(1);
+            exit_with_error("Error", &GitAiError::InvalidArguments(e));
         }
     };
 
     let repo = match find_repository(&Vec::<String>::new()) {
         Ok(repo) => repo,
         Err(e) => {
-            eprintln!("Failed to find repository: {}", e);
-            std::process::exit(1);
+            exit_with_error("Failed to find repository", &e);
         }
     };
 
@@ -33,7 +33,10 @@ pub fn handle_show_prompt(args: &[String]) {
         parsed.commit.as_deref(),
         parsed.offset,
     ) {
-        Ok((commit_sha, prompt_record)) => {
+        Ok((commit_sha, mut prompt_record)) => {
+            if output::anonymize() {
+                anonymize_prompt(&mut prompt_record);
+            }
             // Output the prompt as JSON, including the commit SHA for context
             let output = serde_json::json!({
                 "commit": commit_sha,
@@ -46,8 +49,7 @@ pub fn handle_show_prompt(args: &[String]) {
             );
         }
         Err(e) => {
-            eprintln!("Error: {}", e);
-            std::process::exit(1);
+            exit_with_error("Error", &e);
         }
     }
 }
@@ -114,7 +116,7 @@ pub fn parse_args(args: &[String]) -> Result<ParsedArgs, String> {
 ///
 /// If `commit` is provided, look only in that specific commit.
 /// Otherwise, search through history and skip `offset` occurrences (0 = most recent).
-fn find_prompt(
+pub fn find_prompt(
     repo: &Repository,
     prompt_id: &str,
     commit: Option<&str>,
@@ -141,7 +143,7 @@ fn find_prompt_in_commit(
 
     // Get the authorship log for this commit
     let authorship_log = get_authorship(repo, &commit_sha).ok_or_else(|| {
-        GitAiError::Generic(format!(
+        GitAiError::NoAuthorshipData(format!(
             "No authorship data found for commit: {}",
             commit_rev
         ))
@@ -154,7 +156,7 @@ fn find_prompt_in_commit(
         .get(prompt_id)
         .map(|prompt| (commit_sha, prompt.clone()))
         .ok_or_else(|| {
-            GitAiError::Generic(format!(
+            GitAiError::NoAuthorshipData(format!(
                 "Prompt '{}' not found in commit {}",
                 prompt_id, commit_rev
             ))
@@ -173,7 +175,7 @@ fn find_prompt_in_history(
     let shas = grep_ai_notes(repo, &format!("\"{}\"", prompt_id)).unwrap_or_default();
 
     if shas.is_empty() {
-        return Err(GitAiError::Generic(format!(
+        return Err(GitAiError::NoAuthorshipData(format!(
             "Prompt not found in history: {}",
             prompt_id
         )));
@@ -194,12 +196,12 @@ fn find_prompt_in_history(
 
     // If we get here, we didn't find enough occurrences
     if found_count == 0 {
-        Err(GitAiError::Generic(format!(
+        Err(GitAiError::NoAuthorshipData(format!(
             "Prompt not found in history: {}",
             prompt_id
         )))
     } else {
-        Err(GitAiError::Generic(format!(
+        Err(GitAiError::InvalidArguments(format!(
             "Prompt '{}' found {} time(s), but offset {} requested (max offset: {})",
             prompt_id,
             found_count,
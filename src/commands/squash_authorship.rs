@@ -1,6 +1,10 @@
 use crate::authorship::rebase_authorship::rewrite_authorship_after_squash_or_rebase;
+use crate::error::{GitAiError, exit_with_error};
 use crate::git::find_repository_in_path;
 
+const SQUASH_USAGE: &str =
+    "Usage: git-ai squash-authorship <base_branch> <new_sha> <old_sha> [--dry-run]";
+
 pub fn handle_squash_authorship(args: &[String]) {
     // Parse squash-authorship-specific arguments
     let mut base_branch = None;
@@ -23,8 +27,13 @@ pub fn handle_squash_authorship(args: &[String]) {
                 } else if old_sha.is_none() {
                     old_sha = Some(args[i].clone());
                 } else {
-                    eprintln!("Unknown squash-authorship argument: {}", args[i]);
-                    std::process::exit(1);
+                    exit_with_error(
+                        "Error",
+                        &GitAiError::InvalidArguments(format!(
+                            "Unknown squash-authorship argument: {}",
+                            args[i]
+                        )),
+                    );
                 }
                 i += 1;
             }
@@ -35,33 +44,39 @@ pub fn handle_squash_authorship(args: &[String]) {
     let base_branch = match base_branch {
         Some(s) => s,
         None => {
-            eprintln!("Error: base_branch argument is required");
-            eprintln!(
-                "Usage: git-ai squash-authorship <base_branch> <new_sha> <old_sha> [--dry-run]"
+            exit_with_error(
+                "Error",
+                &GitAiError::InvalidArguments(format!(
+                    "base_branch argument is required\n{}",
+                    SQUASH_USAGE
+                )),
             );
-            std::process::exit(1);
         }
     };
 
     let new_sha = match new_sha {
         Some(s) => s,
         None => {
-            eprintln!("Error: new_sha argument is required");
-            eprintln!(
-                "Usage: git-ai squash-authorship <base_branch> <new_sha> <old_sha> [--dry-run]"
+            exit_with_error(
+                "Error",
+                &GitAiError::InvalidArguments(format!(
+                    "new_sha argument is required\n{}",
+                    SQUASH_USAGE
+                )),
             );
-            std::process::exit(1);
         }
     };
 
     let old_sha = match old_sha {
         Some(s) => s,
         None => {
-            eprintln!("Error: old_sha argument is required");
-            eprintln!(
-                "Usage: git-ai squash-authorship <base_branch> <new_sha> <old_sha> [--dry-run]"
+            exit_with_error(
+                "Error",
+                &GitAiError::InvalidArguments(format!(
+                    "old_sha argument is required\n{}",
+                    SQUASH_USAGE
+                )),
             );
-            std::process::exit(1);
         }
     };
 
@@ -71,8 +86,7 @@ pub fn handle_squash_authorship(args: &[String]) {
     let repo = match find_repository_in_path(".") {
         Ok(repo) => repo,
         Err(e) => {
-            eprintln!("Failed to find repository: {}", e);
-            std::process::exit(1);
+            exit_with_error("Failed to find repository", &e);
         }
     };
 
@@ -85,7 +99,6 @@ pub fn handle_squash_authorship(args: &[String]) {
         &new_sha,     // merge_commit_sha - the new commit
         false,        // suppress_output
     ) {
-        eprintln!("Squash authorship failed: {}", e);
-        std::process::exit(1);
+        exit_with_error("Squash authorship failed", &e);
     }
 }
@@ -0,0 +1,206 @@
+use crate::commands::auto_checkpoint;
+use crate::commands::multi::read_repo_list;
+use crate::commands::output;
+use crate::config::Config;
+use crate::error::{GitAiError, exit_with_error};
+use crate::git::find_repository_in_path;
+use crate::git::repository::Repository;
+use crate::git::sync_authorship::{fetch_authorship_notes, push_authorship_notes};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// The `sync_daemon` section of ~/.git-ai/config.json
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncDaemonConfig {
+    /// Repositories to keep in sync; the current repository when empty
+    #[serde(default)]
+    pub repos: Vec<PathBuf>,
+    /// Time between rounds, e.g. "15m", "1h" or "90s"
+    #[serde(default)]
+    pub interval: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SyncDaemonArgs {
+    pub interval: Option<Duration>,
+    pub once: bool,
+    pub repos_from: Option<String>,
+}
+
+/// Handle the `sync-daemon` command
+///
+/// Usage: git-ai sync-daemon [--interval <duration>] [--once] [--repos-from <file|->]
+///
+/// Fetches and pushes `refs/notes/ai` for each repository on a timer, so notes
+/// reach the remote even when nobody runs `git push` through git-ai. Each round
+/// also records edits left past `human_checkpoint_after_minutes` as human.
+pub fn handle_sync_daemon(args: &[String]) {
+    let parsed = match parse_sync_daemon_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => exit_with_error("Error", &GitAiError::InvalidArguments(e)),
+    };
+    let config = Config::get().sync_daemon();
+
+    let interval = match parsed.interval {
+        Some(interval) => interval,
+        None => match config.interval.as_deref().map(parse_interval) {
+            Some(Ok(interval)) => interval,
+            Some(Err(e)) => exit_with_error("Error", &GitAiError::InvalidArguments(e)),
+            None => DEFAULT_INTERVAL,
+        },
+    };
+    let repos = match &parsed.repos_from {
+        Some(source) => match read_repo_list(source) {
+            Ok(repos) => repos,
+            Err(e) => exit_with_error("Failed to read repository list", &e),
+        },
+        None if !config.repos.is_empty() => config.repos.clone(),
+        None => vec![std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))],
+    };
+
+    loop {
+        let failures = sync_round(&repos);
+        if parsed.once {
+            if failures > 0 {
+                std::process::exit(1);
+            }
+            return;
+        }
+        std::thread::sleep(interval);
+    }
+}
+
+pub fn parse_sync_daemon_args(args: &[String]) -> Result<SyncDaemonArgs, String> {
+    let mut parsed = SyncDaemonArgs {
+        interval: None,
+        once: false,
+        repos_from: None,
+    };
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--once" => parsed.once = true,
+            flag @ ("--interval" | "--repos-from") => {
+                let value = args
+                    .get(i + 1)
+                    .ok_or_else(|| format!("{} requires a value", flag))?;
+                if flag == "--interval" {
+                    parsed.interval = Some(parse_interval(value)?);
+                } else {
+                    parsed.repos_from = Some(value.clone());
+                }
+                i += 1;
+            }
+            other => return Err(format!("Unknown argument: {}", other)),
+        }
+        i += 1;
+    }
+    Ok(parsed)
+}
+
+/// Parse "15m", "2h", "30s" or a bare number of seconds
+pub fn parse_interval(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(split) => value.split_at(split),
+        None => (value, "s"),
+    };
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("Invalid interval '{}'", value))?;
+    let seconds = match unit {
+        "s" => number,
+        "m" => number * 60,
+        "h" => number * 60 * 60,
+        _ => return Err(format!("Invalid interval '{}' (use s, m or h)", value)),
+    };
+    if seconds == 0 {
+        return Err("Interval must be greater than zero".to_string());
+    }
+    Ok(Duration::from_secs(seconds))
+}
+
+/// Sync every repository once, returning how many failed
+fn sync_round(repos: &[PathBuf]) -> usize {
+    let mut failures = 0;
+    for path in repos {
+        let result = find_repository_in_path(&path.to_string_lossy()).and_then(|repo| {
+            auto_checkpoint::record_if_due(&repo);
+            sync_repository(&repo)
+        });
+        match result {
+            Ok(Some(remote)) if !output::quiet() => {
+                eprintln!(
+                    "{}: synced authorship notes with {}",
+                    path.display(),
+                    remote
+                );
+            }
+            Ok(None) if !output::quiet() => {
+                eprintln!("{}: no remote configured, skipping", path.display());
+            }
+            Ok(_) => {}
+            Err(e) => {
+                failures += 1;
+                eprintln!("{}: sync failed: {}", path.display(), e);
+            }
+        }
+    }
+    failures
+}
+
+/// Fetch then push notes for the upstream (or default) remote
+fn sync_repository(repo: &Repository) -> Result<Option<String>, GitAiError> {
+    let remote = match repo.upstream_remote()? {
+        Some(remote) => remote,
+        None => match repo.get_default_remote()? {
+            Some(remote) => remote,
+            None => return Ok(None),
+        },
+    };
+    // Fetch first so the push merges rather than being rejected
+    fetch_authorship_notes(repo, &remote)?;
+    push_authorship_notes(repo, &remote)?;
+    Ok(Some(remote))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("15m"), Ok(Duration::from_secs(900)));
+        assert_eq!(parse_interval("2h"), Ok(Duration::from_secs(7200)));
+        assert_eq!(parse_interval("45"), Ok(Duration::from_secs(45)));
+        assert!(parse_interval("0s").is_err());
+        assert!(parse_interval("5d").is_err());
+        assert!(parse_interval("m").is_err());
+    }
+
+    #[test]
+    fn test_parse_sync_daemon_args() {
+        assert_eq!(
+            parse_sync_daemon_args(&args(&["--interval", "30s", "--once"])).unwrap(),
+            SyncDaemonArgs {
+                interval: Some(Duration::from_secs(30)),
+                once: true,
+                repos_from: None,
+            }
+        );
+        assert!(parse_sync_daemon_args(&args(&["--interval"])).is_err());
+        assert!(parse_sync_daemon_args(&args(&["--bogus"])).is_err());
+
+        let config: SyncDaemonConfig =
+            serde_json::from_str(r#"{"repos":["/src/a"],"interval":"1h"}"#).unwrap();
+        assert_eq!(config.repos, vec![PathBuf::from("/src/a")]);
+    }
+}
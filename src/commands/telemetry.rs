@@ -0,0 +1,100 @@
+use crate::config::{self, Config};
+use crate::error::exit_with_error;
+use crate::git::find_repository;
+use crate::observability::aggregates;
+
+/// Handle the `telemetry` command
+///
+/// Usage: git-ai telemetry <preview|status|enable|disable>
+pub fn handle_telemetry(args: &[String]) {
+    match args.first().map(String::as_str) {
+        Some("preview") => handle_preview(),
+        Some("status") => handle_status(),
+        Some("enable") => set_aggregates(true),
+        Some("disable") => set_aggregates(false),
+        Some(other) => {
+            eprintln!("Unknown telemetry subcommand: {}", other);
+            print_telemetry_help_and_exit();
+        }
+        None => print_telemetry_help_and_exit(),
+    }
+}
+
+/// Print the exact event `flush-logs` would send for this repository
+fn handle_preview() {
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => exit_with_error("Failed to find repository", &e),
+    };
+    let report = match aggregates::collect(&repo) {
+        Ok(report) => report,
+        Err(e) => exit_with_error("Failed to collect aggregates", &e),
+    };
+    let event = aggregates::sentry_event(&report);
+    println!("{}", serde_json::to_string_pretty(&event).unwrap());
+
+    let config = Config::get();
+    if config.is_telemetry_oss_disabled() {
+        eprintln!("OSS telemetry is disabled, so nothing is sent.");
+    } else if config.telemetry_aggregates_enabled() {
+        eprintln!("Aggregates are enabled: this is sent at most once a day per repository.");
+    } else {
+        eprintln!("Aggregates are not enabled. Run `git-ai telemetry enable` to opt in.");
+    }
+}
+
+fn handle_status() {
+    let config = Config::get();
+    let state = if config.is_telemetry_oss_disabled() {
+        "disabled (OSS telemetry is off)"
+    } else if config.telemetry_aggregates_enabled() {
+        "enabled"
+    } else {
+        "not enabled"
+    };
+    println!("Aggregate telemetry: {}", state);
+}
+
+fn set_aggregates(enabled: bool) {
+    let path = match config::update_file_config(|cfg| {
+        cfg.insert(
+            "telemetry_aggregates".to_string(),
+            serde_json::Value::Bool(enabled),
+        );
+    }) {
+        Ok(path) => path,
+        Err(e) => exit_with_error("Failed to update config", &e),
+    };
+    if enabled {
+        println!(
+            "Enabled aggregate telemetry in {}. Run `git-ai telemetry preview` to see what is sent.",
+            path.display()
+        );
+        if Config::get().is_telemetry_oss_disabled() {
+            eprintln!(
+                "Note: OSS telemetry is disabled, so nothing is sent until it is re-enabled."
+            );
+        }
+    } else {
+        println!("Disabled aggregate telemetry in {}", path.display());
+    }
+}
+
+fn print_telemetry_help_and_exit() -> ! {
+    eprintln!("git-ai telemetry - Opt-in aggregate attribution metrics");
+    eprintln!();
+    eprintln!("Usage: git-ai telemetry <preview|status|enable|disable>");
+    eprintln!();
+    eprintln!("Only counts are sent: checkpoints by kind, commits by AI share and commits per");
+    eprintln!(
+        "agent tool over the last {} days. Never file paths, prompts or remotes.",
+        aggregates::PERIOD_DAYS
+    );
+    eprintln!();
+    eprintln!("Commands:");
+    eprintln!("  preview    Print exactly what would be sent for this repository");
+    eprintln!("  status     Show whether aggregates are enabled");
+    eprintln!("  enable     Opt in");
+    eprintln!("  disable    Opt out");
+    std::process::exit(1);
+}
@@ -1,7 +1,13 @@
+use crate::commands::output::{self, paint};
 use crate::config::{self, UpdateChannel};
+use crate::error::{GitAiError, exit_with_error};
+use minisign_verify::{PublicKey, Signature};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fs;
 use std::io::IsTerminal;
+#[cfg(not(windows))]
+use std::io::Write;
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
@@ -19,6 +25,13 @@ const INSTALL_SCRIPT_URL: &str =
 #[cfg(windows)]
 const INSTALL_SCRIPT_PS1_URL: &str =
     "https://raw.githubusercontent.com/acunniffe/git-ai/main/install.ps1";
+/// minisign public key the install scripts and release binaries are signed with,
+/// embedded by the release build. Without it nothing can be verified, so upgrades
+/// are refused unless `--insecure` is passed.
+const RELEASE_PUBLIC_KEY: Option<&str> = option_env!("GIT_AI_RELEASE_PUBLIC_KEY");
+const RELEASE_DOWNLOAD_URL: &str = "https://github.com/acunniffe/git-ai/releases/download";
+/// Verified binary for the install script to install instead of downloading its own
+const GIT_AI_LOCAL_BINARY_ENV: &str = "GIT_AI_LOCAL_BINARY";
 const RELEASES_API_URL: &str = "https://usegitai.com/api/releases";
 const GIT_AI_RELEASE_ENV: &str = "GIT_AI_RELEASE_TAG";
 const BACKGROUND_SPAWN_THROTTLE_SECS: u64 = 60;
@@ -223,7 +236,159 @@ fn try_mock_releases(
     )
 }
 
-fn run_install_script_for_tag(tag: &str, silent: bool) -> Result<(), String> {
+/// A downloaded release file (install script or binary) and its detached
+/// verification files
+struct ReleaseArtifact {
+    contents: Vec<u8>,
+    /// Contents of `<file>.sha256`, in `sha256sum` format
+    checksum: Option<String>,
+    /// Contents of `<file>.minisig`
+    signature: Option<String>,
+}
+
+fn download(url: &str) -> Result<Option<Vec<u8>>, String> {
+    let response = minreq::get(url)
+        .with_header(
+            "User-Agent",
+            format!("git-ai/{}", env!("CARGO_PKG_VERSION")),
+        )
+        .with_timeout(30)
+        .send()
+        .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+    match response.status_code {
+        200..=299 => Ok(Some(response.into_bytes())),
+        404 => Ok(None),
+        status => Err(format!("Failed to download {}: HTTP {}", url, status)),
+    }
+}
+
+fn fetch_release_artifact(url: &str) -> Result<ReleaseArtifact, String> {
+    let contents = download(url)?.ok_or_else(|| format!("Release file not found: {}", url))?;
+    let text = |bytes: Vec<u8>| String::from_utf8_lossy(&bytes).into_owned();
+    Ok(ReleaseArtifact {
+        contents,
+        checksum: download(&format!("{}.sha256", url))?.map(text),
+        signature: download(&format!("{}.minisig", url))?.map(text),
+    })
+}
+
+/// Check the artifact's SHA-256 checksum and minisign signature against
+/// `public_key`. A mismatch always fails; a missing key, signature or checksum (or
+/// an invalid signature) is only tolerated with `insecure`, and is returned as a warning.
+fn verify_artifact(
+    artifact: &ReleaseArtifact,
+    name: &str,
+    public_key: Option<&str>,
+    insecure: bool,
+) -> Result<Vec<String>, String> {
+    let mut warnings = Vec::new();
+    let mut refuse = |problem: String| {
+        if insecure {
+            warnings.push(problem);
+            Ok(())
+        } else {
+            Err(format!(
+                "{}. Refusing to install an unverified {} (pass --insecure to override).",
+                problem, name
+            ))
+        }
+    };
+
+    match artifact
+        .checksum
+        .as_deref()
+        .and_then(|c| c.split_whitespace().next())
+    {
+        Some(expected) => {
+            let actual = format!("{:x}", Sha256::digest(&artifact.contents));
+            if !expected.eq_ignore_ascii_case(&actual) {
+                return Err(format!(
+                    "Checksum mismatch for downloaded {} (expected {}, got {})",
+                    name, expected, actual
+                ));
+            }
+        }
+        None => refuse(format!("The {} has no SHA-256 checksum", name))?,
+    }
+
+    match (public_key, artifact.signature.as_deref()) {
+        (None, _) => refuse(format!(
+            "This build has no release signing key, so the {} can't be verified",
+            name
+        ))?,
+        (Some(_), None) => refuse(format!("The {} is not signed", name))?,
+        (Some(public_key), Some(signature)) => {
+            let verified = PublicKey::from_base64(public_key)
+                .and_then(|key| {
+                    Signature::decode(signature)
+                        .and_then(|signature| key.verify(&artifact.contents, &signature, false))
+                })
+                .map_err(|e| format!("Signature verification failed for the {}: {}", name, e));
+            if let Err(problem) = verified {
+                refuse(problem)?;
+            }
+        }
+    }
+
+    Ok(warnings)
+}
+
+/// Download the release file at `url` and verify it against the embedded release key
+fn fetch_verified(url: &str, name: &str, insecure: bool) -> Result<Vec<u8>, String> {
+    // Fail before downloading anything when verification can't succeed
+    if RELEASE_PUBLIC_KEY.is_none() && !insecure {
+        return Err(format!(
+            "This build has no release signing key, so the {} can't be verified. \
+             Refusing to upgrade (pass --insecure to override).",
+            name
+        ));
+    }
+    let artifact = fetch_release_artifact(url)?;
+    for warning in verify_artifact(&artifact, name, RELEASE_PUBLIC_KEY, insecure)? {
+        eprintln!("{} {}", paint("\x1b[1;33m", "Warning:"), warning);
+    }
+    Ok(artifact.contents)
+}
+
+/// Release asset name of the git-ai binary for this platform
+fn release_binary_name() -> String {
+    let os = match std::env::consts::OS {
+        "macos" => "darwin",
+        other => other,
+    };
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "x64",
+        "aarch64" => "arm64",
+        other => other,
+    };
+    format!("git-ai-{}-{}{}", os, arch, std::env::consts::EXE_SUFFIX)
+}
+
+/// Download and verify the release binary for `tag`, and stage it under
+/// ~/.git-ai/upgrade for the install script. The running executable is only
+/// replaced once these bytes have passed verification.
+fn stage_verified_binary(tag: &str, insecure: bool) -> Result<PathBuf, String> {
+    let name = release_binary_name();
+    let url = format!("{}/{}/{}", RELEASE_DOWNLOAD_URL, tag, name);
+    let binary = fetch_verified(&url, "release binary", insecure)?;
+
+    let dir = dirs::home_dir()
+        .ok_or_else(|| "Could not determine home directory".to_string())?
+        .join(".git-ai")
+        .join("upgrade");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = dir.join(format!("{}-{}", tag, name));
+    fs::write(&path, binary).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Failed to make {} executable: {}", path.display(), e))?;
+    }
+    Ok(path)
+}
+
+fn run_install_script_for_tag(tag: &str, silent: bool, insecure: bool) -> Result<(), String> {
     #[cfg(windows)]
     {
         // On Windows, we need to run the installer detached because the current git-ai
@@ -246,6 +411,14 @@ fn run_install_script_for_tag(tag: &str, silent: bool) -> Result<(), String> {
         fs::write(&log_file, format!("Starting upgrade at PID {}\n", pid))
             .map_err(|e| format!("Failed to create log file: {}", e))?;
 
+        // The verified script is saved for the installer to run instead of fetching it again
+        let script = fetch_verified(INSTALL_SCRIPT_PS1_URL, "install script", insecure)?;
+        let binary = stage_verified_binary(tag, insecure)?;
+        let script_file = log_dir.join(format!("install-{}.ps1", pid));
+        fs::write(&script_file, script)
+            .map_err(|e| format!("Failed to write install script: {}", e))?;
+        let fetch_script = format!("Get-Content -Raw -Path '{}'", script_file.to_string_lossy());
+
         // PowerShell script that handles its own logging
         // The script captures all output using Start-Transcript
         let ps_script = format!(
@@ -254,7 +427,7 @@ fn run_install_script_for_tag(tag: &str, silent: bool) -> Result<(), String> {
              Write-Host 'Fetching install script from {}'; \
              try {{ \
                  $ErrorActionPreference = 'Continue'; \
-                 $script = Invoke-RestMethod -Uri '{}' -UseBasicParsing; \
+                 $script = {}; \
                  Write-Host 'Running install script...'; \
                  Invoke-Expression $script; \
                  Write-Host 'Install script completed'; \
@@ -264,7 +437,7 @@ fn run_install_script_for_tag(tag: &str, silent: bool) -> Result<(), String> {
              }} finally {{ \
                  Stop-Transcript | Out-Null; \
              }}",
-            log_path_str, INSTALL_SCRIPT_PS1_URL, INSTALL_SCRIPT_PS1_URL
+            log_path_str, INSTALL_SCRIPT_PS1_URL, fetch_script
         );
 
         let mut cmd = Command::new("powershell");
@@ -273,7 +446,8 @@ fn run_install_script_for_tag(tag: &str, silent: bool) -> Result<(), String> {
             .arg("Bypass")
             .arg("-Command")
             .arg(&ps_script)
-            .env(GIT_AI_RELEASE_ENV, tag);
+            .env(GIT_AI_RELEASE_ENV, tag)
+            .env(GIT_AI_LOCAL_BINARY_ENV, &binary);
 
         // Hide the spawned console to prevent any host/UI bleed-through
         cmd.creation_flags(CREATE_NO_WINDOW);
@@ -286,7 +460,11 @@ fn run_install_script_for_tag(tag: &str, silent: bool) -> Result<(), String> {
             Ok(_) => {
                 if !silent {
                     println!(
-                        "\x1b[1;33mNote: The installation is running in the background on Windows.\x1b[0m"
+                        "{}",
+                        paint(
+                            "\x1b[1;33m",
+                            "Note: The installation is running in the background on Windows."
+                        )
                     );
                     println!(
                         "This allows the current git-ai process to exit and release file locks."
@@ -304,16 +482,26 @@ fn run_install_script_for_tag(tag: &str, silent: bool) -> Result<(), String> {
 
     #[cfg(not(windows))]
     {
+        let script = fetch_verified(INSTALL_SCRIPT_URL, "install script", insecure)?;
+        let binary = stage_verified_binary(tag, insecure)?;
+        // The verified script is fed to bash as-is rather than fetched again
         let mut cmd = Command::new("bash");
-        cmd.arg("-c")
-            .arg(format!("curl -fsSL {} | bash", INSTALL_SCRIPT_URL))
-            .env(GIT_AI_RELEASE_ENV, tag);
+        cmd.arg("-s")
+            .stdin(Stdio::piped())
+            .env(GIT_AI_RELEASE_ENV, tag)
+            .env(GIT_AI_LOCAL_BINARY_ENV, &binary);
 
         if silent {
             cmd.stdout(Stdio::null()).stderr(Stdio::null());
         }
 
-        match cmd.status() {
+        let status = cmd.spawn().and_then(|mut child| {
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin.write_all(&script)?;
+            }
+            child.wait()
+        });
+        match status {
             Ok(status) => {
                 if status.success() {
                     Ok(())
@@ -332,27 +520,33 @@ fn run_install_script_for_tag(tag: &str, silent: bool) -> Result<(), String> {
 pub fn run_with_args(args: &[String]) {
     let mut force = false;
     let mut background = false;
+    let mut insecure = false;
 
     for arg in args {
         match arg.as_str() {
             "--force" => force = true,
+            "--insecure" => insecure = true,
             "--background" => background = true, // Undocumented flag for internal use when spawning background process
             _ => {
-                eprintln!("Unknown argument: {}", arg);
-                eprintln!("Usage: git-ai upgrade [--force]");
-                std::process::exit(1);
+                exit_with_error(
+                    "Error",
+                    &GitAiError::InvalidArguments(format!(
+                        "Unknown argument: {}\nUsage: git-ai upgrade [--force] [--insecure]",
+                        arg
+                    )),
+                );
             }
         }
     }
 
-    run_impl(force, background);
+    run_impl(force, background, insecure);
 }
 
-fn run_impl(force: bool, background: bool) {
+fn run_impl(force: bool, background: bool, insecure: bool) {
     let config = config::Config::get();
     let channel = config.update_channel();
     let skip_install = background && config.auto_updates_disabled();
-    let _ = run_impl_with_url(force, None, channel, skip_install);
+    let _ = run_impl_with_url(force, None, channel, skip_install, insecure);
 }
 
 fn run_impl_with_url(
@@ -360,6 +554,7 @@ fn run_impl_with_url(
     api_base_url: Option<&str>,
     channel: UpdateChannel,
     skip_install: bool,
+    insecure: bool,
 ) -> UpgradeAction {
     let current_version = env!("CARGO_PKG_VERSION");
 
@@ -368,8 +563,7 @@ fn run_impl_with_url(
     let release = match fetch_release_for_channel(api_base_url, channel) {
         Ok(release) => release,
         Err(err) => {
-            eprintln!("{}", err);
-            std::process::exit(1);
+            exit_with_error("Failed to check for updates", &GitAiError::Generic(err));
         }
     };
 
@@ -391,7 +585,7 @@ fn run_impl_with_url(
             println!("You are already on the latest version!");
             println!();
             println!("To reinstall anyway, run:");
-            println!("  \x1b[1;36mgit-ai upgrade --force\x1b[0m");
+            println!("  {}", paint("\x1b[1;36m", "git-ai upgrade --force"));
             return action;
         }
         UpgradeAction::RunningNewerVersion => {
@@ -399,17 +593,20 @@ fn run_impl_with_url(
             println!("(This usually means you're running a development build)");
             println!();
             println!("To reinstall the selected release anyway, run:");
-            println!("  \x1b[1;36mgit-ai upgrade --force\x1b[0m");
+            println!("  {}", paint("\x1b[1;36m", "git-ai upgrade --force"));
             return action;
         }
         UpgradeAction::ForceReinstall => {
             println!(
-                "\x1b[1;33mForce mode enabled - reinstalling {}\x1b[0m",
-                release.tag
+                "{}",
+                paint(
+                    "\x1b[1;33m",
+                    &format!("Force mode enabled - reinstalling {}", release.tag)
+                )
             );
         }
         UpgradeAction::UpgradeAvailable => {
-            println!("\x1b[1;33mA new version is available!\x1b[0m");
+            println!("{}", paint("\x1b[1;33m", "A new version is available!"));
         }
     }
     println!();
@@ -421,17 +618,20 @@ fn run_impl_with_url(
     println!("Running installation script...");
     println!();
 
-    match run_install_script_for_tag(&release.tag, false) {
+    match run_install_script_for_tag(&release.tag, false, insecure) {
         Ok(()) => {
             // On Windows, we spawn the installer in the background and can't verify success
             #[cfg(not(windows))]
             {
-                println!("\x1b[1;32m✓\x1b[0m Successfully installed {}!", release.tag);
+                println!(
+                    "{} Successfully installed {}!",
+                    paint("\x1b[1;32m", "✓"),
+                    release.tag
+                );
             }
         }
         Err(err) => {
-            eprintln!("{}", err);
-            std::process::exit(1);
+            exit_with_error("Upgrade failed", &GitAiError::Generic(err));
         }
     }
 
@@ -443,7 +643,7 @@ fn print_cached_notice(cache: &UpdateCache) {
         return;
     }
 
-    if !std::io::stdout().is_terminal() {
+    if !std::io::stdout().is_terminal() || output::quiet() {
         // Don't print the version check notice if stdout is not a terminal/interactive shell
         return;
     }
@@ -460,11 +660,16 @@ fn print_cached_notice(cache: &UpdateCache) {
 
     eprintln!();
     eprintln!(
-        "\x1b[1;33mA new version of git-ai is available: \x1b[1;32mv{}\x1b[0m → \x1b[1;32mv{}\x1b[0m",
-        current_version, available_version
+        "{} {} → {}",
+        paint("\x1b[1;33m", "A new version of git-ai is available:"),
+        paint("\x1b[1;32m", &format!("v{}", current_version)),
+        paint("\x1b[1;32m", &format!("v{}", available_version))
     );
     eprintln!(
-        "\x1b[1;33mRun \x1b[1;36mgit-ai upgrade\x1b[0m \x1b[1;33mto upgrade to the latest version.\x1b[0m"
+        "{} {} {}",
+        paint("\x1b[1;33m", "Run"),
+        paint("\x1b[1;36m", "git-ai upgrade"),
+        paint("\x1b[1;33m", "to upgrade to the latest version.")
     );
     eprintln!();
 }
@@ -600,6 +805,7 @@ mod tests {
             )),
             UpdateChannel::Latest,
             false,
+            false,
         );
         assert_eq!(action, UpgradeAction::UpgradeAvailable);
 
@@ -613,6 +819,7 @@ mod tests {
             Some(&mock_url(&same_version_payload)),
             UpdateChannel::Latest,
             false,
+            false,
         );
         assert_eq!(action, UpgradeAction::AlreadyLatest);
 
@@ -622,6 +829,7 @@ mod tests {
             Some(&mock_url(&same_version_payload)),
             UpdateChannel::Latest,
             false,
+            false,
         );
         assert_eq!(action, UpgradeAction::ForceReinstall);
 
@@ -633,6 +841,7 @@ mod tests {
             )),
             UpdateChannel::Latest,
             false,
+            false,
         );
         assert_eq!(action, UpgradeAction::RunningNewerVersion);
 
@@ -644,12 +853,75 @@ mod tests {
             )),
             UpdateChannel::Latest,
             false,
+            false,
         );
         assert_eq!(action, UpgradeAction::ForceReinstall);
 
         clear_test_cache_dir();
     }
 
+    const TEST_PUBLIC_KEY: &str = "RWQM37ZgkT75nu0swjRKGf1RYDsZwGUicWUEqFWJoAzIbcb2MnL5ZZOS";
+    const TEST_SCRIPT: &[u8] = b"#!/bin/sh\necho git-ai\n";
+    const TEST_SIGNATURE: &str = "untrusted comment: signature from git-ai release key
+RUQM37ZgkT75norB0LcwlfjpRxvss4TFZG8OiWNGrWJw5lymOJaYDBT9C47V5V7+gWbLSdJs5AEarBa7op1E9UtFrFWX6qwTHAo=
+trusted comment: git-ai v999.0.0
+TigJGUhzAN/F/cZ7jzGAeNZ9Wpc37yZg5yHKL+6p3fKpetSMBPHHdiUx0Zwa0D8VtsIiv/Kj98gnvOcvMh+ZBQ==
+";
+    const TEST_CHECKSUM: &str =
+        "155e7d5c38e7d8bd28105676e8037abf10a01362e6b1b423a8387f3e1ef52c9b  install.sh";
+
+    fn test_artifact() -> ReleaseArtifact {
+        ReleaseArtifact {
+            contents: TEST_SCRIPT.to_vec(),
+            checksum: Some(TEST_CHECKSUM.to_string()),
+            signature: Some(TEST_SIGNATURE.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_verify_artifact() {
+        let key = Some(TEST_PUBLIC_KEY);
+        assert_eq!(
+            verify_artifact(&test_artifact(), "install script", key, false),
+            Ok(vec![])
+        );
+
+        // Tampered script fails the checksum even with --insecure
+        let mut tampered = test_artifact();
+        tampered.contents.push(b'x');
+        assert!(verify_artifact(&tampered, "install script", key, true).is_err());
+
+        // Signed by a different key
+        let other_key = "RWStfBy7xBybGPMKF3i52CoqsEVyv5cQg7mSblaov4XmJAjtNd9IQ0kv";
+        assert!(
+            verify_artifact(&test_artifact(), "install script", Some(other_key), false).is_err()
+        );
+
+        let mut unsigned = test_artifact();
+        unsigned.signature = None;
+        assert!(verify_artifact(&unsigned, "install script", key, false).is_err());
+        assert_eq!(
+            verify_artifact(&unsigned, "install script", key, true)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        let mut no_checksum = test_artifact();
+        no_checksum.checksum = None;
+        assert!(verify_artifact(&no_checksum, "install script", key, false).is_err());
+
+        // No release key built in: refused unless --insecure, which still checks the checksum
+        assert!(verify_artifact(&test_artifact(), "install script", None, false).is_err());
+        assert_eq!(
+            verify_artifact(&test_artifact(), "install script", None, true)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(verify_artifact(&tampered, "install script", None, true).is_err());
+    }
+
     #[test]
     fn test_should_check_for_updates_respects_interval() {
         let now = current_timestamp();
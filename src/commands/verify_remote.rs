@@ -0,0 +1,346 @@
+use crate::commands::output;
+use crate::error::{GitAiError, exit_with_error};
+use crate::git::find_repository;
+use crate::git::refs::{commits_with_notes_in_ref, ref_exists, tracking_ref_for_remote};
+use crate::git::repository::{Repository, exec_git};
+use crate::git::sync_authorship::fetch_authorship_notes;
+use crate::utils::debug_log;
+use serde::Serialize;
+use std::collections::HashSet;
+
+const USAGE: &str = "Usage: git-ai verify-remote <remote> <branch> [--base <rev>] [--no-fetch]";
+
+#[derive(Debug, PartialEq)]
+pub struct VerifyRemoteArgs {
+    pub remote: String,
+    pub branch: String,
+    /// Where the branch will be merged; defaults to the remote's default branch
+    pub base: Option<String>,
+    pub no_fetch: bool,
+}
+
+pub fn parse_verify_remote_args(args: &[String]) -> Result<VerifyRemoteArgs, String> {
+    let mut positional = Vec::new();
+    let mut base = None;
+    let mut no_fetch = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--base" => {
+                i += 1;
+                base = Some(args.get(i).ok_or("--base requires a value")?.clone());
+            }
+            "--no-fetch" => no_fetch = true,
+            other if other.starts_with('-') => {
+                return Err(format!("Unknown option: {}", other));
+            }
+            other => positional.push(other.to_string()),
+        }
+        i += 1;
+    }
+
+    let [remote, branch]: [String; 2] = positional
+        .try_into()
+        .map_err(|_| "Expected <remote> and <branch>".to_string())?;
+    Ok(VerifyRemoteArgs {
+        remote,
+        branch,
+        base,
+        no_fetch,
+    })
+}
+
+/// Where a commit's authorship note was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteSource {
+    Local,
+    Remote,
+    Missing,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommitCoverage {
+    pub sha: String,
+    pub subject: String,
+    pub source: NoteSource,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CoverageReport {
+    pub remote: String,
+    /// The revisions compared, as resolved
+    pub branch: String,
+    pub base: String,
+    pub commits: Vec<CommitCoverage>,
+}
+
+impl CoverageReport {
+    pub fn missing(&self) -> impl Iterator<Item = &CommitCoverage> {
+        self.commits
+            .iter()
+            .filter(|commit| commit.source == NoteSource::Missing)
+    }
+
+    fn count(&self, source: NoteSource) -> usize {
+        self.commits
+            .iter()
+            .filter(|commit| commit.source == source)
+            .count()
+    }
+}
+
+/// Handle the `verify-remote` command
+///
+/// Usage: git-ai verify-remote <remote> <branch> [--base <rev>] [--no-fetch]
+///
+/// Checks that every commit `<branch>` would bring into `--base` has an
+/// authorship note, either locally or in the remote's `refs/notes/ai`, and
+/// exits non-zero listing the ones that don't. Meant as a pre-merge CI step.
+pub fn handle_verify_remote(args: &[String]) {
+    let args = match parse_verify_remote_args(args) {
+        Ok(args) => args,
+        Err(e) => {
+            exit_with_error(
+                "Error",
+                &GitAiError::InvalidArguments(format!("{}\n{}", e, USAGE)),
+            );
+        }
+    };
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => exit_with_error("Failed to find repository", &e),
+    };
+
+    let report = match verify_remote(&repo, &args) {
+        Ok(report) => report,
+        Err(e) => exit_with_error("verify-remote failed", &e),
+    };
+
+    if output::json_requested(false) {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).unwrap_or_default()
+        );
+    } else {
+        print_report(&report);
+    }
+    if report.missing().next().is_some() {
+        std::process::exit(1);
+    }
+}
+
+pub fn verify_remote(
+    repo: &Repository,
+    args: &VerifyRemoteArgs,
+) -> Result<CoverageReport, GitAiError> {
+    // Read before fetching, which merges the remote's notes into the local ref
+    let local = commits_with_notes_in_ref(repo, "refs/notes/ai")?;
+    if !args.no_fetch
+        && let Err(e) = fetch_authorship_notes(repo, &args.remote)
+    {
+        return Err(GitAiError::Generic(format!(
+            "Failed to fetch authorship notes from '{}': {}",
+            args.remote, e
+        )));
+    }
+    let remote = commits_with_notes_in_ref(repo, &tracking_ref_for_remote(&args.remote))?;
+
+    let branch = resolve_branch(repo, &args.remote, &args.branch);
+    let base = match &args.base {
+        Some(base) => base.clone(),
+        None => repo.remote_head(&args.remote).unwrap_or_else(|e| {
+            debug_log(&format!(
+                "no default branch for '{}', comparing with HEAD: {}",
+                args.remote, e
+            ));
+            "HEAD".to_string()
+        }),
+    };
+
+    let commits = commits_to_merge(repo, &branch, &base)?
+        .into_iter()
+        .map(|(sha, subject)| {
+            let source = classify(&sha, &local, &remote);
+            CommitCoverage {
+                sha,
+                subject,
+                source,
+            }
+        })
+        .collect();
+
+    Ok(CoverageReport {
+        remote: args.remote.clone(),
+        branch,
+        base,
+        commits,
+    })
+}
+
+/// `<remote>/<branch>` when there's a remote-tracking branch for it, so CI
+/// checks what was pushed rather than a stale local branch
+fn resolve_branch(repo: &Repository, remote: &str, branch: &str) -> String {
+    if ref_exists(repo, &format!("refs/remotes/{}/{}", remote, branch)) {
+        format!("{}/{}", remote, branch)
+    } else {
+        branch.to_string()
+    }
+}
+
+fn classify(sha: &str, local: &HashSet<String>, remote: &HashSet<String>) -> NoteSource {
+    if local.contains(sha) {
+        NoteSource::Local
+    } else if remote.contains(sha) {
+        NoteSource::Remote
+    } else {
+        NoteSource::Missing
+    }
+}
+
+/// Non-merge commits reachable from `branch` but not `base`, oldest first,
+/// as (sha, subject). Merge commits carry no authorship of their own.
+fn commits_to_merge(
+    repo: &Repository,
+    branch: &str,
+    base: &str,
+) -> Result<Vec<(String, String)>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("log".to_string());
+    args.push("--no-merges".to_string());
+    args.push("--reverse".to_string());
+    args.push("--format=%H%x1f%s".to_string());
+    args.push(branch.to_string());
+    args.push("--not".to_string());
+    args.push(base.to_string());
+    args.push("--".to_string());
+
+    let output = exec_git(&args)?;
+    let stdout = String::from_utf8(output.stdout)?;
+    Ok(stdout
+        .lines()
+        .filter_map(|line| line.split_once('\x1f'))
+        .map(|(sha, subject)| (sha.to_string(), subject.to_string()))
+        .collect())
+}
+
+fn print_report(report: &CoverageReport) {
+    if report.commits.is_empty() {
+        println!(
+            "No commits on {} that aren't in {}",
+            report.branch, report.base
+        );
+        return;
+    }
+    let missing: Vec<&CommitCoverage> = report.missing().collect();
+    println!(
+        "{} of {} commits on {} not in {} have authorship data ({} local, {} on {})",
+        report.commits.len() - missing.len(),
+        report.commits.len(),
+        report.branch,
+        report.base,
+        report.count(NoteSource::Local),
+        report.count(NoteSource::Remote),
+        report.remote
+    );
+    if missing.is_empty() {
+        return;
+    }
+    println!("Missing authorship data:");
+    for commit in missing {
+        println!(
+            "  {}  {}",
+            &commit.sha[..commit.sha.len().min(8)],
+            commit.subject
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_verify_remote_args() {
+        let parsed =
+            parse_verify_remote_args(&args(&["origin", "feature", "--base", "main"])).unwrap();
+        assert_eq!(
+            parsed,
+            VerifyRemoteArgs {
+                remote: "origin".to_string(),
+                branch: "feature".to_string(),
+                base: Some("main".to_string()),
+                no_fetch: false,
+            }
+        );
+        assert!(
+            parse_verify_remote_args(&args(&["origin", "feature", "--no-fetch"]))
+                .unwrap()
+                .no_fetch
+        );
+
+        assert!(parse_verify_remote_args(&args(&["origin"])).is_err());
+        assert!(parse_verify_remote_args(&args(&["origin", "a", "b"])).is_err());
+        assert!(parse_verify_remote_args(&args(&["origin", "feature", "--base"])).is_err());
+        assert!(parse_verify_remote_args(&args(&["origin", "feature", "--json"])).is_err());
+    }
+
+    #[test]
+    fn test_verify_remote_reports_gaps() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let mut file = tmp_repo.write_file("a.txt", "one\n", true).unwrap();
+        tmp_repo.trigger_checkpoint_with_author("dev").unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let main = tmp_repo.current_branch().unwrap();
+
+        tmp_repo.create_branch("feature").unwrap();
+        file.append("two\n").unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("Add two").unwrap();
+        // Committed without git-ai: no note anywhere
+        tmp_repo
+            .git_command(&["commit", "--allow-empty", "-m", "Untracked"])
+            .unwrap();
+        // Committed elsewhere, with its note only on the remote
+        tmp_repo
+            .git_command(&["commit", "--allow-empty", "-m", "From a teammate"])
+            .unwrap();
+        tmp_repo
+            .git_command(&["notes", "--ref=ai-remote/origin", "add", "-m", "{}", "HEAD"])
+            .unwrap();
+
+        let report = verify_remote(
+            tmp_repo.gitai_repo(),
+            &VerifyRemoteArgs {
+                remote: "origin".to_string(),
+                branch: "feature".to_string(),
+                base: Some(main),
+                no_fetch: true,
+            },
+        )
+        .unwrap();
+
+        let sources: Vec<(&str, NoteSource)> = report
+            .commits
+            .iter()
+            .map(|commit| (commit.subject.as_str(), commit.source))
+            .collect();
+        assert_eq!(
+            sources,
+            vec![
+                ("Add two", NoteSource::Local),
+                ("Untracked", NoteSource::Missing),
+                ("From a teammate", NoteSource::Remote),
+            ]
+        );
+        assert_eq!(report.missing().count(), 1);
+    }
+}
@@ -0,0 +1,294 @@
+use crate::authorship::working_log::CheckpointKind;
+use crate::commands::checkpoint;
+use crate::commands::output;
+use crate::commands::sync_daemon::parse_interval;
+use crate::config::Config;
+use crate::error::{GitAiError, exit_with_error};
+use crate::git::find_repository;
+use crate::git::repository::Repository;
+use crate::utils::debug_log;
+use notify::{RecursiveMode, Watcher};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// How long the working tree has to stay quiet before its edits are checkpointed
+const DEFAULT_IDLE: Duration = Duration::from_secs(30);
+
+/// Let the events from our own working log write arrive before listening again
+const SETTLE: Duration = Duration::from_millis(200);
+
+#[derive(Debug, PartialEq)]
+pub struct WatchArgs {
+    pub idle: Duration,
+    /// Checkpoint edits that have gone unrecorded this long, even while they
+    /// continue; defaults to `human_checkpoint_after_minutes`
+    pub max_pending: Option<Duration>,
+    pub detach: bool,
+}
+
+pub fn parse_watch_args(args: &[String]) -> Result<WatchArgs, String> {
+    let mut parsed = WatchArgs {
+        idle: DEFAULT_IDLE,
+        max_pending: None,
+        detach: false,
+    };
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--idle" => {
+                let value = args.get(i + 1).ok_or("--idle requires a value")?;
+                parsed.idle = parse_interval(value)?;
+                i += 1;
+            }
+            "--max-pending" => {
+                let value = args.get(i + 1).ok_or("--max-pending requires a value")?;
+                parsed.max_pending = Some(parse_interval(value)?);
+                i += 1;
+            }
+            "--detach" => parsed.detach = true,
+            other => return Err(format!("Unknown watch argument: {}", other)),
+        }
+        i += 1;
+    }
+    Ok(parsed)
+}
+
+/// Handle the `watch` command
+///
+/// Usage: git-ai watch [--idle <duration>] [--max-pending <duration>] [--detach]
+///
+/// Watches the working tree and records a Human checkpoint once edits have been
+/// quiet for `--idle`, so hand edits made between agent runs don't get folded
+/// into the next AI checkpoint. Edits that a checkpoint (e.g. an agent's hook)
+/// records first are left alone. In a long stretch of editing that never goes
+/// quiet, `--max-pending` checkpoints anyway once the oldest unrecorded edit is
+/// that old. `--detach` keeps watching in the background.
+pub fn handle_watch(args: &[String]) {
+    let parsed = match parse_watch_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => exit_with_error("Error", &GitAiError::InvalidArguments(e)),
+    };
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => exit_with_error("Failed to find repository", &e),
+    };
+
+    if parsed.detach {
+        let foreground_args: Vec<&String> = args.iter().filter(|a| *a != "--detach").collect();
+        match spawn_detached(&repo, &foreground_args) {
+            Ok(pid) => println!("Watching in the background (pid {})", pid),
+            Err(e) => exit_with_error("Failed to start watcher", &e),
+        }
+        return;
+    }
+
+    let max_pending = parsed
+        .max_pending
+        .or_else(|| Config::get().human_checkpoint_after());
+    if let Err(e) = watch(&repo, parsed.idle, max_pending) {
+        exit_with_error("Watch failed", &e);
+    }
+}
+
+fn spawn_detached(repo: &Repository, args: &[&String]) -> Result<u32, GitAiError> {
+    let mut cmd = Command::new(std::env::current_exe()?);
+    cmd.arg("watch")
+        .args(args)
+        .current_dir(repo.workdir()?)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    // Its own process group, so closing the terminal doesn't take it down
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    Ok(cmd.spawn()?.id())
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Change {
+    /// A file in the working tree
+    WorkingTree,
+    /// Something wrote the working log, i.e. a checkpoint was recorded
+    Checkpoint,
+    /// Git's own bookkeeping
+    Ignored,
+}
+
+pub fn classify(path: &Path, git_dir: &Path, working_logs: &Path) -> Change {
+    if path.starts_with(working_logs) {
+        Change::Checkpoint
+    } else if path.starts_with(git_dir) {
+        Change::Ignored
+    } else {
+        Change::WorkingTree
+    }
+}
+
+fn watch(
+    repo: &Repository,
+    idle: Duration,
+    max_pending: Option<Duration>,
+) -> Result<(), GitAiError> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)
+        .map_err(|e| GitAiError::Generic(format!("Failed to start file watcher: {}", e)))?;
+    let workdir = repo.workdir()?;
+    watcher
+        .watch(&workdir, RecursiveMode::Recursive)
+        .map_err(|e| {
+            GitAiError::Generic(format!("Failed to watch {}: {}", workdir.display(), e))
+        })?;
+    std::fs::create_dir_all(&repo.storage.working_logs)?;
+    // Covers a git dir that lives outside the working tree
+    if !repo.path().starts_with(&workdir) {
+        let _ = watcher.watch(&repo.storage.working_logs, RecursiveMode::Recursive);
+    }
+
+    let canonical = |path: &Path| path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let git_dir = canonical(repo.path());
+    let working_logs = canonical(&repo.storage.working_logs);
+    let author = match repo.config_get_str("user.name") {
+        Ok(Some(name)) if !name.trim().is_empty() => name,
+        _ => "unknown".to_string(),
+    };
+
+    if !output::quiet() {
+        eprintln!(
+            "Watching {} (checkpointing after {}s idle, Ctrl-C to stop)",
+            workdir.display(),
+            idle.as_secs()
+        );
+    }
+
+    // Time of the last working tree edit no checkpoint has recorded yet
+    let mut pending: Option<Instant> = None;
+    // ...and of the first of them
+    let mut pending_since: Option<Instant> = None;
+    loop {
+        let event = match pending {
+            Some(since) => {
+                let mut wait = idle.saturating_sub(since.elapsed());
+                if let (Some(max_pending), Some(first)) = (max_pending, pending_since) {
+                    wait = wait.min(max_pending.saturating_sub(first.elapsed()));
+                }
+                rx.recv_timeout(wait)
+            }
+            None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match event {
+            Ok(Ok(event)) if !event.kind.is_access() => {
+                for path in &event.paths {
+                    match classify(&canonical(path), &git_dir, &working_logs) {
+                        Change::WorkingTree => {
+                            pending = Some(Instant::now());
+                            pending_since = pending_since.or(pending);
+                        }
+                        // Whoever checkpointed already captured the edits so far
+                        Change::Checkpoint => {
+                            pending = None;
+                            pending_since = None;
+                        }
+                        Change::Ignored => {}
+                    }
+                }
+            }
+            Ok(_) => {}
+            Err(RecvTimeoutError::Timeout) => {
+                record_human_checkpoint(repo, &author);
+                // Skip the events from our own working log write
+                pending = None;
+                pending_since = None;
+                while let Ok(event) = rx.recv_timeout(SETTLE) {
+                    let edited = event.is_ok_and(|event| {
+                        !event.kind.is_access()
+                            && event.paths.iter().any(|path| {
+                                classify(&canonical(path), &git_dir, &working_logs)
+                                    == Change::WorkingTree
+                            })
+                    });
+                    if edited {
+                        pending = Some(Instant::now());
+                        pending_since = pending_since.or(pending);
+                    }
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+    }
+}
+
+fn record_human_checkpoint(repo: &Repository, author: &str) {
+    let result = checkpoint::run(
+        repo,
+        author,
+        CheckpointKind::Human,
+        false,
+        false,
+        true,
+        None,
+        false,
+    );
+    match result {
+        Ok((_, files_edited, _)) if files_edited > 0 => {
+            if !output::quiet() {
+                eprintln!(
+                    "[{}] Recorded human checkpoint ({} files)",
+                    chrono::Local::now().format("%H:%M:%S"),
+                    files_edited
+                );
+            }
+        }
+        Ok(_) => debug_log("watch: no human edits to checkpoint"),
+        Err(e) => eprintln!("Human checkpoint failed: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_watch_args_and_classify() {
+        let args: Vec<String> = vec!["--idle".into(), "2m".into(), "--detach".into()];
+        assert_eq!(
+            parse_watch_args(&args).unwrap(),
+            WatchArgs {
+                idle: Duration::from_secs(120),
+                max_pending: None,
+                detach: true,
+            }
+        );
+        let args: Vec<String> = vec!["--max-pending".into(), "15m".into()];
+        assert_eq!(
+            parse_watch_args(&args).unwrap().max_pending,
+            Some(Duration::from_secs(900))
+        );
+        assert_eq!(parse_watch_args(&[]).unwrap().idle, DEFAULT_IDLE);
+        assert!(parse_watch_args(&["--idle".to_string()]).is_err());
+        assert!(parse_watch_args(&["--bogus".to_string()]).is_err());
+
+        let git_dir = Path::new("/repo/.git");
+        let logs = Path::new("/repo/.git/ai/working_logs");
+        assert_eq!(
+            classify(Path::new("/repo/src/lib.rs"), git_dir, logs),
+            Change::WorkingTree
+        );
+        assert_eq!(
+            classify(
+                Path::new("/repo/.git/ai/working_logs/initial/checkpoints.jsonl"),
+                git_dir,
+                logs
+            ),
+            Change::Checkpoint
+        );
+        assert_eq!(
+            classify(Path::new("/repo/.git/index.lock"), git_dir, logs),
+            Change::Ignored
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! Windows counterpart of the proxy's Unix process-group handling.
+//!
+//! The proxied git process is put in a job object so it dies with git-ai
+//! instead of lingering when the console window is closed or git-ai crashes.
+//! Ctrl+C is handled the way a shell would:
+//!
+//! - Interactive runs (stdin is a console) leave git in our console process
+//!   group. Windows already delivers Ctrl+C to git and to any editor it
+//!   started, so git-ai just ignores the event, waits for git to finish and
+//!   exits with its status.
+//! - Non-interactive runs start git in its own process group, which Windows
+//!   shields from console Ctrl+C. The handler forwards the interrupt as a
+//!   Ctrl+Break to that group, the only console event that can target one.
+
+use std::os::windows::process::CommandExt;
+use std::process::{Child, Command};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+use windows_sys::Win32::System::Console::{
+    CTRL_BREAK_EVENT, CTRL_C_EVENT, CTRL_CLOSE_EVENT, CTRL_LOGOFF_EVENT, CTRL_SHUTDOWN_EVENT,
+    GenerateConsoleCtrlEvent, SetConsoleCtrlHandler,
+};
+use windows_sys::Win32::System::JobObjects::{
+    AssignProcessToJobObject, CreateJobObjectW, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    JOB_OBJECT_LIMIT_SILENT_BREAKAWAY_OK, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+    JobObjectExtendedLimitInformation, SetInformationJobObject, TerminateJobObject,
+};
+use windows_sys::Win32::System::Threading::CREATE_NEW_PROCESS_GROUP;
+use windows_sys::core::BOOL;
+
+const TRUE: BOOL = 1;
+const FALSE: BOOL = 0;
+
+/// Exit code git reports when it is ended by Ctrl+C (STATUS_CONTROL_C_EXIT)
+const CONTROL_C_EXIT: u32 = 0xC000_013A;
+
+static CHILD_PID: AtomicU32 = AtomicU32::new(0);
+static CHILD_ISOLATED: AtomicBool = AtomicBool::new(false);
+static CHILD_JOB: AtomicUsize = AtomicUsize::new(0);
+
+/// Whether git should get its own process group, mirroring the Unix rule:
+/// only when nobody is typing at it
+pub fn should_isolate(stdin_is_terminal: bool) -> bool {
+    !stdin_is_terminal
+}
+
+/// Set creation flags before spawning git
+pub fn prepare_command(cmd: &mut Command, isolate: bool) {
+    if isolate {
+        cmd.creation_flags(CREATE_NEW_PROCESS_GROUP);
+    }
+}
+
+/// What the console handler does for a given event while git runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CtrlAction {
+    /// git already received the event; stay alive to report its status
+    Ignore,
+    /// Send Ctrl+Break to git's own process group
+    ForwardBreak,
+    /// The console is going away: take git down with us
+    TerminateJob,
+    /// Nothing is running; let the default handler exit git-ai
+    Default,
+}
+
+pub fn ctrl_action(ctrl_type: u32, child_running: bool, isolated: bool) -> CtrlAction {
+    if !child_running {
+        return CtrlAction::Default;
+    }
+    match ctrl_type {
+        CTRL_C_EVENT | CTRL_BREAK_EVENT if isolated => CtrlAction::ForwardBreak,
+        CTRL_C_EVENT | CTRL_BREAK_EVENT => CtrlAction::Ignore,
+        CTRL_CLOSE_EVENT | CTRL_LOGOFF_EVENT | CTRL_SHUTDOWN_EVENT => CtrlAction::TerminateJob,
+        _ => CtrlAction::Default,
+    }
+}
+
+unsafe extern "system" fn console_ctrl_handler(ctrl_type: u32) -> BOOL {
+    let pid = CHILD_PID.load(Ordering::SeqCst);
+    let isolated = CHILD_ISOLATED.load(Ordering::SeqCst);
+    match ctrl_action(ctrl_type, pid != 0, isolated) {
+        CtrlAction::Ignore => TRUE,
+        CtrlAction::ForwardBreak => {
+            // The group id of a CREATE_NEW_PROCESS_GROUP child is its pid
+            unsafe { GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, pid) };
+            TRUE
+        }
+        CtrlAction::TerminateJob => {
+            let job = CHILD_JOB.load(Ordering::SeqCst);
+            if job != 0 {
+                unsafe { TerminateJobObject(job as HANDLE, CONTROL_C_EXIT) };
+            }
+            FALSE
+        }
+        CtrlAction::Default => FALSE,
+    }
+}
+
+/// Ties a spawned git process to git-ai until it exits. Dropping the guard
+/// removes the console handler and releases the job.
+pub struct ChildGuard {
+    job: HANDLE,
+}
+
+impl ChildGuard {
+    pub fn attach(child: &Child, isolated: bool) -> ChildGuard {
+        let job = unsafe { create_kill_on_close_job() };
+        if !job.is_null() {
+            use std::os::windows::io::AsRawHandle;
+            let assigned = unsafe { AssignProcessToJobObject(job, child.as_raw_handle()) };
+            if assigned == FALSE {
+                // Already in a job that forbids nesting; run without one
+                crate::utils::debug_log("Could not assign git to a job object");
+            }
+        }
+
+        CHILD_JOB.store(job as usize, Ordering::SeqCst);
+        CHILD_ISOLATED.store(isolated, Ordering::SeqCst);
+        CHILD_PID.store(child.id(), Ordering::SeqCst);
+        unsafe { SetConsoleCtrlHandler(Some(console_ctrl_handler), TRUE) };
+        ChildGuard { job }
+    }
+}
+
+impl Drop for ChildGuard {
+    fn drop(&mut self) {
+        unsafe { SetConsoleCtrlHandler(Some(console_ctrl_handler), FALSE) };
+        CHILD_PID.store(0, Ordering::SeqCst);
+        CHILD_JOB.store(0, Ordering::SeqCst);
+        if !self.job.is_null() {
+            unsafe { CloseHandle(self.job) };
+        }
+    }
+}
+
+/// A job that kills git when its last handle (ours) closes, including when
+/// git-ai itself dies. Processes git starts break away silently, so
+/// long-lived helpers like the fsmonitor daemon survive.
+unsafe fn create_kill_on_close_job() -> HANDLE {
+    let job = unsafe { CreateJobObjectW(std::ptr::null(), std::ptr::null()) };
+    if job.is_null() {
+        return job;
+    }
+    let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = unsafe { std::mem::zeroed() };
+    info.BasicLimitInformation.LimitFlags =
+        JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE | JOB_OBJECT_LIMIT_SILENT_BREAKAWAY_OK;
+    let ok = unsafe {
+        SetInformationJobObject(
+            job,
+            JobObjectExtendedLimitInformation,
+            &info as *const _ as *const std::ffi::c_void,
+            std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+        )
+    };
+    if ok == FALSE {
+        unsafe { CloseHandle(job) };
+        return std::ptr::null_mut();
+    }
+    job
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use windows_sys::Win32::System::JobObjects::IsProcessInJob;
+
+    #[test]
+    fn test_ctrl_action() {
+        assert_eq!(ctrl_action(CTRL_C_EVENT, false, false), CtrlAction::Default);
+        assert_eq!(ctrl_action(CTRL_C_EVENT, true, false), CtrlAction::Ignore);
+        assert_eq!(
+            ctrl_action(CTRL_C_EVENT, true, true),
+            CtrlAction::ForwardBreak
+        );
+        assert_eq!(
+            ctrl_action(CTRL_BREAK_EVENT, true, true),
+            CtrlAction::ForwardBreak
+        );
+        assert_eq!(
+            ctrl_action(CTRL_CLOSE_EVENT, true, false),
+            CtrlAction::TerminateJob
+        );
+        assert!(should_isolate(false));
+        assert!(!should_isolate(true));
+    }
+
+    #[test]
+    fn test_child_runs_in_job_and_reports_exit_code() {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", "exit 7"]);
+        prepare_command(&mut cmd, true);
+        let mut child = cmd.spawn().expect("spawn cmd");
+
+        let guard = ChildGuard::attach(&child, true);
+        if !guard.job.is_null() {
+            use std::os::windows::io::AsRawHandle;
+            let mut in_job: BOOL = FALSE;
+            unsafe { IsProcessInJob(child.as_raw_handle(), guard.job, &mut in_job) };
+            // The child may already have exited; only assert while it's alive
+            if child.try_wait().unwrap().is_none() {
+                assert_eq!(in_job, TRUE);
+            }
+        }
+        let status = child.wait().unwrap();
+        drop(guard);
+        assert_eq!(status.code(), Some(7));
+        assert_eq!(CHILD_PID.load(Ordering::SeqCst), 0);
+    }
+}
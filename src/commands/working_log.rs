@@ -0,0 +1,437 @@
+use crate::error::{GitAiError, exit_with_error};
+use crate::git::find_repository;
+use crate::git::repository::{Repository, exec_git, exec_git_stdin};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+const SNAPSHOT_VERSION: u32 = 1;
+const REF_PREFIX: &str = "refs/ai-working-log/";
+const CHECKPOINTS_FILE: &str = "checkpoints.jsonl";
+const INITIAL_FILE: &str = "INITIAL";
+
+/// An uncommitted agent session: the working log for one base commit, with the
+/// file versions its checkpoints point at
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkingLogSnapshot {
+    pub version: u32,
+    pub base_commit: String,
+    /// Raw lines of checkpoints.jsonl, kept as-is so newer fields survive the trip
+    pub checkpoints: Vec<serde_json::Value>,
+    pub blobs: BTreeMap<String, BlobContent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial: Option<serde_json::Value>,
+}
+
+/// A stored file version. Text stays readable in the snapshot; anything that
+/// isn't UTF-8 is kept as raw bytes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BlobContent {
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl BlobContent {
+    fn from_bytes(bytes: Vec<u8>) -> Self {
+        match String::from_utf8(bytes) {
+            Ok(text) => BlobContent::Text(text),
+            Err(e) => BlobContent::Bytes(e.into_bytes()),
+        }
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            BlobContent::Text(text) => text.as_bytes(),
+            BlobContent::Bytes(bytes) => bytes,
+        }
+    }
+}
+
+/// Handle the `working-log` command
+///
+/// Usage: git-ai working-log export [-o <file>] [--ref <name>]
+///        git-ai working-log import [<file>|-] [--ref <name>] [--force]
+pub fn handle_working_log(args: &[String]) {
+    let Some(subcommand) = args.first() else {
+        print_usage_and_exit();
+    };
+    let parsed = match parse_transfer_args(&args[1..]) {
+        Ok(parsed) => parsed,
+        Err(e) => exit_with_error("Error", &GitAiError::InvalidArguments(e)),
+    };
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => exit_with_error("Failed to find repository", &e),
+    };
+
+    let result = match subcommand.as_str() {
+        "export" => run_export(&repo, &parsed),
+        "import" => run_import(&repo, &parsed),
+        _ => print_usage_and_exit(),
+    };
+    if let Err(e) = result {
+        exit_with_error(&format!("working-log {} failed", subcommand), &e);
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct TransferArgs {
+    /// File to write or read; `-` or none means stdout/stdin
+    pub path: Option<String>,
+    pub ref_name: Option<String>,
+    pub force: bool,
+}
+
+pub fn parse_transfer_args(args: &[String]) -> Result<TransferArgs, String> {
+    let mut parsed = TransferArgs::default();
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-o" | "--output" | "--ref" => {
+                let value = args
+                    .get(i + 1)
+                    .ok_or_else(|| format!("{} requires a value", args[i]))?
+                    .clone();
+                if args[i] == "--ref" {
+                    parsed.ref_name = Some(value);
+                } else {
+                    parsed.path = Some(value);
+                }
+                i += 1;
+            }
+            "--force" => parsed.force = true,
+            other if other.starts_with('-') && other != "-" => {
+                return Err(format!("Unknown option: {}", other));
+            }
+            path => parsed.path = Some(path.to_string()),
+        }
+        i += 1;
+    }
+    if parsed.path.is_some() && parsed.ref_name.is_some() {
+        return Err("Use either a file or --ref, not both".to_string());
+    }
+    Ok(parsed)
+}
+
+fn head_sha(repo: &Repository) -> Result<String, GitAiError> {
+    repo.head()?.target()
+}
+
+/// Snapshot the working log for `base_commit`
+pub fn export_snapshot(
+    repo: &Repository,
+    base_commit: &str,
+) -> Result<WorkingLogSnapshot, GitAiError> {
+    let dir = repo.storage.working_logs.join(base_commit);
+
+    let mut checkpoints = Vec::new();
+    if let Ok(content) = fs::read_to_string(dir.join(CHECKPOINTS_FILE)) {
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            checkpoints.push(serde_json::from_str(line)?);
+        }
+    }
+
+    let mut blobs = BTreeMap::new();
+    if let Ok(entries) = fs::read_dir(dir.join("blobs")) {
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            blobs.insert(name, BlobContent::from_bytes(fs::read(entry.path())?));
+        }
+    }
+
+    let initial = match fs::read_to_string(dir.join(INITIAL_FILE)) {
+        Ok(content) => Some(serde_json::from_str(&content)?),
+        Err(_) => None,
+    };
+
+    Ok(WorkingLogSnapshot {
+        version: SNAPSHOT_VERSION,
+        base_commit: base_commit.to_string(),
+        checkpoints,
+        blobs,
+        initial,
+    })
+}
+
+/// Write `snapshot` as the working log for `base_commit`. An existing,
+/// non-empty working log is only replaced with `force`, and only after the
+/// snapshot has been validated and written out in full next to it.
+pub fn import_snapshot(
+    repo: &Repository,
+    snapshot: &WorkingLogSnapshot,
+    base_commit: &str,
+    force: bool,
+) -> Result<(), GitAiError> {
+    if snapshot.version != SNAPSHOT_VERSION {
+        return Err(GitAiError::Generic(format!(
+            "Unsupported working-log snapshot version {}",
+            snapshot.version
+        )));
+    }
+
+    let dir = repo.storage.working_logs.join(base_commit);
+    let existing = fs::read_to_string(dir.join(CHECKPOINTS_FILE)).unwrap_or_default();
+    if !existing.trim().is_empty() && !force {
+        return Err(GitAiError::Generic(format!(
+            "A working log already exists for {}; rerun with --force to replace it",
+            base_commit
+        )));
+    }
+
+    // Blob names are content hashes; refuse anything that could escape the directory
+    if let Some(name) = snapshot
+        .blobs
+        .keys()
+        .find(|name| name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.'))
+    {
+        return Err(GitAiError::Generic(format!("Invalid blob name: {}", name)));
+    }
+    let mut checkpoints = String::new();
+    for checkpoint in &snapshot.checkpoints {
+        checkpoints.push_str(&serde_json::to_string(checkpoint)?);
+        checkpoints.push('\n');
+    }
+    let initial = snapshot
+        .initial
+        .as_ref()
+        .map(serde_json::to_string_pretty)
+        .transpose()?;
+
+    let staging =
+        repo.storage
+            .working_logs
+            .join(format!(".{}.import-{}", base_commit, std::process::id()));
+    let written = write_working_log(&staging, snapshot, &checkpoints, initial.as_deref());
+    if let Err(e) = written {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    // Swap the new log in, keeping the old one until the rename succeeds
+    let previous =
+        repo.storage
+            .working_logs
+            .join(format!(".{}.previous-{}", base_commit, std::process::id()));
+    let had_previous = dir.exists();
+    if had_previous {
+        fs::rename(&dir, &previous)?;
+    }
+    if let Err(e) = fs::rename(&staging, &dir) {
+        if had_previous {
+            let _ = fs::rename(&previous, &dir);
+        }
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e.into());
+    }
+    if had_previous {
+        let _ = fs::remove_dir_all(&previous);
+    }
+    Ok(())
+}
+
+fn write_working_log(
+    dir: &Path,
+    snapshot: &WorkingLogSnapshot,
+    checkpoints: &str,
+    initial: Option<&str>,
+) -> Result<(), GitAiError> {
+    if dir.exists() {
+        fs::remove_dir_all(dir)?;
+    }
+    let blobs_dir = dir.join("blobs");
+    fs::create_dir_all(&blobs_dir)?;
+    for (name, content) in &snapshot.blobs {
+        fs::write(blobs_dir.join(name), content.as_bytes())?;
+    }
+    fs::write(dir.join(CHECKPOINTS_FILE), checkpoints)?;
+    if let Some(initial) = initial {
+        fs::write(dir.join(INITIAL_FILE), initial)?;
+    }
+    Ok(())
+}
+
+fn ref_for(name: &str) -> String {
+    format!("{}{}", REF_PREFIX, name)
+}
+
+fn run_export(repo: &Repository, args: &TransferArgs) -> Result<(), GitAiError> {
+    let snapshot = export_snapshot(repo, &head_sha(repo)?)?;
+    if snapshot.checkpoints.is_empty() && snapshot.initial.is_none() {
+        return Err(GitAiError::Generic(
+            "No uncommitted AI activity to export".to_string(),
+        ));
+    }
+    let json = serde_json::to_string(&snapshot)?;
+
+    if let Some(name) = &args.ref_name {
+        let mut hash_args = repo.global_args_for_exec();
+        hash_args.extend(["hash-object", "-w", "--stdin"].map(String::from));
+        let output = exec_git_stdin(&hash_args, json.as_bytes())?;
+        let blob = String::from_utf8(output.stdout)?.trim().to_string();
+
+        let mut update_args = repo.global_args_for_exec();
+        update_args.extend(["update-ref".to_string(), ref_for(name), blob]);
+        exec_git(&update_args)?;
+        eprintln!(
+            "Saved {} checkpoints to {} (share it with `git push <remote> {}`)",
+            snapshot.checkpoints.len(),
+            ref_for(name),
+            ref_for(name)
+        );
+        return Ok(());
+    }
+
+    match args.path.as_deref() {
+        None | Some("-") => println!("{}", json),
+        Some(path) => {
+            fs::write(path, json)?;
+            eprintln!(
+                "Saved {} checkpoints to {}",
+                snapshot.checkpoints.len(),
+                path
+            );
+        }
+    }
+    Ok(())
+}
+
+fn run_import(repo: &Repository, args: &TransferArgs) -> Result<(), GitAiError> {
+    let json = if let Some(name) = &args.ref_name {
+        let mut show_args = repo.global_args_for_exec();
+        show_args.extend(["cat-file".to_string(), "blob".to_string(), ref_for(name)]);
+        String::from_utf8(exec_git(&show_args)?.stdout)?
+    } else {
+        match args.path.as_deref() {
+            None | Some("-") => {
+                let mut content = String::new();
+                std::io::stdin().read_to_string(&mut content)?;
+                content
+            }
+            Some(path) => fs::read_to_string(path)?,
+        }
+    };
+    let snapshot: WorkingLogSnapshot = serde_json::from_str(&json)?;
+
+    // Attribution is content-based, so the session still applies if the same
+    // changes were carried onto a different commit
+    let head = head_sha(repo)?;
+    if head != snapshot.base_commit {
+        eprintln!(
+            "Warning: snapshot was taken on {}, importing onto HEAD {}",
+            &snapshot.base_commit[..snapshot.base_commit.len().min(8)],
+            &head[..head.len().min(8)]
+        );
+    }
+    import_snapshot(repo, &snapshot, &head, args.force)?;
+    eprintln!(
+        "Imported {} checkpoints for {}",
+        snapshot.checkpoints.len(),
+        &head[..head.len().min(8)]
+    );
+    Ok(())
+}
+
+fn print_usage_and_exit() -> ! {
+    eprintln!("git-ai working-log - Move uncommitted AI attribution between machines");
+    eprintln!();
+    eprintln!("Usage:");
+    eprintln!("  git-ai working-log export [-o <file>] [--ref <name>]");
+    eprintln!("  git-ai working-log import [<file>|-] [--ref <name>] [--force]");
+    std::process::exit(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_transfer_args() {
+        assert_eq!(parse_transfer_args(&[]).unwrap(), TransferArgs::default());
+        let parsed = parse_transfer_args(&args(&["session.json", "--force"])).unwrap();
+        assert_eq!(parsed.path.as_deref(), Some("session.json"));
+        assert!(parsed.force);
+        assert_eq!(
+            parse_transfer_args(&args(&["--ref", "laptop"]))
+                .unwrap()
+                .ref_name
+                .as_deref(),
+            Some("laptop")
+        );
+        assert!(parse_transfer_args(&args(&["a.json", "--ref", "x"])).is_err());
+        assert!(parse_transfer_args(&args(&["--bogus"])).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let (source, _, _) = TmpRepo::new_with_base_commit().unwrap();
+        let base = source.head_commit_sha().unwrap();
+        let dir = source.gitai_repo().storage.working_logs.join(&base);
+        fs::create_dir_all(dir.join("blobs")).unwrap();
+        fs::write(
+            dir.join(CHECKPOINTS_FILE),
+            "{\"kind\":\"AiAgent\",\"future_field\":1}\n",
+        )
+        .unwrap();
+        fs::write(dir.join("blobs").join("abc123"), "fn main() {}\n").unwrap();
+        fs::write(dir.join("blobs").join("def456"), [0xff, 0x00, 0xfe]).unwrap();
+
+        let snapshot = export_snapshot(source.gitai_repo(), &base).unwrap();
+        assert_eq!(snapshot.checkpoints.len(), 1);
+        assert_eq!(snapshot.blobs.len(), 2);
+        assert_eq!(
+            snapshot.blobs["abc123"],
+            BlobContent::Text("fn main() {}\n".to_string())
+        );
+        assert!(snapshot.initial.is_none());
+
+        let (target, _, _) = TmpRepo::new_with_base_commit().unwrap();
+        let repo = target.gitai_repo();
+        let head = target.head_commit_sha().unwrap();
+        import_snapshot(repo, &snapshot, &head, false).unwrap();
+        assert_eq!(
+            export_snapshot(repo, &head).unwrap().checkpoints,
+            snapshot.checkpoints
+        );
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let parsed: WorkingLogSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, snapshot);
+        let blob = repo
+            .storage
+            .working_logs
+            .join(&head)
+            .join("blobs")
+            .join("def456");
+        assert_eq!(fs::read(&blob).unwrap(), [0xff, 0x00, 0xfe]);
+
+        // A second import would clobber the session now in progress
+        assert!(import_snapshot(repo, &snapshot, &head, false).is_err());
+        import_snapshot(repo, &snapshot, &head, true).unwrap();
+
+        // A bad snapshot is rejected before the existing log is touched
+        let mut bad = snapshot.clone();
+        bad.blobs
+            .insert("../escape".to_string(), BlobContent::Text(String::new()));
+        assert!(import_snapshot(repo, &bad, &head, true).is_err());
+        assert_eq!(export_snapshot(repo, &head).unwrap(), {
+            let mut expected = snapshot.clone();
+            expected.base_commit = head.clone();
+            expected
+        });
+        let leftovers: Vec<_> = fs::read_dir(&repo.storage.working_logs)
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with('.'))
+            .collect();
+        assert!(leftovers.is_empty());
+    }
+}
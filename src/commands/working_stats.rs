@@ -1,12 +1,19 @@
 use crate::authorship::attribution_tracker::Attribution;
 use crate::authorship::virtual_attribution::VirtualAttributions;
-use crate::error::GitAiError;
+use crate::commands::output::{self, sgr};
+use crate::error::{GitAiError, exit_with_error};
 use crate::git::find_repository;
 use crate::git::repository::Repository;
+use crate::utils::debug_log;
+use notify::{RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
 
-// ANSI color codes for terminal output
+// ANSI color codes for terminal output, passed through `sgr` so --no-color drops them
 const COLOR_RESET: &str = "\x1b[0m";
 const COLOR_GREEN: &str = "\x1b[32m";  // human
 const COLOR_YELLOW: &str = "\x1b[33m"; // mixed
@@ -14,6 +21,9 @@ const COLOR_BLUE: &str = "\x1b[34m";   // AI
 const COLOR_GRAY: &str = "\x1b[90m";   // skipped
 const COLOR_CYAN: &str = "\x1b[36m";   // for emphasis
 
+/// Editors and agents write in bursts; wait for quiet before recomputing
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(200);
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkingStats {
     pub files_changed: usize,
@@ -138,15 +148,15 @@ fn calculate_file_stats(
     }
 
     // Debug: print basic info
-    eprintln!("DEBUG: content has {} lines", lines.len());
-    eprintln!("DEBUG: content.len() = {}", content.len());
-    eprintln!("DEBUG: {} attributions", attributions.len());
+    debug_log(&format!("content has {} lines", lines.len()));
+    debug_log(&format!("content.len() = {}", content.len()));
+    debug_log(&format!("{} attributions", attributions.len()));
 
     // Debug: print each line with accurate character positions
     for (i, line) in lines.iter().enumerate() {
         let (start, end) = line_boundaries.get(i).copied().unwrap_or((0, 0));
-        eprintln!("DEBUG: line {} (char {}-{}, len={}): {:?}",
-                  i, start, end - 1, end - start, line);
+        debug_log(&format!("line {} (char {}-{}, len={}): {:?}",
+                  i, start, end - 1, end - start, line));
     }
 
     // Mark each line with all its authors (in order)
@@ -154,8 +164,8 @@ fn calculate_file_stats(
         let start_char = attr.start;
         let end_char = attr.end.min(content.len());
 
-        eprintln!("DEBUG: attr[{}]: start={}, end={}, author={}",
-                  attr_idx, start_char, end_char, attr.author_id);
+        debug_log(&format!("attr[{}]: start={}, end={}, author={}",
+                  attr_idx, start_char, end_char, attr.author_id));
 
         // Find which lines this attribution covers
         for (line_idx, &(line_start, line_end)) in line_boundaries.iter().enumerate() {
@@ -163,14 +173,14 @@ fn calculate_file_stats(
             let overlaps = !(end_char <= line_start || start_char >= line_end);
 
             if overlaps {
-                eprintln!("DEBUG:   line {} (char {}-{}) overlaps with attr ({}-{})",
-                          line_idx, line_start, line_end - 1, start_char, end_char - 1);
+                debug_log(&format!("  line {} (char {}-{}) overlaps with attr ({}-{})",
+                          line_idx, line_start, line_end - 1, start_char, end_char - 1));
 
                 // Add this author to the line's author set
                 line_authors[line_idx].insert(attr.author_id.clone());
-                eprintln!("DEBUG:   line {} now has {} authors: {:?}",
+                debug_log(&format!("  line {} now has {} authors: {:?}",
                           line_idx, line_authors[line_idx].len(),
-                          line_authors[line_idx]);
+                          line_authors[line_idx]));
             }
         }
     }
@@ -185,15 +195,15 @@ fn calculate_file_stats(
         // Skip empty lines (lines with no content)
         let line_content = lines.get(line_idx).map(|s| s.trim()).unwrap_or("");
         if line_content.is_empty() {
-            eprintln!("DEBUG: line {} -> {}empty line -> skipping{}",
-                      line_idx, COLOR_GRAY, COLOR_RESET);
+            debug_log(&format!("line {} -> {}empty line -> skipping{}",
+                      line_idx, sgr(COLOR_GRAY), sgr(COLOR_RESET)));
             continue;
         }
 
         if authors.is_empty() {
             // No attribution at all = skip this line
-            eprintln!("DEBUG: line {} ({:?}) -> {}no authors -> skipping{}",
-                      line_idx, lines.get(line_idx), COLOR_GRAY, COLOR_RESET);
+            debug_log(&format!("line {} ({:?}) -> {}no authors -> skipping{}",
+                      line_idx, lines.get(line_idx), sgr(COLOR_GRAY), sgr(COLOR_RESET)));
             continue;
         } else if authors.len() == 1 {
             // Only one author
@@ -201,13 +211,13 @@ fn calculate_file_stats(
             if author == "human" {
                 pure_human_lines += 1;
                 total_lines += 1;
-                eprintln!("DEBUG: line {} ({:?}) -> {}single author: human{}",
-                          line_idx, lines.get(line_idx), COLOR_GREEN, COLOR_RESET);
+                debug_log(&format!("line {} ({:?}) -> {}single author: human{}",
+                          line_idx, lines.get(line_idx), sgr(COLOR_GREEN), sgr(COLOR_RESET)));
             } else {
                 pure_ai_lines += 1;
                 total_lines += 1;
-                eprintln!("DEBUG: line {} ({:?}) -> {}single author: ai{} ({})",
-                          line_idx, lines.get(line_idx), COLOR_BLUE, COLOR_RESET, author);
+                debug_log(&format!("line {} ({:?}) -> {}single author: ai{} ({})",
+                          line_idx, lines.get(line_idx), sgr(COLOR_BLUE), sgr(COLOR_RESET), author));
             }
         } else {
             // Multiple authors
@@ -215,23 +225,23 @@ fn calculate_file_stats(
                 // Human + AI(s) = mixed
                 mixed_lines += 1;
                 total_lines += 1;
-                eprintln!("DEBUG: line {} ({:?}) -> {}human + AI -> mixed{}",
-                          line_idx, lines.get(line_idx), COLOR_YELLOW, COLOR_RESET);
+                debug_log(&format!("line {} ({:?}) -> {}human + AI -> mixed{}",
+                          line_idx, lines.get(line_idx), sgr(COLOR_YELLOW), sgr(COLOR_RESET)));
             } else {
                 // AI + AI = pure_ai (multiple AI sessions still count as pure AI)
                 pure_ai_lines += 1;
                 total_lines += 1;
-                eprintln!("DEBUG: line {} ({:?}) -> {}multiple AI sessions -> pure_ai{}",
-                          line_idx, lines.get(line_idx), COLOR_BLUE, COLOR_RESET);
+                debug_log(&format!("line {} ({:?}) -> {}multiple AI sessions -> pure_ai{}",
+                          line_idx, lines.get(line_idx), sgr(COLOR_BLUE), sgr(COLOR_RESET)));
             }
         }
     }
 
-    eprintln!("DEBUG: final: {}human{}={}, {}ai{}={}, {}mixed{}={}, total={}",
-              COLOR_GREEN, COLOR_RESET, pure_human_lines,
-              COLOR_BLUE, COLOR_RESET, pure_ai_lines,
-              COLOR_YELLOW, COLOR_RESET, mixed_lines,
-              total_lines);
+    debug_log(&format!("final: {}human{}={}, {}ai{}={}, {}mixed{}={}, total={}",
+              sgr(COLOR_GREEN), sgr(COLOR_RESET), pure_human_lines,
+              sgr(COLOR_BLUE), sgr(COLOR_RESET), pure_ai_lines,
+              sgr(COLOR_YELLOW), sgr(COLOR_RESET), mixed_lines,
+              total_lines));
 
     Ok(FileStats {
         pure_human_lines,
@@ -285,7 +295,7 @@ fn glob_match(text: &str, pattern: &str) -> bool {
 
 /// Print working stats to terminal
 pub fn print_working_stats(stats: &WorkingStats) {
-    println!("\n{}Working Area Stats{} (uncommitted changes)", COLOR_CYAN, COLOR_RESET);
+    println!("\n{}Working Area Stats{} (uncommitted changes)", sgr(COLOR_CYAN), sgr(COLOR_RESET));
     println!("════════════════════════════════════════\n");
     println!("Files changed: {}\n", stats.files_changed);
 
@@ -307,40 +317,40 @@ pub fn print_working_stats(stats: &WorkingStats) {
 
     println!(
         "  {}you{}  {}{}{}{}{}{}{}{} {}{}ai{}",
-        COLOR_GREEN, COLOR_RESET,
-        COLOR_GREEN,
+        sgr(COLOR_GREEN), sgr(COLOR_RESET),
+        sgr(COLOR_GREEN),
         "█".repeat(human_bars),
-        COLOR_RESET,
-        COLOR_YELLOW,
+        sgr(COLOR_RESET),
+        sgr(COLOR_YELLOW),
         "▒".repeat(mixed_bars),
-        COLOR_RESET,
-        COLOR_BLUE,
+        sgr(COLOR_RESET),
+        sgr(COLOR_BLUE),
         "░".repeat(ai_bars),
-        COLOR_RESET,
-        COLOR_BLUE, COLOR_RESET
+        sgr(COLOR_RESET),
+        sgr(COLOR_BLUE), sgr(COLOR_RESET)
     );
 
     println!(
-        "     {}{:>8}{}{:>12}{}mixed{} {:>8}{}{:>12}{}{:>8}{}",
-        COLOR_GREEN, format!("{:.0}%", human_pct), COLOR_RESET,
-        "", COLOR_YELLOW, COLOR_RESET, format!("{:.1}%", mixed_pct),
-        "", COLOR_BLUE, COLOR_RESET, format!("{:.0}%", ai_pct), COLOR_RESET
+        "     {}{:>8}{}{:>12}{}mixed{} {:>8}{:>12}{}{:>8}{}",
+        sgr(COLOR_GREEN), format!("{:.0}%", human_pct), sgr(COLOR_RESET),
+        "", sgr(COLOR_YELLOW), sgr(COLOR_RESET), format!("{:.1}%", mixed_pct),
+        sgr(COLOR_BLUE), sgr(COLOR_RESET), format!("{:.0}%", ai_pct), sgr(COLOR_RESET)
     );
     println!();
 
-    println!("{}Summary:{}", COLOR_CYAN, COLOR_RESET);
+    println!("{}Summary:{}", sgr(COLOR_CYAN), sgr(COLOR_RESET));
     println!("  {}Pure human:{}   {}{}{} lines",
-              COLOR_GREEN, COLOR_RESET, COLOR_GREEN, stats.pure_human_lines, COLOR_RESET);
+              sgr(COLOR_GREEN), sgr(COLOR_RESET), sgr(COLOR_GREEN), stats.pure_human_lines, sgr(COLOR_RESET));
     println!("  {}Mixed (AI+human):{} {}{}{} lines",
-              COLOR_YELLOW, COLOR_RESET, COLOR_YELLOW, stats.mixed_lines, COLOR_RESET);
+              sgr(COLOR_YELLOW), sgr(COLOR_RESET), sgr(COLOR_YELLOW), stats.mixed_lines, sgr(COLOR_RESET));
     println!("  {}Pure AI:{}      {}{}{} lines",
-              COLOR_BLUE, COLOR_RESET, COLOR_BLUE, stats.pure_ai_lines, COLOR_RESET);
+              sgr(COLOR_BLUE), sgr(COLOR_RESET), sgr(COLOR_BLUE), stats.pure_ai_lines, sgr(COLOR_RESET));
     println!("  {}Total:{}        {} lines",
-              COLOR_CYAN, COLOR_RESET, stats.total_lines);
+              sgr(COLOR_CYAN), sgr(COLOR_RESET), stats.total_lines);
 
     // Print per-file breakdown
     if !stats.by_file.is_empty() {
-        println!("\n{}By file:{}:", COLOR_CYAN, COLOR_RESET);
+        println!("\n{}By file:{}:", sgr(COLOR_CYAN), sgr(COLOR_RESET));
         let mut files: Vec<_> = stats.by_file.iter().collect();
         files.sort_by(|a, b| b.1.total_lines.cmp(&a.1.total_lines));
 
@@ -349,12 +359,92 @@ pub fn print_working_stats(stats: &WorkingStats) {
                 println!(
                     "  {:30}: {}{}{} human, {}{}{} mixed, {}{}{} ai",
                     file,
-                    COLOR_GREEN, file_stats.pure_human_lines, COLOR_RESET,
-                    COLOR_YELLOW, file_stats.mixed_lines, COLOR_RESET,
-                    COLOR_BLUE, file_stats.pure_ai_lines, COLOR_RESET
+                    sgr(COLOR_GREEN), file_stats.pure_human_lines, sgr(COLOR_RESET),
+                    sgr(COLOR_YELLOW), file_stats.mixed_lines, sgr(COLOR_RESET),
+                    sgr(COLOR_BLUE), file_stats.pure_ai_lines, sgr(COLOR_RESET)
+                );
+            }
+        }
+    }
+}
+
+/// Whether a change at `path` can move the working stats. Git's own
+/// bookkeeping is noise, except for the working logs git-ai writes there.
+fn is_relevant_change(path: &Path, git_dir: &Path, working_logs: &Path) -> bool {
+    !path.starts_with(git_dir) || path.starts_with(working_logs)
+}
+
+/// Re-render the working stats whenever a checkpoint is written or a file in
+/// the working tree changes. Runs until interrupted.
+pub fn watch_working_stats(
+    repo: &Repository,
+    ignore_patterns: &[String],
+    json_output: bool,
+) -> Result<(), GitAiError> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)
+        .map_err(|e| GitAiError::Generic(format!("Failed to start file watcher: {}", e)))?;
+    let workdir = repo.workdir()?;
+    watcher
+        .watch(&workdir, RecursiveMode::Recursive)
+        .map_err(|e| {
+            GitAiError::Generic(format!("Failed to watch {}: {}", workdir.display(), e))
+        })?;
+    // Covers a git dir that lives outside the working tree
+    if !repo.path().starts_with(&workdir) {
+        std::fs::create_dir_all(&repo.storage.working_logs)?;
+        let _ = watcher.watch(&repo.storage.working_logs, RecursiveMode::Recursive);
+    }
+
+    let git_dir = repo
+        .path()
+        .canonicalize()
+        .unwrap_or_else(|_| repo.path().to_path_buf());
+    let working_logs = repo
+        .storage
+        .working_logs
+        .canonicalize()
+        .unwrap_or_else(|_| repo.storage.working_logs.clone());
+    let relevant = |event: &notify::Event| {
+        !event.kind.is_access()
+            && event.paths.iter().any(|path| {
+                let path = path.canonicalize().unwrap_or_else(|_| path.clone());
+                is_relevant_change(&path, &git_dir, &working_logs)
+            })
+    };
+
+    let mut last_rendered = None;
+    loop {
+        let stats = calculate_working_stats(repo, ignore_patterns)?;
+        let snapshot = serde_json::to_string(&stats)?;
+        if last_rendered.as_ref() != Some(&snapshot) {
+            if json_output {
+                // One object per line, so the stream can be piped into jq
+                println!("{}", snapshot);
+            } else if output::renderer().is_some() {
+                print!("\x1b[2J\x1b[H");
+                output::print_structured("working-stats", &stats)?;
+            } else {
+                print!("\x1b[2J\x1b[H");
+                print_working_stats(&stats);
+                println!(
+                    "\n{}Watching for changes (Ctrl-C to stop){}",
+                    sgr(COLOR_GRAY), sgr(COLOR_RESET)
                 );
             }
+            let _ = std::io::stdout().flush();
+            last_rendered = Some(snapshot);
         }
+
+        // Block until something relevant changes, then let the burst settle
+        loop {
+            match rx.recv() {
+                Ok(Ok(event)) if relevant(&event) => break,
+                Ok(_) => continue,
+                Err(_) => return Ok(()),
+            }
+        }
+        while rx.recv_timeout(WATCH_DEBOUNCE).is_ok() {}
     }
 }
 
@@ -363,14 +453,14 @@ pub fn handle_working_stats(args: &[String]) -> Result<(), GitAiError> {
     let repo = match find_repository(&Vec::new()) {
         Ok(repo) => repo,
         Err(e) => {
-            eprintln!("Failed to find repository: {}", e);
-            std::process::exit(1);
+            exit_with_error("Failed to find repository", &e);
         }
     };
 
     // Parse arguments
-    let mut json_output = false;
+    let mut json_output = output::json_requested(false);
     let mut ignore_patterns: Vec<String> = Vec::new();
+    let mut watch = false;
 
     let mut i = 0;
     while i < args.len() {
@@ -379,6 +469,10 @@ pub fn handle_working_stats(args: &[String]) -> Result<(), GitAiError> {
                 json_output = true;
                 i += 1;
             }
+            "--watch" => {
+                watch = true;
+                i += 1;
+            }
             "--ignore" => {
                 i += 1;
                 if i < args.len() && !args[i].starts_with("--") {
@@ -387,22 +481,46 @@ pub fn handle_working_stats(args: &[String]) -> Result<(), GitAiError> {
                 }
             }
             _ => {
-                eprintln!("Unknown argument: {}", args[i]);
-                std::process::exit(1);
+                exit_with_error(
+                    "Error",
+                    &GitAiError::InvalidArguments(format!("Unknown argument: {}", args[i])),
+                );
             }
         }
     }
 
+    if watch {
+        return watch_working_stats(&repo, &ignore_patterns, json_output);
+    }
+
     // Calculate stats
     let stats = calculate_working_stats(&repo, &ignore_patterns)?;
 
     // Output
-    if json_output {
-        let json_str = serde_json::to_string_pretty(&stats).unwrap();
-        println!("{}", json_str);
+    if json_output || output::renderer().is_some() {
+        output::print_structured("working-stats", &stats)?;
     } else {
         print_working_stats(&stats);
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watch_ignores_git_bookkeeping() {
+        let git_dir = Path::new("/repo/.git");
+        let working_logs = Path::new("/repo/.git/ai/working_logs");
+        let relevant = |path: &str| is_relevant_change(Path::new(path), git_dir, working_logs);
+
+        assert!(relevant("/repo/src/lib.rs"));
+        assert!(relevant(
+            "/repo/.git/ai/working_logs/base/checkpoints.jsonl"
+        ));
+        assert!(!relevant("/repo/.git/index.lock"));
+        assert!(!relevant("/repo/.git/objects/ab/cdef"));
+    }
+}
@@ -1,13 +1,30 @@
+use std::collections::BTreeMap;
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
+use std::time::Duration;
 
 use glob::Pattern;
 use serde::{Deserialize, Serialize};
 
+use crate::authorship::co_authors::CoAuthorTrailersConfig;
+use crate::authorship::imara_diff_utils::DiffAlgorithm;
+use crate::authorship::redaction::RedactionConfig;
+use crate::authorship::transcript_summary::TranscriptSummaryConfig;
+use crate::commands::blame_colors::BlameColorsConfig;
+use crate::commands::checkpoint_agent::plugin_preset::PresetPlugin;
+use crate::commands::proxy::ProxyConfig;
+use crate::commands::renderers::ExternalRenderer;
+use crate::commands::safe_mode::DEFAULT_SAFE_MODE_AFTER_FAILURES;
+use crate::commands::sync_daemon::SyncDaemonConfig;
+use crate::error::GitAiError;
+use crate::event_hooks::EventHooksConfig;
 use crate::feature_flags::FeatureFlags;
 use crate::git::repository::Repository;
+use crate::notifications::NotificationsConfig;
+use crate::observability::sinks::SinkConfig;
+use crate::policy::PolicyConfig;
 
 #[cfg(any(test, feature = "test-support"))]
 use std::sync::RwLock;
@@ -19,12 +36,46 @@ pub struct Config {
     ignore_prompts: bool,
     allow_repositories: Vec<Pattern>,
     exclude_repositories: Vec<Pattern>,
+    prompt_unlisted_repositories: bool,
     telemetry_oss_disabled: bool,
+    telemetry_aggregates: bool,
     telemetry_enterprise_dsn: Option<String>,
+    telemetry_otlp_endpoint: Option<String>,
+    telemetry_otlp_headers: BTreeMap<String, String>,
+    telemetry_sinks: Vec<SinkConfig>,
     disable_version_checks: bool,
     disable_auto_updates: bool,
     update_channel: UpdateChannel,
     feature_flags: FeatureFlags,
+    policy: PolicyConfig,
+    notifications: NotificationsConfig,
+    event_hooks: EventHooksConfig,
+    co_author_trailers: CoAuthorTrailersConfig,
+    redaction: RedactionConfig,
+    transcript_summary: TranscriptSummaryConfig,
+    proxy: ProxyConfig,
+    sync_daemon: SyncDaemonConfig,
+    disabled_hooks: Vec<String>,
+    read_only_commands: Vec<String>,
+    perf_records: bool,
+    human_checkpoint_after_minutes: u64,
+    push_summary: bool,
+    commit_summary: bool,
+    safe_mode_after_failures: u32,
+    checkpoint_max_file_bytes: u64,
+    diff_algorithm: DiffAlgorithm,
+    preset_plugins: BTreeMap<String, PresetPlugin>,
+    renderers: BTreeMap<String, ExternalRenderer>,
+    blame_colors: BlameColorsConfig,
+}
+
+/// A repository's standing against `allow_repositories` / `exclude_repositories`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RepositoryAccess {
+    Allowed,
+    Excluded,
+    /// Matches neither list
+    Unlisted,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -66,10 +117,20 @@ struct FileConfig {
     #[serde(default)]
     exclude_repositories: Option<Vec<String>>,
     #[serde(default)]
+    prompt_unlisted_repositories: Option<bool>,
+    #[serde(default)]
     telemetry_oss: Option<String>,
     #[serde(default)]
+    telemetry_aggregates: Option<bool>,
+    #[serde(default)]
     telemetry_enterprise_dsn: Option<String>,
     #[serde(default)]
+    telemetry_otlp_endpoint: Option<String>,
+    #[serde(default)]
+    telemetry_otlp_headers: Option<BTreeMap<String, String>>,
+    #[serde(default)]
+    telemetry_sinks: Option<Vec<SinkConfig>>,
+    #[serde(default)]
     disable_version_checks: Option<bool>,
     #[serde(default)]
     disable_auto_updates: Option<bool>,
@@ -77,10 +138,84 @@ struct FileConfig {
     update_channel: Option<String>,
     #[serde(default)]
     feature_flags: Option<serde_json::Value>,
+    #[serde(default)]
+    policy: Option<PolicyConfig>,
+    #[serde(default)]
+    notifications: Option<NotificationsConfig>,
+    #[serde(default)]
+    event_hooks: Option<EventHooksConfig>,
+    #[serde(default)]
+    co_author_trailers: Option<CoAuthorTrailersConfig>,
+    #[serde(default)]
+    redaction: Option<RedactionConfig>,
+    #[serde(default)]
+    transcript_summary: Option<TranscriptSummaryConfig>,
+    #[serde(default)]
+    proxy: Option<ProxyConfig>,
+    #[serde(default)]
+    sync_daemon: Option<SyncDaemonConfig>,
+    #[serde(default)]
+    disabled_hooks: Option<Vec<String>>,
+    #[serde(default)]
+    read_only_commands: Option<Vec<String>>,
+    #[serde(default)]
+    perf_records: Option<bool>,
+    #[serde(default)]
+    human_checkpoint_after_minutes: Option<u64>,
+    #[serde(default)]
+    push_summary: Option<bool>,
+    #[serde(default)]
+    commit_summary: Option<bool>,
+    #[serde(default)]
+    safe_mode_after_failures: Option<u32>,
+    #[serde(default)]
+    checkpoint_max_file_bytes: Option<u64>,
+    #[serde(default)]
+    diff_algorithm: Option<String>,
+    #[serde(default)]
+    preset_plugins: Option<BTreeMap<String, PresetPlugin>>,
+    #[serde(default)]
+    renderers: Option<BTreeMap<String, ExternalRenderer>>,
+    #[serde(default)]
+    colors: Option<BlameColorsConfig>,
 }
 
 static CONFIG: OnceLock<Config> = OnceLock::new();
 
+const DEFAULT_CHECKPOINT_MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Git subcommands that never change what git-ai tracks, so the proxy runs
+/// them without looking up the repository or running any hooks
+const DEFAULT_READ_ONLY_COMMANDS: &[&str] = &[
+    "status",
+    "log",
+    "show",
+    "diff",
+    "rev-parse",
+    "rev-list",
+    "ls-files",
+    "ls-tree",
+    "cat-file",
+    "describe",
+    "shortlog",
+    "grep",
+    "blame",
+    "for-each-ref",
+    "show-ref",
+    "name-rev",
+    "merge-base",
+    "check-ignore",
+    "var",
+    "version",
+];
+
+fn default_read_only_commands() -> Vec<String> {
+    DEFAULT_READ_ONLY_COMMANDS
+        .iter()
+        .map(|command| command.to_string())
+        .collect()
+}
+
 #[cfg(any(test, feature = "test-support"))]
 static TEST_FEATURE_FLAGS_OVERRIDE: RwLock<Option<FeatureFlags>> = RwLock::new(None);
 
@@ -128,42 +263,43 @@ impl Config {
     }
 
     pub fn is_allowed_repository(&self, repository: &Option<Repository>) -> bool {
-        // First check if repository is in exclusion list - exclusions take precedence
-        if !self.exclude_repositories.is_empty()
-            && let Some(repository) = repository
-        {
-            if let Some(remotes) = repository.remotes_with_urls().ok() {
-                // If any remote matches the exclusion patterns, deny access
-                if remotes.iter().any(|remote| {
-                    self.exclude_repositories
-                        .iter()
-                        .any(|pattern| pattern.matches(&remote.1))
-                }) {
-                    return false;
-                }
-            }
+        match self.repository_access(repository) {
+            RepositoryAccess::Allowed => true,
+            RepositoryAccess::Excluded => false,
+            // If allowlist is empty, allow everything (unless excluded)
+            RepositoryAccess::Unlisted => self.allow_repositories.is_empty(),
         }
+    }
 
-        // If allowlist is empty, allow everything (unless excluded above)
-        if self.allow_repositories.is_empty() {
-            return true;
-        }
+    /// Where the repository's remotes stand against `exclude_repositories` and
+    /// `allow_repositories`. Exclusions take precedence.
+    pub fn repository_access(&self, repository: &Option<Repository>) -> RepositoryAccess {
+        let Some(repository) = repository else {
+            return RepositoryAccess::Unlisted;
+        };
+        // Can't verify without remotes: neither list applies
+        let remotes = repository.remotes_with_urls().unwrap_or_default();
+        let matches = |patterns: &[Pattern]| {
+            remotes
+                .iter()
+                .any(|remote| patterns.iter().any(|pattern| pattern.matches(&remote.1)))
+        };
 
-        // If allowlist is defined, only allow repos whose remotes match the patterns
-        if let Some(repository) = repository {
-            match repository.remotes_with_urls().ok() {
-                Some(remotes) => remotes.iter().any(|remote| {
-                    self.allow_repositories
-                        .iter()
-                        .any(|pattern| pattern.matches(&remote.1))
-                }),
-                None => false, // Can't verify, deny by default when allowlist is active
-            }
+        if matches(&self.exclude_repositories) {
+            RepositoryAccess::Excluded
+        } else if matches(&self.allow_repositories) {
+            RepositoryAccess::Allowed
         } else {
-            false // No repository provided, deny by default when allowlist is active
+            RepositoryAccess::Unlisted
         }
     }
 
+    /// Ask on a terminal whether to enable git-ai for repositories that are
+    /// neither allowed nor excluded, saving the answer to those lists
+    pub fn prompt_unlisted_repositories(&self) -> bool {
+        self.prompt_unlisted_repositories
+    }
+
     /// Returns whether prompts should be ignored (currently unused by internal APIs).
     #[allow(dead_code)]
     pub fn ignore_prompts(&self) -> bool {
@@ -175,11 +311,32 @@ impl Config {
         self.telemetry_oss_disabled
     }
 
+    /// Whether the user opted in to sending aggregate attribution metrics with
+    /// OSS telemetry (see `git-ai telemetry preview`)
+    pub fn telemetry_aggregates_enabled(&self) -> bool {
+        self.telemetry_aggregates && !self.telemetry_oss_disabled
+    }
+
+    /// Extra `flush-logs` destinations from `telemetry_sinks`
+    pub fn telemetry_sinks(&self) -> &[SinkConfig] {
+        &self.telemetry_sinks
+    }
+
     /// Returns the telemetry_enterprise_dsn if set.
     pub fn telemetry_enterprise_dsn(&self) -> Option<&str> {
         self.telemetry_enterprise_dsn.as_deref()
     }
 
+    /// Returns the OTLP/HTTP collector endpoint if set.
+    pub fn telemetry_otlp_endpoint(&self) -> Option<&str> {
+        self.telemetry_otlp_endpoint.as_deref()
+    }
+
+    /// Returns extra headers (e.g. auth) to send with OTLP exports.
+    pub fn telemetry_otlp_headers(&self) -> &BTreeMap<String, String> {
+        &self.telemetry_otlp_headers
+    }
+
     pub fn version_checks_disabled(&self) -> bool {
         self.disable_version_checks
     }
@@ -196,6 +353,118 @@ impl Config {
         &self.feature_flags
     }
 
+    pub fn policy(&self) -> &PolicyConfig {
+        &self.policy
+    }
+
+    pub fn notifications(&self) -> &NotificationsConfig {
+        &self.notifications
+    }
+
+    /// Executables run after checkpoints and authorship writes
+    pub fn event_hooks(&self) -> &EventHooksConfig {
+        &self.event_hooks
+    }
+
+    pub fn co_author_trailers(&self) -> &CoAuthorTrailersConfig {
+        &self.co_author_trailers
+    }
+
+    /// Patterns scrubbed from transcripts before they're stored
+    pub fn redaction(&self) -> &RedactionConfig {
+        &self.redaction
+    }
+
+    /// Size cap for stored transcripts
+    pub fn transcript_summary(&self) -> &TranscriptSummaryConfig {
+        &self.transcript_summary
+    }
+
+    /// Per-subcommand flags and environment for `git-ai proxy`
+    pub fn proxy(&self) -> &ProxyConfig {
+        &self.proxy
+    }
+
+    /// Repositories and interval for `git-ai sync-daemon`
+    pub fn sync_daemon(&self) -> &SyncDaemonConfig {
+        &self.sync_daemon
+    }
+
+    /// False when `disabled_hooks` lists this git subcommand (e.g. "stash"),
+    /// so the proxy runs it without git-ai's pre/post-command hooks
+    pub fn command_hooks_enabled(&self, command: &str) -> bool {
+        !self
+            .disabled_hooks
+            .iter()
+            .any(|disabled| disabled == command)
+    }
+
+    /// True for subcommands in `read_only_commands` (a built-in list of
+    /// commands like `status` and `log` unless configured), which the proxy
+    /// passes straight to git
+    pub fn is_read_only_command(&self, command: &str) -> bool {
+        self.read_only_commands
+            .iter()
+            .any(|read_only| read_only == command)
+    }
+
+    /// Append per-command timings to `perf.jsonl` in the log directory, for
+    /// `git-ai perf report`
+    pub fn perf_records_enabled(&self) -> bool {
+        self.perf_records
+    }
+
+    /// Edits left unrecorded this long without any checkpoint are recorded
+    /// as a Human checkpoint by `git-ai watch` and `git-ai sync-daemon`. `None`
+    /// (the default, or 0) leaves it to idle detection and manual checkpoints.
+    pub fn human_checkpoint_after(&self) -> Option<Duration> {
+        (self.human_checkpoint_after_minutes > 0)
+            .then(|| Duration::from_secs(self.human_checkpoint_after_minutes * 60))
+    }
+
+    /// Print a one-line attribution summary of the pushed commits after `git push`
+    pub fn push_summary_enabled(&self) -> bool {
+        self.push_summary
+    }
+
+    /// Print a compact attribution block after each commit instead of the full stats
+    pub fn commit_summary_enabled(&self) -> bool {
+        self.commit_summary
+    }
+
+    /// Consecutive hook failures in a repository before the wrapper stops
+    /// running hooks there (see `commands::safe_mode`); 0 never does
+    pub fn safe_mode_after_failures(&self) -> u32 {
+        self.safe_mode_after_failures
+    }
+
+    /// Files larger than this (and binary files) are checkpointed as a whole,
+    /// with one author for the file instead of character attributions
+    pub fn checkpoint_max_file_bytes(&self) -> u64 {
+        self.checkpoint_max_file_bytes
+    }
+
+    /// Line diff algorithm for attribution in checkpoints and rewrites; see
+    /// `authorship::attribution_tracker::attribution_diff_algorithm`
+    pub fn diff_algorithm(&self) -> DiffAlgorithm {
+        self.diff_algorithm
+    }
+
+    /// External preset registered under `name` for `git-ai checkpoint <name>`
+    pub fn preset_plugin(&self, name: &str) -> Option<&PresetPlugin> {
+        self.preset_plugins.get(name)
+    }
+
+    /// External renderer registered under `name` for `--format <name>`
+    pub fn renderer_plugin(&self, name: &str) -> Option<&ExternalRenderer> {
+        self.renderers.get(name)
+    }
+
+    /// Colors for `git-ai blame` on a terminal (the `colors` section)
+    pub fn blame_colors(&self) -> &BlameColorsConfig {
+        &self.blame_colors
+    }
+
     /// Override feature flags for testing purposes.
     /// Only available when the `test-support` feature is enabled or in test mode.
     /// Must be `pub` to work with integration tests in the `tests/` directory.
@@ -275,15 +544,35 @@ fn build_config() -> Config {
                 .ok()
         })
         .collect();
+    let prompt_unlisted_repositories = file_cfg
+        .as_ref()
+        .and_then(|c| c.prompt_unlisted_repositories)
+        .unwrap_or(false);
     let telemetry_oss_disabled = file_cfg
         .as_ref()
         .and_then(|c| c.telemetry_oss.clone())
         .filter(|s| s == "off")
         .is_some();
+    let telemetry_aggregates = file_cfg
+        .as_ref()
+        .and_then(|c| c.telemetry_aggregates)
+        .unwrap_or(false);
     let telemetry_enterprise_dsn = file_cfg
         .as_ref()
         .and_then(|c| c.telemetry_enterprise_dsn.clone())
         .filter(|s| !s.is_empty());
+    let telemetry_otlp_endpoint = file_cfg
+        .as_ref()
+        .and_then(|c| c.telemetry_otlp_endpoint.clone())
+        .filter(|s| !s.is_empty());
+    let telemetry_otlp_headers = file_cfg
+        .as_ref()
+        .and_then(|c| c.telemetry_otlp_headers.clone())
+        .unwrap_or_default();
+    let telemetry_sinks = file_cfg
+        .as_ref()
+        .and_then(|c| c.telemetry_sinks.clone())
+        .unwrap_or_default();
 
     // Default to disabled (true) unless this is an OSS build
     // OSS builds set OSS_BUILD env var at compile time to "1", which enables auto-updates by default
@@ -309,6 +598,97 @@ fn build_config() -> Config {
     // Build feature flags from file config
     let feature_flags = build_feature_flags(&file_cfg);
 
+    let policy = file_cfg
+        .as_ref()
+        .and_then(|c| c.policy.clone())
+        .unwrap_or_default();
+    let notifications = file_cfg
+        .as_ref()
+        .and_then(|c| c.notifications.clone())
+        .unwrap_or_default();
+    let event_hooks = file_cfg
+        .as_ref()
+        .and_then(|c| c.event_hooks.clone())
+        .unwrap_or_default();
+    let co_author_trailers = file_cfg
+        .as_ref()
+        .and_then(|c| c.co_author_trailers.clone())
+        .unwrap_or_default();
+    let redaction = file_cfg
+        .as_ref()
+        .and_then(|c| c.redaction.clone())
+        .unwrap_or_default();
+    let transcript_summary = file_cfg
+        .as_ref()
+        .and_then(|c| c.transcript_summary.clone())
+        .unwrap_or_default();
+    let proxy = file_cfg
+        .as_ref()
+        .and_then(|c| c.proxy.clone())
+        .unwrap_or_default();
+    let sync_daemon = file_cfg
+        .as_ref()
+        .and_then(|c| c.sync_daemon.clone())
+        .unwrap_or_default();
+    let disabled_hooks = file_cfg
+        .as_ref()
+        .and_then(|c| c.disabled_hooks.clone())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|command| command.trim().to_string())
+        .collect();
+    let read_only_commands = file_cfg
+        .as_ref()
+        .and_then(|c| c.read_only_commands.clone())
+        .map(|commands| {
+            commands
+                .into_iter()
+                .map(|command| command.trim().to_string())
+                .collect()
+        })
+        .unwrap_or_else(default_read_only_commands);
+    let perf_records = file_cfg
+        .as_ref()
+        .and_then(|c| c.perf_records)
+        .unwrap_or(false);
+    let human_checkpoint_after_minutes = file_cfg
+        .as_ref()
+        .and_then(|c| c.human_checkpoint_after_minutes)
+        .unwrap_or(0);
+    let push_summary = file_cfg
+        .as_ref()
+        .and_then(|c| c.push_summary)
+        .unwrap_or(false);
+    let commit_summary = file_cfg
+        .as_ref()
+        .and_then(|c| c.commit_summary)
+        .unwrap_or(false);
+    let safe_mode_after_failures = file_cfg
+        .as_ref()
+        .and_then(|c| c.safe_mode_after_failures)
+        .unwrap_or(DEFAULT_SAFE_MODE_AFTER_FAILURES);
+    let checkpoint_max_file_bytes = file_cfg
+        .as_ref()
+        .and_then(|c| c.checkpoint_max_file_bytes)
+        .unwrap_or(DEFAULT_CHECKPOINT_MAX_FILE_BYTES);
+    let diff_algorithm = file_cfg
+        .as_ref()
+        .and_then(|c| c.diff_algorithm.as_deref())
+        .and_then(DiffAlgorithm::parse)
+        .unwrap_or_default();
+    let preset_plugins = file_cfg
+        .as_ref()
+        .and_then(|c| c.preset_plugins.clone())
+        .unwrap_or_default();
+    let renderers = file_cfg
+        .as_ref()
+        .and_then(|c| c.renderers.clone())
+        .unwrap_or_default();
+    let blame_colors = file_cfg
+        .as_ref()
+        .and_then(|c| c.colors.clone())
+        .unwrap_or_default();
+
     #[cfg(any(test, feature = "test-support"))]
     {
         let mut config = Config {
@@ -317,12 +697,37 @@ fn build_config() -> Config {
             ignore_prompts,
             allow_repositories,
             exclude_repositories,
+            prompt_unlisted_repositories,
             telemetry_oss_disabled,
+            telemetry_aggregates,
             telemetry_enterprise_dsn,
+            telemetry_otlp_endpoint,
+            telemetry_otlp_headers,
+            telemetry_sinks,
             disable_version_checks,
             disable_auto_updates,
             update_channel,
             feature_flags,
+            policy,
+            notifications,
+            event_hooks,
+            co_author_trailers,
+            redaction,
+            transcript_summary,
+            proxy,
+            sync_daemon,
+            disabled_hooks,
+            read_only_commands,
+            perf_records,
+            human_checkpoint_after_minutes,
+            push_summary,
+            commit_summary,
+            safe_mode_after_failures,
+            checkpoint_max_file_bytes,
+            diff_algorithm,
+            preset_plugins,
+            renderers,
+            blame_colors,
         };
         apply_test_config_patch(&mut config);
         config
@@ -335,12 +740,37 @@ fn build_config() -> Config {
         ignore_prompts,
         allow_repositories,
         exclude_repositories,
+        prompt_unlisted_repositories,
         telemetry_oss_disabled,
+        telemetry_aggregates,
         telemetry_enterprise_dsn,
+        telemetry_otlp_endpoint,
+        telemetry_otlp_headers,
+        telemetry_sinks,
         disable_version_checks,
         disable_auto_updates,
         update_channel,
         feature_flags,
+        policy,
+        notifications,
+        event_hooks,
+        co_author_trailers,
+        redaction,
+        transcript_summary,
+        proxy,
+        sync_daemon,
+        disabled_hooks,
+        read_only_commands,
+        perf_records,
+        human_checkpoint_after_minutes,
+        push_summary,
+        commit_summary,
+        safe_mode_after_failures,
+        checkpoint_max_file_bytes,
+        diff_algorithm,
+        preset_plugins,
+        renderers,
+        blame_colors,
     }
 }
 
@@ -358,17 +788,17 @@ fn build_feature_flags(file_cfg: &Option<FileConfig>) -> FeatureFlags {
 
 fn resolve_git_path(file_cfg: &Option<FileConfig>) -> (String, String) {
     // 1) From config file
-    if let Some(cfg) = file_cfg {
-        if let Some(path) = cfg.git_path.as_ref() {
-            let trimmed = path.trim();
-            if !trimmed.is_empty() {
-                let p = Path::new(trimmed);
-                if is_executable(p) {
-                    let config_path = config_file_path()
-                        .map(|p| p.to_string_lossy().to_string())
-                        .unwrap_or_else(|| "~/.git-ai/config.json".to_string());
-                    return (trimmed.to_string(), format!("配置文件({})", config_path));
-                }
+    if let Some(cfg) = file_cfg
+        && let Some(path) = cfg.git_path.as_ref()
+    {
+        let trimmed = path.trim();
+        if !trimmed.is_empty() {
+            let p = Path::new(trimmed);
+            if is_executable(p) {
+                let config_path = config_file_path()
+                    .map(|p| p.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "~/.git-ai/config.json".to_string());
+                return (trimmed.to_string(), format!("配置文件({})", config_path));
             }
         }
     }
@@ -411,6 +841,44 @@ fn load_file_config() -> Option<FileConfig> {
     serde_json::from_slice::<FileConfig>(&data).ok()
 }
 
+/// Edit ~/.git-ai/config.json in place, keeping keys this version doesn't know
+/// about. The running process keeps the config it loaded at startup.
+pub fn update_file_config(
+    edit: impl FnOnce(&mut serde_json::Map<String, serde_json::Value>),
+) -> Result<PathBuf, GitAiError> {
+    let path = config_file_path()
+        .ok_or_else(|| GitAiError::Generic("Could not determine home directory".to_string()))?;
+    update_config_file_at(&path, edit)?;
+    Ok(path)
+}
+
+fn update_config_file_at(
+    path: &Path,
+    edit: impl FnOnce(&mut serde_json::Map<String, serde_json::Value>),
+) -> Result<(), GitAiError> {
+    let mut object = match fs::read(path) {
+        Ok(data) => match serde_json::from_slice::<serde_json::Value>(&data)? {
+            serde_json::Value::Object(object) => object,
+            _ => {
+                return Err(GitAiError::Generic(format!(
+                    "{} is not a JSON object",
+                    path.display()
+                )));
+            }
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => serde_json::Map::new(),
+        Err(e) => return Err(e.into()),
+    };
+    edit(&mut object);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp_path = path.with_extension(format!("json.{}", std::process::id()));
+    fs::write(&tmp_path, serde_json::to_vec_pretty(&object)?)?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
 fn config_file_path() -> Option<PathBuf> {
     #[cfg(windows)]
     {
@@ -437,20 +905,20 @@ fn is_executable(path: &Path) -> bool {
 /// Reads GIT_AI_TEST_CONFIG_PATCH env var containing JSON and applies patches to config
 #[cfg(any(test, feature = "test-support"))]
 fn apply_test_config_patch(config: &mut Config) {
-    if let Ok(patch_json) = env::var("GIT_AI_TEST_CONFIG_PATCH") {
-        if let Ok(patch) = serde_json::from_str::<ConfigPatch>(&patch_json) {
-            if let Some(ignore_prompts) = patch.ignore_prompts {
-                config.ignore_prompts = ignore_prompts;
-            }
-            if let Some(telemetry_oss_disabled) = patch.telemetry_oss_disabled {
-                config.telemetry_oss_disabled = telemetry_oss_disabled;
-            }
-            if let Some(disable_version_checks) = patch.disable_version_checks {
-                config.disable_version_checks = disable_version_checks;
-            }
-            if let Some(disable_auto_updates) = patch.disable_auto_updates {
-                config.disable_auto_updates = disable_auto_updates;
-            }
+    if let Ok(patch_json) = env::var("GIT_AI_TEST_CONFIG_PATCH")
+        && let Ok(patch) = serde_json::from_str::<ConfigPatch>(&patch_json)
+    {
+        if let Some(ignore_prompts) = patch.ignore_prompts {
+            config.ignore_prompts = ignore_prompts;
+        }
+        if let Some(telemetry_oss_disabled) = patch.telemetry_oss_disabled {
+            config.telemetry_oss_disabled = telemetry_oss_disabled;
+        }
+        if let Some(disable_version_checks) = patch.disable_version_checks {
+            config.disable_version_checks = disable_version_checks;
+        }
+        if let Some(disable_auto_updates) = patch.disable_auto_updates {
+            config.disable_auto_updates = disable_auto_updates;
         }
     }
 }
@@ -475,15 +943,88 @@ mod tests {
                 .into_iter()
                 .filter_map(|s| Pattern::new(&s).ok())
                 .collect(),
+            prompt_unlisted_repositories: false,
             telemetry_oss_disabled: false,
+            telemetry_aggregates: false,
             telemetry_enterprise_dsn: None,
+            telemetry_otlp_endpoint: None,
+            telemetry_otlp_headers: BTreeMap::new(),
+            telemetry_sinks: Vec::new(),
             disable_version_checks: false,
             disable_auto_updates: false,
             update_channel: UpdateChannel::Latest,
             feature_flags: FeatureFlags::default(),
+            policy: PolicyConfig::default(),
+            notifications: NotificationsConfig::default(),
+            event_hooks: EventHooksConfig::default(),
+            co_author_trailers: CoAuthorTrailersConfig::default(),
+            redaction: RedactionConfig::default(),
+            transcript_summary: TranscriptSummaryConfig::default(),
+            proxy: ProxyConfig::default(),
+            sync_daemon: SyncDaemonConfig::default(),
+            disabled_hooks: Vec::new(),
+            read_only_commands: default_read_only_commands(),
+            perf_records: false,
+            human_checkpoint_after_minutes: 0,
+            push_summary: false,
+            commit_summary: false,
+            safe_mode_after_failures: DEFAULT_SAFE_MODE_AFTER_FAILURES,
+            checkpoint_max_file_bytes: DEFAULT_CHECKPOINT_MAX_FILE_BYTES,
+            diff_algorithm: DiffAlgorithm::default(),
+            preset_plugins: BTreeMap::new(),
+            renderers: BTreeMap::new(),
+            blame_colors: BlameColorsConfig::default(),
         }
     }
 
+    #[test]
+    fn test_update_config_file_keeps_unknown_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".git-ai").join("config.json");
+        update_config_file_at(&path, |cfg| {
+            cfg.insert("telemetry_aggregates".to_string(), serde_json::json!(true));
+        })
+        .unwrap();
+
+        fs::write(&path, r#"{"git_path": "/usr/bin/git", "future_key": [1]}"#).unwrap();
+        update_config_file_at(&path, |cfg| {
+            cfg.insert("telemetry_aggregates".to_string(), serde_json::json!(false));
+        })
+        .unwrap();
+        let saved: serde_json::Value = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        assert_eq!(saved["git_path"], "/usr/bin/git");
+        assert_eq!(saved["future_key"], serde_json::json!([1]));
+        assert_eq!(saved["telemetry_aggregates"], false);
+
+        fs::write(&path, "[]").unwrap();
+        assert!(update_config_file_at(&path, |_| {}).is_err());
+    }
+
+    #[test]
+    fn test_command_hooks_enabled() {
+        let mut config = create_test_config(vec![], vec![]);
+        assert!(config.command_hooks_enabled("stash"));
+
+        config.disabled_hooks = vec!["stash".to_string(), "fetch".to_string()];
+        assert!(!config.command_hooks_enabled("stash"));
+        assert!(!config.command_hooks_enabled("fetch"));
+        assert!(config.command_hooks_enabled("commit"));
+        assert!(config.command_hooks_enabled("pull"));
+    }
+
+    #[test]
+    fn test_is_read_only_command() {
+        let mut config = create_test_config(vec![], vec![]);
+        assert!(config.is_read_only_command("status"));
+        assert!(config.is_read_only_command("rev-parse"));
+        assert!(!config.is_read_only_command("commit"));
+        assert!(!config.is_read_only_command("stash"));
+
+        config.read_only_commands = vec!["log".to_string()];
+        assert!(config.is_read_only_command("log"));
+        assert!(!config.is_read_only_command("status"));
+    }
+
     #[test]
     fn test_exclusion_takes_precedence_over_allow() {
         let config = create_test_config(
@@ -512,6 +1053,14 @@ mod tests {
         assert!(config.is_allowed_repository(&None));
     }
 
+    #[test]
+    fn test_repository_access_without_repository() {
+        let config = create_test_config(vec!["https://github.com/allowed/*".to_string()], vec![]);
+        assert_eq!(config.repository_access(&None), RepositoryAccess::Unlisted);
+        assert!(!config.is_allowed_repository(&None));
+        assert!(!config.prompt_unlisted_repositories());
+    }
+
     #[test]
     fn test_allow_without_exclude() {
         let config =
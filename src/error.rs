@@ -1,4 +1,6 @@
+use serde_json::json;
 use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 #[derive(Debug)]
 pub enum GitAiError {
@@ -15,9 +17,115 @@ pub enum GitAiError {
     Utf8Error(std::str::Utf8Error),
     FromUtf8Error(std::string::FromUtf8Error),
     PresetError(String),
+    /// Bad command-line usage
+    InvalidArguments(String),
+    /// The working directory is not inside a git repository
+    NotARepository(String),
+    /// A commit or prompt has no authorship note to read
+    NoAuthorshipData(String),
+    /// A configured policy refused the operation
+    PolicyViolation(String),
     Generic(String),
 }
 
+/// Stable identifiers for `--porcelain-errors` output. Numbers are never
+/// reused; add new codes at the end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    Internal = 1,
+    InvalidArguments = 2,
+    NotARepository = 3,
+    NoAuthorshipData = 4,
+    PolicyViolation = 5,
+    GitCommandFailed = 6,
+    Io = 7,
+    InvalidData = 8,
+}
+
+impl ErrorCode {
+    pub fn as_u16(self) -> u16 {
+        self as u16
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ErrorCode::Internal => "internal",
+            ErrorCode::InvalidArguments => "invalid_arguments",
+            ErrorCode::NotARepository => "not_a_repository",
+            ErrorCode::NoAuthorshipData => "no_authorship_data",
+            ErrorCode::PolicyViolation => "policy_violation",
+            ErrorCode::GitCommandFailed => "git_command_failed",
+            ErrorCode::Io => "io",
+            ErrorCode::InvalidData => "invalid_data",
+        }
+    }
+}
+
+impl GitAiError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            #[cfg(feature = "test-support")]
+            GitAiError::GitError(_) => ErrorCode::GitCommandFailed,
+            GitAiError::IoError(_) => ErrorCode::Io,
+            GitAiError::GitCliError { .. } => ErrorCode::GitCommandFailed,
+            GitAiError::JsonError(_) | GitAiError::Utf8Error(_) | GitAiError::FromUtf8Error(_) => {
+                ErrorCode::InvalidData
+            }
+            GitAiError::InvalidArguments(_) => ErrorCode::InvalidArguments,
+            GitAiError::NotARepository(_) => ErrorCode::NotARepository,
+            GitAiError::NoAuthorshipData(_) => ErrorCode::NoAuthorshipData,
+            GitAiError::PolicyViolation(_) => ErrorCode::PolicyViolation,
+            GitAiError::PresetError(_) | GitAiError::Generic(_) => ErrorCode::Internal,
+        }
+    }
+
+    /// Process exit status for a fatal error. Missing authorship data gets its own
+    /// status (its error code) so scripts can tell "nothing recorded" from a failure.
+    pub fn exit_status(&self) -> i32 {
+        match self.code() {
+            ErrorCode::NoAuthorshipData => ErrorCode::NoAuthorshipData as i32,
+            _ => 1,
+        }
+    }
+
+    /// The `--porcelain-errors` representation written to stderr
+    pub fn to_porcelain(&self) -> serde_json::Value {
+        let code = self.code();
+        json!({
+            "error": {
+                "code": code.as_u16(),
+                "name": code.name(),
+                "message": self.to_string(),
+            }
+        })
+    }
+}
+
+static PORCELAIN_ERRORS: AtomicBool = AtomicBool::new(false);
+
+/// Switch fatal errors to single-line JSON on stderr (`--porcelain-errors`)
+pub fn set_porcelain_errors(enabled: bool) {
+    PORCELAIN_ERRORS.store(enabled, Ordering::Relaxed);
+}
+
+/// Set by the flag or by `GIT_AI_PORCELAIN_ERRORS=1`, which also reaches hook subprocesses
+pub fn porcelain_errors() -> bool {
+    PORCELAIN_ERRORS.load(Ordering::Relaxed)
+        || std::env::var("GIT_AI_PORCELAIN_ERRORS").is_ok_and(|v| v == "1" || v == "true")
+}
+
+/// Report a fatal command error and exit with `err.exit_status()`. `context`
+/// prefixes the human-readable message and is omitted from porcelain output.
+pub fn exit_with_error(context: &str, err: &GitAiError) -> ! {
+    crate::observability::local_log::error(&format!("{}: {}", context, err));
+    if porcelain_errors() {
+        eprintln!("{}", err.to_porcelain());
+    } else {
+        eprintln!("{}: {}", context, err);
+    }
+    std::process::exit(err.exit_status());
+}
+
 impl fmt::Display for GitAiError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -38,6 +146,10 @@ impl fmt::Display for GitAiError {
             GitAiError::Utf8Error(e) => write!(f, "UTF-8 error: {}", e),
             GitAiError::FromUtf8Error(e) => write!(f, "From UTF-8 error: {}", e),
             GitAiError::PresetError(e) => write!(f, "{}", e),
+            GitAiError::InvalidArguments(e) => write!(f, "{}", e),
+            GitAiError::NotARepository(e) => write!(f, "Not a git repository: {}", e),
+            GitAiError::NoAuthorshipData(e) => write!(f, "{}", e),
+            GitAiError::PolicyViolation(e) => write!(f, "Policy violation: {}", e),
             GitAiError::Generic(e) => write!(f, "Generic error: {}", e),
         }
     }
@@ -93,7 +205,52 @@ impl Clone for GitAiError {
             GitAiError::Utf8Error(e) => GitAiError::Utf8Error(*e),
             GitAiError::FromUtf8Error(e) => GitAiError::FromUtf8Error(e.clone()),
             GitAiError::PresetError(s) => GitAiError::PresetError(s.clone()),
+            GitAiError::InvalidArguments(s) => GitAiError::InvalidArguments(s.clone()),
+            GitAiError::NotARepository(s) => GitAiError::NotARepository(s.clone()),
+            GitAiError::NoAuthorshipData(s) => GitAiError::NoAuthorshipData(s.clone()),
+            GitAiError::PolicyViolation(s) => GitAiError::PolicyViolation(s.clone()),
             GitAiError::Generic(s) => GitAiError::Generic(s.clone()),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_porcelain_error_shape() {
+        let err = GitAiError::NotARepository("/tmp/nowhere".to_string());
+        assert_eq!(err.code(), ErrorCode::NotARepository);
+        assert_eq!(
+            err.to_porcelain(),
+            json!({
+                "error": {
+                    "code": 3,
+                    "name": "not_a_repository",
+                    "message": "Not a git repository: /tmp/nowhere",
+                }
+            })
+        );
+
+        let err = GitAiError::GitCliError {
+            code: Some(128),
+            stderr: "fatal".to_string(),
+            args: vec!["log".to_string()],
+        };
+        assert_eq!(err.code().name(), "git_command_failed");
+    }
+
+    #[test]
+    fn test_exit_status_distinguishes_missing_authorship_data() {
+        assert_eq!(
+            GitAiError::NoAuthorshipData("none".to_string()).exit_status(),
+            4
+        );
+        assert_eq!(GitAiError::Generic("boom".to_string()).exit_status(), 1);
+        assert_eq!(
+            GitAiError::InvalidArguments("bad".to_string()).exit_status(),
+            1
+        );
+    }
+}
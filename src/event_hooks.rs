@@ -0,0 +1,238 @@
+use crate::config::Config;
+use crate::git::repository::Repository;
+use crate::utils::debug_log;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    /// A checkpoint was appended to the working log
+    Checkpoint,
+    /// The working log was turned into an authorship note for a new commit
+    AuthorshipWritten,
+}
+
+impl HookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookEvent::Checkpoint => "checkpoint",
+            HookEvent::AuthorshipWritten => "authorship_written",
+        }
+    }
+
+    fn parse(name: &str) -> Option<HookEvent> {
+        serde_json::from_value(Value::String(name.to_string())).ok()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventHook {
+    /// Executable to run; the event is written to its stdin as JSON
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Events this hook subscribes to; empty means all events
+    #[serde(default)]
+    pub events: Vec<HookEvent>,
+    /// Seconds to wait before the hook is killed
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+impl EventHook {
+    fn subscribes_to(&self, event: HookEvent) -> bool {
+        self.events.is_empty() || self.events.contains(&event)
+    }
+}
+
+/// The `event_hooks` section of ~/.git-ai/config.json
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EventHooksConfig {
+    #[serde(default)]
+    pub hooks: Vec<EventHook>,
+}
+
+impl EventHooksConfig {
+    pub fn has_subscribers(&self, event: HookEvent) -> bool {
+        self.hooks.iter().any(|h| h.subscribes_to(event))
+    }
+}
+
+fn build_payload(event: HookEvent, repository: Option<&str>, details: &Value) -> Value {
+    json!({
+        "event": event.as_str(),
+        "repository": repository,
+        "details": details,
+        "git_ai_version": env!("CARGO_PKG_VERSION"),
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+/// Hand `event` to a detached `git-ai event-hooks` process, which runs the
+/// subscribed hooks from the repository root. Hooks can't fail or slow down the
+/// command that triggered them: errors, non-zero exits and timeouts are only logged.
+pub fn emit(event: HookEvent, repo: &Repository, details: Value) {
+    let config = Config::get().event_hooks();
+    if !config.has_subscribers(event) {
+        return;
+    }
+
+    let workdir = repo.workdir().ok();
+    let repository = workdir.as_ref().map(|p| p.display().to_string());
+    let payload = build_payload(event, repository.as_deref(), &details).to_string();
+    let exe = match crate::utils::current_git_ai_exe() {
+        Ok(exe) => exe,
+        Err(e) => {
+            debug_log(&format!("event hooks not run: {}", e));
+            return;
+        }
+    };
+    let mut cmd = Command::new(exe);
+    cmd.arg("event-hooks")
+        .arg(event.as_str())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    if let Some(workdir) = &workdir {
+        cmd.current_dir(workdir);
+    }
+    match cmd.spawn() {
+        // The payload fits in the pipe buffer, so this doesn't wait on the child
+        Ok(mut child) => {
+            if let Some(mut stdin) = child.stdin.take() {
+                let _ = stdin.write_all(payload.as_bytes());
+            }
+        }
+        Err(e) => debug_log(&format!("failed to start event hooks: {}", e)),
+    }
+}
+
+/// Handle `git-ai event-hooks <event>`: run every hook subscribed to the event,
+/// one after another, with the payload read from stdin
+pub fn handle_event_hooks(args: &[String]) {
+    let Some(event) = args.first().and_then(|name| HookEvent::parse(name)) else {
+        debug_log(&format!("event-hooks: unknown event {:?}", args.first()));
+        return;
+    };
+    let mut payload = String::new();
+    if let Err(e) = std::io::stdin().read_to_string(&mut payload) {
+        debug_log(&format!("event-hooks: failed to read payload: {}", e));
+        return;
+    }
+    run_hooks(Config::get().event_hooks(), event, &payload);
+}
+
+fn run_hooks(config: &EventHooksConfig, event: HookEvent, payload: &str) {
+    for hook in config.hooks.iter().filter(|h| h.subscribes_to(event)) {
+        let mut cmd = Command::new(&hook.command);
+        cmd.args(&hook.args)
+            .env("GIT_AI_EVENT", event.as_str())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null());
+        let timeout = Duration::from_secs(hook.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+        if let Err(e) = run_hook(cmd, payload, timeout) {
+            debug_log(&format!("event hook {} failed: {}", hook.command, e));
+        }
+    }
+}
+
+fn run_hook(mut cmd: Command, payload: &str, timeout: Duration) -> Result<(), String> {
+    let mut child = cmd.spawn().map_err(|e| e.to_string())?;
+    // Written from another thread so a hook that never reads its input is
+    // still held to the deadline. The thread isn't joined: it finishes when
+    // the pipe closes.
+    if let Some(mut stdin) = child.stdin.take() {
+        let payload = payload.to_string();
+        std::thread::spawn(move || {
+            // A hook that ignores its input closes the pipe early; that's fine
+            let _ = stdin.write_all(payload.as_bytes());
+        });
+    }
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait().map_err(|e| e.to_string())? {
+            Some(status) if status.success() => return Ok(()),
+            Some(status) => return Err(format!("exited with {}", status)),
+            None if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("timed out after {:?}", timeout));
+            }
+            None => std::thread::sleep(Duration::from_millis(20)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_deserializes_events() {
+        let config: EventHooksConfig = serde_json::from_str(
+            r#"{"hooks":[{"command":"badge","args":["--ci"],"events":["authorship_written"]},{"command":"log-all"}]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.hooks[0].args, vec!["--ci"]);
+        assert!(config.hooks[0].subscribes_to(HookEvent::AuthorshipWritten));
+        assert!(!config.hooks[0].subscribes_to(HookEvent::Checkpoint));
+        assert!(config.hooks[1].subscribes_to(HookEvent::Checkpoint));
+        assert!(!EventHooksConfig::default().has_subscribers(HookEvent::Checkpoint));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hook_receives_payload_on_stdin() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("event.json");
+        let payload = build_payload(
+            HookEvent::Checkpoint,
+            Some("/repo"),
+            &json!({ "kind": "ai_agent" }),
+        );
+
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg(format!("cat > '{}'", out.display()))
+            .stdin(Stdio::piped());
+        run_hook(cmd, &payload.to_string(), Duration::from_secs(5)).unwrap();
+        let written: Value = serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+        assert_eq!(written["event"], "checkpoint");
+        assert_eq!(written["details"]["kind"], "ai_agent");
+
+        let mut failing = Command::new("sh");
+        failing.args(["-c", "exit 3"]).stdin(Stdio::piped());
+        assert!(run_hook(failing, "{}", Duration::from_secs(5)).is_err());
+
+        let mut slow = Command::new("sh");
+        slow.args(["-c", "sleep 5"]).stdin(Stdio::piped());
+        let err = run_hook(slow, "{}", Duration::from_millis(100)).unwrap_err();
+        assert!(err.contains("timed out"));
+
+        // A payload bigger than the pipe buffer, sent to a hook that never reads it
+        let mut deaf = Command::new("sh");
+        deaf.args(["-c", "sleep 5"]).stdin(Stdio::piped());
+        let started = Instant::now();
+        let large = "x".repeat(1 << 20);
+        assert!(run_hook(deaf, &large, Duration::from_millis(100)).is_err());
+        assert!(started.elapsed() < Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_parse_event_names() {
+        assert_eq!(
+            HookEvent::parse("authorship_written"),
+            Some(HookEvent::AuthorshipWritten)
+        );
+        assert_eq!(HookEvent::parse("checkpoint"), Some(HookEvent::Checkpoint));
+        assert_eq!(HookEvent::parse("push"), None);
+    }
+}
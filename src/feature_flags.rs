@@ -86,7 +86,7 @@ impl FeatureFlags {
     /// - Starts with defaults
     /// - Applies file config overrides if present
     /// - Applies environment variable overrides if present (highest priority)
-    pub fn from_env_and_file(file_flags: Option<DeserializableFeatureFlags>) -> Self {
+    pub(crate) fn from_env_and_file(file_flags: Option<DeserializableFeatureFlags>) -> Self {
         // Start with defaults
         let mut result = FeatureFlags::default();
 
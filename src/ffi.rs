@@ -0,0 +1,229 @@
+//! C ABI over [`GitAi`], enabled with the `git-ai-ffi` feature.
+//!
+//! Build the shared library and link against `include/git_ai.h`:
+//!
+//! ```text
+//! cargo rustc --release --lib --features git-ai-ffi --crate-type cdylib
+//! ```
+//!
+//! Every entry point returns a heap-allocated, NUL-terminated JSON string of
+//! the form `{"ok": <result>}` or `{"error": "<message>"}`. Callers own the
+//! string and must release it with `git_ai_string_free`.
+
+use crate::api::GitAi;
+use crate::authorship::transcript::AiTranscript;
+use crate::authorship::working_log::{AgentId, CheckpointKind};
+use crate::commands::checkpoint_agent::agent_presets::AgentRunResult;
+use crate::error::GitAiError;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+/// Agent description accepted by `git_ai_checkpoint`
+#[derive(Debug, Deserialize)]
+struct FfiAgent {
+    tool: String,
+    id: String,
+    model: String,
+    #[serde(default)]
+    transcript: Option<AiTranscript>,
+    #[serde(default)]
+    edited_filepaths: Option<Vec<String>>,
+}
+
+impl From<FfiAgent> for AgentRunResult {
+    fn from(agent: FfiAgent) -> Self {
+        AgentRunResult {
+            agent_id: AgentId {
+                tool: agent.tool,
+                id: agent.id,
+                model: agent.model,
+            },
+            agent_metadata: None,
+            checkpoint_kind: CheckpointKind::AiAgent,
+            transcript: agent.transcript,
+            repo_working_dir: None,
+            edited_filepaths: agent.edited_filepaths,
+            will_edit_filepaths: None,
+            dirty_files: None,
+            commit_message: None,
+            edited_line_ranges: None,
+            append_transcript: false,
+        }
+    }
+}
+
+/// Borrow a required string argument
+///
+/// # Safety
+/// `ptr` must be null or point to a NUL-terminated string valid for the call.
+unsafe fn arg<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, GitAiError> {
+    if ptr.is_null() {
+        return Err(GitAiError::Generic(format!("{} must not be null", name)));
+    }
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map_err(|_| GitAiError::Generic(format!("{} is not valid UTF-8", name)))
+}
+
+/// Run `f`, converting its result (or a panic) into an owned JSON C string
+fn respond<T, F>(f: F) -> *mut c_char
+where
+    T: Serialize,
+    F: FnOnce() -> Result<T, GitAiError>,
+{
+    let response = match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => match serde_json::to_value(value) {
+            Ok(value) => json!({ "ok": value }),
+            Err(e) => json!({ "error": e.to_string() }),
+        },
+        Ok(Err(e)) => json!({ "error": e.to_string() }),
+        Err(_) => json!({ "error": "git-ai panicked" }),
+    };
+    into_c_string(response)
+}
+
+fn into_c_string(value: Value) -> *mut c_char {
+    // serde_json escapes control characters, so the output has no interior NUL
+    CString::new(value.to_string())
+        .unwrap_or_default()
+        .into_raw()
+}
+
+/// Line-level attribution for `file_path` in the repository containing `repo_path`.
+///
+/// # Safety
+/// Both arguments must be NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn git_ai_blame_file(
+    repo_path: *const c_char,
+    file_path: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let repo_path = unsafe { arg(repo_path, "repo_path") }?;
+        let file_path = unsafe { arg(file_path, "file_path") }?;
+        GitAi::open(repo_path)?.blame_file(file_path)
+    })
+}
+
+/// Aggregate attribution for the commits in `start..end`.
+///
+/// # Safety
+/// All arguments must be NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn git_ai_stats_for_range(
+    repo_path: *const c_char,
+    start: *const c_char,
+    end: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let repo_path = unsafe { arg(repo_path, "repo_path") }?;
+        let start = unsafe { arg(start, "start") }?;
+        let end = unsafe { arg(end, "end") }?;
+        GitAi::open(repo_path)?.stats_for_range(start, end)
+    })
+}
+
+/// Attribution for a single commit.
+///
+/// # Safety
+/// Both arguments must be NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn git_ai_stats_for_commit(
+    repo_path: *const c_char,
+    commit: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let repo_path = unsafe { arg(repo_path, "repo_path") }?;
+        let commit = unsafe { arg(commit, "commit") }?;
+        GitAi::open(repo_path)?.stats_for_commit(commit)
+    })
+}
+
+/// Checkpoint the working tree. `agent_json` is null for a human checkpoint, or
+/// `{"tool", "id", "model", "transcript"?, "edited_filepaths"?}` for AI edits.
+///
+/// # Safety
+/// `repo_path` and `author` must be NUL-terminated strings; `agent_json` may be null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn git_ai_checkpoint(
+    repo_path: *const c_char,
+    author: *const c_char,
+    agent_json: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let repo_path = unsafe { arg(repo_path, "repo_path") }?;
+        let author = unsafe { arg(author, "author") }?;
+        let agent = if agent_json.is_null() {
+            None
+        } else {
+            let agent_json = unsafe { arg(agent_json, "agent_json") }?;
+            Some(serde_json::from_str::<FfiAgent>(agent_json)?.into())
+        };
+        GitAi::open(repo_path)?.checkpoint(author, agent)
+    })
+}
+
+/// git-ai version as a static string; do not free.
+#[unsafe(no_mangle)]
+pub extern "C" fn git_ai_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+/// Release a string returned by any `git_ai_*` call. Null is ignored.
+///
+/// # Safety
+/// `ptr` must come from this library and not have been freed already.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn git_ai_string_free(ptr: *mut c_char) {
+    if !ptr.is_null() {
+        drop(unsafe { CString::from_raw(ptr) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    fn take(ptr: *mut c_char) -> Value {
+        let json = unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_string();
+        unsafe { git_ai_string_free(ptr) };
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn test_entry_points_return_json_envelopes() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "one\n", true).unwrap();
+        tmp_repo.trigger_checkpoint_with_author("dev").unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+
+        let repo_path = CString::new(tmp_repo.path().to_str().unwrap()).unwrap();
+        let file = CString::new("a.txt").unwrap();
+        let head = CString::new("HEAD").unwrap();
+
+        let blame = take(unsafe { git_ai_blame_file(repo_path.as_ptr(), file.as_ptr()) });
+        assert_eq!(blame["ok"]["lines"].as_object().unwrap().len(), 1);
+
+        let stats = take(unsafe { git_ai_stats_for_commit(repo_path.as_ptr(), head.as_ptr()) });
+        assert_eq!(stats["ok"]["git_diff_added_lines"], 1);
+
+        let missing = take(unsafe { git_ai_blame_file(repo_path.as_ptr(), std::ptr::null()) });
+        assert_eq!(
+            missing["error"],
+            "Generic error: file_path must not be null"
+        );
+
+        let bad_agent = CString::new("{\"tool\": 1}").unwrap();
+        let author = CString::new("dev").unwrap();
+        let result = take(unsafe {
+            git_ai_checkpoint(repo_path.as_ptr(), author.as_ptr(), bad_agent.as_ptr())
+        });
+        assert!(result["error"].is_string());
+
+        let version = unsafe { CStr::from_ptr(git_ai_version()) };
+        assert_eq!(version.to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+    }
+}
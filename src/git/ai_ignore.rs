@@ -0,0 +1,123 @@
+//! `.gitaiignore`: paths checkpoints never attribute or store, in gitignore
+//! syntax, matched by the `ignore` crate the way git matches `.gitignore`.
+//! Only the file at the repository root is read.
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::fs;
+use std::path::Path;
+
+pub const AI_IGNORE_FILE: &str = ".gitaiignore";
+
+#[derive(Debug)]
+pub struct AiIgnore {
+    rules: Gitignore,
+}
+
+impl Default for AiIgnore {
+    fn default() -> Self {
+        Self {
+            rules: Gitignore::empty(),
+        }
+    }
+}
+
+impl AiIgnore {
+    /// Rules from `<workdir>/.gitaiignore`; none if there isn't one
+    pub fn load(workdir: &Path) -> Self {
+        fs::read_to_string(workdir.join(AI_IGNORE_FILE))
+            .map(|content| Self::parse(&content))
+            .unwrap_or_default()
+    }
+
+    /// Patterns that don't parse are skipped with a warning, as git would
+    pub fn parse(content: &str) -> Self {
+        let mut builder = GitignoreBuilder::new("");
+        for line in content.lines() {
+            if let Err(e) = builder.add_line(None, line) {
+                eprintln!("Warning: Invalid pattern in {}: {}", AI_IGNORE_FILE, e);
+            }
+        }
+        let rules = builder.build().unwrap_or_else(|e| {
+            eprintln!("Warning: Failed to read {}: {}", AI_IGNORE_FILE, e);
+            Gitignore::empty()
+        });
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Whether repo-relative `path` (a file) is ignored, by its own rules or
+    /// those of a directory it's in
+    pub fn is_ignored(&self, path: &str) -> bool {
+        if self.rules.is_empty() {
+            return false;
+        }
+        let path = Path::new(path.trim_start_matches("./"));
+        // As in git, a file inside an ignored directory can't be re-included,
+        // so an ignored directory outranks negations of the file itself
+        path.ancestors()
+            .skip(1)
+            .filter(|directory| !directory.as_os_str().is_empty())
+            .any(|directory| self.rules.matched(directory, true).is_ignore())
+            || self.rules.matched(path, false).is_ignore()
+    }
+
+    /// `paths` without the ignored ones
+    pub fn filter(&self, paths: impl IntoIterator<Item = String>) -> Vec<String> {
+        paths
+            .into_iter()
+            .filter(|path| !self.is_ignored(path))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ai_ignore_rules() {
+        let ignore = AiIgnore::parse(
+            "# generated and vendored code\n\
+             *.lock\n\
+             !keep.lock\n\
+             vendor/\n\
+             /dist\n\
+             src/**/*.generated.ts\n\
+             \n",
+        );
+
+        assert!(ignore.is_ignored("Cargo.lock"));
+        assert!(ignore.is_ignored("web/yarn.lock"));
+        assert!(!ignore.is_ignored("keep.lock"));
+        assert!(ignore.is_ignored("vendor/lib.rs"));
+        assert!(ignore.is_ignored("third_party/vendor/a/b.c"));
+        // `vendor/` only matches directories
+        assert!(!ignore.is_ignored("docs/vendor"));
+        assert!(ignore.is_ignored("dist/app.js"));
+        assert!(!ignore.is_ignored("web/dist/app.js"));
+        assert!(ignore.is_ignored("src/api/client.generated.ts"));
+        assert!(ignore.is_ignored("src/client.generated.ts"));
+        assert!(!ignore.is_ignored("src/main.rs"));
+
+        // Can't re-include a file inside an ignored directory
+        let ignore = AiIgnore::parse("build/\n!build/keep.txt\n");
+        assert!(ignore.is_ignored("build/keep.txt"));
+
+        // `**` spans directories, escapes and trailing spaces as in git
+        let ignore = AiIgnore::parse("docs/**/draft.md\n\\#notes\n!/docs/a/draft.md\nlog.txt  \n");
+        assert!(ignore.is_ignored("docs/draft.md"));
+        assert!(ignore.is_ignored("docs/x/y/draft.md"));
+        assert!(!ignore.is_ignored("docs/a/draft.md"));
+        assert!(ignore.is_ignored("#notes"));
+        assert!(ignore.is_ignored("out/log.txt"));
+
+        assert_eq!(
+            AiIgnore::parse("*.min.js\n")
+                .filter(vec!["app.js".to_string(), "app.min.js".to_string()]),
+            vec!["app.js".to_string()]
+        );
+    }
+}
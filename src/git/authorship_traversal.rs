@@ -9,6 +9,16 @@ pub async fn load_ai_touched_files_for_commits(
     commit_shas: Vec<String>,
 ) -> Result<HashSet<String>, GitAiError> {
     let global_args = repo.global_args_for_exec();
+    // Notes on replacement objects count for the commits they replace
+    let replace_map = repo.replace_map();
+    let mut commit_shas = commit_shas;
+    if !replace_map.is_empty() {
+        let aliases: Vec<String> = commit_shas
+            .iter()
+            .flat_map(|sha| replace_map.aliases(sha))
+            .collect();
+        commit_shas.extend(aliases);
+    }
 
     smol::unblock(move || {
         if commit_shas.is_empty() {
@@ -0,0 +1,607 @@
+//! In-process history walking backed by git's commit-graph file.
+//!
+//! Range stats need "every commit in start..end" and a few ancestry checks.
+//! Each of those used to be a git subprocess, and `rev-list`/`merge-base` on a
+//! repo with hundreds of thousands of commits is slow to start and slower to
+//! walk. The commit-graph (`git commit-graph write`, also kept up to date by
+//! `gc` and `fetch.writeCommitGraph`) stores parents and generation numbers
+//! for every commit it covers, so the walk can stop as soon as nothing left in
+//! the queue can reach the range.
+//!
+//! Commits newer than the graph are read from a single `git rev-list
+//! --parents`, which is stopped as soon as the missing history has been seen. Whenever the graph can't be trusted (no
+//! graph, shallow clone, grafts or replace refs, which git itself also refuses
+//! to combine with the graph) callers fall back to plain git.
+
+use crate::config::Config;
+use crate::error::GitAiError;
+use crate::git::repository::Repository;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+const GRAPH_SIGNATURE: &[u8] = b"CGPH";
+const CHUNK_OID_FANOUT: u32 = u32::from_be_bytes(*b"OIDF");
+const CHUNK_OID_LOOKUP: u32 = u32::from_be_bytes(*b"OIDL");
+const CHUNK_COMMIT_DATA: u32 = u32::from_be_bytes(*b"CDAT");
+const CHUNK_EXTRA_EDGES: u32 = u32::from_be_bytes(*b"EDGE");
+
+const PARENT_NONE: u32 = 0x7000_0000;
+const PARENT_EXTRA_EDGES: u32 = 0x8000_0000;
+const LAST_EDGE: u32 = 0x8000_0000;
+
+/// One commit-graph file (a single graph, or one layer of a split chain)
+struct GraphLayer {
+    data: Vec<u8>,
+    hash_len: usize,
+    num_commits: u32,
+    fanout: usize,
+    lookup: usize,
+    commit_data: usize,
+    extra_edges: Option<usize>,
+}
+
+fn read_u32(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        data.get(at..at.checked_add(4)?)?.try_into().ok()?,
+    ))
+}
+
+fn read_u64(data: &[u8], at: usize) -> Option<u64> {
+    Some(u64::from_be_bytes(
+        data.get(at..at.checked_add(8)?)?.try_into().ok()?,
+    ))
+}
+
+impl GraphLayer {
+    fn parse(data: Vec<u8>) -> Option<GraphLayer> {
+        if data.get(0..4)? != GRAPH_SIGNATURE || *data.get(4)? != 1 {
+            return None;
+        }
+        let hash_len = match *data.get(5)? {
+            1 => 20,
+            2 => 32,
+            _ => return None,
+        };
+        let mut chunks = HashMap::new();
+        for i in 0..*data.get(6)? as usize {
+            let entry = 8 + i * 12;
+            chunks.insert(
+                read_u32(&data, entry)?,
+                read_u64(&data, entry + 4)? as usize,
+            );
+        }
+
+        let fanout = *chunks.get(&CHUNK_OID_FANOUT)?;
+        let lookup = *chunks.get(&CHUNK_OID_LOOKUP)?;
+        let commit_data = *chunks.get(&CHUNK_COMMIT_DATA)?;
+        let num_commits = read_u32(&data, fanout.checked_add(255 * 4)?)?;
+        let n = num_commits as usize;
+        let lookup_end = lookup.checked_add(n.checked_mul(hash_len)?)?;
+        let commit_data_end = commit_data.checked_add(n.checked_mul(hash_len + 16)?)?;
+        if data.len() < lookup_end || data.len() < commit_data_end {
+            return None;
+        }
+        // `find` binary-searches between fanout entries, so they must be
+        // non-decreasing and stay within the lookup table
+        let mut previous = 0;
+        for i in 0..256 {
+            let count = read_u32(&data, fanout + i * 4)?;
+            if count < previous || count > num_commits {
+                return None;
+            }
+            previous = count;
+        }
+        Some(GraphLayer {
+            hash_len,
+            num_commits,
+            fanout,
+            lookup,
+            commit_data,
+            extra_edges: chunks.get(&CHUNK_EXTRA_EDGES).copied(),
+            data,
+        })
+    }
+
+    fn oid(&self, local: u32) -> &[u8] {
+        let at = self.lookup + local as usize * self.hash_len;
+        &self.data[at..at + self.hash_len]
+    }
+
+    fn find(&self, oid: &[u8]) -> Option<u32> {
+        let first = oid[0] as usize;
+        let mut lo = match first {
+            0 => 0,
+            _ => read_u32(&self.data, self.fanout + (first - 1) * 4)?,
+        };
+        let mut hi = read_u32(&self.data, self.fanout + first * 4)?;
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            match self.oid(mid).cmp(oid) {
+                std::cmp::Ordering::Equal => return Some(mid),
+                std::cmp::Ordering::Less => lo = mid + 1,
+                std::cmp::Ordering::Greater => hi = mid,
+            }
+        }
+        None
+    }
+}
+
+/// Parents, topological level and commit time of a commit
+#[derive(Debug, Clone)]
+struct CommitNode {
+    parents: Vec<String>,
+    generation: u32,
+    time: u64,
+}
+
+pub struct CommitGraph {
+    /// Base layer first
+    layers: Vec<GraphLayer>,
+    /// Number of commits in all layers below each layer
+    offsets: Vec<u32>,
+}
+
+impl CommitGraph {
+    /// Read `objects/info/commit-graph`, or the split chain when there is no
+    /// single file
+    pub fn load(objects_dir: &Path) -> Option<CommitGraph> {
+        let info = objects_dir.join("info");
+        if let Ok(data) = std::fs::read(info.join("commit-graph")) {
+            return CommitGraph::from_layers(vec![GraphLayer::parse(data)?]);
+        }
+
+        let graphs_dir = info.join("commit-graphs");
+        let chain = std::fs::read_to_string(graphs_dir.join("commit-graph-chain")).ok()?;
+        let mut layers = Vec::new();
+        for hash in chain.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let data = std::fs::read(graphs_dir.join(format!("graph-{}.graph", hash))).ok()?;
+            layers.push(GraphLayer::parse(data)?);
+        }
+        CommitGraph::from_layers(layers)
+    }
+
+    fn from_layers(layers: Vec<GraphLayer>) -> Option<CommitGraph> {
+        let hash_len = layers.first()?.hash_len;
+        if layers.iter().any(|layer| layer.hash_len != hash_len) {
+            return None;
+        }
+        let mut offsets = Vec::with_capacity(layers.len());
+        let mut total = 0u32;
+        for layer in &layers {
+            offsets.push(total);
+            total = total.checked_add(layer.num_commits)?;
+        }
+        Some(CommitGraph { layers, offsets })
+    }
+
+    fn position(&self, oid_hex: &str) -> Option<u32> {
+        let oid = decode_hex(oid_hex)?;
+        if oid.len() != self.layers[0].hash_len {
+            return None;
+        }
+        // Newer layers are more likely to hold recent commits
+        self.layers
+            .iter()
+            .zip(&self.offsets)
+            .rev()
+            .find_map(|(layer, offset)| layer.find(&oid).map(|local| offset + local))
+    }
+
+    fn layer_for(&self, position: u32) -> Option<(&GraphLayer, u32)> {
+        let index = self.offsets.partition_point(|&offset| offset <= position);
+        let layer = self.layers.get(index.checked_sub(1)?)?;
+        let local = position - self.offsets[index - 1];
+        (local < layer.num_commits).then_some((layer, local))
+    }
+
+    fn oid_hex(&self, position: u32) -> Option<String> {
+        let (layer, local) = self.layer_for(position)?;
+        Some(encode_hex(layer.oid(local)))
+    }
+
+    fn node(&self, oid_hex: &str) -> Option<CommitNode> {
+        let (layer, local) = self.layer_for(self.position(oid_hex)?)?;
+        let at = layer.commit_data + local as usize * (layer.hash_len + 16) + layer.hash_len;
+        let data = &layer.data;
+        let parent1 = read_u32(data, at)?;
+        let parent2 = read_u32(data, at + 4)?;
+        let generation_and_time = read_u64(data, at + 8)?;
+        let generation = (generation_and_time >> 34) as u32;
+        // Level 0 means the writer didn't compute levels; the walk needs them
+        if generation == 0 {
+            return None;
+        }
+
+        let mut parent_positions = Vec::new();
+        if parent1 != PARENT_NONE {
+            parent_positions.push(parent1);
+        }
+        if parent2 & PARENT_EXTRA_EDGES != 0 {
+            let edges = layer.extra_edges?;
+            let mut index = (parent2 & !PARENT_EXTRA_EDGES) as usize;
+            loop {
+                let edge = read_u32(data, edges + index * 4)?;
+                parent_positions.push(edge & !LAST_EDGE);
+                if edge & LAST_EDGE != 0 {
+                    break;
+                }
+                index += 1;
+            }
+        } else if parent2 != PARENT_NONE {
+            parent_positions.push(parent2);
+        }
+
+        let parents = parent_positions
+            .into_iter()
+            .map(|position| self.oid_hex(position))
+            .collect::<Option<Vec<_>>>()?;
+        Some(CommitNode {
+            parents,
+            generation,
+            time: generation_and_time & 0x3_ffff_ffff,
+        })
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Objects directory shared by all worktrees
+fn objects_dir(repo: &Repository) -> PathBuf {
+    let git_dir = repo.path();
+    match std::fs::read_to_string(git_dir.join("commondir")) {
+        Ok(common) => git_dir.join(common.trim()).join("objects"),
+        Err(_) => git_dir.join("objects"),
+    }
+}
+
+pub struct HistoryWalker<'a> {
+    repo: &'a Repository,
+    graph: CommitGraph,
+    /// Commits read so far, from the graph or from git for newer ones
+    nodes: HashMap<String, CommitNode>,
+    /// Commits newer than the graph: (parents, commit time)
+    outside: HashMap<String, (Vec<String>, u64)>,
+}
+
+impl<'a> HistoryWalker<'a> {
+    /// None when the repository has no usable commit-graph
+    pub fn open(repo: &'a Repository) -> Option<HistoryWalker<'a>> {
+        let git_dir = repo.path();
+        // Same conditions under which git ignores its commit-graph
+        if git_dir.join("shallow").exists()
+            || git_dir.join("info").join("grafts").exists()
+            || !repo.replace_map().is_empty()
+        {
+            return None;
+        }
+        Some(HistoryWalker {
+            repo,
+            graph: CommitGraph::load(&objects_dir(repo))?,
+            nodes: HashMap::new(),
+            outside: HashMap::new(),
+        })
+    }
+
+    /// Read commits that aren't in the graph. rev-list lists children before
+    /// parents, so once every missing ancestor of `tips` has been printed the
+    /// walk has reached the graph and is cut short.
+    fn load_outside(&mut self, tips: &[&str]) -> Result<(), GitAiError> {
+        let mut pending: HashSet<String> = tips
+            .iter()
+            .filter(|tip| !self.outside.contains_key(**tip) && self.graph.position(tip).is_none())
+            .map(|tip| tip.to_string())
+            .collect();
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        let mut args = self.repo.global_args_for_exec();
+        args.extend(["rev-list", "--parents", "--timestamp", "--topo-order"].map(String::from));
+        args.extend(pending.iter().cloned());
+        let mut child = Command::new(Config::get().git_cmd())
+            .args(&args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let Some(stdout) = child.stdout.take() else {
+            return Err(GitAiError::Generic(
+                "git rev-list has no stdout".to_string(),
+            ));
+        };
+
+        for line in BufReader::new(stdout).lines() {
+            let line = line?;
+            // <commit time> <oid> <parents...>
+            let mut fields = line.split_whitespace();
+            let (Some(time), Some(oid)) = (fields.next(), fields.next()) else {
+                continue;
+            };
+            if !pending.remove(oid) {
+                continue;
+            }
+            let parents: Vec<String> = fields.map(str::to_string).collect();
+            for parent in &parents {
+                if !self.outside.contains_key(parent) && self.graph.position(parent).is_none() {
+                    pending.insert(parent.clone());
+                }
+            }
+            self.outside
+                .insert(oid.to_string(), (parents, time.parse().unwrap_or(0)));
+            if pending.is_empty() {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Ok(());
+            }
+        }
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(GitAiError::GitCliError {
+                code: output.status.code(),
+                stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+                args,
+            });
+        }
+        let missing = pending.into_iter().next().unwrap_or_default();
+        Err(GitAiError::Generic(format!("Unknown commit {}", missing)))
+    }
+
+    fn node(&mut self, oid: &str) -> Result<CommitNode, GitAiError> {
+        if let Some(node) = self.nodes.get(oid) {
+            return Ok(node.clone());
+        }
+        if !self.outside.contains_key(oid) {
+            let node = self.graph.node(oid).ok_or_else(|| {
+                GitAiError::Generic(format!(
+                    "Commit {} can't be read from the commit-graph",
+                    oid
+                ))
+            })?;
+            self.nodes.insert(oid.to_string(), node.clone());
+            return Ok(node);
+        }
+
+        // Newer than the graph: one more than the highest parent level,
+        // resolving parents first without recursing
+        let mut stack = vec![oid.to_string()];
+        while let Some(current) = stack.last().cloned() {
+            if self.nodes.contains_key(&current) {
+                stack.pop();
+                continue;
+            }
+            let Some((parents, time)) = self.outside.get(&current).cloned() else {
+                let node = self
+                    .graph
+                    .node(&current)
+                    .ok_or_else(|| GitAiError::Generic(format!("Commit {} is missing", current)))?;
+                self.nodes.insert(current, node);
+                stack.pop();
+                continue;
+            };
+            let unresolved: Vec<String> = parents
+                .iter()
+                .filter(|parent| !self.nodes.contains_key(*parent))
+                .cloned()
+                .collect();
+            if unresolved.is_empty() {
+                let generation = parents
+                    .iter()
+                    .map(|parent| self.nodes[parent].generation)
+                    .max()
+                    .unwrap_or(0)
+                    + 1;
+                self.nodes.insert(
+                    current,
+                    CommitNode {
+                        parents,
+                        generation,
+                        time,
+                    },
+                );
+                stack.pop();
+            } else {
+                stack.extend(unresolved);
+            }
+        }
+        Ok(self.nodes[oid].clone())
+    }
+
+    /// Record that `oid` is reachable from the sides in `flag`, queueing it
+    /// the first time it is seen
+    fn mark(&mut self, walk: &mut Walk, oid: &str, flag: u8) -> Result<(), GitAiError> {
+        match walk.flags.get_mut(oid) {
+            Some(existing) => {
+                if *existing == FROM_END && flag & FROM_START != 0 && !walk.done.contains(oid) {
+                    walk.interesting -= 1;
+                }
+                *existing |= flag;
+            }
+            None => {
+                let node = self.node(oid)?;
+                walk.flags.insert(oid.to_string(), flag);
+                if flag == FROM_END {
+                    walk.interesting += 1;
+                }
+                walk.queue
+                    .push((node.generation, node.time, oid.to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Commits reachable from `end` but not from `start` (`git rev-list
+    /// start..end`), newest first. An empty `start` means all of history.
+    pub fn range_commits(&mut self, start: &str, end: &str) -> Result<Vec<String>, GitAiError> {
+        let tips: Vec<&str> = [start, end].into_iter().filter(|t| !t.is_empty()).collect();
+        self.load_outside(&tips)?;
+
+        let mut walk = Walk::default();
+        self.mark(&mut walk, end, FROM_END)?;
+        if !start.is_empty() {
+            self.mark(&mut walk, start, FROM_START)?;
+        }
+
+        // Children always have a higher level than their parents, so by the
+        // time a commit is popped every path into it has been seen and its
+        // flags are final
+        let mut commits = Vec::new();
+        while walk.interesting > 0 {
+            let Some((_, _, oid)) = walk.queue.pop() else {
+                break;
+            };
+            if !walk.done.insert(oid.clone()) {
+                continue;
+            }
+            let flag = walk.flags[&oid];
+            if flag == FROM_END {
+                walk.interesting -= 1;
+                commits.push(oid.clone());
+            }
+            for parent in self.node(&oid)?.parents {
+                self.mark(&mut walk, &parent, flag)?;
+            }
+        }
+        Ok(commits)
+    }
+
+    /// Whether `ancestor` is reachable from `descendant` (`git merge-base
+    /// --is-ancestor`). The walk never goes below the ancestor's level.
+    pub fn is_ancestor(&mut self, ancestor: &str, descendant: &str) -> Result<bool, GitAiError> {
+        self.load_outside(&[ancestor, descendant])?;
+        let cutoff = self.node(ancestor)?.generation;
+
+        let mut seen = HashSet::new();
+        let mut stack = vec![descendant.to_string()];
+        while let Some(oid) = stack.pop() {
+            if oid == ancestor {
+                return Ok(true);
+            }
+            if !seen.insert(oid.clone()) {
+                continue;
+            }
+            let node = self.node(&oid)?;
+            if node.generation <= cutoff {
+                continue;
+            }
+            stack.extend(node.parents);
+        }
+        Ok(false)
+    }
+}
+
+const FROM_END: u8 = 1;
+const FROM_START: u8 = 2;
+
+/// State of one `range_commits` walk
+#[derive(Default)]
+struct Walk {
+    flags: HashMap<String, u8>,
+    /// Highest level first, then newest
+    queue: BinaryHeap<(u32, u64, String)>,
+    done: HashSet<String>,
+    /// Queued commits that so far are only reachable from `end`
+    interesting: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    fn rev_list(repo: &Repository, range: &str) -> HashSet<String> {
+        let mut args = repo.global_args_for_exec();
+        args.extend(["rev-list".to_string(), range.to_string()]);
+        let output = crate::git::repository::exec_git(&args).unwrap();
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_range_matches_rev_list_across_graph_boundary() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "one\n", true).unwrap();
+        tmp_repo.commit_with_message("base").unwrap();
+        let base = tmp_repo.get_head_commit_sha().unwrap();
+
+        let default_branch = tmp_repo.current_branch().unwrap();
+        tmp_repo.create_branch("feature").unwrap();
+        tmp_repo.write_file("b.txt", "feature\n", true).unwrap();
+        tmp_repo.commit_with_message("feature").unwrap();
+        tmp_repo.switch_branch(&default_branch).unwrap();
+        tmp_repo.write_file("a.txt", "one\ntwo\n", true).unwrap();
+        tmp_repo.commit_with_message("main").unwrap();
+        tmp_repo.merge_branch("feature", "merge feature").unwrap();
+        let merge = tmp_repo.get_head_commit_sha().unwrap();
+
+        tmp_repo
+            .git_command(&["commit-graph", "write", "--reachable"])
+            .unwrap();
+
+        // Commits written after the graph have to be read from git
+        tmp_repo.write_file("c.txt", "later\n", true).unwrap();
+        tmp_repo.commit_with_message("after graph").unwrap();
+        let head = tmp_repo.get_head_commit_sha().unwrap();
+
+        let repo = crate::git::find_repository_in_path(&tmp_repo.path().to_string_lossy()).unwrap();
+        let mut walker = HistoryWalker::open(&repo).expect("commit-graph should be usable");
+
+        for (start, end) in [
+            (&base, &head),
+            (&merge, &head),
+            (&base, &merge),
+            (&head, &base),
+        ] {
+            let walked: HashSet<String> = walker
+                .range_commits(start, end)
+                .unwrap()
+                .into_iter()
+                .collect();
+            assert_eq!(walked, rev_list(&repo, &format!("{}..{}", start, end)));
+        }
+        let all = walker.range_commits("", &head).unwrap();
+        assert_eq!(all.len(), 5);
+        assert_eq!(all[0], head);
+
+        assert!(walker.is_ancestor(&base, &head).unwrap());
+        assert!(walker.is_ancestor(&merge, &head).unwrap());
+        assert!(!walker.is_ancestor(&head, &base).unwrap());
+    }
+
+    #[test]
+    fn test_parse_rejects_truncated_and_corrupt_graphs() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "one\n", true).unwrap();
+        tmp_repo.commit_with_message("base").unwrap();
+        tmp_repo
+            .git_command(&["commit-graph", "write", "--reachable"])
+            .unwrap();
+        let data = std::fs::read(tmp_repo.path().join(".git/objects/info/commit-graph")).unwrap();
+        let layer = GraphLayer::parse(data.clone()).unwrap();
+
+        for len in [0, 4, 5, 6, 7, 40, layer.commit_data + 1] {
+            assert!(GraphLayer::parse(data[..len].to_vec()).is_none());
+        }
+
+        // A fanout entry past the number of commits would send `find` out of bounds
+        let mut corrupt = data;
+        corrupt[layer.fanout..layer.fanout + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(GraphLayer::parse(corrupt).is_none());
+    }
+}
@@ -0,0 +1,265 @@
+//! Version guard for the real git binary. Several hooks shell out to git
+//! features that only exist in newer releases, and on an older git they fail in
+//! ways that are easy to miss (a hook error is logged, the git command still
+//! succeeds). The detected version decides up front which hooks can run.
+//!
+//! Detection runs `git --version` once per git binary: the result is cached in
+//! `~/.git-ai/internal/git_version.json` keyed by the binary's path, size and
+//! modification time, so upgrading git is picked up on the next run.
+
+use crate::config::Config;
+use crate::utils::debug_log;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+use std::time::UNIX_EPOCH;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct GitVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl GitVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        GitVersion {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parse `git --version` output, e.g. "git version 2.39.3 (Apple Git-145)"
+    /// or "git version 2.45.1.windows.1"
+    pub fn parse(output: &str) -> Option<GitVersion> {
+        let version = output
+            .trim()
+            .strip_prefix("git version ")
+            .unwrap_or(output.trim())
+            .split_whitespace()
+            .next()?;
+        let mut parts = version.split('.').map(|part| {
+            let digits: String = part.chars().take_while(|c| c.is_ascii_digit()).collect();
+            digits.parse::<u32>().ok()
+        });
+        let major = parts.next()??;
+        let minor = parts.next()??;
+        let patch = parts.next().flatten().unwrap_or(0);
+        Some(GitVersion::new(major, minor, patch))
+    }
+}
+
+impl fmt::Display for GitVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Oldest git any hook runs against: every checkpoint reads `status --porcelain=v2`
+pub const MIN_SUPPORTED: GitVersion = GitVersion::new(2, 11, 0);
+
+/// First git with `commit --trailer`; co-author trailers are skipped before it
+pub const COMMIT_TRAILER: GitVersion = GitVersion::new(2, 32, 0);
+
+/// A git feature a subcommand's hooks depend on beyond [`MIN_SUPPORTED`]
+pub struct Requirement {
+    pub commands: &'static [&'static str],
+    pub version: GitVersion,
+    pub feature: &'static str,
+}
+
+pub const REQUIREMENTS: &[Requirement] = &[
+    Requirement {
+        commands: &["push", "fetch", "pull"],
+        version: GitVersion::new(2, 22, 0),
+        feature: "branch --show-current",
+    },
+    Requirement {
+        commands: &["stash"],
+        version: GitVersion::new(2, 13, 0),
+        feature: "stash push",
+    },
+];
+
+/// Why hooks for `command` can't run on `version`, if they can't
+pub fn unsupported_reason(version: GitVersion, command: &str) -> Option<String> {
+    if version < MIN_SUPPORTED {
+        return Some(format!(
+            "git {} is older than {}, the oldest version git-ai supports (needs status --porcelain=v2)",
+            version, MIN_SUPPORTED
+        ));
+    }
+    REQUIREMENTS
+        .iter()
+        .find(|req| req.commands.contains(&command) && version < req.version)
+        .map(|req| {
+            format!(
+                "git {} is older than {} (needs {})",
+                version, req.version, req.feature
+            )
+        })
+}
+
+/// Subcommands whose hooks are skipped on `version`, with the reason
+pub fn disabled_hooks(version: GitVersion) -> Vec<(&'static str, String)> {
+    REQUIREMENTS
+        .iter()
+        .flat_map(|req| req.commands.iter().copied())
+        .filter_map(|command| unsupported_reason(version, command).map(|r| (command, r)))
+        .collect()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedVersion {
+    git_path: PathBuf,
+    size: u64,
+    mtime: u64,
+    version: GitVersion,
+}
+
+static DETECTED: OnceLock<Option<GitVersion>> = OnceLock::new();
+
+/// Version of the configured real git, or `None` if it couldn't be determined.
+/// An unknown version never disables anything.
+pub fn detected() -> Option<GitVersion> {
+    *DETECTED.get_or_init(|| detect(Config::get().git_cmd()))
+}
+
+fn detect(git_cmd: &str) -> Option<GitVersion> {
+    let binary = resolve_binary(git_cmd);
+    let fingerprint = binary.as_deref().and_then(fingerprint);
+    let cache_path = cache_path();
+
+    if let (Some(binary), Some((size, mtime)), Some(cache_path)) =
+        (&binary, fingerprint, &cache_path)
+        && let Ok(contents) = std::fs::read_to_string(cache_path)
+        && let Ok(cached) = serde_json::from_str::<CachedVersion>(&contents)
+        && &cached.git_path == binary
+        && cached.size == size
+        && cached.mtime == mtime
+    {
+        return Some(cached.version);
+    }
+
+    let output = Command::new(git_cmd).arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let version = GitVersion::parse(&String::from_utf8_lossy(&output.stdout))?;
+    debug_log(&format!("Detected git {} ({})", version, git_cmd));
+
+    // First time we see this binary: say once which hooks it can't run
+    warn_unsupported(version);
+
+    if let (Some(git_path), Some((size, mtime)), Some(cache_path)) =
+        (binary, fingerprint, cache_path)
+    {
+        let cached = CachedVersion {
+            git_path,
+            size,
+            mtime,
+            version,
+        };
+        if let Some(dir) = cache_path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        if let Ok(json) = serde_json::to_string(&cached) {
+            let _ = std::fs::write(&cache_path, json);
+        }
+    }
+    Some(version)
+}
+
+fn warn_unsupported(version: GitVersion) {
+    if version < MIN_SUPPORTED {
+        eprintln!(
+            "[git-ai] warning: git {} is older than {}; git-ai hooks are disabled until git is upgraded",
+            version, MIN_SUPPORTED
+        );
+        return;
+    }
+    for (command, reason) in disabled_hooks(version) {
+        eprintln!(
+            "[git-ai] warning: skipping git-ai hooks for `git {}`: {}",
+            command, reason
+        );
+    }
+}
+
+/// Full path of the git binary, searching PATH for a bare command name
+fn resolve_binary(git_cmd: &str) -> Option<PathBuf> {
+    let path = Path::new(git_cmd);
+    if path.components().count() > 1 {
+        return Some(path.to_path_buf());
+    }
+    let names: &[&str] = if cfg!(windows) {
+        &["git.exe", "git"]
+    } else {
+        &["git"]
+    };
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .find(|candidate| candidate.is_file())
+}
+
+fn fingerprint(binary: &Path) -> Option<(u64, u64)> {
+    let metadata = std::fs::metadata(binary).ok()?;
+    let mtime = metadata
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    Some((metadata.len(), mtime))
+}
+
+fn cache_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| {
+        home.join(".git-ai")
+            .join("internal")
+            .join("git_version.json")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_requirements() {
+        assert_eq!(
+            GitVersion::parse("git version 2.39.3 (Apple Git-145)\n"),
+            Some(GitVersion::new(2, 39, 3))
+        );
+        assert_eq!(
+            GitVersion::parse("git version 2.45.1.windows.1"),
+            Some(GitVersion::new(2, 45, 1))
+        );
+        assert_eq!(
+            GitVersion::parse("git version 2.50.0-rc1"),
+            Some(GitVersion::new(2, 50, 0))
+        );
+        assert_eq!(
+            GitVersion::parse("git version 3.0"),
+            Some(GitVersion::new(3, 0, 0))
+        );
+        assert_eq!(GitVersion::parse("not git"), None);
+        assert!(GitVersion::new(2, 9, 5) < GitVersion::new(2, 11, 0));
+
+        let old = GitVersion::new(2, 20, 1);
+        assert!(unsupported_reason(old, "commit").is_none());
+        assert!(
+            unsupported_reason(old, "push")
+                .unwrap()
+                .contains("branch --show-current")
+        );
+        let disabled: Vec<_> = disabled_hooks(old).into_iter().map(|(c, _)| c).collect();
+        assert_eq!(disabled, vec!["push", "fetch", "pull"]);
+
+        assert!(unsupported_reason(GitVersion::new(2, 7, 4), "commit").is_some());
+        assert!(disabled_hooks(GitVersion::new(2, 43, 0)).is_empty());
+    }
+}
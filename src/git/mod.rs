@@ -1,6 +1,10 @@
+pub mod ai_ignore;
 pub mod cli_parser;
+pub mod commit_graph;
 pub mod diff_tree_to_tree;
+pub mod git_version;
 pub mod refs;
+pub mod replace_objects;
 pub mod repository;
 
 pub mod authorship_traversal;
@@ -29,6 +29,17 @@ pub fn notes_add(
     Ok(())
 }
 
+pub fn notes_remove(repo: &Repository, commit_sha: &str) -> Result<(), GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("notes".to_string());
+    args.push("--ref=ai".to_string());
+    args.push("remove".to_string());
+    args.push("--ignore-missing".to_string());
+    args.push(commit_sha.to_string());
+    exec_git(&args)?;
+    Ok(())
+}
+
 // Check which commits from the given list have authorship notes.
 // Uses git cat-file --batch-check to efficiently check multiple commits in one invocation.
 // Returns a Vec of CommitAuthorship for each commit.
@@ -42,7 +53,7 @@ pub enum CommitAuthorship {
     Log {
         sha: String,
         git_author: String,
-        authorship_log: AuthorshipLog,
+        authorship_log: Box<AuthorshipLog>,
     },
 }
 pub fn get_commits_with_notes_from_list(
@@ -103,7 +114,7 @@ pub fn get_commits_with_notes_from_list(
             result.push(CommitAuthorship::Log {
                 sha: sha.clone(),
                 git_author,
-                authorship_log,
+                authorship_log: Box::new(authorship_log),
             });
         } else {
             result.push(CommitAuthorship::NoLog {
@@ -117,7 +128,18 @@ pub fn get_commits_with_notes_from_list(
 }
 
 // Show an authorship note and return its JSON content if found, or None if it doesn't exist.
+// A commit that has been replaced (`git replace`) may carry its note on the
+// other side of the replacement, so fall back to those object ids.
 pub fn show_authorship_note(repo: &Repository, commit_sha: &str) -> Option<String> {
+    show_note_for_object(repo, commit_sha).or_else(|| {
+        repo.replace_map()
+            .aliases(commit_sha)
+            .iter()
+            .find_map(|alias| show_note_for_object(repo, alias))
+    })
+}
+
+fn show_note_for_object(repo: &Repository, commit_sha: &str) -> Option<String> {
     let mut args = repo.global_args_for_exec();
     args.push("notes".to_string());
     args.push("--ref=ai".to_string());
@@ -221,6 +243,31 @@ mod tests {
         );
         assert!(non_existent_content.is_none());
     }
+
+    #[test]
+    fn test_show_authorship_note_follows_replace_refs() {
+        let tmp_repo = TmpRepo::new().expect("Failed to create tmp repo");
+        tmp_repo.commit_with_message("Imported").unwrap();
+        let original = tmp_repo.get_head_commit_sha().unwrap();
+        tmp_repo.commit_with_message("Rewritten").unwrap();
+        let replacement = tmp_repo.get_head_commit_sha().unwrap();
+
+        let git = |args: &[&str]| {
+            let mut full = tmp_repo.gitai_repo().global_args_for_exec();
+            full.extend(args.iter().map(|a| a.to_string()));
+            exec_git(&full).unwrap();
+        };
+        git(&["notes", "--ref=ai", "remove", &original]);
+        notes_add(tmp_repo.gitai_repo(), &replacement, "replacement note").unwrap();
+        git(&["replace", &original, &replacement]);
+
+        // Fresh instance so the replace refs are read after they exist
+        let repo = crate::git::find_repository_in_path(&tmp_repo.path().to_string_lossy()).unwrap();
+        assert_eq!(
+            show_authorship_note(&repo, &original).as_deref(),
+            Some("replacement note")
+        );
+    }
 }
 
 /// Sanitize a remote name to create a safe ref name
@@ -262,6 +309,29 @@ pub fn ref_exists(repo: &Repository, ref_name: &str) -> bool {
     exec_git(&args).is_ok()
 }
 
+/// Commits that have a note in `notes_ref` (a full ref name); empty if the
+/// ref doesn't exist
+pub fn commits_with_notes_in_ref(
+    repo: &Repository,
+    notes_ref: &str,
+) -> Result<HashSet<String>, GitAiError> {
+    if !ref_exists(repo, notes_ref) {
+        return Ok(HashSet::new());
+    }
+    let mut args = repo.global_args_for_exec();
+    args.push("notes".to_string());
+    args.push(format!("--ref={}", notes_ref));
+    args.push("list".to_string());
+
+    let output = exec_git(&args)?;
+    let stdout = String::from_utf8(output.stdout)?;
+    Ok(stdout
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(str::to_string)
+        .collect())
+}
+
 /// Merge notes from a source ref into refs/notes/ai
 /// Uses the 'ours' strategy to combine notes without data loss
 pub fn merge_notes_from_ref(repo: &Repository, source_ref: &str) -> Result<(), GitAiError> {
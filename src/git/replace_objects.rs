@@ -0,0 +1,137 @@
+//! `git replace` support for authorship lookups.
+//!
+//! Git already follows replace refs and graft points when it walks history, so
+//! rev-list, blame and friends cross the boundary left by a shallow-history
+//! import. Notes are different: they stay keyed by whichever object id they
+//! were written on. When a commit has been replaced, its authorship note may
+//! live on the original or on the replacement, so lookups have to try both.
+
+use crate::error::GitAiError;
+use crate::git::repository::{Repository, exec_git};
+use std::collections::HashMap;
+
+const DEFAULT_REPLACE_REF_BASE: &str = "refs/replace/";
+
+/// Git stops following replacements after this many hops
+const MAX_REPLACE_DEPTH: usize = 5;
+
+#[derive(Debug, Clone, Default)]
+pub struct ReplaceMap {
+    /// original -> replacement
+    replacements: HashMap<String, String>,
+    /// replacement -> originals
+    originals: HashMap<String, Vec<String>>,
+}
+
+impl ReplaceMap {
+    /// Read the repository's replace refs, honouring the same switches git
+    /// does (`--no-replace-objects`, `GIT_NO_REPLACE_OBJECTS`,
+    /// `core.useReplaceRefs`). Failures yield an empty map.
+    pub fn load(repo: &Repository) -> ReplaceMap {
+        let disabled = repo
+            .global_args_for_exec()
+            .iter()
+            .any(|arg| arg == "--no-replace-objects")
+            || std::env::var_os("GIT_NO_REPLACE_OBJECTS").is_some();
+        if disabled {
+            return ReplaceMap::default();
+        }
+        let base = std::env::var("GIT_REPLACE_REF_BASE")
+            .ok()
+            .filter(|base| !base.is_empty())
+            .map(|base| format!("{}/", base.trim_end_matches('/')))
+            .unwrap_or_else(|| DEFAULT_REPLACE_REF_BASE.to_string());
+
+        let map = match list_replace_refs(repo, &base) {
+            Ok(stdout) => parse_replace_refs(&stdout, &base),
+            Err(_) => return ReplaceMap::default(),
+        };
+        if map.is_empty() {
+            return map;
+        }
+        match repo.config_get_str("core.useReplaceRefs") {
+            Ok(Some(value)) if matches!(value.as_str(), "false" | "no" | "off" | "0") => {
+                ReplaceMap::default()
+            }
+            _ => map,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.replacements.is_empty()
+    }
+
+    /// Other object ids that stand for `sha`: the replacement chain it
+    /// resolves to and anything it replaces
+    pub fn aliases(&self, sha: &str) -> Vec<String> {
+        let mut aliases = Vec::new();
+        let mut current = sha;
+        for _ in 0..MAX_REPLACE_DEPTH {
+            match self.replacements.get(current) {
+                Some(next) if next != sha && !aliases.contains(next) => {
+                    aliases.push(next.clone());
+                    current = next;
+                }
+                _ => break,
+            }
+        }
+        for original in self.originals.get(sha).into_iter().flatten() {
+            if !aliases.contains(original) {
+                aliases.push(original.clone());
+            }
+        }
+        aliases
+    }
+}
+
+fn list_replace_refs(repo: &Repository, base: &str) -> Result<String, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("for-each-ref".to_string());
+    args.push("--format=%(refname) %(objectname)".to_string());
+    args.push(base.to_string());
+    let output = exec_git(&args)?;
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Parse `for-each-ref --format='%(refname) %(objectname)'` output for refs
+/// under `base`
+pub fn parse_replace_refs(stdout: &str, base: &str) -> ReplaceMap {
+    let mut map = ReplaceMap::default();
+    for line in stdout.lines() {
+        let Some((refname, replacement)) = line.trim().split_once(' ') else {
+            continue;
+        };
+        let Some(original) = refname.strip_prefix(base) else {
+            continue;
+        };
+        if original.is_empty() || original == replacement {
+            continue;
+        }
+        map.replacements
+            .insert(original.to_string(), replacement.to_string());
+        map.originals
+            .entry(replacement.to_string())
+            .or_default()
+            .push(original.to_string());
+    }
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aliases_follow_replacements_both_ways() {
+        let stdout = "refs/replace/aaa bbb\nrefs/replace/bbb ccc\nrefs/replace/ddd ccc\nrefs/heads/main eee\n";
+        let map = parse_replace_refs(stdout, DEFAULT_REPLACE_REF_BASE);
+
+        assert_eq!(map.aliases("aaa"), vec!["bbb", "ccc"]);
+        assert_eq!(map.aliases("ccc"), vec!["bbb", "ddd"]);
+        assert!(map.aliases("eee").is_empty());
+
+        // A cycle doesn't loop forever
+        let cycle = parse_replace_refs("refs/replace/x y\nrefs/replace/y x\n", "refs/replace/");
+        assert_eq!(cycle.aliases("x"), vec!["y"]);
+    }
+}
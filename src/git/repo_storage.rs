@@ -1,6 +1,7 @@
 use crate::authorship::attribution_tracker::LineAttribution;
 use crate::authorship::authorship_log::PromptRecord;
 use crate::authorship::authorship_log_serialization::generate_short_hash;
+use crate::authorship::transcript::{AiTranscript, Message};
 use crate::authorship::working_log::{CHECKPOINT_API_VERSION, Checkpoint, CheckpointKind};
 use crate::error::GitAiError;
 use crate::git::rewrite_log::{RewriteLogEvent, append_event_to_file};
@@ -20,6 +21,20 @@ pub struct InitialAttributions {
     pub prompts: HashMap<String, PromptRecord>,
 }
 
+/// A binary or oversized file's size and mtime when it was last hashed, so
+/// checkpoints only re-read it once it changes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileStamp {
+    pub len: u64,
+    pub mtime_nanos: u128,
+    pub hash: String,
+}
+
+/// Key of `content` in the working log's blob store
+pub fn blob_sha(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
 #[derive(Debug, Clone)]
 pub struct RepoStorage {
     pub repo_path: PathBuf,
@@ -27,6 +42,17 @@ pub struct RepoStorage {
     pub working_logs: PathBuf,
     pub rewrite_log: PathBuf,
     pub logs: PathBuf,
+    pub metrics: PathBuf,
+    pub sync_failures: PathBuf,
+    pub aggregation_sync: PathBuf,
+    pub telemetry_aggregates: PathBuf,
+    pub prompt_index: PathBuf,
+    pub commit_message_draft: PathBuf,
+    pub rebase_ambiguities: PathBuf,
+    pub hook_failures: PathBuf,
+    pub anonymize_salt: PathBuf,
+    /// jj change-ids of annotated commits, see `restack`
+    pub change_ids: PathBuf,
 }
 
 impl RepoStorage {
@@ -35,6 +61,16 @@ impl RepoStorage {
         let working_logs_dir = ai_dir.join("working_logs");
         let rewrite_log_file = ai_dir.join("rewrite_log");
         let logs_dir = ai_dir.join("logs");
+        let metrics_file = ai_dir.join("metrics.json");
+        let sync_failures_file = ai_dir.join("sync_failures");
+        let aggregation_sync_file = ai_dir.join("aggregation_sync.json");
+        let telemetry_aggregates_file = ai_dir.join("telemetry_aggregates.json");
+        let prompt_index_file = ai_dir.join("prompt_index.json");
+        let commit_message_draft_file = ai_dir.join("commit_message_draft.json");
+        let rebase_ambiguities_file = ai_dir.join("rebase_ambiguities.json");
+        let hook_failures_file = ai_dir.join("hook_failures.json");
+        let anonymize_salt_file = ai_dir.join("anonymize_salt");
+        let change_ids_file = ai_dir.join("change_ids.json");
 
         let config = RepoStorage {
             repo_path: repo_path.to_path_buf(),
@@ -42,6 +78,16 @@ impl RepoStorage {
             working_logs: working_logs_dir,
             rewrite_log: rewrite_log_file,
             logs: logs_dir,
+            metrics: metrics_file,
+            sync_failures: sync_failures_file,
+            aggregation_sync: aggregation_sync_file,
+            telemetry_aggregates: telemetry_aggregates_file,
+            prompt_index: prompt_index_file,
+            commit_message_draft: commit_message_draft_file,
+            rebase_ambiguities: rebase_ambiguities_file,
+            hook_failures: hook_failures_file,
+            anonymize_salt: anonymize_salt_file,
+            change_ids: change_ids_file,
         };
 
         config.ensure_config_directory().unwrap();
@@ -189,6 +235,11 @@ impl PersistedWorkingLog {
         let checkpoints_file = self.dir.join("checkpoints.jsonl");
         fs::write(&checkpoints_file, "")?;
 
+        let transcripts_dir = self.dir.join("transcripts");
+        if transcripts_dir.exists() {
+            fs::remove_dir_all(&transcripts_dir)?;
+        }
+
         Ok(())
     }
 
@@ -199,20 +250,31 @@ impl PersistedWorkingLog {
     }
 
     pub fn persist_file_version(&self, content: &str) -> Result<String, GitAiError> {
-        // Create SHA256 hash of the content
-        let mut hasher = Sha256::new();
-        hasher.update(content.as_bytes());
-        let sha = format!("{:x}", hasher.finalize());
+        let sha = blob_sha(content);
+        self.persist_blobs([(sha.as_str(), content)])?;
+        Ok(sha)
+    }
 
-        // Ensure blobs directory exists
+    /// Write a batch of `(sha, content)` blobs. Blobs are content-addressed, so
+    /// ones already in the store (unchanged files, or files sharing content)
+    /// aren't written again.
+    pub fn persist_blobs<'a>(
+        &self,
+        blobs: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Result<usize, GitAiError> {
         let blobs_dir = self.dir.join("blobs");
         fs::create_dir_all(&blobs_dir)?;
 
-        // Write content to blob file
-        let blob_path = blobs_dir.join(&sha);
-        fs::write(blob_path, content)?;
-
-        Ok(sha)
+        let mut written = HashSet::new();
+        for (sha, content) in blobs {
+            let blob_path = blobs_dir.join(sha);
+            if written.contains(sha) || blob_path.exists() {
+                continue;
+            }
+            fs::write(blob_path, content)?;
+            written.insert(sha);
+        }
+        Ok(written.len())
     }
 
     pub fn to_repo_absolute_path(&self, file_path: &str) -> String {
@@ -299,9 +361,11 @@ impl PersistedWorkingLog {
         let checkpoints_file = self.dir.join("checkpoints.jsonl");
 
         // Serialize checkpoint to JSON and append to JSONL file
-        let json_line = serde_json::to_string(checkpoint)?;
+        let mut json_line = serde_json::to_string(checkpoint)?;
+        json_line.push('\n');
 
-        // Open file in append mode and write the JSON line
+        // One write and one sync for the whole checkpoint, however many files
+        // it covers; its blobs are already in place by the time it lands
         use std::fs::OpenOptions;
         use std::io::Write;
 
@@ -310,11 +374,51 @@ impl PersistedWorkingLog {
             .append(true)
             .open(&checkpoints_file)?;
 
-        writeln!(file, "{}", json_line)?;
+        file.write_all(json_line.as_bytes())?;
+        file.sync_data()?;
+
+        Ok(())
+    }
+
+    /* transcript chunks */
+    /// Append messages a session sent on their own (agent-v1 `append_transcript`)
+    pub fn append_transcript_chunk(
+        &self,
+        author_id: &str,
+        messages: &[Message],
+    ) -> Result<(), GitAiError> {
+        use std::fs::OpenOptions;
+        use std::io::Write;
 
+        let transcripts_dir = self.dir.join("transcripts");
+        fs::create_dir_all(&transcripts_dir)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(transcripts_dir.join(format!("{}.jsonl", author_id)))?;
+        for message in messages {
+            writeln!(file, "{}", serde_json::to_string(message)?)?;
+        }
         Ok(())
     }
 
+    /// Every chunk a session has sent, in order, if it sent any
+    pub fn read_transcript_chunks(&self, author_id: &str) -> Option<AiTranscript> {
+        let path = self
+            .dir
+            .join("transcripts")
+            .join(format!("{}.jsonl", author_id));
+        let content = fs::read_to_string(path).ok()?;
+        let mut transcript = AiTranscript::new();
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            match serde_json::from_str(line) {
+                Ok(message) => transcript.add_message(message),
+                Err(e) => debug_log(&format!("Skipping transcript chunk line: {}", e)),
+            }
+        }
+        Some(transcript)
+    }
+
     pub fn read_all_checkpoints(&self) -> Result<Vec<Checkpoint>, GitAiError> {
         let checkpoints_file = self.dir.join("checkpoints.jsonl");
 
@@ -431,9 +535,14 @@ impl PersistedWorkingLog {
         let checkpoints = self.read_all_checkpoints()?;
         let mut touched_files = HashSet::new();
         for checkpoint in checkpoints {
-            // Only include files from AI checkpoints (AiAgent, AiTab, or Mixed)
+            // Only include files from AI and generator checkpoints
             match checkpoint.kind {
-                CheckpointKind::AiAgent | CheckpointKind::AiTab | CheckpointKind::Mixed => {
+                CheckpointKind::AiAgent
+                | CheckpointKind::AiTab
+                | CheckpointKind::Mixed
+                | CheckpointKind::Generated
+                | CheckpointKind::AiRefactorTool
+                | CheckpointKind::Imported => {
                     for entry in checkpoint.entries {
                         touched_files.insert(entry.file);
                     }
@@ -446,6 +555,33 @@ impl PersistedWorkingLog {
         Ok(touched_files)
     }
 
+    /* whole-file hash cache */
+
+    fn whole_file_stamps_path(&self) -> PathBuf {
+        self.dir.join("whole_file_stamps.json")
+    }
+
+    /// Hashes of binary and oversized files by path, with the size and mtime
+    /// they were taken at. Empty if the cache is missing or unreadable.
+    pub fn read_whole_file_stamps(&self) -> HashMap<String, FileStamp> {
+        fs::read_to_string(self.whole_file_stamps_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn write_whole_file_stamps(
+        &self,
+        stamps: &HashMap<String, FileStamp>,
+    ) -> Result<(), GitAiError> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(
+            self.whole_file_stamps_path(),
+            serde_json::to_string(stamps)?,
+        )?;
+        Ok(())
+    }
+
     /* INITIAL attributions file */
 
     /// Write initial attributions to the INITIAL file.
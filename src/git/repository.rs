@@ -2,7 +2,9 @@ use crate::authorship::authorship_log_serialization::AuthorshipLog;
 use crate::authorship::rebase_authorship::rewrite_authorship_if_needed;
 use crate::config;
 use crate::error::GitAiError;
+use crate::git::commit_graph::HistoryWalker;
 use crate::git::refs::get_authorship;
+use crate::git::replace_objects::ReplaceMap;
 use crate::git::repo_storage::RepoStorage;
 use crate::git::rewrite_log::RewriteLogEvent;
 use crate::git::sync_authorship::{fetch_authorship_notes, push_authorship_notes};
@@ -146,57 +148,68 @@ impl<'a> CommitRange<'a> {
         }
         self.repo.find_commit(self.end_oid.clone())?;
 
-        // Check that both commits exist on the refname
-        // Use git merge-base --is-ancestor <commit> <refname>
-        // Skip merge-base check for empty tree hash since it's not part of commit history
-        if self.start_oid != EMPTY_TREE_HASH {
-            let mut args = self.repo.global_args_for_exec();
-            args.push("merge-base".to_string());
-            args.push("--is-ancestor".to_string());
-            args.push(self.start_oid.clone());
-            args.push(self.refname.clone());
-
-            exec_git(&args).map_err(|_| {
-                GitAiError::Generic(format!(
-                    "Commit {} is not reachable from refname {}",
-                    self.start_oid, self.refname
-                ))
-            })?;
-        }
+        // Check that both commits exist on the refname, and that start is an
+        // ancestor of end (direct path between them)
+        // Skip checks against the empty tree hash since it's not part of commit history
+        let mut walker = HistoryWalker::open(self.repo);
+        let refname_oid = walker.as_ref().and_then(|_| {
+            self.repo
+                .revparse_single(&self.refname)
+                .and_then(|object| object.peel_to_commit())
+                .map(|commit| commit.id())
+                .ok()
+        });
+        let refname_oid = refname_oid.as_deref().unwrap_or(&self.refname);
 
-        let mut args = self.repo.global_args_for_exec();
-        args.push("merge-base".to_string());
-        args.push("--is-ancestor".to_string());
-        args.push(self.end_oid.clone());
-        args.push(self.refname.clone());
+        if self.start_oid != EMPTY_TREE_HASH
+            && !self.is_ancestor(&mut walker, &self.start_oid, refname_oid)
+        {
+            return Err(GitAiError::Generic(format!(
+                "Commit {} is not reachable from refname {}",
+                self.start_oid, self.refname
+            )));
+        }
 
-        exec_git(&args).map_err(|_| {
-            GitAiError::Generic(format!(
+        if !self.is_ancestor(&mut walker, &self.end_oid, refname_oid) {
+            return Err(GitAiError::Generic(format!(
                 "Commit {} is not reachable from refname {}",
                 self.end_oid, self.refname
-            ))
-        })?;
+            )));
+        }
 
-        // Check that start is an ancestor of end (direct path between them)
-        // Skip for empty tree hash - it's not part of the commit DAG
-        if self.start_oid != EMPTY_TREE_HASH {
-            let mut args = self.repo.global_args_for_exec();
-            args.push("merge-base".to_string());
-            args.push("--is-ancestor".to_string());
-            args.push(self.start_oid.clone());
-            args.push(self.end_oid.clone());
-
-            exec_git(&args).map_err(|_| {
-                GitAiError::Generic(format!(
-                    "Commit {} is not an ancestor of {}",
-                    self.start_oid, self.end_oid
-                ))
-            })?;
+        if self.start_oid != EMPTY_TREE_HASH
+            && !self.is_ancestor(&mut walker, &self.start_oid, &self.end_oid)
+        {
+            return Err(GitAiError::Generic(format!(
+                "Commit {} is not an ancestor of {}",
+                self.start_oid, self.end_oid
+            )));
         }
 
         Ok(())
     }
 
+    /// Ancestry check through the commit-graph when there is one, otherwise
+    /// with git merge-base --is-ancestor <ancestor> <descendant>
+    fn is_ancestor(
+        &self,
+        walker: &mut Option<HistoryWalker<'a>>,
+        ancestor: &str,
+        descendant: &str,
+    ) -> bool {
+        if let Some(walker) = walker.as_mut()
+            && let Ok(result) = walker.is_ancestor(ancestor, descendant)
+        {
+            return result;
+        }
+        let mut args = self.repo.global_args_for_exec();
+        args.push("merge-base".to_string());
+        args.push("--is-ancestor".to_string());
+        args.push(ancestor.to_string());
+        args.push(descendant.to_string());
+        exec_git(&args).is_ok()
+    }
+
     #[allow(dead_code)]
     pub fn length(&self) -> usize {
         // Use git rev-list --count to get the number of commits between start and end
@@ -249,6 +262,17 @@ impl<'a> IntoIterator for CommitRange<'a> {
             };
         }
 
+        // Walk the commit-graph in-process when possible; rev-list otherwise
+        if let Some(mut walker) = HistoryWalker::open(self.repo)
+            && let Ok(commit_oids) = walker.range_commits(&self.start_oid, &self.end_oid)
+        {
+            return CommitRangeIterator {
+                repo: self.repo,
+                commit_oids,
+                index: 0,
+            };
+        }
+
         // Use git rev-list to get all commits between start and end
         // Format: start_oid..end_oid means commits reachable from end_oid but not from start_oid
         let mut args = self.repo.global_args_for_exec();
@@ -447,6 +471,19 @@ impl<'a> Commit<'a> {
         Ok(String::from_utf8(output.stdout)?.trim().to_string())
     }
 
+    // Get the full commit message (subject and body).
+    pub fn message(&self) -> Result<String, GitAiError> {
+        let mut args = self.repo.global_args_for_exec();
+        args.push("show".to_string());
+        args.push("-s".to_string());
+        args.push("--no-notes".to_string());
+        args.push("--encoding=UTF-8".to_string());
+        args.push("--format=%B".to_string());
+        args.push(self.oid.clone());
+        let output = exec_git(&args)?;
+        Ok(String::from_utf8(output.stdout)?.trim().to_string())
+    }
+
     // Get the author of this commit.
     #[allow(dead_code)]
     pub fn author(&self) -> Result<Signature<'a>, GitAiError> {
@@ -836,6 +873,8 @@ pub struct Repository {
     /// Canonical (absolute, resolved) version of workdir for reliable path comparisons
     /// On Windows, this uses the \\?\ UNC prefix format
     canonical_workdir: PathBuf,
+    /// Loaded on first use; see [`Repository::replace_map`]
+    replace_map: std::sync::OnceLock<ReplaceMap>,
 }
 
 impl Repository {
@@ -881,6 +920,9 @@ impl Repository {
     /// * `supress_output` - 是否抑制输出
     /// * `apply_side_effects` - 是否应用副作用（转换 working log 为 authorship log）
     ///
+    /// # 返回值
+    /// 写入 rewrite log 或重写归属失败时返回错误；git 操作本身已完成，不受影响
+    ///
     /// # 功能说明
     /// 这是 git-ai 代码归属追踪的核心函数，处理两个关键任务：
     ///
@@ -911,15 +953,13 @@ impl Repository {
         commit_author: String,
         supress_output: bool,
         apply_side_effects: bool,
-    ) {
+    ) -> Result<(), GitAiError> {
         // 步骤 1: 将 rewrite 事件追加到持久化日志中
         // 这个日志存储在 .git/ai/rewrite_log 文件中
         // 记录所有改变提交历史的操作，用于后续的归属追踪和 blame 功能
         let log = self
             .storage
-            .append_rewrite_event(rewrite_log_event.clone())
-            .ok()
-            .expect("Error writing .git/ai/rewrite_log");
+            .append_rewrite_event(rewrite_log_event.clone())?;
 
         // 步骤 2: 如果需要应用副作用，则重写代码归属
         // apply_side_effects=true 表示这是一个实际的提交操作
@@ -930,19 +970,17 @@ impl Repository {
             // 2. 将 working log 转换为 authorship log（永久归属记录）
             // 3. 清理 working log，为下一次提交做准备
             // 4. 更新 .git/ai/authorship/ 目录下的归属数据
-            match rewrite_authorship_if_needed(
+            rewrite_authorship_if_needed(
                 self,
                 &rewrite_log_event,
                 commit_author,
                 &log,
                 supress_output,
-            ) {
-                Ok(_) => (), // 成功：归属数据已更新
-                Err(_) => {} // 失败：静默处理，不影响 Git 操作
-            }
+            )?;
             // 注意：即使 rewrite_authorship_if_needed 失败，Git 操作仍会成功
-            // 这是优雅降级策略：git-ai 的问题不应阻止用户使用 Git
+            // 错误交给调用方的钩子返回，计入安全模式的连续失败次数
         }
+        Ok(())
     }
 
     // Internal util to get the git object type for a given OID
@@ -1025,6 +1063,60 @@ impl Repository {
         normalized.starts_with(&self.workdir)
     }
 
+    /// Resolve a path as sent by a preset (absolute, relative to the workdir, or
+    /// `~`-prefixed) to a repo-relative POSIX path, erroring if it leaves the worktree
+    pub fn repo_relative_path(&self, path: &str) -> Result<String, GitAiError> {
+        let outside =
+            || GitAiError::InvalidArguments(format!("Path is outside the repository: {}", path));
+        let expanded = match path.strip_prefix('~') {
+            Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => {
+                let home = dirs::home_dir().ok_or_else(outside)?;
+                home.join(rest.trim_start_matches(['/', '\\']))
+            }
+            _ => PathBuf::from(path),
+        };
+        let absolute = if expanded.is_absolute() {
+            expanded
+        } else {
+            self.workdir.join(expanded)
+        };
+
+        // Resolve . and .. without touching the filesystem; the file may not exist yet
+        let mut normalized = PathBuf::new();
+        for component in absolute.components() {
+            match component {
+                std::path::Component::ParentDir => {
+                    normalized.pop();
+                }
+                std::path::Component::CurDir => {}
+                _ => normalized.push(component),
+            }
+        }
+
+        let relative = match normalized.strip_prefix(&self.workdir) {
+            Ok(relative) => relative.to_path_buf(),
+            // Symlinked or differently-spelled workdirs only match once canonicalized
+            Err(_) => normalized
+                .canonicalize()
+                .ok()
+                .and_then(|canonical| {
+                    canonical
+                        .strip_prefix(&self.canonical_workdir)
+                        .ok()
+                        .map(Path::to_path_buf)
+                })
+                .ok_or_else(outside)?,
+        };
+        let relative = crate::utils::normalize_to_posix(&relative.to_string_lossy());
+        if relative.is_empty() {
+            return Err(GitAiError::InvalidArguments(format!(
+                "Path is the repository root, not a file: {}",
+                path
+            )));
+        }
+        Ok(relative)
+    }
+
     // List all remotes for a given repository
     pub fn remotes(&self) -> Result<Vec<String>, GitAiError> {
         let mut args = self.global_args_for_exec();
@@ -1062,6 +1154,11 @@ impl Repository {
         Ok(remotes)
     }
 
+    /// The repository's `git replace` mappings, read once per instance
+    pub fn replace_map(&self) -> &ReplaceMap {
+        self.replace_map.get_or_init(|| ReplaceMap::load(self))
+    }
+
     pub fn config_get_str(&self, key: &str) -> Result<Option<String>, GitAiError> {
         let mut args = self.global_args_for_exec();
         args.push("config".to_string());
@@ -1578,6 +1675,58 @@ impl Repository {
         Ok(output.stdout)
     }
 
+    /// Contents of `file_paths` in `treeish`, read with a single
+    /// `git cat-file --batch` rather than a git call per file. Paths the tree
+    /// doesn't have are left out.
+    pub fn get_files_content(
+        &self,
+        treeish: &str,
+        file_paths: &[String],
+    ) -> Result<HashMap<String, Vec<u8>>, GitAiError> {
+        // cat-file reads one object name per line
+        let file_paths: Vec<&String> = file_paths
+            .iter()
+            .filter(|path| !path.contains('\n'))
+            .collect();
+        if file_paths.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let mut args = self.global_args_for_exec();
+        args.push("cat-file".to_string());
+        args.push("--batch".to_string());
+        let stdin: String = file_paths
+            .iter()
+            .map(|path| format!("{}:{}\n", treeish, path))
+            .collect();
+        let output = exec_git_stdin(&args, stdin.as_bytes())?;
+
+        // Each object is "<oid> <type> <size>\n<content>\n", in input order,
+        // or "<name> missing\n"
+        let data = output.stdout;
+        let mut contents = HashMap::new();
+        let mut pos = 0;
+        for path in file_paths {
+            let Some(header_len) = data[pos..].iter().position(|&b| b == b'\n') else {
+                break;
+            };
+            let header = String::from_utf8_lossy(&data[pos..pos + header_len]).to_string();
+            pos += header_len + 1;
+            let mut fields = header.rsplitn(3, ' ');
+            // "missing" and similar headers have no body
+            let Some(size) = fields.next().and_then(|size| size.parse::<usize>().ok()) else {
+                continue;
+            };
+            // Skip the body of trees and other non-blobs so the next header lines up
+            let end = (pos + size).min(data.len());
+            if fields.next() == Some("blob") {
+                contents.insert(path.clone(), data[pos..end].to_vec());
+            }
+            pos = (end + 1).min(data.len());
+        }
+        Ok(contents)
+    }
+
     /// Get content of all staged files concurrently
     /// Returns a HashMap of file paths to their staged content as strings
     /// Skips files that fail to read or aren't valid UTF-8
@@ -1773,15 +1922,15 @@ impl Repository {
     }
 
     /// Get added line ranges from git diff between a commit and the working directory,
-    /// along with information about which lines are pure insertions (old_count=0).
+    /// along with information about which lines are pure insertions (old_count=0)
+    /// and the hunks themselves.
     ///
-    /// Returns (all_added_lines, pure_insertion_lines)
     /// Pure insertions are lines that were added without modifying existing lines at that position.
     pub fn diff_workdir_added_lines_with_insertions(
         &self,
         from_ref: &str,
         pathspecs: Option<&HashSet<String>>,
-    ) -> Result<(HashMap<String, Vec<u32>>, HashMap<String, Vec<u32>>), GitAiError> {
+    ) -> Result<WorkdirDiff, GitAiError> {
         let mut args = self.global_args_for_exec();
         args.push("diff".to_string());
         args.push("-U0".to_string()); // Zero context lines
@@ -1793,7 +1942,7 @@ impl Repository {
             // for case where pathspec filter provided BUT not pathspecs.
             // otherwise it would default to full repo
             if paths.is_empty() {
-                return Ok((HashMap::new(), HashMap::new()));
+                return Ok(WorkdirDiff::default());
             }
             args.push("--".to_string());
             for path in paths {
@@ -1823,7 +1972,12 @@ pub fn find_repository(global_args: &Vec<String>) -> Result<Repository, GitAiErr
     args.push("--absolute-git-dir".to_string());
     args.push("--show-toplevel".to_string());
 
-    let output = exec_git(&args)?;
+    let output = exec_git(&args).map_err(|e| match e {
+        GitAiError::GitCliError { stderr, .. } if stderr.contains("not a git repository") => {
+            GitAiError::NotARepository(stderr.trim().to_string())
+        }
+        e => e,
+    })?;
     let both_dirs = String::from_utf8(output.stdout)?;
 
     let both_dirs = both_dirs.trim();
@@ -1877,6 +2031,7 @@ pub fn find_repository(global_args: &Vec<String>) -> Result<Repository, GitAiErr
         pre_reset_target_commit: None,
         workdir,
         canonical_workdir,
+        replace_map: std::sync::OnceLock::new(),
     })
 }
 
@@ -1899,6 +2054,7 @@ pub fn from_bare_repository(git_dir: &Path) -> Result<Repository, GitAiError> {
         pre_reset_target_commit: None,
         workdir,
         canonical_workdir,
+        replace_map: std::sync::OnceLock::new(),
     })
 }
 
@@ -2044,15 +2200,63 @@ fn parse_diff_added_lines(diff_output: &str) -> Result<HashMap<String, Vec<u32>>
     Ok(result)
 }
 
+/// Line numbers of one zero-context diff hunk on both sides
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HunkSpan {
+    pub old_start: u32,
+    pub old_count: u32,
+    pub new_start: u32,
+    pub new_count: u32,
+}
+
+impl HunkSpan {
+    /// Whether the hunk lies entirely before `new_line`. A pure deletion
+    /// (new_count=0) sits just after its `new_start`.
+    fn ends_before(&self, new_line: u32) -> bool {
+        if self.new_count == 0 {
+            self.new_start < new_line
+        } else {
+            self.new_start + self.new_count <= new_line
+        }
+    }
+}
+
+/// Map a line of the new side of a diff to the old side. Unchanged lines
+/// shift by whatever the hunks above them added or removed; changed lines
+/// pair up with the old line at the same offset in their hunk. `hunks` must
+/// be in file order.
+pub fn new_line_to_old_line(hunks: &[HunkSpan], new_line: u32) -> u32 {
+    let mut shift: i64 = 0;
+    for hunk in hunks {
+        if hunk.new_count > 0
+            && new_line >= hunk.new_start
+            && new_line < hunk.new_start + hunk.new_count
+        {
+            return hunk.old_start + (new_line - hunk.new_start);
+        }
+        if !hunk.ends_before(new_line) {
+            break;
+        }
+        shift += hunk.old_count as i64 - hunk.new_count as i64;
+    }
+    (new_line as i64 + shift).max(1) as u32
+}
+
+/// Zero-context diff from a commit to the working directory
+#[derive(Debug, Default)]
+pub struct WorkdirDiff {
+    /// Working directory lines that are new or changed, per file
+    pub added_lines: HashMap<String, Vec<u32>>,
+    /// The subset of `added_lines` from hunks that replace nothing (old_count=0)
+    pub insertion_lines: HashMap<String, Vec<u32>>,
+    /// Every hunk per file, in file order
+    pub hunks: HashMap<String, Vec<HunkSpan>>,
+}
+
 /// Parses the unified diff output to extract line numbers of added lines,
 /// along with information about which are pure insertions (old_count=0).
-///
-/// Returns (all_added_lines, pure_insertion_lines)
-fn parse_diff_added_lines_with_insertions(
-    diff_output: &str,
-) -> Result<(HashMap<String, Vec<u32>>, HashMap<String, Vec<u32>>), GitAiError> {
-    let mut all_lines: HashMap<String, Vec<u32>> = HashMap::new();
-    let mut insertion_lines: HashMap<String, Vec<u32>> = HashMap::new();
+fn parse_diff_added_lines_with_insertions(diff_output: &str) -> Result<WorkdirDiff, GitAiError> {
+    let mut diff = WorkdirDiff::default();
     let mut current_file: Option<String> = None;
 
     for line in diff_output.lines() {
@@ -2066,33 +2270,56 @@ fn parse_diff_added_lines_with_insertions(
             // Parse hunk header: @@ -old_start,old_count +new_start,new_count @@
             if let Some(ref file) = current_file {
                 if let Some((added_lines, is_pure_insertion)) = parse_hunk_header(line) {
-                    all_lines
+                    diff.added_lines
                         .entry(file.clone())
                         .or_insert_with(Vec::new)
                         .extend(added_lines.clone());
 
                     if is_pure_insertion {
-                        insertion_lines
+                        diff.insertion_lines
                             .entry(file.clone())
                             .or_insert_with(Vec::new)
                             .extend(added_lines);
                     }
                 }
+                if let Some(span) = parse_hunk_span(line) {
+                    diff.hunks.entry(file.clone()).or_default().push(span);
+                }
             }
         }
     }
 
     // Sort and deduplicate line numbers for each file
-    for lines in all_lines.values_mut() {
+    for lines in diff.added_lines.values_mut() {
         lines.sort_unstable();
         lines.dedup();
     }
-    for lines in insertion_lines.values_mut() {
+    for lines in diff.insertion_lines.values_mut() {
         lines.sort_unstable();
         lines.dedup();
     }
 
-    Ok((all_lines, insertion_lines))
+    Ok(diff)
+}
+
+/// Parse `@@ -old_start[,old_count] +new_start[,new_count] @@`
+fn parse_hunk_span(line: &str) -> Option<HunkSpan> {
+    let info = line.split("@@").nth(1)?.trim();
+    let parse = |range: &str| -> Option<(u32, u32)> {
+        match range.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let mut ranges = info.split_whitespace();
+    let (old_start, old_count) = parse(ranges.next()?.strip_prefix('-')?)?;
+    let (new_start, new_count) = parse(ranges.next()?.strip_prefix('+')?)?;
+    Some(HunkSpan {
+        old_start,
+        old_count,
+        new_start,
+        new_count,
+    })
 }
 
 /// Parse a hunk header line to extract added line numbers and whether it's a pure insertion
@@ -2157,3 +2384,27 @@ fn parse_hunk_header(line: &str) -> Option<(Vec<u32>, bool)> {
 
     Some((lines, is_pure_insertion))
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_get_files_content_skips_trees() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo
+            .write_file("src/lib.rs", "fn lib() {}\n", true)
+            .unwrap();
+        tmp_repo.write_file("b.txt", "bee\n", true).unwrap();
+        tmp_repo.trigger_checkpoint_with_author("dev").unwrap();
+        tmp_repo.commit_with_message("Initial").unwrap();
+
+        let paths = ["src", "gone.txt", "b.txt"].map(str::to_string);
+        let contents = tmp_repo
+            .gitai_repo()
+            .get_files_content("HEAD", &paths)
+            .unwrap();
+        assert_eq!(contents.len(), 1);
+        assert_eq!(contents["b.txt"], b"bee\n");
+    }
+}
@@ -205,6 +205,12 @@ impl MergeSquashEvent {
 pub struct RebaseStartEvent {
     pub original_head: String,
     pub is_interactive: bool,
+    /// Resolved `--onto` target, when the range is transplanted onto another base
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub onto: Option<String>,
+    /// Resolved upstream that bounds the transplanted range (`<upstream>..<branch>`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upstream: Option<String>,
 }
 
 impl RebaseStartEvent {
@@ -212,8 +218,16 @@ impl RebaseStartEvent {
         Self {
             original_head,
             is_interactive,
+            onto: None,
+            upstream: None,
         }
     }
+
+    pub fn with_onto(mut self, onto: String, upstream: String) -> Self {
+        self.onto = Some(onto);
+        self.upstream = Some(upstream);
+        self
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -223,6 +237,9 @@ pub struct RebaseCompleteEvent {
     pub is_interactive: bool,
     pub original_commits: Vec<String>,
     pub new_commits: Vec<String>,
+    /// Set for `rebase --onto`; authorship is then carried over by patch-id first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub onto: Option<String>,
 }
 
 impl RebaseCompleteEvent {
@@ -239,8 +256,14 @@ impl RebaseCompleteEvent {
             is_interactive,
             original_commits,
             new_commits,
+            onto: None,
         }
     }
+
+    pub fn with_onto(mut self, onto: String) -> Self {
+        self.onto = Some(onto);
+        self
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -400,6 +400,9 @@ impl TmpRepo {
             edited_filepaths: None,
             will_edit_filepaths: None,
             dirty_files: None,
+            commit_message: None,
+            edited_line_ranges: None,
+            append_transcript: false,
         };
 
         checkpoint(
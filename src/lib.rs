@@ -1,9 +1,41 @@
-// pub mod authorship;
-// pub mod ci;
-// pub mod commands;
-// pub mod config;
-// pub mod error;
-// pub mod feature_flags;
-// pub mod git;
-// pub mod observability;
-// pub mod utils;
+//! AI authorship tracking for git.
+//!
+//! Most embedders only need [`GitAi`]; the modules below are what the `git-ai`
+//! binary is built from and are public for lower-level access. Building with
+//! `--no-default-features` leaves only [`attribution_core`].
+
+pub mod attribution_core;
+
+#[cfg(feature = "cli")]
+pub mod api;
+#[cfg(feature = "cli")]
+pub mod authorship;
+#[cfg(feature = "cli")]
+pub mod ci;
+#[cfg(feature = "cli")]
+pub mod commands;
+#[cfg(feature = "cli")]
+pub mod config;
+#[cfg(feature = "cli")]
+pub mod error;
+#[cfg(feature = "cli")]
+pub mod event_hooks;
+#[cfg(feature = "cli")]
+pub mod feature_flags;
+#[cfg(feature = "git-ai-ffi")]
+pub mod ffi;
+#[cfg(feature = "cli")]
+pub mod git;
+#[cfg(feature = "cli")]
+pub mod notifications;
+#[cfg(feature = "cli")]
+pub mod observability;
+#[cfg(feature = "cli")]
+pub mod policy;
+#[cfg(feature = "cli")]
+pub mod utils;
+
+#[cfg(feature = "cli")]
+pub use api::{CheckpointSummary, FileBlame, GitAi};
+#[cfg(feature = "cli")]
+pub use error::GitAiError;
@@ -1,14 +1,5 @@
-mod authorship;
-mod ci;
-mod commands;
-mod config;
-mod error;
-mod feature_flags;
-mod git;
-mod observability;
-mod utils;
-
 use clap::Parser;
+use git_ai::commands;
 
 #[derive(Parser)]
 #[command(name = "git-ai")]
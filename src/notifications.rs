@@ -0,0 +1,349 @@
+use crate::config::Config;
+use crate::error::GitAiError;
+use crate::git::repository::Repository;
+use crate::observability::offline_queue::OfflineQueue;
+use crate::utils::debug_log;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_SYNC_FAILURE_THRESHOLD: u32 = 3;
+
+/// Pending deliveries, one per (webhook, event), under ~/.git-ai
+const NOTIFICATION_QUEUE: &str = "notifications";
+/// Keys of recently delivered events, so a repeat within a day isn't sent again
+const SENT_KEYS_FILE: &str = "notifications_sent.json";
+const SENT_KEY_TTL_SECS: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationEvent {
+    PolicyViolation,
+    SyncFailure,
+    CiFailure,
+}
+
+impl NotificationEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationEvent::PolicyViolation => "policy_violation",
+            NotificationEvent::SyncFailure => "sync_failure",
+            NotificationEvent::CiFailure => "ci_failure",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Events this webhook subscribes to; empty means all events
+    #[serde(default)]
+    pub events: Vec<NotificationEvent>,
+}
+
+impl WebhookConfig {
+    fn subscribes_to(&self, event: NotificationEvent) -> bool {
+        self.events.is_empty() || self.events.contains(&event)
+    }
+}
+
+/// The `notifications` section of ~/.git-ai/config.json
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NotificationsConfig {
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+    /// Consecutive authorship sync failures before a `sync_failure` notification fires
+    #[serde(default)]
+    pub sync_failure_threshold: Option<u32>,
+}
+
+impl NotificationsConfig {
+    pub fn has_subscribers(&self, event: NotificationEvent) -> bool {
+        self.webhooks.iter().any(|w| w.subscribes_to(event))
+    }
+}
+
+/// Slack-compatible payload: `text` renders in Slack, the rest is for other consumers.
+fn build_payload(event: NotificationEvent, text: &str, details: &Value) -> Value {
+    json!({
+        "text": format!("[git-ai] {}", text),
+        "event": event.as_str(),
+        "details": details,
+        "git_ai_version": env!("CARGO_PKG_VERSION"),
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+/// Queue `event` for every configured webhook that subscribes to it and
+/// leave delivery to a background `flush-logs`, so a slow endpoint never holds
+/// up the command that raised it. An event identical to one still queued or
+/// sent in the last day is dropped.
+pub fn notify(event: NotificationEvent, text: &str, details: Value) {
+    if queue_notification(event, text, &details) {
+        crate::observability::spawn_background_flush();
+    }
+}
+
+/// Like [`notify`], but delivers before returning, for callers about to exit
+/// where nothing would be left to flush the queue later (CI runners).
+pub fn notify_blocking(event: NotificationEvent, text: &str, details: Value) {
+    if queue_notification(event, text, &details) {
+        flush_queue();
+    }
+}
+
+/// Deliver queued notifications, backing off while endpoints keep failing
+pub fn flush_queue() {
+    if let Some(dir) = queue_dir() {
+        flush_queue_in(&dir, post_webhook);
+    }
+}
+
+/// Returns whether anything new was queued
+fn queue_notification(event: NotificationEvent, text: &str, details: &Value) -> bool {
+    let config = Config::get().notifications();
+    if !config.has_subscribers(event) {
+        return false;
+    }
+    let Some(dir) = queue_dir() else {
+        return false;
+    };
+
+    let key = dedup_key(event, details);
+    let payload = build_payload(event, text, details);
+    let entries: Vec<Value> = config
+        .webhooks
+        .iter()
+        .filter(|w| w.subscribes_to(event))
+        .map(|w| json!({ "url": w.url, "key": key, "payload": payload }))
+        .collect();
+    match enqueue(&dir, &entries) {
+        Ok(queued) => queued > 0,
+        Err(e) => {
+            debug_log(&format!(
+                "failed to queue {} notification: {}",
+                event.as_str(),
+                e
+            ));
+            false
+        }
+    }
+}
+
+fn queue_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".git-ai"))
+}
+
+/// Same event with the same details, whatever the wording or timestamp
+fn dedup_key(event: NotificationEvent, details: &Value) -> String {
+    let digest = Sha256::digest(format!("{}:{}", event.as_str(), details).as_bytes());
+    format!("{:x}", digest)[..16].to_string()
+}
+
+fn entry_id(entry: &Value) -> (Option<&str>, Option<&str>) {
+    (entry["url"].as_str(), entry["key"].as_str())
+}
+
+/// Queue the entries that aren't already queued or recently sent, returning how many were
+fn enqueue(dir: &Path, entries: &[Value]) -> std::io::Result<usize> {
+    fs::create_dir_all(dir)?;
+    let queue = OfflineQueue::new(dir, NOTIFICATION_QUEUE);
+    let queued = queue.events();
+    let sent = read_sent_keys(dir, now_secs());
+    let fresh: Vec<Value> = entries
+        .iter()
+        .filter(|entry| {
+            !queued.iter().any(|q| entry_id(q) == entry_id(entry))
+                && !entry["key"]
+                    .as_str()
+                    .is_some_and(|key| sent.iter().any(|(k, _)| k == key))
+        })
+        .cloned()
+        .collect();
+    queue.enqueue(&fresh)?;
+    Ok(fresh.len())
+}
+
+fn flush_queue_in<F>(dir: &Path, mut send: F)
+where
+    F: FnMut(&str, &Value) -> bool,
+{
+    let queue = OfflineQueue::new(dir, NOTIFICATION_QUEUE);
+    let now = now_secs();
+    let mut delivered = Vec::new();
+    let result = queue.drain(now, usize::MAX, |entry| {
+        let ok = send(entry["url"].as_str().unwrap_or_default(), &entry["payload"]);
+        if ok && let Some(key) = entry["key"].as_str() {
+            delivered.push((key.to_string(), now));
+        }
+        ok
+    });
+    if let Err(e) = result {
+        debug_log(&format!("failed to drain notification queue: {}", e));
+    }
+    if !delivered.is_empty() {
+        let mut sent = read_sent_keys(dir, now);
+        sent.extend(delivered);
+        let _ = fs::write(
+            dir.join(SENT_KEYS_FILE),
+            serde_json::to_string(&sent).unwrap_or_default(),
+        );
+    }
+}
+
+/// Delivered keys younger than the TTL, with when they were sent
+fn read_sent_keys(dir: &Path, now: u64) -> Vec<(String, u64)> {
+    let sent: Vec<(String, u64)> = fs::read_to_string(dir.join(SENT_KEYS_FILE))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+    sent.into_iter()
+        .filter(|(_, at)| now.saturating_sub(*at) < SENT_KEY_TTL_SECS)
+        .collect()
+}
+
+fn now_secs() -> u64 {
+    chrono::Utc::now().timestamp().max(0) as u64
+}
+
+fn post_webhook(url: &str, payload: &Value) -> bool {
+    let result = minreq::post(url)
+        .with_header("Content-Type", "application/json")
+        .with_header(
+            "User-Agent",
+            format!("git-ai/{}", env!("CARGO_PKG_VERSION")),
+        )
+        .with_timeout(5)
+        .with_body(payload.to_string())
+        .send();
+    match result {
+        Ok(response) if (200..300).contains(&response.status_code) => true,
+        Ok(response) => {
+            debug_log(&format!(
+                "webhook {} returned status {}",
+                url, response.status_code
+            ));
+            false
+        }
+        Err(e) => {
+            debug_log(&format!("webhook {} failed: {}", url, e));
+            false
+        }
+    }
+}
+
+/// Track consecutive authorship sync failures for `repo`, firing a `sync_failure`
+/// notification when the configured threshold is reached. Success resets the count.
+pub fn record_sync_result<T>(
+    repo: &Repository,
+    operation: &str,
+    remote: &str,
+    result: &Result<T, GitAiError>,
+) {
+    let counter_path = &repo.storage.sync_failures;
+    let error = match result {
+        Ok(_) => {
+            if counter_path.exists() {
+                let _ = fs::remove_file(counter_path);
+            }
+            return;
+        }
+        Err(e) => e,
+    };
+
+    let failures = fs::read_to_string(counter_path)
+        .ok()
+        .and_then(|s| s.trim().parse::<u32>().ok())
+        .unwrap_or(0)
+        + 1;
+    let _ = fs::write(counter_path, failures.to_string());
+
+    let threshold = Config::get()
+        .notifications()
+        .sync_failure_threshold
+        .unwrap_or(DEFAULT_SYNC_FAILURE_THRESHOLD);
+    // Fire once when the streak reaches the threshold rather than on every failure after it
+    if failures == threshold {
+        notify(
+            NotificationEvent::SyncFailure,
+            &format!(
+                "Authorship {} to '{}' has failed {} times in a row: {}",
+                operation, remote, failures, error
+            ),
+            json!({
+                "operation": operation,
+                "remote": remote,
+                "consecutive_failures": failures,
+                "error": error.to_string(),
+                "repository": repo.workdir().ok().map(|p| p.display().to_string()),
+            }),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_deserializes_events() {
+        let config: NotificationsConfig = serde_json::from_str(
+            r#"{"webhooks":[{"url":"https://hooks.slack.com/x","events":["policy_violation"]},{"url":"https://example.com"}]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.webhooks.len(), 2);
+        assert!(config.webhooks[0].subscribes_to(NotificationEvent::PolicyViolation));
+        assert!(!config.webhooks[0].subscribes_to(NotificationEvent::CiFailure));
+        assert!(config.webhooks[1].subscribes_to(NotificationEvent::SyncFailure));
+        assert!(config.has_subscribers(NotificationEvent::CiFailure));
+        assert!(!NotificationsConfig::default().has_subscribers(NotificationEvent::CiFailure));
+    }
+
+    #[test]
+    fn test_payload_is_slack_compatible() {
+        let payload = build_payload(
+            NotificationEvent::CiFailure,
+            "check failed",
+            &json!({ "sha": "abc" }),
+        );
+        assert_eq!(payload["text"], "[git-ai] check failed");
+        assert_eq!(payload["event"], "ci_failure");
+        assert_eq!(payload["details"]["sha"], "abc");
+    }
+
+    #[test]
+    fn test_queue_drops_duplicates_and_delivers_in_flush() {
+        let dir = tempfile::tempdir().unwrap();
+        let details = json!({ "commit": "abc", "rule": "max_ai_percentage" });
+        let key = dedup_key(NotificationEvent::PolicyViolation, &details);
+        let entry = |url: &str| {
+            json!({
+                "url": url,
+                "key": key,
+                "payload": build_payload(NotificationEvent::PolicyViolation, "x", &details),
+            })
+        };
+
+        assert_eq!(
+            enqueue(dir.path(), &[entry("https://a"), entry("https://b")]).unwrap(),
+            2
+        );
+        assert_eq!(enqueue(dir.path(), &[entry("https://a")]).unwrap(), 0);
+        assert_ne!(key, dedup_key(NotificationEvent::CiFailure, &details));
+
+        let mut posted = Vec::new();
+        flush_queue_in(dir.path(), |url, payload| {
+            posted.push((url.to_string(), payload["event"].clone()));
+            true
+        });
+        assert_eq!(posted.len(), 2);
+        assert_eq!(posted[0].1, "policy_violation");
+
+        // Delivered events aren't queued again
+        assert_eq!(enqueue(dir.path(), &[entry("https://a")]).unwrap(), 0);
+        flush_queue_in(dir.path(), |_, _| panic!("queue should be empty"));
+    }
+}
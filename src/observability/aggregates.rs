@@ -0,0 +1,194 @@
+//! Opt-in aggregate attribution metrics for the OSS telemetry path. The report
+//! only holds counts: checkpoints by kind, commits by AI-share bucket and
+//! commits per agent tool. No file paths, prompts, commit ids, remotes or
+//! author names ever go into it. `git-ai telemetry preview` prints the exact
+//! event `flush-logs` would send.
+
+use crate::authorship::stats::get_git_diff_stats_matching;
+use crate::error::GitAiError;
+use crate::git::refs::get_authorship;
+use crate::git::repository::{Repository, exec_git};
+use crate::observability::metrics::MetricsSnapshot;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::fs;
+
+/// Window of commits the report covers
+pub const PERIOD_DAYS: u32 = 30;
+
+/// Reports are sent at most once per day per repository
+const SEND_INTERVAL_SECS: u64 = 24 * 60 * 60;
+
+const MAX_COMMITS: usize = 1000;
+
+/// Commits by the share of their added lines attributed to AI
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AiPercentBuckets {
+    #[serde(rename = "0%")]
+    pub none: u64,
+    #[serde(rename = "1-25%")]
+    pub up_to_25: u64,
+    #[serde(rename = "26-50%")]
+    pub up_to_50: u64,
+    #[serde(rename = "51-75%")]
+    pub up_to_75: u64,
+    #[serde(rename = "76-99%")]
+    pub under_100: u64,
+    #[serde(rename = "100%")]
+    pub all: u64,
+}
+
+impl AiPercentBuckets {
+    pub fn add(&mut self, ai_lines: u32, added_lines: u32) {
+        let bucket = match (ai_lines, added_lines) {
+            (0, _) | (_, 0) => &mut self.none,
+            (ai, added) if ai >= added => &mut self.all,
+            (ai, added) => match ai as u64 * 100 / added as u64 {
+                0..=25 => &mut self.up_to_25,
+                26..=50 => &mut self.up_to_50,
+                51..=75 => &mut self.up_to_75,
+                _ => &mut self.under_100,
+            },
+        };
+        *bucket += 1;
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AggregateReport {
+    pub schema_version: u32,
+    pub git_ai_version: String,
+    pub period_days: u32,
+    /// Checkpoints recorded in this repository, by checkpoint kind
+    pub checkpoints: BTreeMap<String, u64>,
+    pub commits: u64,
+    pub commits_without_attribution: u64,
+    pub commits_by_ai_percent: AiPercentBuckets,
+    /// Commits with accepted lines from each agent tool
+    pub agents: BTreeMap<String, u64>,
+}
+
+/// Build the report for `repo` from its metrics file and recent authorship notes
+pub fn collect(repo: &Repository) -> Result<AggregateReport, GitAiError> {
+    let mut report = AggregateReport {
+        schema_version: 1,
+        git_ai_version: env!("CARGO_PKG_VERSION").to_string(),
+        period_days: PERIOD_DAYS,
+        checkpoints: MetricsSnapshot::load(&repo.storage.metrics).checkpoints_total,
+        ..Default::default()
+    };
+
+    let mut args = repo.global_args_for_exec();
+    args.push("log".to_string());
+    args.push("--format=%H".to_string());
+    args.push(format!("--since={}.days.ago", PERIOD_DAYS));
+    args.push(format!("--max-count={}", MAX_COMMITS));
+    args.push("HEAD".to_string());
+    // An unborn HEAD just means there's nothing to count yet
+    let Ok(output) = exec_git(&args) else {
+        return Ok(report);
+    };
+
+    for sha in String::from_utf8_lossy(&output.stdout).lines() {
+        report.commits += 1;
+        let Some(log) = get_authorship(repo, sha) else {
+            report.commits_without_attribution += 1;
+            continue;
+        };
+        let (added, _) = get_git_diff_stats_matching(repo, sha, |_| true)?;
+        let ai_lines = log
+            .metadata
+            .prompts
+            .values()
+            .map(|prompt| prompt.accepted_lines)
+            .sum();
+        report.commits_by_ai_percent.add(ai_lines, added);
+
+        let mut tools: Vec<&str> = log
+            .metadata
+            .prompts
+            .values()
+            .filter(|prompt| prompt.accepted_lines > 0)
+            .map(|prompt| prompt.agent_id.tool.as_str())
+            .collect();
+        tools.sort_unstable();
+        tools.dedup();
+        for tool in tools {
+            *report.agents.entry(tool.to_string()).or_default() += 1;
+        }
+    }
+    Ok(report)
+}
+
+/// The Sentry event carrying the report. Unlike log events it has no tags, so
+/// nothing about the repository (e.g. remote URLs) goes with it.
+pub fn sentry_event(report: &AggregateReport) -> Value {
+    json!({
+        "message": "git-ai attribution aggregates",
+        "level": "info",
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "platform": "other",
+        "extra": report,
+        "release": format!("git-ai@{}", env!("CARGO_PKG_VERSION")),
+    })
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SendState {
+    last_sent: u64,
+}
+
+/// Whether a day has passed since this repository last sent a report
+pub fn due(repo: &Repository, now: u64) -> bool {
+    let last_sent = fs::read(&repo.storage.telemetry_aggregates)
+        .ok()
+        .and_then(|data| serde_json::from_slice::<SendState>(&data).ok())
+        .map(|state| state.last_sent)
+        .unwrap_or(0);
+    now.saturating_sub(last_sent) >= SEND_INTERVAL_SECS
+}
+
+pub fn mark_sent(repo: &Repository, now: u64) -> Result<(), GitAiError> {
+    let json = serde_json::to_vec(&SendState { last_sent: now })?;
+    fs::write(&repo.storage.telemetry_aggregates, json)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ai_percent_buckets() {
+        let mut buckets = AiPercentBuckets::default();
+        buckets.add(0, 10);
+        buckets.add(5, 0);
+        buckets.add(2, 10);
+        buckets.add(5, 10);
+        buckets.add(7, 10);
+        buckets.add(99, 100);
+        buckets.add(10, 10);
+        buckets.add(12, 10);
+        assert_eq!(
+            buckets,
+            AiPercentBuckets {
+                none: 2,
+                up_to_25: 1,
+                up_to_50: 1,
+                up_to_75: 1,
+                under_100: 1,
+                all: 2,
+            }
+        );
+
+        let event = sentry_event(&AggregateReport::default());
+        assert!(event.get("tags").is_none());
+        let keys: Vec<&String> = event["extra"]["commits_by_ai_percent"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .collect();
+        assert_eq!(keys.len(), 6);
+    }
+}
@@ -1,15 +1,25 @@
 use crate::config::Config;
 use crate::git::find_repository_in_path;
+use crate::observability::aggregates;
+use crate::observability::offline_queue::OfflineQueue;
+use crate::observability::otlp::{self, OtlpExporter};
+use crate::observability::sinks::{self, SinkConfig};
 use futures::stream::{self, StreamExt};
 use serde_json::{Value, json};
 use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// Events uploaded per batch when draining the enterprise queue
+const ENTERPRISE_BATCH_SIZE: usize = 100;
+
 /// Handle the flush-logs command
 pub fn handle_flush_logs(args: &[String]) {
+    // Webhooks are the user's own endpoints, so they go out even from debug builds
+    crate::notifications::flush_queue();
+
     let force = args.contains(&"--force".to_string());
     if cfg!(debug_assertions) && !force {
         eprintln!(
@@ -43,8 +53,12 @@ pub fn handle_flush_logs(args: &[String]) {
             .filter(|s| !s.is_empty())
     };
 
-    // Need at least one DSN to proceed
-    if oss_dsn.is_none() && enterprise_dsn.is_none() {
+    let otlp_exporter = OtlpExporter::from_config(config);
+    let sinks = config.telemetry_sinks();
+
+    // Need at least one DSN, an OTLP collector or a configured sink to proceed
+    if oss_dsn.is_none() && enterprise_dsn.is_none() && otlp_exporter.is_none() && sinks.is_empty()
+    {
         std::process::exit(1);
     }
 
@@ -56,6 +70,13 @@ pub fn handle_flush_logs(args: &[String]) {
         }
     };
 
+    // Opt-in aggregate report, independent of whether there are logs to flush
+    if config.telemetry_aggregates_enabled()
+        && let Some(dsn) = &oss_dsn
+    {
+        flush_aggregates(&logs_dir, dsn);
+    }
+
     // Get current PID to exclude our own log file
     let current_pid = std::process::id();
     let current_log_file = format!("{}.log", current_pid);
@@ -80,6 +101,11 @@ pub fn handle_flush_logs(args: &[String]) {
     };
 
     if log_files.is_empty() {
+        // Still retry anything queued while offline
+        if let Some(client) = initialize_sentry_clients(None, enterprise_dsn).1 {
+            flush_enterprise_queue(&logs_dir, &client, &[]);
+        }
+        flush_sinks(&logs_dir, sinks, &[]);
         std::process::exit(1);
     }
 
@@ -94,23 +120,30 @@ pub fn handle_flush_logs(args: &[String]) {
     let (oss_client, enterprise_client) = initialize_sentry_clients(oss_dsn, enterprise_dsn);
 
     // Check if clients are present (needed for cleanup logic later)
-    let has_clients = oss_client.is_some() || enterprise_client.is_some();
+    let has_clients = oss_client.is_some()
+        || enterprise_client.is_some()
+        || otlp_exporter.is_some()
+        || !sinks.is_empty();
 
     eprintln!(
         "Processing {} log files (max 10 concurrent)...",
         log_files.len()
     );
 
+    // Enterprise and sink events are queued on disk here and uploaded afterwards with backoff
+    let queue_enterprise = enterprise_client.is_some();
+    let queue_sinks = !sinks.is_empty();
+
     // Process log files in parallel (max 10 at a time)
     let results = smol::block_on(async {
         let oss_client = Arc::new(oss_client);
-        let enterprise_client = Arc::new(enterprise_client);
+        let otlp_exporter = Arc::new(otlp_exporter);
         let remotes_info = Arc::new(remotes_info);
 
         stream::iter(log_files)
             .map(|log_file| {
                 let oss_client = Arc::clone(&oss_client);
-                let enterprise_client = Arc::clone(&enterprise_client);
+                let otlp_exporter = Arc::clone(&otlp_exporter);
                 let remotes_info = Arc::clone(&remotes_info);
 
                 smol::unblock(move || {
@@ -122,12 +155,14 @@ pub fn handle_flush_logs(args: &[String]) {
                     match process_log_file(
                         &log_file,
                         &oss_client,
-                        &enterprise_client,
+                        queue_enterprise,
+                        queue_sinks,
+                        &otlp_exporter,
                         &remotes_info,
                     ) {
-                        Ok(count) if count > 0 => {
-                            eprintln!("  ✓ {} - sent {} events", file_name, count);
-                            Some((log_file, count))
+                        Ok(processed) if processed.count > 0 => {
+                            eprintln!("  ✓ {} - sent {} events", file_name, processed.count);
+                            Some((log_file, processed))
                         }
                         Ok(_) => {
                             eprintln!("  ○ {} - no events to send", file_name);
@@ -148,14 +183,23 @@ pub fn handle_flush_logs(args: &[String]) {
     // Collect results
     let mut events_sent = 0;
     let mut files_to_delete = Vec::new();
+    let mut enterprise_events = Vec::new();
+    let mut sink_envelopes = Vec::new();
 
     for result in results {
-        if let Some((log_file, count)) = result {
-            events_sent += count;
+        if let Some((log_file, processed)) = result {
+            events_sent += processed.count;
             files_to_delete.push(log_file);
+            enterprise_events.extend(processed.enterprise_events);
+            sink_envelopes.extend(processed.sink_envelopes);
         }
     }
 
+    if let Some(client) = enterprise_client.as_ref() {
+        flush_enterprise_queue(&logs_dir, client, &enterprise_events);
+    }
+    flush_sinks(&logs_dir, sinks, &sink_envelopes);
+
     eprintln!(
         "\nSummary: {} events sent from {} files",
         events_sent,
@@ -180,6 +224,27 @@ pub fn handle_flush_logs(args: &[String]) {
     }
 }
 
+/// Send the repository's aggregate report, at most once a day
+fn flush_aggregates(logs_dir: &Path, dsn: &str) {
+    let Ok(repo) = find_repository_in_path(&logs_dir.to_string_lossy()) else {
+        return;
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    if !aggregates::due(&repo, now) {
+        return;
+    }
+    let (Some(client), Ok(report)) = (SentryClient::from_dsn(dsn), aggregates::collect(&repo))
+    else {
+        return;
+    };
+    if client.send_event(aggregates::sentry_event(&report)).is_ok() {
+        let _ = aggregates::mark_sent(&repo, now);
+    }
+}
+
 /// Clean up old log files when count > 100
 /// Deletes logs older than a week based on file modification time
 fn cleanup_old_logs(logs_dir: &PathBuf) {
@@ -192,10 +257,11 @@ fn cleanup_old_logs(logs_dir: &PathBuf) {
     for entry in entries {
         if let Ok(entry) = entry {
             let path = entry.path();
-            if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("log") {
-                if let Ok(metadata) = entry.metadata() {
-                    log_files.push((path, metadata));
-                }
+            if path.is_file()
+                && path.extension().and_then(|s| s.to_str()) == Some("log")
+                && let Ok(metadata) = entry.metadata()
+            {
+                log_files.push((path, metadata));
             }
         }
     }
@@ -215,17 +281,17 @@ fn cleanup_old_logs(logs_dir: &PathBuf) {
     // Delete logs older than a week
     for (path, metadata) in log_files {
         if let Ok(modified) = metadata.modified() {
-            if let Ok(modified_secs) = modified.duration_since(UNIX_EPOCH) {
-                if modified_secs.as_secs() < one_week_ago {
-                    let _ = fs::remove_file(&path);
-                }
+            if let Ok(modified_secs) = modified.duration_since(UNIX_EPOCH)
+                && modified_secs.as_secs() < one_week_ago
+            {
+                let _ = fs::remove_file(&path);
             }
         } else if let Ok(created) = metadata.created() {
             // Fallback to creation time if modification time is not available
-            if let Ok(created_secs) = created.duration_since(UNIX_EPOCH) {
-                if created_secs.as_secs() < one_week_ago {
-                    let _ = fs::remove_file(&path);
-                }
+            if let Ok(created_secs) = created.duration_since(UNIX_EPOCH)
+                && created_secs.as_secs() < one_week_ago
+            {
+                let _ = fs::remove_file(&path);
             }
         }
     }
@@ -285,6 +351,7 @@ impl SentryClient {
         let response = minreq::post(&self.endpoint)
             .with_header("X-Sentry-Auth", auth_header)
             .with_header("Content-Type", "application/json")
+            .with_timeout(10)
             .with_body(body)
             .send()?;
 
@@ -316,54 +383,142 @@ fn initialize_sentry_clients(
     (oss_client, enterprise_client)
 }
 
+/// Outcome of reading one log file
+struct ProcessedLog {
+    /// Envelopes handled by at least one destination
+    count: usize,
+    /// Sentry events to queue for the enterprise DSN (empty unless `queue_enterprise`)
+    enterprise_events: Vec<Value>,
+    /// Raw envelopes for the configured sinks (empty unless `queue_sinks`)
+    sink_envelopes: Vec<Value>,
+}
+
 fn process_log_file(
     path: &PathBuf,
     oss_client: &Option<SentryClient>,
-    enterprise_client: &Option<SentryClient>,
+    queue_enterprise: bool,
+    queue_sinks: bool,
+    otlp_exporter: &Option<OtlpExporter>,
     remotes_info: &[(String, String)],
-) -> Result<usize, Box<dyn std::error::Error>> {
+) -> Result<ProcessedLog, Box<dyn std::error::Error>> {
     let content = fs::read_to_string(path)?;
-    let mut count = 0;
+    let envelopes: Vec<Value> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .collect();
+
+    // Send the whole file to the OTLP collector in one batch per signal. A
+    // collector that's down shouldn't hold back the other destinations.
+    let otlp_sent = match otlp_exporter {
+        Some(exporter) => match exporter.export(&envelopes) {
+            Ok(exported) => exported > 0,
+            Err(e) => {
+                eprintln!("OTLP export failed for {}: {}", path.display(), e);
+                false
+            }
+        },
+        None => false,
+    };
 
-    for line in content.lines() {
-        if line.trim().is_empty() {
+    let mut count = 0;
+    let mut enterprise_events = Vec::new();
+    for envelope in &envelopes {
+        // Sinks get every envelope, so they count as handled once queued
+        let mut sent = queue_sinks || (otlp_sent && otlp::is_exportable(envelope));
+        let Some(event) = build_sentry_event(envelope, remotes_info) else {
+            if sent {
+                count += 1;
+            }
             continue;
+        };
+
+        // Send to OSS if configured
+        if let Some(client) = oss_client
+            && client.send_event(event.clone()).is_ok()
+        {
+            sent = true;
         }
 
-        match serde_json::from_str::<Value>(line) {
-            Ok(envelope) => {
-                let mut sent = false;
+        // Enterprise events count as handled once they're in the durable queue
+        if queue_enterprise {
+            enterprise_events.push(event);
+            sent = true;
+        }
 
-                // Send to OSS if configured
-                if let Some(client) = oss_client {
-                    if send_envelope_to_sentry(&envelope, client, remotes_info) {
-                        sent = true;
-                    }
-                }
+        if sent {
+            count += 1;
+        }
+    }
 
-                // Send to Enterprise if configured
-                if let Some(client) = enterprise_client {
-                    if send_envelope_to_sentry(&envelope, client, remotes_info) {
-                        sent = true;
-                    }
-                }
+    Ok(ProcessedLog {
+        count,
+        enterprise_events,
+        sink_envelopes: if queue_sinks { envelopes } else { Vec::new() },
+    })
+}
+
+/// Hand `envelopes` to every configured sink; each queues what it can't send yet
+fn flush_sinks(logs_dir: &Path, sinks: &[SinkConfig], envelopes: &[Value]) {
+    let queue_dir = logs_dir.parent().unwrap_or(logs_dir);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    for sink in sinks {
+        sinks::deliver(sink, queue_dir, envelopes, now);
+    }
+}
 
-                if sent {
-                    count += 1;
+/// Queue `events` for the enterprise DSN and upload as much of the queue as the
+/// backoff schedule allows.
+fn flush_enterprise_queue(logs_dir: &Path, client: &SentryClient, events: &[Value]) {
+    let queue_dir = logs_dir.parent().unwrap_or(logs_dir);
+    let queue = OfflineQueue::new(queue_dir, "enterprise_queue");
+
+    match queue.enqueue(events) {
+        Ok(dropped) if dropped > 0 => {
+            eprintln!("Enterprise queue full, dropped {} oldest events", dropped);
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("Failed to queue enterprise events: {}", e),
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    loop {
+        match queue.drain(now, ENTERPRISE_BATCH_SIZE, |event| {
+            client.send_event(event.clone()).is_ok()
+        }) {
+            Ok(result) if result.deferred => {
+                eprintln!(
+                    "Enterprise upload backing off, {} events queued",
+                    result.remaining
+                );
+                break;
+            }
+            // Stop on a failed batch (backoff is now scheduled) or an empty queue
+            Ok(result) if result.sent < ENTERPRISE_BATCH_SIZE || result.remaining == 0 => {
+                if result.remaining > 0 {
+                    eprintln!(
+                        "Enterprise upload failed, {} events queued for retry",
+                        result.remaining
+                    );
                 }
+                break;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("Failed to drain enterprise queue: {}", e);
+                break;
             }
-            Err(_) => {}
         }
     }
-
-    Ok(count)
 }
 
-fn send_envelope_to_sentry(
-    envelope: &Value,
-    client: &SentryClient,
-    remotes_info: &[(String, String)],
-) -> bool {
+fn build_sentry_event(envelope: &Value, remotes_info: &[(String, String)]) -> Option<Value> {
     let event_type = envelope.get("type").and_then(|t| t.as_str());
     let timestamp = envelope
         .get("timestamp")
@@ -387,11 +542,11 @@ fn send_envelope_to_sentry(
             let context = envelope.get("context");
 
             let mut extra = BTreeMap::new();
-            if let Some(ctx) = context {
-                if let Some(obj) = ctx.as_object() {
-                    for (key, value) in obj {
-                        extra.insert(key.clone(), value.clone());
-                    }
+            if let Some(ctx) = context
+                && let Some(obj) = ctx.as_object()
+            {
+                for (key, value) in obj {
+                    extra.insert(key.clone(), value.clone());
                 }
             }
 
@@ -419,11 +574,11 @@ fn send_envelope_to_sentry(
             let mut extra = BTreeMap::new();
             extra.insert("operation".to_string(), json!(operation));
             extra.insert("duration_ms".to_string(), json!(duration_ms));
-            if let Some(ctx) = context {
-                if let Some(obj) = ctx.as_object() {
-                    for (key, value) in obj {
-                        extra.insert(key.clone(), value.clone());
-                    }
+            if let Some(ctx) = context
+                && let Some(obj) = ctx.as_object()
+            {
+                for (key, value) in obj {
+                    extra.insert(key.clone(), value.clone());
                 }
             }
 
@@ -449,11 +604,11 @@ fn send_envelope_to_sentry(
             let context = envelope.get("context");
 
             let mut extra = BTreeMap::new();
-            if let Some(ctx) = context {
-                if let Some(obj) = ctx.as_object() {
-                    for (key, value) in obj {
-                        extra.insert(key.clone(), value.clone());
-                    }
+            if let Some(ctx) = context
+                && let Some(obj) = ctx.as_object()
+            {
+                for (key, value) in obj {
+                    extra.insert(key.clone(), value.clone());
                 }
             }
 
@@ -468,12 +623,9 @@ fn send_envelope_to_sentry(
             })
         }
         _ => {
-            return false;
+            return None;
         }
     };
 
-    match client.send_event(event) {
-        Ok(_) => true,
-        Err(_) => false,
-    }
+    Some(event)
 }
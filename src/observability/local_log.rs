@@ -0,0 +1,194 @@
+//! Local, human-readable log of debug and error events under `~/.git-ai/logs/`.
+//!
+//! The active file is `git-ai.log`. It is rotated to `git-ai.log.1` (shifting
+//! older files up) once it grows past `MAX_BYTES` or was last written more than
+//! `MAX_AGE` ago; at most `MAX_ROTATED` old files are kept. `git-ai logs tail`
+//! reads it back, so a bug report can include what happened without re-running
+//! with `GIT_AI_DEBUG=1`.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+pub const LOG_FILE_NAME: &str = "git-ai.log";
+
+const MAX_BYTES: u64 = 5 * 1024 * 1024;
+const MAX_AGE: Duration = Duration::from_secs(24 * 3600);
+const MAX_ROTATED: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Debug,
+    Error,
+}
+
+impl Level {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Level::Debug => "DEBUG",
+            Level::Error => "ERROR",
+        }
+    }
+}
+
+/// Rotation limits, split out so tests can use small values
+#[derive(Debug, Clone, Copy)]
+struct Rotation {
+    max_bytes: u64,
+    max_age: Duration,
+    max_rotated: usize,
+}
+
+const DEFAULT_ROTATION: Rotation = Rotation {
+    max_bytes: MAX_BYTES,
+    max_age: MAX_AGE,
+    max_rotated: MAX_ROTATED,
+};
+
+/// `GIT_AI_LOG_DIR` overrides the default `~/.git-ai/logs`
+pub fn log_dir() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var("GIT_AI_LOG_DIR")
+        && !dir.is_empty()
+    {
+        return Some(PathBuf::from(dir));
+    }
+    dirs::home_dir().map(|home| home.join(".git-ai").join("logs"))
+}
+
+pub fn debug(message: &str) {
+    write(Level::Debug, message);
+}
+
+pub fn error(message: &str) {
+    write(Level::Error, message);
+}
+
+/// Best effort: logging never fails the command that triggered it
+pub fn write(level: Level, message: &str) {
+    let Some(dir) = log_dir() else {
+        return;
+    };
+    let line = format!(
+        "{} {} [{}] {}",
+        chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        level.as_str(),
+        std::process::id(),
+        message
+    );
+    let _ = append_line(&dir, &line, DEFAULT_ROTATION);
+}
+
+fn append_line(dir: &Path, line: &str, rotation: Rotation) -> std::io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(LOG_FILE_NAME);
+    if needs_rotation(&path, rotation) {
+        rotate(dir, rotation.max_rotated)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", line)
+}
+
+fn needs_rotation(path: &Path, rotation: Rotation) -> bool {
+    let Ok(metadata) = fs::metadata(path) else {
+        return false;
+    };
+    let stale = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age > rotation.max_age);
+    metadata.len() >= rotation.max_bytes || stale
+}
+
+fn rotated_path(dir: &Path, index: usize) -> PathBuf {
+    dir.join(format!("{}.{}", LOG_FILE_NAME, index))
+}
+
+/// Shift `git-ai.log.N` to `.N+1`, dropping the oldest, then move the active file to `.1`
+fn rotate(dir: &Path, max_rotated: usize) -> std::io::Result<()> {
+    let _ = fs::remove_file(rotated_path(dir, max_rotated));
+    for index in (1..max_rotated).rev() {
+        let from = rotated_path(dir, index);
+        if from.exists() {
+            fs::rename(&from, rotated_path(dir, index + 1))?;
+        }
+    }
+    // Another process may have rotated first; that's fine
+    match fs::rename(dir.join(LOG_FILE_NAME), rotated_path(dir, 1)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// The last `count` lines across the active file and, if it is short, the most
+/// recent rotated one
+pub fn tail_lines(dir: &Path, count: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for path in [dir.join(LOG_FILE_NAME), rotated_path(dir, 1)] {
+        if lines.len() >= count {
+            break;
+        }
+        if let Ok(content) = fs::read_to_string(&path) {
+            let mut older: Vec<String> = content.lines().map(str::to_string).collect();
+            older.append(&mut lines);
+            lines = older;
+        }
+    }
+    let skip = lines.len().saturating_sub(count);
+    lines.split_off(skip)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotates_by_size_and_keeps_bounded_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let rotation = Rotation {
+            max_bytes: 20,
+            max_age: MAX_AGE,
+            max_rotated: 2,
+        };
+
+        for i in 0..8 {
+            append_line(dir.path(), &format!("line number {}", i), rotation).unwrap();
+        }
+
+        // Each 14-byte line pushes the file past 20 bytes, so every other write rotates
+        assert!(dir.path().join(LOG_FILE_NAME).exists());
+        assert!(rotated_path(dir.path(), 1).exists());
+        assert!(rotated_path(dir.path(), 2).exists());
+        assert!(!rotated_path(dir.path(), 3).exists());
+
+        assert_eq!(
+            tail_lines(dir.path(), 3),
+            vec!["line number 5", "line number 6", "line number 7"]
+        );
+        assert_eq!(tail_lines(dir.path(), 100).len(), 4);
+    }
+
+    #[test]
+    fn test_rotates_stale_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let rotation = Rotation {
+            max_bytes: MAX_BYTES,
+            max_age: Duration::ZERO,
+            max_rotated: MAX_ROTATED,
+        };
+
+        append_line(dir.path(), "old", rotation).unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+        append_line(dir.path(), "new", rotation).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dir.path().join(LOG_FILE_NAME)).unwrap(),
+            "new\n"
+        );
+        assert_eq!(
+            fs::read_to_string(rotated_path(dir.path(), 1)).unwrap(),
+            "old\n"
+        );
+    }
+}
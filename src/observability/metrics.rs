@@ -0,0 +1,269 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// Upper bounds (seconds) of the hook duration histogram buckets
+const DURATION_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Histogram {
+    /// Non-cumulative count per bucket in `DURATION_BUCKETS`; observations above the last bound
+    /// only show up in `count`
+    pub buckets: Vec<u64>,
+    pub count: u64,
+    pub sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        if self.buckets.len() != DURATION_BUCKETS.len() {
+            self.buckets = vec![0; DURATION_BUCKETS.len()];
+        }
+        if let Some(idx) = DURATION_BUCKETS.iter().position(|bound| value <= *bound) {
+            self.buckets[idx] += 1;
+        }
+        self.count += 1;
+        self.sum += value;
+    }
+}
+
+/// Counters persisted in .git/ai/metrics.json. Every git-ai process adds to them and
+/// `git-ai serve` renders them in the Prometheus text format.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetricsSnapshot {
+    /// Checkpoints created, by checkpoint kind
+    #[serde(default)]
+    pub checkpoints_total: BTreeMap<String, u64>,
+    /// Lines added in checkpoints, by checkpoint kind
+    #[serde(default)]
+    pub attribution_lines_total: BTreeMap<String, u64>,
+    /// Wrapper hook durations, by git command then phase
+    #[serde(default)]
+    pub hook_duration_seconds: BTreeMap<String, BTreeMap<String, Histogram>>,
+}
+
+impl MetricsSnapshot {
+    pub fn load(path: &Path) -> MetricsSnapshot {
+        fs::read(path)
+            .ok()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .unwrap_or_default()
+    }
+
+    fn update(path: &Path, f: impl FnOnce(&mut MetricsSnapshot)) {
+        // Wrapper hooks and agent checkpoints can update at the same time; without
+        // the lock both read the same snapshot and the last rename drops the other's
+        // counts. Held on a sidecar file since metrics.json itself is replaced.
+        // Metrics are best-effort, so a lock that can't be taken isn't fatal.
+        let lock = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path.with_extension("json.lock"))
+            .ok()
+            .filter(|file| file.lock().is_ok());
+
+        let mut snapshot = MetricsSnapshot::load(path);
+        f(&mut snapshot);
+
+        // Write to a temp file and rename so a concurrent scrape never sees a partial file
+        let tmp_path = path.with_extension(format!("json.{}", std::process::id()));
+        if let Ok(data) = serde_json::to_vec(&snapshot)
+            && fs::write(&tmp_path, data).is_ok()
+            && fs::rename(&tmp_path, path).is_err()
+        {
+            let _ = fs::remove_file(&tmp_path);
+        }
+        drop(lock);
+    }
+
+    pub fn render_prometheus(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP git_ai_checkpoints_total Checkpoints created.\n");
+        out.push_str("# TYPE git_ai_checkpoints_total counter\n");
+        for (kind, value) in &self.checkpoints_total {
+            out.push_str(&format!(
+                "git_ai_checkpoints_total{{kind=\"{}\"}} {}\n",
+                escape_label(kind),
+                value
+            ));
+        }
+
+        out.push_str("# HELP git_ai_attribution_lines_total Lines added in checkpoints.\n");
+        out.push_str("# TYPE git_ai_attribution_lines_total counter\n");
+        for (kind, value) in &self.attribution_lines_total {
+            out.push_str(&format!(
+                "git_ai_attribution_lines_total{{kind=\"{}\"}} {}\n",
+                escape_label(kind),
+                value
+            ));
+        }
+
+        out.push_str("# HELP git_ai_hook_duration_seconds Time spent in git-ai hooks.\n");
+        out.push_str("# TYPE git_ai_hook_duration_seconds histogram\n");
+        for (command, phases) in &self.hook_duration_seconds {
+            for (phase, histogram) in phases {
+                let labels = format!(
+                    "command=\"{}\",phase=\"{}\"",
+                    escape_label(command),
+                    escape_label(phase)
+                );
+                let mut cumulative = 0u64;
+                for (bound, count) in DURATION_BUCKETS.iter().zip(&histogram.buckets) {
+                    cumulative += count;
+                    out.push_str(&format!(
+                        "git_ai_hook_duration_seconds_bucket{{{},le=\"{}\"}} {}\n",
+                        labels, bound, cumulative
+                    ));
+                }
+                out.push_str(&format!(
+                    "git_ai_hook_duration_seconds_bucket{{{},le=\"+Inf\"}} {}\n",
+                    labels, histogram.count
+                ));
+                out.push_str(&format!(
+                    "git_ai_hook_duration_seconds_sum{{{}}} {}\n",
+                    labels, histogram.sum
+                ));
+                out.push_str(&format!(
+                    "git_ai_hook_duration_seconds_count{{{}}} {}\n",
+                    labels, histogram.count
+                ));
+            }
+        }
+
+        out
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Count a created checkpoint and the lines it added.
+pub fn record_checkpoint(path: &Path, kind: &str, lines_added: u32) {
+    MetricsSnapshot::update(path, |snapshot| {
+        *snapshot
+            .checkpoints_total
+            .entry(kind.to_string())
+            .or_default() += 1;
+        *snapshot
+            .attribution_lines_total
+            .entry(kind.to_string())
+            .or_default() += lines_added as u64;
+    });
+}
+
+/// Record pre- and post-command hook durations for a wrapped git command.
+pub fn record_hook_durations(
+    path: &Path,
+    command: &str,
+    pre_command: Duration,
+    post_command: Duration,
+) {
+    MetricsSnapshot::update(path, |snapshot| {
+        let phases = snapshot
+            .hook_duration_seconds
+            .entry(command.to_string())
+            .or_default();
+        phases
+            .entry("pre_command".to_string())
+            .or_default()
+            .observe(pre_command.as_secs_f64());
+        phases
+            .entry("post_command".to_string())
+            .or_default()
+            .observe(post_command.as_secs_f64());
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("metrics.json");
+
+        record_checkpoint(&path, "ai_agent", 12);
+        record_checkpoint(&path, "ai_agent", 3);
+        record_checkpoint(&path, "human", 1);
+        record_hook_durations(
+            &path,
+            "commit",
+            Duration::from_millis(20),
+            Duration::from_millis(300),
+        );
+
+        let snapshot = MetricsSnapshot::load(&path);
+        assert_eq!(snapshot.checkpoints_total["ai_agent"], 2);
+        assert_eq!(snapshot.attribution_lines_total["ai_agent"], 15);
+        assert_eq!(snapshot.checkpoints_total["human"], 1);
+        let commit = &snapshot.hook_duration_seconds["commit"];
+        assert_eq!(commit["pre_command"].count, 1);
+        assert_eq!(commit["post_command"].buckets[6], 1); // le=0.5
+    }
+
+    #[test]
+    fn test_concurrent_updates_are_not_lost() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("metrics.json");
+
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..25 {
+                        record_checkpoint(&path, "ai_agent", 1);
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let snapshot = MetricsSnapshot::load(&path);
+        assert_eq!(snapshot.checkpoints_total["ai_agent"], 200);
+        assert_eq!(snapshot.attribution_lines_total["ai_agent"], 200);
+    }
+
+    #[test]
+    fn test_render_prometheus_histogram_is_cumulative() {
+        let mut histogram = Histogram::default();
+        histogram.observe(0.003);
+        histogram.observe(0.2);
+        histogram.observe(30.0);
+
+        let mut snapshot = MetricsSnapshot::default();
+        snapshot.checkpoints_total.insert("ai_tab".to_string(), 4);
+        snapshot
+            .hook_duration_seconds
+            .entry("push".to_string())
+            .or_default()
+            .insert("post_command".to_string(), histogram);
+
+        let text = snapshot.render_prometheus();
+        assert!(text.contains("git_ai_checkpoints_total{kind=\"ai_tab\"} 4\n"));
+        assert!(text.contains(
+            "git_ai_hook_duration_seconds_bucket{command=\"push\",phase=\"post_command\",le=\"0.005\"} 1\n"
+        ));
+        assert!(text.contains(
+            "git_ai_hook_duration_seconds_bucket{command=\"push\",phase=\"post_command\",le=\"0.25\"} 2\n"
+        ));
+        assert!(text.contains(
+            "git_ai_hook_duration_seconds_bucket{command=\"push\",phase=\"post_command\",le=\"+Inf\"} 3\n"
+        ));
+        assert!(text.contains(
+            "git_ai_hook_duration_seconds_count{command=\"push\",phase=\"post_command\"} 3\n"
+        ));
+    }
+}
@@ -1,11 +1,19 @@
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fs::OpenOptions;
 use std::io::Write;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Mutex, OnceLock};
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+pub mod aggregates;
 pub mod flush;
+pub mod local_log;
+pub mod metrics;
+pub mod offline_queue;
+pub mod otlp;
+pub mod sinks;
 pub mod wrapper_performance_targets;
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -40,10 +48,26 @@ struct PerformanceEnvelope {
     context: Option<serde_json::Value>,
 }
 
+#[derive(Serialize, Deserialize, Clone)]
+struct SpanEnvelope {
+    #[serde(rename = "type")]
+    event_type: String,
+    timestamp: String,
+    trace_id: String,
+    span_id: String,
+    name: String,
+    start_time_unix_nano: u64,
+    end_time_unix_nano: u64,
+    is_error: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attributes: Option<serde_json::Value>,
+}
+
 #[derive(Clone)]
 enum LogEnvelope {
     Error(ErrorEnvelope),
     Performance(PerformanceEnvelope),
+    Span(SpanEnvelope),
     #[allow(dead_code)]
     Message(MessageEnvelope),
 }
@@ -53,6 +77,7 @@ impl LogEnvelope {
         match self {
             LogEnvelope::Error(e) => serde_json::to_value(e).ok(),
             LogEnvelope::Performance(p) => serde_json::to_value(p).ok(),
+            LogEnvelope::Span(s) => serde_json::to_value(s).ok(),
             LogEnvelope::Message(m) => serde_json::to_value(m).ok(),
         }
     }
@@ -135,8 +160,13 @@ fn append_envelope(envelope: LogEnvelope) {
     }
 }
 
-/// Log an error to Sentry
+/// Log an error to Sentry and the local log file
 pub fn log_error(error: &dyn std::error::Error, context: Option<serde_json::Value>) {
+    match &context {
+        Some(context) => local_log::error(&format!("{} {}", error, context)),
+        None => local_log::error(&error.to_string()),
+    }
+
     let envelope = ErrorEnvelope {
         event_type: "error".to_string(),
         timestamp: chrono::Utc::now().to_rfc3339(),
@@ -174,6 +204,96 @@ pub fn log_message(message: &str, level: &str, context: Option<serde_json::Value
     append_envelope(LogEnvelope::Message(envelope));
 }
 
+static SPAN_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn hex_digest(input: &str, len: usize) -> String {
+    format!("{:x}", Sha256::digest(input.as_bytes()))[..len].to_string()
+}
+
+/// All spans emitted by one git-ai process share a trace
+fn process_trace_id() -> &'static str {
+    static TRACE_ID: OnceLock<String> = OnceLock::new();
+    TRACE_ID.get_or_init(|| {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        hex_digest(&format!("{}:{}", std::process::id(), now), 32)
+    })
+}
+
+/// Record a finished span for OTLP export. No-op unless an OTLP endpoint is configured.
+pub fn log_span(
+    name: &str,
+    start: SystemTime,
+    duration: Duration,
+    is_error: bool,
+    attributes: Option<serde_json::Value>,
+) {
+    if otlp::OtlpExporter::from_config(crate::config::Config::get()).is_none() {
+        return;
+    }
+
+    let start_nanos = start
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+    let trace_id = process_trace_id();
+    let span_id = hex_digest(
+        &format!(
+            "{}:{}",
+            trace_id,
+            SPAN_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ),
+        16,
+    );
+
+    let envelope = SpanEnvelope {
+        event_type: "span".to_string(),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        trace_id: trace_id.to_string(),
+        span_id,
+        name: name.to_string(),
+        start_time_unix_nano: start_nanos,
+        end_time_unix_nano: start_nanos + duration.as_nanos() as u64,
+        is_error,
+        attributes,
+    };
+
+    append_envelope(LogEnvelope::Span(envelope));
+}
+
+/// Record one span per wrapper phase (pre-command hooks, git, post-command hooks),
+/// laid out back to back and ending now.
+pub fn log_command_phase_spans(
+    command: &str,
+    pre_command: Duration,
+    git_duration: Duration,
+    post_command: Duration,
+) {
+    let end = SystemTime::now();
+    let post_start = end - post_command;
+    let git_start = post_start - git_duration;
+    let pre_start = git_start - pre_command;
+    let attributes = Some(serde_json::json!({ "git.command": command }));
+
+    log_span(
+        "git-ai.hooks.pre_command",
+        pre_start,
+        pre_command,
+        false,
+        attributes.clone(),
+    );
+    log_span("git", git_start, git_duration, false, attributes.clone());
+    log_span(
+        "git-ai.hooks.post_command",
+        post_start,
+        post_command,
+        false,
+        attributes,
+    );
+}
+
 /// Spawn a background process to flush logs to Sentry
 pub fn spawn_background_flush() {
     // Always spawn flush process - it will handle OSS/Enterprise DSN logic
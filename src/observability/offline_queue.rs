@@ -0,0 +1,286 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Oldest events are dropped once the queue holds this many
+pub const DEFAULT_MAX_EVENTS: usize = 10_000;
+
+const BASE_BACKOFF_SECS: u64 = 30;
+const MAX_BACKOFF_SECS: u64 = 6 * 60 * 60;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct QueueState {
+    consecutive_failures: u32,
+    next_attempt_at: u64,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct DrainResult {
+    pub sent: usize,
+    pub remaining: usize,
+    /// True when the upload was skipped because the backoff window hasn't elapsed
+    pub deferred: bool,
+}
+
+/// Disk-backed FIFO of telemetry events waiting to be uploaded.
+///
+/// Events are stored one JSON object per line; upload failures are tracked in a
+/// sidecar state file so later flushes back off exponentially instead of
+/// retrying (and waiting on the network) every time.
+pub struct OfflineQueue {
+    path: PathBuf,
+    state_path: PathBuf,
+    max_events: usize,
+}
+
+impl OfflineQueue {
+    /// Queue named `name` inside `dir` (normally `.git/ai`)
+    pub fn new(dir: &Path, name: &str) -> Self {
+        OfflineQueue {
+            path: dir.join(format!("{}.jsonl", name)),
+            state_path: dir.join(format!("{}.state.json", name)),
+            max_events: DEFAULT_MAX_EVENTS,
+        }
+    }
+
+    pub fn with_max_events(mut self, max_events: usize) -> Self {
+        self.max_events = max_events;
+        self
+    }
+
+    /// Append events, dropping the oldest if the cap is exceeded. Returns how many were dropped.
+    pub fn enqueue(&self, events: &[Value]) -> io::Result<usize> {
+        if events.is_empty() {
+            return Ok(0);
+        }
+        let mut queued = self.read_events();
+        queued.extend(events.iter().cloned());
+        let dropped = queued.len().saturating_sub(self.max_events);
+        queued.drain(..dropped);
+        self.write_events(&queued)?;
+        Ok(dropped)
+    }
+
+    /// Upload up to `batch_size` events in order with `send`, stopping at the first failure.
+    /// Sent events are removed; a failure schedules the next attempt with exponential backoff.
+    pub fn drain<F>(&self, now: u64, batch_size: usize, mut send: F) -> io::Result<DrainResult>
+    where
+        F: FnMut(&Value) -> bool,
+    {
+        let mut events = self.read_events();
+        if events.is_empty() {
+            return Ok(DrainResult::default());
+        }
+
+        let mut state = self.read_state();
+        if now < state.next_attempt_at {
+            return Ok(DrainResult {
+                sent: 0,
+                remaining: events.len(),
+                deferred: true,
+            });
+        }
+
+        let mut sent = 0;
+        let mut failed = false;
+        for event in events.iter().take(batch_size) {
+            if send(event) {
+                sent += 1;
+            } else {
+                failed = true;
+                break;
+            }
+        }
+        events.drain(..sent);
+        self.write_events(&events)?;
+
+        if failed {
+            state.consecutive_failures += 1;
+            state.next_attempt_at = now + backoff_secs(state.consecutive_failures);
+        } else {
+            state = QueueState::default();
+        }
+        self.write_state(&state)?;
+
+        Ok(DrainResult {
+            sent,
+            remaining: events.len(),
+            deferred: false,
+        })
+    }
+
+    /// Upload the oldest `batch_size` events in one call to `send`. All or nothing:
+    /// on failure the batch stays queued and backoff is scheduled as in [`Self::drain`].
+    pub fn drain_batch<F>(&self, now: u64, batch_size: usize, send: F) -> io::Result<DrainResult>
+    where
+        F: FnOnce(&[Value]) -> bool,
+    {
+        let mut events = self.read_events();
+        if events.is_empty() {
+            return Ok(DrainResult::default());
+        }
+
+        let mut state = self.read_state();
+        if now < state.next_attempt_at {
+            return Ok(DrainResult {
+                sent: 0,
+                remaining: events.len(),
+                deferred: true,
+            });
+        }
+
+        let batch_len = batch_size.max(1).min(events.len());
+        let sent = if send(&events[..batch_len]) {
+            events.drain(..batch_len);
+            self.write_events(&events)?;
+            state = QueueState::default();
+            batch_len
+        } else {
+            state.consecutive_failures += 1;
+            state.next_attempt_at = now + backoff_secs(state.consecutive_failures);
+            0
+        };
+        self.write_state(&state)?;
+
+        Ok(DrainResult {
+            sent,
+            remaining: events.len(),
+            deferred: false,
+        })
+    }
+
+    /// Events still waiting to be uploaded, oldest first
+    pub fn events(&self) -> Vec<Value> {
+        self.read_events()
+    }
+
+    fn read_events(&self) -> Vec<Value> {
+        fs::read_to_string(&self.path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+            .collect()
+    }
+
+    fn write_events(&self, events: &[Value]) -> io::Result<()> {
+        if events.is_empty() {
+            return match fs::remove_file(&self.path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            };
+        }
+        let mut content = String::new();
+        for event in events {
+            content.push_str(&event.to_string());
+            content.push('\n');
+        }
+        // Write-then-rename so a crash mid-write can't truncate the queue
+        let tmp_path = self.path.with_extension("jsonl.tmp");
+        fs::write(&tmp_path, content)?;
+        fs::rename(&tmp_path, &self.path)
+    }
+
+    fn read_state(&self) -> QueueState {
+        fs::read_to_string(&self.state_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    fn write_state(&self, state: &QueueState) -> io::Result<()> {
+        if state.consecutive_failures == 0 {
+            return match fs::remove_file(&self.state_path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            };
+        }
+        fs::write(
+            &self.state_path,
+            serde_json::to_string(state).map_err(io::Error::other)?,
+        )
+    }
+}
+
+/// 30s, 60s, 120s, ... capped at 6h
+fn backoff_secs(consecutive_failures: u32) -> u64 {
+    let exponent = consecutive_failures.saturating_sub(1).min(20);
+    (BASE_BACKOFF_SECS << exponent).min(MAX_BACKOFF_SECS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_enqueue_caps_size_by_dropping_oldest() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = OfflineQueue::new(dir.path(), "queue").with_max_events(3);
+
+        assert_eq!(queue.enqueue(&[json!(1), json!(2)]).unwrap(), 0);
+        assert_eq!(queue.enqueue(&[json!(3), json!(4)]).unwrap(), 1);
+        assert_eq!(queue.read_events(), vec![json!(2), json!(3), json!(4)]);
+    }
+
+    #[test]
+    fn test_drain_backs_off_after_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = OfflineQueue::new(dir.path(), "queue");
+        queue.enqueue(&[json!(1), json!(2), json!(3)]).unwrap();
+
+        // First event goes out, then the network drops
+        let result = queue.drain(1000, 10, |event| *event == json!(1)).unwrap();
+        assert_eq!(result.sent, 1);
+        assert_eq!(result.remaining, 2);
+
+        // Within the backoff window nothing is attempted
+        let result = queue
+            .drain(1010, 10, |_| panic!("should not send"))
+            .unwrap();
+        assert!(result.deferred);
+
+        // After the window, a batch-limited drain resumes in order
+        let mut seen = Vec::new();
+        let result = queue
+            .drain(1000 + BASE_BACKOFF_SECS, 1, |event| {
+                seen.push(event.clone());
+                true
+            })
+            .unwrap();
+        assert_eq!(seen, vec![json!(2)]);
+        assert_eq!(result.remaining, 1);
+        assert!(!queue.state_path.exists());
+    }
+
+    #[test]
+    fn test_drain_batch_is_all_or_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = OfflineQueue::new(dir.path(), "queue");
+        queue.enqueue(&[json!(1), json!(2), json!(3)]).unwrap();
+
+        let result = queue
+            .drain_batch(1000, 2, |batch| batch.len() == 99)
+            .unwrap();
+        assert_eq!(result.sent, 0);
+        assert_eq!(result.remaining, 3);
+
+        let result = queue
+            .drain_batch(1000 + BASE_BACKOFF_SECS, 2, |batch| {
+                assert_eq!(batch, &[json!(1), json!(2)]);
+                true
+            })
+            .unwrap();
+        assert_eq!(result.sent, 2);
+        assert_eq!(queue.read_events(), vec![json!(3)]);
+    }
+
+    #[test]
+    fn test_backoff_grows_and_caps() {
+        assert_eq!(backoff_secs(1), 30);
+        assert_eq!(backoff_secs(2), 60);
+        assert_eq!(backoff_secs(3), 120);
+        assert_eq!(backoff_secs(40), MAX_BACKOFF_SECS);
+    }
+}
@@ -0,0 +1,285 @@
+use crate::config::Config;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+
+/// Exports span and error envelopes to an OpenTelemetry collector over OTLP/HTTP (JSON encoding).
+///
+/// The endpoint comes from `telemetry_otlp_endpoint` in the config file, falling back to the
+/// standard `OTEL_EXPORTER_OTLP_ENDPOINT` / `OTEL_EXPORTER_OTLP_HEADERS` environment variables.
+pub struct OtlpExporter {
+    endpoint: String,
+    headers: BTreeMap<String, String>,
+}
+
+impl OtlpExporter {
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let endpoint = config
+            .telemetry_otlp_endpoint()
+            .map(|s| s.to_string())
+            .or_else(|| std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok())
+            .filter(|s| !s.trim().is_empty())?;
+
+        let mut headers = std::env::var("OTEL_EXPORTER_OTLP_HEADERS")
+            .map(|raw| parse_headers(&raw))
+            .unwrap_or_default();
+        headers.extend(
+            config
+                .telemetry_otlp_headers()
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone())),
+        );
+
+        Some(OtlpExporter {
+            endpoint: endpoint.trim().trim_end_matches('/').to_string(),
+            headers,
+        })
+    }
+
+    /// Send all exportable envelopes. Returns the number of envelopes delivered.
+    pub fn export(&self, envelopes: &[Value]) -> Result<usize, Box<dyn std::error::Error>> {
+        let spans: Vec<&Value> = envelopes
+            .iter()
+            .filter(|e| envelope_type(e) == Some("span"))
+            .collect();
+        let errors: Vec<&Value> = envelopes
+            .iter()
+            .filter(|e| envelope_type(e) == Some("error"))
+            .collect();
+
+        let mut sent = 0;
+        if !spans.is_empty() {
+            self.post("/v1/traces", &traces_payload(&spans))?;
+            sent += spans.len();
+        }
+        if !errors.is_empty() {
+            self.post("/v1/logs", &logs_payload(&errors))?;
+            sent += errors.len();
+        }
+        Ok(sent)
+    }
+
+    fn post(&self, signal_path: &str, payload: &Value) -> Result<(), Box<dyn std::error::Error>> {
+        let url = signal_url(&self.endpoint, signal_path);
+        let mut request = minreq::post(&url)
+            .with_header("Content-Type", "application/json")
+            .with_timeout(10)
+            .with_body(serde_json::to_string(payload)?);
+        for (key, value) in &self.headers {
+            request = request.with_header(key.as_str(), value.as_str());
+        }
+
+        let status = request.send()?.status_code;
+        if (200..300).contains(&status) {
+            Ok(())
+        } else {
+            Err(format!("OTLP collector returned status {} for {}", status, url).into())
+        }
+    }
+}
+
+/// Whether the OTLP exporter knows how to send this envelope
+pub fn is_exportable(envelope: &Value) -> bool {
+    matches!(envelope_type(envelope), Some("span") | Some("error"))
+}
+
+fn envelope_type(envelope: &Value) -> Option<&str> {
+    envelope.get("type").and_then(|t| t.as_str())
+}
+
+/// Accept either a base collector URL or one that already names the signal path.
+fn signal_url(endpoint: &str, signal_path: &str) -> String {
+    if endpoint.ends_with("/v1/traces") || endpoint.ends_with("/v1/logs") {
+        let base = endpoint
+            .trim_end_matches("/v1/traces")
+            .trim_end_matches("/v1/logs");
+        format!("{}{}", base, signal_path)
+    } else {
+        format!("{}{}", endpoint, signal_path)
+    }
+}
+
+/// Parse the `key1=value1,key2=value2` format used by OTEL_EXPORTER_OTLP_HEADERS.
+fn parse_headers(raw: &str) -> BTreeMap<String, String> {
+    raw.split(',')
+        .filter_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            let key = key.trim();
+            if key.is_empty() {
+                return None;
+            }
+            Some((key.to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+fn resource() -> Value {
+    json!({
+        "attributes": [
+            { "key": "service.name", "value": { "stringValue": "git-ai" } },
+            { "key": "service.version", "value": { "stringValue": env!("CARGO_PKG_VERSION") } },
+            { "key": "os.type", "value": { "stringValue": std::env::consts::OS } },
+            { "key": "host.arch", "value": { "stringValue": std::env::consts::ARCH } },
+        ]
+    })
+}
+
+fn scope() -> Value {
+    json!({ "name": "git-ai", "version": env!("CARGO_PKG_VERSION") })
+}
+
+/// Convert a flat JSON object into OTLP KeyValue attributes.
+fn to_attributes(value: Option<&Value>) -> Vec<Value> {
+    let Some(obj) = value.and_then(|v| v.as_object()) else {
+        return Vec::new();
+    };
+
+    obj.iter()
+        .map(|(key, value)| {
+            let any_value = match value {
+                Value::String(s) => json!({ "stringValue": s }),
+                Value::Bool(b) => json!({ "boolValue": b }),
+                Value::Number(n) if n.is_i64() || n.is_u64() => {
+                    json!({ "intValue": n.to_string() })
+                }
+                Value::Number(n) => json!({ "doubleValue": n.as_f64().unwrap_or(0.0) }),
+                other => json!({ "stringValue": other.to_string() }),
+            };
+            json!({ "key": key, "value": any_value })
+        })
+        .collect()
+}
+
+fn traces_payload(spans: &[&Value]) -> Value {
+    let otlp_spans: Vec<Value> = spans
+        .iter()
+        .map(|span| {
+            let is_error = span
+                .get("is_error")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let nanos = |key: &str| span.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+            json!({
+                "traceId": span.get("trace_id").and_then(|v| v.as_str()).unwrap_or_default(),
+                "spanId": span.get("span_id").and_then(|v| v.as_str()).unwrap_or_default(),
+                "name": span.get("name").and_then(|v| v.as_str()).unwrap_or("unknown"),
+                // SPAN_KIND_INTERNAL
+                "kind": 1,
+                "startTimeUnixNano": nanos("start_time_unix_nano").to_string(),
+                "endTimeUnixNano": nanos("end_time_unix_nano").to_string(),
+                "attributes": to_attributes(span.get("attributes")),
+                // STATUS_CODE_OK / STATUS_CODE_ERROR
+                "status": { "code": if is_error { 2 } else { 1 } },
+            })
+        })
+        .collect();
+
+    json!({
+        "resourceSpans": [{
+            "resource": resource(),
+            "scopeSpans": [{ "scope": scope(), "spans": otlp_spans }],
+        }]
+    })
+}
+
+fn logs_payload(errors: &[&Value]) -> Value {
+    let records: Vec<Value> = errors
+        .iter()
+        .map(|error| {
+            let time_nanos = error
+                .get("timestamp")
+                .and_then(|t| t.as_str())
+                .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+                .and_then(|t| t.timestamp_nanos_opt())
+                .unwrap_or(0);
+            let message = error
+                .get("message")
+                .and_then(|m| m.as_str())
+                .unwrap_or("Unknown error");
+            json!({
+                "timeUnixNano": time_nanos.to_string(),
+                // SEVERITY_NUMBER_ERROR
+                "severityNumber": 17,
+                "severityText": "ERROR",
+                "body": { "stringValue": message },
+                "attributes": to_attributes(error.get("context")),
+            })
+        })
+        .collect();
+
+    json!({
+        "resourceLogs": [{
+            "resource": resource(),
+            "scopeLogs": [{ "scope": scope(), "logRecords": records }],
+        }]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_headers() {
+        let headers = parse_headers("authorization=Bearer abc, x-tenant = acme,,bad");
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers["authorization"], "Bearer abc");
+        assert_eq!(headers["x-tenant"], "acme");
+    }
+
+    #[test]
+    fn test_signal_url() {
+        assert_eq!(
+            signal_url("http://collector:4318", "/v1/traces"),
+            "http://collector:4318/v1/traces"
+        );
+        assert_eq!(
+            signal_url("http://collector:4318/v1/traces", "/v1/logs"),
+            "http://collector:4318/v1/logs"
+        );
+    }
+
+    #[test]
+    fn test_traces_payload_shape() {
+        let span = json!({
+            "type": "span",
+            "timestamp": "2025-01-01T00:00:00Z",
+            "trace_id": "0123456789abcdef0123456789abcdef",
+            "span_id": "0123456789abcdef",
+            "name": "checkpoint",
+            "start_time_unix_nano": 1000,
+            "end_time_unix_nano": 2000,
+            "is_error": true,
+            "attributes": { "checkpoint.kind": "ai_agent", "files": 3 },
+        });
+        let payload = traces_payload(&[&span]);
+        let otlp_span = &payload["resourceSpans"][0]["scopeSpans"][0]["spans"][0];
+
+        assert_eq!(otlp_span["name"], "checkpoint");
+        assert_eq!(otlp_span["startTimeUnixNano"], "1000");
+        assert_eq!(otlp_span["endTimeUnixNano"], "2000");
+        assert_eq!(otlp_span["status"]["code"], 2);
+        let attrs = otlp_span["attributes"].as_array().unwrap();
+        assert!(attrs.contains(&json!({ "key": "files", "value": { "intValue": "3" } })));
+        assert!(attrs.contains(
+            &json!({ "key": "checkpoint.kind", "value": { "stringValue": "ai_agent" } })
+        ));
+    }
+
+    #[test]
+    fn test_logs_payload_from_error_envelope() {
+        let error = json!({
+            "type": "error",
+            "timestamp": "1970-01-01T00:00:01Z",
+            "message": "boom",
+            "context": { "function": "checkpoint" },
+        });
+        let payload = logs_payload(&[&error]);
+        let record = &payload["resourceLogs"][0]["scopeLogs"][0]["logRecords"][0];
+
+        assert_eq!(record["timeUnixNano"], "1000000000");
+        assert_eq!(record["severityText"], "ERROR");
+        assert_eq!(record["body"]["stringValue"], "boom");
+        assert!(is_exportable(&error));
+        assert!(!is_exportable(&json!({ "type": "performance" })));
+    }
+}
@@ -0,0 +1,275 @@
+//! Extra destinations for `flush-logs`, configured under `telemetry_sinks` in
+//! `~/.git-ai/config.json`:
+//!
+//! ```json
+//! "telemetry_sinks": [
+//!   { "type": "file", "path": "~/git-ai-events.jsonl" },
+//!   { "type": "https", "url": "https://logs.example.com/ingest", "headers": { "Authorization": "Bearer ..." } },
+//!   { "type": "syslog", "address": "127.0.0.1:514" }
+//! ]
+//! ```
+//!
+//! Each sink has its own offline queue next to the log directory, so a sink that
+//! is down keeps its events (with backoff) without holding up the others.
+
+use crate::observability::offline_queue::OfflineQueue;
+use crate::utils::expand_home;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::net::UdpSocket;
+use std::path::Path;
+
+const DEFAULT_BATCH_SIZE: usize = 100;
+const DEFAULT_SYSLOG_ADDRESS: &str = "127.0.0.1:514";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SinkConfig {
+    /// Append envelopes as JSON lines
+    File {
+        path: String,
+        #[serde(default)]
+        batch_size: Option<usize>,
+    },
+    /// POST `{"events": [...]}` batches
+    Https {
+        url: String,
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+        #[serde(default)]
+        batch_size: Option<usize>,
+    },
+    /// RFC 5424 datagrams over UDP, or a local socket when `address` is a path
+    Syslog {
+        #[serde(default)]
+        address: Option<String>,
+        #[serde(default)]
+        batch_size: Option<usize>,
+    },
+}
+
+impl SinkConfig {
+    pub fn name(&self) -> String {
+        match self {
+            SinkConfig::File { path, .. } => format!("file:{}", path),
+            SinkConfig::Https { url, .. } => format!("https:{}", url),
+            SinkConfig::Syslog { address, .. } => format!(
+                "syslog:{}",
+                address.as_deref().unwrap_or(DEFAULT_SYSLOG_ADDRESS)
+            ),
+        }
+    }
+
+    fn batch_size(&self) -> usize {
+        match self {
+            SinkConfig::File { batch_size, .. }
+            | SinkConfig::Https { batch_size, .. }
+            | SinkConfig::Syslog { batch_size, .. } => {
+                batch_size.unwrap_or(DEFAULT_BATCH_SIZE).max(1)
+            }
+        }
+    }
+
+    /// Queue file name, stable per destination
+    fn queue_name(&self) -> String {
+        let name = self.name();
+        let digest = crate::observability::hex_digest(&name, 12);
+        format!("sink_{}", digest)
+    }
+
+    fn send_batch(&self, envelopes: &[Value]) -> Result<(), String> {
+        match self {
+            SinkConfig::File { path, .. } => write_file(&expand_home(path), envelopes),
+            SinkConfig::Https { url, headers, .. } => post_batch(url, headers, envelopes),
+            SinkConfig::Syslog { address, .. } => send_syslog(
+                address.as_deref().unwrap_or(DEFAULT_SYSLOG_ADDRESS),
+                envelopes,
+            ),
+        }
+    }
+}
+
+fn write_file(path: &Path, envelopes: &[Value]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let mut content = String::new();
+    for envelope in envelopes {
+        content.push_str(&envelope.to_string());
+        content.push('\n');
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn post_batch(
+    url: &str,
+    headers: &BTreeMap<String, String>,
+    envelopes: &[Value],
+) -> Result<(), String> {
+    let body = json!({
+        "git_ai_version": env!("CARGO_PKG_VERSION"),
+        "events": envelopes,
+    });
+    let mut request = minreq::post(url)
+        .with_header("Content-Type", "application/json")
+        .with_timeout(10)
+        .with_body(body.to_string());
+    for (name, value) in headers {
+        request = request.with_header(name, value);
+    }
+    let response = request.send().map_err(|e| e.to_string())?;
+    if (200..300).contains(&response.status_code) {
+        Ok(())
+    } else {
+        Err(format!("{} returned status {}", url, response.status_code))
+    }
+}
+
+/// `<PRI>1 TIMESTAMP HOST git-ai PID - - MSG` with the user facility
+fn syslog_line(envelope: &Value) -> String {
+    let severity = match envelope.get("type").and_then(Value::as_str) {
+        Some("error") => 3,
+        _ => 6,
+    };
+    let timestamp = envelope
+        .get("timestamp")
+        .and_then(Value::as_str)
+        .unwrap_or("-");
+    format!(
+        "<{}>1 {} - git-ai {} - - {}",
+        8 + severity,
+        timestamp,
+        std::process::id(),
+        envelope
+    )
+}
+
+fn send_syslog(address: &str, envelopes: &[Value]) -> Result<(), String> {
+    #[cfg(unix)]
+    if address.starts_with('/') {
+        let socket = std::os::unix::net::UnixDatagram::unbound().map_err(|e| e.to_string())?;
+        for envelope in envelopes {
+            socket
+                .send_to(syslog_line(envelope).as_bytes(), address)
+                .map_err(|e| format!("{}: {}", address, e))?;
+        }
+        return Ok(());
+    }
+
+    let socket = UdpSocket::bind("0.0.0.0:0").map_err(|e| e.to_string())?;
+    for envelope in envelopes {
+        socket
+            .send_to(syslog_line(envelope).as_bytes(), address)
+            .map_err(|e| format!("{}: {}", address, e))?;
+    }
+    Ok(())
+}
+
+/// Queue `envelopes` for `sink` and send as many batches as its backoff allows.
+/// Returns how many events are still queued.
+pub fn deliver(sink: &SinkConfig, queue_dir: &Path, envelopes: &[Value], now: u64) -> usize {
+    let queue = OfflineQueue::new(queue_dir, &sink.queue_name());
+    if let Err(e) = queue.enqueue(envelopes) {
+        eprintln!("  ✗ {} - failed to queue events: {}", sink.name(), e);
+        return envelopes.len();
+    }
+
+    let mut last_error = None;
+    loop {
+        let result = queue.drain_batch(now, sink.batch_size(), |batch| {
+            match sink.send_batch(batch) {
+                Ok(()) => true,
+                Err(e) => {
+                    last_error = Some(e);
+                    false
+                }
+            }
+        });
+        match result {
+            Ok(result) if result.sent > 0 && result.remaining > 0 => continue,
+            Ok(result) => {
+                if let Some(e) = last_error {
+                    eprintln!(
+                        "  ✗ {} - {} ({} events queued for retry)",
+                        sink.name(),
+                        e,
+                        result.remaining
+                    );
+                } else if result.deferred {
+                    eprintln!(
+                        "  ○ {} - backing off, {} events queued",
+                        sink.name(),
+                        result.remaining
+                    );
+                }
+                return result.remaining;
+            }
+            Err(e) => {
+                eprintln!("  ✗ {} - failed to drain queue: {}", sink.name(), e);
+                return envelopes.len();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sink_config() {
+        let sinks: Vec<SinkConfig> = serde_json::from_str(
+            r#"[
+                {"type": "file", "path": "/tmp/events.jsonl"},
+                {"type": "https", "url": "https://logs.example.com", "headers": {"X-Key": "k"}, "batch_size": 10},
+                {"type": "syslog"}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(sinks[1].batch_size(), 10);
+        assert_eq!(sinks[2].batch_size(), DEFAULT_BATCH_SIZE);
+        assert_eq!(sinks[2].name(), "syslog:127.0.0.1:514");
+        assert!(serde_json::from_str::<SinkConfig>(r#"{"type": "kafka"}"#).is_err());
+    }
+
+    #[test]
+    fn test_file_sink_delivers_in_batches() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("out").join("events.jsonl");
+        let sink = SinkConfig::File {
+            path: out.to_string_lossy().to_string(),
+            batch_size: Some(2),
+        };
+        let envelopes = vec![json!({"type": "error"}), json!({"n": 2}), json!({"n": 3})];
+
+        assert_eq!(deliver(&sink, dir.path(), &envelopes, 1000), 0);
+        assert_eq!(std::fs::read_to_string(&out).unwrap().lines().count(), 3);
+    }
+
+    #[test]
+    fn test_failed_sink_keeps_events_queued() {
+        let dir = tempfile::tempdir().unwrap();
+        // A directory can't be opened for appending
+        let sink = SinkConfig::File {
+            path: dir.path().to_string_lossy().to_string(),
+            batch_size: None,
+        };
+
+        assert_eq!(deliver(&sink, dir.path(), &[json!({"n": 1})], 1000), 1);
+        assert_eq!(deliver(&sink, dir.path(), &[json!({"n": 2})], 1001), 2);
+    }
+
+    #[test]
+    fn test_syslog_line() {
+        let line = syslog_line(&json!({"type": "error", "timestamp": "2025-01-01T00:00:00Z"}));
+        assert!(line.starts_with("<11>1 2025-01-01T00:00:00Z - git-ai "));
+    }
+}
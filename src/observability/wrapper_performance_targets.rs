@@ -1,15 +1,30 @@
-use std::{ops::Add, time::Duration};
+use std::{
+    collections::BTreeMap,
+    fs::{self, OpenOptions},
+    io::Write,
+    ops::Add,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 use crate::{
     authorship::working_log::CheckpointKind,
-    observability::log_performance,
+    config::Config,
+    observability::{local_log, log_performance},
     utils::{debug_performance_log, debug_performance_log_structured},
 };
 
 pub const PERFORMANCE_FLOOR_MS: Duration = Duration::from_millis(270);
 
+/// Per-command timings, next to `git-ai.log` in the log directory
+pub const PERF_RECORDS_FILE_NAME: &str = "perf.jsonl";
+
+/// Past this size the records move to `perf.jsonl.1`, replacing the older ones there
+const PERF_RECORDS_MAX_BYTES: u64 = 5 * 1024 * 1024;
+
 /// Performance benchmark result containing timing breakdowns
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -63,6 +78,13 @@ pub fn log_performance_target_if_violated(
     });
 
     debug_performance_log_structured(perf_json);
+    append_perf_record(&PerfRecord::new(
+        command,
+        pre_command,
+        git_duration,
+        post_command,
+        None,
+    ));
 
     if !within_target {
         debug_performance_log(&format!(
@@ -94,12 +116,17 @@ pub fn log_performance_target_if_violated(
     }
 }
 
+/// Checkpoints get 50ms per edited file
+pub fn checkpoint_within_target(files_edited: usize, duration: Duration) -> bool {
+    Duration::from_millis(50 * files_edited as u64) >= duration
+}
+
 pub fn log_performance_for_checkpoint(
     files_edited: usize,
     duration: Duration,
     checkpoint_kind: CheckpointKind,
 ) {
-    let within_target = Duration::from_millis(50 * files_edited as u64) >= duration;
+    let within_target = checkpoint_within_target(files_edited, duration);
 
     // Output structured JSON for benchmarking (when GIT_AI_DEBUG_PERFORMANCE >= 2)
     // For git-ai commands like checkpoint, there's no pre/post/git breakdown - just total time
@@ -114,6 +141,13 @@ pub fn log_performance_for_checkpoint(
         "within_target": within_target,
     });
     debug_performance_log_structured(perf_json);
+    append_perf_record(&PerfRecord::new(
+        "checkpoint",
+        Duration::ZERO,
+        Duration::ZERO,
+        duration,
+        Some(files_edited),
+    ));
 
     if !within_target {
         log_performance(
@@ -139,3 +173,251 @@ pub fn log_performance_for_checkpoint(
         ));
     }
 }
+
+/// One line of `perf.jsonl`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PerfRecord {
+    pub timestamp: u64,
+    pub command: String,
+    pub pre_command_ms: u64,
+    pub git_ms: u64,
+    pub post_command_ms: u64,
+    pub total_ms: u64,
+    /// Files a checkpoint touched; not known for proxied git commands
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub files: Option<usize>,
+}
+
+impl PerfRecord {
+    pub fn new(
+        command: &str,
+        pre_command: Duration,
+        git: Duration,
+        post_command: Duration,
+        files: Option<usize>,
+    ) -> Self {
+        PerfRecord {
+            timestamp: chrono::Utc::now().timestamp().max(0) as u64,
+            command: command.to_string(),
+            pre_command_ms: pre_command.as_millis() as u64,
+            git_ms: git.as_millis() as u64,
+            post_command_ms: post_command.as_millis() as u64,
+            total_ms: (pre_command + git + post_command).as_millis() as u64,
+            files,
+        }
+    }
+
+    /// Time spent in git-ai rather than in git itself
+    pub fn overhead_ms(&self) -> u64 {
+        self.total_ms.saturating_sub(self.git_ms)
+    }
+}
+
+pub fn perf_records_path() -> Option<PathBuf> {
+    local_log::log_dir().map(|dir| dir.join(PERF_RECORDS_FILE_NAME))
+}
+
+/// Best effort, and only with `perf_records` enabled in the config
+fn append_perf_record(record: &PerfRecord) {
+    if !Config::get().perf_records_enabled() {
+        return;
+    }
+    if let Some(path) = perf_records_path() {
+        let _ = append_perf_record_to(&path, record, PERF_RECORDS_MAX_BYTES);
+    }
+}
+
+fn append_perf_record_to(path: &Path, record: &PerfRecord, max_bytes: u64) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    if fs::metadata(path).is_ok_and(|metadata| metadata.len() >= max_bytes) {
+        fs::rename(path, rotated_perf_records_path(path))?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(record)?)
+}
+
+fn rotated_perf_records_path(path: &Path) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(".1");
+    PathBuf::from(rotated)
+}
+
+/// Records from the rotated file and then the active one, oldest first.
+/// Lines that don't parse are skipped.
+pub fn read_perf_records(path: &Path) -> Vec<PerfRecord> {
+    [rotated_perf_records_path(path), path.to_path_buf()]
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .flat_map(|content| {
+            content
+                .lines()
+                .filter_map(|line| serde_json::from_str::<PerfRecord>(line).ok())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Timing percentiles for one command in `git-ai perf report`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CommandPerfSummary {
+    pub command: String,
+    pub count: usize,
+    pub total_p50_ms: u64,
+    pub total_p95_ms: u64,
+    pub overhead_p50_ms: u64,
+    pub overhead_p95_ms: u64,
+}
+
+/// One summary per command, sorted by command name
+pub fn summarize_perf_records(records: &[PerfRecord]) -> Vec<CommandPerfSummary> {
+    let mut by_command: BTreeMap<&str, Vec<&PerfRecord>> = BTreeMap::new();
+    for record in records {
+        by_command.entry(&record.command).or_default().push(record);
+    }
+    by_command
+        .into_iter()
+        .map(|(command, records)| {
+            let mut totals: Vec<u64> = records.iter().map(|r| r.total_ms).collect();
+            let mut overheads: Vec<u64> = records.iter().map(|r| r.overhead_ms()).collect();
+            totals.sort_unstable();
+            overheads.sort_unstable();
+            CommandPerfSummary {
+                command: command.to_string(),
+                count: records.len(),
+                total_p50_ms: percentile(&totals, 50),
+                total_p95_ms: percentile(&totals, 95),
+                overhead_p50_ms: percentile(&overheads, 50),
+                overhead_p95_ms: percentile(&overheads, 95),
+            }
+        })
+        .collect()
+}
+
+/// Nearest-rank percentile of sorted, non-empty `values`
+fn percentile(values: &[u64], pct: usize) -> u64 {
+    let rank = (pct * values.len()).div_ceil(100).max(1);
+    values[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(command: &str, git_ms: u64, total_ms: u64) -> PerfRecord {
+        PerfRecord {
+            timestamp: 0,
+            command: command.to_string(),
+            pre_command_ms: 0,
+            git_ms,
+            post_command_ms: total_ms - git_ms,
+            total_ms,
+            files: None,
+        }
+    }
+
+    #[test]
+    fn test_perf_records_round_trip_and_summary() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(PERF_RECORDS_FILE_NAME);
+        for total in 1..=20 {
+            append_perf_record_to(&path, &record("commit", 1, total), 400).unwrap();
+        }
+        append_perf_record_to(&path, &record("push", 10, 30), 400).unwrap();
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"not json\n")
+            .unwrap();
+
+        // Rotation keeps the previous file, so some of the oldest are gone
+        assert!(rotated_perf_records_path(&path).exists());
+        let records = read_perf_records(&path);
+        assert!(records.len() < 21);
+        assert_eq!(records.last().unwrap().command, "push");
+
+        let all: Vec<PerfRecord> = (1..=20).map(|total| record("commit", 1, total)).collect();
+        let summary = summarize_perf_records(&all);
+        assert_eq!(
+            summary,
+            vec![CommandPerfSummary {
+                command: "commit".to_string(),
+                count: 20,
+                total_p50_ms: 10,
+                total_p95_ms: 19,
+                overhead_p50_ms: 9,
+                overhead_p95_ms: 18,
+            }]
+        );
+        assert_eq!(percentile(&[7], 95), 7);
+    }
+
+    #[test]
+    fn test_checkpoint_of_large_agent_edit_meets_target() {
+        use crate::authorship::working_log::AgentId;
+        use crate::commands::checkpoint_agent::agent_presets::AgentRunResult;
+        use crate::git::test_utils::TmpRepo;
+        use std::time::Instant;
+
+        const FILES: usize = 500;
+        let tmp_repo = TmpRepo::new().unwrap();
+        for i in 0..FILES {
+            let contents = format!("fn f{i}() {{}}\n");
+            tmp_repo
+                .write_file(&format!("src/f{i}.rs"), &contents, true)
+                .unwrap();
+        }
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        for i in 0..FILES {
+            let contents = format!("fn f{i}() {{}}\nfn g{i}() {{}}\n");
+            tmp_repo
+                .write_file(&format!("src/f{i}.rs"), &contents, false)
+                .unwrap();
+        }
+
+        let agent_run_result = AgentRunResult {
+            agent_id: AgentId {
+                tool: "claude".to_string(),
+                id: "session-1".to_string(),
+                model: "sonnet".to_string(),
+            },
+            agent_metadata: None,
+            checkpoint_kind: CheckpointKind::AiAgent,
+            transcript: None,
+            repo_working_dir: None,
+            edited_filepaths: Some((0..FILES).map(|i| format!("src/f{i}.rs")).collect()),
+            will_edit_filepaths: None,
+            dirty_files: None,
+            commit_message: None,
+            edited_line_ranges: None,
+            append_transcript: false,
+        };
+
+        let start = Instant::now();
+        let (entries, _, _) = tmp_repo
+            .trigger_checkpoint_with_agent_result("claude", Some(agent_run_result))
+            .unwrap();
+        let duration = start.elapsed();
+        assert_eq!(entries, FILES);
+        assert!(
+            checkpoint_within_target(FILES, duration),
+            "checkpoint of {} files took {:?}",
+            FILES,
+            duration
+        );
+
+        // One log line for the whole edit, one blob per file
+        let working_log = tmp_repo
+            .gitai_repo()
+            .storage
+            .working_log_for_base_commit("initial");
+        let log = fs::read_to_string(working_log.dir.join("checkpoints.jsonl")).unwrap();
+        assert_eq!(log.lines().count(), 1);
+        assert_eq!(
+            fs::read_dir(working_log.dir.join("blobs")).unwrap().count(),
+            FILES
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use crate::authorship::stats::CommitStats;
+use crate::notifications::{self, NotificationEvent};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// Repository policy thresholds, read from the `policy` section of ~/.git-ai/config.json
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PolicyConfig {
+    /// Maximum share (0-100) of added lines that may be AI-authored
+    #[serde(default)]
+    pub max_ai_percentage: Option<f64>,
+    /// Every AI-authored line must be marked as reviewed by a human (`git-ai review mark`).
+    /// Reviews happen after the commit, so this is enforced by `git-ai review check`
+    /// rather than at commit time.
+    #[serde(default)]
+    pub require_ai_review: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyViolation {
+    pub rule: &'static str,
+    pub message: String,
+}
+
+impl PolicyConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.max_ai_percentage.is_some()
+    }
+
+    /// Evaluate a freshly written commit and send a `policy_violation` notification on breach.
+    pub fn notify_on_commit_violation(&self, commit_sha: &str, stats: &CommitStats) {
+        if let Some(violation) = self.check_ai_share(stats.ai_additions, stats.git_diff_added_lines)
+        {
+            let short_sha = &commit_sha[..commit_sha.len().min(8)];
+            notifications::notify(
+                NotificationEvent::PolicyViolation,
+                &format!(
+                    "Commit {} violates policy: {}",
+                    short_sha, violation.message
+                ),
+                json!({
+                    "commit": commit_sha,
+                    "rule": violation.rule,
+                    "message": violation.message,
+                    "ai_additions": stats.ai_additions,
+                    "added_lines": stats.git_diff_added_lines,
+                }),
+            );
+        }
+    }
+
+    /// Check that all AI lines have been reviewed, when `require_ai_review` is set.
+    pub fn check_ai_review(&self, reviewed_lines: u32, ai_lines: u32) -> Option<PolicyViolation> {
+        if !self.require_ai_review || reviewed_lines >= ai_lines {
+            return None;
+        }
+        Some(PolicyViolation {
+            rule: "require_ai_review",
+            message: format!(
+                "{} of {} AI-authored lines have not been reviewed",
+                ai_lines - reviewed_lines,
+                ai_lines
+            ),
+        })
+    }
+
+    /// Check the AI share of a set of added lines against `max_ai_percentage`.
+    pub fn check_ai_share(&self, ai_lines: u32, added_lines: u32) -> Option<PolicyViolation> {
+        let max = self.max_ai_percentage?;
+        if added_lines == 0 {
+            return None;
+        }
+
+        let pct = ai_lines as f64 / added_lines as f64 * 100.0;
+        if pct > max {
+            Some(PolicyViolation {
+                rule: "max_ai_percentage",
+                message: format!(
+                    "{:.1}% of added lines are AI-authored ({} of {}), limit is {:.1}%",
+                    pct, ai_lines, added_lines, max
+                ),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_threshold_never_violates() {
+        let policy = PolicyConfig::default();
+        assert!(policy.check_ai_share(10, 10).is_none());
+    }
+
+    #[test]
+    fn test_threshold_exceeded() {
+        let policy = PolicyConfig {
+            max_ai_percentage: Some(50.0),
+            ..Default::default()
+        };
+        let violation = policy.check_ai_share(6, 10).unwrap();
+        assert_eq!(violation.rule, "max_ai_percentage");
+        assert!(violation.message.contains("60.0%"));
+    }
+
+    #[test]
+    fn test_threshold_boundary_and_empty() {
+        let policy = PolicyConfig {
+            max_ai_percentage: Some(50.0),
+            ..Default::default()
+        };
+        assert!(policy.check_ai_share(5, 10).is_none());
+        assert!(policy.check_ai_share(0, 0).is_none());
+    }
+
+    #[test]
+    fn test_require_ai_review() {
+        assert!(PolicyConfig::default().check_ai_review(0, 10).is_none());
+
+        let policy = PolicyConfig {
+            require_ai_review: true,
+            ..Default::default()
+        };
+        assert!(policy.check_ai_review(10, 10).is_none());
+        assert!(policy.check_ai_review(0, 0).is_none());
+        let violation = policy.check_ai_review(7, 10).unwrap();
+        assert_eq!(violation.rule, "require_ai_review");
+        assert!(violation.message.starts_with("3 of 10"));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::commands::output::paint;
 use crate::error::GitAiError;
 use crate::git::diff_tree_to_tree::Diff;
 use std::path::PathBuf;
@@ -31,28 +32,34 @@ fn is_debug_performance_enabled() -> bool {
 
 pub fn debug_performance_log(msg: &str) {
     if is_debug_performance_enabled() {
-        eprintln!("\x1b[1;33m[git-ai (perf)]\x1b[0m {}", msg);
+        eprintln!("{} {}", paint("\x1b[1;33m", "[git-ai (perf)]"), msg);
     }
 }
 
 pub fn debug_performance_log_structured(json: serde_json::Value) {
     let level = debug_performance_level();
     if level >= 2 {
-        eprintln!("\x1b[1;33m[git-ai (perf-json)]\x1b[0m {}", json);
+        eprintln!("{} {}", paint("\x1b[1;33m", "[git-ai (perf-json)]"), json);
     }
 }
 
+static DEBUG_TO_FILE: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+
 /// Debug logging utility function
 ///
 /// Prints debug messages with a colored prefix when debug assertions are enabled or when
-/// the `GIT_AI_DEBUG` environment variable is set to "1".
+/// the `GIT_AI_DEBUG` environment variable is set to "1". With `GIT_AI_DEBUG=1` the message
+/// is also appended to the local log file (see `git-ai logs tail`).
 ///
 /// # Arguments
 ///
 /// * `msg` - The debug message to print
 pub fn debug_log(msg: &str) {
     if is_debug_enabled() {
-        eprintln!("\x1b[1;33m[git-ai]\x1b[0m {}", msg);
+        eprintln!("{} {}", paint("\x1b[1;33m", "[git-ai]"), msg);
+    }
+    if *DEBUG_TO_FILE.get_or_init(|| std::env::var("GIT_AI_DEBUG").unwrap_or_default() == "1") {
+        crate::observability::local_log::debug(msg);
     }
 }
 
@@ -95,6 +102,14 @@ pub fn normalize_to_posix(path: &str) -> String {
     path.replace('\\', "/")
 }
 
+/// `path` with a leading `~/` resolved against the home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
 pub fn current_git_ai_exe() -> Result<PathBuf, GitAiError> {
     let path = std::env::current_exe()?;
 